@@ -0,0 +1,203 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+// Benchmarks of `RBTree` with `Box`, `Rc` and `Arc` pointers, against
+// `BTreeSet` holding the same keys. The objects of the intrusive trees are
+// allocated outside of the measurements, since avoiding that allocation is
+// the point of an intrusive collection.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use intrusive_collections::{intrusive_adapter, KeyAdapter, RBTree, RBTreeLink};
+use std::collections::BTreeSet;
+use std::hint::black_box;
+use std::ops::ControlFlow;
+use std::rc::Rc;
+use std::sync::Arc;
+
+const LEN: u64 = 1000;
+
+struct Obj {
+    link: RBTreeLink,
+    key: u64,
+}
+
+fn obj(key: u64) -> Obj {
+    Obj {
+        link: RBTreeLink::new(),
+        key,
+    }
+}
+
+// Every key from 0 to `LEN` once, in a scrambled order. 7919 is a prime, so
+// multiplying by it is a permutation modulo `LEN`.
+fn keys() -> impl Iterator<Item = u64> {
+    (0..LEN).map(|i| i * 7919 % LEN)
+}
+
+intrusive_adapter!(BoxAdapter = Box<Obj>: Obj { link: RBTreeLink });
+intrusive_adapter!(RcAdapter = Rc<Obj>: Obj { link: RBTreeLink });
+intrusive_adapter!(ArcAdapter = Arc<Obj>: Obj { link: RBTreeLink });
+
+// Runs `$bench!(name, adapter, pointer)` for each pointer type.
+macro_rules! for_each_pointer {
+    ($bench:ident) => {
+        $bench!("Box", BoxAdapter, Box);
+        $bench!("Rc", RcAdapter, Rc);
+        $bench!("Arc", ArcAdapter, Arc);
+    };
+}
+
+// Orders the objects of every adapter by their key.
+macro_rules! key_adapter {
+    ($name:expr, $adapter:ident, $pointer:ident) => {
+        impl<'a> KeyAdapter<'a> for $adapter {
+            type Key = u64;
+            fn get_key(&self, obj: &'a Obj) -> u64 {
+                obj.key
+            }
+        }
+    };
+}
+for_each_pointer!(key_adapter);
+
+macro_rules! tree {
+    ($adapter:ident, $pointer:ident) => {{
+        let mut tree = RBTree::new($adapter::new());
+        for key in keys() {
+            tree.insert($pointer::new(obj(key)));
+        }
+        tree
+    }};
+}
+
+fn insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rbtree/insert");
+    group.throughput(Throughput::Elements(LEN));
+    macro_rules! bench {
+        ($name:expr, $adapter:ident, $pointer:ident) => {
+            group.bench_function($name, |b| {
+                b.iter_batched(
+                    || {
+                        keys()
+                            .map(|key| $pointer::new(obj(key)))
+                            .collect::<Vec<_>>()
+                    },
+                    |objs| {
+                        let mut tree = RBTree::new($adapter::new());
+                        for obj in objs {
+                            tree.insert(obj);
+                        }
+                        tree
+                    },
+                    BatchSize::LargeInput,
+                )
+            });
+        };
+    }
+    for_each_pointer!(bench);
+    group.bench_function("BTreeSet", |b| {
+        b.iter(|| keys().map(black_box).collect::<BTreeSet<_>>())
+    });
+    group.finish();
+}
+
+fn find(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rbtree/find");
+    group.throughput(Throughput::Elements(LEN));
+    macro_rules! bench {
+        ($name:expr, $adapter:ident, $pointer:ident) => {
+            let tree = tree!($adapter, $pointer);
+            group.bench_function($name, |b| {
+                b.iter(|| keys().filter(|key| !tree.find(key).is_null()).count())
+            });
+        };
+    }
+    for_each_pointer!(bench);
+    let set = keys().collect::<BTreeSet<_>>();
+    group.bench_function("BTreeSet", |b| {
+        b.iter(|| keys().filter(|key| set.contains(key)).count())
+    });
+    group.finish();
+}
+
+fn iterate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rbtree/iterate");
+    group.throughput(Throughput::Elements(LEN));
+    macro_rules! bench {
+        ($name:expr, $adapter:ident, $pointer:ident) => {
+            let tree = tree!($adapter, $pointer);
+            group.bench_function($name, |b| {
+                b.iter(|| tree.iter().map(|obj| obj.key).sum::<u64>())
+            });
+        };
+    }
+    for_each_pointer!(bench);
+    let set = keys().collect::<BTreeSet<_>>();
+    group.bench_function("BTreeSet", |b| b.iter(|| set.iter().sum::<u64>()));
+    group.finish();
+}
+
+// Same as `iterate`, but with the internal iteration of `visit`.
+fn visit(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rbtree/visit");
+    group.throughput(Throughput::Elements(LEN));
+    macro_rules! bench {
+        ($name:expr, $adapter:ident, $pointer:ident) => {
+            let tree = tree!($adapter, $pointer);
+            group.bench_function($name, |b| {
+                b.iter(|| {
+                    let mut sum = 0;
+                    let _ = tree.visit(|obj| {
+                        sum += obj.key;
+                        ControlFlow::<()>::Continue(())
+                    });
+                    sum
+                })
+            });
+        };
+    }
+    for_each_pointer!(bench);
+    group.finish();
+}
+
+fn remove(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rbtree/remove");
+    group.throughput(Throughput::Elements(LEN));
+    macro_rules! bench {
+        ($name:expr, $adapter:ident, $pointer:ident) => {
+            group.bench_function($name, |b| {
+                b.iter_batched(
+                    || (tree!($adapter, $pointer), Vec::with_capacity(LEN as usize)),
+                    |(mut tree, mut objs)| {
+                        for key in keys() {
+                            objs.extend(tree.find_mut(&key).remove());
+                        }
+                        objs
+                    },
+                    BatchSize::LargeInput,
+                )
+            });
+        };
+    }
+    for_each_pointer!(bench);
+    group.bench_function("BTreeSet", |b| {
+        b.iter_batched(
+            || keys().collect::<BTreeSet<_>>(),
+            |mut set| {
+                for key in keys() {
+                    black_box(set.remove(&key));
+                }
+                set
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, insert, find, iterate, visit, remove);
+criterion_main!(benches);
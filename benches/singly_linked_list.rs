@@ -0,0 +1,187 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+// Benchmarks of `SinglyLinkedList` with `Box`, `Rc` and `Arc` pointers, used
+// as a stack, against `Vec` and `std::collections::LinkedList` holding the same values. The
+// objects of the intrusive lists are allocated outside of the measurements,
+// since avoiding that allocation is the point of an intrusive collection.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use intrusive_collections::{intrusive_adapter, SinglyLinkedList, SinglyLinkedListLink};
+use std::collections;
+use std::hint::black_box;
+use std::rc::Rc;
+use std::sync::Arc;
+
+const LEN: u64 = 1000;
+
+struct Obj {
+    link: SinglyLinkedListLink,
+    value: u64,
+}
+
+fn obj(value: u64) -> Obj {
+    Obj {
+        link: SinglyLinkedListLink::new(),
+        value,
+    }
+}
+
+intrusive_adapter!(BoxAdapter = Box<Obj>: Obj { link: SinglyLinkedListLink });
+intrusive_adapter!(RcAdapter = Rc<Obj>: Obj { link: SinglyLinkedListLink });
+intrusive_adapter!(ArcAdapter = Arc<Obj>: Obj { link: SinglyLinkedListLink });
+
+// Runs `$bench!(name, adapter, pointer)` for each pointer type.
+macro_rules! for_each_pointer {
+    ($bench:ident) => {
+        $bench!("Box", BoxAdapter, Box);
+        $bench!("Rc", RcAdapter, Rc);
+        $bench!("Arc", ArcAdapter, Arc);
+    };
+}
+
+macro_rules! list {
+    ($adapter:ident, $pointer:ident) => {{
+        let mut list = SinglyLinkedList::new($adapter::new());
+        for value in (0..LEN).rev() {
+            list.push_front($pointer::new(obj(value)));
+        }
+        list
+    }};
+}
+
+fn push_front(c: &mut Criterion) {
+    let mut group = c.benchmark_group("singly_linked_list/push_front");
+    group.throughput(Throughput::Elements(LEN));
+    macro_rules! bench {
+        ($name:expr, $adapter:ident, $pointer:ident) => {
+            group.bench_function($name, |b| {
+                b.iter_batched(
+                    || {
+                        (0..LEN)
+                            .map(|value| $pointer::new(obj(value)))
+                            .collect::<Vec<_>>()
+                    },
+                    |objs| {
+                        let mut list = SinglyLinkedList::new($adapter::new());
+                        for obj in objs {
+                            list.push_front(obj);
+                        }
+                        list
+                    },
+                    BatchSize::LargeInput,
+                )
+            });
+        };
+    }
+    for_each_pointer!(bench);
+    group.bench_function("Vec", |b| {
+        b.iter(|| (0..LEN).map(black_box).collect::<Vec<_>>())
+    });
+    group.bench_function("std::LinkedList", |b| {
+        b.iter(|| {
+            let mut std_list = collections::LinkedList::new();
+            for value in 0..LEN {
+                std_list.push_front(black_box(value));
+            }
+            std_list
+        })
+    });
+    group.finish();
+}
+
+fn find(c: &mut Criterion) {
+    let mut group = c.benchmark_group("singly_linked_list/find");
+    macro_rules! bench {
+        ($name:expr, $adapter:ident, $pointer:ident) => {
+            let list = list!($adapter, $pointer);
+            group.bench_function($name, |b| {
+                b.iter(|| list.iter().find(|obj| obj.value == black_box(LEN - 1)))
+            });
+        };
+    }
+    for_each_pointer!(bench);
+    let vec = (0..LEN).collect::<Vec<_>>();
+    group.bench_function("Vec", |b| {
+        b.iter(|| vec.iter().find(|&&value| value == black_box(LEN - 1)))
+    });
+    let std_list = (0..LEN).collect::<collections::LinkedList<_>>();
+    group.bench_function("std::LinkedList", |b| {
+        b.iter(|| std_list.iter().find(|&&value| value == black_box(LEN - 1)))
+    });
+    group.finish();
+}
+
+fn iterate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("singly_linked_list/iterate");
+    group.throughput(Throughput::Elements(LEN));
+    macro_rules! bench {
+        ($name:expr, $adapter:ident, $pointer:ident) => {
+            let list = list!($adapter, $pointer);
+            group.bench_function($name, |b| {
+                b.iter(|| list.iter().map(|obj| obj.value).sum::<u64>())
+            });
+        };
+    }
+    for_each_pointer!(bench);
+    let vec = (0..LEN).collect::<Vec<_>>();
+    group.bench_function("Vec", |b| b.iter(|| vec.iter().sum::<u64>()));
+    let std_list = (0..LEN).collect::<collections::LinkedList<_>>();
+    group.bench_function("std::LinkedList", |b| {
+        b.iter(|| std_list.iter().sum::<u64>())
+    });
+    group.finish();
+}
+
+fn pop_front(c: &mut Criterion) {
+    let mut group = c.benchmark_group("singly_linked_list/pop_front");
+    group.throughput(Throughput::Elements(LEN));
+    macro_rules! bench {
+        ($name:expr, $adapter:ident, $pointer:ident) => {
+            group.bench_function($name, |b| {
+                b.iter_batched(
+                    || (list!($adapter, $pointer), Vec::with_capacity(LEN as usize)),
+                    |(mut list, mut objs)| {
+                        while let Some(obj) = list.pop_front() {
+                            objs.push(obj);
+                        }
+                        objs
+                    },
+                    BatchSize::LargeInput,
+                )
+            });
+        };
+    }
+    for_each_pointer!(bench);
+    group.bench_function("Vec", |b| {
+        b.iter_batched(
+            || (0..LEN).collect::<Vec<_>>(),
+            |mut vec| {
+                while let Some(value) = vec.pop() {
+                    black_box(value);
+                }
+                vec
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("std::LinkedList", |b| {
+        b.iter_batched(
+            || (0..LEN).collect::<collections::LinkedList<_>>(),
+            |mut std_list| {
+                while let Some(value) = std_list.pop_front() {
+                    black_box(value);
+                }
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, push_front, find, iterate, pop_front);
+criterion_main!(benches);
@@ -0,0 +1,7 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    intrusive_collections::ops_interpreter::run_singly_linked_list(data);
+});
@@ -6,8 +6,10 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use crate::link_ops::LinkOps;
-use crate::pointer_ops::PointerOps;
+use core::fmt;
+
+use crate::link_ops::{LinkOps, LinkStateOps};
+use crate::pointer_ops::{PointerOps, TryPointerOps};
 
 /// Trait for a adapter which allows a type to be inserted into an intrusive
 /// collection.
@@ -75,11 +77,521 @@ pub unsafe trait Adapter {
 
     /// Returns a reference to the pointer converter.
     fn pointer_ops(&self) -> &Self::PointerOps;
+
+    /// Returns a name for this adapter which is used in panic messages.
+    ///
+    /// By default this is the name of the adapter type.
+    #[inline]
+    fn debug_name(&self) -> &'static str {
+        core::any::type_name::<Self>()
+    }
+
+    /// Called by collections when an object is inserted.
+    ///
+    /// This is called once the link of the object has been acquired, before
+    /// the object is linked into the collection. If this panics the link is
+    /// released and the object is dropped without being inserted, except in
+    /// an `AppendOnlyList`, which leaks it with its link in the linked state.
+    ///
+    /// The default implementation does nothing.
+    #[inline]
+    fn on_insert(&self, value: &<Self::PointerOps as PointerOps>::Value) {
+        let _ = value;
+    }
+
+    /// Called by collections when an object is removed.
+    ///
+    /// This is called after the object has been unlinked, right before the
+    /// pointer to it is returned or dropped. It is not called by `fast_clear`
+    /// or when objects are moved between collections with `splice_*` or
+    /// `split_*`, since those do not unlink objects.
+    ///
+    /// The default implementation does nothing.
+    #[inline]
+    fn on_remove(&self, value: &<Self::PointerOps as PointerOps>::Value) {
+        let _ = value;
+    }
+}
+
+/// Hooks called when objects are inserted into or removed from a collection.
+///
+/// This allows `Adapter::on_insert` and `Adapter::on_remove` to be provided
+/// for adapters generated by `intrusive_adapter!`, with a `hooks` clause. As
+/// the hooks have no access to the adapter, any state they update must live
+/// in the object itself or in a `static`.
+///
+/// # Examples
+///
+/// ```
+/// use core::cell::Cell;
+/// use intrusive_collections::{intrusive_adapter, AdapterHooks, LinkedList, LinkedListLink};
+///
+/// struct Request {
+///     link: LinkedListLink,
+///     queued: Cell<u32>,
+/// }
+///
+/// struct CountQueued;
+/// impl AdapterHooks<Request> for CountQueued {
+///     fn on_insert(request: &Request) {
+///         request.queued.set(request.queued.get() + 1);
+///     }
+/// }
+///
+/// intrusive_adapter!(RequestAdapter = Box<Request>: Request { link: LinkedListLink }
+///     hooks: CountQueued);
+///
+/// let mut list = LinkedList::new(RequestAdapter::new());
+/// list.push_back(Box::new(Request { link: LinkedListLink::new(), queued: Cell::new(0) }));
+/// let request = list.pop_front().unwrap();
+/// list.push_back(request);
+/// assert_eq!(list.front().get().unwrap().queued.get(), 2);
+/// ```
+pub trait AdapterHooks<T: ?Sized> {
+    /// Called when an object is inserted into a collection.
+    #[inline]
+    fn on_insert(value: &T) {
+        let _ = value;
+    }
+
+    /// Called when an object is removed from a collection.
+    #[inline]
+    fn on_remove(value: &T) {
+        let _ = value;
+    }
+}
+
+// Implements `Debug` for the cursors of collections. The current element and
+// its neighbors are shown as the addresses of the values, with `None` for the
+// null object. `prev` is `None` for collections which can't find the previous
+// element.
+pub(crate) fn debug_cursor<A: Adapter>(
+    f: &mut fmt::Formatter<'_>,
+    name: &str,
+    adapter: &A,
+    current: Option<<A::LinkOps as LinkOps>::LinkPtr>,
+    prev: Option<Option<<A::LinkOps as LinkOps>::LinkPtr>>,
+    next: Option<<A::LinkOps as LinkOps>::LinkPtr>,
+) -> fmt::Result {
+    // Links in a collection always belong to a value of the adapter.
+    let value = |link: Option<_>| link.map(|link| unsafe { adapter.get_value(link) });
+    let mut s = f.debug_struct(name);
+    s.field("current", &value(current));
+    if let Some(prev) = prev {
+        s.field("prev", &value(prev));
+    }
+    s.field("next", &value(next));
+    s.finish()
+}
+
+// Same as `debug_cursor`, but implementing `defmt::Format`.
+#[cfg(feature = "defmt")]
+pub(crate) fn defmt_cursor<A: Adapter>(
+    f: defmt::Formatter<'_>,
+    name: &str,
+    adapter: &A,
+    current: Option<<A::LinkOps as LinkOps>::LinkPtr>,
+    prev: Option<Option<<A::LinkOps as LinkOps>::LinkPtr>>,
+    next: Option<<A::LinkOps as LinkOps>::LinkPtr>,
+) {
+    // Links in a collection always belong to a value of the adapter. The
+    // values may be unsized, so only their addresses are kept.
+    let value = |link: Option<_>| link.map(|link| unsafe { adapter.get_value(link) } as *const u8);
+    match prev {
+        Some(prev) => defmt::write!(
+            f,
+            "{=str} {{ current: {}, prev: {}, next: {} }}",
+            name,
+            value(current),
+            value(prev),
+            value(next)
+        ),
+        None => defmt::write!(
+            f,
+            "{=str} {{ current: {}, next: {} }}",
+            name,
+            value(current),
+            value(next)
+        ),
+    }
+}
+
+// Implements `defmt::Format` for collections, formatting their values as a
+// list.
+#[cfg(feature = "defmt")]
+pub(crate) fn defmt_list<'a, T: defmt::Format + ?Sized + 'a>(
+    f: defmt::Formatter<'_>,
+    values: impl Iterator<Item = &'a T>,
+) {
+    defmt::write!(f, "[");
+    for (i, value) in values.enumerate() {
+        if i != 0 {
+            defmt::write!(f, ", ");
+        }
+        defmt::write!(f, "{}", value);
+    }
+    defmt::write!(f, "]");
+}
+
+// Converts the link of an object which has just been unlinked back into an
+// owned pointer, calling the removal hook of the adapter.
+#[inline]
+pub(crate) unsafe fn unlinked_pointer<A: Adapter>(
+    adapter: &A,
+    link: <A::LinkOps as LinkOps>::LinkPtr,
+) -> <A::PointerOps as PointerOps>::Pointer {
+    let value = adapter.get_value(link);
+    adapter.on_remove(&*value);
+    adapter.pointer_ops().from_raw(value)
+}
+
+// The result of converting an element back with `TryPointerOps::try_from_raw`.
+pub(crate) type TryPointer<A> = Result<
+    <<A as Adapter>::PointerOps as PointerOps>::Pointer,
+    <<A as Adapter>::PointerOps as TryPointerOps>::Error,
+>;
+
+// The result of moving an element into another collection: `None` if there
+// was no element to move, and otherwise its link in the destination, as an
+// error if the element was already linked there and was left where it was.
+pub(crate) type Transfer<A> = Option<
+    Result<
+        <<A as Adapter>::LinkOps as LinkOps>::LinkPtr,
+        <<A as Adapter>::LinkOps as LinkOps>::LinkPtr,
+    >,
+>;
+
+// Same as `unlinked_pointer`, but with the fallible conversion of
+// `TryPointerOps`.
+#[inline]
+pub(crate) unsafe fn try_unlinked_pointer<A: Adapter>(
+    adapter: &A,
+    link: <A::LinkOps as LinkOps>::LinkPtr,
+) -> TryPointer<A>
+where
+    A::PointerOps: TryPointerOps,
+{
+    let value = adapter.get_value(link);
+    adapter.on_remove(&*value);
+    adapter.pointer_ops().try_from_raw(value)
+}
+
+// Drop guard for an object whose link has been acquired by a collection but
+// which isn't linked into it yet, held while user code runs: the `on_insert`
+// hook, and the key or priority comparisons which find where the object goes.
+// If that code panics, the link is released and the object is dropped, so the
+// collection is left untouched and the object isn't leaked in the linked
+// state. `disarm` must be called before linking the object.
+pub(crate) struct AcquiredLink<'a, A: Adapter> {
+    adapter: &'a mut A,
+    link: <A::LinkOps as LinkOps>::LinkPtr,
+}
+
+impl<'a, A: Adapter> AcquiredLink<'a, A> {
+    #[inline]
+    pub(crate) unsafe fn new(
+        adapter: &'a mut A,
+        link: <A::LinkOps as LinkOps>::LinkPtr,
+    ) -> AcquiredLink<'a, A> {
+        AcquiredLink { adapter, link }
+    }
+
+    #[inline]
+    pub(crate) fn adapter(&self) -> &A {
+        self.adapter
+    }
+
+    // Calls the `on_insert` hook of the adapter for the object.
+    #[inline]
+    pub(crate) fn on_insert(&self) {
+        unsafe { link_acquired(&*self.adapter, self.adapter.get_value(self.link)) }
+    }
+
+    #[inline]
+    pub(crate) fn disarm(self) {
+        core::mem::forget(self);
+    }
+}
+
+impl<A: Adapter> Drop for AcquiredLink<'_, A> {
+    fn drop(&mut self) {
+        unsafe {
+            self.adapter.link_ops_mut().release_link(self.link);
+            let value = self.adapter.get_value(self.link);
+            drop(self.adapter.pointer_ops().from_raw(value));
+        }
+    }
+}
+
+// Calls the hooks of the pointer operations and of the adapter for an object
+// passed to `into_raw`, whose link has just been acquired.
+#[inline]
+pub(crate) unsafe fn link_acquired<A: Adapter>(
+    adapter: &A,
+    value: *const <A::PointerOps as PointerOps>::Value,
+) {
+    adapter.pointer_ops().on_link_acquired(value);
+    adapter.on_insert(&*value);
+}
+
+// Calls the `on_insert` hook for an object whose link has just been acquired,
+// releasing the link and dropping the object if it panics.
+#[inline]
+pub(crate) unsafe fn acquired_on_insert<A: Adapter>(
+    adapter: &mut A,
+    link: <A::LinkOps as LinkOps>::LinkPtr,
+) {
+    let guard = AcquiredLink::new(adapter, link);
+    guard.on_insert();
+    guard.disarm();
+}
+
+// Checks whether the link of an object is currently linked into a collection,
+// without changing the state of the link.
+#[inline]
+pub(crate) unsafe fn value_is_linked<A: Adapter>(
+    adapter: &A,
+    value: *const <A::PointerOps as PointerOps>::Value,
+) -> bool
+where
+    A::LinkOps: LinkStateOps,
+{
+    adapter.link_ops().is_linked(adapter.get_link(value))
+}
+
+// Panics after a method creating a cursor from a pointer was given an object
+// which `problem` describes, with the `debug-checks` feature. `collection` is
+// the debug name of the collection.
+#[cfg(feature = "debug-checks")]
+#[cold]
+#[track_caller]
+pub(crate) fn bad_cursor_ptr<A: Adapter>(
+    adapter: &A,
+    collection: Option<&'static str>,
+    value: *const <A::PointerOps as PointerOps>::Value,
+    problem: &str,
+) -> ! {
+    panic!(
+        "attempted to create a cursor from an object {} (adapter: {}, collection: {}, object: {:p})",
+        problem,
+        adapter.debug_name(),
+        collection.unwrap_or("<unnamed>"),
+        value,
+    );
+}
+
+// Panics after a method was given an object which the `debug_owner` feature
+// recorded as being in another collection. `collection` is the debug name of
+// the collection.
+#[cfg(feature = "debug_owner")]
+#[cold]
+#[track_caller]
+pub(crate) fn wrong_owner<A: Adapter>(adapter: &A, collection: Option<&'static str>) -> ! {
+    #[cfg(debug_assertions)]
+    panic!(
+        "attempted to use an object which is not in this collection (adapter: {}, collection: {})",
+        adapter.debug_name(),
+        collection.unwrap_or("<unnamed>"),
+    );
+    #[cfg(not(debug_assertions))]
+    {
+        let _ = (adapter, collection);
+        panic!("attempted to use an object which is not in this collection");
+    }
+}
+
+// Panics if an element can't be moved from a collection using `src` into one
+// using `dest` without converting it, because their pointer operations don't
+// have the same state. This is checked before either collection is modified.
+#[inline]
+#[track_caller]
+pub(crate) fn check_transfer<A, B>(src: &A, dest: &B)
+where
+    A: Adapter,
+    B: Adapter<PointerOps = A::PointerOps>,
+{
+    if !src.pointer_ops().same_state(dest.pointer_ops()) {
+        different_pointer_ops();
+    }
+}
+
+#[cold]
+#[track_caller]
+fn different_pointer_ops() -> ! {
+    panic!("attempted to transfer an object between collections whose pointer operations differ");
+}
+
+// Panics after an insertion method was given an object which is already
+// linked. `collection` is the debug name of the collection, and `owner` holds
+// the identifiers of the collection the object is in and of this one, for
+// collections which record them with the `debug_owner` feature.
+//
+// With the `debug-checks` feature, the message also names the address of the
+// object and the collection it is in, even in release builds.
+#[cold]
+pub(crate) fn already_linked<A: Adapter>(
+    adapter: &A,
+    collection: Option<&'static str>,
+    value: *const <A::PointerOps as PointerOps>::Value,
+    owner: Option<(usize, usize)>,
+) -> ! {
+    #[cfg(feature = "debug-checks")]
+    {
+        struct LinkedInto(Option<(usize, usize)>);
+        impl core::fmt::Display for LinkedInto {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                match self.0 {
+                    None => Ok(()),
+                    Some((0, _)) => write!(f, ", linked into: another collection"),
+                    Some((owner, this)) if owner == this => {
+                        write!(f, ", linked into: this collection")
+                    }
+                    Some((owner, _)) => write!(f, ", linked into: collection #{}", owner),
+                }
+            }
+        }
+
+        panic!(
+            "attempted to insert an object that is already linked (adapter: {}, collection: {}, object: {:p}{})",
+            adapter.debug_name(),
+            collection.unwrap_or("<unnamed>"),
+            value,
+            LinkedInto(owner),
+        );
+    }
+    #[cfg(not(feature = "debug-checks"))]
+    {
+        let _ = (value, owner);
+        #[cfg(debug_assertions)]
+        panic!(
+            "attempted to insert an object that is already linked (adapter: {}, collection: {})",
+            adapter.debug_name(),
+            collection.unwrap_or("<unnamed>"),
+        );
+        #[cfg(not(debug_assertions))]
+        {
+            let _ = (adapter, collection);
+            panic!("attempted to insert an object that is already linked");
+        }
+    }
+}
+
+// Returns `true` if dropping a collection using `adapter` while it still
+// contains objects should panic, which is the case with the `debug_leaks`
+// feature for pointer types which don't free the objects.
+#[inline]
+pub(crate) fn checks_leaks<A: Adapter>(adapter: &A) -> bool {
+    let _ = adapter;
+    cfg!(feature = "debug_leaks") && !<A::PointerOps as PointerOps>::OWNS_VALUE
+}
+
+// Panics after a collection which still contained objects was dropped, for
+// the `debug_leaks` feature. The collection has been cleared first, so that
+// the objects are no longer linked.
+//
+// This is called from `drop`, where panicking while the thread is already
+// unwinding would abort and lose the original panic, so the check is skipped
+// in that case. Without the `std` feature this can't be detected, so the
+// check is only done in debug builds, like a `debug_assert!`.
+#[cold]
+pub(crate) fn leaked_on_drop<A: Adapter>(adapter: &A, collection: Option<&'static str>) {
+    #[cfg(feature = "std")]
+    let check = !std::thread::panicking();
+    #[cfg(not(feature = "std"))]
+    let check = cfg!(debug_assertions);
+    if check {
+        panic!(
+            "dropped a collection which still contains objects that its pointer type doesn't free (adapter: {}, collection: {})",
+            adapter.debug_name(),
+            collection.unwrap_or("<unnamed>"),
+        );
+    }
+}
+
+/// Gets a raw pointer to an outer object from a pointer to a field located
+/// `offset` bytes into that object.
+///
+/// This is the function form of `container_of!`, for use in manual `Adapter`
+/// implementations where the offset isn't that of a direct field, such as a
+/// link inside a nested struct or inside an FFI struct with a known layout.
+/// The pointer provenance is preserved, which is what makes this sound where
+/// an integer round-trip would not be.
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::{container_of_ptr, field_ptr, offset_of};
+///
+/// struct Inner { a: u32, b: u32 }
+/// struct Outer { x: u64, inner: Inner }
+///
+/// let outer = Outer { x: 1, inner: Inner { a: 2, b: 3 } };
+/// let offset = offset_of!(Outer, inner) + offset_of!(Inner, b);
+/// unsafe {
+///     let b: *const u32 = field_ptr(&outer, offset);
+///     assert_eq!(*b, 3);
+///     let outer2: *const Outer = container_of_ptr(b, offset);
+///     assert_eq!(&outer as *const Outer, outer2);
+/// }
+/// ```
+///
+/// # Safety
+///
+/// `field` must point to a field located exactly `offset` bytes after the
+/// start of a valid object of type `T`.
+#[inline]
+pub unsafe fn container_of_ptr<T, F: ?Sized>(field: *const F, offset: usize) -> *const T {
+    #[allow(clippy::cast_ptr_alignment)]
+    {
+        (field as *const u8).sub(offset) as *const T
+    }
+}
+
+/// Gets a raw pointer to an unsized object from a pointer to one of its
+/// fields, located `offset` bytes after its start.
+///
+/// This is the same as `container_of_ptr`, but reconstructs the pointer
+/// metadata with `Erasable::unerase`, so it can be used in the `get_value` of
+/// an adapter for values which store their own metadata, such as slice DSTs
+/// with a length header.
+///
+/// # Safety
+///
+/// `field` must point to a field located exactly `offset` bytes after the
+/// start of a valid object of type `T`.
+#[cfg(feature = "erasable")]
+#[inline]
+pub unsafe fn container_of_erased<T: ?Sized + erasable::Erasable, F: ?Sized>(
+    field: *const F,
+    offset: usize,
+) -> *const T {
+    let start = (field as *const u8).sub(offset) as *mut u8;
+    T::unerase(core::ptr::NonNull::new_unchecked(start).cast()).as_ptr()
+}
+
+/// Gets a raw pointer to a field located `offset` bytes into the object
+/// pointed to by `container`.
+///
+/// This is the inverse of `container_of_ptr`.
+///
+/// # Safety
+///
+/// `container` must point to a valid object which has a field of type `F`
+/// located exactly `offset` bytes after its start.
+#[inline]
+pub unsafe fn field_ptr<T: ?Sized, F>(container: *const T, offset: usize) -> *const F {
+    #[allow(clippy::cast_ptr_alignment)]
+    {
+        (container as *const u8).add(offset) as *const F
+    }
 }
 
 /// Unsafe macro to get a raw pointer to an outer object from a pointer to one
 /// of its fields.
 ///
+/// The field pointer may be either a reference or a raw pointer. See
+/// `container_of_ptr` if the offset of the field has to be computed manually.
+///
 /// # Examples
 ///
 /// ```
@@ -99,14 +611,30 @@ pub unsafe trait Adapter {
 #[macro_export]
 macro_rules! container_of {
     ($ptr:expr, $container:path, $field:ident) => {
-        #[allow(clippy::cast_ptr_alignment)]
-        {
-            ($ptr as *const _ as *const u8).sub($crate::offset_of!($container, $field))
-                as *const $container
-        }
+        $crate::container_of_ptr::<$container, u8>(
+            $ptr as *const _ as *const u8,
+            $crate::offset_of!($container, $field),
+        )
     };
 }
 
+// Expands its input only when offsets can be computed in constant contexts,
+// which is guaranteed with `core::mem::offset_of!`.
+#[doc(hidden)]
+#[cfg(feature = "offset_of")]
+#[macro_export]
+macro_rules! __intrusive_if_const_offset_of {
+    ($($tt:tt)*) => {
+        $($tt)*
+    };
+}
+#[doc(hidden)]
+#[cfg(not(feature = "offset_of"))]
+#[macro_export]
+macro_rules! __intrusive_if_const_offset_of {
+    ($($tt:tt)*) => {};
+}
+
 /// Macro to generate an implementation of `Adapter` for a given set of types.
 /// In particular this will automatically generate implementations of the
 /// `get_value` and `get_link` methods for a given named field in a struct.
@@ -140,6 +668,94 @@ macro_rules! container_of {
 /// supported in the generic argument list. You must list any trait bounds in
 /// a separate `where` clause at the end of the macro.
 ///
+/// Const generic parameters are supported as well, with the usual
+/// `const N: usize` syntax:
+///
+/// ```rust,ignore
+/// intrusive_adapter!(Adapter<const N: usize> = Box<Buffer<N>>: Buffer<N> { link: LinkType });
+/// ```
+///
+/// # Generic link types
+///
+/// The link type may itself be one of the generic arguments of the adapter.
+/// This allows the same value type and adapter definition to be used with
+/// both the normal and the atomic variant of a link:
+///
+/// ```rust,ignore
+/// intrusive_adapter!(Adapter<L> = Pointer: Value<L> { link_field: L });
+/// ```
+///
+/// The macro automatically adds the necessary bounds on the link type, so no
+/// `where` clause is needed for it. Any link type which implements
+/// `DefaultLinkOps` with `Copy` link operations that implement
+/// `LinkFieldOps` can be used, which includes all the links in this crate.
+///
+/// Other impls for such an adapter, such as a `KeyAdapter` impl, need to
+/// repeat the `L: DefaultLinkOps, L::Ops: LinkFieldOps<L>` bounds.
+///
+/// # Arrays of links
+///
+/// If the link field is an array of links, the adapter chooses which link of
+/// the array it uses when it is created. Such an adapter has no `NEW`
+/// constant and does not implement `Default`; instead its `new` method takes
+/// the index of the link:
+///
+/// ```rust,ignore
+/// intrusive_adapter!(Adapter = Pointer: Value { link_field: [LinkType; N] });
+/// let adapter = Adapter::new(index);
+/// ```
+///
+/// This is useful for objects which can be in one of a set of lists, for
+/// example one list per CPU or per priority level.
+///
+/// # Priorities
+///
+/// An implementation of `PriorityAdapter` can be generated along with the
+/// adapter by adding a `priority` clause after the link field. It gives the
+/// priority type and a closure-like expression, whose body must be a block,
+/// to compute the priority of a value:
+///
+/// ```rust,ignore
+/// intrusive_adapter!(Adapter = Pointer: Value { link_field: LinkType }
+///     priority: PriorityType = |value| { value.priority });
+/// ```
+///
+/// The `priority` clause comes before the `where` clause, if there is one.
+///
+/// # Ghost cells
+///
+/// With the `ghost-cell` feature, an implementation of `GhostAdapter` can be
+/// generated along with the adapter by adding a `ghost` clause after the link
+/// field, which names the `GhostCell` field of the value and gives its type:
+///
+/// ```rust,ignore
+/// intrusive_adapter!(Adapter<'brand> = Pointer: Value { link_field: LinkType }
+///     ghost { cell_field: GhostCell<'brand, DataType> });
+/// ```
+///
+/// The `ghost` clause can't be combined with a `priority` clause.
+///
+/// # Hooks
+///
+/// A `hooks` clause after the link field, and after the `priority` or
+/// `ghost` clause if there is one, implements `Adapter::on_insert` and `Adapter::on_remove` by
+/// calling the functions of a type implementing `AdapterHooks`:
+///
+/// ```rust,ignore
+/// intrusive_adapter!(Adapter = Pointer: Value { link_field: LinkType } hooks: HooksType);
+/// ```
+///
+/// # Offsets
+///
+/// The offset of the link field is computed with `offset_of!`, which is
+/// re-exported from `memoffset` or, with the `offset_of` feature, from
+/// `core::mem`. The generated code never creates a reference to the value to
+/// find its link, only raw pointers.
+///
+/// With the `offset_of` feature, the adapter also has a `LINK_OFFSET`
+/// associated constant containing the offset of the link field in the value,
+/// which can be used in constant contexts.
+///
 /// # Examples
 ///
 /// ```
@@ -161,60 +777,294 @@ macro_rules! container_of {
 ///     val: T,
 /// }
 /// intrusive_adapter!(MyAdapter4<'a, T> = &'a Test2<T>: Test2<T> { link: LinkedListLink } where T: ?Sized + Clone + 'a);
+///
+/// pub struct Test3<L> {
+///     link: L,
+///     val: u32,
+/// }
+/// intrusive_adapter!(MyAdapter5<L> = Box<Test3<L>>: Test3<L> { link: L });
+///
+/// type LocalAdapter = MyAdapter5<LinkedListLink>;
+/// type SharedAdapter = MyAdapter5<intrusive_collections::LinkedListAtomicLink>;
+///
+/// pub struct Test4<T> {
+///     link: LinkedListLink,
+///     priority: T,
+/// }
+/// intrusive_adapter!(MyAdapter6<T> = Box<Test4<T>>: Test4<T> { link: LinkedListLink }
+///     priority: T = |test| { test.priority.clone() } where T: Ord + Clone);
+///
+/// pub struct Test5<T, const N: usize> {
+///     link: LinkedListLink,
+///     data: [T; N],
+/// }
+/// intrusive_adapter!(MyAdapter7<'a, T, const N: usize> = &'a Test5<T, N>: Test5<T, N> { link: LinkedListLink } where T: 'a);
 /// ```
 #[macro_export]
 macro_rules! intrusive_adapter {
     (@impl
-        $(#[$attr:meta])* $vis:vis $name:ident ($($args:tt),*)
-        = $pointer:ty: $value:path { $field:ident: $link:ty } $($where_:tt)*
+        $(#[$attr:meta])* $vis:vis $name:ident ($($decl:tt)*) ($($use:tt)*)
+        = $pointer:ty: $value:path { $field:ident: [$link:ty; $len:expr] }
+        $(hooks: $hooks:ty)? $(where $($where_:tt)*)?
+    ) => {
+        #[allow(explicit_outlives_requirements)]
+        $(#[$attr])*
+        $vis struct $name<$($decl)*>
+        where
+            $link: $crate::DefaultLinkOps,
+            $($($where_)*)?
+        {
+            link_ops: <$link as $crate::DefaultLinkOps>::Ops,
+            pointer_ops: $crate::DefaultPointerOps<$pointer>,
+            index: usize,
+        }
+        unsafe impl<$($decl)*> Send for $name<$($use)*>
+        where
+            $link: $crate::DefaultLinkOps,
+            $($($where_)*)?
+        {}
+        unsafe impl<$($decl)*> Sync for $name<$($use)*>
+        where
+            $link: $crate::DefaultLinkOps,
+            $($($where_)*)?
+        {}
+        impl<$($decl)*> Copy for $name<$($use)*>
+        where
+            $link: $crate::DefaultLinkOps,
+            <$link as $crate::DefaultLinkOps>::Ops: Copy,
+            $($($where_)*)?
+        {}
+        impl<$($decl)*> Clone for $name<$($use)*>
+        where
+            $link: $crate::DefaultLinkOps,
+            <$link as $crate::DefaultLinkOps>::Ops: Copy,
+            $($($where_)*)?
+        {
+            #[inline]
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+        #[allow(dead_code)]
+        impl<$($decl)*> $name<$($use)*>
+        where
+            $link: $crate::DefaultLinkOps,
+            $($($where_)*)?
+        {
+            /// Creates an adapter which uses the link at `index` in the array.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `index` is out of bounds for the array.
+            #[inline]
+            pub const fn new(index: usize) -> Self {
+                assert!(index < $len, "link index out of bounds");
+                $name {
+                    link_ops: <$link as $crate::DefaultLinkOps>::NEW,
+                    pointer_ops: $crate::DefaultPointerOps::<$pointer>::new(),
+                    index,
+                }
+            }
+            /// Returns the index of the link used by this adapter.
+            #[inline]
+            pub const fn index(&self) -> usize {
+                self.index
+            }
+            $crate::__intrusive_if_const_offset_of! {
+                /// Offset of the link array in the value.
+                pub const LINK_OFFSET: usize = $crate::offset_of!($value, $field);
+            }
+        }
+        #[allow(dead_code, unsafe_code)]
+        unsafe impl<$($decl)*> $crate::Adapter for $name<$($use)*>
+        where
+            $link: $crate::DefaultLinkOps,
+            <$link as $crate::DefaultLinkOps>::Ops: $crate::LinkFieldOps<$link>,
+            $($($where_)*)?
+        {
+            type LinkOps = <$link as $crate::DefaultLinkOps>::Ops;
+            type PointerOps = $crate::DefaultPointerOps<$pointer>;
+
+            #[inline]
+            unsafe fn get_value(&self, link: <Self::LinkOps as $crate::LinkOps>::LinkPtr) -> *const <Self::PointerOps as $crate::PointerOps>::Value {
+                let link = $crate::LinkFieldOps::<$link>::link_field(&self.link_ops, link);
+                $crate::container_of!(link.sub(self.index), $value, $field)
+            }
+            #[inline]
+            unsafe fn get_link(&self, value: *const <Self::PointerOps as $crate::PointerOps>::Value) -> <Self::LinkOps as $crate::LinkOps>::LinkPtr {
+                let links: *const $link = $crate::field_ptr(value, $crate::offset_of!($value, $field));
+                $crate::LinkFieldOps::<$link>::link_ptr(&self.link_ops, links.add(self.index))
+            }
+            #[inline]
+            fn link_ops(&self) -> &Self::LinkOps {
+                &self.link_ops
+            }
+            #[inline]
+            fn link_ops_mut(&mut self) -> &mut Self::LinkOps {
+                &mut self.link_ops
+            }
+            #[inline]
+            fn pointer_ops(&self) -> &Self::PointerOps {
+                &self.pointer_ops
+            }
+            $(
+                #[inline]
+                fn on_insert(&self, value: &<Self::PointerOps as $crate::PointerOps>::Value) {
+                    <$hooks as $crate::AdapterHooks<<Self::PointerOps as $crate::PointerOps>::Value>>::on_insert(value)
+                }
+                #[inline]
+                fn on_remove(&self, value: &<Self::PointerOps as $crate::PointerOps>::Value) {
+                    <$hooks as $crate::AdapterHooks<<Self::PointerOps as $crate::PointerOps>::Value>>::on_remove(value)
+                }
+            )?
+        }
+    };
+    (@impl
+        $(#[$attr:meta])* $vis:vis $name:ident ($($decl:tt)*) ($($use:tt)*)
+        = $pointer:ty: $value:path { $field:ident: $link:ty }
+        priority: $priority:ty = |$priority_value:ident| $priority_body:block
+        $(hooks: $hooks:ty)? $(where $($where_:tt)*)?
+    ) => {
+        $crate::intrusive_adapter!(@impl
+            $(#[$attr])* $vis $name ($($decl)*) ($($use)*)
+            = $pointer: $value { $field: $link } $(hooks: $hooks)? $(where $($where_)*)?
+        );
+        impl<$($decl)*> $crate::PriorityAdapter for $name<$($use)*>
+        where
+            $link: $crate::DefaultLinkOps,
+            <$link as $crate::DefaultLinkOps>::Ops: $crate::LinkFieldOps<$link>,
+            $($($where_)*)?
+        {
+            type Priority = $priority;
+
+            #[inline]
+            fn get_priority(&self, $priority_value: &<Self::PointerOps as $crate::PointerOps>::Value) -> $priority $priority_body
+        }
+    };
+    (@impl
+        $(#[$attr:meta])* $vis:vis $name:ident ($($decl:tt)*) ($($use:tt)*)
+        = $pointer:ty: $value:path { $field:ident: $link:ty }
+        ghost { $cell:ident: $($cell_ty:ident)::+ <$brand:lifetime, $data:ty> }
+        $(hooks: $hooks:ty)? $(where $($where_:tt)*)?
+    ) => {
+        $crate::intrusive_adapter!(@impl
+            $(#[$attr])* $vis $name ($($decl)*) ($($use)*)
+            = $pointer: $value { $field: $link } $(hooks: $hooks)? $(where $($where_)*)?
+        );
+        // The cell is taken from the value by destructuring it rather than
+        // with a field access, which could go through a `Deref` impl and
+        // return a cell shared between objects.
+        #[allow(unsafe_code)]
+        unsafe impl<$($decl)*> $crate::GhostAdapter<$brand> for $name<$($use)*>
+        where
+            $link: $crate::DefaultLinkOps,
+            <$link as $crate::DefaultLinkOps>::Ops: $crate::LinkFieldOps<$link>,
+            $($($where_)*)?
+        {
+            type Data = $data;
+
+            #[inline]
+            fn get_cell<'__cell>(
+                &self,
+                value: &'__cell <Self::PointerOps as $crate::PointerOps>::Value,
+            ) -> &'__cell $($cell_ty)::+<$brand, $data> {
+                let $value { $cell: cell, .. } = value;
+                cell
+            }
+        }
+    };
+    (@impl
+        $(#[$attr:meta])* $vis:vis $name:ident ($($decl:tt)*) ($($use:tt)*)
+        = $pointer:ty: $value:path { $field:ident: $link:ty }
+        $(hooks: $hooks:ty)? $(where $($where_:tt)*)?
     ) => {
         #[allow(explicit_outlives_requirements)]
         $(#[$attr])*
-        $vis struct $name<$($args),*> $($where_)* {
+        $vis struct $name<$($decl)*>
+        where
+            $link: $crate::DefaultLinkOps,
+            $($($where_)*)?
+        {
             link_ops: <$link as $crate::DefaultLinkOps>::Ops,
             pointer_ops: $crate::DefaultPointerOps<$pointer>,
         }
-        unsafe impl<$($args),*> Send for $name<$($args),*> $($where_)* {}
-        unsafe impl<$($args),*> Sync for $name<$($args),*> $($where_)* {}
-        impl<$($args),*> Copy for $name<$($args),*> $($where_)* {}
-        impl<$($args),*> Clone for $name<$($args),*> $($where_)* {
+        unsafe impl<$($decl)*> Send for $name<$($use)*>
+        where
+            $link: $crate::DefaultLinkOps,
+            $($($where_)*)?
+        {}
+        unsafe impl<$($decl)*> Sync for $name<$($use)*>
+        where
+            $link: $crate::DefaultLinkOps,
+            $($($where_)*)?
+        {}
+        impl<$($decl)*> Copy for $name<$($use)*>
+        where
+            $link: $crate::DefaultLinkOps,
+            <$link as $crate::DefaultLinkOps>::Ops: Copy,
+            $($($where_)*)?
+        {}
+        impl<$($decl)*> Clone for $name<$($use)*>
+        where
+            $link: $crate::DefaultLinkOps,
+            <$link as $crate::DefaultLinkOps>::Ops: Copy,
+            $($($where_)*)?
+        {
             #[inline]
             fn clone(&self) -> Self {
                 *self
             }
         }
-        impl<$($args),*> Default for $name<$($args),*> $($where_)* {
+        impl<$($decl)*> Default for $name<$($use)*>
+        where
+            $link: $crate::DefaultLinkOps,
+            $($($where_)*)?
+        {
             #[inline]
             fn default() -> Self {
                 Self::NEW
             }
         }
         #[allow(dead_code)]
-        impl<$($args),*> $name<$($args),*> $($where_)* {
+        impl<$($decl)*> $name<$($use)*>
+        where
+            $link: $crate::DefaultLinkOps,
+            $($($where_)*)?
+        {
             pub const NEW: Self = $name {
                 link_ops: <$link as $crate::DefaultLinkOps>::NEW,
                 pointer_ops: $crate::DefaultPointerOps::<$pointer>::new(),
             };
             #[inline]
-            pub fn new() -> Self {
+            pub const fn new() -> Self {
                 Self::NEW
             }
+            $crate::__intrusive_if_const_offset_of! {
+                /// Offset of the link in the value.
+                pub const LINK_OFFSET: usize = $crate::offset_of!($value, $field);
+            }
         }
         #[allow(dead_code, unsafe_code)]
-        unsafe impl<$($args),*> $crate::Adapter for $name<$($args),*> $($where_)* {
+        unsafe impl<$($decl)*> $crate::Adapter for $name<$($use)*>
+        where
+            $link: $crate::DefaultLinkOps,
+            <$link as $crate::DefaultLinkOps>::Ops: $crate::LinkFieldOps<$link>,
+            $($($where_)*)?
+        {
             type LinkOps = <$link as $crate::DefaultLinkOps>::Ops;
             type PointerOps = $crate::DefaultPointerOps<$pointer>;
 
             #[inline]
             unsafe fn get_value(&self, link: <Self::LinkOps as $crate::LinkOps>::LinkPtr) -> *const <Self::PointerOps as $crate::PointerOps>::Value {
-                $crate::container_of!(link.as_ptr(), $value, $field)
+                let link = $crate::LinkFieldOps::<$link>::link_field(&self.link_ops, link);
+                $crate::container_of!(link, $value, $field)
             }
             #[inline]
             unsafe fn get_link(&self, value: *const <Self::PointerOps as $crate::PointerOps>::Value) -> <Self::LinkOps as $crate::LinkOps>::LinkPtr {
                 // We need to do this instead of just accessing the field directly
                 // to strictly follow the stack borrow rules.
-                let ptr = (value as *const u8).add($crate::offset_of!($value, $field));
-                core::ptr::NonNull::new_unchecked(ptr as *mut _)
+                let link: *const $link = $crate::field_ptr(value, $crate::offset_of!($value, $field));
+                $crate::LinkFieldOps::<$link>::link_ptr(&self.link_ops, link)
             }
             #[inline]
             fn link_ops(&self) -> &Self::LinkOps {
@@ -228,38 +1078,64 @@ macro_rules! intrusive_adapter {
             fn pointer_ops(&self) -> &Self::PointerOps {
                 &self.pointer_ops
             }
+            $(
+                #[inline]
+                fn on_insert(&self, value: &<Self::PointerOps as $crate::PointerOps>::Value) {
+                    <$hooks as $crate::AdapterHooks<<Self::PointerOps as $crate::PointerOps>::Value>>::on_insert(value)
+                }
+                #[inline]
+                fn on_remove(&self, value: &<Self::PointerOps as $crate::PointerOps>::Value) {
+                    <$hooks as $crate::AdapterHooks<<Self::PointerOps as $crate::PointerOps>::Value>>::on_remove(value)
+                }
+            )?
         }
     };
     (@find_generic
-        $(#[$attr:meta])* $vis:vis $name:ident ($($prev:tt)*) > $($rest:tt)*
+        $(#[$attr:meta])* $vis:vis $name:ident ($($decl:tt)*) ($($use:tt)*)
+        const $param:ident: $ty:ty, $($rest:tt)*
+    ) => {
+        $crate::intrusive_adapter!(@find_generic
+            $(#[$attr])* $vis $name ($($decl)* const $param: $ty,) ($($use)* $param,) $($rest)*
+        );
+    };
+    (@find_generic
+        $(#[$attr:meta])* $vis:vis $name:ident ($($decl:tt)*) ($($use:tt)*)
+        const $param:ident: $ty:ty> $($rest:tt)*
+    ) => {
+        $crate::intrusive_adapter!(@impl
+            $(#[$attr])* $vis $name ($($decl)* const $param: $ty) ($($use)* $param) $($rest)*
+        );
+    };
+    (@find_generic
+        $(#[$attr:meta])* $vis:vis $name:ident ($($decl:tt)*) ($($use:tt)*) > $($rest:tt)*
     ) => {
-        intrusive_adapter!(@impl
-            $(#[$attr])* $vis $name ($($prev)*) $($rest)*
+        $crate::intrusive_adapter!(@impl
+            $(#[$attr])* $vis $name ($($decl)*) ($($use)*) $($rest)*
         );
     };
     (@find_generic
-        $(#[$attr:meta])* $vis:vis $name:ident ($($prev:tt)*) $cur:tt $($rest:tt)*
+        $(#[$attr:meta])* $vis:vis $name:ident ($($decl:tt)*) ($($use:tt)*) $cur:tt $($rest:tt)*
     ) => {
-        intrusive_adapter!(@find_generic
-            $(#[$attr])* $vis $name ($($prev)* $cur) $($rest)*
+        $crate::intrusive_adapter!(@find_generic
+            $(#[$attr])* $vis $name ($($decl)* $cur) ($($use)* $cur) $($rest)*
         );
     };
     (@find_if_generic
         $(#[$attr:meta])* $vis:vis $name:ident < $($rest:tt)*
     ) => {
-        intrusive_adapter!(@find_generic
-            $(#[$attr])* $vis $name () $($rest)*
+        $crate::intrusive_adapter!(@find_generic
+            $(#[$attr])* $vis $name () () $($rest)*
         );
     };
     (@find_if_generic
         $(#[$attr:meta])* $vis:vis $name:ident $($rest:tt)*
     ) => {
-        intrusive_adapter!(@impl
-            $(#[$attr])* $vis $name () $($rest)*
+        $crate::intrusive_adapter!(@impl
+            $(#[$attr])* $vis $name () () $($rest)*
         );
     };
     ($(#[$attr:meta])* $vis:vis $name:ident $($rest:tt)*) => {
-        intrusive_adapter!(@find_if_generic
+        $crate::intrusive_adapter!(@find_if_generic
             $(#[$attr])* $vis $name $($rest)*
         );
     };
@@ -267,8 +1143,17 @@ macro_rules! intrusive_adapter {
 
 #[cfg(test)]
 mod tests {
-    use crate::LinkedListLink;
+    use crate::offset_of;
+    use crate::SinglyLinkedListLink;
+    use crate::{AdapterHooks, DefaultLinkOps, KeyAdapter, LinkFieldOps, RBTree};
+    use crate::{LinkedList, LinkedListAtomicLink, LinkedListLink, RBTreeAtomicLink, RBTreeLink};
+    use core::cell::Cell;
+    use core::ptr::NonNull;
+    use std::boxed::Box;
     use std::rc::Rc;
+    use std::sync::Arc;
+    use std::thread;
+    use std::vec::Vec;
 
     struct Obj {
         link: LinkedListLink,
@@ -278,4 +1163,514 @@ mod tests {
         /// Test doc comment
         ObjAdapter1 = Rc<Obj>: Obj { link: LinkedListLink }
     }
+
+    #[derive(Default)]
+    struct GenericObj<L> {
+        link: L,
+        value: u32,
+    }
+
+    intrusive_adapter!(GenericObjAdapter<L> = Box<GenericObj<L>>: GenericObj<L> { link: L });
+    intrusive_adapter!(GenericArcAdapter<L> = Arc<GenericObj<L>>: GenericObj<L> { link: L } where L: Send + Sync);
+
+    impl<'a, L> KeyAdapter<'a> for GenericObjAdapter<L>
+    where
+        L: DefaultLinkOps,
+        L::Ops: LinkFieldOps<L>,
+    {
+        type Key = u32;
+        fn get_key(&self, value: &'a GenericObj<L>) -> u32 {
+            value.value
+        }
+    }
+
+    fn make_generic<L: Default>(value: u32) -> Box<GenericObj<L>> {
+        Box::new(GenericObj {
+            link: L::default(),
+            value,
+        })
+    }
+
+    #[test]
+    fn test_generic_link() {
+        let mut a = LinkedList::new(GenericObjAdapter::<LinkedListLink>::new());
+        let mut b = LinkedList::new(GenericObjAdapter::<LinkedListAtomicLink>::new());
+        for i in 0..3 {
+            a.push_back(make_generic(i));
+            b.push_front(make_generic(i));
+        }
+        assert_eq!(a.iter().map(|x| x.value).collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(b.iter().map(|x| x.value).collect::<Vec<_>>(), [2, 1, 0]);
+
+        let mut c = RBTree::new(GenericObjAdapter::<RBTreeLink>::new());
+        let mut d = RBTree::new(GenericObjAdapter::<RBTreeAtomicLink>::default());
+        for i in [2, 0, 1] {
+            c.insert(make_generic(i));
+            d.insert(make_generic(i));
+        }
+        assert_eq!(c.iter().map(|x| x.value).collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(d.iter().map(|x| x.value).collect::<Vec<_>>(), [0, 1, 2]);
+    }
+
+    struct Header {
+        link: LinkedListLink,
+    }
+
+    struct Nested {
+        value: u32,
+        header: Header,
+    }
+
+    #[derive(Default)]
+    struct NestedAdapter(
+        crate::linked_list::LinkOps,
+        crate::DefaultPointerOps<Box<Nested>>,
+    );
+
+    unsafe impl crate::Adapter for NestedAdapter {
+        type LinkOps = crate::linked_list::LinkOps;
+        type PointerOps = crate::DefaultPointerOps<Box<Nested>>;
+
+        unsafe fn get_value(&self, link: NonNull<LinkedListLink>) -> *const Nested {
+            crate::container_of_ptr(
+                link.as_ptr(),
+                offset_of!(Nested, header) + offset_of!(Header, link),
+            )
+        }
+        unsafe fn get_link(&self, value: *const Nested) -> NonNull<LinkedListLink> {
+            let link: *const LinkedListLink =
+                crate::field_ptr(value, offset_of!(Nested, header) + offset_of!(Header, link));
+            NonNull::new_unchecked(link as *mut _)
+        }
+        fn link_ops(&self) -> &Self::LinkOps {
+            &self.0
+        }
+        fn link_ops_mut(&mut self) -> &mut Self::LinkOps {
+            &mut self.0
+        }
+        fn pointer_ops(&self) -> &Self::PointerOps {
+            &self.1
+        }
+    }
+
+    #[test]
+    fn test_nested_link() {
+        let mut list = LinkedList::new(NestedAdapter::default());
+        for value in 0..3 {
+            list.push_back(Box::new(Nested {
+                value,
+                header: Header {
+                    link: LinkedListLink::new(),
+                },
+            }));
+        }
+        assert_eq!(list.iter().map(|x| x.value).collect::<Vec<_>>(), [0, 1, 2]);
+        assert!(list.front().get().unwrap().header.link.is_linked());
+    }
+
+    #[test]
+    fn test_generic_atomic_link_threads() {
+        let obj = Arc::new(GenericObj::<LinkedListAtomicLink>::default());
+        let mut list = LinkedList::new(GenericArcAdapter::new());
+        list.push_back(obj.clone());
+
+        let obj2 = obj.clone();
+        thread::spawn(move || assert!(obj2.link.is_linked()))
+            .join()
+            .unwrap();
+
+        list.clear();
+        assert!(!obj.link.is_linked());
+    }
+
+    struct Buffer<T, const N: usize> {
+        link: LinkedListLink,
+        data: [T; N],
+    }
+
+    intrusive_adapter!(BufferAdapter<T, const N: usize> = Box<Buffer<T, N>>: Buffer<T, N> { link: LinkedListLink });
+    intrusive_adapter!(BufferRefAdapter<'a, const N: usize, T> = &'a Buffer<T, N>: Buffer<T, N> { link: LinkedListLink } where T: 'a);
+
+    #[test]
+    fn test_const_generic() {
+        let mut list = LinkedList::new(BufferAdapter::<u8, 4>::new());
+        for i in 0..3 {
+            list.push_back(Box::new(Buffer {
+                link: LinkedListLink::new(),
+                data: [i; 4],
+            }));
+        }
+        assert_eq!(
+            list.iter().map(|x| x.data[3]).collect::<Vec<_>>(),
+            [0, 1, 2]
+        );
+
+        let buffer = Buffer {
+            link: LinkedListLink::new(),
+            data: [1u16; 2],
+        };
+        let mut list = LinkedList::new(BufferRefAdapter::<2, u16>::new());
+        list.push_back(&buffer);
+        assert_eq!(list.front().get().unwrap().data, [1, 1]);
+        list.clear();
+    }
+
+    struct MultiObj {
+        value: u32,
+        links: [LinkedListLink; 3],
+    }
+
+    intrusive_adapter!(MultiObjAdapter = Rc<MultiObj>: MultiObj { links: [LinkedListLink; 3] });
+
+    #[test]
+    fn test_link_array() {
+        let mut lists = [
+            LinkedList::new(MultiObjAdapter::new(0)),
+            LinkedList::new(MultiObjAdapter::new(1)),
+            LinkedList::new(MultiObjAdapter::new(2)),
+        ];
+        assert_eq!(MultiObjAdapter::new(2).index(), 2);
+
+        for value in 0..4 {
+            let obj = Rc::new(MultiObj {
+                value,
+                links: [
+                    LinkedListLink::new(),
+                    LinkedListLink::new(),
+                    LinkedListLink::new(),
+                ],
+            });
+            lists[0].push_back(obj.clone());
+            if value % 2 == 0 {
+                lists[2].push_front(obj);
+            }
+        }
+        assert_eq!(
+            lists[0].iter().map(|x| x.value).collect::<Vec<_>>(),
+            [0, 1, 2, 3]
+        );
+        assert!(lists[1].is_empty());
+        assert_eq!(lists[2].iter().map(|x| x.value).collect::<Vec<_>>(), [2, 0]);
+
+        let obj = lists[0].front().clone_pointer().unwrap();
+        assert!(obj.links[0].is_linked());
+        assert!(!obj.links[1].is_linked());
+        assert!(obj.links[2].is_linked());
+    }
+
+    #[test]
+    #[should_panic(expected = "link index out of bounds")]
+    fn test_link_array_out_of_bounds() {
+        MultiObjAdapter::new(3);
+    }
+
+    #[cfg(feature = "offset_of")]
+    #[test]
+    fn test_link_offset() {
+        const OFFSET: usize = MultiObjAdapter::LINK_OFFSET;
+        assert_eq!(OFFSET, offset_of!(MultiObj, links));
+        assert_eq!(ObjAdapter1::LINK_OFFSET, offset_of!(Obj, link));
+        assert_eq!(
+            GenericObjAdapter::<LinkedListLink>::LINK_OFFSET,
+            offset_of!(GenericObj<LinkedListLink>, link)
+        );
+    }
+
+    struct HookObj {
+        link: LinkedListLink,
+        tree_link: RBTreeLink,
+        value: u32,
+        inserted: Cell<u32>,
+        removed: Cell<u32>,
+    }
+
+    struct CountHooks;
+    impl AdapterHooks<HookObj> for CountHooks {
+        fn on_insert(value: &HookObj) {
+            value.inserted.set(value.inserted.get() + 1);
+        }
+        fn on_remove(value: &HookObj) {
+            value.removed.set(value.removed.get() + 1);
+        }
+    }
+
+    intrusive_adapter!(HookListAdapter = Rc<HookObj>: HookObj { link: LinkedListLink } hooks: CountHooks);
+    intrusive_adapter!(HookTreeAdapter = Rc<HookObj>: HookObj { tree_link: RBTreeLink }
+        priority: u32 = |obj| { obj.value } hooks: CountHooks);
+    impl<'a> KeyAdapter<'a> for HookTreeAdapter {
+        type Key = u32;
+        fn get_key(&self, value: &'a HookObj) -> u32 {
+            value.value
+        }
+    }
+
+    fn make_hook_obj(value: u32) -> Rc<HookObj> {
+        Rc::new(HookObj {
+            link: LinkedListLink::new(),
+            tree_link: RBTreeLink::new(),
+            value,
+            inserted: Cell::new(0),
+            removed: Cell::new(0),
+        })
+    }
+
+    fn hook_counts(obj: &HookObj) -> (u32, u32) {
+        (obj.inserted.get(), obj.removed.get())
+    }
+
+    #[test]
+    fn test_hooks() {
+        let a = make_hook_obj(1);
+        let b = make_hook_obj(2);
+
+        let mut list = LinkedList::new(HookListAdapter::new());
+        list.push_back(a.clone());
+        assert_eq!(hook_counts(&a), (1, 0));
+        assert!(list.front_mut().replace_with(b.clone()).is_ok());
+        assert_eq!(hook_counts(&a), (1, 1));
+        assert_eq!(hook_counts(&b), (1, 0));
+        list.push_back(a.clone());
+        list.clear();
+        assert_eq!(hook_counts(&a), (2, 2));
+        assert_eq!(hook_counts(&b), (1, 1));
+
+        let mut tree = RBTree::new(HookTreeAdapter::new());
+        tree.insert(a.clone());
+        tree.insert(b.clone());
+        assert_eq!(hook_counts(&a), (3, 2));
+        let _ = tree.into_iter().collect::<Vec<_>>();
+        assert_eq!(hook_counts(&a), (3, 3));
+        assert_eq!(hook_counts(&b), (2, 2));
+    }
+
+    // Object whose callbacks panic while `panic` holds their name.
+    struct PanicObj {
+        link: LinkedListLink,
+        singly_link: SinglyLinkedListLink,
+        tree_link: RBTreeLink,
+        value: u32,
+        panic: Cell<Option<&'static str>>,
+    }
+
+    impl PanicObj {
+        fn callback(&self, name: &'static str) {
+            assert!(self.panic.get() != Some(name), "{} panicked", name);
+        }
+    }
+
+    struct PanicHooks;
+    impl AdapterHooks<PanicObj> for PanicHooks {
+        fn on_insert(value: &PanicObj) {
+            value.callback("on_insert");
+        }
+        fn on_remove(value: &PanicObj) {
+            value.callback("on_remove");
+        }
+    }
+
+    intrusive_adapter!(PanicListAdapter<'a> = &'a PanicObj: PanicObj { link: LinkedListLink }
+        priority: u32 = |obj| { obj.callback("get_priority"); obj.value } hooks: PanicHooks);
+    intrusive_adapter!(PanicSinglyAdapter<'a> = &'a PanicObj: PanicObj { singly_link: SinglyLinkedListLink }
+        priority: u32 = |obj| { obj.callback("get_priority"); obj.value } hooks: PanicHooks);
+    intrusive_adapter!(PanicTreeAdapter<'a> = &'a PanicObj: PanicObj { tree_link: RBTreeLink }
+        hooks: PanicHooks);
+
+    // Key which panics when it is compared while `panic` is `"cmp"`.
+    #[derive(PartialEq, Eq)]
+    struct PanicKey<'a>(&'a PanicObj);
+    impl PartialOrd for PanicKey<'_> {
+        fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+    impl Ord for PanicKey<'_> {
+        fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+            self.0.callback("cmp");
+            other.0.callback("cmp");
+            self.0.value.cmp(&other.0.value)
+        }
+    }
+    impl PartialEq for PanicObj {
+        fn eq(&self, other: &PanicObj) -> bool {
+            self.value == other.value
+        }
+    }
+    impl Eq for PanicObj {}
+    impl<'a, 'b> KeyAdapter<'a> for PanicTreeAdapter<'b> {
+        type Key = PanicKey<'a>;
+        fn get_key(&self, value: &'a PanicObj) -> PanicKey<'a> {
+            PanicKey(value)
+        }
+    }
+
+    #[test]
+    fn test_panicking_callbacks() {
+        use crate::SinglyLinkedList;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let objs = (0..4)
+            .map(|value| PanicObj {
+                link: LinkedListLink::new(),
+                singly_link: SinglyLinkedListLink::new(),
+                tree_link: RBTreeLink::new(),
+                value,
+                panic: Cell::new(None),
+            })
+            .collect::<Vec<_>>();
+        let new = &objs[3];
+        let panics = |f: &mut dyn FnMut()| catch_unwind(AssertUnwindSafe(f)).is_err();
+
+        let mut list = LinkedList::new(PanicListAdapter::new());
+        let mut singly = SinglyLinkedList::new(PanicSinglyAdapter::new());
+        let mut tree = RBTree::new(PanicTreeAdapter::new());
+        for obj in &objs[..3] {
+            list.insert_sorted(obj);
+            singly.insert_sorted(obj);
+            tree.insert(obj);
+        }
+
+        // A new element whose callbacks panic is dropped without being
+        // inserted, and its link is released.
+        for &(obj, name) in &[
+            (new, "on_insert"),
+            (new, "get_priority"),
+            (&objs[1], "get_priority"),
+            (new, "cmp"),
+            (&objs[1], "cmp"),
+        ] {
+            obj.panic.set(Some(name));
+            if name != "cmp" {
+                assert!(panics(&mut || {
+                    list.insert_sorted(new);
+                }));
+                assert!(panics(&mut || {
+                    singly.insert_sorted(new);
+                }));
+            }
+            if name != "get_priority" {
+                assert!(panics(&mut || {
+                    tree.insert(new);
+                }));
+            }
+            obj.panic.set(None);
+            assert!(!new.link.is_linked());
+            assert!(!new.singly_link.is_linked());
+            assert!(!new.tree_link.is_linked());
+            assert_eq!(list.assert_valid(), 3);
+            assert_eq!(singly.iter().count(), 3);
+            assert_eq!(
+                tree.iter().map(|obj| obj.value).collect::<Vec<_>>(),
+                [0, 1, 2]
+            );
+        }
+        list.insert_sorted(new);
+        singly.insert_sorted(new);
+        tree.insert(new);
+
+        // A panic while clearing a list leaves the remaining elements in it.
+        objs[1].panic.set(Some("on_remove"));
+        assert!(panics(&mut || list.clear()));
+        assert!(panics(&mut || singly.clear()));
+        objs[1].panic.set(None);
+        #[cfg(feature = "poison")]
+        {
+            list.clear_poison();
+            singly.clear_poison();
+        }
+        assert!(!objs[1].link.is_linked());
+        assert_eq!(list.assert_valid(), 2);
+        assert_eq!(list.front().get().unwrap().value, 2);
+        assert_eq!(
+            singly.iter().map(|obj| obj.value).collect::<Vec<_>>(),
+            [2, 3]
+        );
+        list.clear();
+        singly.clear();
+        tree.clear();
+    }
+
+    #[test]
+    #[cfg(feature = "poison")]
+    fn test_poison() {
+        use crate::SinglyLinkedList;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let objs = (0..3)
+            .map(|value| PanicObj {
+                link: LinkedListLink::new(),
+                singly_link: SinglyLinkedListLink::new(),
+                tree_link: RBTreeLink::new(),
+                value,
+                panic: Cell::new(None),
+            })
+            .collect::<Vec<_>>();
+        let panics = |f: &mut dyn FnMut()| catch_unwind(AssertUnwindSafe(f)).is_err();
+
+        let mut list = LinkedList::new(PanicListAdapter::new());
+        let mut singly = SinglyLinkedList::new(PanicSinglyAdapter::new());
+        let mut tree = RBTree::new(PanicTreeAdapter::new());
+        for obj in &objs[..2] {
+            list.push_back(obj);
+            singly.push_front(obj);
+            tree.insert(obj);
+        }
+
+        // Panics raised before the links are modified, by a hook of the new
+        // element or because it is already linked, don't poison.
+        objs[2].panic.set(Some("on_insert"));
+        assert!(panics(&mut || list.push_back(&objs[2])));
+        assert!(panics(&mut || singly.push_front(&objs[2])));
+        assert!(panics(&mut || {
+            tree.insert(&objs[2]);
+        }));
+        objs[2].panic.set(None);
+        assert!(panics(&mut || list.push_back(&objs[0])));
+        assert!(panics(&mut || singly.push_front(&objs[0])));
+        assert!(panics(&mut || {
+            tree.front_mut().insert_before(&objs[0]);
+        }));
+        assert!(!list.is_poisoned());
+        assert!(!singly.is_poisoned());
+        assert!(!tree.is_poisoned());
+
+        // A panic escaping a modification of the links poisons the
+        // collection, after which only reads are allowed.
+        objs[0].panic.set(Some("on_remove"));
+        assert!(panics(&mut || {
+            list.front_mut().remove();
+        }));
+        assert!(panics(&mut || {
+            singly.front_mut().remove_next();
+        }));
+        assert!(panics(&mut || {
+            tree.front_mut().remove();
+        }));
+        objs[0].panic.set(None);
+        assert!(list.is_poisoned());
+        assert!(singly.is_poisoned());
+        assert!(tree.is_poisoned());
+        assert!(panics(&mut || list.push_back(&objs[2])));
+        assert!(panics(&mut || singly.push_front(&objs[2])));
+        assert!(panics(&mut || {
+            tree.front_mut().remove();
+        }));
+        assert!(!objs[2].link.is_linked());
+        assert!(!objs[2].singly_link.is_linked());
+        assert_eq!(list.iter().count(), 1);
+        assert_eq!(singly.iter().count(), 1);
+        assert_eq!(tree.front().get().unwrap().value, 1);
+
+        list.clear_poison();
+        singly.clear_poison();
+        tree.clear_poison();
+        list.push_back(&objs[2]);
+        singly.push_front(&objs[2]);
+        tree.insert(&objs[2]);
+        assert_eq!(list.assert_valid(), 2);
+        assert_eq!(singly.iter().count(), 2);
+        assert_eq!(tree.iter().count(), 2);
+        list.clear();
+        singly.clear();
+        tree.clear();
+    }
 }
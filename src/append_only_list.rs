@@ -0,0 +1,438 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Intrusive list which several threads can append to at the same time.
+
+use core::fmt;
+use core::iter::FusedIterator;
+use core::ptr::{null_mut, NonNull};
+
+use crate::adapter::{already_linked, unlinked_pointer, Adapter};
+use crate::link_ops::LinkOps;
+use crate::pointer_ops::PointerOps;
+use crate::singly_linked_list::{AtomicLink, AtomicLinkOps};
+use crate::sync::{AtomicPtr, Ordering};
+
+// =============================================================================
+// AppendOnlyList
+// =============================================================================
+
+/// An intrusive list which can be pushed to and iterated from several threads
+/// at once.
+///
+/// Elements are linked with a `SinglyLinkedListAtomicLink`. `push` only needs
+/// a shared reference and is lock-free, and `iter` is wait-free: it takes a
+/// snapshot of the list, so elements pushed while iterating are not visited.
+/// Elements can't be removed one at a time, only all together with `drain`,
+/// which needs exclusive access to the list. Since no element can go away
+/// while the list is shared, references returned by `iter` stay valid for
+/// as long as the list is borrowed.
+///
+/// This fits logs and registries which are filled concurrently and only
+/// emptied at shutdown or at a point where the list is no longer shared.
+///
+/// The most recently pushed element comes first, both in `iter` and `drain`.
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::append_only_list::AppendOnlyList;
+/// use intrusive_collections::{intrusive_adapter, SinglyLinkedListAtomicLink};
+/// use std::thread;
+///
+/// struct Entry {
+///     link: SinglyLinkedListAtomicLink,
+///     value: u32,
+/// }
+/// intrusive_adapter!(EntryAdapter = Box<Entry>: Entry { link: SinglyLinkedListAtomicLink });
+///
+/// let mut log = AppendOnlyList::new(EntryAdapter::new());
+/// thread::scope(|s| {
+///     for value in 0..4 {
+///         let log = &log;
+///         s.spawn(move || {
+///             log.push(Box::new(Entry { link: SinglyLinkedListAtomicLink::new(), value }));
+///         });
+///     }
+/// });
+/// assert_eq!(log.iter().map(|x| x.value).sum::<u32>(), 6);
+///
+/// let mut values: Vec<u32> = log.drain().map(|x| x.value).collect();
+/// values.sort();
+/// assert_eq!(values, [0, 1, 2, 3]);
+/// assert!(log.is_empty());
+/// ```
+pub struct AppendOnlyList<A: Adapter<LinkOps = AtomicLinkOps>> {
+    head: AtomicPtr<AtomicLink>,
+    adapter: A,
+}
+
+impl<A: Adapter<LinkOps = AtomicLinkOps>> AppendOnlyList<A> {
+    loom_const_fn! {
+        /// Creates an empty `AppendOnlyList`.
+        #[inline]
+        pub fn new(adapter: A) -> AppendOnlyList<A> {
+            AppendOnlyList {
+                head: AtomicPtr::new(null_mut()),
+                adapter,
+            }
+        }
+    }
+
+    /// Returns `true` if the list doesn't contain any elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Acquire).is_null()
+    }
+
+    /// Returns a reference to the adapter of the list.
+    #[inline]
+    pub fn adapter(&self) -> &A {
+        &self.adapter
+    }
+
+    /// Inserts a new element at the start of the list.
+    ///
+    /// This can be called from several threads at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new element is already linked to a different intrusive
+    /// collection.
+    #[inline]
+    pub fn push(&self, val: <A::PointerOps as PointerOps>::Pointer) {
+        unsafe {
+            let raw = self.adapter.pointer_ops().into_raw(val);
+            let link = self.adapter.get_link(raw);
+            if !link.as_ref().acquire() {
+                // convert the node back into a pointer
+                self.adapter.pointer_ops().from_raw(raw);
+
+                already_linked(&self.adapter, None, raw, None);
+            }
+            crate::adapter::link_acquired(&self.adapter, raw);
+
+            // The release ordering makes the element, and through the release
+            // sequence on `head` all the elements after it, visible to
+            // threads which load it in `iter`.
+            let next = link.as_ref().next_ptr();
+            let mut head = self.head.load(Ordering::Relaxed);
+            loop {
+                next.store(head, Ordering::Relaxed);
+                match self.head.compare_exchange_weak(
+                    head,
+                    link.as_ptr(),
+                    Ordering::Release,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => break,
+                    Err(current) => head = current,
+                }
+            }
+        }
+    }
+
+    /// Returns an iterator over the elements which are in the list when it
+    /// is called.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, A> {
+        Iter {
+            next: NonNull::new(self.head.load(Ordering::Acquire)),
+            list: self,
+        }
+    }
+
+    /// Removes all elements from the list, returning them as an iterator.
+    ///
+    /// Elements which are not consumed are dropped along with the iterator.
+    #[inline]
+    pub fn drain(&mut self) -> Drain<'_, A> {
+        Drain {
+            next: NonNull::new(self.head.swap(null_mut(), Ordering::Acquire)),
+            list: self,
+        }
+    }
+
+    /// Removes all elements from the list.
+    ///
+    /// This will unlink all object currently in the list, which requires
+    /// iterating through all elements in the list. Each element is
+    /// converted back to an owned pointer and then dropped.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.drain().for_each(drop);
+    }
+
+    /// Empties the list without unlinking or freeing objects in it.
+    ///
+    /// Since this does not unlink any objects, any attempts to link these
+    /// objects into another collection will fail but will not cause any
+    /// memory unsafety. To unlink those objects manually, you must call the
+    /// `force_unlink` function on them.
+    #[inline]
+    pub fn fast_clear(&mut self) {
+        self.head.swap(null_mut(), Ordering::Relaxed);
+    }
+}
+
+// Allow read-only access to values from multiple threads, and pushing
+// elements from any of them.
+unsafe impl<A: Adapter<LinkOps = AtomicLinkOps> + Sync> Sync for AppendOnlyList<A>
+where
+    <A::PointerOps as PointerOps>::Value: Sync,
+    <A::PointerOps as PointerOps>::Pointer: Send,
+{
+}
+
+// Allow sending to another thread if the ownership of the elements can be
+// transferred to another thread.
+unsafe impl<A: Adapter<LinkOps = AtomicLinkOps> + Send> Send for AppendOnlyList<A> where
+    <A::PointerOps as PointerOps>::Pointer: Send
+{
+}
+
+impl<A: Adapter<LinkOps = AtomicLinkOps>> Drop for AppendOnlyList<A> {
+    #[inline]
+    fn drop(&mut self) {
+        let leaked = crate::adapter::checks_leaks(&self.adapter) && !self.is_empty();
+        self.clear();
+        if leaked {
+            crate::adapter::leaked_on_drop(&self.adapter, None);
+        }
+    }
+}
+
+impl<'a, A: Adapter<LinkOps = AtomicLinkOps> + 'a> IntoIterator for &'a AppendOnlyList<A> {
+    type Item = &'a <A::PointerOps as PointerOps>::Value;
+    type IntoIter = Iter<'a, A>;
+
+    #[inline]
+    fn into_iter(self) -> Iter<'a, A> {
+        self.iter()
+    }
+}
+
+impl<A: Adapter<LinkOps = AtomicLinkOps> + Default> Default for AppendOnlyList<A> {
+    fn default() -> AppendOnlyList<A> {
+        AppendOnlyList::new(A::default())
+    }
+}
+
+impl<A: Adapter<LinkOps = AtomicLinkOps>> fmt::Debug for AppendOnlyList<A>
+where
+    <A::PointerOps as PointerOps>::Value: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+// =============================================================================
+// Iter
+// =============================================================================
+
+/// An iterator over references to the items of an `AppendOnlyList`.
+pub struct Iter<'a, A: Adapter<LinkOps = AtomicLinkOps>> {
+    next: Option<NonNull<AtomicLink>>,
+    list: &'a AppendOnlyList<A>,
+}
+
+impl<'a, A: Adapter<LinkOps = AtomicLinkOps> + 'a> Iterator for Iter<'a, A> {
+    type Item = &'a <A::PointerOps as PointerOps>::Value;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a <A::PointerOps as PointerOps>::Value> {
+        let link = self.next?;
+        unsafe {
+            // Links are never modified after they are pushed, and the load of
+            // `head` in `iter` synchronized with all the pushes before it.
+            self.next = NonNull::new(link.as_ref().next_ptr().load(Ordering::Relaxed));
+            Some(&*self.list.adapter.get_value(link))
+        }
+    }
+}
+
+impl<'a, A: Adapter<LinkOps = AtomicLinkOps> + 'a> FusedIterator for Iter<'a, A> {}
+
+impl<'a, A: Adapter<LinkOps = AtomicLinkOps> + 'a> Clone for Iter<'a, A> {
+    #[inline]
+    fn clone(&self) -> Iter<'a, A> {
+        Iter {
+            next: self.next,
+            list: self.list,
+        }
+    }
+}
+
+unsafe impl<A: Adapter<LinkOps = AtomicLinkOps>> Send for Iter<'_, A> where AppendOnlyList<A>: Sync {}
+unsafe impl<A: Adapter<LinkOps = AtomicLinkOps>> Sync for Iter<'_, A> where AppendOnlyList<A>: Sync {}
+
+// =============================================================================
+// Drain
+// =============================================================================
+
+/// An iterator which removes all the elements of an `AppendOnlyList`.
+pub struct Drain<'a, A: Adapter<LinkOps = AtomicLinkOps>> {
+    next: Option<NonNull<AtomicLink>>,
+    list: &'a mut AppendOnlyList<A>,
+}
+
+impl<A: Adapter<LinkOps = AtomicLinkOps>> Iterator for Drain<'_, A> {
+    type Item = <A::PointerOps as PointerOps>::Pointer;
+
+    #[inline]
+    fn next(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        let link = self.next?;
+        unsafe {
+            self.next = NonNull::new(link.as_ref().next_ptr().load(Ordering::Relaxed));
+            self.list.adapter.link_ops_mut().release_link(link);
+            Some(unlinked_pointer(&self.list.adapter, link))
+        }
+    }
+}
+
+impl<A: Adapter<LinkOps = AtomicLinkOps>> FusedIterator for Drain<'_, A> {}
+
+impl<A: Adapter<LinkOps = AtomicLinkOps>> Drop for Drain<'_, A> {
+    #[inline]
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+unsafe impl<A: Adapter<LinkOps = AtomicLinkOps>> Send for Drain<'_, A> where AppendOnlyList<A>: Send {}
+unsafe impl<A: Adapter<LinkOps = AtomicLinkOps>> Sync for Drain<'_, A> where AppendOnlyList<A>: Sync {}
+
+#[cfg(test)]
+mod tests {
+    use super::AppendOnlyList;
+    use crate::SinglyLinkedListAtomicLink;
+    use std::boxed::Box;
+    use std::format;
+    use std::sync::Arc;
+    use std::thread;
+    use std::vec::Vec;
+
+    #[derive(Debug)]
+    struct Obj {
+        link: SinglyLinkedListAtomicLink,
+        value: u32,
+    }
+    intrusive_adapter!(ObjAdapter = Box<Obj>: Obj { link: SinglyLinkedListAtomicLink });
+    intrusive_adapter!(ArcObjAdapter = Arc<Obj>: Obj { link: SinglyLinkedListAtomicLink });
+
+    fn make_obj(value: u32) -> Box<Obj> {
+        Box::new(Obj {
+            link: SinglyLinkedListAtomicLink::new(),
+            value,
+        })
+    }
+
+    #[test]
+    fn test_push_drain() {
+        let mut l = AppendOnlyList::new(ObjAdapter::new());
+        assert!(l.is_empty());
+        for value in 0..3 {
+            l.push(make_obj(value));
+        }
+        assert!(!l.is_empty());
+
+        let mut iter = l.iter();
+        assert_eq!(iter.next().unwrap().value, 2);
+        l.push(make_obj(3));
+        // The iterator only sees the elements which were in the list when it
+        // was created.
+        assert_eq!(iter.map(|x| x.value).collect::<Vec<_>>(), [1, 0]);
+        assert_eq!((&l).into_iter().count(), 4);
+
+        let mut drain = l.drain();
+        let obj = drain.next().unwrap();
+        assert_eq!(obj.value, 3);
+        assert!(!obj.link.is_linked());
+        drop(drain);
+        assert!(l.is_empty());
+        assert_eq!(l.iter().count(), 0);
+        l.push(obj);
+        assert_eq!(
+            format!("{:?}", l.iter().map(|x| x.value).collect::<Vec<_>>()),
+            "[3]"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "already linked")]
+    fn test_push_linked() {
+        let obj = Arc::new(Obj {
+            link: SinglyLinkedListAtomicLink::new(),
+            value: 0,
+        });
+        let l = AppendOnlyList::new(ArcObjAdapter::new());
+        l.push(obj.clone());
+        l.push(obj);
+    }
+
+    #[test]
+    fn test_concurrent_push() {
+        let obj = Arc::new(Obj {
+            link: SinglyLinkedListAtomicLink::new(),
+            value: 100,
+        });
+        let mut l = AppendOnlyList::new(ArcObjAdapter::new());
+        thread::scope(|s| {
+            for thread in 0..4 {
+                let l = &l;
+                s.spawn(move || {
+                    for value in 0..25 {
+                        l.push(Arc::new(Obj {
+                            link: SinglyLinkedListAtomicLink::new(),
+                            value: thread * 25 + value,
+                        }));
+                        assert!(l.iter().count() > value as usize);
+                    }
+                });
+            }
+            l.push(obj.clone());
+        });
+        let mut values = l.iter().map(|x| x.value).collect::<Vec<_>>();
+        values.sort_unstable();
+        assert_eq!(values, (0..=100).collect::<Vec<_>>());
+
+        l.clear();
+        assert!(l.is_empty());
+        assert!(!obj.link.is_linked());
+        assert_eq!(Arc::strong_count(&obj), 1);
+    }
+
+    #[cfg(loom)]
+    #[test]
+    fn test_loom_push_iter() {
+        use loom::thread;
+
+        loom::model(|| {
+            let l = Arc::new(AppendOnlyList::new(ArcObjAdapter::new()));
+            let pushers: Vec<_> = (0..2)
+                .map(|value| {
+                    let l = l.clone();
+                    thread::spawn(move || {
+                        l.push(Arc::new(Obj {
+                            link: SinglyLinkedListAtomicLink::new(),
+                            value,
+                        }))
+                    })
+                })
+                .collect();
+            // Every element the reader sees must be fully initialized.
+            for obj in l.iter() {
+                assert!(obj.value < 2 && obj.link.is_linked());
+            }
+            for pusher in pushers {
+                pusher.join().unwrap();
+            }
+            assert_eq!(l.iter().map(|x| x.value).sum::<u32>(), 1);
+        });
+    }
+}
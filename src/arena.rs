@@ -0,0 +1,340 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Typed bump arena, whose allocations can be inserted into the collections.
+//!
+//! `Arena::alloc` moves an object into the arena and returns an `ArenaBox`,
+//! which owns the object and implements `PointerOps` and
+//! `ExclusivePointerOps` like `Box`. The memory of the objects is only freed
+//! with the arena, so allocating is cheap, and the borrow checker makes sure
+//! that no collection outlives the arena. An object which needs to be in
+//! several collections at once can be turned into a shared reference with
+//! `ArenaBox::leak`, and inserted into collections of references.
+//!
+//! # Examples
+//!
+//! ```
+//! use intrusive_collections::arena::{Arena, ArenaBox};
+//! use intrusive_collections::{intrusive_adapter, KeyAdapter};
+//! use intrusive_collections::{LinkedList, LinkedListLink, RBTree, RBTreeLink};
+//!
+//! struct Node {
+//!     list_link: LinkedListLink,
+//!     tree_link: RBTreeLink,
+//!     key: u32,
+//! }
+//! intrusive_adapter!(NodeListAdapter<'a> = ArenaBox<'a, Node>: Node { list_link: LinkedListLink });
+//! intrusive_adapter!(NodeRefListAdapter<'a> = &'a Node: Node { list_link: LinkedListLink });
+//! intrusive_adapter!(NodeTreeAdapter<'a> = &'a Node: Node { tree_link: RBTreeLink });
+//! impl<'a> KeyAdapter<'a> for NodeTreeAdapter<'_> {
+//!     type Key = u32;
+//!     fn get_key(&self, node: &'a Node) -> u32 {
+//!         node.key
+//!     }
+//! }
+//!
+//! let arena = Arena::new();
+//! let new_node = |key| Node {
+//!     list_link: LinkedListLink::new(),
+//!     tree_link: RBTreeLink::new(),
+//!     key,
+//! };
+//!
+//! // The list owns its nodes, which are dropped with it.
+//! let mut owned = LinkedList::new(NodeListAdapter::new());
+//! for key in 0..3 {
+//!     owned.push_back(arena.alloc(new_node(key)));
+//! }
+//! unsafe { owned.front_mut().get_mut().unwrap().key = 10 };
+//! assert_eq!(owned.iter().map(|n| n.key).collect::<Vec<_>>(), [10, 1, 2]);
+//!
+//! // Leaked nodes live as long as the arena, and can be in both collections.
+//! let mut list = LinkedList::new(NodeRefListAdapter::new());
+//! let mut tree = RBTree::new(NodeTreeAdapter::new());
+//! for &key in &[5, 3, 4] {
+//!     let node: &Node = ArenaBox::leak(arena.alloc(new_node(key)));
+//!     list.push_back(node);
+//!     tree.insert(node);
+//! }
+//! assert_eq!(list.iter().map(|n| n.key).collect::<Vec<_>>(), [5, 3, 4]);
+//! assert_eq!(tree.iter().map(|n| n.key).collect::<Vec<_>>(), [3, 4, 5]);
+//! assert_eq!(arena.len(), 6);
+//! # list.fast_clear();
+//! # tree.fast_clear();
+//! ```
+
+use core::cell::{Cell, RefCell};
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::{self, ManuallyDrop, MaybeUninit};
+use core::ops::{Deref, DerefMut};
+use core::ptr::{self, NonNull};
+
+use crate::alloc::vec::Vec;
+
+// Number of objects of the first chunk, when no capacity is given. The
+// chunks after it are twice as large as the one before.
+const FIRST_CHUNK_LEN: usize = 8;
+
+// =============================================================================
+// Arena
+// =============================================================================
+
+/// A bump allocator for objects of type `T`.
+///
+/// The arena allocates its memory in chunks which never move, and hands out
+/// the slots of the last chunk in order. The objects are dropped by their
+/// `ArenaBox`, but their slots are only reused once the arena is dropped.
+pub struct Arena<T> {
+    // The start and length of each chunk. They are kept as raw pointers,
+    // since the slots are borrowed by the `ArenaBox`es.
+    chunks: RefCell<Vec<(NonNull<MaybeUninit<T>>, usize)>>,
+    // Number of slots used in the last chunk.
+    used: Cell<usize>,
+    len: Cell<usize>,
+}
+
+unsafe impl<T: Send> Send for Arena<T> {}
+
+impl<T> Arena<T> {
+    /// Creates an empty `Arena`, which doesn't allocate until the first
+    /// object is allocated.
+    #[inline]
+    pub const fn new() -> Arena<T> {
+        Arena {
+            chunks: RefCell::new(Vec::new()),
+            used: Cell::new(0),
+            len: Cell::new(0),
+        }
+    }
+
+    /// Creates an empty `Arena` with room for `capacity` objects in its first
+    /// chunk.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Arena<T> {
+        let arena = Arena::new();
+        if capacity != 0 {
+            arena.chunks.borrow_mut().push(new_chunk(capacity));
+        }
+        arena
+    }
+
+    /// Returns the number of objects allocated from the arena, including the
+    /// ones which were dropped since.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len.get()
+    }
+
+    /// Returns `true` if no object was allocated from the arena.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Moves `value` into the arena, and returns an owning pointer to it.
+    #[inline]
+    pub fn alloc(&self, value: T) -> ArenaBox<'_, T> {
+        let mut chunks = self.chunks.borrow_mut();
+        let mut used = self.used.get();
+        match chunks.last() {
+            Some(&(_, len)) if used < len => {}
+            last => {
+                let len = last.map_or(FIRST_CHUNK_LEN, |&(_, len)| len.saturating_mul(2));
+                chunks.push(new_chunk(len));
+                used = 0;
+            }
+        }
+        let (chunk, _) = *chunks.last().unwrap();
+        self.used.set(used + 1);
+        self.len.set(self.len.get() + 1);
+        unsafe {
+            let slot = chunk.as_ptr().add(used) as *mut T;
+            slot.write(value);
+            ArenaBox {
+                ptr: NonNull::new_unchecked(slot),
+                _arena: PhantomData,
+            }
+        }
+    }
+}
+
+// Allocates a chunk of `len` uninitialized slots.
+#[inline]
+fn new_chunk<T>(len: usize) -> (NonNull<MaybeUninit<T>>, usize) {
+    let mut chunk = ManuallyDrop::new(Vec::<MaybeUninit<T>>::with_capacity(len));
+    let len = chunk.capacity();
+    (NonNull::new(chunk.as_mut_ptr()).unwrap(), len)
+}
+
+impl<T> Drop for Arena<T> {
+    #[inline]
+    fn drop(&mut self) {
+        // The objects were dropped by their `ArenaBox`es, or leaked.
+        for &(chunk, len) in self.chunks.get_mut().iter() {
+            unsafe { drop(Vec::from_raw_parts(chunk.as_ptr(), 0, len)) };
+        }
+    }
+}
+
+impl<T> Default for Arena<T> {
+    #[inline]
+    fn default() -> Arena<T> {
+        Arena::new()
+    }
+}
+
+impl<T> fmt::Debug for Arena<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Arena")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+// =============================================================================
+// ArenaBox
+// =============================================================================
+
+/// An owning pointer to an object allocated from an `Arena`.
+///
+/// Dropping an `ArenaBox` drops its object, and leaves its slot unused until
+/// the arena is dropped.
+pub struct ArenaBox<'a, T> {
+    ptr: NonNull<T>,
+    _arena: PhantomData<&'a mut T>,
+}
+
+impl<'a, T> ArenaBox<'a, T> {
+    /// Consumes the `ArenaBox` and returns a reference to its object, which
+    /// lives as long as the arena and is never dropped.
+    #[inline]
+    pub fn leak(this: ArenaBox<'a, T>) -> &'a mut T {
+        unsafe { &mut *ArenaBox::into_raw(this) }
+    }
+
+    /// Consumes the `ArenaBox` and returns a raw pointer to its object, which
+    /// isn't dropped.
+    #[inline]
+    pub fn into_raw(this: ArenaBox<'a, T>) -> *mut T {
+        let ptr = this.ptr.as_ptr();
+        mem::forget(this);
+        ptr
+    }
+
+    /// Converts a raw pointer back into an `ArenaBox`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by `ArenaBox::into_raw` for an arena
+    /// which lives for `'a`, and must not be converted back more than once.
+    #[inline]
+    pub unsafe fn from_raw(ptr: *mut T) -> ArenaBox<'a, T> {
+        ArenaBox {
+            ptr: NonNull::new_unchecked(ptr),
+            _arena: PhantomData,
+        }
+    }
+}
+
+impl<T> Deref for ArenaBox<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T> DerefMut for ArenaBox<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl<T> Drop for ArenaBox<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.ptr.as_ptr()) };
+    }
+}
+
+unsafe impl<T: Send> Send for ArenaBox<'_, T> {}
+unsafe impl<T: Sync> Sync for ArenaBox<'_, T> {}
+
+impl<T: fmt::Debug> fmt::Debug for ArenaBox<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Arena, ArenaBox};
+    use crate::{LinkedList, LinkedListLink};
+    use core::cell::Cell;
+    use std::vec::Vec;
+
+    struct Obj<'a> {
+        link: LinkedListLink,
+        value: usize,
+        drops: &'a Cell<usize>,
+    }
+    impl Drop for Obj<'_> {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+        }
+    }
+    intrusive_adapter!(ObjAdapter<'a, 'b> = ArenaBox<'a, Obj<'b>>: Obj<'b> { link: LinkedListLink } where 'b: 'a);
+
+    #[test]
+    fn test_alloc() {
+        let drops = Cell::new(0);
+        let arena = Arena::with_capacity(2);
+        assert!(arena.is_empty());
+        let objs: Vec<_> = (0..20)
+            .map(|value| {
+                arena.alloc(Obj {
+                    link: LinkedListLink::new(),
+                    value,
+                    drops: &drops,
+                })
+            })
+            .collect();
+        assert_eq!(arena.len(), 20);
+        assert!(objs.iter().enumerate().all(|(i, x)| x.value == i));
+        assert_eq!(arena.chunks.borrow().len(), 4);
+
+        let mut list = LinkedList::new(ObjAdapter::new());
+        for obj in objs.into_iter().rev() {
+            list.push_back(obj);
+        }
+        unsafe { list.front_mut().get_mut().unwrap().value = 100 };
+        assert_eq!(list.pop_front().unwrap().value, 100);
+        assert_eq!(drops.get(), 1);
+
+        let leaked: &Obj<'_> = ArenaBox::leak(list.pop_front().unwrap());
+        drop(list);
+        assert_eq!(drops.get(), 19);
+        assert_eq!(leaked.value, 18);
+        let raw = ArenaBox::into_raw(arena.alloc(Obj {
+            link: LinkedListLink::new(),
+            value: 20,
+            drops: &drops,
+        }));
+        drop(unsafe { ArenaBox::from_raw(raw) });
+        assert_eq!(drops.get(), 20);
+    }
+
+    #[test]
+    fn test_zero_sized() {
+        let arena = Arena::new();
+        let units: Vec<ArenaBox<'_, ()>> = (0..100).map(|_| arena.alloc(())).collect();
+        assert_eq!(units.len(), arena.len());
+    }
+}
@@ -0,0 +1,315 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+
+// =============================================================================
+// AssertSend
+// =============================================================================
+
+/// Wrapper which makes a value `Send`, for values which the compiler can't
+/// prove are safe to move to another thread.
+///
+/// A collection is only `Send` if its pointer type is, so for example a
+/// `LinkedList` of `Rc` is never `Send`. It can still be moved to another
+/// thread along with every other reference to its elements, which the
+/// compiler has no way of checking. `AssertSend` lets the user take
+/// responsibility for this.
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::{intrusive_adapter, AssertSend, LinkedList, LinkedListLink};
+/// use std::rc::Rc;
+/// use std::thread;
+///
+/// struct Obj {
+///     link: LinkedListLink,
+///     value: u32,
+/// }
+/// intrusive_adapter!(ObjAdapter = Rc<Obj>: Obj { link: LinkedListLink });
+///
+/// let mut list = LinkedList::new(ObjAdapter::new());
+/// list.push_back(Rc::new(Obj { link: LinkedListLink::new(), value: 1 }));
+///
+/// // The list holds the only references to its elements, so all of them are
+/// // moved to the other thread.
+/// let list = unsafe { AssertSend::new(list) };
+/// let sum = thread::spawn(move || list.iter().map(|x| x.value).sum::<u32>())
+///     .join()
+///     .unwrap();
+/// assert_eq!(sum, 1);
+/// ```
+#[repr(transparent)]
+pub struct AssertSend<T>(T);
+
+impl<T> AssertSend<T> {
+    /// Wraps a value to make it `Send`.
+    ///
+    /// # Safety
+    ///
+    /// Moving `value` to another thread must be safe. In particular, any
+    /// value which shares state with it without synchronization, such as
+    /// other `Rc` pointing to the same objects, must either be moved along
+    /// with it or no longer be used on the original thread.
+    #[inline]
+    pub const unsafe fn new(value: T) -> AssertSend<T> {
+        AssertSend(value)
+    }
+
+    /// Unwraps the value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+unsafe impl<T> Send for AssertSend<T> {}
+
+impl<T> Deref for AssertSend<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for AssertSend<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for AssertSend<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AssertSend").field(&self.0).finish()
+    }
+}
+
+// =============================================================================
+// AssertSync
+// =============================================================================
+
+/// Wrapper which makes a value `Sync`, for values which the compiler can't
+/// prove are safe to share between threads.
+///
+/// This is the counterpart of `AssertSend` for shared access. For example, a
+/// `LinkedList` whose elements use `LinkedListLink` is not `Sync` since the
+/// links use `Cell`, but sharing it is fine as long as nothing modifies the
+/// list or its elements while it is shared.
+#[repr(transparent)]
+pub struct AssertSync<T>(T);
+
+impl<T> AssertSync<T> {
+    /// Wraps a value to make it `Sync`.
+    ///
+    /// # Safety
+    ///
+    /// Accessing `value` through a shared reference from several threads at
+    /// once must be safe: nothing reachable from it may be modified without
+    /// synchronization while it is shared, including by cloning `Rc`
+    /// pointers.
+    #[inline]
+    pub const unsafe fn new(value: T) -> AssertSync<T> {
+        AssertSync(value)
+    }
+
+    /// Unwraps the value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+unsafe impl<T> Sync for AssertSync<T> {}
+
+impl<T> Deref for AssertSync<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for AssertSync<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for AssertSync<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AssertSync").field(&self.0).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AssertSend, AssertSync};
+    use crate::{
+        linked_list, rbtree, singly_linked_list, xor_linked_list, CircularList, KeyAdapter,
+        LinkedList, LinkedListAtomicLink, LinkedListLink, RBTree, RBTreeAtomicLink,
+        SinglyLinkedList, SinglyLinkedListAtomicLink, UnsafeRef, XorLinkedList,
+        XorLinkedListAtomicLink,
+    };
+    use std::boxed::Box;
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use std::thread;
+    use std::vec::Vec;
+
+    fn assert_send<T: Send>() {}
+    fn assert_sync<T: Sync>() {}
+
+    struct Obj {
+        ll: LinkedListAtomicLink,
+        sll: SinglyLinkedListAtomicLink,
+        xll: XorLinkedListAtomicLink,
+        rb: RBTreeAtomicLink,
+    }
+    intrusive_adapter!(LlAdapter = Arc<Obj>: Obj { ll: LinkedListAtomicLink });
+    intrusive_adapter!(SllAdapter = Box<Obj>: Obj { sll: SinglyLinkedListAtomicLink });
+    intrusive_adapter!(XllAdapter = UnsafeRef<Obj>: Obj { xll: XorLinkedListAtomicLink });
+    intrusive_adapter!(RbAdapter = Arc<Obj>: Obj { rb: RBTreeAtomicLink });
+    impl<'a> KeyAdapter<'a> for RbAdapter {
+        type Key = usize;
+        fn get_key(&self, x: &'a Obj) -> usize {
+            x as *const Obj as usize
+        }
+    }
+
+    #[test]
+    fn test_send_sync() {
+        assert_send::<LinkedList<LlAdapter>>();
+        assert_sync::<LinkedList<LlAdapter>>();
+        assert_send::<linked_list::Cursor<'_, LlAdapter>>();
+        assert_sync::<linked_list::Cursor<'_, LlAdapter>>();
+        assert_send::<linked_list::CursorMut<'_, LlAdapter>>();
+        assert_sync::<linked_list::CursorMut<'_, LlAdapter>>();
+        assert_send::<linked_list::CursorOwning<LlAdapter>>();
+        assert_sync::<linked_list::CursorOwning<LlAdapter>>();
+        assert_send::<linked_list::Iter<'_, LlAdapter>>();
+        assert_sync::<linked_list::Iter<'_, LlAdapter>>();
+        assert_send::<linked_list::IntoIter<LlAdapter>>();
+        assert_send::<CircularList<LlAdapter>>();
+        assert_sync::<CircularList<LlAdapter>>();
+
+        assert_send::<SinglyLinkedList<SllAdapter>>();
+        assert_sync::<SinglyLinkedList<SllAdapter>>();
+        assert_send::<singly_linked_list::Cursor<'_, SllAdapter>>();
+        assert_send::<singly_linked_list::CursorMut<'_, SllAdapter>>();
+        assert_send::<singly_linked_list::Iter<'_, SllAdapter>>();
+        assert_send::<singly_linked_list::IterMut<'_, SllAdapter>>();
+        assert_sync::<singly_linked_list::IterMut<'_, SllAdapter>>();
+
+        assert_send::<XorLinkedList<XllAdapter>>();
+        assert_sync::<XorLinkedList<XllAdapter>>();
+        assert_send::<xor_linked_list::Cursor<'_, XllAdapter>>();
+        assert_send::<xor_linked_list::CursorMut<'_, XllAdapter>>();
+        assert_send::<xor_linked_list::Iter<'_, XllAdapter>>();
+
+        assert_send::<RBTree<RbAdapter>>();
+        assert_sync::<RBTree<RbAdapter>>();
+        assert_send::<rbtree::Cursor<'_, RbAdapter>>();
+        assert_send::<rbtree::CursorMut<'_, RbAdapter>>();
+        assert_send::<rbtree::InsertCursor<'_, RbAdapter>>();
+        assert_send::<rbtree::Iter<'_, RbAdapter>>();
+        assert_send::<rbtree::IntoIter<RbAdapter>>();
+        assert_sync::<rbtree::IntoIter<RbAdapter>>();
+    }
+
+    #[test]
+    fn test_cursor_threads() {
+        let objs: Vec<Arc<Obj>> = (0..3)
+            .map(|_| {
+                Arc::new(Obj {
+                    ll: LinkedListAtomicLink::new(),
+                    sll: SinglyLinkedListAtomicLink::new(),
+                    xll: XorLinkedListAtomicLink::new(),
+                    rb: RBTreeAtomicLink::new(),
+                })
+            })
+            .collect();
+        let mut l = LinkedList::new(LlAdapter::new());
+        for obj in &objs {
+            l.push_back(obj.clone());
+        }
+        thread::scope(|s| {
+            let cursor = l.front();
+            let handle = s.spawn(move || cursor.clone_pointer().unwrap());
+            let iter = l.iter();
+            s.spawn(move || assert_eq!(iter.count(), 3));
+            assert!(Arc::ptr_eq(&handle.join().unwrap(), &objs[0]));
+        });
+        thread::scope(|s| {
+            let mut cursor = l.front_mut();
+            s.spawn(move || {
+                cursor.remove();
+            });
+        });
+        assert_eq!(Arc::strong_count(&objs[0]), 1);
+    }
+
+    #[test]
+    fn test_assert_send() {
+        struct RcObj {
+            link: LinkedListLink,
+            value: u32,
+        }
+        intrusive_adapter!(RcAdapter = Rc<RcObj>: RcObj { link: LinkedListLink });
+
+        let mut l = LinkedList::new(RcAdapter::new());
+        for value in 0..3 {
+            l.push_back(Rc::new(RcObj {
+                link: LinkedListLink::new(),
+                value,
+            }));
+        }
+        let l = unsafe { AssertSend::new(l) };
+        let mut l = thread::spawn(move || {
+            let mut l = l;
+            l.pop_front();
+            l
+        })
+        .join()
+        .unwrap()
+        .into_inner();
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 2]);
+        l.clear();
+    }
+
+    #[test]
+    fn test_assert_sync() {
+        struct CellObj {
+            link: LinkedListLink,
+            value: u32,
+        }
+        intrusive_adapter!(CellAdapter = Box<CellObj>: CellObj { link: LinkedListLink });
+
+        let mut l = LinkedList::new(CellAdapter::new());
+        for value in 0..3 {
+            l.push_back(Box::new(CellObj {
+                link: LinkedListLink::new(),
+                value,
+            }));
+        }
+        let l = unsafe { AssertSync::new(AssertSend::new(l)) };
+        thread::scope(|s| {
+            for _ in 0..2 {
+                s.spawn(|| assert_eq!(l.iter().map(|x| x.value).sum::<u32>(), 3));
+            }
+        });
+        assert_eq!(l.into_inner().into_inner().iter().count(), 3);
+    }
+}
@@ -0,0 +1,640 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Intrusive doubly-linked list whose elements remove themselves from it when
+//! they are dropped.
+
+use core::cell::Cell;
+use core::fmt;
+use core::marker::PhantomPinned;
+use core::mem;
+use core::pin::Pin;
+use core::ptr::NonNull;
+
+use crate::link_ops::{self, DefaultLinkOps};
+use crate::pointer_ops::PointerOps;
+use crate::Adapter;
+
+// =============================================================================
+// Link
+// =============================================================================
+
+/// Intrusive link that allows an object to be inserted into an
+/// `AutoUnlinkList`.
+///
+/// The links of an `AutoUnlinkList` form a ring through a sentinel link
+/// stored in the list, so a link can reach both of its neighbors without
+/// going through the list. Dropping a linked `Link`, which happens when the
+/// object containing it is dropped, removes the object from its list.
+///
+/// The link isn't `Send`, so an object can't be dropped on another thread
+/// while its list is used.
+pub struct Link {
+    next: Cell<Option<NonNull<Link>>>,
+    prev: Cell<Option<NonNull<Link>>>,
+}
+
+impl Link {
+    /// Creates a new `Link`.
+    #[inline]
+    pub const fn new() -> Link {
+        Link {
+            next: Cell::new(None),
+            prev: Cell::new(None),
+        }
+    }
+
+    /// Checks whether the `Link` is linked into an `AutoUnlinkList`.
+    #[inline]
+    pub fn is_linked(&self) -> bool {
+        self.next.get().is_some()
+    }
+
+    /// Removes the object from the `AutoUnlinkList` it is in, if any.
+    ///
+    /// This is what dropping the link does. The pointer held by the list is
+    /// forgotten rather than dropped, and `Adapter::on_remove` isn't called, so
+    /// this is mostly useful with `UnsafeRef`. Use `AutoUnlinkList::remove` to
+    /// get the pointer back instead.
+    ///
+    /// # Safety
+    ///
+    /// It is undefined behavior to call this function while an iterator over
+    /// the `AutoUnlinkList` is alive, since the iterator could then follow the
+    /// stale neighbors of the object. The list doesn't know about the
+    /// element being removed, so this can't be checked:
+    ///
+    /// ```compile_fail
+    /// use intrusive_collections::auto_unlink_list::{AutoUnlinkList, Link};
+    /// use intrusive_collections::intrusive_adapter;
+    /// use std::rc::Rc;
+    ///
+    /// struct Obj {
+    ///     link: Link,
+    /// }
+    /// intrusive_adapter!(ObjAdapter = Rc<Obj>: Obj { link: Link });
+    ///
+    /// let mut list = Box::pin(AutoUnlinkList::new(ObjAdapter::new()));
+    /// list.as_mut().push_back(Rc::new(Obj { link: Link::new() }));
+    /// for obj in list.iter() {
+    ///     obj.link.unlink();
+    /// }
+    /// ```
+    #[inline]
+    pub unsafe fn unlink(&self) {
+        if let (Some(next), Some(prev)) = (self.next.get(), self.prev.get()) {
+            prev.as_ref().next.set(Some(next));
+            next.as_ref().prev.set(Some(prev));
+            self.next.set(None);
+            self.prev.set(None);
+        }
+    }
+}
+
+impl DefaultLinkOps for Link {
+    type Ops = LinkOps;
+
+    const NEW: Self::Ops = LinkOps;
+}
+
+impl Drop for Link {
+    #[inline]
+    fn drop(&mut self) {
+        // An object can only be dropped while it is linked if the list holds
+        // an `UnsafeRef` to it, which must not be dropped while the list is
+        // iterated.
+        unsafe { self.unlink() };
+    }
+}
+
+// Provide an implementation of Clone which simply initializes the new link as
+// unlinked. This allows structs containing a link to derive Clone.
+impl Clone for Link {
+    #[inline]
+    fn clone(&self) -> Link {
+        Link::new()
+    }
+}
+
+// Same as above
+impl Default for Link {
+    #[inline]
+    fn default() -> Link {
+        Link::new()
+    }
+}
+
+// Provide an implementation of Debug so that structs containing a link can
+// still derive Debug.
+impl fmt::Debug for Link {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // There isn't anything sensible to print here except whether the link
+        // is currently in a list.
+        if self.is_linked() {
+            write!(f, "linked")
+        } else {
+            write!(f, "unlinked")
+        }
+    }
+}
+
+// =============================================================================
+// LinkOps
+// =============================================================================
+
+/// Default `LinkOps` implementation for `AutoUnlinkList`.
+#[derive(Clone, Copy, Default)]
+pub struct LinkOps;
+
+unsafe impl link_ops::LinkOps for LinkOps {
+    type LinkPtr = NonNull<Link>;
+
+    const LINK_SIZE: Option<usize> = Some(mem::size_of::<Link>());
+
+    #[inline]
+    unsafe fn acquire_link(&mut self, ptr: Self::LinkPtr) -> bool {
+        if ptr.as_ref().is_linked() {
+            false
+        } else {
+            // Marks the link as linked until the list sets its neighbors.
+            ptr.as_ref().next.set(Some(ptr));
+            true
+        }
+    }
+
+    #[inline]
+    unsafe fn release_link(&mut self, ptr: Self::LinkPtr) {
+        ptr.as_ref().next.set(None);
+        ptr.as_ref().prev.set(None);
+    }
+}
+
+unsafe impl link_ops::LinkStateOps for LinkOps {
+    #[inline]
+    unsafe fn is_linked(&self, ptr: Self::LinkPtr) -> bool {
+        ptr.as_ref().is_linked()
+    }
+}
+
+// =============================================================================
+// AutoUnlinkList
+// =============================================================================
+
+/// An intrusive doubly-linked list whose elements remove themselves from it
+/// when they are dropped.
+///
+/// This is useful for RAII registrations, such as observers which
+/// unsubscribe when they go away. Since an element can only be dropped while
+/// it is in the list if the list doesn't own it, the list is normally used
+/// with `UnsafeRef` pointers.
+///
+/// The list stores the sentinel link which its first and last elements point
+/// to, so it must not move while it has elements: the methods which insert
+/// elements take a pinned list, for example from `Box::pin`. Elements can
+/// also leave the list without it knowing, so it doesn't keep track of its
+/// length and `len` walks the list.
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::auto_unlink_list::{AutoUnlinkList, Link};
+/// use intrusive_collections::{intrusive_adapter, UnsafeRef};
+///
+/// struct Observer {
+///     link: Link,
+///     id: u32,
+/// }
+/// intrusive_adapter!(ObserverAdapter = UnsafeRef<Observer>: Observer { link: Link });
+///
+/// let mut observers = Box::pin(AutoUnlinkList::new(ObserverAdapter::new()));
+/// let a = Box::new(Observer { link: Link::new(), id: 1 });
+/// let b = Box::new(Observer { link: Link::new(), id: 2 });
+/// observers.as_mut().push_back(unsafe { UnsafeRef::from_raw(&*a) });
+/// observers.as_mut().push_back(unsafe { UnsafeRef::from_raw(&*b) });
+///
+/// drop(a);
+/// assert_eq!(observers.iter().map(|o| o.id).collect::<Vec<_>>(), [2]);
+/// ```
+pub struct AutoUnlinkList<A: Adapter<LinkOps = LinkOps>> {
+    // Points to itself, or is unlinked, while the list is empty.
+    sentinel: Link,
+    adapter: A,
+    _pinned: PhantomPinned,
+}
+
+impl<A: Adapter<LinkOps = LinkOps>> AutoUnlinkList<A> {
+    /// Creates an empty `AutoUnlinkList`.
+    #[inline]
+    pub const fn new(adapter: A) -> AutoUnlinkList<A> {
+        AutoUnlinkList {
+            sentinel: Link::new(),
+            adapter,
+            _pinned: PhantomPinned,
+        }
+    }
+
+    #[inline]
+    fn sentinel_ptr(&self) -> NonNull<Link> {
+        NonNull::from(&self.sentinel)
+    }
+
+    // Returns the first element, or the last one if `back` is true.
+    #[inline]
+    fn end(&self, back: bool) -> Option<NonNull<Link>> {
+        let link = if back {
+            self.sentinel.prev.get()
+        } else {
+            self.sentinel.next.get()
+        };
+        link.filter(|&link| link != self.sentinel_ptr())
+    }
+
+    /// Returns `true` if the `AutoUnlinkList` is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.end(false).is_none()
+    }
+
+    /// Returns the number of elements in the `AutoUnlinkList`.
+    ///
+    /// This walks the whole list, since elements can remove themselves
+    /// without the list knowing about it.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Returns a reference to the first element of the `AutoUnlinkList`.
+    #[inline]
+    pub fn front(&self) -> Option<&<A::PointerOps as PointerOps>::Value> {
+        Some(unsafe { &*self.adapter.get_value(self.end(false)?) })
+    }
+
+    /// Returns a reference to the last element of the `AutoUnlinkList`.
+    #[inline]
+    pub fn back(&self) -> Option<&<A::PointerOps as PointerOps>::Value> {
+        Some(unsafe { &*self.adapter.get_value(self.end(true)?) })
+    }
+
+    /// Gets an iterator over the objects in the `AutoUnlinkList`.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, A> {
+        Iter {
+            head: self.end(false),
+            tail: self.end(true),
+            list: self,
+        }
+    }
+
+    #[inline]
+    fn node_from_value(&mut self, val: <A::PointerOps as PointerOps>::Pointer) -> NonNull<Link> {
+        use link_ops::LinkOps;
+
+        unsafe {
+            let raw = self.adapter.pointer_ops().into_raw(val);
+            let link = self.adapter.get_link(raw);
+
+            if !self.adapter.link_ops_mut().acquire_link(link) {
+                // convert the node back into a pointer
+                self.adapter.pointer_ops().from_raw(raw);
+
+                crate::adapter::already_linked(&self.adapter, None, raw, None);
+            }
+            crate::adapter::acquired_on_insert(&mut self.adapter, link);
+
+            link
+        }
+    }
+
+    // Links `new` between `prev` and `next`, which are either linked
+    // neighbors or the sentinel.
+    #[inline]
+    unsafe fn link_between(
+        &mut self,
+        new: NonNull<Link>,
+        prev: NonNull<Link>,
+        next: NonNull<Link>,
+    ) {
+        new.as_ref().prev.set(Some(prev));
+        new.as_ref().next.set(Some(next));
+        prev.as_ref().next.set(Some(new));
+        next.as_ref().prev.set(Some(new));
+    }
+
+    #[inline]
+    unsafe fn unlink_node(
+        &mut self,
+        link: NonNull<Link>,
+    ) -> <A::PointerOps as PointerOps>::Pointer {
+        use link_ops::LinkOps;
+
+        link.as_ref().unlink();
+        self.adapter.link_ops_mut().release_link(link);
+        crate::adapter::unlinked_pointer(&self.adapter, link)
+    }
+
+    /// Inserts a new element at the start of the `AutoUnlinkList`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new element is already linked to a different intrusive
+    /// collection.
+    #[inline]
+    pub fn push_front(self: Pin<&mut Self>, val: <A::PointerOps as PointerOps>::Pointer) {
+        // The list is only accessed through its fields, it isn't moved.
+        let this = unsafe { self.get_unchecked_mut() };
+        let new = this.node_from_value(val);
+        let sentinel = this.sentinel_ptr();
+        let next = this.end(false).unwrap_or(sentinel);
+        unsafe {
+            this.link_between(new, sentinel, next);
+        }
+    }
+
+    /// Inserts a new element at the end of the `AutoUnlinkList`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new element is already linked to a different intrusive
+    /// collection.
+    #[inline]
+    pub fn push_back(self: Pin<&mut Self>, val: <A::PointerOps as PointerOps>::Pointer) {
+        // The list is only accessed through its fields, it isn't moved.
+        let this = unsafe { self.get_unchecked_mut() };
+        let new = this.node_from_value(val);
+        let sentinel = this.sentinel_ptr();
+        let prev = this.end(true).unwrap_or(sentinel);
+        unsafe {
+            this.link_between(new, prev, sentinel);
+        }
+    }
+
+    /// Removes the first element of the `AutoUnlinkList`.
+    ///
+    /// This returns `None` if the `AutoUnlinkList` is empty.
+    #[inline]
+    pub fn pop_front(self: Pin<&mut Self>) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        let this = unsafe { self.get_unchecked_mut() };
+        let front = this.end(false)?;
+        Some(unsafe { this.unlink_node(front) })
+    }
+
+    /// Removes the last element of the `AutoUnlinkList`.
+    ///
+    /// This returns `None` if the `AutoUnlinkList` is empty.
+    #[inline]
+    pub fn pop_back(self: Pin<&mut Self>) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        let this = unsafe { self.get_unchecked_mut() };
+        let back = this.end(true)?;
+        Some(unsafe { this.unlink_node(back) })
+    }
+
+    /// Removes an element from the `AutoUnlinkList` and returns the pointer
+    /// which the list held to it.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a pointer to an object that is part of this list.
+    #[inline]
+    pub unsafe fn remove(
+        self: Pin<&mut Self>,
+        ptr: *const <A::PointerOps as PointerOps>::Value,
+    ) -> <A::PointerOps as PointerOps>::Pointer {
+        let this = self.get_unchecked_mut();
+        let link = this.adapter.get_link(ptr);
+        this.unlink_node(link)
+    }
+
+    /// Removes all elements from the `AutoUnlinkList`.
+    ///
+    /// This will unlink all object currently in the list, which requires
+    /// iterating through all elements in the `AutoUnlinkList`. Each element is
+    /// converted back to an owned pointer and then dropped.
+    #[inline]
+    pub fn clear(self: Pin<&mut Self>) {
+        unsafe { self.get_unchecked_mut() }.clear_inner();
+    }
+
+    fn clear_inner(&mut self) {
+        while let Some(front) = self.end(false) {
+            unsafe {
+                self.unlink_node(front);
+            }
+        }
+        // Leaves the sentinel unlinked rather than pointing to itself.
+        self.sentinel.next.set(None);
+        self.sentinel.prev.set(None);
+    }
+}
+
+// Drop all owned pointers if the collection is dropped
+impl<A: Adapter<LinkOps = LinkOps>> Drop for AutoUnlinkList<A> {
+    #[inline]
+    fn drop(&mut self) {
+        self.clear_inner();
+    }
+}
+
+impl<'a, A: Adapter<LinkOps = LinkOps> + 'a> IntoIterator for &'a AutoUnlinkList<A> {
+    type Item = &'a <A::PointerOps as PointerOps>::Value;
+    type IntoIter = Iter<'a, A>;
+
+    #[inline]
+    fn into_iter(self) -> Iter<'a, A> {
+        self.iter()
+    }
+}
+
+impl<A: Adapter<LinkOps = LinkOps> + Default> Default for AutoUnlinkList<A> {
+    fn default() -> AutoUnlinkList<A> {
+        AutoUnlinkList::new(A::default())
+    }
+}
+
+impl<A: Adapter<LinkOps = LinkOps>> fmt::Debug for AutoUnlinkList<A>
+where
+    <A::PointerOps as PointerOps>::Value: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+// =============================================================================
+// Iter
+// =============================================================================
+
+/// An iterator over references to the items of an `AutoUnlinkList`.
+///
+/// Elements must not be dropped while the iterator is in use.
+pub struct Iter<'a, A: Adapter<LinkOps = LinkOps>> {
+    head: Option<NonNull<Link>>,
+    tail: Option<NonNull<Link>>,
+    list: &'a AutoUnlinkList<A>,
+}
+impl<'a, A: Adapter<LinkOps = LinkOps> + 'a> Iterator for Iter<'a, A> {
+    type Item = &'a <A::PointerOps as PointerOps>::Value;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a <A::PointerOps as PointerOps>::Value> {
+        let head = self.head?;
+
+        if Some(head) == self.tail {
+            self.head = None;
+            self.tail = None;
+        } else {
+            self.head = unsafe { head.as_ref().next.get() };
+        }
+        Some(unsafe { &*self.list.adapter.get_value(head) })
+    }
+}
+impl<'a, A: Adapter<LinkOps = LinkOps> + 'a> DoubleEndedIterator for Iter<'a, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a <A::PointerOps as PointerOps>::Value> {
+        let tail = self.tail?;
+
+        if Some(tail) == self.head {
+            self.head = None;
+            self.tail = None;
+        } else {
+            self.tail = unsafe { tail.as_ref().prev.get() };
+        }
+        Some(unsafe { &*self.list.adapter.get_value(tail) })
+    }
+}
+impl<'a, A: Adapter<LinkOps = LinkOps> + 'a> Clone for Iter<'a, A> {
+    #[inline]
+    fn clone(&self) -> Iter<'a, A> {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            list: self.list,
+        }
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{AutoUnlinkList, Link};
+    use crate::UnsafeRef;
+    use std::boxed::Box;
+    use std::format;
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    #[derive(Clone)]
+    struct Obj {
+        link: Link,
+        value: u32,
+    }
+    intrusive_adapter!(ObjAdapter = UnsafeRef<Obj>: Obj { link: Link });
+
+    fn make_obj(value: u32) -> Box<Obj> {
+        Box::new(Obj {
+            link: Link::new(),
+            value,
+        })
+    }
+
+    fn values(l: &AutoUnlinkList<ObjAdapter>) -> Vec<u32> {
+        l.iter().map(|x| x.value).collect()
+    }
+
+    #[test]
+    fn test_auto_unlink() {
+        let mut l = Box::pin(AutoUnlinkList::new(ObjAdapter::new()));
+        assert!(l.is_empty());
+        let objs: Vec<Box<Obj>> = (0..4).map(make_obj).collect();
+        for obj in &objs {
+            l.as_mut().push_back(unsafe { UnsafeRef::from_raw(&**obj) });
+        }
+        assert_eq!(values(&l), [0, 1, 2, 3]);
+        assert_eq!(
+            l.iter().rev().map(|x| x.value).collect::<Vec<_>>(),
+            [3, 2, 1, 0]
+        );
+        assert_eq!(l.len(), 4);
+        assert!(objs[0].link.is_linked());
+        assert!(!Obj::clone(&objs[0]).link.is_linked());
+
+        let mut objs = objs.into_iter();
+        let first = objs.next().unwrap();
+        let rest: Vec<Box<Obj>> = objs.collect();
+        drop(first);
+        assert_eq!(values(&l), [1, 2, 3]);
+        assert_eq!(l.front().unwrap().value, 1);
+        unsafe { rest[1].link.unlink() };
+        assert!(!rest[1].link.is_linked());
+        assert_eq!(values(&l), [1, 3]);
+        drop(rest);
+        assert!(l.is_empty());
+        assert_eq!(l.len(), 0);
+        assert!(l.front().is_none());
+
+        // The list can be reused once all its elements removed themselves.
+        let a = make_obj(5);
+        l.as_mut().push_front(unsafe { UnsafeRef::from_raw(&*a) });
+        let b = make_obj(6);
+        l.as_mut().push_front(unsafe { UnsafeRef::from_raw(&*b) });
+        assert_eq!(values(&l), [6, 5]);
+        assert_eq!(format!("{:?}", a.link), "linked");
+        assert_eq!(l.as_mut().pop_front().unwrap().value, 6);
+        assert_eq!(format!("{:?}", b.link), "unlinked");
+        unsafe {
+            assert_eq!(l.as_mut().remove(&*a).value, 5);
+        }
+        assert!(!a.link.is_linked());
+        assert!(l.as_mut().pop_back().is_none());
+    }
+
+    #[test]
+    fn test_drop_list() {
+        let a = make_obj(1);
+        let b = make_obj(2);
+        {
+            let mut l = Box::pin(AutoUnlinkList::new(ObjAdapter::new()));
+            l.as_mut().push_back(unsafe { UnsafeRef::from_raw(&*a) });
+            l.as_mut().push_back(unsafe { UnsafeRef::from_raw(&*b) });
+            assert_eq!(l.back().unwrap().value, 2);
+            assert_eq!(l.as_mut().pop_back().unwrap().value, 2);
+            l.as_mut().push_back(unsafe { UnsafeRef::from_raw(&*b) });
+        }
+        assert!(!a.link.is_linked());
+        assert!(!b.link.is_linked());
+    }
+
+    #[test]
+    fn test_owned_pointers() {
+        struct RcObj {
+            link: Link,
+        }
+        intrusive_adapter!(RcAdapter = Rc<RcObj>: RcObj { link: Link });
+
+        let a = Rc::new(RcObj { link: Link::new() });
+        let mut l = Box::pin(AutoUnlinkList::new(RcAdapter::new()));
+        l.as_mut().push_back(a.clone());
+        l.as_mut().push_back(Rc::new(RcObj { link: Link::new() }));
+        assert_eq!(Rc::strong_count(&a), 2);
+        l.as_mut().clear();
+        assert_eq!(Rc::strong_count(&a), 1);
+        assert!(l.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "attempted to insert an object that is already linked")]
+    fn test_already_linked() {
+        let a = make_obj(1);
+        let mut l = Box::pin(AutoUnlinkList::new(ObjAdapter::new()));
+        l.as_mut().push_back(unsafe { UnsafeRef::from_raw(&*a) });
+        l.as_mut().push_back(unsafe { UnsafeRef::from_raw(&*a) });
+    }
+}
@@ -0,0 +1,360 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Intrusive first-in, first-out queue with a capacity, on which threads
+//! block while it is full or empty.
+
+use core::fmt;
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+
+use crate::linked_list::{LinkedList, LinkedListOps};
+use crate::pointer_ops::PointerOps;
+use crate::Adapter;
+
+// The queue and its state, protected by the mutex.
+struct State<A: Adapter>
+where
+    A::LinkOps: LinkedListOps,
+{
+    list: LinkedList<A>,
+    len: usize,
+    closed: bool,
+}
+
+/// A first-in, first-out queue shared between threads, holding at most a
+/// given number of elements.
+///
+/// `push` blocks while the queue is full and `pop` blocks while it is empty,
+/// so that producers are slowed down to the pace of the consumers. Any
+/// number of threads can push and pop through a shared reference. The
+/// elements are kept in a `LinkedList`, so the queue doesn't allocate.
+///
+/// Once `close` is called, `push` fails, and `pop` returns the remaining
+/// elements and then `None` instead of blocking.
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::bounded_queue::BoundedQueue;
+/// use intrusive_collections::{intrusive_adapter, LinkedListAtomicLink};
+/// use std::thread;
+///
+/// struct Job {
+///     link: LinkedListAtomicLink,
+///     id: u32,
+/// }
+/// intrusive_adapter!(JobAdapter = Box<Job>: Job { link: LinkedListAtomicLink });
+///
+/// let queue = BoundedQueue::new(JobAdapter::new(), 2);
+/// thread::scope(|s| {
+///     s.spawn(|| {
+///         for id in 0..10 {
+///             let job = Box::new(Job { link: LinkedListAtomicLink::new(), id });
+///             assert!(queue.push(job).is_ok());
+///         }
+///         queue.close();
+///     });
+///     let mut ids = Vec::new();
+///     while let Some(job) = queue.pop() {
+///         ids.push(job.id);
+///     }
+///     assert_eq!(ids, (0..10).collect::<Vec<_>>());
+/// });
+/// ```
+pub struct BoundedQueue<A: Adapter>
+where
+    A::LinkOps: LinkedListOps,
+{
+    state: Mutex<State<A>>,
+    capacity: usize,
+    not_empty: Condvar,
+    not_full: Condvar,
+}
+
+impl<A: Adapter> BoundedQueue<A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    /// Creates an empty, open `BoundedQueue` which holds at most `capacity`
+    /// elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    #[inline]
+    pub fn new(adapter: A, capacity: usize) -> BoundedQueue<A> {
+        assert!(
+            capacity != 0,
+            "a BoundedQueue needs a capacity of at least 1"
+        );
+        BoundedQueue {
+            state: Mutex::new(State {
+                list: LinkedList::new(adapter),
+                len: 0,
+                closed: false,
+            }),
+            capacity,
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+        }
+    }
+
+    // The list is never left in an inconsistent state by a panic, so a
+    // poisoned lock can still be used.
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, State<A>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the greatest number of elements the queue holds.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of elements in the queue.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.lock().len
+    }
+
+    /// Returns `true` if the queue contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the queue was closed.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.lock().closed
+    }
+
+    /// Closes the queue, and wakes up all the threads blocked on it.
+    #[inline]
+    pub fn close(&self) {
+        self.lock().closed = true;
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+    }
+
+    #[inline]
+    fn push_locked(
+        &self,
+        mut state: MutexGuard<'_, State<A>>,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) {
+        state.list.push_back(val);
+        state.len += 1;
+        drop(state);
+        self.not_empty.notify_one();
+    }
+
+    /// Adds an element to the back of the queue, blocking while the queue is
+    /// full.
+    ///
+    /// If the queue is closed, the element is given back as an error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new element is already linked to a different intrusive
+    /// collection.
+    #[inline]
+    pub fn push(
+        &self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<(), <A::PointerOps as PointerOps>::Pointer> {
+        let mut state = self.lock();
+        while state.len == self.capacity && !state.closed {
+            state = self
+                .not_full
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        if state.closed {
+            return Err(val);
+        }
+        self.push_locked(state, val);
+        Ok(())
+    }
+
+    /// Adds an element to the back of the queue if it isn't full.
+    ///
+    /// If the queue is full or closed, the element is given back as an
+    /// error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new element is already linked to a different intrusive
+    /// collection.
+    #[inline]
+    pub fn try_push(
+        &self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<(), <A::PointerOps as PointerOps>::Pointer> {
+        let state = self.lock();
+        if state.len == self.capacity || state.closed {
+            return Err(val);
+        }
+        self.push_locked(state, val);
+        Ok(())
+    }
+
+    #[inline]
+    fn pop_locked(
+        &self,
+        mut state: MutexGuard<'_, State<A>>,
+    ) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        let val = state.list.pop_front()?;
+        state.len -= 1;
+        drop(state);
+        self.not_full.notify_one();
+        Some(val)
+    }
+
+    /// Removes the element at the front of the queue, blocking while the
+    /// queue is empty.
+    ///
+    /// This returns `None` once the queue is closed and empty.
+    #[inline]
+    pub fn pop(&self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        let mut state = self.lock();
+        while state.len == 0 && !state.closed {
+            state = self
+                .not_empty
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        self.pop_locked(state)
+    }
+
+    /// Removes the element at the front of the queue, if there is one.
+    #[inline]
+    pub fn try_pop(&self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        self.pop_locked(self.lock())
+    }
+
+    /// Consumes the queue and returns the list holding the queued elements.
+    #[inline]
+    pub fn into_list(self) -> LinkedList<A> {
+        self.state
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+            .list
+    }
+}
+
+impl<A: Adapter> fmt::Debug for BoundedQueue<A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.lock();
+        f.debug_struct("BoundedQueue")
+            .field("len", &state.len)
+            .field("capacity", &self.capacity)
+            .field("closed", &state.closed)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BoundedQueue;
+    use crate::LinkedListAtomicLink;
+    use std::boxed::Box;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::vec::Vec;
+
+    struct Obj {
+        link: LinkedListAtomicLink,
+        value: usize,
+    }
+    intrusive_adapter!(ObjAdapter = Box<Obj>: Obj { link: LinkedListAtomicLink });
+
+    fn make_obj(value: usize) -> Box<Obj> {
+        Box::new(Obj {
+            link: LinkedListAtomicLink::new(),
+            value,
+        })
+    }
+
+    #[test]
+    fn test_try_push_pop() {
+        let queue = BoundedQueue::new(ObjAdapter::new(), 2);
+        assert!(queue.try_pop().is_none());
+        assert!(queue.try_push(make_obj(0)).is_ok());
+        assert!(queue.push(make_obj(1)).is_ok());
+        assert_eq!(queue.try_push(make_obj(2)).unwrap_err().value, 2);
+        assert_eq!(queue.len(), 2);
+
+        assert_eq!(queue.pop().unwrap().value, 0);
+        assert!(queue.try_push(make_obj(3)).is_ok());
+        queue.close();
+        assert!(queue.is_closed());
+        assert_eq!(queue.push(make_obj(4)).unwrap_err().value, 4);
+        assert_eq!(queue.try_pop().unwrap().value, 1);
+        assert_eq!(queue.pop().unwrap().value, 3);
+        assert!(queue.pop().is_none());
+        assert!(queue.is_empty());
+        assert!(queue.into_list().is_empty());
+    }
+
+    #[test]
+    fn test_threads() {
+        const PRODUCERS: usize = 4;
+        const ITEMS: usize = 1000;
+        let queue = BoundedQueue::new(ObjAdapter::new(), 3);
+        let producers_left = AtomicUsize::new(PRODUCERS);
+        let (queue, producers_left) = (&queue, &producers_left);
+        let mut values = thread::scope(|s| {
+            for p in 0..PRODUCERS {
+                s.spawn(move || {
+                    for i in 0..ITEMS {
+                        assert!(queue.push(make_obj(p * ITEMS + i)).is_ok());
+                        assert!(queue.len() <= queue.capacity());
+                    }
+                    if producers_left.fetch_sub(1, Ordering::Relaxed) == 1 {
+                        queue.close();
+                    }
+                });
+            }
+            let consumers: Vec<_> = (0..3)
+                .map(|_| {
+                    s.spawn(move || {
+                        let mut values = Vec::new();
+                        while let Some(obj) = queue.pop() {
+                            values.push(obj.value);
+                        }
+                        values
+                    })
+                })
+                .collect();
+            consumers
+                .into_iter()
+                .flat_map(|c| c.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        values.sort_unstable();
+        assert_eq!(values, (0..PRODUCERS * ITEMS).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_close_wakes_producers() {
+        let queue = BoundedQueue::new(ObjAdapter::new(), 1);
+        assert!(queue.push(make_obj(0)).is_ok());
+        thread::scope(|s| {
+            let producer = s.spawn(|| queue.push(make_obj(1)));
+            // The push fails whether it blocked before the queue is closed
+            // or not.
+            queue.close();
+            assert_eq!(producer.join().unwrap().unwrap_err().value, 1);
+        });
+        assert_eq!(queue.pop().unwrap().value, 0);
+    }
+}
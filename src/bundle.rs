@@ -0,0 +1,367 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::adapter::Adapter;
+use crate::pointer_ops::PointerOps;
+
+/// A collection which can be part of a bundle generated by
+/// `intrusive_bundle!`.
+///
+/// This is implemented for `LinkedList` and `RBTree`, which can remove an
+/// arbitrary element without scanning the collection.
+///
+/// # Safety
+///
+/// `insert_member` must insert the value into the collection and
+/// `remove_member` must remove it again.
+pub unsafe trait BundleMember {
+    /// The adapter used by the collection.
+    type Adapter: Adapter;
+
+    /// Returns whether the link used by this collection is in use in the
+    /// given value.
+    ///
+    /// The link may be in use by a different collection with the same kind of
+    /// adapter, so this does not guarantee that the value is in this
+    /// collection. The state of the link is not changed.
+    fn is_linked(
+        &self,
+        value: &<<Self::Adapter as Adapter>::PointerOps as PointerOps>::Value,
+    ) -> bool;
+
+    /// Inserts a value into the collection at its default position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new element is already linked to a different intrusive
+    /// collection.
+    fn insert_member(
+        &mut self,
+        val: <<Self::Adapter as Adapter>::PointerOps as PointerOps>::Pointer,
+    );
+
+    /// Removes a value from the collection, returning `None` if the value is
+    /// not linked.
+    ///
+    /// # Safety
+    ///
+    /// `value` must either be unlinked or be part of this collection.
+    unsafe fn remove_member(
+        &mut self,
+        value: &<<Self::Adapter as Adapter>::PointerOps as PointerOps>::Value,
+    ) -> Option<<<Self::Adapter as Adapter>::PointerOps as PointerOps>::Pointer>;
+}
+
+/// Macro to generate a set of adapters for a value which is part of several
+/// intrusive collections at once, along with a struct owning one collection
+/// of each kind.
+///
+/// The generated struct keeps the memberships consistent: a value is either in
+/// all of the collections or in none of them. It has the following methods:
+///
+/// - `new` and `Default`, which create empty collections.
+/// - A getter with the name of each member, which returns a shared reference
+///   to that collection for lookups and iteration.
+/// - `insert`, which inserts a value into every collection.
+/// - `remove`, which removes a value from every collection.
+/// - `transfer`, which moves a value into another bundle of the same type.
+/// - `clear`, which removes all values.
+/// - `collections_mut`, which gives mutable access to all the collections,
+///   for example to reorder a list.
+///
+/// The pointer type must implement `Clone` and `Deref`, since each collection
+/// holds its own copy of the pointer. Each collection type must implement
+/// `BundleMember`.
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+/// use intrusive_collections::{intrusive_bundle, KeyAdapter, LinkedList, LinkedListLink};
+/// use intrusive_collections::{RBTree, RBTreeLink};
+///
+/// struct Entry {
+///     key_link: RBTreeLink,
+///     lru_link: LinkedListLink,
+///     key: u32,
+/// }
+///
+/// intrusive_bundle!(struct Cache = Rc<Entry>: Entry {
+///     by_key: RBTree<ByKeyAdapter> { key_link: RBTreeLink },
+///     lru: LinkedList<LruAdapter> { lru_link: LinkedListLink },
+/// });
+/// impl<'a> KeyAdapter<'a> for ByKeyAdapter {
+///     type Key = u32;
+///     fn get_key(&self, e: &'a Entry) -> u32 { e.key }
+/// }
+///
+/// let mut cache = Cache::new();
+/// for key in [3, 1, 2] {
+///     cache.insert(Rc::new(Entry {
+///         key_link: RBTreeLink::new(),
+///         lru_link: LinkedListLink::new(),
+///         key,
+///     }));
+/// }
+/// assert_eq!(cache.lru().front().get().unwrap().key, 3);
+///
+/// // Evict the entry with key 1 from both collections
+/// let entry = cache.by_key().find(&1).clone_pointer().unwrap();
+/// unsafe { cache.remove(&entry) };
+/// assert!(cache.by_key().find(&1).is_null());
+/// assert_eq!(cache.lru().iter().map(|e| e.key).collect::<Vec<_>>(), [3, 2]);
+/// ```
+#[macro_export]
+macro_rules! intrusive_bundle {
+    ($(#[$attr:meta])* $vis:vis struct $name:ident = $pointer:ty: $value:path {
+        $($member:ident: $collection:ident<$adapter:ident> { $field:ident: $link:ty }),+ $(,)?
+    }) => {
+        $(
+            $crate::intrusive_adapter!($vis $adapter = $pointer: $value { $field: $link });
+        )+
+
+        $(#[$attr])*
+        $vis struct $name {
+            $($member: $collection<$adapter>,)+
+        }
+
+        #[allow(dead_code)]
+        impl $name {
+            /// Creates an empty bundle.
+            #[inline]
+            pub const fn new() -> $name {
+                $name {
+                    $($member: $collection::new($adapter::NEW),)+
+                }
+            }
+
+            $(
+                /// Returns a reference to one of the collections.
+                #[inline]
+                pub fn $member(&self) -> &$collection<$adapter> {
+                    &self.$member
+                }
+            )+
+
+            /// Returns mutable references to all the collections.
+            ///
+            /// # Safety
+            ///
+            /// When the returned references are released, every value in any
+            /// of the collections must be in all of them.
+            #[inline]
+            pub unsafe fn collections_mut(&mut self) -> ($(&mut $collection<$adapter>,)+) {
+                ($(&mut self.$member,)+)
+            }
+
+            /// Inserts a value into all the collections.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the value is already linked to any collection, in
+            /// which case none of the collections are modified.
+            #[inline]
+            pub fn insert(&mut self, val: $pointer) {
+                $(
+                    if $crate::BundleMember::is_linked(&self.$member, &*val) {
+                        panic!("attempted to insert an object that is already linked");
+                    }
+                )+
+                $(
+                    $crate::BundleMember::insert_member(
+                        &mut self.$member,
+                        core::clone::Clone::clone(&val),
+                    );
+                )+
+            }
+
+            /// Removes a value from all the collections, returning `None` if
+            /// the value was not in the bundle.
+            ///
+            /// # Safety
+            ///
+            /// `value` must either be part of this bundle or not be linked to
+            /// any collection through the links used by this bundle.
+            #[inline]
+            pub unsafe fn remove(&mut self, value: &$value) -> Option<$pointer> {
+                let mut result = None;
+                $(
+                    result = $crate::BundleMember::remove_member(&mut self.$member, value).or(result);
+                )+
+                result
+            }
+
+            /// Moves a value from this bundle into `other`, returning whether
+            /// the value was in this bundle.
+            ///
+            /// # Safety
+            ///
+            /// The same requirements as for `remove` apply.
+            #[inline]
+            pub unsafe fn transfer(&mut self, value: &$value, other: &mut $name) -> bool {
+                match self.remove(value) {
+                    Some(val) => {
+                        other.insert(val);
+                        true
+                    }
+                    None => false,
+                }
+            }
+
+            /// Removes all values from the bundle.
+            #[inline]
+            pub fn clear(&mut self) {
+                $(self.$member.clear();)+
+            }
+        }
+
+        impl Default for $name {
+            #[inline]
+            fn default() -> $name {
+                $name::new()
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GenerationLink, KeyAdapter, LinkedList, LinkedListLink, RBTree, RBTreeLink};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::rc::Rc;
+    use std::vec;
+    use std::vec::Vec;
+
+    struct Obj {
+        key_link: RBTreeLink,
+        list_link: LinkedListLink,
+        key: u32,
+    }
+
+    intrusive_bundle!(struct Bundle = Rc<Obj>: Obj {
+        by_key: RBTree<KeyObjAdapter> { key_link: RBTreeLink },
+        list: LinkedList<ListObjAdapter> { list_link: LinkedListLink },
+    });
+    impl<'a> KeyAdapter<'a> for KeyObjAdapter {
+        type Key = u32;
+        fn get_key(&self, value: &'a Obj) -> u32 {
+            value.key
+        }
+    }
+
+    fn make_obj(key: u32) -> Rc<Obj> {
+        Rc::new(Obj {
+            key_link: RBTreeLink::new(),
+            list_link: LinkedListLink::new(),
+            key,
+        })
+    }
+
+    fn keys(bundle: &Bundle) -> (Vec<u32>, Vec<u32>) {
+        (
+            bundle.by_key().iter().map(|x| x.key).collect(),
+            bundle.list().iter().map(|x| x.key).collect(),
+        )
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut bundle = Bundle::new();
+        let a = make_obj(2);
+        let b = make_obj(1);
+        bundle.insert(a.clone());
+        bundle.insert(b.clone());
+        assert_eq!(Rc::strong_count(&a), 3);
+        assert_eq!(keys(&bundle), (vec![1, 2], vec![2, 1]));
+
+        let removed = unsafe { bundle.remove(&a) }.unwrap();
+        assert!(Rc::ptr_eq(&removed, &a));
+        drop(removed);
+        assert_eq!(Rc::strong_count(&a), 1);
+        assert!(!a.key_link.is_linked());
+        assert!(!a.list_link.is_linked());
+        assert_eq!(keys(&bundle), (vec![1], vec![1]));
+
+        assert!(unsafe { bundle.remove(&a) }.is_none());
+
+        bundle.clear();
+        assert_eq!(Rc::strong_count(&b), 1);
+        assert_eq!(keys(&bundle), (vec![], vec![]));
+    }
+
+    #[test]
+    fn test_insert_linked() {
+        let mut bundle = Bundle::new();
+        let mut list = LinkedList::new(ListObjAdapter::new());
+        let a = make_obj(1);
+        list.push_back(a.clone());
+
+        let result = catch_unwind(AssertUnwindSafe(|| bundle.insert(a.clone())));
+        assert!(result.is_err());
+        assert!(!a.key_link.is_linked());
+        assert_eq!(keys(&bundle), (vec![], vec![]));
+        list.clear();
+    }
+
+    #[test]
+    fn test_transfer() {
+        let mut bundle1 = Bundle::new();
+        let mut bundle2 = Bundle::default();
+        let a = make_obj(1);
+        let b = make_obj(2);
+        bundle1.insert(a.clone());
+        bundle1.insert(b.clone());
+
+        assert!(unsafe { bundle1.transfer(&a, &mut bundle2) });
+        assert!(!unsafe { bundle1.transfer(&make_obj(3), &mut bundle2) });
+        assert_eq!(keys(&bundle1), (vec![2], vec![2]));
+        assert_eq!(keys(&bundle2), (vec![1], vec![1]));
+        assert_eq!(Rc::strong_count(&a), 3);
+    }
+
+    #[test]
+    fn test_collections_mut() {
+        let mut bundle = Bundle::new();
+        for key in 0..3 {
+            bundle.insert(make_obj(key));
+        }
+        unsafe {
+            let (_, list) = bundle.collections_mut();
+            let front = list.pop_front().unwrap();
+            list.push_back(front);
+        }
+        assert_eq!(keys(&bundle), (vec![0, 1, 2], vec![1, 2, 0]));
+    }
+
+    #[test]
+    fn test_insert_keeps_generation() {
+        struct GenObj {
+            key_link: RBTreeLink,
+            list_link: GenerationLink<LinkedListLink>,
+        }
+        intrusive_bundle!(struct GenBundle = Rc<GenObj>: GenObj {
+            by_key: RBTree<GenKeyAdapter> { key_link: RBTreeLink },
+            list: LinkedList<GenListAdapter> { list_link: GenerationLink<LinkedListLink> },
+        });
+        impl<'a> KeyAdapter<'a> for GenKeyAdapter {
+            type Key = usize;
+            fn get_key(&self, value: &'a GenObj) -> usize {
+                value as *const GenObj as usize
+            }
+        }
+
+        let mut bundle = GenBundle::new();
+        let a = Rc::new(GenObj {
+            key_link: RBTreeLink::new(),
+            list_link: GenerationLink::new(LinkedListLink::new()),
+        });
+        bundle.insert(a.clone());
+        assert_eq!(a.list_link.generation(), 0);
+        assert!(unsafe { bundle.remove(&a) }.is_some());
+        assert_eq!(a.list_link.generation(), 1);
+    }
+}
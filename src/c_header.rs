@@ -0,0 +1,435 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! C definitions of the link types.
+//!
+//! All link types are `repr(C)`, so objects shared with C code can embed them
+//! and C code can walk the collections they are in. `write_c_header` writes a
+//! C header declaring a struct with the same layout for each link type, along
+//! with the value of its unlinked marker and the other special values of its
+//! fields:
+//!
+//! ```
+//! let mut header = String::new();
+//! intrusive_collections::c_header::write_c_header(&mut header).unwrap();
+//! assert!(header.contains("struct ic_linked_list_link {"));
+//! ```
+//!
+//! The header checks the size of every struct with `_Static_assert`, so it
+//! fails to compile if the C compiler targets a different platform than the
+//! one it was generated for. Enabling `debug_owner` changes the layout of
+//! every link, so the header must be generated with the same features as the
+//! Rust code it is used with.
+//!
+//! C code must only modify the links of objects which no Rust collection is
+//! accessing at the same time, and must keep the collections valid: a list
+//! inserted into by C code must still be correctly linked, and a red-black
+//! tree must still be balanced and sorted.
+
+use core::fmt;
+use core::mem;
+
+use crate::{linked_list, rbtree, singly_linked_list, xor_linked_list};
+
+struct LinkDef {
+    name: &'static str,
+    size: usize,
+    fields: &'static [(&'static str, &'static str)],
+}
+
+fn link_defs() -> &'static [LinkDef] {
+    // The `prev` field of `Link` holds the user tag in its low bits.
+    const LINKED_LIST: &[(&str, &str)] = if cfg!(feature = "debug_owner") {
+        &[
+            ("void *", "next"),
+            ("uintptr_t ", "prev"),
+            ("uintptr_t ", "owner"),
+        ]
+    } else {
+        &[("void *", "next"), ("uintptr_t ", "prev")]
+    };
+    #[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+    const LINKED_LIST_ATOMIC: &[(&str, &str)] = if cfg!(feature = "debug_owner") {
+        &[
+            ("void *", "next"),
+            ("void *", "prev"),
+            ("uintptr_t ", "owner"),
+        ]
+    } else {
+        &[("void *", "next"), ("void *", "prev")]
+    };
+    const SINGLY_LINKED_LIST: &[(&str, &str)] = if cfg!(feature = "debug_owner") {
+        &[("void *", "next"), ("uintptr_t ", "owner")]
+    } else {
+        &[("void *", "next")]
+    };
+    const XOR_LINKED_LIST: &[(&str, &str)] = if cfg!(feature = "debug_owner") {
+        &[("uintptr_t ", "packed"), ("uintptr_t ", "owner")]
+    } else {
+        &[("uintptr_t ", "packed")]
+    };
+    const RBTREE: &[(&str, &str)] = if cfg!(feature = "debug_owner") {
+        &[
+            ("void *", "left"),
+            ("void *", "right"),
+            ("uintptr_t ", "parent_color"),
+            ("uintptr_t ", "owner"),
+        ]
+    } else {
+        &[
+            ("void *", "left"),
+            ("void *", "right"),
+            ("uintptr_t ", "parent_color"),
+        ]
+    };
+    const DEFS: &[LinkDef] = &[
+        LinkDef {
+            name: "linked_list_link",
+            size: mem::size_of::<linked_list::Link>(),
+            fields: LINKED_LIST,
+        },
+        #[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+        LinkDef {
+            name: "linked_list_atomic_link",
+            size: mem::size_of::<linked_list::AtomicLink>(),
+            fields: LINKED_LIST_ATOMIC,
+        },
+        LinkDef {
+            name: "singly_linked_list_link",
+            size: mem::size_of::<singly_linked_list::Link>(),
+            fields: SINGLY_LINKED_LIST,
+        },
+        #[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+        LinkDef {
+            name: "singly_linked_list_atomic_link",
+            size: mem::size_of::<singly_linked_list::AtomicLink>(),
+            fields: SINGLY_LINKED_LIST,
+        },
+        LinkDef {
+            name: "xor_linked_list_link",
+            size: mem::size_of::<xor_linked_list::Link>(),
+            fields: XOR_LINKED_LIST,
+        },
+        #[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+        LinkDef {
+            name: "xor_linked_list_atomic_link",
+            size: mem::size_of::<xor_linked_list::AtomicLink>(),
+            fields: XOR_LINKED_LIST,
+        },
+        LinkDef {
+            name: "rbtree_link",
+            size: mem::size_of::<rbtree::Link>(),
+            fields: RBTREE,
+        },
+        #[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+        LinkDef {
+            name: "rbtree_atomic_link",
+            size: mem::size_of::<rbtree::AtomicLink>(),
+            fields: RBTREE,
+        },
+    ];
+    DEFS
+}
+
+/// Writes a C header declaring the layout of every link type.
+///
+/// Each link is declared as `struct ic_<module>_link` (or
+/// `struct ic_<module>_atomic_link` for atomic links), with pointers to
+/// neighboring links declared as `void *`. Those point to the link inside the
+/// neighboring object rather than to the object itself. The header also
+/// defines `IC_<MODULE>_UNLINKED`, the value of the first field of an unlinked
+/// link, and `IC_<MODULE>_CLAIMED`, its value while an atomic link is claimed
+/// by `try_acquire_for_insertion`. The `prev` field of `struct ic_linked_list_link` is a `uintptr_t`
+/// since its low bits hold the user tag of the link (see `Link::set_tag`):
+/// the pointer to the previous link is `prev & ~IC_LINKED_LIST_TAG_MASK`.
+/// The atomic links are only declared if they are available on the
+/// target. With the `ffi` feature, the header also declares the handles and
+/// functions of the `ffi` module.
+pub fn write_c_header<W: fmt::Write>(w: &mut W) -> fmt::Result {
+    writeln!(w, "/* Generated by intrusive-collections, do not edit. */")?;
+    writeln!(w, "#ifndef INTRUSIVE_COLLECTIONS_LINKS_H")?;
+    writeln!(w, "#define INTRUSIVE_COLLECTIONS_LINKS_H")?;
+    writeln!(w)?;
+    writeln!(w, "#include <stdint.h>")?;
+    writeln!(w)?;
+    writeln!(w, "#define IC_LINKED_LIST_UNLINKED ((void *)1)")?;
+    writeln!(
+        w,
+        "#define IC_LINKED_LIST_TAG_MASK ((uintptr_t){})",
+        linked_list::TAG_MASK
+    )?;
+    writeln!(w, "#define IC_SINGLY_LINKED_LIST_UNLINKED ((void *)1)")?;
+    writeln!(w, "#define IC_XOR_LINKED_LIST_UNLINKED ((uintptr_t)1)")?;
+    writeln!(w, "#define IC_RBTREE_UNLINKED ((uintptr_t)0)")?;
+    writeln!(w, "#define IC_RBTREE_BLACK ((uintptr_t)1)")?;
+    #[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+    {
+        let claimed = [
+            (
+                "LINKED_LIST",
+                "void *",
+                linked_list::ATOMIC_CLAIMED_MARKER_ADDR,
+            ),
+            (
+                "SINGLY_LINKED_LIST",
+                "void *",
+                singly_linked_list::ATOMIC_CLAIMED_MARKER_ADDR,
+            ),
+            (
+                "XOR_LINKED_LIST",
+                "uintptr_t",
+                xor_linked_list::CLAIMED_MARKER,
+            ),
+            ("RBTREE", "uintptr_t", rbtree::CLAIMED_MARKER),
+        ];
+        for &(module, ty, value) in claimed.iter() {
+            if value == usize::MAX {
+                writeln!(w, "#define IC_{}_CLAIMED (({})UINTPTR_MAX)", module, ty)?;
+            } else {
+                writeln!(w, "#define IC_{}_CLAIMED (({}){})", module, ty, value)?;
+            }
+        }
+    }
+    for def in link_defs() {
+        writeln!(w)?;
+        writeln!(w, "struct ic_{} {{", def.name)?;
+        for &(ty, field) in def.fields {
+            writeln!(w, "    {}{};", ty, field)?;
+        }
+        writeln!(w, "}};")?;
+        writeln!(
+            w,
+            "_Static_assert(sizeof(struct ic_{0}) == {1}, \"struct ic_{0} does not match the Rust layout\");",
+            def.name, def.size
+        )?;
+    }
+    #[cfg(feature = "ffi")]
+    {
+        writeln!(w)?;
+        w.write_str(crate::ffi::C_DECLARATIONS)?;
+    }
+    writeln!(w)?;
+    writeln!(w, "#endif")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{link_defs, write_c_header};
+    use crate::{linked_list, rbtree, singly_linked_list, xor_linked_list, KeyAdapter, UnsafeRef};
+    use core::mem;
+    use std::string::String;
+
+    // Reads the words making up a link.
+    fn words<L>(link: &L) -> std::vec::Vec<usize> {
+        let len = mem::size_of::<L>() / mem::size_of::<usize>();
+        unsafe { std::slice::from_raw_parts(link as *const L as *const usize, len).to_vec() }
+    }
+
+    #[test]
+    fn test_layout() {
+        let ptr = mem::size_of::<usize>();
+        for def in link_defs().iter() {
+            assert_eq!(def.size, def.fields.len() * ptr, "{}", def.name);
+        }
+
+        struct Obj {
+            ll: linked_list::Link,
+            sll: singly_linked_list::Link,
+            xll: xor_linked_list::Link,
+            rb: rbtree::Link,
+        }
+        intrusive_adapter!(LlAdapter<'a> = &'a Obj: Obj { ll: linked_list::Link });
+        intrusive_adapter!(SllAdapter<'a> = &'a Obj: Obj { sll: singly_linked_list::Link });
+        intrusive_adapter!(XllAdapter<'a> = &'a Obj: Obj { xll: xor_linked_list::Link });
+        intrusive_adapter!(RbAdapter = UnsafeRef<Obj>: Obj { rb: rbtree::Link });
+        impl<'a> KeyAdapter<'a> for RbAdapter {
+            type Key = usize;
+            fn get_key(&self, x: &'a Obj) -> usize {
+                x as *const Obj as usize
+            }
+        }
+        fn new_obj() -> Obj {
+            Obj {
+                ll: linked_list::Link::new(),
+                sll: singly_linked_list::Link::new(),
+                xll: xor_linked_list::Link::new(),
+                rb: rbtree::Link::new(),
+            }
+        }
+        let a = new_obj();
+        let b = new_obj();
+        assert_eq!(words(&a.ll)[0], 1);
+        assert_eq!(words(&a.sll)[0], 1);
+        assert_eq!(words(&a.xll)[0], 1);
+        assert_eq!(words(&a.rb)[2], 0);
+
+        let addr = |link: &dyn core::any::Any| link as *const _ as *const u8 as usize;
+        let mut ll = linked_list::LinkedList::new(LlAdapter::new());
+        ll.push_back(&a);
+        ll.push_back(&b);
+        assert_eq!(words(&a.ll)[..2], [addr(&b.ll), 0]);
+        assert_eq!(words(&b.ll)[..2], [0, addr(&a.ll)]);
+        b.ll.set_tag(2);
+        assert_eq!(words(&b.ll)[1] & linked_list::TAG_MASK, 2);
+        assert_eq!(words(&b.ll)[1] & !linked_list::TAG_MASK, addr(&a.ll));
+        let mut sll = singly_linked_list::SinglyLinkedList::new(SllAdapter::new());
+        sll.push_front(&b);
+        sll.push_front(&a);
+        assert_eq!(words(&a.sll)[0], addr(&b.sll));
+        assert_eq!(words(&b.sll)[0], 0);
+        let mut xll = xor_linked_list::XorLinkedList::new(XllAdapter::new());
+        xll.push_back(&a);
+        xll.push_back(&b);
+        assert_eq!(words(&a.xll)[0], addr(&b.xll));
+        let mut rb = rbtree::RBTree::new(RbAdapter::new());
+        unsafe {
+            rb.insert(UnsafeRef::from_raw(&a));
+            rb.insert(UnsafeRef::from_raw(&b));
+        }
+        // The root is black and has no parent, the other node is its red
+        // child.
+        let (root, child) = if words(&a.rb)[2] == 1 {
+            (&a, &b)
+        } else {
+            (&b, &a)
+        };
+        assert_eq!(words(&root.rb)[2], 1);
+        assert_eq!(words(&child.rb)[2], addr(&root.rb));
+        let side = if words(&root.rb)[0] == 0 { 1 } else { 0 };
+        assert_eq!(words(&root.rb)[side], addr(&child.rb));
+        ll.fast_clear();
+        sll.fast_clear();
+        xll.fast_clear();
+        rb.fast_clear();
+    }
+
+    // Reads the value of a `#define` of the header.
+    fn define(header: &str, name: &str) -> usize {
+        let prefix = std::format!("#define {} ((", name);
+        let line = header
+            .lines()
+            .find_map(|line| line.strip_prefix(&*prefix))
+            .unwrap_or_else(|| panic!("{} is not defined", name));
+        let value = &line[line.find(')').unwrap() + 1..line.len() - 1];
+        match value {
+            "UINTPTR_MAX" => usize::MAX,
+            _ => value.parse().unwrap(),
+        }
+    }
+
+    // Checks the special values defined by the header against the links.
+    #[test]
+    fn test_markers() {
+        let mut header = String::new();
+        write_c_header(&mut header).unwrap();
+
+        let ll = linked_list::Link::new();
+        let sll = singly_linked_list::Link::new();
+        let xll = xor_linked_list::Link::new();
+        let rb = rbtree::Link::new();
+        assert_eq!(words(&ll)[0], define(&header, "IC_LINKED_LIST_UNLINKED"));
+        assert_eq!(
+            words(&sll)[0],
+            define(&header, "IC_SINGLY_LINKED_LIST_UNLINKED")
+        );
+        assert_eq!(
+            words(&xll)[0],
+            define(&header, "IC_XOR_LINKED_LIST_UNLINKED")
+        );
+        assert_eq!(words(&rb)[2], define(&header, "IC_RBTREE_UNLINKED"));
+        assert_eq!(
+            define(&header, "IC_LINKED_LIST_TAG_MASK"),
+            (1 << linked_list::Link::TAG_BITS) - 1
+        );
+
+        #[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+        {
+            // Checks the unlinked and claimed values of an atomic link.
+            fn check<L>(
+                header: &str,
+                module: &str,
+                link: &L,
+                field: usize,
+                claim: fn(&L) -> bool,
+                unclaim: unsafe fn(&L),
+            ) {
+                let unlinked = define(header, &std::format!("IC_{}_UNLINKED", module));
+                let claimed = define(header, &std::format!("IC_{}_CLAIMED", module));
+                assert_eq!(words(link)[field], unlinked, "{}", module);
+                assert!(claim(link));
+                assert_eq!(words(link)[field], claimed, "{}", module);
+                unsafe { unclaim(link) };
+                assert_eq!(words(link)[field], unlinked, "{}", module);
+            }
+            check(
+                &header,
+                "LINKED_LIST",
+                &linked_list::AtomicLink::new(),
+                0,
+                linked_list::AtomicLink::try_acquire_for_insertion,
+                linked_list::AtomicLink::force_unlink,
+            );
+            check(
+                &header,
+                "SINGLY_LINKED_LIST",
+                &singly_linked_list::AtomicLink::new(),
+                0,
+                singly_linked_list::AtomicLink::try_acquire_for_insertion,
+                singly_linked_list::AtomicLink::force_unlink,
+            );
+            check(
+                &header,
+                "XOR_LINKED_LIST",
+                &xor_linked_list::AtomicLink::new(),
+                0,
+                xor_linked_list::AtomicLink::try_acquire_for_insertion,
+                xor_linked_list::AtomicLink::force_unlink,
+            );
+            check(
+                &header,
+                "RBTREE",
+                &rbtree::AtomicLink::new(),
+                2,
+                rbtree::AtomicLink::try_acquire_for_insertion,
+                rbtree::AtomicLink::force_unlink,
+            );
+        }
+    }
+
+    #[test]
+    fn test_write_c_header() {
+        let mut header = String::new();
+        write_c_header(&mut header).unwrap();
+        assert!(header.starts_with("/* Generated by intrusive-collections"));
+        assert!(header.ends_with("#endif\n"));
+        let ptr = mem::size_of::<usize>();
+        // `debug_owner` appends an `owner` field to every link.
+        let (owner, owner_size) = if cfg!(feature = "debug_owner") {
+            ("    uintptr_t owner;\n", ptr)
+        } else {
+            ("", 0)
+        };
+        assert!(header.contains(&std::format!(
+            "struct ic_rbtree_link {{\n    void *left;\n    void *right;\n    uintptr_t parent_color;\n{}}};\n_Static_assert(sizeof(struct ic_rbtree_link) == {}, ",
+            owner,
+            3 * ptr + owner_size
+        )));
+        assert!(header.contains(&std::format!(
+            "struct ic_singly_linked_list_atomic_link {{\n    void *next;\n{}}};\n_Static_assert(sizeof(struct ic_singly_linked_list_atomic_link) == {}, ",
+            owner,
+            ptr + owner_size
+        )));
+        assert!(
+            header.contains("struct ic_linked_list_link {\n    void *next;\n    uintptr_t prev;\n")
+        );
+        assert!(header.contains("#define IC_LINKED_LIST_TAG_MASK ((uintptr_t)3)\n"));
+        #[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+        assert!(header.contains("#define IC_RBTREE_CLAIMED ((uintptr_t)UINTPTR_MAX)\n"));
+        #[cfg(feature = "ffi")]
+        assert!(header.contains("\nbool ic_rbtree_insert(struct ic_rbtree *tree, "));
+    }
+}
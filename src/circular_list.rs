@@ -0,0 +1,291 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use core::fmt;
+
+use crate::adapter::Adapter;
+use crate::linked_list::{LinkedList, LinkedListOps};
+use crate::pointer_ops::PointerOps;
+
+/// A `LinkedList` which is walked as a ring, with a current position that
+/// `next_round_robin` advances and wraps around.
+///
+/// This is a run queue for cooperative schedulers: each call to
+/// `next_round_robin` returns the next entry, and entries can be added and
+/// removed between the calls. New entries are inserted just before the
+/// current one, so that they get their turn after all the other entries and
+/// before the current entry runs again.
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::{intrusive_adapter, CircularList, LinkedListLink};
+///
+/// struct Task {
+///     link: LinkedListLink,
+///     id: u32,
+/// }
+/// intrusive_adapter!(TaskAdapter = Box<Task>: Task { link: LinkedListLink });
+///
+/// let new_task = |id| Box::new(Task { link: LinkedListLink::new(), id });
+/// let mut tasks = CircularList::new(TaskAdapter::new());
+/// tasks.insert(new_task(1));
+/// tasks.insert(new_task(2));
+///
+/// assert_eq!(tasks.next_round_robin().unwrap().id, 1);
+/// tasks.insert(new_task(3));
+/// assert_eq!(tasks.next_round_robin().unwrap().id, 2);
+/// assert_eq!(tasks.next_round_robin().unwrap().id, 3);
+/// assert_eq!(tasks.next_round_robin().unwrap().id, 1);
+///
+/// assert_eq!(tasks.remove_current().unwrap().id, 1);
+/// assert_eq!(tasks.next_round_robin().unwrap().id, 2);
+/// ```
+pub struct CircularList<A: Adapter>
+where
+    A::LinkOps: LinkedListOps,
+{
+    list: LinkedList<A>,
+    // The element last returned by `next_round_robin`, or `None` if the
+    // next one is the front of the list.
+    current: Option<*const <A::PointerOps as PointerOps>::Value>,
+}
+
+impl<A: Adapter> CircularList<A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    /// Creates an empty `CircularList`.
+    #[inline]
+    pub fn new(adapter: A) -> CircularList<A> {
+        CircularList {
+            list: LinkedList::new(adapter),
+            current: None,
+        }
+    }
+
+    /// Returns `true` if the `CircularList` is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Returns the list of entries, from the front of the list rather than
+    /// from the current position.
+    #[inline]
+    pub fn list(&self) -> &LinkedList<A> {
+        &self.list
+    }
+
+    /// Returns a reference to the entry last returned by `next_round_robin`.
+    ///
+    /// This returns `None` if the rotation hasn't started, or if the current
+    /// entry was removed from the front of the list.
+    #[inline]
+    pub fn current(&self) -> Option<&<A::PointerOps as PointerOps>::Value> {
+        // `current` is always an element of the list.
+        Some(unsafe { &*self.current? })
+    }
+
+    /// Advances to the next entry and returns it, wrapping around to the
+    /// front of the list after the back.
+    ///
+    /// This returns `None` if the `CircularList` is empty.
+    #[inline]
+    pub fn next_round_robin(&mut self) -> Option<&<A::PointerOps as PointerOps>::Value> {
+        let mut cursor = match self.current {
+            Some(current) => unsafe { self.list.cursor_from_ptr(current) },
+            None => self.list.cursor(),
+        };
+        cursor.move_next();
+        if cursor.is_null() {
+            cursor.move_next();
+        }
+        let next = cursor.get()?;
+        self.current = Some(next);
+        Some(next)
+    }
+
+    /// Inserts a new entry just before the current one, so that it is the
+    /// last entry returned by `next_round_robin` before the current one comes
+    /// around again. If the rotation hasn't started, the entry is inserted at
+    /// the back of the list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new entry is already linked to a different intrusive
+    /// collection.
+    #[inline]
+    pub fn insert(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        match self.current {
+            Some(current) => unsafe { self.list.cursor_mut_from_ptr(current) }.insert_before(val),
+            None => self.list.push_back(val),
+        }
+    }
+
+    /// Removes the entry last returned by `next_round_robin` and returns it.
+    /// The next call to `next_round_robin` returns the entry that was after
+    /// it.
+    ///
+    /// This returns `None` if there is no current entry.
+    #[inline]
+    pub fn remove_current(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        let current = self.current?;
+        unsafe { Some(self.remove(current)) }
+    }
+
+    /// Removes an entry from the `CircularList` and returns it.
+    ///
+    /// If it is the current entry, the next call to `next_round_robin`
+    /// returns the entry that was after it.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a pointer to an object that is part of this list.
+    #[inline]
+    pub unsafe fn remove(
+        &mut self,
+        ptr: *const <A::PointerOps as PointerOps>::Value,
+    ) -> <A::PointerOps as PointerOps>::Pointer {
+        let mut cursor = self.list.cursor_mut_from_ptr(ptr);
+        if self.current == Some(ptr) {
+            self.current = cursor.peek_prev().get().map(|prev| prev as *const _);
+        }
+        cursor.remove().unwrap()
+    }
+
+    /// Removes all the entries of the `CircularList`, and resets the
+    /// rotation.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.current = None;
+        self.list.clear();
+    }
+
+    /// Consumes the `CircularList` and returns its entries as a
+    /// `LinkedList`.
+    #[inline]
+    pub fn into_list(self) -> LinkedList<A> {
+        self.list
+    }
+}
+
+// `current` is an element of the list, so the list gets the same thread
+// safety as a `LinkedList`.
+unsafe impl<A: Adapter + Sync> Sync for CircularList<A>
+where
+    <A::PointerOps as PointerOps>::Value: Sync,
+    <A::PointerOps as PointerOps>::Pointer: Sync,
+    A::LinkOps: LinkedListOps,
+{
+}
+
+unsafe impl<A: Adapter + Send> Send for CircularList<A>
+where
+    <A::PointerOps as PointerOps>::Pointer: Send,
+    A::LinkOps: LinkedListOps,
+{
+}
+
+impl<A: Adapter + Default> Default for CircularList<A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    #[inline]
+    fn default() -> CircularList<A> {
+        CircularList::new(A::default())
+    }
+}
+
+impl<A: Adapter> fmt::Debug for CircularList<A>
+where
+    A::LinkOps: LinkedListOps,
+    <A::PointerOps as PointerOps>::Value: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CircularList")
+            .field("list", &self.list)
+            .field("current", &self.current())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CircularList;
+    use crate::LinkedListLink;
+    use std::boxed::Box;
+    use std::vec::Vec;
+
+    struct Obj {
+        link: LinkedListLink,
+        value: u32,
+    }
+    intrusive_adapter!(ObjAdapter = Box<Obj>: Obj { link: LinkedListLink });
+
+    fn make_obj(value: u32) -> Box<Obj> {
+        Box::new(Obj {
+            link: LinkedListLink::new(),
+            value,
+        })
+    }
+
+    fn rounds(l: &mut CircularList<ObjAdapter>, n: usize) -> Vec<u32> {
+        (0..n)
+            .map(|_| l.next_round_robin().unwrap().value)
+            .collect()
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let mut l = CircularList::new(ObjAdapter::new());
+        assert!(l.next_round_robin().is_none());
+        assert!(l.remove_current().is_none());
+        for value in 0..3 {
+            l.insert(make_obj(value));
+        }
+        assert!(l.current().is_none());
+        assert_eq!(rounds(&mut l, 7), [0, 1, 2, 0, 1, 2, 0]);
+        assert_eq!(l.current().unwrap().value, 0);
+
+        // New entries run after the others, before the current one again.
+        l.insert(make_obj(3));
+        assert_eq!(rounds(&mut l, 5), [1, 2, 3, 0, 1]);
+        let values = l.list().iter().map(|x| x.value).collect::<Vec<_>>();
+        assert_eq!(values, [3, 0, 1, 2]);
+
+        l.clear();
+        assert!(l.is_empty());
+        assert!(l.current().is_none());
+        assert!(l.next_round_robin().is_none());
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut l = CircularList::new(ObjAdapter::new());
+        for value in 0..4 {
+            l.insert(make_obj(value));
+        }
+        assert_eq!(rounds(&mut l, 2), [0, 1]);
+        assert_eq!(l.remove_current().unwrap().value, 1);
+        assert_eq!(l.current().unwrap().value, 0);
+        assert_eq!(rounds(&mut l, 3), [2, 3, 0]);
+
+        // Removing the front makes the rotation restart from the front.
+        assert_eq!(l.remove_current().unwrap().value, 0);
+        assert!(l.current().is_none());
+        assert_eq!(rounds(&mut l, 3), [2, 3, 2]);
+
+        let other = l.list().back().get().unwrap() as *const _;
+        assert_eq!(unsafe { l.remove(other) }.value, 3);
+        assert_eq!(rounds(&mut l, 2), [2, 2]);
+        assert_eq!(l.remove_current().unwrap().value, 2);
+        assert!(l.is_empty());
+        assert!(l.next_round_robin().is_none());
+        assert!(l.into_list().is_empty());
+    }
+}
@@ -0,0 +1,314 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! A trait for writing code that is generic over the intrusive collections.
+
+use core::fmt;
+use core::iter::FusedIterator;
+
+use crate::adapter::Adapter;
+use crate::pointer_ops::PointerOps;
+
+// The value and pointer types of a collection.
+type Value<C> = <<<C as Collection>::Adapter as Adapter>::PointerOps as PointerOps>::Value;
+type Pointer<C> = <<<C as Collection>::Adapter as Adapter>::PointerOps as PointerOps>::Pointer;
+
+/// Operations shared by `LinkedList`, `SinglyLinkedList`, `XorLinkedList` and
+/// `RBTree`, so that generic code such as a cache can be written once and
+/// used with any of them.
+///
+/// `insert` adds an element where the collection naturally puts new ones:
+/// at the back of `LinkedList` and `XorLinkedList`, at the front of
+/// `SinglyLinkedList`, and at the position given by its key in `RBTree`.
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::{intrusive_adapter, Adapter, Collection, DefaultPointerOps};
+/// use intrusive_collections::{LinkedList, LinkedListLink, SinglyLinkedList, SinglyLinkedListLink};
+///
+/// struct Entry {
+///     link: LinkedListLink,
+///     slink: SinglyLinkedListLink,
+///     key: u32,
+/// }
+/// intrusive_adapter!(EntryAdapter = Box<Entry>: Entry { link: LinkedListLink });
+/// intrusive_adapter!(SEntryAdapter = Box<Entry>: Entry { slink: SinglyLinkedListLink });
+///
+/// fn evict<C: Collection>(entries: &mut C, key: u32) -> Option<Box<Entry>>
+/// where
+///     C::Adapter: Adapter<PointerOps = DefaultPointerOps<Box<Entry>>>,
+/// {
+///     entries.remove_first(|entry| entry.key == key)
+/// }
+///
+/// let new_entry = |key| Box::new(Entry {
+///     link: LinkedListLink::new(),
+///     slink: SinglyLinkedListLink::new(),
+///     key,
+/// });
+/// let mut list = LinkedList::new(EntryAdapter::new());
+/// let mut slist = SinglyLinkedList::new(SEntryAdapter::new());
+/// for key in 0..3 {
+///     list.insert(new_entry(key));
+///     slist.insert(new_entry(key));
+/// }
+/// assert_eq!(evict(&mut list, 1).unwrap().key, 1);
+/// assert_eq!(evict(&mut slist, 1).unwrap().key, 1);
+/// assert_eq!(Collection::iter(&list).map(|e| e.key).collect::<Vec<_>>(), [0, 2]);
+/// assert_eq!(Collection::iter(&slist).map(|e| e.key).collect::<Vec<_>>(), [2, 0]);
+/// ```
+pub trait Collection {
+    /// Adapter of the collection.
+    type Adapter: Adapter;
+
+    /// Position of an element, used by `Iter`.
+    #[doc(hidden)]
+    type Position: Copy;
+
+    /// Returns `true` if the collection is empty.
+    fn is_empty(&self) -> bool;
+
+    /// Removes all the elements of the collection.
+    fn clear(&mut self);
+
+    /// Inserts a new element into the collection.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new element is already linked to a different intrusive
+    /// collection.
+    fn insert(&mut self, val: Pointer<Self>);
+
+    /// Removes the first element for which `f` returns `true`, in iteration
+    /// order, and returns it.
+    ///
+    /// Returns `None` if there is no such element.
+    fn remove_first<F: FnMut(&Value<Self>) -> bool>(&mut self, f: F) -> Option<Pointer<Self>>;
+
+    /// Takes all the elements out of the collection, leaving it empty. The
+    /// taken elements are returned as a new collection, with a clone of the
+    /// adapter.
+    ///
+    /// This takes constant time.
+    fn take(&mut self) -> Self
+    where
+        Self: Sized,
+        Self::Adapter: Clone;
+
+    /// Swaps the elements of this collection with those of `other`, along
+    /// with their adapters.
+    ///
+    /// This takes constant time. `mem::swap` can also be used, since the
+    /// collections don't point into themselves.
+    fn swap(&mut self, other: &mut Self)
+    where
+        Self: Sized;
+
+    /// Gets an iterator over the objects in the collection.
+    #[inline]
+    fn iter(&self) -> Iter<'_, Self> {
+        Iter {
+            position: self.front_position(),
+            collection: self,
+        }
+    }
+
+    /// Returns the position of the first element.
+    #[doc(hidden)]
+    fn front_position(&self) -> Self::Position;
+
+    /// Returns the element at `position`, or `None` at the end of the
+    /// collection.
+    ///
+    /// # Safety
+    ///
+    /// `position` must have been returned by `front_position` or
+    /// `next_position`, and the collection must not have been modified since.
+    #[doc(hidden)]
+    unsafe fn value_at(&self, position: Self::Position) -> Option<&Value<Self>>;
+
+    /// Returns the position of the element after the one at `position`, which
+    /// must not be at the end of the collection.
+    ///
+    /// # Safety
+    ///
+    /// Same as for `value_at`.
+    #[doc(hidden)]
+    unsafe fn next_position(&self, position: Self::Position) -> Self::Position;
+}
+
+/// An iterator over the objects in a `Collection`, returned by
+/// `Collection::iter`.
+pub struct Iter<'a, C: Collection + ?Sized> {
+    collection: &'a C,
+    position: C::Position,
+}
+
+impl<'a, C: Collection + ?Sized> Iterator for Iter<'a, C> {
+    type Item = &'a Value<C>;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a Value<C>> {
+        // The collection is borrowed for as long as the iterator exists, so
+        // the position stays valid.
+        unsafe {
+            let value = self.collection.value_at(self.position)?;
+            self.position = self.collection.next_position(self.position);
+            Some(value)
+        }
+    }
+}
+
+impl<C: Collection + ?Sized> FusedIterator for Iter<'_, C> {}
+
+impl<C: Collection + ?Sized> Clone for Iter<'_, C> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Iter {
+            collection: self.collection,
+            position: self.position,
+        }
+    }
+}
+
+impl<C: Collection + ?Sized> fmt::Debug for Iter<'_, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Iter").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Collection;
+    use crate::{
+        Adapter, KeyAdapter, LinkedList, LinkedListLink, PointerOps, RBTree, RBTreeLink,
+        SinglyLinkedList, SinglyLinkedListLink, XorLinkedList, XorLinkedListLink,
+    };
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    struct Obj {
+        ll: LinkedListLink,
+        sll: SinglyLinkedListLink,
+        xll: XorLinkedListLink,
+        rb: RBTreeLink,
+        value: u32,
+    }
+    intrusive_adapter!(LlAdapter = Rc<Obj>: Obj { ll: LinkedListLink });
+    intrusive_adapter!(SllAdapter = Rc<Obj>: Obj { sll: SinglyLinkedListLink });
+    intrusive_adapter!(XllAdapter = Rc<Obj>: Obj { xll: XorLinkedListLink });
+    intrusive_adapter!(RbAdapter = Rc<Obj>: Obj { rb: RBTreeLink });
+    impl<'a> KeyAdapter<'a> for RbAdapter {
+        type Key = u32;
+        fn get_key(&self, x: &'a Obj) -> u32 {
+            x.value
+        }
+    }
+
+    // Inserts the objects, removes the even ones and returns the values left.
+    fn exercise<C>(mut c: C, objs: &[Rc<Obj>]) -> Vec<u32>
+    where
+        C: Collection,
+        C::Adapter: Adapter,
+        <C::Adapter as Adapter>::PointerOps: PointerOps<Value = Obj, Pointer = Rc<Obj>>,
+    {
+        assert!(c.is_empty());
+        for obj in objs {
+            c.insert(obj.clone());
+        }
+        assert!(!c.is_empty());
+        while let Some(obj) = c.remove_first(|x| x.value % 2 == 0) {
+            assert_eq!(obj.value % 2, 0);
+        }
+        assert!(c.remove_first(|x| x.value == 2).is_none());
+        let values = c.iter().map(|x| x.value).collect::<Vec<_>>();
+        c.clear();
+        assert!(c.is_empty());
+        assert_eq!(c.iter().count(), 0);
+        values
+    }
+
+    // Double-buffers `objs` through the collections: `front` gets the objects,
+    // which are moved to `back` to be drained.
+    fn double_buffer<C>(mut front: C, mut back: C, objs: &[Rc<Obj>]) -> Vec<u32>
+    where
+        C: Collection,
+        C::Adapter: Adapter + Clone,
+        <C::Adapter as Adapter>::PointerOps: PointerOps<Value = Obj, Pointer = Rc<Obj>>,
+    {
+        for obj in &objs[..2] {
+            front.insert(obj.clone());
+        }
+        front.swap(&mut back);
+        assert!(front.is_empty());
+        for obj in &objs[2..] {
+            front.insert(obj.clone());
+        }
+        let mut taken = front.take();
+        assert!(front.is_empty());
+        taken.swap(&mut back);
+        let mut values = taken.iter().map(|x| x.value).collect::<Vec<_>>();
+        values.extend(back.iter().map(|x| x.value));
+        taken.clear();
+        back.clear();
+        values
+    }
+
+    #[test]
+    fn test_take_swap() {
+        let objs = [3, 4, 1, 2, 5]
+            .iter()
+            .map(|&value| {
+                Rc::new(Obj {
+                    ll: LinkedListLink::new(),
+                    sll: SinglyLinkedListLink::new(),
+                    xll: XorLinkedListLink::new(),
+                    rb: RBTreeLink::new(),
+                    value,
+                })
+            })
+            .collect::<Vec<_>>();
+        let l = || LinkedList::new(LlAdapter::new());
+        assert_eq!(double_buffer(l(), l(), &objs), [3, 4, 1, 2, 5]);
+        let s = || SinglyLinkedList::new(SllAdapter::new());
+        assert_eq!(double_buffer(s(), s(), &objs), [4, 3, 5, 2, 1]);
+        let x = || XorLinkedList::new(XllAdapter::new());
+        assert_eq!(double_buffer(x(), x(), &objs), [3, 4, 1, 2, 5]);
+        let t = || RBTree::new(RbAdapter::new());
+        assert_eq!(double_buffer(t(), t(), &objs), [3, 4, 1, 2, 5]);
+    }
+
+    #[test]
+    fn test_collections() {
+        let objs = [3, 4, 1, 2, 5]
+            .iter()
+            .map(|&value| {
+                Rc::new(Obj {
+                    ll: LinkedListLink::new(),
+                    sll: SinglyLinkedListLink::new(),
+                    xll: XorLinkedListLink::new(),
+                    rb: RBTreeLink::new(),
+                    value,
+                })
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            exercise(LinkedList::new(LlAdapter::new()), &objs),
+            [3, 1, 5]
+        );
+        assert_eq!(
+            exercise(SinglyLinkedList::new(SllAdapter::new()), &objs),
+            [5, 1, 3]
+        );
+        assert_eq!(
+            exercise(XorLinkedList::new(XllAdapter::new()), &objs),
+            [3, 1, 5]
+        );
+        assert_eq!(exercise(RBTree::new(RbAdapter::new()), &objs), [1, 3, 5]);
+    }
+}
@@ -0,0 +1,299 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::adapter::Adapter;
+use crate::key_adapter::KeyAdapter;
+use crate::link_ops::LinkOps;
+use crate::pointer_ops::PointerOps;
+use crate::priority_adapter::PriorityAdapter;
+use crate::sync::{AtomicUsize, Ordering};
+use core::fmt;
+use core::ops::Deref;
+
+/// Adapter which counts the elements of the collections using it in an
+/// `AtomicUsize`.
+///
+/// `A` is the adapter doing the actual work and `C` is a reference to the
+/// counter, such as `&'static AtomicUsize` or `Arc<AtomicUsize>`. The counter
+/// is incremented and decremented by `on_insert` and `on_remove`, so other
+/// threads can read the length of a collection without locking it, which is
+/// useful to monitor the depth of a queue. `KeyAdapter` and
+/// `PriorityAdapter` are forwarded to `A` if it implements them.
+///
+/// The collection doesn't call `on_insert` and `on_remove` for `fast_clear`
+/// or when splicing or splitting collections, so the counter is only
+/// accurate if those aren't used. Collections sharing the same counter count
+/// their elements together.
+///
+/// Like the atomic links, the counter is the `AtomicUsize` of the
+/// `portable-atomic` crate when the `portable-atomic` feature is enabled.
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::{intrusive_adapter, AtomicCountedAdapter, LinkedList};
+/// use intrusive_collections::LinkedListAtomicLink;
+/// # #[cfg(not(feature = "portable-atomic"))]
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// # #[cfg(feature = "portable-atomic")]
+/// # use portable_atomic::{AtomicUsize, Ordering};
+/// use std::sync::{Arc, Mutex};
+///
+/// struct Task {
+///     link: LinkedListAtomicLink,
+/// }
+/// intrusive_adapter!(TaskAdapter = Box<Task>: Task { link: LinkedListAtomicLink });
+///
+/// let depth = Arc::new(AtomicUsize::new(0));
+/// let queue = Mutex::new(LinkedList::new(AtomicCountedAdapter::new(
+///     TaskAdapter::new(),
+///     depth.clone(),
+/// )));
+/// queue.lock().unwrap().push_back(Box::new(Task { link: LinkedListAtomicLink::new() }));
+///
+/// // The depth can be read without locking the queue.
+/// assert_eq!(depth.load(Ordering::Relaxed), 1);
+/// ```
+pub struct AtomicCountedAdapter<A, C> {
+    inner: A,
+    count: C,
+}
+
+impl<A, C> AtomicCountedAdapter<A, C> {
+    /// Creates a new `AtomicCountedAdapter` which forwards to `inner` and
+    /// counts elements in `count`.
+    #[inline]
+    pub const fn new(inner: A, count: C) -> AtomicCountedAdapter<A, C> {
+        AtomicCountedAdapter { inner, count }
+    }
+
+    /// Returns a reference to the wrapped adapter.
+    #[inline]
+    pub fn inner(&self) -> &A {
+        &self.inner
+    }
+
+    /// Returns a reference to the counter.
+    #[inline]
+    pub fn counter(&self) -> &C {
+        &self.count
+    }
+}
+
+impl<A, C: Deref<Target = AtomicUsize>> AtomicCountedAdapter<A, C> {
+    /// Returns the current value of the counter.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if the counter is zero.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<A: Clone, C: Clone> Clone for AtomicCountedAdapter<A, C> {
+    #[inline]
+    fn clone(&self) -> Self {
+        AtomicCountedAdapter::new(self.inner.clone(), self.count.clone())
+    }
+}
+
+impl<A: fmt::Debug, C: Deref<Target = AtomicUsize>> fmt::Debug for AtomicCountedAdapter<A, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AtomicCountedAdapter")
+            .field("inner", &self.inner)
+            .field("count", &self.len())
+            .finish()
+    }
+}
+
+unsafe impl<A, C> Adapter for AtomicCountedAdapter<A, C>
+where
+    A: Adapter,
+    C: Deref<Target = AtomicUsize>,
+{
+    type LinkOps = A::LinkOps;
+    type PointerOps = A::PointerOps;
+
+    #[inline]
+    unsafe fn get_value(
+        &self,
+        link: <Self::LinkOps as LinkOps>::LinkPtr,
+    ) -> *const <Self::PointerOps as PointerOps>::Value {
+        self.inner.get_value(link)
+    }
+
+    #[inline]
+    unsafe fn get_link(
+        &self,
+        value: *const <Self::PointerOps as PointerOps>::Value,
+    ) -> <Self::LinkOps as LinkOps>::LinkPtr {
+        self.inner.get_link(value)
+    }
+
+    #[inline]
+    fn link_ops(&self) -> &Self::LinkOps {
+        self.inner.link_ops()
+    }
+
+    #[inline]
+    fn link_ops_mut(&mut self) -> &mut Self::LinkOps {
+        self.inner.link_ops_mut()
+    }
+
+    #[inline]
+    fn pointer_ops(&self) -> &Self::PointerOps {
+        self.inner.pointer_ops()
+    }
+
+    #[inline]
+    fn on_insert(&self, value: &<Self::PointerOps as PointerOps>::Value) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.inner.on_insert(value);
+    }
+
+    #[inline]
+    fn on_remove(&self, value: &<Self::PointerOps as PointerOps>::Value) {
+        self.count.fetch_sub(1, Ordering::Relaxed);
+        self.inner.on_remove(value);
+    }
+}
+
+impl<'a, A, C> KeyAdapter<'a> for AtomicCountedAdapter<A, C>
+where
+    A: KeyAdapter<'a>,
+    C: Deref<Target = AtomicUsize>,
+{
+    type Key = A::Key;
+
+    #[inline]
+    fn get_key(&self, value: &'a <Self::PointerOps as PointerOps>::Value) -> A::Key {
+        self.inner.get_key(value)
+    }
+}
+
+impl<A, C> PriorityAdapter for AtomicCountedAdapter<A, C>
+where
+    A: PriorityAdapter,
+    C: Deref<Target = AtomicUsize>,
+{
+    type Priority = A::Priority;
+
+    #[inline]
+    fn get_priority(&self, value: &<Self::PointerOps as PointerOps>::Value) -> A::Priority {
+        self.inner.get_priority(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AtomicCountedAdapter;
+    use crate::sync::{AtomicUsize, Ordering};
+    use crate::{KeyAdapter, LinkedList, LinkedListAtomicLink, RBTree, RBTreeLink, UnsafeRef};
+    use std::boxed::Box;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::string::String;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::vec::Vec;
+
+    struct Obj {
+        link: LinkedListAtomicLink,
+        tree_link: RBTreeLink,
+        value: u32,
+    }
+    intrusive_adapter!(ObjAdapter = Box<Obj>: Obj { link: LinkedListAtomicLink });
+    intrusive_adapter!(ObjTreeAdapter = UnsafeRef<Obj>: Obj { tree_link: RBTreeLink });
+    impl<'a> KeyAdapter<'a> for ObjTreeAdapter {
+        type Key = u32;
+        fn get_key(&self, value: &'a Obj) -> u32 {
+            value.value
+        }
+    }
+
+    fn make_obj(value: u32) -> Box<Obj> {
+        Box::new(Obj {
+            link: LinkedListAtomicLink::new(),
+            tree_link: RBTreeLink::new(),
+            value,
+        })
+    }
+
+    #[test]
+    fn test_counted_list() {
+        let count = AtomicUsize::new(0);
+        let mut list = LinkedList::new(AtomicCountedAdapter::new(ObjAdapter::new(), &count));
+        for value in 0..4 {
+            list.push_back(make_obj(value));
+        }
+        assert_eq!(count.load(Ordering::Relaxed), 4);
+
+        let mut cursor = list.front_mut();
+        cursor.move_next();
+        assert_eq!(cursor.remove().unwrap().value, 1);
+        cursor.insert_before(make_obj(5));
+        assert_eq!(count.load(Ordering::Relaxed), 4);
+        assert_eq!(list.pop_back().unwrap().value, 3);
+        assert_eq!(count.load(Ordering::Relaxed), 3);
+
+        assert_eq!(list.assert_valid_count(), 3);
+        count.fetch_add(1, Ordering::Relaxed);
+        let err = catch_unwind(AssertUnwindSafe(|| list.assert_valid_count())).unwrap_err();
+        let msg = err.downcast::<String>().unwrap();
+        assert!(msg.contains("the counter is 4"), "{}", msg);
+
+        count.fetch_sub(1, Ordering::Relaxed);
+        list.clear();
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_counted_tree() {
+        let count = AtomicUsize::new(0);
+        let objs = (0..3).map(make_obj).collect::<Vec<_>>();
+        let mut tree = RBTree::new(AtomicCountedAdapter::new(ObjTreeAdapter::new(), &count));
+        for obj in objs.iter().rev() {
+            tree.insert(unsafe { UnsafeRef::from_raw(&**obj) });
+        }
+        assert_eq!(tree.find(&1).get().unwrap().value, 1);
+        assert_eq!(tree.find_mut(&1).remove().unwrap().value, 1);
+        assert_eq!(count.load(Ordering::Relaxed), 2);
+        tree.clear();
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_counted_threads() {
+        let depth = Arc::new(AtomicUsize::new(0));
+        let adapter = AtomicCountedAdapter::new(ObjAdapter::new(), depth.clone());
+        assert!(adapter.is_empty());
+        let queue = Arc::new(Mutex::new(LinkedList::new(adapter)));
+
+        let producer = {
+            let queue = queue.clone();
+            thread::spawn(move || {
+                for value in 0..100 {
+                    queue.lock().unwrap().push_back(make_obj(value));
+                }
+            })
+        };
+        // Monitor the depth without taking the lock.
+        let mut max = 0;
+        while max < 100 {
+            max = max.max(depth.load(Ordering::Relaxed));
+        }
+        producer.join().unwrap();
+
+        let mut queue = queue.lock().unwrap();
+        while queue.pop_front().is_some() {}
+        assert_eq!(depth.load(Ordering::Relaxed), 0);
+    }
+}
@@ -0,0 +1,265 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::adapter::Adapter;
+use crate::pointer_ops::PointerOps;
+
+// The value and pointer types of a cursor.
+type Value<C> = <<<C as CursorOps>::Adapter as Adapter>::PointerOps as PointerOps>::Value;
+type Pointer<C> = <<<C as CursorOps>::Adapter as Adapter>::PointerOps as PointerOps>::Pointer;
+
+/// Operations shared by the `Cursor` and `CursorMut` of every collection, so
+/// that algorithms walking a collection can be written once for all of them.
+///
+/// As with the inherent methods, a cursor can point to the null object, which
+/// sits between the last and the first element.
+pub trait CursorOps {
+    /// Adapter of the collection.
+    type Adapter: Adapter;
+
+    /// Checks if the cursor is currently pointing to the null object.
+    fn is_null(&self) -> bool;
+
+    /// Returns a reference to the object that the cursor is currently
+    /// pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the null
+    /// object.
+    fn get(&self) -> Option<&Value<Self>>;
+
+    /// Moves the cursor to the next element of the collection.
+    ///
+    /// If the cursor is pointing to the null object then this will move it to
+    /// the first element. If it is pointing to the last element then this
+    /// will move it to the null object.
+    fn move_next(&mut self);
+}
+
+/// Cursor operations of the collections which can be walked backwards, which
+/// is all of them except `SinglyLinkedList`.
+pub trait DoubleEndedCursorOps: CursorOps {
+    /// Moves the cursor to the previous element of the collection.
+    ///
+    /// If the cursor is pointing to the null object then this will move it to
+    /// the last element. If it is pointing to the first element then this
+    /// will move it to the null object.
+    fn move_prev(&mut self);
+}
+
+/// Operations shared by the `CursorMut` of every collection.
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::{intrusive_adapter, Adapter, CursorMutOps, CursorOps, DefaultPointerOps};
+/// use intrusive_collections::{LinkedList, LinkedListLink, SinglyLinkedList, SinglyLinkedListLink};
+///
+/// struct Job {
+///     link: LinkedListLink,
+///     slink: SinglyLinkedListLink,
+///     done: bool,
+/// }
+/// intrusive_adapter!(JobAdapter = Box<Job>: Job { link: LinkedListLink });
+/// intrusive_adapter!(SJobAdapter = Box<Job>: Job { slink: SinglyLinkedListLink });
+///
+/// // Removes the jobs which are done, wherever they are in the collection.
+/// fn remove_done<C: CursorMutOps>(mut cursor: C) -> usize
+/// where
+///     C::Adapter: Adapter<PointerOps = DefaultPointerOps<Box<Job>>>,
+/// {
+///     let mut removed = 0;
+///     while let Some(job) = cursor.get() {
+///         if job.done {
+///             cursor.remove();
+///             removed += 1;
+///         } else {
+///             cursor.move_next();
+///         }
+///     }
+///     removed
+/// }
+///
+/// let new_job = |done| Box::new(Job {
+///     link: LinkedListLink::new(),
+///     slink: SinglyLinkedListLink::new(),
+///     done,
+/// });
+/// let mut list = LinkedList::new(JobAdapter::new());
+/// let mut slist = SinglyLinkedList::new(SJobAdapter::new());
+/// for &done in &[true, false, true] {
+///     list.push_back(new_job(done));
+///     slist.push_front(new_job(done));
+/// }
+/// assert_eq!(remove_done(list.front_mut()), 2);
+/// assert_eq!(remove_done(slist.front_mut()), 2);
+/// assert!(list.iter().all(|job| !job.done));
+/// assert!(slist.iter().all(|job| !job.done));
+/// ```
+pub trait CursorMutOps: CursorOps {
+    /// Removes the current element from the collection.
+    ///
+    /// A pointer to the element that was removed is returned, and the cursor
+    /// is moved to point to the next element.
+    ///
+    /// If the cursor is currently pointing to the null object then no element
+    /// is removed and `None` is returned.
+    fn remove(&mut self) -> Option<Pointer<Self>>;
+
+    /// Inserts a new element into the collection after the current one.
+    ///
+    /// If the cursor is pointing at the null object then the new element is
+    /// inserted at the front of the collection.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new element is already linked to a different intrusive
+    /// collection.
+    fn insert_after(&mut self, val: Pointer<Self>);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CursorMutOps, CursorOps, DoubleEndedCursorOps};
+    use crate::{
+        Adapter, KeyAdapter, LinkedList, LinkedListLink, PointerOps, RBTree, RBTreeLink,
+        SinglyLinkedList, SinglyLinkedListLink, UnsafeRef, XorLinkedList, XorLinkedListLink,
+    };
+    use std::boxed::Box;
+    use std::vec::Vec;
+
+    struct Obj {
+        ll: LinkedListLink,
+        sll: SinglyLinkedListLink,
+        xll: XorLinkedListLink,
+        rb: RBTreeLink,
+        value: u32,
+    }
+    intrusive_adapter!(LlAdapter = UnsafeRef<Obj>: Obj { ll: LinkedListLink });
+    intrusive_adapter!(SllAdapter = UnsafeRef<Obj>: Obj { sll: SinglyLinkedListLink });
+    intrusive_adapter!(XllAdapter = UnsafeRef<Obj>: Obj { xll: XorLinkedListLink });
+    intrusive_adapter!(RbAdapter = UnsafeRef<Obj>: Obj { rb: RBTreeLink });
+    impl<'a> KeyAdapter<'a> for RbAdapter {
+        type Key = u32;
+        fn get_key(&self, x: &'a Obj) -> u32 {
+            x.value
+        }
+    }
+
+    fn make_obj(value: u32) -> UnsafeRef<Obj> {
+        UnsafeRef::from_box(Box::new(Obj {
+            ll: LinkedListLink::new(),
+            sll: SinglyLinkedListLink::new(),
+            xll: XorLinkedListLink::new(),
+            rb: RBTreeLink::new(),
+            value,
+        }))
+    }
+
+    fn values<C>(mut cursor: C) -> Vec<u32>
+    where
+        C: CursorOps,
+        <C::Adapter as Adapter>::PointerOps: PointerOps<Value = Obj>,
+    {
+        let mut values = Vec::new();
+        while let Some(x) = cursor.get() {
+            values.push(x.value);
+            cursor.move_next();
+        }
+        assert!(cursor.is_null());
+        values
+    }
+
+    fn values_rev<C>(mut cursor: C) -> Vec<u32>
+    where
+        C: DoubleEndedCursorOps,
+        <C::Adapter as Adapter>::PointerOps: PointerOps<Value = Obj>,
+    {
+        let mut values = Vec::new();
+        cursor.move_prev();
+        while let Some(x) = cursor.get() {
+            values.push(x.value);
+            cursor.move_prev();
+        }
+        values
+    }
+
+    // Removes the odd values from the cursor onwards and inserts 0 at the front.
+    fn edit<C>(mut cursor: C)
+    where
+        C: CursorMutOps,
+        <C::Adapter as Adapter>::PointerOps: PointerOps<Value = Obj, Pointer = UnsafeRef<Obj>>,
+    {
+        while let Some(x) = cursor.get() {
+            if x.value % 2 == 1 {
+                let removed = cursor.remove().unwrap();
+                assert_eq!(removed.value % 2, 1);
+                unsafe { drop(UnsafeRef::into_box(removed)) };
+            } else {
+                cursor.move_next();
+            }
+        }
+        assert!(cursor.remove().is_none());
+        cursor.insert_after(make_obj(0));
+    }
+
+    fn free<C>(mut cursor: C)
+    where
+        C: CursorMutOps,
+        <C::Adapter as Adapter>::PointerOps: PointerOps<Pointer = UnsafeRef<Obj>>,
+    {
+        cursor.move_next();
+        while let Some(x) = cursor.remove() {
+            unsafe { drop(UnsafeRef::into_box(x)) };
+        }
+    }
+
+    #[test]
+    fn test_lists() {
+        let mut l = LinkedList::new(LlAdapter::new());
+        let mut s = SinglyLinkedList::new(SllAdapter::new());
+        let mut x = XorLinkedList::new(XllAdapter::new());
+        for &value in &[1, 2, 3, 4, 5] {
+            l.push_back(make_obj(value));
+            s.push_front(make_obj(value));
+            x.push_back(make_obj(value));
+        }
+        assert_eq!(values(l.front()), [1, 2, 3, 4, 5]);
+        assert_eq!(values(s.front_mut()), [5, 4, 3, 2, 1]);
+        assert_eq!(values_rev(x.cursor()), [5, 4, 3, 2, 1]);
+
+        edit(l.front_mut());
+        edit(s.front_mut());
+        edit(x.front_mut());
+        assert_eq!(values(l.front()), [0, 2, 4]);
+        assert_eq!(values(s.front()), [0, 4, 2]);
+        assert_eq!(values_rev(x.cursor_mut()), [4, 2, 0]);
+
+        free(l.cursor_mut());
+        free(s.cursor_mut());
+        free(x.cursor_mut());
+        assert!(l.is_empty() && s.is_empty() && x.is_empty());
+    }
+
+    #[test]
+    fn test_rbtree() {
+        let mut t = RBTree::new(RbAdapter::new());
+        for &value in &[4, 1, 3, 2, 5] {
+            t.insert(make_obj(value));
+        }
+        assert_eq!(values(t.front()), [1, 2, 3, 4, 5]);
+        assert_eq!(values_rev(t.cursor()), [5, 4, 3, 2, 1]);
+
+        let mut cursor = t.front_mut();
+        cursor.move_next();
+        cursor.move_next();
+        edit(cursor);
+        assert_eq!(values(t.front()), [0, 1, 2, 4]);
+
+        free(t.cursor_mut());
+        assert!(t.is_empty());
+    }
+}
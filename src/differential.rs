@@ -0,0 +1,905 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Differential testing of collections against `std` containers.
+//!
+//! The types in this module wrap a collection together with an oracle, a
+//! `Vec` for lists and a `BTreeMap` for trees, and perform every operation
+//! on both. After each operation they check that the collection holds the
+//! same elements in the same order as the oracle, in both directions for
+//! doubly-linked lists, that the values it returned match, and that the
+//! cursor used by the operation is at the expected position. This makes
+//! them useful to test custom adapters, link types and pointer types: any
+//! disagreement panics with the operation which caused it.
+//!
+//! Elements are compared through a key extracted from them, which is given
+//! to the list wrappers as a function and taken from the `KeyAdapter` of
+//! trees. Elements with equal keys can't be told apart.
+//!
+//! Cursor operations apply to a cursor position kept by the wrapper, which
+//! starts at the null object and behaves like the cursors of the collection.
+//! A new cursor is moved to that position for every operation, which takes
+//! linear time.
+//!
+//! The `ops_interpreter` and `proptest` modules run their operations through
+//! these wrappers.
+//!
+//! # Examples
+//!
+//! ```
+//! use intrusive_collections::differential::DiffLinkedList;
+//! use intrusive_collections::{intrusive_adapter, LinkedListLink};
+//!
+//! struct Obj {
+//!     link: LinkedListLink,
+//!     value: u32,
+//! }
+//! intrusive_adapter!(ObjAdapter = Box<Obj>: Obj { link: LinkedListLink });
+//! let make = |value| Box::new(Obj { link: LinkedListLink::new(), value });
+//!
+//! let mut list = DiffLinkedList::new(ObjAdapter::new(), |obj: &Obj| obj.value);
+//! list.push_back(make(1));
+//! list.push_back(make(3));
+//! list.move_next();
+//! list.insert_after(make(2));
+//! assert_eq!(list.pop_back().map(|obj| obj.value), Some(3));
+//! assert_eq!(list.oracle(), [1, 2]);
+//! ```
+
+use core::fmt::Debug;
+use core::ops::{self, Deref};
+use std::collections::BTreeMap;
+use std::vec::Vec;
+
+use crate::linked_list::{LinkedList, LinkedListOps};
+use crate::pointer_ops::PointerOps;
+use crate::rbtree::{RBTree, RBTreeOps};
+use crate::singly_linked_list::{SinglyLinkedList, SinglyLinkedListOps};
+use crate::xor_linked_list::{XorLinkedList, XorLinkedListOps};
+use crate::{Adapter, Bound, KeyAdapter};
+
+// Creates a cursor on `$list` at the element at `$index`, or at the null
+// object.
+macro_rules! cursor_at {
+    ($list:expr, $index:expr) => {
+        match $index {
+            None => $list.cursor_mut(),
+            Some(i) => {
+                let mut cursor = $list.front_mut();
+                for _ in 0..i {
+                    cursor.move_next();
+                }
+                cursor
+            }
+        }
+    };
+}
+
+// Checks the element a cursor points to after an operation. This takes the
+// fields of a wrapper rather than the wrapper, since the cursor borrows its
+// collection.
+fn check_cursor<K: PartialEq + Debug>(
+    op: &str,
+    actual: Option<K>,
+    oracle: &[K],
+    index: Option<usize>,
+) {
+    assert_eq!(
+        actual.as_ref(),
+        index.map(|i| &oracle[i]),
+        "cursor after {}",
+        op
+    );
+}
+
+// The APIs of `LinkedList` and `XorLinkedList` are the same, so both
+// wrappers are generated from the same code.
+macro_rules! diff_list {
+    (
+        $(#[$attr:meta])*
+        $name:ident, $list_type:ident, $ops_trait:ident,
+        |$list:ident| $assert_valid:block
+    ) => {
+        $(#[$attr])*
+        pub struct $name<A: Adapter, K, F>
+        where
+            A::LinkOps: $ops_trait,
+        {
+            list: $list_type<A>,
+            oracle: Vec<K>,
+            cursor: Option<usize>,
+            get: F,
+        }
+
+        impl<A: Adapter, K, F> $name<A, K, F>
+        where
+            A::LinkOps: $ops_trait,
+            <A::PointerOps as PointerOps>::Pointer:
+                Deref<Target = <A::PointerOps as PointerOps>::Value>,
+            F: Fn(&<A::PointerOps as PointerOps>::Value) -> K,
+            K: PartialEq + Debug,
+        {
+            #[doc = concat!("Creates an empty `", stringify!($list_type), "` using `adapter`, ")]
+            /// whose elements are compared through the keys returned by
+            /// `get`.
+            #[inline]
+            pub fn new(adapter: A, get: F) -> Self {
+                $name {
+                    list: $list_type::new(adapter),
+                    oracle: Vec::new(),
+                    cursor: None,
+                    get,
+                }
+            }
+
+            /// Returns the collection.
+            #[inline]
+            pub fn list(&self) -> &$list_type<A> {
+                &self.list
+            }
+
+            /// Returns the keys of the elements which the collection should
+            /// hold, in order.
+            #[inline]
+            pub fn oracle(&self) -> &[K] {
+                &self.oracle
+            }
+
+            /// Returns the index of the element the cursor points to, or
+            /// `None` for the null object.
+            #[inline]
+            pub fn cursor_index(&self) -> Option<usize> {
+                self.cursor
+            }
+
+            /// Checks that the collection holds the elements of the oracle.
+            ///
+            /// This is done after every operation.
+            pub fn check(&self) {
+                let $list = &self.list;
+                assert_eq!(self.keys($list), self.oracle, "contents");
+                let mut reversed = $list.iter().rev().map(&self.get).collect::<Vec<_>>();
+                reversed.reverse();
+                assert_eq!(reversed, self.oracle, "reverse iteration");
+                $assert_valid
+            }
+
+            fn key(&self, val: &<A::PointerOps as PointerOps>::Pointer) -> K {
+                (self.get)(val)
+            }
+
+            fn keys(&self, list: &$list_type<A>) -> Vec<K> {
+                list.iter().map(&self.get).collect()
+            }
+
+            fn check_removed(
+                &self,
+                op: &str,
+                removed: &Option<<A::PointerOps as PointerOps>::Pointer>,
+                expected: Option<K>,
+            ) {
+                assert_eq!(removed.as_ref().map(|x| self.key(x)), expected, "{}", op);
+            }
+
+            /// Performs `push_front`.
+            pub fn push_front(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+                self.oracle.insert(0, self.key(&val));
+                self.cursor = self.cursor.map(|i| i + 1);
+                self.list.push_front(val);
+                self.check();
+            }
+
+            /// Performs `push_back`.
+            pub fn push_back(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+                self.oracle.push(self.key(&val));
+                self.list.push_back(val);
+                self.check();
+            }
+
+            /// Performs `pop_front`, which moves the cursor to the null
+            /// object if it points to the removed element.
+            pub fn pop_front(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+                let removed = self.list.pop_front();
+                let expected = if self.oracle.is_empty() {
+                    None
+                } else {
+                    Some(self.oracle.remove(0))
+                };
+                self.check_removed("pop_front", &removed, expected);
+                self.cursor = match self.cursor {
+                    Some(0) => None,
+                    cursor => cursor.map(|i| i - 1),
+                };
+                self.check();
+                removed
+            }
+
+            /// Performs `pop_back`, which moves the cursor to the null
+            /// object if it points to the removed element.
+            pub fn pop_back(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+                let removed = self.list.pop_back();
+                let expected = self.oracle.pop();
+                self.check_removed("pop_back", &removed, expected);
+                if self.cursor == Some(self.oracle.len()) {
+                    self.cursor = None;
+                }
+                self.check();
+                removed
+            }
+
+            /// Performs `clear`, which moves the cursor to the null object.
+            pub fn clear(&mut self) {
+                self.list.clear();
+                self.oracle.clear();
+                self.cursor = None;
+                self.check();
+            }
+
+            /// Performs `CursorMut::move_next`.
+            pub fn move_next(&mut self) {
+                let mut cursor = cursor_at!(self.list, self.cursor);
+                cursor.move_next();
+                self.cursor = match self.cursor {
+                    None if !self.oracle.is_empty() => Some(0),
+                    Some(i) if i + 1 < self.oracle.len() => Some(i + 1),
+                    _ => None,
+                };
+                check_cursor("move_next", cursor.get().map(&self.get), &self.oracle, self.cursor);
+            }
+
+            /// Performs `CursorMut::move_prev`.
+            pub fn move_prev(&mut self) {
+                let mut cursor = cursor_at!(self.list, self.cursor);
+                cursor.move_prev();
+                self.cursor = match self.cursor {
+                    None => self.oracle.len().checked_sub(1),
+                    Some(i) => i.checked_sub(1),
+                };
+                check_cursor("move_prev", cursor.get().map(&self.get), &self.oracle, self.cursor);
+            }
+
+            /// Performs `CursorMut::insert_after`.
+            pub fn insert_after(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+                let index = self.cursor.map_or(0, |i| i + 1);
+                self.oracle.insert(index, self.key(&val));
+                let mut cursor = cursor_at!(self.list, self.cursor);
+                cursor.insert_after(val);
+                check_cursor("insert_after", cursor.get().map(&self.get), &self.oracle, self.cursor);
+                self.check();
+            }
+
+            /// Performs `CursorMut::insert_before`.
+            pub fn insert_before(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+                let key = self.key(&val);
+                let mut cursor = cursor_at!(self.list, self.cursor);
+                cursor.insert_before(val);
+                match self.cursor {
+                    None => self.oracle.push(key),
+                    Some(i) => {
+                        self.oracle.insert(i, key);
+                        self.cursor = Some(i + 1);
+                    }
+                }
+                check_cursor("insert_before", cursor.get().map(&self.get), &self.oracle, self.cursor);
+                self.check();
+            }
+
+            /// Performs `CursorMut::remove`.
+            pub fn remove(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+                let mut cursor = cursor_at!(self.list, self.cursor);
+                let removed = cursor.remove();
+                let oracle = &mut self.oracle;
+                let expected = self.cursor.map(|i| oracle.remove(i));
+                if self.cursor == Some(self.oracle.len()) {
+                    self.cursor = None;
+                }
+                check_cursor("remove", cursor.get().map(&self.get), &self.oracle, self.cursor);
+                self.check_removed("remove", &removed, expected);
+                self.check();
+                removed
+            }
+
+            /// Performs `CursorMut::splice_after`.
+            pub fn splice_after(&mut self, list: $list_type<A>) {
+                let keys = self.keys(&list);
+                let index = self.cursor.map_or(0, |i| i + 1);
+                let mut cursor = cursor_at!(self.list, self.cursor);
+                cursor.splice_after(list);
+                self.oracle.splice(index..index, keys);
+                check_cursor("splice_after", cursor.get().map(&self.get), &self.oracle, self.cursor);
+                self.check();
+            }
+
+            /// Performs `CursorMut::splice_before`.
+            pub fn splice_before(&mut self, list: $list_type<A>) {
+                let keys = self.keys(&list);
+                let mut cursor = cursor_at!(self.list, self.cursor);
+                cursor.splice_before(list);
+                match self.cursor {
+                    None => self.oracle.extend(keys),
+                    Some(i) => {
+                        self.cursor = Some(i + keys.len());
+                        self.oracle.splice(i..i, keys);
+                    }
+                }
+                check_cursor("splice_before", cursor.get().map(&self.get), &self.oracle, self.cursor);
+                self.check();
+            }
+
+            /// Performs `CursorMut::split_after`.
+            pub fn split_after(&mut self) -> $list_type<A>
+            where
+                A: Clone,
+            {
+                let index = self.cursor.map_or(0, |i| i + 1);
+                let expected = self.oracle.split_off(index);
+                let mut cursor = cursor_at!(self.list, self.cursor);
+                let split = cursor.split_after();
+                check_cursor("split_after", cursor.get().map(&self.get), &self.oracle, self.cursor);
+                assert_eq!(self.keys(&split), expected, "split_after");
+                self.check();
+                split
+            }
+
+            /// Performs `CursorMut::split_before`.
+            pub fn split_before(&mut self) -> $list_type<A>
+            where
+                A: Clone,
+            {
+                let index = self.cursor.unwrap_or(self.oracle.len());
+                let rest = self.oracle.split_off(index);
+                let expected = core::mem::replace(&mut self.oracle, rest);
+                let mut cursor = cursor_at!(self.list, self.cursor);
+                let split = cursor.split_before();
+                self.cursor = self.cursor.map(|_| 0);
+                check_cursor("split_before", cursor.get().map(&self.get), &self.oracle, self.cursor);
+                assert_eq!(self.keys(&split), expected, "split_before");
+                self.check();
+                split
+            }
+        }
+    };
+}
+
+diff_list!(
+    /// A `LinkedList` checked against a `Vec` after every operation.
+    ///
+    /// The links of the list are also checked with `assert_valid`.
+    DiffLinkedList, LinkedList, LinkedListOps,
+    |list| {
+        list.assert_valid();
+    }
+);
+
+diff_list!(
+    /// An `XorLinkedList` checked against a `Vec` after every operation.
+    DiffXorLinkedList, XorLinkedList, XorLinkedListOps,
+    |list| {}
+);
+
+// =============================================================================
+// DiffSinglyLinkedList
+// =============================================================================
+
+/// A `SinglyLinkedList` checked against a `Vec` after every operation.
+pub struct DiffSinglyLinkedList<A: Adapter, K, F>
+where
+    A::LinkOps: SinglyLinkedListOps,
+{
+    list: SinglyLinkedList<A>,
+    oracle: Vec<K>,
+    cursor: Option<usize>,
+    get: F,
+}
+
+impl<A: Adapter, K, F> DiffSinglyLinkedList<A, K, F>
+where
+    A::LinkOps: SinglyLinkedListOps,
+    <A::PointerOps as PointerOps>::Pointer: Deref<Target = <A::PointerOps as PointerOps>::Value>,
+    F: Fn(&<A::PointerOps as PointerOps>::Value) -> K,
+    K: PartialEq + Debug,
+{
+    /// Creates an empty `SinglyLinkedList` using `adapter`, whose elements
+    /// are compared through the keys returned by `get`.
+    #[inline]
+    pub fn new(adapter: A, get: F) -> Self {
+        DiffSinglyLinkedList {
+            list: SinglyLinkedList::new(adapter),
+            oracle: Vec::new(),
+            cursor: None,
+            get,
+        }
+    }
+
+    /// Returns the collection.
+    #[inline]
+    pub fn list(&self) -> &SinglyLinkedList<A> {
+        &self.list
+    }
+
+    /// Returns the keys of the elements which the collection should hold, in
+    /// order.
+    #[inline]
+    pub fn oracle(&self) -> &[K] {
+        &self.oracle
+    }
+
+    /// Returns the index of the element the cursor points to, or `None` for
+    /// the null object.
+    #[inline]
+    pub fn cursor_index(&self) -> Option<usize> {
+        self.cursor
+    }
+
+    /// Checks that the collection holds the elements of the oracle.
+    ///
+    /// This is done after every operation.
+    pub fn check(&self) {
+        assert_eq!(self.keys(&self.list), self.oracle, "contents");
+    }
+
+    fn key(&self, val: &<A::PointerOps as PointerOps>::Pointer) -> K {
+        (self.get)(val)
+    }
+
+    fn keys(&self, list: &SinglyLinkedList<A>) -> Vec<K> {
+        list.iter().map(&self.get).collect()
+    }
+
+    fn check_removed(
+        &self,
+        op: &str,
+        removed: &Option<<A::PointerOps as PointerOps>::Pointer>,
+        expected: Option<K>,
+    ) {
+        assert_eq!(removed.as_ref().map(|x| self.key(x)), expected, "{}", op);
+    }
+
+    // Index of the first element after the cursor, which is the front of the
+    // list if it is null.
+    fn next_index(&self) -> usize {
+        self.cursor.map_or(0, |i| i + 1)
+    }
+
+    /// Performs `push_front`.
+    pub fn push_front(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        self.oracle.insert(0, self.key(&val));
+        self.cursor = self.cursor.map(|i| i + 1);
+        self.list.push_front(val);
+        self.check();
+    }
+
+    /// Performs `pop_front`, which moves the cursor to the null object if it
+    /// points to the removed element.
+    pub fn pop_front(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        let removed = self.list.pop_front();
+        let expected = if self.oracle.is_empty() {
+            None
+        } else {
+            Some(self.oracle.remove(0))
+        };
+        self.check_removed("pop_front", &removed, expected);
+        self.cursor = match self.cursor {
+            Some(0) => None,
+            cursor => cursor.map(|i| i - 1),
+        };
+        self.check();
+        removed
+    }
+
+    /// Performs `clear`, which moves the cursor to the null object.
+    pub fn clear(&mut self) {
+        self.list.clear();
+        self.oracle.clear();
+        self.cursor = None;
+        self.check();
+    }
+
+    /// Performs `CursorMut::move_next`.
+    pub fn move_next(&mut self) {
+        let mut cursor = cursor_at!(self.list, self.cursor);
+        cursor.move_next();
+        self.cursor = match self.cursor {
+            None if !self.oracle.is_empty() => Some(0),
+            Some(i) if i + 1 < self.oracle.len() => Some(i + 1),
+            _ => None,
+        };
+        check_cursor(
+            "move_next",
+            cursor.get().map(&self.get),
+            &self.oracle,
+            self.cursor,
+        );
+    }
+
+    /// Performs `CursorMut::insert_after`.
+    pub fn insert_after(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        self.oracle.insert(self.next_index(), self.key(&val));
+        let mut cursor = cursor_at!(self.list, self.cursor);
+        cursor.insert_after(val);
+        check_cursor(
+            "insert_after",
+            cursor.get().map(&self.get),
+            &self.oracle,
+            self.cursor,
+        );
+        self.check();
+    }
+
+    /// Performs `CursorMut::remove_next`.
+    pub fn remove_next(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        let index = self.next_index();
+        let mut cursor = cursor_at!(self.list, self.cursor);
+        let removed = cursor.remove_next();
+        let expected = if index < self.oracle.len() {
+            Some(self.oracle.remove(index))
+        } else {
+            None
+        };
+        check_cursor(
+            "remove_next",
+            cursor.get().map(&self.get),
+            &self.oracle,
+            self.cursor,
+        );
+        self.check_removed("remove_next", &removed, expected);
+        self.check();
+        removed
+    }
+
+    /// Performs `CursorMut::splice_after`.
+    pub fn splice_after(&mut self, list: SinglyLinkedList<A>) {
+        let keys = self.keys(&list);
+        let index = self.next_index();
+        let mut cursor = cursor_at!(self.list, self.cursor);
+        cursor.splice_after(list);
+        self.oracle.splice(index..index, keys);
+        check_cursor(
+            "splice_after",
+            cursor.get().map(&self.get),
+            &self.oracle,
+            self.cursor,
+        );
+        self.check();
+    }
+
+    /// Performs `CursorMut::split_after`.
+    pub fn split_after(&mut self) -> SinglyLinkedList<A>
+    where
+        A: Clone,
+    {
+        let expected = self.oracle.split_off(self.next_index());
+        let mut cursor = cursor_at!(self.list, self.cursor);
+        let split = cursor.split_after();
+        check_cursor(
+            "split_after",
+            cursor.get().map(&self.get),
+            &self.oracle,
+            self.cursor,
+        );
+        assert_eq!(self.keys(&split), expected, "split_after");
+        self.check();
+        split
+    }
+}
+
+// =============================================================================
+// DiffRBTree
+// =============================================================================
+
+/// An `RBTree` checked against a `BTreeMap` counting the elements with each
+/// key after every operation.
+///
+/// The keys of the elements are those returned by the `KeyAdapter` of the
+/// tree, so the adapter is cloned to read them. The links of the tree are
+/// also checked with `assert_valid`.
+///
+/// The cursor position is the index of an element in the order of the
+/// tree. Since elements with equal keys can't be told apart, the index of an
+/// element which is inserted or removed by key is looked up by address.
+pub struct DiffRBTree<A: Adapter, K>
+where
+    A::LinkOps: RBTreeOps,
+{
+    tree: RBTree<A>,
+    oracle: BTreeMap<K, usize>,
+    cursor: Option<usize>,
+    adapter: A,
+}
+
+impl<A, K> DiffRBTree<A, K>
+where
+    A: Adapter + Clone + for<'a> KeyAdapter<'a, Key = K>,
+    A::LinkOps: RBTreeOps,
+    <A::PointerOps as PointerOps>::Pointer: Deref<Target = <A::PointerOps as PointerOps>::Value>,
+    K: Ord + Clone + Debug,
+{
+    /// Creates an empty `RBTree` using `adapter`.
+    #[inline]
+    pub fn new(adapter: A) -> Self {
+        DiffRBTree {
+            tree: RBTree::new(adapter.clone()),
+            oracle: BTreeMap::new(),
+            cursor: None,
+            adapter,
+        }
+    }
+
+    /// Returns the collection.
+    #[inline]
+    pub fn tree(&self) -> &RBTree<A> {
+        &self.tree
+    }
+
+    /// Returns the number of elements the collection should hold with each
+    /// key.
+    #[inline]
+    pub fn oracle(&self) -> &BTreeMap<K, usize> {
+        &self.oracle
+    }
+
+    /// Returns the index of the element the cursor points to, or `None` for
+    /// the null object.
+    #[inline]
+    pub fn cursor_index(&self) -> Option<usize> {
+        self.cursor
+    }
+
+    // Returns the keys of the elements which the collection should hold, in
+    // order.
+    fn expected(&self) -> Vec<K> {
+        self.oracle
+            .iter()
+            .flat_map(|(k, &count)| core::iter::repeat(k.clone()).take(count))
+            .collect()
+    }
+
+    // Returns the index of the element at `ptr` in the tree.
+    fn index_of(&self, ptr: *const <A::PointerOps as PointerOps>::Value) -> usize {
+        self.tree
+            .iter()
+            .position(|value| core::ptr::eq(value, ptr))
+            .expect("element isn't in the tree")
+    }
+
+    /// Checks that the collection holds the elements of the oracle.
+    ///
+    /// This is done after every operation.
+    pub fn check(&self) {
+        let key = |value: &<A::PointerOps as PointerOps>::Value| self.adapter.get_key(value);
+        let expected = self
+            .oracle
+            .iter()
+            .flat_map(|(k, &count)| core::iter::repeat(k.clone()).take(count));
+        assert!(
+            self.tree.iter().map(key).eq(expected.clone()),
+            "contents: {:?}, expected {:?}",
+            self.tree.iter().map(key).collect::<Vec<_>>(),
+            self.oracle
+        );
+        assert!(
+            self.tree.iter().rev().map(key).eq(expected.clone().rev()),
+            "reverse iteration: {:?}, expected {:?}",
+            self.tree.iter().rev().map(key).collect::<Vec<_>>(),
+            self.oracle
+        );
+        let len = self.tree.assert_valid();
+        if let Some(i) = self.cursor {
+            assert!(i < len, "cursor at {} in a tree of {} elements", i, len);
+            let mut cursor = self.tree.front();
+            for _ in 0..i {
+                cursor.move_next();
+            }
+            assert!(
+                cursor.get().map(key) == expected.clone().nth(i),
+                "cursor at {}: {:?}, expected {:?}",
+                i,
+                cursor.get().map(key),
+                expected.clone().nth(i)
+            );
+        }
+    }
+
+    // Updates the oracle and the cursor after the element at `index` was
+    // removed.
+    fn removed(&mut self, op: &str, index: Option<usize>, removed: Option<K>, expected: Option<K>) {
+        assert_eq!(removed, expected, "{}", op);
+        if let Some(index) = index {
+            self.cursor = match self.cursor {
+                Some(i) if i == index => None,
+                Some(i) if i > index => Some(i - 1),
+                cursor => cursor,
+            };
+        }
+        if let Some(k) = removed {
+            let count = self
+                .oracle
+                .get_mut(&k)
+                .expect("removed a key which isn't in the tree");
+            *count -= 1;
+            if *count == 0 {
+                self.oracle.remove(&k);
+            }
+        }
+        self.check();
+    }
+
+    /// Performs `insert`.
+    pub fn insert(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        let key = self.adapter.get_key(&val);
+        let adapter = &self.adapter;
+        let cursor = self.tree.insert(val);
+        assert_eq!(
+            cursor.get().map(|x| adapter.get_key(x)),
+            Some(key.clone()),
+            "insert"
+        );
+        let ptr: *const _ = cursor.get().unwrap();
+        *self.oracle.entry(key).or_insert(0) += 1;
+        let index = self.index_of(ptr);
+        self.cursor = self.cursor.map(|i| if i >= index { i + 1 } else { i });
+        self.check();
+    }
+
+    /// Performs `find_mut` followed by `CursorMut::remove`.
+    pub fn remove(&mut self, key: &K) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        let index = self.tree.find(key).get().map(|x| self.index_of(x));
+        let removed = self.tree.find_mut(key).remove();
+        let expected = self.oracle.get(key).map(|_| key.clone());
+        let k = removed.as_ref().map(|x| self.adapter.get_key(x));
+        self.removed("remove", index, k, expected);
+        removed
+    }
+
+    /// Performs `front_mut` followed by `CursorMut::remove`.
+    pub fn pop_front(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        let removed = self.tree.front_mut().remove();
+        let expected = self.oracle.keys().next().cloned();
+        let k = removed.as_ref().map(|x| self.adapter.get_key(x));
+        self.removed("pop_front", Some(0), k, expected);
+        removed
+    }
+
+    /// Performs `back_mut` followed by `CursorMut::remove`.
+    pub fn pop_back(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        let removed = self.tree.back_mut().remove();
+        let expected = self.oracle.keys().next_back().cloned();
+        let k = removed.as_ref().map(|x| self.adapter.get_key(x));
+        let index = self.expected().len().checked_sub(1);
+        self.removed("pop_back", index, k, expected);
+        removed
+    }
+
+    /// Performs `CursorMut::move_next`.
+    pub fn move_next(&mut self) {
+        let expected = self.expected();
+        let from = self.cursor;
+        self.cursor = match self.cursor {
+            None if expected.is_empty() => None,
+            None => Some(0),
+            Some(i) if i + 1 < expected.len() => Some(i + 1),
+            Some(_) => None,
+        };
+        let adapter = &self.adapter;
+        let mut cursor = cursor_at!(self.tree, from);
+        cursor.move_next();
+        let actual = cursor.get().map(|x| adapter.get_key(x));
+        check_cursor("move_next", actual, &expected, self.cursor);
+        self.check();
+    }
+
+    /// Performs `CursorMut::move_prev`.
+    pub fn move_prev(&mut self) {
+        let expected = self.expected();
+        let from = self.cursor;
+        self.cursor = match self.cursor {
+            None => expected.len().checked_sub(1),
+            Some(i) => i.checked_sub(1),
+        };
+        let adapter = &self.adapter;
+        let mut cursor = cursor_at!(self.tree, from);
+        cursor.move_prev();
+        let actual = cursor.get().map(|x| adapter.get_key(x));
+        check_cursor("move_prev", actual, &expected, self.cursor);
+        self.check();
+    }
+
+    /// Performs `CursorMut::remove` at the cursor, which then points to the
+    /// next element.
+    pub fn remove_current(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        let mut expected = self.expected();
+        let index = self.cursor;
+        let adapter = &self.adapter;
+        let mut cursor = cursor_at!(self.tree, index);
+        let removed = cursor.remove();
+        let removed_key = index.map(|i| expected.remove(i));
+        let next = index.filter(|&i| i < expected.len());
+        let actual = cursor.get().map(|x| adapter.get_key(x));
+        check_cursor("remove_current", actual, &expected, next);
+        self.cursor = next;
+        let k = removed.as_ref().map(|x| self.adapter.get_key(x));
+        self.removed("remove_current", None, k, removed_key);
+        removed
+    }
+
+    /// Checks `find`, and `lower_bound` and `upper_bound` with inclusive and
+    /// exclusive bounds, for `key`.
+    pub fn check_key(&self, key: &K) {
+        let get = |value: &<A::PointerOps as PointerOps>::Value| self.adapter.get_key(value);
+        assert_eq!(
+            self.tree.find(key).get().map(get).as_ref(),
+            self.oracle.get_key_value(key).map(|(k, _)| k),
+            "find"
+        );
+        for &(bound, range_bound, name) in &[
+            (Bound::Included(key), ops::Bound::Included(key), "included"),
+            (Bound::Excluded(key), ops::Bound::Excluded(key), "excluded"),
+        ] {
+            assert_eq!(
+                self.tree.lower_bound(bound).get().map(get).as_ref(),
+                self.oracle
+                    .range((range_bound, ops::Bound::Unbounded))
+                    .next()
+                    .map(|(k, _)| k),
+                "lower_bound, {}",
+                name
+            );
+            assert_eq!(
+                self.tree.upper_bound(bound).get().map(get).as_ref(),
+                self.oracle
+                    .range((ops::Bound::Unbounded, range_bound))
+                    .next_back()
+                    .map(|(k, _)| k),
+                "upper_bound, {}",
+                name
+            );
+        }
+    }
+
+    /// Performs `clear`.
+    pub fn clear(&mut self) {
+        self.tree.clear();
+        self.oracle.clear();
+        self.cursor = None;
+        self.check();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DiffLinkedList;
+    use crate::LinkedListLink;
+    use core::cell::Cell;
+    use std::rc::Rc;
+
+    struct Obj {
+        link: LinkedListLink,
+        value: Cell<u32>,
+    }
+    intrusive_adapter!(ObjAdapter = Rc<Obj>: Obj { link: LinkedListLink });
+
+    fn make_obj(value: u32) -> Rc<Obj> {
+        Rc::new(Obj {
+            link: LinkedListLink::new(),
+            value: Cell::new(value),
+        })
+    }
+
+    #[test]
+    #[should_panic(expected = "contents")]
+    fn test_mismatch() {
+        let mut list = DiffLinkedList::new(ObjAdapter::new(), |obj: &Obj| obj.value.get());
+        let a = make_obj(1);
+        list.push_back(a.clone());
+        list.push_back(make_obj(2));
+        assert_eq!(list.oracle(), [1, 2]);
+
+        // The oracle still holds the old key of `a`.
+        a.value.set(3);
+        list.push_back(make_obj(4));
+    }
+}
@@ -0,0 +1,160 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+// Helpers shared by the `dump` methods of the lists, which write the chain of
+// elements of a list in a line-based format:
+//
+//     LinkedList<ObjAdapter> "timers": head 0x1000, tail 0x1080
+//       #0 0x1000: prev null, next 0x1040
+//       #1 0x1040: prev 0x1000, next 0x1080
+//       #2 0x1080: prev 0x1060, next null
+//       ! prev is 0x1060, but the previous element is 0x1040
+//     3 elements, 1 problem found
+//
+// Problems found while walking the list are written on lines starting with
+// `!`, right after the element they were found on.
+
+use core::fmt;
+
+// Writes the `Debug` representation of a value after the links of its
+// element.
+pub(crate) type WriteValue<T> = fn(&T, &mut dyn fmt::Write) -> fmt::Result;
+
+// Address of a value in a dump, or `null` for the null object.
+pub(crate) struct Addr<T: ?Sized>(pub(crate) Option<*const T>);
+
+impl<T: ?Sized> fmt::Display for Addr<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(ptr) => write!(f, "{:p}", ptr),
+            None => f.write_str("null"),
+        }
+    }
+}
+
+// Finds a cycle in the chain of positions starting at `start` with Brent's
+// algorithm, which doesn't need to remember the positions already visited.
+// Returns the index of the first position of the cycle and the length of the
+// cycle, or `None` if the chain ends.
+pub(crate) fn find_cycle<P: Copy + PartialEq>(
+    start: Option<P>,
+    mut next: impl FnMut(P) -> Option<P>,
+) -> Option<(usize, usize)> {
+    let mut power = 1;
+    let mut len = 1;
+    let mut tortoise = start?;
+    let mut hare = next(tortoise)?;
+    while tortoise != hare {
+        if power == len {
+            tortoise = hare;
+            power *= 2;
+            len = 0;
+        }
+        hare = next(hare)?;
+        len += 1;
+    }
+
+    // Walk a second time with the hare `len` positions ahead, so that they
+    // meet at the first position of the cycle.
+    let mut first = 0;
+    let mut tortoise = start?;
+    let mut hare = tortoise;
+    for _ in 0..len {
+        hare = next(hare)?;
+    }
+    while tortoise != hare {
+        tortoise = next(tortoise)?;
+        hare = next(hare)?;
+        first += 1;
+    }
+    Some((first, len))
+}
+
+// Writes the name of the list, of its adapter and the name set with
+// `set_debug_name`, up to the colon before its head.
+pub(crate) fn write_header(
+    out: &mut dyn fmt::Write,
+    list: &str,
+    adapter: &str,
+    debug_name: Option<&str>,
+) -> fmt::Result {
+    write!(out, "{}<{}>", list, adapter)?;
+    if let Some(name) = debug_name {
+        write!(out, " {:?}", name)?;
+    }
+    out.write_str(":")
+}
+
+// Writes the element at `index`, which is followed by the description of its
+// links and its value.
+pub(crate) fn write_element<T: ?Sized>(
+    out: &mut dyn fmt::Write,
+    index: usize,
+    value: *const T,
+) -> fmt::Result {
+    write!(out, "  #{} {}:", index, Addr(Some(value)))
+}
+
+// Ends the line of an element, with its value if `write_value` is set.
+pub(crate) unsafe fn write_value<T: ?Sized>(
+    out: &mut dyn fmt::Write,
+    value: *const T,
+    write_value: Option<WriteValue<T>>,
+) -> fmt::Result {
+    if let Some(write_value) = write_value {
+        out.write_str(", value ")?;
+        write_value(&*value, out)?;
+    }
+    out.write_str("\n")
+}
+
+// Writes a problem found on the last element written.
+pub(crate) fn write_problem(
+    out: &mut dyn fmt::Write,
+    problems: &mut usize,
+    args: fmt::Arguments<'_>,
+) -> fmt::Result {
+    *problems += 1;
+    writeln!(out, "  ! {}", args)
+}
+
+// Writes the last line of the dump.
+pub(crate) fn write_summary(out: &mut dyn fmt::Write, len: usize, problems: usize) -> fmt::Result {
+    let plural = |n: usize| if n == 1 { "" } else { "s" };
+    write!(out, "{} element{}, ", len, plural(len))?;
+    match problems {
+        0 => writeln!(out, "no problems found"),
+        n => writeln!(out, "{} problem{} found", n, plural(n)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::find_cycle;
+
+    #[test]
+    fn test_find_cycle() {
+        // Positions 0..n, where the last one links back to `back`.
+        let chain = |n: usize, back: Option<usize>| {
+            move |i: usize| if i + 1 < n { Some(i + 1) } else { back }
+        };
+        assert_eq!(find_cycle(None, chain(0, None)), None);
+        assert_eq!(find_cycle(Some(0), chain(1, None)), None);
+        assert_eq!(find_cycle(Some(0), chain(10, None)), None);
+        assert_eq!(find_cycle(Some(0), chain(1, Some(0))), Some((0, 1)));
+        assert_eq!(find_cycle(Some(0), chain(10, Some(0))), Some((0, 10)));
+        assert_eq!(find_cycle(Some(0), chain(10, Some(9))), Some((9, 1)));
+        for n in 1..20 {
+            for back in 0..n {
+                assert_eq!(
+                    find_cycle(Some(0), chain(n, Some(back))),
+                    Some((back, n - back))
+                );
+            }
+        }
+    }
+}
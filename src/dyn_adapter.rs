@@ -0,0 +1,395 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use core::cell::Cell;
+use core::fmt;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+
+use crate::adapter::Adapter;
+use crate::link_ops::{DefaultLinkOps, LinkFieldOps, LinkOps};
+use crate::pointer_ops::{DefaultPointerOps, PointerOps};
+
+/// A link which also remembers the full pointer to the value containing it.
+///
+/// A plain link only allows getting back to its value if the value is
+/// `Sized`, since a link pointer carries no metadata. `DynLink` stores the
+/// (possibly fat) value pointer next to the link when the value is inserted
+/// into a collection, which allows collections of unsized values such as
+/// `Box<dyn Trait>` to be built with `DynAdapter`.
+///
+/// The layout is `#[repr(C)]` with the link as the first field, so a pointer
+/// to the link is also a pointer to the `DynLink`.
+#[repr(C)]
+pub struct DynLink<L, T: ?Sized> {
+    link: L,
+    value: Cell<Option<NonNull<T>>>,
+}
+
+impl<L, T: ?Sized> DynLink<L, T> {
+    /// Creates a new `DynLink` wrapping the given link.
+    #[inline]
+    pub const fn new(link: L) -> DynLink<L, T> {
+        DynLink {
+            link,
+            value: Cell::new(None),
+        }
+    }
+
+    /// Returns a reference to the underlying link.
+    #[inline]
+    pub fn link(&self) -> &L {
+        &self.link
+    }
+}
+
+// The value pointer only ever points back to the object containing the link,
+// so it doesn't affect whether that object can be sent to another thread.
+unsafe impl<L: Send, T: ?Sized> Send for DynLink<L, T> {}
+
+impl<L: Default, T: ?Sized> Default for DynLink<L, T> {
+    #[inline]
+    fn default() -> DynLink<L, T> {
+        DynLink::new(L::default())
+    }
+}
+
+// Like the other links, a clone starts out unlinked.
+impl<L: Default, T: ?Sized> Clone for DynLink<L, T> {
+    #[inline]
+    fn clone(&self) -> DynLink<L, T> {
+        DynLink::default()
+    }
+}
+
+impl<L: fmt::Debug, T: ?Sized> fmt::Debug for DynLink<L, T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.link, f)
+    }
+}
+
+/// Trait for (possibly unsized) values which contain a `DynLink`.
+///
+/// This is typically implemented for a trait object type, by forwarding to
+/// a method of the trait which returns the link of the implementing type.
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::{DynAdapter, DynLink, HasDynLink};
+/// use intrusive_collections::{LinkedList, LinkedListLink};
+///
+/// trait Handler {
+///     fn link(&self) -> &DynLink<LinkedListLink, dyn Handler>;
+///     fn handle(&self) -> u32;
+/// }
+///
+/// unsafe impl HasDynLink<LinkedListLink> for dyn Handler {
+///     fn dyn_link(&self) -> &DynLink<LinkedListLink, dyn Handler> {
+///         self.link()
+///     }
+/// }
+///
+/// #[derive(Default)]
+/// struct Fixed {
+///     link: DynLink<LinkedListLink, dyn Handler>,
+/// }
+/// impl Handler for Fixed {
+///     fn link(&self) -> &DynLink<LinkedListLink, dyn Handler> { &self.link }
+///     fn handle(&self) -> u32 { 1 }
+/// }
+///
+/// #[derive(Default)]
+/// struct Counter {
+///     link: DynLink<LinkedListLink, dyn Handler>,
+///     count: u32,
+/// }
+/// impl Handler for Counter {
+///     fn link(&self) -> &DynLink<LinkedListLink, dyn Handler> { &self.link }
+///     fn handle(&self) -> u32 { self.count }
+/// }
+///
+/// let mut list = LinkedList::new(DynAdapter::<LinkedListLink, Box<dyn Handler>>::new());
+/// list.push_back(Box::new(Fixed::default()));
+/// list.push_back(Box::new(Counter { count: 5, ..Default::default() }));
+/// assert_eq!(list.iter().map(|h| h.handle()).collect::<Vec<_>>(), [1, 5]);
+/// ```
+///
+/// # Safety
+///
+/// `dyn_link` must always return the same link, which must be located inside
+/// the object that `self` points to.
+pub unsafe trait HasDynLink<L> {
+    /// Returns the `DynLink` embedded in this value.
+    fn dyn_link(&self) -> &DynLink<L, Self>;
+}
+
+/// An adapter for values which contain a `DynLink`, which works for unsized
+/// values such as trait objects.
+///
+/// `L` is the link type wrapped by the `DynLink` and `P` is the owned pointer
+/// type, for example `Box<dyn Trait>`.
+pub struct DynAdapter<L, P>
+where
+    L: DefaultLinkOps,
+{
+    link_ops: L::Ops,
+    pointer_ops: DefaultPointerOps<P>,
+    _marker: PhantomData<L>,
+}
+
+impl<L, P> DynAdapter<L, P>
+where
+    L: DefaultLinkOps,
+{
+    /// Constructs an instance of `DynAdapter`.
+    pub const NEW: Self = DynAdapter {
+        link_ops: L::NEW,
+        pointer_ops: DefaultPointerOps::new(),
+        _marker: PhantomData,
+    };
+
+    /// Constructs an instance of `DynAdapter`.
+    #[inline]
+    pub const fn new() -> Self {
+        Self::NEW
+    }
+}
+
+impl<L, P> Default for DynAdapter<L, P>
+where
+    L: DefaultLinkOps,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::NEW
+    }
+}
+
+impl<L, P> Clone for DynAdapter<L, P>
+where
+    L: DefaultLinkOps,
+    L::Ops: Copy,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<L, P> Copy for DynAdapter<L, P>
+where
+    L: DefaultLinkOps,
+    L::Ops: Copy,
+{
+}
+
+unsafe impl<L: DefaultLinkOps, P> Send for DynAdapter<L, P> {}
+
+unsafe impl<L: DefaultLinkOps, P> Sync for DynAdapter<L, P> {}
+
+unsafe impl<L, P> Adapter for DynAdapter<L, P>
+where
+    L: DefaultLinkOps,
+    L::Ops: LinkFieldOps<L>,
+    DefaultPointerOps<P>: PointerOps,
+    <DefaultPointerOps<P> as PointerOps>::Value: HasDynLink<L>,
+{
+    type LinkOps = L::Ops;
+    type PointerOps = DefaultPointerOps<P>;
+
+    #[inline]
+    unsafe fn get_value(
+        &self,
+        link: <Self::LinkOps as LinkOps>::LinkPtr,
+    ) -> *const <Self::PointerOps as PointerOps>::Value {
+        let dyn_link = self.link_ops.link_field(link)
+            as *const DynLink<L, <Self::PointerOps as PointerOps>::Value>;
+        match (*dyn_link).value.get() {
+            Some(value) => value.as_ptr(),
+            None => unreachable!("DynLink used before being inserted into a collection"),
+        }
+    }
+
+    #[inline]
+    unsafe fn get_link(
+        &self,
+        value: *const <Self::PointerOps as PointerOps>::Value,
+    ) -> <Self::LinkOps as LinkOps>::LinkPtr {
+        let dyn_link = (*value).dyn_link();
+        dyn_link
+            .value
+            .set(Some(NonNull::new_unchecked(value as *mut _)));
+        self.link_ops.link_ptr(&dyn_link.link)
+    }
+
+    #[inline]
+    fn link_ops(&self) -> &Self::LinkOps {
+        &self.link_ops
+    }
+
+    #[inline]
+    fn link_ops_mut(&mut self) -> &mut Self::LinkOps {
+        &mut self.link_ops
+    }
+
+    #[inline]
+    fn pointer_ops(&self) -> &Self::PointerOps {
+        &self.pointer_ops
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DynAdapter, DynLink, HasDynLink};
+    use crate::{LinkedList, LinkedListLink, SinglyLinkedList, SinglyLinkedListLink};
+    use std::boxed::Box;
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    trait Shape {
+        fn link(&self) -> &DynLink<LinkedListLink, dyn Shape>;
+        fn area(&self) -> u32;
+    }
+
+    unsafe impl HasDynLink<LinkedListLink> for dyn Shape {
+        fn dyn_link(&self) -> &DynLink<LinkedListLink, dyn Shape> {
+            self.link()
+        }
+    }
+
+    struct Square {
+        link: DynLink<LinkedListLink, dyn Shape>,
+        side: u32,
+    }
+    impl Shape for Square {
+        fn link(&self) -> &DynLink<LinkedListLink, dyn Shape> {
+            &self.link
+        }
+        fn area(&self) -> u32 {
+            self.side * self.side
+        }
+    }
+
+    struct Rect {
+        width: u32,
+        height: u16,
+        link: DynLink<LinkedListLink, dyn Shape>,
+    }
+    impl Shape for Rect {
+        fn link(&self) -> &DynLink<LinkedListLink, dyn Shape> {
+            &self.link
+        }
+        fn area(&self) -> u32 {
+            self.width * self.height as u32
+        }
+    }
+
+    fn square(side: u32) -> Square {
+        Square {
+            link: DynLink::default(),
+            side,
+        }
+    }
+
+    fn rect(width: u32, height: u16) -> Rect {
+        Rect {
+            width,
+            height,
+            link: DynLink::default(),
+        }
+    }
+
+    #[test]
+    fn test_box_dyn() {
+        let mut list = LinkedList::new(DynAdapter::<LinkedListLink, Box<dyn Shape>>::new());
+        list.push_back(Box::new(square(2)));
+        list.push_back(Box::new(rect(2, 3)));
+        list.push_front(Box::new(square(3)));
+        assert_eq!(list.iter().map(|x| x.area()).collect::<Vec<_>>(), [9, 4, 6]);
+        assert!(list.front().get().unwrap().link().link().is_linked());
+
+        let front = list.pop_front().unwrap();
+        assert_eq!(front.area(), 9);
+        assert!(!front.link().link().is_linked());
+        assert_eq!(
+            list.iter().rev().map(|x| x.area()).collect::<Vec<_>>(),
+            [6, 4]
+        );
+    }
+
+    #[test]
+    fn test_rc_dyn_cursor_from_ptr() {
+        let a: Rc<dyn Shape> = Rc::new(square(1));
+        let b: Rc<dyn Shape> = Rc::new(rect(1, 2));
+        let mut list = LinkedList::new(DynAdapter::<LinkedListLink, Rc<dyn Shape>>::new());
+        list.push_back(a);
+        list.push_back(b.clone());
+        let cursor = unsafe { list.cursor_from_ptr(&*b) };
+        assert_eq!(cursor.get().unwrap().area(), 2);
+        assert_eq!(cursor.peek_prev().get().unwrap().area(), 1);
+    }
+
+    trait Named {
+        fn link(&self) -> &DynLink<SinglyLinkedListLink, dyn Named>;
+        fn list_link(&self) -> &DynLink<LinkedListLink, dyn Named>;
+        fn name(&self) -> &str;
+    }
+
+    unsafe impl HasDynLink<SinglyLinkedListLink> for dyn Named {
+        fn dyn_link(&self) -> &DynLink<SinglyLinkedListLink, dyn Named> {
+            self.link()
+        }
+    }
+
+    unsafe impl HasDynLink<LinkedListLink> for dyn Named {
+        fn dyn_link(&self) -> &DynLink<LinkedListLink, dyn Named> {
+            self.list_link()
+        }
+    }
+
+    #[derive(Default)]
+    struct Person {
+        link: DynLink<SinglyLinkedListLink, dyn Named>,
+        list_link: DynLink<LinkedListLink, dyn Named>,
+        name: &'static str,
+    }
+    impl Named for Person {
+        fn link(&self) -> &DynLink<SinglyLinkedListLink, dyn Named> {
+            &self.link
+        }
+        fn list_link(&self) -> &DynLink<LinkedListLink, dyn Named> {
+            &self.list_link
+        }
+        fn name(&self) -> &str {
+            self.name
+        }
+    }
+
+    #[test]
+    fn test_multiple_dyn_links() {
+        let mut a = SinglyLinkedList::new(DynAdapter::<SinglyLinkedListLink, Rc<dyn Named>>::new());
+        let mut b = LinkedList::new(DynAdapter::<LinkedListLink, Rc<dyn Named>>::new());
+        for &name in ["carol", "alice", "bob"].iter() {
+            let person: Rc<dyn Named> = Rc::new(Person {
+                name,
+                ..Default::default()
+            });
+            a.push_front(person.clone());
+            b.push_back(person);
+        }
+        assert_eq!(
+            a.iter().map(|x| x.name()).collect::<Vec<_>>(),
+            ["bob", "alice", "carol"]
+        );
+        assert_eq!(
+            b.iter().map(|x| x.name()).collect::<Vec<_>>(),
+            ["carol", "alice", "bob"]
+        );
+    }
+}
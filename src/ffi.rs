@@ -0,0 +1,679 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! C functions operating on Rust collections.
+//!
+//! This module exports `extern "C"` functions which let C code manipulate a
+//! `LinkedList` or an `RBTree` owned by Rust, as long as the collection uses
+//! the default link operations of its module (`linked_list::Link` or
+//! `rbtree::Link`). The functions are declared by the header written by
+//! `c_header::write_c_header` when this feature is enabled.
+//!
+//! Rust code lends a collection to C code with `LinkedList::with_c_handle` or
+//! `RBTree::with_c_handle`, which pass a pointer to a `#[repr(C)]` handle to a
+//! closure. The handle is only valid inside of that closure, and the
+//! collection is updated with the changes made through it once the closure
+//! returns:
+//!
+//! ```
+//! use intrusive_collections::ffi::{self, LinkedListHandle};
+//! use intrusive_collections::{intrusive_adapter, LinkedList, LinkedListLink, UnsafeRef};
+//!
+//! struct Obj {
+//!     link: LinkedListLink,
+//!     value: u32,
+//! }
+//! intrusive_adapter!(ObjAdapter = UnsafeRef<Obj>: Obj { link: LinkedListLink });
+//!
+//! // Stands in for a C function taking a `struct ic_linked_list *`.
+//! unsafe extern "C" fn c_push(list: *mut LinkedListHandle, obj: *mut Obj) {
+//!     ffi::ic_linked_list_push_back(list, &mut (*obj).link);
+//! }
+//!
+//! let obj = Box::into_raw(Box::new(Obj { link: LinkedListLink::new(), value: 1 }));
+//! let mut list = LinkedList::new(ObjAdapter::new());
+//! list.with_c_handle(|handle| unsafe { c_push(handle, obj) });
+//! assert_eq!(list.front().get().unwrap().value, 1);
+//! # list.fast_clear();
+//! # unsafe { drop(Box::from_raw(obj)) };
+//! ```
+//!
+//! C code must only pass links of objects which the adapter of the collection
+//! can convert back into pointers, since Rust code takes ownership of them
+//! when it removes them. The `on_insert` and `on_remove` hooks of the adapter
+//! aren't called for the objects inserted and removed by C code, and they
+//! aren't counted by the `stats` feature.
+//!
+//! Functions which insert a link return `false` instead of panicking when the
+//! link is already in a collection, since a panic can't unwind into C code.
+
+use core::ffi::c_void;
+use core::ptr::{self, NonNull};
+
+use crate::link_ops::LinkOps;
+use crate::linked_list::{self, LinkedListOps};
+use crate::rbtree::{self, RBTreeOps};
+
+// C declarations of the types and functions of this module, written in the
+// header by `c_header::write_c_header`.
+pub(crate) const C_DECLARATIONS: &str = "\
+#include <stdbool.h>
+
+struct ic_linked_list {
+    struct ic_linked_list_link *head;
+    struct ic_linked_list_link *tail;
+};
+
+bool ic_linked_list_link_is_linked(const struct ic_linked_list_link *link);
+bool ic_linked_list_is_empty(const struct ic_linked_list *list);
+struct ic_linked_list_link *ic_linked_list_front(const struct ic_linked_list *list);
+struct ic_linked_list_link *ic_linked_list_back(const struct ic_linked_list *list);
+struct ic_linked_list_link *ic_linked_list_next(const struct ic_linked_list_link *link);
+struct ic_linked_list_link *ic_linked_list_prev(const struct ic_linked_list_link *link);
+bool ic_linked_list_push_front(struct ic_linked_list *list, struct ic_linked_list_link *link);
+bool ic_linked_list_push_back(struct ic_linked_list *list, struct ic_linked_list_link *link);
+bool ic_linked_list_insert_after(struct ic_linked_list *list, struct ic_linked_list_link *pos, struct ic_linked_list_link *link);
+bool ic_linked_list_insert_before(struct ic_linked_list *list, struct ic_linked_list_link *pos, struct ic_linked_list_link *link);
+struct ic_linked_list_link *ic_linked_list_pop_front(struct ic_linked_list *list);
+struct ic_linked_list_link *ic_linked_list_pop_back(struct ic_linked_list *list);
+void ic_linked_list_remove(struct ic_linked_list *list, struct ic_linked_list_link *link);
+
+struct ic_rbtree {
+    struct ic_rbtree_link *root;
+};
+
+typedef int32_t (*ic_rbtree_cmp)(const void *key, const struct ic_rbtree_link *link, void *ctx);
+
+bool ic_rbtree_link_is_linked(const struct ic_rbtree_link *link);
+bool ic_rbtree_is_empty(const struct ic_rbtree *tree);
+struct ic_rbtree_link *ic_rbtree_front(const struct ic_rbtree *tree);
+struct ic_rbtree_link *ic_rbtree_back(const struct ic_rbtree *tree);
+struct ic_rbtree_link *ic_rbtree_next(const struct ic_rbtree_link *link);
+struct ic_rbtree_link *ic_rbtree_prev(const struct ic_rbtree_link *link);
+struct ic_rbtree_link *ic_rbtree_find(const struct ic_rbtree *tree, const void *key, ic_rbtree_cmp cmp, void *ctx);
+bool ic_rbtree_insert(struct ic_rbtree *tree, struct ic_rbtree_link *link, ic_rbtree_cmp cmp, void *ctx);
+void ic_rbtree_remove(struct ic_rbtree *tree, struct ic_rbtree_link *link);
+";
+
+#[inline]
+fn link_ptr<T>(link: Option<NonNull<T>>) -> *mut T {
+    link.map_or(ptr::null_mut(), NonNull::as_ptr)
+}
+
+// =============================================================================
+// LinkedList
+// =============================================================================
+
+/// Handle to a `LinkedList`, declared as `struct ic_linked_list` in C.
+///
+/// Handles are created by `LinkedList::with_c_handle`.
+#[repr(C)]
+#[derive(Debug)]
+pub struct LinkedListHandle {
+    pub(crate) head: Option<NonNull<linked_list::Link>>,
+    pub(crate) tail: Option<NonNull<linked_list::Link>>,
+}
+
+/// Returns whether `link` is in a list.
+///
+/// # Safety
+/// `link` must point to a valid link.
+#[no_mangle]
+pub unsafe extern "C" fn ic_linked_list_link_is_linked(link: *const linked_list::Link) -> bool {
+    (*link).is_linked()
+}
+
+/// Returns whether the list is empty.
+///
+/// # Safety
+/// `list` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn ic_linked_list_is_empty(list: *const LinkedListHandle) -> bool {
+    (*list).head.is_none()
+}
+
+/// Returns the first link of the list, or null if it is empty.
+///
+/// # Safety
+/// `list` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn ic_linked_list_front(
+    list: *const LinkedListHandle,
+) -> *mut linked_list::Link {
+    link_ptr((*list).head)
+}
+
+/// Returns the last link of the list, or null if it is empty.
+///
+/// # Safety
+/// `list` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn ic_linked_list_back(
+    list: *const LinkedListHandle,
+) -> *mut linked_list::Link {
+    link_ptr((*list).tail)
+}
+
+/// Returns the link after `link`, or null if it is the last one.
+///
+/// # Safety
+/// `link` must point to a link in a list.
+#[no_mangle]
+pub unsafe extern "C" fn ic_linked_list_next(
+    link: *const linked_list::Link,
+) -> *mut linked_list::Link {
+    link_ptr(linked_list::LinkOps.next(NonNull::from(&*link)))
+}
+
+/// Returns the link before `link`, or null if it is the first one.
+///
+/// # Safety
+/// `link` must point to a link in a list.
+#[no_mangle]
+pub unsafe extern "C" fn ic_linked_list_prev(
+    link: *const linked_list::Link,
+) -> *mut linked_list::Link {
+    link_ptr(linked_list::LinkOps.prev(NonNull::from(&*link)))
+}
+
+// Links `link` between `prev` and `next` in `list`, returning `false` if it
+// is already linked.
+unsafe fn linked_list_insert(
+    list: &mut LinkedListHandle,
+    link: *mut linked_list::Link,
+    prev: Option<NonNull<linked_list::Link>>,
+    next: Option<NonNull<linked_list::Link>>,
+) -> bool {
+    let mut link_ops = linked_list::LinkOps;
+    let link = NonNull::new_unchecked(link);
+    if !link_ops.acquire_link(link) {
+        return false;
+    }
+    linked_list::link_between(&mut link_ops, link, prev, next);
+    if prev.is_none() {
+        list.head = Some(link);
+    }
+    if next.is_none() {
+        list.tail = Some(link);
+    }
+    true
+}
+
+/// Inserts `link` at the front of the list. Returns `false` without doing
+/// anything if `link` is already in a list.
+///
+/// # Safety
+/// `list` must be a valid handle, and `link` must point to a link of an
+/// object which the adapter of the list can take ownership of.
+#[no_mangle]
+pub unsafe extern "C" fn ic_linked_list_push_front(
+    list: *mut LinkedListHandle,
+    link: *mut linked_list::Link,
+) -> bool {
+    let head = (*list).head;
+    linked_list_insert(&mut *list, link, None, head)
+}
+
+/// Inserts `link` at the back of the list. Returns `false` without doing
+/// anything if `link` is already in a list.
+///
+/// # Safety
+/// `list` must be a valid handle, and `link` must point to a link of an
+/// object which the adapter of the list can take ownership of.
+#[no_mangle]
+pub unsafe extern "C" fn ic_linked_list_push_back(
+    list: *mut LinkedListHandle,
+    link: *mut linked_list::Link,
+) -> bool {
+    let tail = (*list).tail;
+    linked_list_insert(&mut *list, link, tail, None)
+}
+
+/// Inserts `link` after `pos`. Returns `false` without doing anything if
+/// `link` is already in a list.
+///
+/// # Safety
+/// `list` must be a valid handle, `pos` must point to a link in that list,
+/// and `link` must point to a link of an object which the adapter of the list
+/// can take ownership of.
+#[no_mangle]
+pub unsafe extern "C" fn ic_linked_list_insert_after(
+    list: *mut LinkedListHandle,
+    pos: *mut linked_list::Link,
+    link: *mut linked_list::Link,
+) -> bool {
+    let pos = NonNull::new_unchecked(pos);
+    let next = linked_list::LinkOps.next(pos);
+    linked_list_insert(&mut *list, link, Some(pos), next)
+}
+
+/// Inserts `link` before `pos`. Returns `false` without doing anything if
+/// `link` is already in a list.
+///
+/// # Safety
+/// `list` must be a valid handle, `pos` must point to a link in that list,
+/// and `link` must point to a link of an object which the adapter of the list
+/// can take ownership of.
+#[no_mangle]
+pub unsafe extern "C" fn ic_linked_list_insert_before(
+    list: *mut LinkedListHandle,
+    pos: *mut linked_list::Link,
+    link: *mut linked_list::Link,
+) -> bool {
+    let pos = NonNull::new_unchecked(pos);
+    let prev = linked_list::LinkOps.prev(pos);
+    linked_list_insert(&mut *list, link, prev, Some(pos))
+}
+
+/// Removes `link` from the list.
+///
+/// # Safety
+/// `list` must be a valid handle and `link` must point to a link in that
+/// list.
+#[no_mangle]
+pub unsafe extern "C" fn ic_linked_list_remove(
+    list: *mut LinkedListHandle,
+    link: *mut linked_list::Link,
+) {
+    let list = &mut *list;
+    let mut link_ops = linked_list::LinkOps;
+    let link = NonNull::new_unchecked(link);
+    if list.head == Some(link) {
+        list.head = link_ops.next(link);
+    }
+    if list.tail == Some(link) {
+        list.tail = link_ops.prev(link);
+    }
+    linked_list::remove(&mut link_ops, link);
+}
+
+/// Removes the first link of the list and returns it, or returns null if the
+/// list is empty.
+///
+/// # Safety
+/// `list` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn ic_linked_list_pop_front(
+    list: *mut LinkedListHandle,
+) -> *mut linked_list::Link {
+    let head = link_ptr((*list).head);
+    if !head.is_null() {
+        ic_linked_list_remove(list, head);
+    }
+    head
+}
+
+/// Removes the last link of the list and returns it, or returns null if the
+/// list is empty.
+///
+/// # Safety
+/// `list` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn ic_linked_list_pop_back(
+    list: *mut LinkedListHandle,
+) -> *mut linked_list::Link {
+    let tail = link_ptr((*list).tail);
+    if !tail.is_null() {
+        ic_linked_list_remove(list, tail);
+    }
+    tail
+}
+
+// =============================================================================
+// RBTree
+// =============================================================================
+
+/// Handle to an `RBTree`, declared as `struct ic_rbtree` in C.
+///
+/// Handles are created by `RBTree::with_c_handle`.
+#[repr(C)]
+#[derive(Debug)]
+pub struct RBTreeHandle {
+    pub(crate) root: Option<NonNull<rbtree::Link>>,
+}
+
+/// Comparison function for the keys of an `RBTree`, declared as
+/// `ic_rbtree_cmp` in C.
+///
+/// Returns a negative value if `key` is less than the key of the object
+/// containing `link`, 0 if they are equal, and a positive value otherwise.
+/// `ctx` is passed through unchanged from the caller.
+pub type RBTreeCmp =
+    unsafe extern "C" fn(key: *const c_void, link: *const rbtree::Link, ctx: *mut c_void) -> i32;
+
+/// Returns whether `link` is in a tree.
+///
+/// # Safety
+/// `link` must point to a valid link.
+#[no_mangle]
+pub unsafe extern "C" fn ic_rbtree_link_is_linked(link: *const rbtree::Link) -> bool {
+    (*link).is_linked()
+}
+
+/// Returns whether the tree is empty.
+///
+/// # Safety
+/// `tree` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn ic_rbtree_is_empty(tree: *const RBTreeHandle) -> bool {
+    (*tree).root.is_none()
+}
+
+/// Returns the first link of the tree, or null if it is empty.
+///
+/// # Safety
+/// `tree` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn ic_rbtree_front(tree: *const RBTreeHandle) -> *mut rbtree::Link {
+    link_ptr(
+        (*tree)
+            .root
+            .map(|root| rbtree::first_child(&rbtree::LinkOps, root)),
+    )
+}
+
+/// Returns the last link of the tree, or null if it is empty.
+///
+/// # Safety
+/// `tree` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn ic_rbtree_back(tree: *const RBTreeHandle) -> *mut rbtree::Link {
+    link_ptr(
+        (*tree)
+            .root
+            .map(|root| rbtree::last_child(&rbtree::LinkOps, root)),
+    )
+}
+
+/// Returns the link after `link` in the order of the tree, or null if it is
+/// the last one.
+///
+/// # Safety
+/// `link` must point to a link in a tree.
+#[no_mangle]
+pub unsafe extern "C" fn ic_rbtree_next(link: *const rbtree::Link) -> *mut rbtree::Link {
+    link_ptr(rbtree::next(&rbtree::LinkOps, NonNull::from(&*link)))
+}
+
+/// Returns the link before `link` in the order of the tree, or null if it is
+/// the first one.
+///
+/// # Safety
+/// `link` must point to a link in a tree.
+#[no_mangle]
+pub unsafe extern "C" fn ic_rbtree_prev(link: *const rbtree::Link) -> *mut rbtree::Link {
+    link_ptr(rbtree::prev(&rbtree::LinkOps, NonNull::from(&*link)))
+}
+
+/// Returns a link whose key is equal to `key`, or null if there is none.
+///
+/// # Safety
+/// `tree` must be a valid handle, and `cmp` must order the links consistently
+/// with the `KeyAdapter` of the tree.
+#[no_mangle]
+pub unsafe extern "C" fn ic_rbtree_find(
+    tree: *const RBTreeHandle,
+    key: *const c_void,
+    cmp: RBTreeCmp,
+    ctx: *mut c_void,
+) -> *mut rbtree::Link {
+    let link_ops = rbtree::LinkOps;
+    let mut current = (*tree).root;
+    while let Some(x) = current {
+        let ord = cmp(key, x.as_ptr(), ctx);
+        if ord < 0 {
+            current = link_ops.left(x);
+        } else if ord > 0 {
+            current = link_ops.right(x);
+        } else {
+            return x.as_ptr();
+        }
+    }
+    ptr::null_mut()
+}
+
+/// Inserts `link` into the tree, after any links with an equal key. `cmp` is
+/// called with `link` itself as the key. Returns `false` without doing
+/// anything if `link` is already in a tree.
+///
+/// # Safety
+/// `tree` must be a valid handle, `link` must point to a link of an object
+/// which the adapter of the tree can take ownership of, and `cmp` must order
+/// the links consistently with the `KeyAdapter` of the tree.
+#[no_mangle]
+pub unsafe extern "C" fn ic_rbtree_insert(
+    tree: *mut RBTreeHandle,
+    link: *mut rbtree::Link,
+    cmp: RBTreeCmp,
+    ctx: *mut c_void,
+) -> bool {
+    let tree = &mut *tree;
+    let mut link_ops = rbtree::LinkOps;
+    let link = NonNull::new_unchecked(link);
+    if !link_ops.acquire_link(link) {
+        return false;
+    }
+    let mut parent = match tree.root {
+        Some(root) => root,
+        None => {
+            link_ops.set_parent(link, None);
+            link_ops.set_color(link, rbtree::Color::Black);
+            link_ops.set_left(link, None);
+            link_ops.set_right(link, None);
+            tree.root = Some(link);
+            return true;
+        }
+    };
+    loop {
+        if cmp(link.as_ptr() as *const c_void, parent.as_ptr(), ctx) < 0 {
+            match link_ops.left(parent) {
+                Some(left) => parent = left,
+                None => {
+                    rbtree::insert_left(&mut link_ops, parent, link, &mut tree.root);
+                    return true;
+                }
+            }
+        } else {
+            match link_ops.right(parent) {
+                Some(right) => parent = right,
+                None => {
+                    rbtree::insert_right(&mut link_ops, parent, link, &mut tree.root);
+                    return true;
+                }
+            }
+        }
+    }
+}
+
+/// Removes `link` from the tree.
+///
+/// # Safety
+/// `tree` must be a valid handle and `link` must point to a link in that
+/// tree.
+#[no_mangle]
+pub unsafe extern "C" fn ic_rbtree_remove(tree: *mut RBTreeHandle, link: *mut rbtree::Link) {
+    rbtree::remove(
+        &mut rbtree::LinkOps,
+        NonNull::new_unchecked(link),
+        &mut (*tree).root,
+    );
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{KeyAdapter, LinkedList, RBTree, UnsafeRef};
+    use std::boxed::Box;
+    use std::vec::Vec;
+
+    struct Obj {
+        list_link: linked_list::Link,
+        tree_link: rbtree::Link,
+        value: u32,
+    }
+    intrusive_adapter!(ListAdapter = UnsafeRef<Obj>: Obj { list_link: linked_list::Link });
+    intrusive_adapter!(TreeAdapter = UnsafeRef<Obj>: Obj { tree_link: rbtree::Link });
+    impl<'a> KeyAdapter<'a> for TreeAdapter {
+        type Key = u32;
+        fn get_key(&self, x: &'a Obj) -> u32 {
+            x.value
+        }
+    }
+
+    fn make_obj(value: u32) -> Box<Obj> {
+        Box::new(Obj {
+            list_link: linked_list::Link::new(),
+            tree_link: rbtree::Link::new(),
+            value,
+        })
+    }
+
+    fn list_obj(link: *mut linked_list::Link) -> u32 {
+        unsafe { (*container_of!(link, Obj, list_link)).value }
+    }
+
+    unsafe extern "C" fn cmp(key: *const c_void, link: *const rbtree::Link, _: *mut c_void) -> i32 {
+        let key = *(key as *const u32);
+        let value = (*container_of!(link, Obj, tree_link)).value;
+        key as i32 - value as i32
+    }
+
+    unsafe extern "C" fn cmp_links(
+        key: *const c_void,
+        link: *const rbtree::Link,
+        ctx: *mut c_void,
+    ) -> i32 {
+        let key = container_of!(key as *const rbtree::Link, Obj, tree_link);
+        cmp(&(*key).value as *const u32 as *const c_void, link, ctx)
+    }
+
+    #[test]
+    fn test_linked_list() {
+        let objs: Vec<_> = (0..4).map(make_obj).collect();
+        let mut list = LinkedList::new(ListAdapter::new());
+        list.push_back(unsafe { UnsafeRef::from_raw(&*objs[1]) });
+        let link = |i: usize| &objs[i].list_link as *const _ as *mut linked_list::Link;
+        list.with_c_handle(|handle| unsafe {
+            assert!(!ic_linked_list_is_empty(handle));
+            assert!(ic_linked_list_push_front(handle, link(0)));
+            assert!(ic_linked_list_push_back(handle, link(3)));
+            assert!(ic_linked_list_insert_after(handle, link(1), link(2)));
+            assert!(!ic_linked_list_push_back(handle, link(2)));
+            assert_eq!(list_obj(ic_linked_list_front(handle)), 0);
+            assert_eq!(list_obj(ic_linked_list_back(handle)), 3);
+            assert_eq!(list_obj(ic_linked_list_next(link(1))), 2);
+            assert_eq!(list_obj(ic_linked_list_prev(link(1))), 0);
+        });
+        assert_eq!(
+            list.iter().map(|x| x.value).collect::<Vec<_>>(),
+            [0, 1, 2, 3]
+        );
+        list.with_c_handle(|handle| unsafe {
+            assert_eq!(list_obj(ic_linked_list_pop_front(handle)), 0);
+            assert_eq!(list_obj(ic_linked_list_pop_back(handle)), 3);
+            ic_linked_list_remove(handle, link(2));
+            assert!(ic_linked_list_insert_before(handle, link(1), link(3)));
+            assert!(!ic_linked_list_link_is_linked(link(0)));
+        });
+        assert_eq!(list.iter().map(|x| x.value).collect::<Vec<_>>(), [3, 1]);
+        list.fast_clear();
+    }
+
+    #[test]
+    fn test_rbtree() {
+        let objs: Vec<_> = [5, 1, 3, 4, 2].iter().copied().map(make_obj).collect();
+        let mut tree = RBTree::new(TreeAdapter::new());
+        tree.insert(unsafe { UnsafeRef::from_raw(&*objs[0]) });
+        let link = |i: usize| &objs[i].tree_link as *const _ as *mut rbtree::Link;
+        let value =
+            |link: *mut rbtree::Link| unsafe { (*container_of!(link, Obj, tree_link)).value };
+        tree.with_c_handle(|handle| unsafe {
+            for i in 1..objs.len() {
+                assert!(ic_rbtree_insert(
+                    handle,
+                    link(i),
+                    cmp_links,
+                    ptr::null_mut()
+                ));
+            }
+            assert!(!ic_rbtree_insert(
+                handle,
+                link(1),
+                cmp_links,
+                ptr::null_mut()
+            ));
+            assert_eq!(value(ic_rbtree_front(handle)), 1);
+            assert_eq!(value(ic_rbtree_back(handle)), 5);
+            assert_eq!(value(ic_rbtree_next(link(2))), 4);
+            assert_eq!(value(ic_rbtree_prev(link(2))), 2);
+            let key = 4u32;
+            let found = ic_rbtree_find(
+                handle,
+                &key as *const u32 as *const c_void,
+                cmp,
+                ptr::null_mut(),
+            );
+            assert_eq!(found, link(3));
+            ic_rbtree_remove(handle, found);
+            assert!(!ic_rbtree_link_is_linked(link(3)));
+        });
+        assert_eq!(
+            tree.iter().map(|x| x.value).collect::<Vec<_>>(),
+            [1, 2, 3, 5]
+        );
+        assert!(!tree.find(&5).is_null());
+        tree.fast_clear();
+    }
+
+    #[test]
+    fn test_c_handle_panic() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let objs: Vec<_> = (0..2).map(make_obj).collect();
+        let mut list = LinkedList::new(ListAdapter::new());
+        let mut tree = RBTree::new(TreeAdapter::new());
+        let list_link = |i: usize| &objs[i].list_link as *const _ as *mut linked_list::Link;
+        let tree_link = |i: usize| &objs[i].tree_link as *const _ as *mut rbtree::Link;
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            list.with_c_handle(|handle| unsafe {
+                assert!(ic_linked_list_push_back(handle, list_link(0)));
+                assert!(ic_linked_list_push_back(handle, list_link(1)));
+                panic!("C callback failed");
+            })
+        }));
+        assert!(result.is_err());
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            tree.with_c_handle(|handle| unsafe {
+                assert!(ic_rbtree_insert(
+                    handle,
+                    tree_link(1),
+                    cmp_links,
+                    ptr::null_mut()
+                ));
+                panic!("C callback failed");
+            })
+        }));
+        assert!(result.is_err());
+        #[cfg(feature = "poison")]
+        {
+            list.clear_poison();
+            tree.clear_poison();
+        }
+
+        // The elements linked before the panic are in the collections.
+        assert_eq!(list.iter().map(|x| x.value).collect::<Vec<_>>(), [0, 1]);
+        assert_eq!(list.back().get().unwrap().value, 1);
+        assert_eq!(tree.iter().map(|x| x.value).collect::<Vec<_>>(), [1]);
+        assert!(!tree.find(&1).is_null());
+        list.fast_clear();
+        tree.fast_clear();
+    }
+}
@@ -0,0 +1,49 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use core::mem;
+
+use crate::link_ops::LinkOps;
+
+/// Memory used by a collection on top of the data of its elements, returned
+/// by the `memory_overhead` method of the collections.
+///
+/// This is meant for budgeting memory and comparing link types, for example
+/// `LinkedListLink` with `LinkedListAtomicLink` or `CachePadded` links. The
+/// link sizes don't include the padding that embedding a link may add to an
+/// object, which depends on its other fields.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MemoryOverhead {
+    /// Size in bytes of the link embedded in each element, or `None` if the
+    /// link operations don't provide it through `LinkOps::LINK_SIZE`.
+    pub link_bytes: Option<usize>,
+
+    /// Size in bytes of the collection itself, including its adapter and
+    /// any counters enabled by Cargo features.
+    pub collection_bytes: usize,
+
+    /// Number of elements in the collection.
+    pub len: usize,
+}
+
+impl MemoryOverhead {
+    #[inline]
+    pub(crate) fn new<L: LinkOps, C>(len: usize) -> MemoryOverhead {
+        MemoryOverhead {
+            link_bytes: L::LINK_SIZE,
+            collection_bytes: mem::size_of::<C>(),
+            len,
+        }
+    }
+
+    /// Returns the total number of bytes used by the collection and the
+    /// links of its elements, or `None` if the size of the links is unknown.
+    #[inline]
+    pub fn total_bytes(&self) -> Option<usize> {
+        Some(self.collection_bytes + self.link_bytes? * self.len)
+    }
+}
@@ -0,0 +1,483 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use core::borrow::Borrow;
+use core::fmt;
+
+use crate::adapter::Adapter;
+use crate::key_adapter::KeyAdapter;
+use crate::linked_list::{self, LinkedList, LinkedListOps};
+use crate::pointer_ops::PointerOps;
+use crate::rbtree::{self, RBTree, RBTreeOps};
+use crate::singly_linked_list::{self, SinglyLinkedList, SinglyLinkedListOps};
+use crate::xor_linked_list::{self, XorLinkedList, XorLinkedListOps};
+use crate::Bound;
+
+/// Read-only view of a collection.
+///
+/// A `Frozen` is a shared reference to a collection which only offers the
+/// methods that read it: there is no way of getting a `CursorMut` or of
+/// reaching the collection itself from it. It is `Copy`, and it is `Send`
+/// and `Sync` whenever the collection is `Sync`, for example when its
+/// elements use atomic links, which makes it a convenient way of handing an
+/// immutable snapshot of a collection to several threads.
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::{intrusive_adapter, Frozen, LinkedList, LinkedListAtomicLink};
+/// use std::thread;
+///
+/// struct Obj {
+///     link: LinkedListAtomicLink,
+///     value: u32,
+/// }
+/// intrusive_adapter!(ObjAdapter = Box<Obj>: Obj { link: LinkedListAtomicLink });
+///
+/// let mut list = LinkedList::new(ObjAdapter::new());
+/// for value in 1..=3 {
+///     list.push_back(Box::new(Obj { link: LinkedListAtomicLink::new(), value }));
+/// }
+///
+/// let frozen = Frozen::new(&list);
+/// thread::scope(|s| {
+///     for _ in 0..2 {
+///         s.spawn(move || assert_eq!(frozen.iter().map(|x| x.value).sum::<u32>(), 6));
+///     }
+/// });
+/// ```
+pub struct Frozen<'a, C: ?Sized> {
+    pub(crate) collection: &'a C,
+}
+
+impl<'a, C: ?Sized> Frozen<'a, C> {
+    /// Creates a read-only view of a collection.
+    #[inline]
+    pub const fn new(collection: &'a C) -> Frozen<'a, C> {
+        Frozen { collection }
+    }
+}
+
+impl<'a, C: ?Sized> From<&'a C> for Frozen<'a, C> {
+    #[inline]
+    fn from(collection: &'a C) -> Frozen<'a, C> {
+        Frozen::new(collection)
+    }
+}
+
+impl<C: ?Sized> Clone for Frozen<'_, C> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C: ?Sized> Copy for Frozen<'_, C> {}
+
+impl<C: ?Sized + fmt::Debug> fmt::Debug for Frozen<'_, C> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Frozen").field(&self.collection).finish()
+    }
+}
+
+// =============================================================================
+// LinkedList
+// =============================================================================
+
+impl<'a, A: Adapter> Frozen<'a, LinkedList<A>>
+where
+    A::LinkOps: LinkedListOps,
+{
+    /// Returns the debug name of the `LinkedList`, if any.
+    #[inline]
+    pub fn debug_name(&self) -> Option<&'static str> {
+        self.collection.debug_name()
+    }
+
+    /// Returns `true` if the `LinkedList` is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.collection.is_empty()
+    }
+
+    /// Returns a null `Cursor` for the `LinkedList`.
+    #[inline]
+    pub fn cursor(&self) -> linked_list::Cursor<'a, A> {
+        self.collection.cursor()
+    }
+
+    /// Creates a `Cursor` from a pointer to an element.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a pointer to an object that is part of the list.
+    #[inline]
+    pub unsafe fn cursor_from_ptr(
+        &self,
+        ptr: *const <A::PointerOps as PointerOps>::Value,
+    ) -> linked_list::Cursor<'a, A> {
+        self.collection.cursor_from_ptr(ptr)
+    }
+
+    /// Returns a `Cursor` pointing to the first element of the `LinkedList`.
+    /// If the list is empty then a null cursor is returned.
+    #[inline]
+    pub fn front(&self) -> linked_list::Cursor<'a, A> {
+        self.collection.front()
+    }
+
+    /// Returns a `Cursor` pointing to the last element of the `LinkedList`.
+    /// If the list is empty then a null cursor is returned.
+    #[inline]
+    pub fn back(&self) -> linked_list::Cursor<'a, A> {
+        self.collection.back()
+    }
+
+    /// Gets an iterator over the objects in the `LinkedList`.
+    #[inline]
+    pub fn iter(&self) -> linked_list::Iter<'a, A> {
+        self.collection.iter()
+    }
+}
+
+impl<'a, A: Adapter + 'a> IntoIterator for Frozen<'a, LinkedList<A>>
+where
+    A::LinkOps: LinkedListOps,
+{
+    type Item = &'a <A::PointerOps as PointerOps>::Value;
+    type IntoIter = linked_list::Iter<'a, A>;
+
+    #[inline]
+    fn into_iter(self) -> linked_list::Iter<'a, A> {
+        self.iter()
+    }
+}
+
+// =============================================================================
+// SinglyLinkedList
+// =============================================================================
+
+impl<'a, A: Adapter> Frozen<'a, SinglyLinkedList<A>>
+where
+    A::LinkOps: SinglyLinkedListOps,
+{
+    /// Returns the debug name of the `SinglyLinkedList`, if any.
+    #[inline]
+    pub fn debug_name(&self) -> Option<&'static str> {
+        self.collection.debug_name()
+    }
+
+    /// Returns `true` if the `SinglyLinkedList` is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.collection.is_empty()
+    }
+
+    /// Returns a null `Cursor` for the `SinglyLinkedList`.
+    #[inline]
+    pub fn cursor(&self) -> singly_linked_list::Cursor<'a, A> {
+        self.collection.cursor()
+    }
+
+    /// Creates a `Cursor` from a pointer to an element.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a pointer to an object that is part of the list.
+    #[inline]
+    pub unsafe fn cursor_from_ptr(
+        &self,
+        ptr: *const <A::PointerOps as PointerOps>::Value,
+    ) -> singly_linked_list::Cursor<'a, A> {
+        self.collection.cursor_from_ptr(ptr)
+    }
+
+    /// Returns a `Cursor` pointing to the first element of the
+    /// `SinglyLinkedList`. If the list is empty then a null cursor is
+    /// returned.
+    #[inline]
+    pub fn front(&self) -> singly_linked_list::Cursor<'a, A> {
+        self.collection.front()
+    }
+
+    /// Gets an iterator over the objects in the `SinglyLinkedList`.
+    #[inline]
+    pub fn iter(&self) -> singly_linked_list::Iter<'a, A> {
+        self.collection.iter()
+    }
+}
+
+impl<'a, A: Adapter + 'a> IntoIterator for Frozen<'a, SinglyLinkedList<A>>
+where
+    A::LinkOps: SinglyLinkedListOps,
+{
+    type Item = &'a <A::PointerOps as PointerOps>::Value;
+    type IntoIter = singly_linked_list::Iter<'a, A>;
+
+    #[inline]
+    fn into_iter(self) -> singly_linked_list::Iter<'a, A> {
+        self.iter()
+    }
+}
+
+// =============================================================================
+// XorLinkedList
+// =============================================================================
+
+impl<'a, A: Adapter> Frozen<'a, XorLinkedList<A>>
+where
+    A::LinkOps: XorLinkedListOps,
+{
+    /// Returns the debug name of the `XorLinkedList`, if any.
+    #[inline]
+    pub fn debug_name(&self) -> Option<&'static str> {
+        self.collection.debug_name()
+    }
+
+    /// Returns `true` if the `XorLinkedList` is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.collection.is_empty()
+    }
+
+    /// Returns a null `Cursor` for the `XorLinkedList`.
+    #[inline]
+    pub fn cursor(&self) -> xor_linked_list::Cursor<'a, A> {
+        self.collection.cursor()
+    }
+
+    /// Returns a `Cursor` pointing to the first element of the
+    /// `XorLinkedList`. If the list is empty then a null cursor is returned.
+    #[inline]
+    pub fn front(&self) -> xor_linked_list::Cursor<'a, A> {
+        self.collection.front()
+    }
+
+    /// Returns a `Cursor` pointing to the last element of the
+    /// `XorLinkedList`. If the list is empty then a null cursor is returned.
+    #[inline]
+    pub fn back(&self) -> xor_linked_list::Cursor<'a, A> {
+        self.collection.back()
+    }
+
+    /// Gets an iterator over the objects in the `XorLinkedList`.
+    #[inline]
+    pub fn iter(&self) -> xor_linked_list::Iter<'a, A> {
+        self.collection.iter()
+    }
+}
+
+impl<'a, A: Adapter + 'a> IntoIterator for Frozen<'a, XorLinkedList<A>>
+where
+    A::LinkOps: XorLinkedListOps,
+{
+    type Item = &'a <A::PointerOps as PointerOps>::Value;
+    type IntoIter = xor_linked_list::Iter<'a, A>;
+
+    #[inline]
+    fn into_iter(self) -> xor_linked_list::Iter<'a, A> {
+        self.iter()
+    }
+}
+
+// =============================================================================
+// RBTree
+// =============================================================================
+
+impl<'a, A: Adapter> Frozen<'a, RBTree<A>>
+where
+    A::LinkOps: RBTreeOps,
+{
+    /// Returns the debug name of the `RBTree`, if any.
+    #[inline]
+    pub fn debug_name(&self) -> Option<&'static str> {
+        self.collection.debug_name()
+    }
+
+    /// Returns `true` if the `RBTree` is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.collection.is_empty()
+    }
+
+    /// Returns a null `Cursor` for the `RBTree`.
+    #[inline]
+    pub fn cursor(&self) -> rbtree::Cursor<'a, A> {
+        self.collection.cursor()
+    }
+
+    /// Creates a `Cursor` from a pointer to an element.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a pointer to an object that is part of the tree.
+    #[inline]
+    pub unsafe fn cursor_from_ptr(
+        &self,
+        ptr: *const <A::PointerOps as PointerOps>::Value,
+    ) -> rbtree::Cursor<'a, A> {
+        self.collection.cursor_from_ptr(ptr)
+    }
+
+    /// Returns a `Cursor` pointing to the first element of the `RBTree`. If
+    /// the tree is empty then a null cursor is returned.
+    #[inline]
+    pub fn front(&self) -> rbtree::Cursor<'a, A> {
+        self.collection.front()
+    }
+
+    /// Returns a `Cursor` pointing to the last element of the `RBTree`. If
+    /// the tree is empty then a null cursor is returned.
+    #[inline]
+    pub fn back(&self) -> rbtree::Cursor<'a, A> {
+        self.collection.back()
+    }
+
+    /// Gets an iterator over the objects in the `RBTree`.
+    #[inline]
+    pub fn iter(&self) -> rbtree::Iter<'a, A> {
+        self.collection.iter()
+    }
+}
+
+impl<'a, A: for<'b> KeyAdapter<'b>> Frozen<'a, RBTree<A>>
+where
+    <A as Adapter>::LinkOps: RBTreeOps,
+{
+    /// Returns a `Cursor` pointing to an element with the given key. If no
+    /// such element is found then a null cursor is returned.
+    ///
+    /// If multiple elements with an identical key are found then an
+    /// arbitrary one is returned.
+    #[inline]
+    pub fn find<'b, Q: ?Sized + Ord>(&self, key: &Q) -> rbtree::Cursor<'a, A>
+    where
+        <A as KeyAdapter<'b>>::Key: Borrow<Q>,
+        'a: 'b,
+    {
+        self.collection.find(key)
+    }
+
+    /// Returns a `Cursor` pointing to the lowest element whose key is above
+    /// the given bound. If no such element is found then a null cursor is
+    /// returned.
+    #[inline]
+    pub fn lower_bound<'b, Q: ?Sized + Ord>(&self, bound: Bound<&Q>) -> rbtree::Cursor<'a, A>
+    where
+        <A as KeyAdapter<'b>>::Key: Borrow<Q>,
+        'a: 'b,
+    {
+        self.collection.lower_bound(bound)
+    }
+
+    /// Returns a `Cursor` pointing to the last element whose key is below
+    /// the given bound. If no such element is found then a null cursor is
+    /// returned.
+    #[inline]
+    pub fn upper_bound<'b, Q: ?Sized + Ord>(&self, bound: Bound<&Q>) -> rbtree::Cursor<'a, A>
+    where
+        <A as KeyAdapter<'b>>::Key: Borrow<Q>,
+        'a: 'b,
+    {
+        self.collection.upper_bound(bound)
+    }
+
+    /// Constructs a double-ended iterator over a sub-range of elements in
+    /// the tree, starting at min, and ending at max.
+    #[inline]
+    pub fn range<Min: ?Sized + Ord, Max: ?Sized + Ord>(
+        &self,
+        min: Bound<&Min>,
+        max: Bound<&Max>,
+    ) -> rbtree::Iter<'a, A>
+    where
+        <A as KeyAdapter<'a>>::Key: Borrow<Min> + Borrow<Max>,
+        <A as KeyAdapter<'a>>::Key: Ord,
+    {
+        self.collection.range(min, max)
+    }
+}
+
+impl<'a, A: Adapter + 'a> IntoIterator for Frozen<'a, RBTree<A>>
+where
+    A::LinkOps: RBTreeOps,
+{
+    type Item = &'a <A::PointerOps as PointerOps>::Value;
+    type IntoIter = rbtree::Iter<'a, A>;
+
+    #[inline]
+    fn into_iter(self) -> rbtree::Iter<'a, A> {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Frozen;
+    use crate::Bound::{Excluded, Included, Unbounded};
+    use crate::{KeyAdapter, RBTree, RBTreeAtomicLink};
+    use std::boxed::Box;
+    use std::thread;
+    use std::vec;
+    use std::vec::Vec;
+
+    struct Obj {
+        link: RBTreeAtomicLink,
+        value: u32,
+    }
+    intrusive_adapter!(ObjAdapter = Box<Obj>: Obj { link: RBTreeAtomicLink });
+    impl<'a> KeyAdapter<'a> for ObjAdapter {
+        type Key = u32;
+        fn get_key(&self, x: &'a Obj) -> u32 {
+            x.value
+        }
+    }
+
+    fn assert_sync<T: Sync + Send + Copy>(_: &T) {}
+
+    #[test]
+    fn test_frozen_rbtree() {
+        let mut tree = RBTree::new(ObjAdapter::new());
+        for value in [5, 1, 3, 4, 2] {
+            tree.insert(Box::new(Obj {
+                link: RBTreeAtomicLink::new(),
+                value,
+            }));
+        }
+        let frozen = Frozen::from(&tree);
+        assert_sync(&frozen);
+        let results: Vec<Vec<u32>> = thread::scope(|s| {
+            let handles: Vec<_> = (0..2)
+                .map(|i| {
+                    s.spawn(move || match i {
+                        0 => frozen.into_iter().map(|x| x.value).collect(),
+                        _ => frozen
+                            .range(Excluded(&1), Included(&4))
+                            .map(|x| x.value)
+                            .collect(),
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert_eq!(results, [vec![1, 2, 3, 4, 5], vec![2, 3, 4]]);
+
+        assert_eq!(frozen.find(&3).get().unwrap().value, 3);
+        assert!(frozen.find(&6).is_null());
+        assert_eq!(frozen.lower_bound(Excluded(&3)).get().unwrap().value, 4);
+        assert_eq!(
+            frozen.upper_bound(Unbounded::<&u32>).get().unwrap().value,
+            5
+        );
+        assert_eq!(frozen.back().get().unwrap().value, 5);
+
+        // The tree can be modified again once the view is gone.
+        tree.clear();
+        assert!(tree.is_empty());
+    }
+}
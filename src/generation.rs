@@ -0,0 +1,57 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#[cfg(feature = "debug_generation")]
+use core::cell::Cell;
+
+// Number of structural operations performed on a collection, for the
+// `debug_generation` feature. Iterators and cursors record it when they are
+// created, and panic if it changed when they are used since the links they
+// hold may be stale. This is a `Cell` so that the compiler doesn't assume it
+// is unchanged while the collection is borrowed, since unsafe code can still
+// modify the collection then.
+#[cfg(feature = "debug_generation")]
+pub(crate) struct Generation(Cell<usize>);
+
+#[cfg(feature = "debug_generation")]
+impl Generation {
+    #[inline]
+    pub(crate) const fn new() -> Generation {
+        Generation(Cell::new(0))
+    }
+
+    #[inline]
+    pub(crate) fn get(&self) -> usize {
+        self.0.get()
+    }
+
+    #[inline]
+    pub(crate) fn bump(&mut self) {
+        let generation = self.0.get_mut();
+        *generation = generation.wrapping_add(1);
+    }
+
+    // Checks that no structural operation happened since `generation` was
+    // recorded by an iterator or a cursor.
+    #[inline]
+    #[track_caller]
+    pub(crate) fn check(&self, generation: usize, collection: Option<&'static str>) {
+        if generation != self.get() {
+            stale(collection);
+        }
+    }
+}
+
+#[cfg(feature = "debug_generation")]
+#[cold]
+#[track_caller]
+fn stale(collection: Option<&'static str>) -> ! {
+    panic!(
+        "attempted to use an iterator or cursor after its collection was modified (collection: {})",
+        collection.unwrap_or("<unnamed>"),
+    );
+}
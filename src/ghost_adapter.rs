@@ -0,0 +1,146 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use core::marker::PhantomData;
+
+use ghost_cell::{GhostCell, GhostToken};
+
+use crate::adapter::Adapter;
+use crate::pointer_ops::PointerOps;
+
+/// Extension of the `Adapter` trait to provide a way of getting a
+/// `GhostCell` holding the mutable data of an object.
+///
+/// A `GhostCell` can be mutated through a shared reference by whoever holds
+/// the `GhostToken` of its brand, which makes it possible to mutate the
+/// objects of a collection while the collection itself is only borrowed, even
+/// if they are held through `Rc` or `UnsafeRef` pointers. With this trait,
+/// the `iter_ghost_mut` methods of the collections go further and return
+/// mutable references to the data of all the elements at once, since
+/// elements are distinct objects.
+///
+/// The `ghost` clause of `intrusive_adapter!` implements this trait for the
+/// generated adapter without any unsafe code, by returning a `GhostCell` field
+/// of the value.
+///
+/// # Safety
+///
+/// `get_cell` must return a `GhostCell` stored in `value` itself, for example
+/// one of its fields, so that distinct objects always have distinct cells. It
+/// must not return a cell which is shared between objects or stored in
+/// another object that `value` points to.
+///
+/// # Examples
+///
+/// ```
+/// use ghost_cell::{GhostCell, GhostToken};
+/// use intrusive_collections::intrusive_adapter;
+/// use intrusive_collections::{LinkedList, LinkedListLink};
+/// use std::rc::Rc;
+///
+/// struct Obj<'brand> {
+///     link: LinkedListLink,
+///     value: GhostCell<'brand, u32>,
+/// }
+/// intrusive_adapter!(ObjAdapter<'brand> = Rc<Obj<'brand>>: Obj<'brand> { link: LinkedListLink }
+///     ghost { value: GhostCell<'brand, u32> });
+///
+/// GhostToken::new(|mut token| {
+///     let mut list = LinkedList::new(ObjAdapter::new());
+///     for value in 0..3 {
+///         list.push_back(Rc::new(Obj {
+///             link: LinkedListLink::new(),
+///             value: GhostCell::new(value),
+///         }));
+///     }
+///
+///     let list = &list;
+///     for value in list.iter_ghost_mut(&mut token) {
+///         *value *= 10;
+///     }
+///     let values = list.iter().map(|obj| *obj.value.borrow(&token));
+///     assert!(values.eq([0, 10, 20]));
+/// });
+/// ```
+pub unsafe trait GhostAdapter<'brand>: Adapter {
+    /// Type of the data held by the `GhostCell`.
+    type Data: ?Sized;
+
+    /// Gets the `GhostCell` of the given object.
+    fn get_cell<'a>(
+        &self,
+        value: &'a <Self::PointerOps as PointerOps>::Value,
+    ) -> &'a GhostCell<'brand, Self::Data>;
+}
+
+/// An iterator over mutable references to the data of the elements of a
+/// collection, returned by the `iter_ghost_mut` methods of the collections.
+///
+/// `I` is the iterator over the elements of the collection. The
+/// `GhostToken` is mutably borrowed for as long as the references are used.
+pub struct GhostIterMut<'a, 'brand, A: GhostAdapter<'brand>, I> {
+    iter: I,
+    adapter: &'a A,
+    _token: PhantomData<&'a mut GhostToken<'brand>>,
+}
+
+impl<'a, 'brand, A: GhostAdapter<'brand>, I> GhostIterMut<'a, 'brand, A, I> {
+    // `iter` must yield distinct elements of a collection using `adapter`.
+    #[inline]
+    pub(crate) unsafe fn new(
+        iter: I,
+        adapter: &'a A,
+        _token: &'a mut GhostToken<'brand>,
+    ) -> GhostIterMut<'a, 'brand, A, I> {
+        GhostIterMut {
+            iter,
+            adapter,
+            _token: PhantomData,
+        }
+    }
+
+    // The token is borrowed mutably, so no other reference to the data of a
+    // cell with this brand exists, and the elements, and therefore their
+    // cells, are distinct.
+    #[inline]
+    fn data(&self, value: &'a <A::PointerOps as PointerOps>::Value) -> *mut A::Data {
+        self.adapter.get_cell(value).as_ptr()
+    }
+}
+
+impl<'a, 'brand, A, I> Iterator for GhostIterMut<'a, 'brand, A, I>
+where
+    A: GhostAdapter<'brand>,
+    A::Data: 'a,
+    I: Iterator<Item = &'a <A::PointerOps as PointerOps>::Value>,
+{
+    type Item = &'a mut A::Data;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut A::Data> {
+        let value = self.iter.next()?;
+        Some(unsafe { &mut *self.data(value) })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, 'brand, A, I> DoubleEndedIterator for GhostIterMut<'a, 'brand, A, I>
+where
+    A: GhostAdapter<'brand>,
+    A::Data: 'a,
+    I: DoubleEndedIterator<Item = &'a <A::PointerOps as PointerOps>::Value>,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut A::Data> {
+        let value = self.iter.next_back()?;
+        Some(unsafe { &mut *self.data(value) })
+    }
+}
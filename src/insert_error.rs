@@ -0,0 +1,60 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use core::fmt;
+
+/// The error returned by the `try_` variants of the insertion methods of the
+/// collections, such as `LinkedList::try_push_back`.
+///
+/// These methods return an error instead of panicking when the object is
+/// already linked or the cursor is pointing to the null object, which lets
+/// `#![no_std]` code built with `panic = "abort"` recover. The collection is
+/// left unchanged, and the pointer that was passed in is handed back.
+///
+/// The checks enabled by some features still panic, as they do for the other
+/// methods: with the `poison` feature, modifying a poisoned collection
+/// panics, and with the `debug_owner` feature, `try_replace_with` panics if
+/// the current element was linked by a different collection. Panics from
+/// callbacks, such as the `on_insert` hook, are also propagated.
+pub enum InsertError<P> {
+    /// The object is already linked into a collection.
+    AlreadyLinked(P),
+    /// The cursor is pointing to the null object.
+    NullCursor(P),
+}
+
+impl<P> InsertError<P> {
+    /// Returns the pointer that couldn't be inserted.
+    #[inline]
+    pub fn into_inner(self) -> P {
+        match self {
+            InsertError::AlreadyLinked(val) | InsertError::NullCursor(val) => val,
+        }
+    }
+}
+
+// The pointer is left out, so that the error can be unwrapped for any pointer
+// type.
+impl<P> fmt::Debug for InsertError<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InsertError::AlreadyLinked(_) => f.write_str("AlreadyLinked(..)"),
+            InsertError::NullCursor(_) => f.write_str("NullCursor(..)"),
+        }
+    }
+}
+
+impl<P> fmt::Display for InsertError<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InsertError::AlreadyLinked(_) => {
+                f.write_str("attempted to insert an object that is already linked")
+            }
+            InsertError::NullCursor(_) => f.write_str("the cursor is pointing to the null object"),
+        }
+    }
+}
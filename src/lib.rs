@@ -245,8 +245,109 @@
 //! // the list.
 //! a.value.set(7);
 //! assert_eq!(list2.iter().map(|x| x.value.get()).collect::<Vec<_>>(), [7, 5, 6]);
+//! # list.clear();
+//! # list2.clear();
 //! ```
 //!
+//! Unlike the collections of `std`, intrusive collections don't mark their
+//! `Drop` implementations with `#[may_dangle]`, even with the `nightly`
+//! feature: dropping a collection walks the links of its elements to unlink
+//! them and calls the `on_remove` hook of the adapter, so borrowed elements
+//! must still be alive at that point. Values declared after the collection
+//! are therefore rejected, even if nothing else uses the collection:
+//!
+//! ```compile_fail
+//! use intrusive_collections::intrusive_adapter;
+//! use intrusive_collections::{LinkedListLink, LinkedList};
+//!
+//! struct Value {
+//!     link: LinkedListLink,
+//! }
+//! intrusive_adapter!(ValueAdapter<'a> = &'a Value: Value { link: LinkedListLink });
+//!
+//! let mut list = LinkedList::new(ValueAdapter::new());
+//! let a = Value { link: LinkedListLink::new() };
+//! list.push_back(&a); // error: `a` does not live long enough
+//! ```
+//!
+//! The lifetime of the references ties every element to its arena, so the
+//! adapter doesn't need to know about the arena at all. Arena allocators
+//! usually hand out `&'a mut T`, which can also be used as the pointer type.
+//! This gives the collection exclusive ownership of its elements, which allows
+//! them to be modified through `CursorMut::get_mut` and `iter_mut` instead of
+//! requiring interior mutability, but rules out `clone_pointer`. With the
+//! `bumpalo` feature, `bumpalo::boxed::Box` can be used as well, which
+//! additionally drops the elements when they are removed from the collection,
+//! and `BumpPointerOps` keeps a handle to a `Bump` in the adapter, which
+//! checks in debug builds that the references inserted into the collection
+//! were allocated from that arena.
+//!
+//! ```
+//! use intrusive_collections::intrusive_adapter;
+//! use intrusive_collections::{LinkedListLink, LinkedList};
+//! use typed_arena::Arena;
+//!
+//! struct Value {
+//!     link: LinkedListLink,
+//!     value: i32,
+//! }
+//!
+//! intrusive_adapter!(ValueAdapter<'a> = &'a mut Value: Value { link: LinkedListLink });
+//!
+//! let arena = Arena::new();
+//! let mut list = LinkedList::new(ValueAdapter::new());
+//! for value in 1..4 {
+//!     list.push_back(arena.alloc(Value {
+//!         link: LinkedListLink::new(),
+//!         value,
+//!     }));
+//! }
+//! unsafe {
+//!     for v in list.iter_mut() {
+//!         v.value *= 10;
+//!     }
+//! }
+//! assert_eq!(list.iter().map(|x| x.value).collect::<Vec<_>>(), [10, 20, 30]);
+//! # list.clear();
+//! ```
+//!
+//! # Thread safety
+//!
+//! The `Send` and `Sync` implementations of collections follow those of the
+//! pointer and value types, so the compiler checks that sharing a collection
+//! between threads is sound:
+//!
+//! - A collection is `Send` if its adapter and its pointer type are `Send`.
+//!   Moving a collection moves the ownership of its elements.
+//! - A collection is `Sync` if its adapter, its pointer type and its value
+//!   type are `Sync`. Shared access to a collection gives shared access to its
+//!   elements, and allows cloning pointers with `clone_pointer`.
+//! - `Cursor` and `Iter` are `Send` and `Sync` if the collection is `Sync`,
+//!   like a shared reference to it. `CursorMut` and `IterMut` are `Send` if
+//!   the collection is `Send`, like a mutable reference to it.
+//!
+//! Since `Link` types use `Cell`, values containing them are never `Sync`,
+//! and sharing a collection between threads requires the `AtomicLink` types.
+//! Likewise, a collection of `Rc` is neither `Send` nor `Sync`:
+//!
+//! ```compile_fail
+//! use intrusive_collections::{intrusive_adapter, LinkedList, LinkedListAtomicLink};
+//! use std::rc::Rc;
+//!
+//! struct Obj {
+//!     link: LinkedListAtomicLink,
+//! }
+//! intrusive_adapter!(ObjAdapter = Rc<Obj>: Obj { link: LinkedListAtomicLink });
+//!
+//! fn assert_sync<T: Sync>(_: &T) {}
+//! assert_sync(&LinkedList::new(ObjAdapter::new()));
+//! ```
+//!
+//! When the compiler can't prove that sharing or moving a value is safe but
+//! the user can, `AssertSend` and `AssertSync` take responsibility for it.
+//! A `Frozen` view of a collection only exposes its read methods, which makes
+//! it a convenient handle to give to threads reading a shared collection.
+//!
 //! # Safety
 //!
 //! While it is possible to use intrusive collections without any unsafe code,
@@ -256,6 +357,8 @@
 //! cursor pointing to a specific element in the collection from a pointer to
 //! that element. This is unsafe because it assumes that the objected pointed to
 //! is currently inserted in the collection.
+//! With the `debug-checks` feature, they scan the collection and panic if the
+//! object isn't in it.
 //!
 //! The `UnsafeRef` type acts like `Rc`, except without the reference count.
 //! Instead, you are responsible for keeping track of the number of active
@@ -267,50 +370,173 @@
 #![warn(missing_docs)]
 #![warn(rust_2018_idioms)]
 #![no_std]
+#![cfg_attr(feature = "nightly", feature(coerce_unsized, unsize))]
 #![allow(
     clippy::declare_interior_mutable_const,
     clippy::collapsible_if,
-    clippy::collapsible_else_if
+    clippy::collapsible_else_if,
+    clippy::manual_dangling_ptr
 )]
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
-#[cfg(test)]
+#[cfg(any(test, feature = "std", feature = "differential"))]
 extern crate std;
 
+#[macro_use]
+mod sync;
+
 mod unsafe_ref;
 #[macro_use]
 mod adapter;
+mod assert_send;
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+mod counted_adapter;
+mod cursor_ops;
+#[macro_use]
+mod bundle;
+mod circular_list;
+#[cfg(feature = "dump")]
+mod dump;
+mod dyn_adapter;
+mod footprint;
+mod frozen;
+mod generation;
+#[cfg(feature = "ghost-cell")]
+mod ghost_adapter;
+mod insert_error;
 mod key_adapter;
 mod link_ops;
+mod object_pool;
+mod pin_project;
 mod pointer_ops;
+#[macro_use]
+mod poison;
+mod priority_adapter;
+mod sanitizer;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod slot_pointer_ops;
+#[cfg(feature = "static_pool")]
+mod static_pool;
+#[macro_use]
+mod stats;
+#[cfg(feature = "alloc")]
+mod std_convert;
+mod transparent_adapter;
 mod unchecked_option;
 
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+pub mod append_only_list;
+#[cfg(feature = "alloc")]
+pub mod arena;
+pub mod auto_unlink_list;
+#[cfg(feature = "std")]
+pub mod bounded_queue;
+pub mod c_header;
+pub mod collection;
+#[cfg(feature = "differential")]
+pub mod differential;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod linked_list;
+pub mod observer_list;
+#[cfg(any(feature = "proptest", feature = "fuzzing"))]
+pub mod ops_interpreter;
+#[cfg(feature = "proptest")]
+pub mod proptest;
 pub mod rbtree;
+#[cfg(feature = "alloc")]
+pub mod rbtree_map;
+#[cfg(feature = "alloc")]
+pub mod retire_list;
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+pub mod seq_locked;
+#[cfg(feature = "std")]
+pub mod sharded;
 pub mod singly_linked_list;
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+pub mod steal_deque;
+#[cfg(feature = "futures")]
+pub mod stream;
+pub mod union_find;
+pub mod weak_list;
 pub mod xor_linked_list;
 
-pub use crate::adapter::Adapter;
+#[cfg(feature = "erasable")]
+pub use crate::adapter::container_of_erased;
+pub use crate::adapter::{container_of_ptr, field_ptr, Adapter, AdapterHooks};
+pub use crate::assert_send::{AssertSend, AssertSync};
+pub use crate::bundle::BundleMember;
+pub use crate::circular_list::CircularList;
+pub use crate::collection::Collection;
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+pub use crate::counted_adapter::AtomicCountedAdapter;
+pub use crate::cursor_ops::{CursorMutOps, CursorOps, DoubleEndedCursorOps};
+pub use crate::dyn_adapter::{DynAdapter, DynLink, HasDynLink};
+pub use crate::footprint::MemoryOverhead;
+pub use crate::frozen::Frozen;
+#[cfg(feature = "ghost-cell")]
+pub use crate::ghost_adapter::{GhostAdapter, GhostIterMut};
+pub use crate::insert_error::InsertError;
 pub use crate::key_adapter::KeyAdapter;
-pub use crate::link_ops::{DefaultLinkOps, LinkOps};
+pub use crate::link_ops::{
+    CachePadded, DefaultLinkOps, DefaultSentinel, GenerationLink, LinkFieldOps, LinkOps,
+    LinkSentinel, LinkStamp, LinkStateOps, LinkWrapper, WrapperLinkOps,
+};
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 pub use crate::linked_list::AtomicLink as LinkedListAtomicLink;
 pub use crate::linked_list::Link as LinkedListLink;
 pub use crate::linked_list::LinkedList;
+pub use crate::linked_list::LinkedListOps;
+pub use crate::linked_list::SentinelLink as LinkedListSentinelLink;
+pub use crate::object_pool::ObjectPool;
+#[cfg(feature = "allocator-api2")]
+pub use crate::pointer_ops::BoxPointerOps;
+#[cfg(feature = "bumpalo")]
+pub use crate::pointer_ops::BumpPointerOps;
 pub use crate::pointer_ops::{
-    DefaultPointerOps, ExclusivePointerOps, PointerOps, TryExclusivePointerOps,
+    clone_pointer_from_raw, from_tagged_raw, into_tagged_raw, tag_mask, CowPointerOps,
+    DefaultPointerOps, DowngradePointerOps, ExclusivePinnedPointerOps, ExclusivePointerOps,
+    PinnedPointerOps, PointerOps, ProjectedPointerOps, RawPointerOps, TryExclusivePointerOps,
+    TryPointerOps,
 };
+#[cfg(feature = "stable_deref_trait")]
+pub use crate::pointer_ops::{IntoRawPointer, StablePointerOps};
+pub use crate::priority_adapter::PriorityAdapter;
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 pub use crate::rbtree::AtomicLink as RBTreeAtomicLink;
 pub use crate::rbtree::Link as RBTreeLink;
 pub use crate::rbtree::RBTree;
+pub use crate::rbtree::RBTreeOps;
+#[cfg(feature = "alloc")]
+pub use crate::rbtree_map::RBTreeMap;
+#[cfg(feature = "std")]
+pub use crate::sharded::Sharded;
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 pub use crate::singly_linked_list::AtomicLink as SinglyLinkedListAtomicLink;
 pub use crate::singly_linked_list::Link as SinglyLinkedListLink;
+pub use crate::singly_linked_list::SentinelLink as SinglyLinkedListSentinelLink;
 pub use crate::singly_linked_list::SinglyLinkedList;
+pub use crate::singly_linked_list::SinglyLinkedListOps;
+pub use crate::slot_pointer_ops::{SlotKey, SlotPointerOps, SlotStorage, StaleKey};
+#[cfg(all(feature = "static_pool", target_has_atomic = "8"))]
+pub use crate::static_pool::ArrayPool;
+#[cfg(feature = "static_pool")]
+pub use crate::static_pool::{PoolBox, StaticPool};
+#[cfg(feature = "stats")]
+pub use crate::stats::Stats;
+pub use crate::transparent_adapter::{TransparentAdapter, TransparentWrapper};
 pub use crate::unsafe_ref::{UnsafeMut, UnsafeRef};
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 pub use crate::xor_linked_list::AtomicLink as XorLinkedListAtomicLink;
 pub use crate::xor_linked_list::Link as XorLinkedListLink;
 pub use crate::xor_linked_list::XorLinkedList;
+pub use crate::xor_linked_list::XorLinkedListOps;
+#[cfg(feature = "offset_of")]
+pub use core::mem::offset_of;
+#[cfg(not(feature = "offset_of"))]
 pub use memoffset::offset_of;
 
 /// An endpoint of a range of keys.
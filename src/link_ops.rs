@@ -5,20 +5,144 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+use core::fmt;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+
+use crate::sync::{self as atomic, AtomicUsize};
+
 /// Base trait for link operations.
 ///
 /// `LinkPtr` is the representation of a link pointer.
 /// Typically this is `NonNull`, but compact representations such
 /// as `u8` or `u16` are possible.
+///
+/// Together with one of the collection-specific traits
+/// (`LinkedListOps`, `SinglyLinkedListOps`, `XorLinkedListOps` or
+/// `RBTreeOps`), this trait is all a collection needs to know about a link,
+/// so it can be implemented outside this crate to define custom link
+/// layouts. A link type which also implements `DefaultLinkOps` (and whose
+/// link operations implement `LinkFieldOps`) can be used with
+/// `intrusive_adapter!`.
+///
+/// Collections follow this protocol when using link operations:
+///
+/// - `acquire_link` is called when an object is inserted. If it returns
+///   `false` the insertion is aborted with a panic and the link is left
+///   untouched.
+/// - Between a successful `acquire_link` and the matching `release_link`,
+///   the collection has exclusive access to the link and uses the
+///   collection-specific operations to read and write it. Nothing else may
+///   modify the link during that time.
+/// - `release_link` is called when the object is removed, after which the
+///   collection never touches the link again.
+///
+/// # Examples
+///
+/// A link for `SinglyLinkedList` which keeps a user-defined flag in the
+/// otherwise unused low bit of the `next` pointer:
+///
+/// ```
+/// use core::cell::Cell;
+/// use core::ptr::NonNull;
+/// use intrusive_collections::{intrusive_adapter, DefaultLinkOps, LinkOps};
+/// use intrusive_collections::{SinglyLinkedList, SinglyLinkedListOps};
+///
+/// const UNLINKED: usize = 2;
+///
+/// #[repr(align(4))]
+/// pub struct FlagLink {
+///     next: Cell<usize>,
+/// }
+///
+/// impl FlagLink {
+///     pub const fn new() -> FlagLink {
+///         FlagLink { next: Cell::new(UNLINKED) }
+///     }
+///     pub fn flag(&self) -> bool {
+///         self.next.get() & 1 != 0
+///     }
+///     pub fn set_flag(&self, flag: bool) {
+///         self.next.set(self.next.get() & !1 | flag as usize);
+///     }
+/// }
+///
+/// #[derive(Clone, Copy, Default)]
+/// pub struct FlagLinkOps;
+///
+/// unsafe impl LinkOps for FlagLinkOps {
+///     type LinkPtr = NonNull<FlagLink>;
+///
+///     unsafe fn acquire_link(&mut self, ptr: Self::LinkPtr) -> bool {
+///         let link = ptr.as_ref();
+///         if link.next.get() & !1 != UNLINKED {
+///             return false;
+///         }
+///         link.next.set(link.next.get() & 1);
+///         true
+///     }
+///
+///     unsafe fn release_link(&mut self, ptr: Self::LinkPtr) {
+///         let link = ptr.as_ref();
+///         link.next.set(link.next.get() & 1 | UNLINKED);
+///     }
+/// }
+///
+/// unsafe impl SinglyLinkedListOps for FlagLinkOps {
+///     unsafe fn next(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
+///         NonNull::new((ptr.as_ref().next.get() & !1) as *mut FlagLink)
+///     }
+///
+///     unsafe fn set_next(&mut self, ptr: Self::LinkPtr, next: Option<Self::LinkPtr>) {
+///         let link = ptr.as_ref();
+///         let next = next.map_or(0, |x| x.as_ptr() as usize);
+///         link.next.set(link.next.get() & 1 | next);
+///     }
+/// }
+///
+/// impl DefaultLinkOps for FlagLink {
+///     type Ops = FlagLinkOps;
+///     const NEW: FlagLinkOps = FlagLinkOps;
+/// }
+///
+/// struct Value {
+///     link: FlagLink,
+///     value: u32,
+/// }
+/// intrusive_adapter!(ValueAdapter = Box<Value>: Value { link: FlagLink });
+///
+/// let mut list = SinglyLinkedList::new(ValueAdapter::new());
+/// for value in 0..4 {
+///     let v = Box::new(Value { link: FlagLink::new(), value });
+///     v.link.set_flag(value % 2 == 1);
+///     list.push_front(v);
+/// }
+/// assert_eq!(list.iter().map(|v| v.value).collect::<Vec<_>>(), [3, 2, 1, 0]);
+/// assert_eq!(list.iter().map(|v| v.link.flag()).collect::<Vec<_>>(), [true, false, true, false]);
+/// ```
+///
+/// # Safety
+///
+/// A link must only be acquired by a single collection at a time: once
+/// `acquire_link` has returned `true` for a link, it must return `false` for
+/// that link until `release_link` is called.
 pub unsafe trait LinkOps {
     /// The link pointer type.
     type LinkPtr: Copy + Eq;
 
+    /// Size in bytes of the links these operations act on, as reported by
+    /// the `memory_overhead` methods of the collections. This is `None` if
+    /// the link operations don't provide it.
+    const LINK_SIZE: Option<usize> = None;
+
     /// Attempts to acquire ownership of a link so that it can be used in an
     /// intrusive collection.
     ///
     /// If this function succeeds then the intrusive collection will have
     /// exclusive access to the link until `release_link` is called.
+    ///
+    /// # Safety
+    /// `ptr` must point to a valid link.
     unsafe fn acquire_link(&mut self, ptr: Self::LinkPtr) -> bool;
 
     /// Releases ownership of a link that was previously acquired with `acquire_link`.
@@ -28,7 +152,30 @@ pub unsafe trait LinkOps {
     unsafe fn release_link(&mut self, ptr: Self::LinkPtr);
 }
 
+/// Link operations which can tell whether a link is in use without acquiring
+/// it.
+///
+/// This is required by operations which need to check whether an object is
+/// still in a collection, such as `UnsafeRef::try_into_box`. All the links in
+/// this crate implement it.
+///
+/// # Safety
+///
+/// `is_linked` must return `true` exactly when `acquire_link` would fail for
+/// the link.
+pub unsafe trait LinkStateOps: LinkOps {
+    /// Returns whether a link is in use, and `acquire_link` would fail for
+    /// it, without changing the state of the link.
+    ///
+    /// # Safety
+    /// `ptr` must point to a valid link.
+    unsafe fn is_linked(&self, ptr: Self::LinkPtr) -> bool;
+}
+
 /// The default implementation of `LinkOps` associated with a link type.
+///
+/// This is what `intrusive_adapter!` uses to find the link operations for
+/// the type of the link field.
 pub trait DefaultLinkOps {
     /// The default link operations.
     type Ops: LinkOps + Default;
@@ -38,3 +185,521 @@ pub trait DefaultLinkOps {
     /// This exists because `Default::default()` is not a constant function.
     const NEW: Self::Ops;
 }
+
+/// Link operations which can convert between a link pointer and a raw
+/// pointer to a link of type `L` stored in an object.
+///
+/// `intrusive_adapter!` uses this to implement `Adapter::get_link` and
+/// `Adapter::get_value`. It is implemented automatically for all link
+/// operations whose `LinkPtr` is `NonNull<L>`, which covers all the links in
+/// this crate. Link operations using a different link pointer representation
+/// can implement it to be usable with `intrusive_adapter!`.
+///
+/// # Examples
+///
+/// ```
+/// use core::ptr::NonNull;
+/// use intrusive_collections::{LinkFieldOps, LinkOps};
+///
+/// pub struct MyLink;
+///
+/// #[derive(Clone, Copy, PartialEq, Eq)]
+/// pub struct MyLinkPtr(NonNull<MyLink>);
+///
+/// #[derive(Clone, Copy, Default)]
+/// pub struct MyLinkOps;
+///
+/// unsafe impl LinkOps for MyLinkOps {
+///     type LinkPtr = MyLinkPtr;
+///     # unsafe fn acquire_link(&mut self, ptr: MyLinkPtr) -> bool { unimplemented!() }
+///     # unsafe fn release_link(&mut self, ptr: MyLinkPtr) { unimplemented!() }
+///     // ...
+/// }
+///
+/// unsafe impl LinkFieldOps<MyLink> for MyLinkOps {
+///     unsafe fn link_ptr(&self, link: *const MyLink) -> MyLinkPtr {
+///         MyLinkPtr(NonNull::new_unchecked(link as *mut MyLink))
+///     }
+///     fn link_field(&self, ptr: MyLinkPtr) -> *const MyLink {
+///         ptr.0.as_ptr()
+///     }
+/// }
+/// ```
+///
+/// # Safety
+///
+/// `link_ptr` and `link_field` must be inverses of each other.
+pub unsafe trait LinkFieldOps<L>: LinkOps {
+    /// Returns the link pointer for a link stored in an object.
+    ///
+    /// # Safety
+    /// `link` must point to a valid link.
+    unsafe fn link_ptr(&self, link: *const L) -> Self::LinkPtr;
+
+    /// Returns a raw pointer to the link referred to by a link pointer.
+    fn link_field(&self, ptr: Self::LinkPtr) -> *const L;
+}
+
+unsafe impl<L, T: LinkOps<LinkPtr = NonNull<L>>> LinkFieldOps<L> for T {
+    #[inline]
+    unsafe fn link_ptr(&self, link: *const L) -> NonNull<L> {
+        NonNull::new_unchecked(link as *mut L)
+    }
+
+    #[inline]
+    fn link_field(&self, ptr: NonNull<L>) -> *const L {
+        ptr.as_ptr()
+    }
+}
+
+/// A link padded and aligned to 64 bytes, the size of a cache line on most
+/// architectures.
+///
+/// Links of neighboring objects in a collection are written whenever an
+/// object is inserted or removed next to them. If those objects are also
+/// modified from other cores, putting their links on their own cache line
+/// avoids false sharing between the two. `CachePadded` dereferences to the
+/// inner link and can be used as the link type in `intrusive_adapter!`:
+///
+/// ```
+/// use intrusive_collections::{intrusive_adapter, CachePadded, LinkedList, LinkedListLink};
+///
+/// struct Value {
+///     link: CachePadded<LinkedListLink>,
+///     value: u32,
+/// }
+/// intrusive_adapter!(ValueAdapter = Box<Value>: Value { link: CachePadded<LinkedListLink> });
+///
+/// let mut list = LinkedList::new(ValueAdapter::new());
+/// list.push_back(Box::new(Value { link: CachePadded::new(LinkedListLink::new()), value: 1 }));
+/// assert!(list.front().get().unwrap().link.is_linked());
+/// assert_eq!(core::mem::align_of::<Value>(), 64);
+/// ```
+#[repr(C, align(64))]
+#[derive(Clone, Default, Debug)]
+pub struct CachePadded<L> {
+    link: L,
+}
+
+impl<L> CachePadded<L> {
+    /// Wraps a link in a `CachePadded`.
+    #[inline]
+    pub const fn new(link: L) -> CachePadded<L> {
+        CachePadded { link }
+    }
+
+    /// Returns the inner link.
+    #[inline]
+    pub fn into_inner(self) -> L {
+        self.link
+    }
+}
+
+impl<L> core::ops::Deref for CachePadded<L> {
+    type Target = L;
+
+    #[inline]
+    fn deref(&self) -> &L {
+        &self.link
+    }
+}
+
+unsafe impl<L> LinkWrapper for CachePadded<L> {
+    type Link = L;
+}
+
+impl<L: DefaultLinkOps> DefaultLinkOps for CachePadded<L>
+where
+    L::Ops: LinkOps<LinkPtr = NonNull<L>>,
+{
+    type Ops = WrapperLinkOps<CachePadded<L>, L::Ops>;
+
+    const NEW: Self::Ops = WrapperLinkOps::new(L::NEW);
+}
+
+/// A link type which wraps another link stored at its start, such as
+/// `CachePadded` and `GenerationLink`.
+///
+/// `WrapperLinkOps` implements the link operations of such a link on top of
+/// the link operations of the wrapped link.
+///
+/// # Safety
+///
+/// `Self` must be `repr(C)` with the wrapped link as its first field, so that
+/// a pointer to `Self` is also a pointer to the wrapped link.
+pub unsafe trait LinkWrapper {
+    /// The wrapped link type.
+    type Link;
+
+    /// Called after the wrapped link was released by a collection.
+    #[inline]
+    fn on_release(&self) {}
+}
+
+/// Link operations for a `LinkWrapper` link, which forward to the link
+/// operations `O` of the wrapped link.
+pub struct WrapperLinkOps<W, O> {
+    pub(crate) ops: O,
+    marker: PhantomData<fn() -> W>,
+}
+
+impl<W, O> WrapperLinkOps<W, O> {
+    /// Wraps the link operations of the wrapped link.
+    #[inline]
+    pub const fn new(ops: O) -> WrapperLinkOps<W, O> {
+        WrapperLinkOps {
+            ops,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<W, O: Clone> Clone for WrapperLinkOps<W, O> {
+    #[inline]
+    fn clone(&self) -> Self {
+        WrapperLinkOps::new(self.ops.clone())
+    }
+}
+
+impl<W, O: Copy> Copy for WrapperLinkOps<W, O> {}
+
+impl<W, O: Default> Default for WrapperLinkOps<W, O> {
+    #[inline]
+    fn default() -> Self {
+        WrapperLinkOps::new(O::default())
+    }
+}
+
+// The wrapped link is the first field of the `repr(C)` wrapper, so a pointer
+// to the wrapper can be cast to a pointer to the link and back.
+#[inline]
+pub(crate) fn wrapper_ptr<W: LinkWrapper>(ptr: Option<NonNull<W::Link>>) -> Option<NonNull<W>> {
+    ptr.map(NonNull::cast)
+}
+
+unsafe impl<W: LinkWrapper, O: LinkOps<LinkPtr = NonNull<W::Link>>> LinkOps
+    for WrapperLinkOps<W, O>
+{
+    type LinkPtr = NonNull<W>;
+
+    const LINK_SIZE: Option<usize> = Some(core::mem::size_of::<W>());
+
+    #[inline]
+    unsafe fn acquire_link(&mut self, ptr: Self::LinkPtr) -> bool {
+        self.ops.acquire_link(ptr.cast())
+    }
+
+    #[inline]
+    unsafe fn release_link(&mut self, ptr: Self::LinkPtr) {
+        self.ops.release_link(ptr.cast());
+        ptr.as_ref().on_release();
+    }
+}
+
+unsafe impl<W: LinkWrapper, O: LinkStateOps<LinkPtr = NonNull<W::Link>>> LinkStateOps
+    for WrapperLinkOps<W, O>
+{
+    #[inline]
+    unsafe fn is_linked(&self, ptr: Self::LinkPtr) -> bool {
+        self.ops.is_linked(ptr.cast())
+    }
+}
+
+/// A link paired with a generation counter which is incremented every time
+/// the link is removed from a collection.
+///
+/// A `LinkStamp` taken from the link can later be checked against it with
+/// `is_current`: it stops being current once the object has been unlinked,
+/// so a long-lived handle to an object can detect that the object was removed
+/// (and possibly inserted again) in the meantime. This also catches ABA
+/// problems in structures which identify objects by their address.
+///
+/// Like `CachePadded`, a `GenerationLink` dereferences to the inner link and
+/// can be used as the link type in `intrusive_adapter!`:
+///
+/// ```
+/// use intrusive_collections::{intrusive_adapter, GenerationLink, LinkedList, LinkedListLink};
+///
+/// struct Value {
+///     link: GenerationLink<LinkedListLink>,
+///     value: u32,
+/// }
+/// intrusive_adapter!(ValueAdapter = Box<Value>: Value { link: GenerationLink<LinkedListLink> });
+///
+/// let mut list = LinkedList::new(ValueAdapter::new());
+/// list.push_back(Box::new(Value { link: GenerationLink::new(LinkedListLink::new()), value: 1 }));
+/// let stamp = list.front().get().unwrap().link.stamp();
+/// let value = list.pop_front().unwrap();
+/// assert!(!value.link.is_current(stamp));
+/// assert_eq!(value.link.generation(), 1);
+/// ```
+///
+/// The generation is only incremented when a collection releases the link,
+/// which excludes `fast_clear` followed by `force_unlink`. Moving objects
+/// between collections with `take`, `split_after` or `splice_after` doesn't
+/// release their links either, so it keeps their generation.
+#[repr(C)]
+#[derive(Default)]
+pub struct GenerationLink<L> {
+    link: L,
+    generation: AtomicUsize,
+}
+
+impl<L> GenerationLink<L> {
+    loom_const_fn! {
+        /// Wraps a link in a `GenerationLink`, starting at generation 0.
+        #[inline]
+        pub fn new(link: L) -> GenerationLink<L> {
+            GenerationLink {
+                link,
+                generation: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    /// Returns the number of times the link has been removed from a
+    /// collection.
+    #[inline]
+    pub fn generation(&self) -> usize {
+        self.generation.load(atomic::Ordering::Acquire)
+    }
+
+    /// Returns a stamp identifying this link in its current generation.
+    #[inline]
+    pub fn stamp(&self) -> LinkStamp {
+        LinkStamp {
+            addr: self as *const _ as usize,
+            generation: self.generation(),
+        }
+    }
+
+    /// Checks whether `stamp` was taken from this link and the link hasn't been
+    /// removed from a collection since.
+    #[inline]
+    pub fn is_current(&self, stamp: LinkStamp) -> bool {
+        stamp == self.stamp()
+    }
+
+    /// Returns the inner link.
+    #[inline]
+    pub fn into_inner(self) -> L {
+        self.link
+    }
+}
+
+impl<L> core::ops::Deref for GenerationLink<L> {
+    type Target = L;
+
+    #[inline]
+    fn deref(&self) -> &L {
+        &self.link
+    }
+}
+
+// A clone is a new object as far as handles are concerned, so it starts over
+// at generation 0.
+impl<L: Clone> Clone for GenerationLink<L> {
+    #[inline]
+    fn clone(&self) -> Self {
+        GenerationLink::new(self.link.clone())
+    }
+}
+
+impl<L: fmt::Debug> fmt::Debug for GenerationLink<L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GenerationLink")
+            .field("link", &self.link)
+            .field("generation", &self.generation())
+            .finish()
+    }
+}
+
+unsafe impl<L> LinkWrapper for GenerationLink<L> {
+    type Link = L;
+
+    #[inline]
+    fn on_release(&self) {
+        // Only the collection owning the link releases it, so this doesn't
+        // need a read-modify-write, which not all targets support.
+        let generation = self.generation.load(atomic::Ordering::Relaxed);
+        self.generation
+            .store(generation.wrapping_add(1), atomic::Ordering::Release);
+    }
+}
+
+impl<L: DefaultLinkOps> DefaultLinkOps for GenerationLink<L>
+where
+    L::Ops: LinkOps<LinkPtr = NonNull<L>>,
+{
+    type Ops = WrapperLinkOps<GenerationLink<L>, L::Ops>;
+
+    const NEW: Self::Ops = WrapperLinkOps::new(L::NEW);
+}
+
+/// A handle to a `GenerationLink` in a given generation, returned by
+/// `GenerationLink::stamp`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct LinkStamp {
+    addr: usize,
+    generation: usize,
+}
+
+impl LinkStamp {
+    /// Returns the generation of the link when the stamp was taken.
+    #[inline]
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+}
+
+/// Chooses the value stored in a link to mark it as unlinked, for
+/// `linked_list::SentinelLink` and `singly_linked_list::SentinelLink`.
+///
+/// The default links of `LinkedList` and `SinglyLinkedList` store address 1 in
+/// their `next` pointer while unlinked, which is what `DefaultSentinel` does.
+/// Other sentinels are useful when the links are shared with C code which
+/// uses a different convention, such as pointing to a static dummy node:
+///
+/// ```
+/// use core::ptr::NonNull;
+/// use intrusive_collections::LinkSentinel;
+///
+/// static LIST_POISON: u8 = 0;
+///
+/// struct Poison;
+/// unsafe impl LinkSentinel for Poison {
+///     fn unlinked() -> NonNull<u8> {
+///         NonNull::from(&LIST_POISON)
+///     }
+/// }
+/// ```
+///
+/// The links of `XorLinkedList` and `RBTree` have no sentinel variant: their
+/// unlinked marker is stored in a word which also holds the XOR of two
+/// addresses, or the parent pointer and the color of a node, so an arbitrary
+/// address could be mistaken for a linked state.
+///
+/// # Safety
+///
+/// `unlinked` must always return the same address, and no link may be stored
+/// at that address while it is in a collection.
+pub unsafe trait LinkSentinel {
+    /// Returns the address stored in an unlinked link.
+    fn unlinked() -> NonNull<u8>;
+}
+
+/// The sentinel used by the default links: address 1, which is never the
+/// address of a link since links are aligned to at least 2 bytes.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct DefaultSentinel;
+
+unsafe impl LinkSentinel for DefaultSentinel {
+    #[inline]
+    fn unlinked() -> NonNull<u8> {
+        unsafe { NonNull::new_unchecked(1 as *mut u8) }
+    }
+}
+
+// Pattern written in debug builds into the fields of a link which aren't used
+// once it is unlinked. It is odd, so it never matches a link pointer.
+pub(crate) const POISON: usize = usize::MAX / 0xff * 0xa5;
+
+#[inline]
+pub(crate) fn poison_ptr<T>() -> Option<NonNull<T>> {
+    NonNull::new(POISON as *mut T)
+}
+
+// Checks in debug builds that a value read from a link is neither the unlinked
+// marker, which is 1 for all the pointer-based links in this crate, nor the
+// poison pattern. Either means that the link is used after being removed from
+// its collection, for example through a stale cursor.
+#[inline]
+#[track_caller]
+pub(crate) fn check_link_value(raw: usize) -> usize {
+    debug_assert!(
+        raw != 1 && raw != POISON,
+        "link operation on an unlinked link, it was probably used after being removed from its collection"
+    );
+    raw
+}
+
+// Same as `check_link_value` for a value which may have tag bits set, and only
+// checks for the poison pattern since the tag bits can make a null pointer look
+// like the unlinked marker.
+#[inline]
+#[track_caller]
+pub(crate) fn check_tagged_link_value(raw: usize) -> usize {
+    debug_assert!(
+        raw != POISON,
+        "link operation on an unlinked link, it was probably used after being removed from its collection"
+    );
+    raw
+}
+
+#[inline]
+#[track_caller]
+pub(crate) fn check_link_ptr<T>(ptr: Option<NonNull<T>>) -> Option<NonNull<T>> {
+    check_link_value(ptr.map(|x| x.as_ptr() as usize).unwrap_or(0));
+    ptr
+}
+
+// Returns a new identifier for a collection, used by the `debug_owner`
+// feature to record which collection a link is in. 0 is never returned, it
+// means that a collection hasn't been assigned an identifier yet.
+#[cfg(feature = "debug_owner")]
+#[inline]
+pub(crate) fn new_owner_id() -> usize {
+    #[cfg(not(feature = "portable-atomic"))]
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    #[cfg(feature = "portable-atomic")]
+    use portable_atomic::{AtomicUsize, Ordering};
+
+    static NEXT_OWNER_ID: AtomicUsize = AtomicUsize::new(1);
+    NEXT_OWNER_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+// Shared test of the atomic links of the collections. `fill` modifies a
+// collection of `objs` while another thread keeps checking whether they are
+// linked, and returns it with all of them linked. The collection is then
+// moved to another thread, where `check` reads it, and dropping it must
+// unlink all of them.
+#[cfg(test)]
+pub(crate) fn check_atomic_link_threads<T: Sync, C: Send>(
+    objs: &[T],
+    is_linked: impl Fn(&T) -> bool + Sync,
+    fill: impl FnOnce() -> C,
+    check: impl FnOnce(&C) + Send,
+) {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::thread;
+
+    // Stops the watcher even if `fill` panics, so that the scope can end.
+    struct Done<'a>(&'a AtomicBool);
+    impl Drop for Done<'_> {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::Release);
+        }
+    }
+
+    let done = AtomicBool::new(false);
+    let collection = thread::scope(|s| {
+        s.spawn(|| {
+            while !done.load(Ordering::Acquire) {
+                for obj in objs {
+                    let _ = is_linked(obj);
+                }
+            }
+        });
+        let _done = Done(&done);
+        let collection = fill();
+        s.spawn(move || {
+            check(&collection);
+            collection
+        })
+        .join()
+        .unwrap()
+    });
+    assert!(objs.iter().all(&is_linked));
+    drop(collection);
+    assert!(!objs.iter().any(is_linked));
+}
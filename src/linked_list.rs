@@ -10,12 +10,54 @@
 
 use core::cell::Cell;
 use core::fmt;
-use core::ptr::{null_mut, NonNull};
-use core::sync::atomic::{AtomicPtr, Ordering};
-
-use crate::link_ops::{self, DefaultLinkOps};
-use crate::pointer_ops::{ExclusivePointerOps, PointerOps, TryExclusivePointerOps};
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{ControlFlow, Deref};
+use core::pin::Pin;
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+use core::ptr::null_mut;
+use core::ptr::NonNull;
+
+#[cfg(feature = "ghost-cell")]
+use ghost_cell::GhostToken;
+
+use crate::adapter::TryPointer;
+#[cfg(feature = "alloc")]
+use crate::alloc::boxed::Box;
+use crate::bundle::BundleMember;
+use crate::collection::Collection;
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+use crate::counted_adapter::AtomicCountedAdapter;
+use crate::cursor_ops::{CursorMutOps, CursorOps, DoubleEndedCursorOps};
+#[cfg(feature = "dump")]
+use crate::dump;
+use crate::footprint::MemoryOverhead;
+#[cfg(feature = "debug_generation")]
+use crate::generation::Generation;
+#[cfg(feature = "ghost-cell")]
+use crate::ghost_adapter::{GhostAdapter, GhostIterMut};
+use crate::insert_error::InsertError;
+use crate::link_ops::{
+    self, check_link_ptr, check_tagged_link_value, poison_ptr, wrapper_ptr, DefaultLinkOps,
+    DefaultSentinel, GenerationLink, LinkSentinel, LinkStamp, LinkWrapper, WrapperLinkOps,
+};
+#[cfg(feature = "alloc")]
+use crate::pointer_ops::DefaultPointerOps;
+use crate::pointer_ops::{
+    CowPointerOps, ExclusivePinnedPointerOps, ExclusivePointerOps, PinnedPointerOps, PointerOps,
+    TryExclusivePointerOps, TryPointerOps,
+};
+#[cfg(feature = "poison")]
+use crate::poison::Poison;
+use crate::priority_adapter::PriorityAdapter;
+#[cfg(feature = "alloc")]
+use crate::retire_list::RetireList;
+use crate::sanitizer;
 use crate::singly_linked_list::SinglyLinkedListOps;
+#[cfg(feature = "stats")]
+use crate::stats::{Stats, StatsCounters};
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+use crate::sync::{AtomicPtr, Ordering};
 use crate::xor_linked_list::XorLinkedListOps;
 use crate::Adapter;
 // Necessary for Rust 1.56 compatability
@@ -27,6 +69,11 @@ use crate::unchecked_option::UncheckedOptionExt;
 // =============================================================================
 
 /// Link operations for `LinkedList`.
+///
+/// # Safety
+///
+/// Implementations must correctly maintain the links of the objects passed
+/// to them, since collections rely on these operations for memory safety.
 pub unsafe trait LinkedListOps: link_ops::LinkOps {
     /// Returns the "next" link pointer of `ptr`.
     ///
@@ -51,6 +98,114 @@ pub unsafe trait LinkedListOps: link_ops::LinkOps {
     /// # Safety
     /// An implementation of `set_prev` must not panic.
     unsafe fn set_prev(&mut self, ptr: Self::LinkPtr, prev: Option<Self::LinkPtr>);
+
+    /// Returns the number of bits available for the user tag of a link, or 0
+    /// if the link type has no room for a tag.
+    ///
+    /// Link types which don't store a tag can leave `tag_bits`, `tag` and
+    /// `set_tag` unimplemented.
+    #[inline]
+    fn tag_bits(&self) -> u32 {
+        0
+    }
+
+    /// Returns the user tag of `ptr`.
+    ///
+    /// # Safety
+    /// An implementation of `tag` must not panic.
+    #[inline]
+    unsafe fn tag(&self, ptr: Self::LinkPtr) -> u8 {
+        let _ = ptr;
+        0
+    }
+
+    /// Sets the user tag of `ptr`. `tag` always fits in `tag_bits` bits.
+    ///
+    /// This takes `&self` so that cursors which only borrow the list can tag
+    /// elements, the tag must use interior mutability.
+    ///
+    /// # Safety
+    /// An implementation of `set_tag` must not panic.
+    #[inline]
+    unsafe fn set_tag(&self, ptr: Self::LinkPtr, tag: u8) {
+        let _ = (ptr, tag);
+    }
+
+    /// Records the identifier of the `LinkedList` that `ptr` was inserted
+    /// into, for the `debug_owner` feature.
+    ///
+    /// Link types which don't store an owner can leave this unimplemented.
+    ///
+    /// # Safety
+    /// An implementation of `set_owner` must not panic.
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn set_owner(&mut self, ptr: Self::LinkPtr, owner: usize) {
+        let _ = (ptr, owner);
+    }
+
+    /// Returns the identifier recorded by `set_owner`, 0 if the link was
+    /// released since, or `None` if the link type doesn't store an owner.
+    ///
+    /// # Safety
+    /// An implementation of `owner` must not panic.
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn owner(&self, ptr: Self::LinkPtr) -> Option<usize> {
+        let _ = ptr;
+        None
+    }
+}
+
+unsafe impl<W: LinkWrapper, O: LinkedListOps<LinkPtr = NonNull<W::Link>>> LinkedListOps
+    for WrapperLinkOps<W, O>
+{
+    #[inline]
+    unsafe fn next(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
+        wrapper_ptr(self.ops.next(ptr.cast()))
+    }
+
+    #[inline]
+    unsafe fn prev(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
+        wrapper_ptr(self.ops.prev(ptr.cast()))
+    }
+
+    #[inline]
+    unsafe fn set_next(&mut self, ptr: Self::LinkPtr, next: Option<Self::LinkPtr>) {
+        self.ops.set_next(ptr.cast(), next.map(NonNull::cast));
+    }
+
+    #[inline]
+    unsafe fn set_prev(&mut self, ptr: Self::LinkPtr, prev: Option<Self::LinkPtr>) {
+        self.ops.set_prev(ptr.cast(), prev.map(NonNull::cast));
+    }
+
+    #[inline]
+    fn tag_bits(&self) -> u32 {
+        self.ops.tag_bits()
+    }
+
+    #[inline]
+    unsafe fn tag(&self, ptr: Self::LinkPtr) -> u8 {
+        self.ops.tag(ptr.cast())
+    }
+
+    #[inline]
+    unsafe fn set_tag(&self, ptr: Self::LinkPtr, tag: u8) {
+        self.ops.set_tag(ptr.cast(), tag);
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn set_owner(&mut self, ptr: Self::LinkPtr, owner: usize) {
+        self.ops.set_owner(ptr.cast(), owner);
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn owner(&self, ptr: Self::LinkPtr) -> Option<usize> {
+        self.ops.owner(ptr.cast())
+    }
 }
 
 // =============================================================================
@@ -59,16 +214,37 @@ pub unsafe trait LinkedListOps: link_ops::LinkOps {
 
 /// Intrusive link that allows an object to be inserted into a
 /// `LinkedList`.
-#[repr(align(2))]
+///
+/// The link is `repr(C)` with the layout of `struct ic_linked_list_link` in
+/// the header written by `c_header::write_c_header`: a `next` pointer
+/// followed by a `prev` pointer, with `next` set to 1 while unlinked. The
+/// `debug_owner` feature appends an `owner` field.
+///
+/// While the link is in a list, the low `TAG_BITS` bits of `prev` hold a
+/// user tag, which can mark an element (for example as cancelled) without
+/// adding a field to it or needing a mutable reference to it. C code must
+/// mask them off with `IC_LINKED_LIST_TAG_MASK` to get the `prev` pointer.
+#[repr(C, align(4))]
 pub struct Link {
     next: Cell<Option<NonNull<Link>>>,
     prev: Cell<Option<NonNull<Link>>>,
+    #[cfg(feature = "debug_owner")]
+    owner: Cell<usize>,
 }
 
 // Use a special value to indicate an unlinked node
 const UNLINKED_MARKER: Option<NonNull<Link>> =
     unsafe { Some(NonNull::new_unchecked(1 as *mut Link)) };
 
+// The bits of `prev` which hold the user tag. `Link` is aligned to 4 bytes so
+// these are always 0 in a link pointer.
+pub(crate) const TAG_MASK: usize = (1 << Link::TAG_BITS) - 1;
+
+#[inline]
+fn link_addr(ptr: Option<NonNull<Link>>) -> usize {
+    ptr.map(|x| x.as_ptr() as usize).unwrap_or(0)
+}
+
 impl Link {
     /// Creates a new `Link`.
     #[inline]
@@ -76,15 +252,51 @@ impl Link {
         Link {
             next: Cell::new(UNLINKED_MARKER),
             prev: Cell::new(UNLINKED_MARKER),
+            #[cfg(feature = "debug_owner")]
+            owner: Cell::new(0),
         }
     }
 
+    /// Number of bits available for the user tag of a `Link`.
+    pub const TAG_BITS: u32 = 2;
+
     /// Checks whether the `Link` is linked into a `LinkedList`.
     #[inline]
     pub fn is_linked(&self) -> bool {
         self.next.get() != UNLINKED_MARKER
     }
 
+    /// Returns the user tag of the `Link`, or 0 if it isn't linked into a
+    /// `LinkedList`.
+    ///
+    /// The tag is reset to 0 when the object is removed from its list.
+    #[inline]
+    pub fn tag(&self) -> u8 {
+        if self.is_linked() {
+            (link_addr(self.prev.get()) & TAG_MASK) as u8
+        } else {
+            0
+        }
+    }
+
+    /// Sets the user tag of a `Link` which is linked into a `LinkedList`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the link isn't linked, or if `tag` doesn't fit in `TAG_BITS`
+    /// bits.
+    #[inline]
+    pub fn set_tag(&self, tag: u8) {
+        assert!(
+            self.is_linked(),
+            "attempted to tag an object which is not linked"
+        );
+        assert!(tag as usize <= TAG_MASK, "tag does not fit in the link");
+        let prev = link_addr(self.prev.get()) & !TAG_MASK;
+        self.prev
+            .set(NonNull::new((prev | tag as usize) as *mut Link));
+    }
+
     /// Forcibly unlinks an object from a `LinkedList`.
     ///
     /// # Safety
@@ -140,6 +352,19 @@ impl fmt::Debug for Link {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for Link {
+    #[inline]
+    fn format(&self, f: defmt::Formatter<'_>) {
+        let state = if self.is_linked() {
+            "linked"
+        } else {
+            "unlinked"
+        };
+        defmt::write!(f, "{=str}", state)
+    }
+}
+
 // =============================================================================
 // LinkOps
 // =============================================================================
@@ -151,31 +376,50 @@ pub struct LinkOps;
 unsafe impl link_ops::LinkOps for LinkOps {
     type LinkPtr = NonNull<Link>;
 
+    const LINK_SIZE: Option<usize> = Some(mem::size_of::<Link>());
+
     #[inline]
     unsafe fn acquire_link(&mut self, ptr: Self::LinkPtr) -> bool {
         if ptr.as_ref().is_linked() {
             false
         } else {
             ptr.as_ref().next.set(None);
+            // Clears the tag bits, `set_prev` keeps them.
+            sanitizer::unpoison(&ptr.as_ref().prev);
+            ptr.as_ref().prev.set(None);
             true
         }
     }
 
     #[inline]
     unsafe fn release_link(&mut self, ptr: Self::LinkPtr) {
+        if cfg!(debug_assertions) {
+            ptr.as_ref().prev.set(poison_ptr());
+        }
+        sanitizer::poison(&ptr.as_ref().prev);
+        #[cfg(feature = "debug_owner")]
+        ptr.as_ref().owner.set(0);
         ptr.as_ref().next.set(UNLINKED_MARKER);
     }
 }
 
+unsafe impl link_ops::LinkStateOps for LinkOps {
+    #[inline]
+    unsafe fn is_linked(&self, ptr: Self::LinkPtr) -> bool {
+        ptr.as_ref().is_linked()
+    }
+}
+
 unsafe impl LinkedListOps for LinkOps {
     #[inline]
     unsafe fn next(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
-        ptr.as_ref().next.get()
+        check_link_ptr(ptr.as_ref().next.get())
     }
 
     #[inline]
     unsafe fn prev(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
-        ptr.as_ref().prev.get()
+        let raw = check_tagged_link_value(link_addr(ptr.as_ref().prev.get()));
+        NonNull::new((raw & !TAG_MASK) as *mut Link)
     }
 
     #[inline]
@@ -185,14 +429,47 @@ unsafe impl LinkedListOps for LinkOps {
 
     #[inline]
     unsafe fn set_prev(&mut self, ptr: Self::LinkPtr, prev: Option<Self::LinkPtr>) {
-        ptr.as_ref().prev.set(prev);
+        let tag = link_addr(ptr.as_ref().prev.get()) & TAG_MASK;
+        ptr.as_ref()
+            .prev
+            .set(NonNull::new((link_addr(prev) | tag) as *mut Link));
+    }
+
+    #[inline]
+    fn tag_bits(&self) -> u32 {
+        Link::TAG_BITS
+    }
+
+    #[inline]
+    unsafe fn tag(&self, ptr: Self::LinkPtr) -> u8 {
+        (link_addr(ptr.as_ref().prev.get()) & TAG_MASK) as u8
+    }
+
+    #[inline]
+    unsafe fn set_tag(&self, ptr: Self::LinkPtr, tag: u8) {
+        let prev = link_addr(ptr.as_ref().prev.get()) & !TAG_MASK;
+        ptr.as_ref()
+            .prev
+            .set(NonNull::new((prev | tag as usize) as *mut Link));
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn set_owner(&mut self, ptr: Self::LinkPtr, owner: usize) {
+        ptr.as_ref().owner.set(owner);
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn owner(&self, ptr: Self::LinkPtr) -> Option<usize> {
+        Some(ptr.as_ref().owner.get())
     }
 }
 
 unsafe impl SinglyLinkedListOps for LinkOps {
     #[inline]
     unsafe fn next(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
-        ptr.as_ref().next.get()
+        check_link_ptr(ptr.as_ref().next.get())
     }
 
     #[inline]
@@ -208,10 +485,7 @@ unsafe impl XorLinkedListOps for LinkOps {
         ptr: Self::LinkPtr,
         prev: Option<Self::LinkPtr>,
     ) -> Option<Self::LinkPtr> {
-        let packed = ptr
-            .as_ref()
-            .next
-            .get()
+        let packed = check_link_ptr(ptr.as_ref().next.get())
             .map(|x| x.as_ptr() as usize)
             .unwrap_or(0);
         let raw = packed ^ prev.map(|x| x.as_ptr() as usize).unwrap_or(0);
@@ -224,10 +498,7 @@ unsafe impl XorLinkedListOps for LinkOps {
         ptr: Self::LinkPtr,
         next: Option<Self::LinkPtr>,
     ) -> Option<Self::LinkPtr> {
-        let packed = ptr
-            .as_ref()
-            .next
-            .get()
+        let packed = check_link_ptr(ptr.as_ref().next.get())
             .map(|x| x.as_ptr() as usize)
             .unwrap_or(0);
         let raw = packed ^ next.map(|x| x.as_ptr() as usize).unwrap_or(0);
@@ -255,10 +526,7 @@ unsafe impl XorLinkedListOps for LinkOps {
         old: Option<Self::LinkPtr>,
         new: Option<Self::LinkPtr>,
     ) {
-        let packed = ptr
-            .as_ref()
-            .next
-            .get()
+        let packed = check_link_ptr(ptr.as_ref().next.get())
             .map(|x| x.as_ptr() as usize)
             .unwrap_or(0);
         let new_packed = packed
@@ -271,38 +539,45 @@ unsafe impl XorLinkedListOps for LinkOps {
 }
 
 // =============================================================================
-// AtomicLink
+// SentinelLink
 // =============================================================================
 
-/// Intrusive atomic link that allows an object to be inserted into a
-/// `LinkedList`. This link allows the structure to be shared between threads.
-#[repr(align(2))]
-pub struct AtomicLink {
-    next: AtomicPtr<AtomicLink>,
-    prev: Cell<Option<NonNull<AtomicLink>>>,
+/// Intrusive link that allows an object to be inserted into a
+/// `LinkedList`, which marks itself as unlinked by storing the address chosen
+/// by `S` in its `next` pointer.
+///
+/// This has the same layout as `Link`, with a different unlinked encoding. It
+/// is meant for links shared with C code which already has a convention for
+/// unlinked nodes, for example pointing them to a static dummy node. With
+/// `DefaultSentinel` it behaves exactly like `Link`, except that `new` isn't
+/// a `const fn`.
+#[repr(C, align(2))]
+pub struct SentinelLink<S: LinkSentinel = DefaultSentinel> {
+    next: Cell<Option<NonNull<SentinelLink<S>>>>,
+    prev: Cell<Option<NonNull<SentinelLink<S>>>>,
+    marker: PhantomData<fn() -> S>,
 }
 
-// Use a special value to indicate an unlinked node
-const ATOMIC_UNLINKED_MARKER_PTR: *mut AtomicLink = 1 as *mut AtomicLink;
-
-// Use a special value to indicate an unlinked node
-const ATOMIC_UNLINKED_MARKER: Option<NonNull<AtomicLink>> =
-    unsafe { Some(NonNull::new_unchecked(ATOMIC_UNLINKED_MARKER_PTR)) };
-
-impl AtomicLink {
-    /// Creates a new `AtomicLink`.
+impl<S: LinkSentinel> SentinelLink<S> {
+    /// Creates a new `SentinelLink`.
     #[inline]
-    pub const fn new() -> AtomicLink {
-        Self {
-            next: AtomicPtr::new(ATOMIC_UNLINKED_MARKER_PTR),
-            prev: Cell::new(ATOMIC_UNLINKED_MARKER),
+    pub fn new() -> SentinelLink<S> {
+        SentinelLink {
+            next: Cell::new(Self::unlinked_marker()),
+            prev: Cell::new(Self::unlinked_marker()),
+            marker: PhantomData,
         }
     }
 
-    /// Checks whether the `AtomicLink` is linked into a `LinkedList`.
+    #[inline]
+    fn unlinked_marker() -> Option<NonNull<SentinelLink<S>>> {
+        Some(S::unlinked().cast())
+    }
+
+    /// Checks whether the `SentinelLink` is linked into a `LinkedList`.
     #[inline]
     pub fn is_linked(&self) -> bool {
-        self.next.load(Ordering::Relaxed) != ATOMIC_UNLINKED_MARKER_PTR
+        self.next.get() != Self::unlinked_marker()
     }
 
     /// Forcibly unlinks an object from a `LinkedList`.
@@ -315,51 +590,39 @@ impl AtomicLink {
     /// the collection without marking the nodes as unlinked.
     #[inline]
     pub unsafe fn force_unlink(&self) {
-        self.next
-            .store(ATOMIC_UNLINKED_MARKER_PTR, Ordering::Release)
-    }
-
-    /// Access the `next` pointer in an exclusive context.
-    ///
-    /// # Safety
-    ///
-    /// This can only be called after `acquire_link` has been succesfully called.
-    #[inline]
-    unsafe fn next_exclusive(&self) -> &Cell<Option<NonNull<AtomicLink>>> {
-        // This is safe because currently AtomicPtr<AtomicLink> has the same representation Cell<Option<NonNull<AtomicLink>>>.
-        core::mem::transmute(&self.next)
+        self.next.set(Self::unlinked_marker());
     }
 }
 
-impl DefaultLinkOps for AtomicLink {
-    type Ops = AtomicLinkOps;
+impl<S: LinkSentinel> DefaultLinkOps for SentinelLink<S> {
+    type Ops = SentinelLinkOps<S>;
 
-    const NEW: Self::Ops = AtomicLinkOps;
+    const NEW: Self::Ops = SentinelLinkOps(PhantomData);
 }
 
-// An object containing a link can be sent to another thread since `acquire_link` is atomic.
-unsafe impl Send for AtomicLink {}
-
-// An object containing a link can be shared between threads since `acquire_link` is atomic.
-unsafe impl Sync for AtomicLink {}
+// An object containing a link can be sent to another thread if it is unlinked.
+unsafe impl<S: LinkSentinel> Send for SentinelLink<S> {}
 
-impl Clone for AtomicLink {
+// Provide an implementation of Clone which simply initializes the new link as
+// unlinked. This allows structs containing a link to derive Clone.
+impl<S: LinkSentinel> Clone for SentinelLink<S> {
     #[inline]
-    fn clone(&self) -> AtomicLink {
-        AtomicLink::new()
+    fn clone(&self) -> SentinelLink<S> {
+        SentinelLink::new()
     }
 }
 
-impl Default for AtomicLink {
+// Same as above
+impl<S: LinkSentinel> Default for SentinelLink<S> {
     #[inline]
-    fn default() -> AtomicLink {
-        AtomicLink::new()
+    fn default() -> SentinelLink<S> {
+        SentinelLink::new()
     }
 }
 
 // Provide an implementation of Debug so that structs containing a link can
 // still derive Debug.
-impl fmt::Debug for AtomicLink {
+impl<S: LinkSentinel> fmt::Debug for SentinelLink<S> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // There isn't anything sensible to print here except whether the link
@@ -372,42 +635,74 @@ impl fmt::Debug for AtomicLink {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl<S: LinkSentinel> defmt::Format for SentinelLink<S> {
+    #[inline]
+    fn format(&self, f: defmt::Formatter<'_>) {
+        let state = if self.is_linked() {
+            "linked"
+        } else {
+            "unlinked"
+        };
+        defmt::write!(f, "{=str}", state)
+    }
+}
+
 // =============================================================================
-// AtomicLinkOps
+// SentinelLinkOps
 // =============================================================================
 
-/// Default `AtomicLinkOps` implementation for `LinkedList`.
-#[derive(Clone, Copy, Default)]
-pub struct AtomicLinkOps;
+/// Default `LinkOps` implementation for `LinkedList` using `SentinelLink`.
+pub struct SentinelLinkOps<S>(PhantomData<fn() -> S>);
 
-unsafe impl link_ops::LinkOps for AtomicLinkOps {
-    type LinkPtr = NonNull<AtomicLink>;
+impl<S> Clone for SentinelLinkOps<S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for SentinelLinkOps<S> {}
+
+impl<S> Default for SentinelLinkOps<S> {
+    #[inline]
+    fn default() -> Self {
+        SentinelLinkOps(PhantomData)
+    }
+}
+
+unsafe impl<S: LinkSentinel> link_ops::LinkOps for SentinelLinkOps<S> {
+    type LinkPtr = NonNull<SentinelLink<S>>;
+
+    const LINK_SIZE: Option<usize> = Some(mem::size_of::<SentinelLink<S>>());
 
     #[inline]
     unsafe fn acquire_link(&mut self, ptr: Self::LinkPtr) -> bool {
-        ptr.as_ref()
-            .next
-            .compare_exchange(
-                ATOMIC_UNLINKED_MARKER_PTR,
-                null_mut(),
-                Ordering::Acquire,
-                Ordering::Relaxed,
-            )
-            .is_ok()
+        if ptr.as_ref().is_linked() {
+            false
+        } else {
+            ptr.as_ref().next.set(None);
+            true
+        }
     }
 
     #[inline]
     unsafe fn release_link(&mut self, ptr: Self::LinkPtr) {
-        ptr.as_ref()
-            .next
-            .store(ATOMIC_UNLINKED_MARKER_PTR, Ordering::Release)
+        ptr.as_ref().force_unlink();
     }
 }
 
-unsafe impl LinkedListOps for AtomicLinkOps {
+unsafe impl<S: LinkSentinel> link_ops::LinkStateOps for SentinelLinkOps<S> {
+    #[inline]
+    unsafe fn is_linked(&self, ptr: Self::LinkPtr) -> bool {
+        ptr.as_ref().is_linked()
+    }
+}
+
+unsafe impl<S: LinkSentinel> LinkedListOps for SentinelLinkOps<S> {
     #[inline]
     unsafe fn next(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
-        ptr.as_ref().next_exclusive().get()
+        ptr.as_ref().next.get()
     }
 
     #[inline]
@@ -417,7 +712,7 @@ unsafe impl LinkedListOps for AtomicLinkOps {
 
     #[inline]
     unsafe fn set_next(&mut self, ptr: Self::LinkPtr, next: Option<Self::LinkPtr>) {
-        ptr.as_ref().next_exclusive().set(next);
+        ptr.as_ref().next.set(next);
     }
 
     #[inline]
@@ -426,29 +721,310 @@ unsafe impl LinkedListOps for AtomicLinkOps {
     }
 }
 
-unsafe impl SinglyLinkedListOps for AtomicLinkOps {
-    #[inline]
-    unsafe fn next(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
-        ptr.as_ref().next_exclusive().get()
+// =============================================================================
+// AtomicLink
+// =============================================================================
+
+/// Intrusive atomic link that allows an object to be inserted into a
+/// `LinkedList`. This link allows the structure to be shared between threads.
+///
+/// Whether the link is in a list is tracked atomically, so `is_linked` can be
+/// called from any thread while another thread owns the list, and an object
+/// can only be inserted into one list at a time even if several threads race
+/// to insert it.
+///
+/// The link has the same layout as `Link`, described in the header written by
+/// `c_header::write_c_header` as `struct ic_linked_list_atomic_link`. C code
+/// must access its `next` field atomically.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+#[repr(C, align(2))]
+pub struct AtomicLink {
+    next: AtomicPtr<AtomicLink>,
+    prev: Cell<Option<NonNull<AtomicLink>>>,
+    // Atomic since it is read when checking a link which may be owned by a
+    // list on another thread.
+    #[cfg(feature = "debug_owner")]
+    owner: crate::sync::AtomicUsize,
+}
+
+// Use a special value to indicate an unlinked node
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+const ATOMIC_UNLINKED_MARKER_PTR: *mut AtomicLink = 1 as *mut AtomicLink;
+
+// Marks a link claimed by `try_acquire_for_insertion`. Links are aligned to
+// 2 bytes, so this is never the address of a link.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+pub(crate) const ATOMIC_CLAIMED_MARKER_ADDR: usize = 3;
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+const ATOMIC_CLAIMED_MARKER_PTR: *mut AtomicLink = ATOMIC_CLAIMED_MARKER_ADDR as *mut AtomicLink;
+
+// Use a special value to indicate an unlinked node
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+const ATOMIC_UNLINKED_MARKER: Option<NonNull<AtomicLink>> =
+    unsafe { Some(NonNull::new_unchecked(ATOMIC_UNLINKED_MARKER_PTR)) };
+
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+impl AtomicLink {
+    loom_const_fn! {
+        /// Creates a new `AtomicLink`.
+        #[inline]
+        pub fn new() -> AtomicLink {
+            Self {
+                next: AtomicPtr::new(ATOMIC_UNLINKED_MARKER_PTR),
+                prev: Cell::new(ATOMIC_UNLINKED_MARKER),
+                #[cfg(feature = "debug_owner")]
+                owner: crate::sync::AtomicUsize::new(0),
+            }
+        }
     }
 
+    /// Checks whether the `AtomicLink` is linked into a `LinkedList`.
+    ///
+    /// This can be called from any thread. The load has acquire ordering, so
+    /// if it returns `false`, everything the thread which removed the object
+    /// did before removing it is visible to the caller. An object claimed
+    /// with `try_acquire_for_insertion` counts as linked.
     #[inline]
-    unsafe fn set_next(&mut self, ptr: Self::LinkPtr, next: Option<Self::LinkPtr>) {
-        ptr.as_ref().next_exclusive().set(next);
+    pub fn is_linked(&self) -> bool {
+        self.next.load(Ordering::Acquire) != ATOMIC_UNLINKED_MARKER_PTR
     }
-}
 
-unsafe impl XorLinkedListOps for AtomicLinkOps {
+    /// Atomically marks an unlinked `AtomicLink` as about to be inserted into a
+    /// `LinkedList`, returning `false` if it is linked or already claimed.
+    ///
+    /// This lets several threads race to queue the same object: only the one
+    /// which got `true` goes on to insert it, and `is_linked` returns `true`
+    /// from the moment it is claimed. Collections treat a claimed object as
+    /// linked, so the claim must be dropped with `force_unlink` before the
+    /// object is inserted.
     #[inline]
-    unsafe fn next(
-        &self,
-        ptr: Self::LinkPtr,
+    pub fn try_acquire_for_insertion(&self) -> bool {
+        self.next
+            .compare_exchange(
+                ATOMIC_UNLINKED_MARKER_PTR,
+                ATOMIC_CLAIMED_MARKER_PTR,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            )
+            .is_ok()
+    }
+
+    /// Forcibly unlinks an object from a `LinkedList`.
+    ///
+    /// # Safety
+    ///
+    /// It is undefined behavior to call this function while still linked into a
+    /// `LinkedList`. The only situation where this function is useful is
+    /// after calling `fast_clear` on a `LinkedList`, since this clears
+    /// the collection without marking the nodes as unlinked.
+    ///
+    /// It can also be used to drop a claim taken with
+    /// `try_acquire_for_insertion`, before inserting the object or if it
+    /// won't be inserted.
+    #[inline]
+    pub unsafe fn force_unlink(&self) {
+        self.next
+            .store(ATOMIC_UNLINKED_MARKER_PTR, Ordering::Release)
+    }
+
+    /// Reads the `next` pointer in an exclusive context.
+    ///
+    /// The pointer is still accessed atomically, since other threads may
+    /// call `is_linked` while the list is being modified.
+    ///
+    /// # Safety
+    ///
+    /// This can only be called after `acquire_link` has been succesfully called.
+    #[inline]
+    unsafe fn load_next(&self) -> Option<NonNull<AtomicLink>> {
+        NonNull::new(self.next.load(Ordering::Relaxed))
+    }
+
+    /// Reads the `next` pointer from a reader of a `SeqLocked` list, which
+    /// may run while another thread modifies the list. The result must be
+    /// validated before it is dereferenced.
+    #[inline]
+    pub(crate) fn load_next_racy(&self) -> *mut AtomicLink {
+        self.next.load(Ordering::Relaxed)
+    }
+
+    /// Writes the `next` pointer in an exclusive context.
+    ///
+    /// # Safety
+    ///
+    /// This can only be called after `acquire_link` has been succesfully called.
+    #[inline]
+    unsafe fn store_next(&self, next: Option<NonNull<AtomicLink>>) {
+        self.next.store(
+            next.map(|x| x.as_ptr()).unwrap_or(null_mut()),
+            Ordering::Relaxed,
+        );
+    }
+}
+
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+impl DefaultLinkOps for AtomicLink {
+    type Ops = AtomicLinkOps;
+
+    const NEW: Self::Ops = AtomicLinkOps;
+}
+
+// An object containing a link can be sent to another thread since `acquire_link` is atomic.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+unsafe impl Send for AtomicLink {}
+
+// An object containing a link can be shared between threads since `acquire_link` is atomic.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+unsafe impl Sync for AtomicLink {}
+
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+impl Clone for AtomicLink {
+    #[inline]
+    fn clone(&self) -> AtomicLink {
+        AtomicLink::new()
+    }
+}
+
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+impl Default for AtomicLink {
+    #[inline]
+    fn default() -> AtomicLink {
+        AtomicLink::new()
+    }
+}
+
+// Provide an implementation of Debug so that structs containing a link can
+// still derive Debug.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+impl fmt::Debug for AtomicLink {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // There isn't anything sensible to print here except whether the link
+        // is currently in a list.
+        if self.is_linked() {
+            write!(f, "linked")
+        } else {
+            write!(f, "unlinked")
+        }
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for AtomicLink {
+    #[inline]
+    fn format(&self, f: defmt::Formatter<'_>) {
+        let state = if self.is_linked() {
+            "linked"
+        } else {
+            "unlinked"
+        };
+        defmt::write!(f, "{=str}", state)
+    }
+}
+
+// =============================================================================
+// AtomicLinkOps
+// =============================================================================
+
+/// Default `AtomicLinkOps` implementation for `LinkedList`.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+#[derive(Clone, Copy, Default)]
+pub struct AtomicLinkOps;
+
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+unsafe impl link_ops::LinkOps for AtomicLinkOps {
+    type LinkPtr = NonNull<AtomicLink>;
+
+    const LINK_SIZE: Option<usize> = Some(mem::size_of::<AtomicLink>());
+
+    #[inline]
+    unsafe fn acquire_link(&mut self, ptr: Self::LinkPtr) -> bool {
+        let next = &ptr.as_ref().next;
+        next.compare_exchange(
+            ATOMIC_UNLINKED_MARKER_PTR,
+            null_mut(),
+            Ordering::Acquire,
+            Ordering::Relaxed,
+        )
+        .is_ok()
+    }
+
+    #[inline]
+    unsafe fn release_link(&mut self, ptr: Self::LinkPtr) {
+        #[cfg(feature = "debug_owner")]
+        ptr.as_ref().owner.store(0, Ordering::Relaxed);
+        ptr.as_ref()
+            .next
+            .store(ATOMIC_UNLINKED_MARKER_PTR, Ordering::Release)
+    }
+}
+
+unsafe impl link_ops::LinkStateOps for AtomicLinkOps {
+    #[inline]
+    unsafe fn is_linked(&self, ptr: Self::LinkPtr) -> bool {
+        ptr.as_ref().is_linked()
+    }
+}
+
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+unsafe impl LinkedListOps for AtomicLinkOps {
+    #[inline]
+    unsafe fn next(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
+        ptr.as_ref().load_next()
+    }
+
+    #[inline]
+    unsafe fn prev(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
+        ptr.as_ref().prev.get()
+    }
+
+    #[inline]
+    unsafe fn set_next(&mut self, ptr: Self::LinkPtr, next: Option<Self::LinkPtr>) {
+        ptr.as_ref().store_next(next);
+    }
+
+    #[inline]
+    unsafe fn set_prev(&mut self, ptr: Self::LinkPtr, prev: Option<Self::LinkPtr>) {
+        ptr.as_ref().prev.set(prev);
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn set_owner(&mut self, ptr: Self::LinkPtr, owner: usize) {
+        ptr.as_ref().owner.store(owner, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn owner(&self, ptr: Self::LinkPtr) -> Option<usize> {
+        Some(ptr.as_ref().owner.load(Ordering::Relaxed))
+    }
+}
+
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+unsafe impl SinglyLinkedListOps for AtomicLinkOps {
+    #[inline]
+    unsafe fn next(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
+        ptr.as_ref().load_next()
+    }
+
+    #[inline]
+    unsafe fn set_next(&mut self, ptr: Self::LinkPtr, next: Option<Self::LinkPtr>) {
+        ptr.as_ref().store_next(next);
+    }
+}
+
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+unsafe impl XorLinkedListOps for AtomicLinkOps {
+    #[inline]
+    unsafe fn next(
+        &self,
+        ptr: Self::LinkPtr,
         prev: Option<Self::LinkPtr>,
     ) -> Option<Self::LinkPtr> {
         let packed = ptr
             .as_ref()
-            .next_exclusive()
-            .get()
+            .load_next()
             .map(|x| x.as_ptr() as usize)
             .unwrap_or(0);
         let raw = packed ^ prev.map(|x| x.as_ptr() as usize).unwrap_or(0);
@@ -463,8 +1039,7 @@ unsafe impl XorLinkedListOps for AtomicLinkOps {
     ) -> Option<Self::LinkPtr> {
         let packed = ptr
             .as_ref()
-            .next_exclusive()
-            .get()
+            .load_next()
             .map(|x| x.as_ptr() as usize)
             .unwrap_or(0);
         let raw = packed ^ next.map(|x| x.as_ptr() as usize).unwrap_or(0);
@@ -482,7 +1057,7 @@ unsafe impl XorLinkedListOps for AtomicLinkOps {
             ^ next.map(|x| x.as_ptr() as usize).unwrap_or(0);
 
         let new_next = NonNull::new(new_packed as *mut _);
-        ptr.as_ref().next_exclusive().set(new_next);
+        ptr.as_ref().store_next(new_next);
     }
 
     #[inline]
@@ -494,8 +1069,7 @@ unsafe impl XorLinkedListOps for AtomicLinkOps {
     ) {
         let packed = ptr
             .as_ref()
-            .next_exclusive()
-            .get()
+            .load_next()
             .map(|x| x.as_ptr() as usize)
             .unwrap_or(0);
         let new_packed = packed
@@ -503,12 +1077,12 @@ unsafe impl XorLinkedListOps for AtomicLinkOps {
             ^ new.map(|x| x.as_ptr() as usize).unwrap_or(0);
 
         let new_next = NonNull::new(new_packed as *mut _);
-        ptr.as_ref().next_exclusive().set(new_next);
+        ptr.as_ref().store_next(new_next);
     }
 }
 
 #[inline]
-unsafe fn link_between<T: LinkedListOps>(
+pub(crate) unsafe fn link_between<T: LinkedListOps>(
     link_ops: &mut T,
     ptr: T::LinkPtr,
     prev: Option<T::LinkPtr>,
@@ -551,7 +1125,15 @@ unsafe fn replace_with<T: LinkedListOps>(link_ops: &mut T, ptr: T::LinkPtr, new:
 }
 
 #[inline]
-unsafe fn remove<T: LinkedListOps>(link_ops: &mut T, ptr: T::LinkPtr) {
+pub(crate) unsafe fn remove<T: LinkedListOps>(link_ops: &mut T, ptr: T::LinkPtr) {
+    unlink_neighbors(link_ops, ptr);
+    link_ops.release_link(ptr);
+}
+
+// Makes the neighbors of `ptr` point to each other, without touching the links
+// of `ptr` itself.
+#[inline]
+unsafe fn unlink_neighbors<T: LinkedListOps>(link_ops: &mut T, ptr: T::LinkPtr) {
     let prev = link_ops.prev(ptr);
     let next = link_ops.next(ptr);
 
@@ -561,7 +1143,6 @@ unsafe fn remove<T: LinkedListOps>(link_ops: &mut T, ptr: T::LinkPtr) {
     if let Some(prev) = prev {
         link_ops.set_next(prev, next);
     }
-    link_ops.release_link(ptr);
 }
 
 #[inline]
@@ -593,6 +1174,8 @@ where
 {
     current: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
     list: &'a LinkedList<A>,
+    #[cfg(feature = "debug_generation")]
+    generation: usize,
 }
 
 impl<'a, A: Adapter> Clone for Cursor<'a, A>
@@ -604,6 +1187,8 @@ where
         Cursor {
             current: self.current,
             list: self.list,
+            #[cfg(feature = "debug_generation")]
+            generation: self.generation,
         }
     }
 }
@@ -625,9 +1210,49 @@ where
     /// object.
     #[inline]
     pub fn get(&self) -> Option<&'a <A::PointerOps as PointerOps>::Value> {
+        #[cfg(feature = "debug_generation")]
+        self.list
+            .generation
+            .check(self.generation, self.list.debug_name());
         Some(unsafe { &*self.list.adapter.get_value(self.current?) })
     }
 
+    /// Returns the user tag of the element that the cursor is currently
+    /// pointing to, or 0 if the link type doesn't store a tag.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the null
+    /// object.
+    #[inline]
+    pub fn tag(&self) -> Option<u8> {
+        Some(unsafe { self.list.adapter.link_ops().tag(self.current?) })
+    }
+
+    /// Sets the user tag of the element that the cursor is currently
+    /// pointing to.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cursor is currently pointing to the null object, or if
+    /// `tag` doesn't fit in the `tag_bits` of the link type.
+    #[inline]
+    #[track_caller]
+    pub fn set_tag(&self, tag: u8) {
+        self.list.set_tag_of(self.current, tag);
+    }
+
+    /// Returns a pinned reference to the object that the cursor is currently
+    /// pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the null
+    /// object.
+    #[inline]
+    pub fn get_pin(&self) -> Option<Pin<&'a <A::PointerOps as PointerOps>::Value>>
+    where
+        A::PointerOps: PinnedPointerOps,
+    {
+        Some(unsafe { Pin::new_unchecked(self.get()?) })
+    }
+
     /// Clones and returns the pointer that points to the element that the
     /// cursor is referencing.
     ///
@@ -638,6 +1263,10 @@ where
     where
         <A::PointerOps as PointerOps>::Pointer: Clone,
     {
+        #[cfg(feature = "debug_generation")]
+        self.list
+            .generation
+            .check(self.generation, self.list.debug_name());
         let raw_pointer = unsafe { self.list.adapter.get_value(self.current?) };
         Some(unsafe {
             crate::pointer_ops::clone_pointer_from_raw(self.list.adapter.pointer_ops(), raw_pointer)
@@ -652,6 +1281,11 @@ where
     /// null object.
     #[inline]
     pub fn move_next(&mut self) {
+        #[cfg(feature = "debug_generation")]
+        self.list
+            .generation
+            .check(self.generation, self.list.debug_name());
+        record_stat!(self.list, cursor_moves);
         if let Some(current) = self.current {
             self.current = unsafe { self.list.adapter.link_ops().next(current) };
         } else {
@@ -666,6 +1300,11 @@ where
     /// element of the `LinkedList` then this will move it to the null object.
     #[inline]
     pub fn move_prev(&mut self) {
+        #[cfg(feature = "debug_generation")]
+        self.list
+            .generation
+            .check(self.generation, self.list.debug_name());
+        record_stat!(self.list, cursor_moves);
         if let Some(current) = self.current {
             self.current = unsafe { self.list.adapter.link_ops().prev(current) };
         } else {
@@ -727,6 +1366,45 @@ where
         Some(unsafe { &*self.list.adapter.get_value(self.current?) })
     }
 
+    /// Returns the user tag of the element that the cursor is currently
+    /// pointing to, or 0 if the link type doesn't store a tag.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the null
+    /// object.
+    #[inline]
+    pub fn tag(&self) -> Option<u8> {
+        Some(unsafe { self.list.adapter.link_ops().tag(self.current?) })
+    }
+
+    /// Sets the user tag of the element that the cursor is currently
+    /// pointing to.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cursor is currently pointing to the null object, or if
+    /// `tag` doesn't fit in the `tag_bits` of the link type.
+    #[inline]
+    #[track_caller]
+    pub fn set_tag(&self, tag: u8) {
+        self.list.set_tag_of(self.current, tag);
+    }
+
+    /// Clones and returns the pointer that points to the element that the
+    /// cursor is referencing.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the null
+    /// object.
+    #[inline]
+    pub fn clone_pointer(&self) -> Option<<A::PointerOps as PointerOps>::Pointer>
+    where
+        <A::PointerOps as PointerOps>::Pointer: Clone,
+    {
+        let raw_pointer = unsafe { self.list.adapter.get_value(self.current?) };
+        Some(unsafe {
+            crate::pointer_ops::clone_pointer_from_raw(self.list.adapter.pointer_ops(), raw_pointer)
+        })
+    }
+
     /// Returns a mutable reference to the object that the cursor is currently
     /// pointing to.
     ///
@@ -755,6 +1433,10 @@ where
     ///
     /// This returns None if the cursor is currently pointing to the null
     /// object.
+    ///
+    /// # Safety
+    ///
+    /// You must not modify any links that are linked
     #[inline]
     pub unsafe fn get_mut(&mut self) -> Option<&mut <A::PointerOps as PointerOps>::Value>
     where
@@ -765,10 +1447,57 @@ where
                 .list
                 .adapter
                 .pointer_ops()
-                .get_mut(self.list.adapter.get_value(self.current?)))
+                .get_mut(self.list.adapter.get_value(self.current?))),
         )
     }
 
+    /// Returns a pinned mutable reference to the object that the cursor is
+    /// currently pointing to.
+    ///
+    /// This returns None if the cursor is currently pointing to the null
+    /// object.
+    ///
+    /// # Safety
+    ///
+    /// You must not modify any links that are linked
+    #[inline]
+    pub unsafe fn get_pin_mut(&mut self) -> Option<Pin<&mut <A::PointerOps as PointerOps>::Value>>
+    where
+        A::PointerOps: ExclusivePinnedPointerOps,
+    {
+        let value = self.list.adapter.get_value(self.current?);
+        Some(Pin::new_unchecked(
+            &mut *self.list.adapter.pointer_ops().get_pin_mut(value),
+        ))
+    }
+
+    /// Returns a mutable reference to the object that the cursor is currently
+    /// pointing to, cloning it first if it is shared.
+    ///
+    /// If the object is not uniquely owned by the collection, it is replaced
+    /// in the `LinkedList` by a clone and the collection's pointer to the
+    /// original object is dropped. Other pointers to the original object keep
+    /// seeing the old value.
+    ///
+    /// This returns None if the cursor is currently pointing to the null
+    /// object.
+    ///
+    /// # Safety
+    ///
+    /// You must not modify any links that are linked
+    #[inline]
+    pub unsafe fn make_mut(&mut self) -> Option<&mut <A::PointerOps as PointerOps>::Value>
+    where
+        A::PointerOps: CowPointerOps,
+    {
+        let value = self.list.adapter.get_value(self.current?);
+        if self.list.adapter.pointer_ops().try_get_mut(value).is_none() {
+            let clone = self.list.adapter.pointer_ops().clone_value(&*value);
+            let _ = self.replace_with(clone);
+        }
+        self.try_get_mut()
+    }
+
     /// Returns a read-only cursor pointing to the current element.
     ///
     /// The lifetime of the returned `Cursor` is bound to that of the
@@ -779,17 +1508,33 @@ where
         Cursor {
             current: self.current,
             list: self.list,
+            #[cfg(feature = "debug_generation")]
+            generation: self.list.generation.get(),
         }
     }
 
-    /// Moves the cursor to the next element of the `LinkedList`.
-    ///
-    /// If the cursor is pointer to the null object then this will move it to
-    /// the first element of the `LinkedList`. If it is pointing to the
+    /// Consumes `CursorMut` and returns a read-only cursor pointing to the
+    /// current element. Unlike [as_cursor](Self::as_cursor), the returned
+    /// cursor's lifetime is tied to `LinkedList`'s lifetime.
+    #[inline]
+    pub fn into_cursor(self) -> Cursor<'a, A> {
+        Cursor {
+            current: self.current,
+            list: self.list,
+            #[cfg(feature = "debug_generation")]
+            generation: self.list.generation.get(),
+        }
+    }
+
+    /// Moves the cursor to the next element of the `LinkedList`.
+    ///
+    /// If the cursor is pointer to the null object then this will move it to
+    /// the first element of the `LinkedList`. If it is pointing to the
     /// last element of the `LinkedList` then this will move it to the
     /// null object.
     #[inline]
     pub fn move_next(&mut self) {
+        record_stat!(self.list, cursor_moves);
         if let Some(current) = self.current {
             self.current = unsafe { self.list.adapter.link_ops().next(current) };
         } else {
@@ -804,6 +1549,7 @@ where
     /// element of the `LinkedList` then this will move it to the null object.
     #[inline]
     pub fn move_prev(&mut self) {
+        record_stat!(self.list, cursor_moves);
         if let Some(current) = self.current {
             self.current = unsafe { self.list.adapter.link_ops().prev(current) };
         } else {
@@ -844,28 +1590,96 @@ where
     /// is removed and `None` is returned.
     #[inline]
     pub fn remove(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
-        unsafe {
-            if let Some(current) = self.current {
-                if self.list.head == self.current {
-                    self.list.head = self.list.adapter.link_ops().next(current);
-                }
-                if self.list.tail == self.current {
-                    self.list.tail = self.list.adapter.link_ops().prev(current);
-                }
-                let next = self.list.adapter.link_ops().next(current);
-                let result = current;
-                remove(self.list.adapter.link_ops_mut(), current);
-                self.current = next;
-                Some(
+        self.check_current_owner();
+        structural!(self.list, unsafe {
+            self.unlink_current()
+                .map(|current| crate::adapter::unlinked_pointer(&self.list.adapter, current))
+        })
+    }
+
+    /// Removes the current element from the `LinkedList`, converting it back
+    /// with `TryPointerOps::try_from_raw`.
+    ///
+    /// This is the same as `remove`, except that the conversion back into a
+    /// pointer may fail, in which case the error is returned. The element is
+    /// removed from the `LinkedList` either way.
+    ///
+    /// If the cursor is currently pointing to the null object then no element
+    /// is removed and `None` is returned.
+    #[inline]
+    pub fn remove_checked(&mut self) -> Option<TryPointer<A>>
+    where
+        A::PointerOps: TryPointerOps,
+    {
+        self.check_current_owner();
+        structural!(self.list, unsafe {
+            self.unlink_current()
+                .map(|current| crate::adapter::try_unlinked_pointer(&self.list.adapter, current))
+        })
+    }
+
+    // Checks that the current element is in this list, for the `debug_owner`
+    // feature. This is done before modifying the list, so that the panic
+    // doesn't poison it.
+    #[inline]
+    #[track_caller]
+    fn check_current_owner(&self) {
+        if let Some(current) = self.current {
+            unsafe { self.list.check_owner(current) };
+        }
+    }
+
+    // Unlinks the current element and moves the cursor to the next one,
+    // returning the unlinked element.
+    #[inline]
+    unsafe fn unlink_current(&mut self) -> Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr> {
+        let current = self.detach_current()?;
+        link_ops::LinkOps::release_link(self.list.adapter.link_ops_mut(), current);
+        Some(current)
+    }
+
+    // Same as `unlink_current`, but leaves the links of the element as they
+    // are instead of releasing them.
+    #[inline]
+    unsafe fn detach_current(&mut self) -> Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr> {
+        let current = self.current?;
+        if self.list.head == self.current {
+            self.list.head = self.list.adapter.link_ops().next(current);
+        }
+        if self.list.tail == self.current {
+            self.list.tail = self.list.adapter.link_ops().prev(current);
+        }
+        self.current = self.list.adapter.link_ops().next(current);
+        unlink_neighbors(self.list.adapter.link_ops_mut(), current);
+        record_stat!(self.list, removals);
+        Some(current)
+    }
+
+    /// Removes the current element from the `LinkedList` and adds it to
+    /// `retired`, to be reclaimed once concurrent readers are done with it.
+    ///
+    /// The links of the element are left untouched, so a reader which is on
+    /// it can still move to its old neighbors. The cursor is moved to point to
+    /// the next element in the `LinkedList`. See `RetireList` for details.
+    ///
+    /// Returns `false` if the cursor is currently pointing to the null
+    /// object, in which case no element is removed.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn retire(&mut self, retired: &mut RetireList<A>) -> bool {
+        self.check_current_owner();
+        structural!(self.list, unsafe {
+            match self.detach_current() {
+                Some(current) => {
                     self.list
                         .adapter
-                        .pointer_ops()
-                        .from_raw(self.list.adapter.get_value(result)),
-                )
-            } else {
-                None
+                        .on_remove(&*self.list.adapter.get_value(current));
+                    retired.push(current);
+                    true
+                }
+                None => false,
             }
-        }
+        })
     }
 
     /// Removes the current element from the `LinkedList` and inserts another
@@ -889,27 +1703,65 @@ where
     {
         unsafe {
             if let Some(current) = self.current {
+                self.list.check_owner(current);
                 let new = self.list.node_from_value(val);
-                if self.list.head == self.current {
-                    self.list.head = Some(new);
-                }
-                if self.list.tail == self.current {
-                    self.list.tail = Some(new);
-                }
-                let result = current;
-                replace_with(self.list.adapter.link_ops_mut(), current, new);
-                self.current = Some(new);
-                Ok(self
-                    .list
-                    .adapter
-                    .pointer_ops()
-                    .from_raw(self.list.adapter.get_value(result)))
+                Ok(structural!(self.list, self.replace_current(current, new)))
             } else {
                 Err(val)
             }
         }
     }
 
+    /// Removes the current element from the `LinkedList` and inserts another
+    /// object in its place, without panicking.
+    ///
+    /// This is the same as `replace_with`, except that an error containing
+    /// `val` is returned if the new element is already linked to an intrusive
+    /// collection, in which case the `LinkedList` is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// With the `debug_owner` feature, panics if the current element was
+    /// linked by a different `LinkedList`, like `replace_with`.
+    #[inline]
+    pub fn try_replace_with(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<
+        <A::PointerOps as PointerOps>::Pointer,
+        InsertError<<A::PointerOps as PointerOps>::Pointer>,
+    > {
+        unsafe {
+            match self.current {
+                Some(current) => {
+                    self.list.check_owner(current);
+                    self.list
+                        .try_node_from_value(val)
+                        .map(|new| structural!(self.list, self.replace_current(current, new)))
+                }
+                None => Err(InsertError::NullCursor(val)),
+            }
+        }
+    }
+
+    #[inline]
+    unsafe fn replace_current(
+        &mut self,
+        current: <A::LinkOps as link_ops::LinkOps>::LinkPtr,
+        new: <A::LinkOps as link_ops::LinkOps>::LinkPtr,
+    ) -> <A::PointerOps as PointerOps>::Pointer {
+        if self.list.head == self.current {
+            self.list.head = Some(new);
+        }
+        if self.list.tail == self.current {
+            self.list.tail = Some(new);
+        }
+        replace_with(self.list.adapter.link_ops_mut(), current, new);
+        self.current = Some(new);
+        record_stat!(self.list, removals);
+        crate::adapter::unlinked_pointer(&self.list.adapter, current)
+    }
+
     /// Inserts a new element into the `LinkedList` after the current one.
     ///
     /// If the cursor is pointing at the null object then the new element is
@@ -923,15 +1775,7 @@ where
     pub fn insert_after(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
         unsafe {
             let new = self.list.node_from_value(val);
-            if let Some(current) = self.current {
-                link_after(self.list.adapter.link_ops_mut(), new, current);
-            } else {
-                link_between(self.list.adapter.link_ops_mut(), new, None, self.list.head);
-                self.list.head = Some(new);
-            }
-            if self.list.tail == self.current {
-                self.list.tail = Some(new);
-            }
+            structural!(self.list, self.link_node_after(new));
         }
     }
 
@@ -948,75 +1792,277 @@ where
     pub fn insert_before(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
         unsafe {
             let new = self.list.node_from_value(val);
+            structural!(self.list, self.link_node_before(new));
+        }
+    }
 
-            let link_ops = self.list.adapter.link_ops_mut();
+    /// Inserts a new element into the `LinkedList` after the current one,
+    /// without panicking.
+    ///
+    /// This is the same as `insert_after`, except that an error containing
+    /// `val` is returned if the new element is already linked to an intrusive
+    /// collection.
+    #[inline]
+    pub fn try_insert_after(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<(), InsertError<<A::PointerOps as PointerOps>::Pointer>> {
+        unsafe {
+            self.list
+                .try_node_from_value(val)
+                .map(|new| structural!(self.list, self.link_node_after(new)))
+        }
+    }
 
-            if let Some(current) = self.current {
-                link_before(link_ops, new, current);
-            } else {
-                link_between(link_ops, new, self.list.tail, None);
-                self.list.tail = Some(new);
-            }
-            if self.list.head == self.current {
-                self.list.head = Some(new);
-            }
+    /// Inserts a new element into the `LinkedList` before the current one,
+    /// without panicking.
+    ///
+    /// This is the same as `insert_before`, except that an error containing
+    /// `val` is returned if the new element is already linked to an intrusive
+    /// collection.
+    #[inline]
+    pub fn try_insert_before(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<(), InsertError<<A::PointerOps as PointerOps>::Pointer>> {
+        unsafe {
+            self.list
+                .try_node_from_value(val)
+                .map(|new| structural!(self.list, self.link_node_before(new)))
+        }
+    }
+
+    #[inline]
+    unsafe fn link_node_after(&mut self, new: <A::LinkOps as link_ops::LinkOps>::LinkPtr) {
+        if let Some(current) = self.current {
+            link_after(self.list.adapter.link_ops_mut(), new, current);
+        } else {
+            link_between(self.list.adapter.link_ops_mut(), new, None, self.list.head);
+            self.list.head = Some(new);
+        }
+        if self.list.tail == self.current {
+            self.list.tail = Some(new);
+        }
+    }
+
+    #[inline]
+    unsafe fn link_node_before(&mut self, new: <A::LinkOps as link_ops::LinkOps>::LinkPtr) {
+        let link_ops = self.list.adapter.link_ops_mut();
+
+        if let Some(current) = self.current {
+            link_before(link_ops, new, current);
+        } else {
+            link_between(link_ops, new, self.list.tail, None);
+            self.list.tail = Some(new);
+        }
+        if self.list.head == self.current {
+            self.list.head = Some(new);
+        }
+    }
+
+    /// Moves the current element into another `LinkedList`, inserting it
+    /// after the element that `dest` is pointing to.
+    ///
+    /// The element is moved without converting it back into an owned pointer,
+    /// so reference counts are left untouched. The two lists may use different
+    /// links in the object, or the same one. The cursor is moved to the next
+    /// element, as with `remove`. This returns `false` if the cursor is
+    /// currently pointing to the null object.
+    ///
+    /// If `dest` is pointing at the null object then the element is inserted
+    /// at the start of its `LinkedList`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element is already linked to a different intrusive
+    /// collection through the link used by `dest`. The element is left in
+    /// this list in that case.
+    ///
+    /// Also panics if the `PointerOps` of the two lists don't have the same
+    /// state (see `PointerOps::same_state`), in which case neither list is
+    /// modified.
+    #[inline]
+    pub fn transfer_after<B>(&mut self, dest: &mut CursorMut<'_, B>) -> bool
+    where
+        B: Adapter<PointerOps = A::PointerOps>,
+        B::LinkOps: LinkedListOps,
+    {
+        crate::adapter::check_transfer(&self.list.adapter, &dest.list.adapter);
+        self.check_current_owner();
+        let result = structural!(
+            self.list,
+            structural!(dest.list, unsafe {
+                let result = self.unlink_for_transfer(dest.list);
+                if let Some(Ok(new)) = result {
+                    dest.link_node_after(new);
+                }
+                result
+            })
+        );
+        unsafe { dest.list.transferred(result) }
+    }
+
+    /// Moves the current element into another `LinkedList`, inserting it
+    /// before the element that `dest` is pointing to.
+    ///
+    /// This is the same as `transfer_after`, except that if `dest` is
+    /// pointing at the null object then the element is inserted at the end of
+    /// its `LinkedList`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element is already linked to a different intrusive
+    /// collection through the link used by `dest`. The element is left in
+    /// this list in that case.
+    ///
+    /// Also panics if the `PointerOps` of the two lists don't have the same
+    /// state (see `PointerOps::same_state`), in which case neither list is
+    /// modified.
+    #[inline]
+    pub fn transfer_before<B>(&mut self, dest: &mut CursorMut<'_, B>) -> bool
+    where
+        B: Adapter<PointerOps = A::PointerOps>,
+        B::LinkOps: LinkedListOps,
+    {
+        crate::adapter::check_transfer(&self.list.adapter, &dest.list.adapter);
+        self.check_current_owner();
+        let result = structural!(
+            self.list,
+            structural!(dest.list, unsafe {
+                let result = self.unlink_for_transfer(dest.list);
+                if let Some(Ok(new)) = result {
+                    dest.link_node_before(new);
+                }
+                result
+            })
+        );
+        unsafe { dest.list.transferred(result) }
+    }
+
+    // Unlinks the current element and acquires its link in `dest`. The
+    // element is put back if the link can't be acquired, which happens after
+    // unlinking so that both lists can use the same link, and its link in
+    // `dest` is returned as an error.
+    #[inline]
+    unsafe fn unlink_for_transfer<B>(
+        &mut self,
+        dest: &mut LinkedList<B>,
+    ) -> crate::adapter::Transfer<B>
+    where
+        B: Adapter<PointerOps = A::PointerOps>,
+        B::LinkOps: LinkedListOps,
+    {
+        use link_ops::LinkOps;
+
+        let current = self.current?;
+        let value = self.list.adapter.get_value(current);
+        if self.list.head == self.current {
+            self.list.head = self.list.adapter.link_ops().next(current);
         }
+        if self.list.tail == self.current {
+            self.list.tail = self.list.adapter.link_ops().prev(current);
+        }
+        self.current = self.list.adapter.link_ops().next(current);
+        remove(self.list.adapter.link_ops_mut(), current);
+
+        let new = dest.adapter.get_link(value);
+        if !dest.adapter.link_ops_mut().acquire_link(new) {
+            let acquired = self.list.adapter.link_ops_mut().acquire_link(current);
+            debug_assert!(acquired);
+            self.link_node_before(current);
+            self.current = Some(current);
+            return Some(Err(new));
+        }
+        #[cfg(feature = "debug_owner")]
+        {
+            let owner = dest.owner_id();
+            dest.adapter.link_ops_mut().set_owner(new, owner);
+        }
+        // If a hook panics, the guard drops the element, which is then in
+        // neither list.
+        let guard = crate::adapter::AcquiredLink::new(&mut dest.adapter, new);
+        self.list.adapter.on_remove(&*value);
+        guard.on_insert();
+        guard.disarm();
+        record_stat!(self.list, removals);
+        record_stat!(dest, insertions);
+        Some(Ok(new))
     }
 
     /// Inserts the elements from the given `LinkedList` after the current one.
     ///
     /// If the cursor is pointing at the null object then the new elements are
     /// inserted at the start of the `LinkedList`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `PointerOps` of the two lists don't have the same state
+    /// (see `PointerOps::same_state`).
     #[inline]
     pub fn splice_after(&mut self, mut list: LinkedList<A>) {
-        if !list.is_empty() {
-            unsafe {
-                let head = list.head.unwrap_unchecked();
-                let tail = list.tail.unwrap_unchecked();
-
-                let link_ops = self.list.adapter.link_ops_mut();
-
-                if let Some(current) = self.current {
-                    splice(link_ops, head, tail, Some(current), link_ops.next(current));
-                } else {
-                    splice(link_ops, head, tail, None, self.list.head);
-                    self.list.head = list.head;
-                }
-                if self.list.tail == self.current {
-                    self.list.tail = list.tail;
+        crate::adapter::check_transfer(&self.list.adapter, &list.adapter);
+        structural!(self.list, {
+            if !list.is_empty() {
+                #[cfg(feature = "debug_owner")]
+                list.set_owner_all(self.list.owner_id());
+                unsafe {
+                    let head = list.head.unwrap_unchecked();
+                    let tail = list.tail.unwrap_unchecked();
+
+                    let link_ops = self.list.adapter.link_ops_mut();
+
+                    if let Some(current) = self.current {
+                        splice(link_ops, head, tail, Some(current), link_ops.next(current));
+                    } else {
+                        splice(link_ops, head, tail, None, self.list.head);
+                        self.list.head = list.head;
+                    }
+                    if self.list.tail == self.current {
+                        self.list.tail = list.tail;
+                    }
+                    list.head = None;
+                    list.tail = None;
                 }
-                list.head = None;
-                list.tail = None;
             }
-        }
+        })
     }
 
     /// Moves all element from the given `LinkedList` before the current one.
     ///
     /// If the cursor is pointing at the null object then the new elements are
     /// inserted at the end of the `LinkedList`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `PointerOps` of the two lists don't have the same state
+    /// (see `PointerOps::same_state`).
     #[inline]
     pub fn splice_before(&mut self, mut list: LinkedList<A>) {
-        if !list.is_empty() {
-            unsafe {
-                let head = list.head.unwrap_unchecked();
-                let tail = list.tail.unwrap_unchecked();
-
-                let link_ops = self.list.adapter.link_ops_mut();
-
-                if let Some(current) = self.current {
-                    splice(link_ops, head, tail, link_ops.prev(current), Some(current));
-                } else {
-                    splice(link_ops, head, tail, self.list.tail, None);
-                    self.list.tail = list.tail;
-                }
-                if self.list.head == self.current {
-                    self.list.head = list.head;
+        crate::adapter::check_transfer(&self.list.adapter, &list.adapter);
+        structural!(self.list, {
+            if !list.is_empty() {
+                #[cfg(feature = "debug_owner")]
+                list.set_owner_all(self.list.owner_id());
+                unsafe {
+                    let head = list.head.unwrap_unchecked();
+                    let tail = list.tail.unwrap_unchecked();
+
+                    let link_ops = self.list.adapter.link_ops_mut();
+
+                    if let Some(current) = self.current {
+                        splice(link_ops, head, tail, link_ops.prev(current), Some(current));
+                    } else {
+                        splice(link_ops, head, tail, self.list.tail, None);
+                        self.list.tail = list.tail;
+                    }
+                    if self.list.head == self.current {
+                        self.list.head = list.head;
+                    }
+                    list.head = None;
+                    list.tail = None;
                 }
-                list.head = None;
-                list.tail = None;
             }
-        }
+        })
     }
 
     /// Splits the list into two after the current element. This will return a
@@ -1030,32 +2076,60 @@ where
     where
         A: Clone,
     {
-        if let Some(current) = self.current {
-            unsafe {
-                let mut list = LinkedList {
-                    head: self.list.adapter.link_ops().next(current),
+        structural!(self.list, {
+            if let Some(current) = self.current {
+                unsafe {
+                    let mut list = LinkedList {
+                        head: self.list.adapter.link_ops().next(current),
+                        tail: self.list.tail,
+                        adapter: self.list.adapter.clone(),
+                        #[cfg(debug_assertions)]
+                        debug_name: self.list.debug_name,
+                        #[cfg(feature = "debug_owner")]
+                        owner: 0,
+                        #[cfg(feature = "stats")]
+                        stats: StatsCounters::new(),
+                        #[cfg(feature = "poison")]
+                        poison: Poison::new(),
+                        #[cfg(feature = "debug_generation")]
+                        generation: Generation::new(),
+                    };
+                    if let Some(head) = list.head {
+                        self.list.adapter.link_ops_mut().set_prev(head, None);
+                    } else {
+                        list.tail = None;
+                    }
+                    self.list.adapter.link_ops_mut().set_next(current, None);
+                    self.list.tail = self.current;
+                    #[cfg(feature = "debug_owner")]
+                    list.set_owner_all(link_ops::new_owner_id());
+                    list
+                }
+            } else {
+                let list = LinkedList {
+                    head: self.list.head,
                     tail: self.list.tail,
                     adapter: self.list.adapter.clone(),
+                    #[cfg(debug_assertions)]
+                    debug_name: self.list.debug_name,
+                    #[cfg(feature = "debug_owner")]
+                    owner: self.list.owner,
+                    #[cfg(feature = "stats")]
+                    stats: StatsCounters::new(),
+                    #[cfg(feature = "poison")]
+                    poison: Poison::new(),
+                    #[cfg(feature = "debug_generation")]
+                    generation: Generation::new(),
                 };
-                if let Some(head) = list.head {
-                    self.list.adapter.link_ops_mut().set_prev(head, None);
-                } else {
-                    list.tail = None;
+                self.list.head = None;
+                self.list.tail = None;
+                #[cfg(feature = "debug_owner")]
+                {
+                    self.list.owner = 0;
                 }
-                self.list.adapter.link_ops_mut().set_next(current, None);
-                self.list.tail = self.current;
                 list
             }
-        } else {
-            let list = LinkedList {
-                head: self.list.head,
-                tail: self.list.tail,
-                adapter: self.list.adapter.clone(),
-            };
-            self.list.head = None;
-            self.list.tail = None;
-            list
-        }
+        })
     }
 
     /// Splits the list into two before the current element. This will return a
@@ -1069,32 +2143,60 @@ where
     where
         A: Clone,
     {
-        if let Some(current) = self.current {
-            unsafe {
-                let mut list = LinkedList {
+        structural!(self.list, {
+            if let Some(current) = self.current {
+                unsafe {
+                    let mut list = LinkedList {
+                        head: self.list.head,
+                        tail: self.list.adapter.link_ops().prev(current),
+                        adapter: self.list.adapter.clone(),
+                        #[cfg(debug_assertions)]
+                        debug_name: self.list.debug_name,
+                        #[cfg(feature = "debug_owner")]
+                        owner: 0,
+                        #[cfg(feature = "stats")]
+                        stats: StatsCounters::new(),
+                        #[cfg(feature = "poison")]
+                        poison: Poison::new(),
+                        #[cfg(feature = "debug_generation")]
+                        generation: Generation::new(),
+                    };
+                    if let Some(tail) = list.tail {
+                        self.list.adapter.link_ops_mut().set_next(tail, None);
+                    } else {
+                        list.head = None;
+                    }
+                    self.list.adapter.link_ops_mut().set_prev(current, None);
+                    self.list.head = self.current;
+                    #[cfg(feature = "debug_owner")]
+                    list.set_owner_all(link_ops::new_owner_id());
+                    list
+                }
+            } else {
+                let list = LinkedList {
                     head: self.list.head,
-                    tail: self.list.adapter.link_ops().prev(current),
+                    tail: self.list.tail,
                     adapter: self.list.adapter.clone(),
+                    #[cfg(debug_assertions)]
+                    debug_name: self.list.debug_name,
+                    #[cfg(feature = "debug_owner")]
+                    owner: self.list.owner,
+                    #[cfg(feature = "stats")]
+                    stats: StatsCounters::new(),
+                    #[cfg(feature = "poison")]
+                    poison: Poison::new(),
+                    #[cfg(feature = "debug_generation")]
+                    generation: Generation::new(),
                 };
-                if let Some(tail) = list.tail {
-                    self.list.adapter.link_ops_mut().set_prev(tail, None);
-                } else {
-                    list.head = None;
+                self.list.head = None;
+                self.list.tail = None;
+                #[cfg(feature = "debug_owner")]
+                {
+                    self.list.owner = 0;
                 }
-                self.list.adapter.link_ops_mut().set_prev(current, None);
-                self.list.head = self.current;
                 list
             }
-        } else {
-            let list = LinkedList {
-                head: self.list.head,
-                tail: self.list.tail,
-                adapter: self.list.adapter.clone(),
-            };
-            self.list.head = None;
-            self.list.tail = None;
-            list
-        }
+        })
     }
 
     /// Consumes `CursorMut` and returns a reference to the object that
@@ -1139,6 +2241,8 @@ where
         Cursor {
             current: self.current,
             list: &self.list,
+            #[cfg(feature = "debug_generation")]
+            generation: self.list.generation.get(),
         }
     }
 
@@ -1162,968 +2266,3817 @@ where
     A::LinkOps: LinkedListOps,
 {
 }
-
-// =============================================================================
-// LinkedList
-// =============================================================================
-
-/// An intrusive doubly-linked list.
-///
-/// When this collection is dropped, all elements linked into it will be
-/// converted back to owned pointers and dropped.
-pub struct LinkedList<A: Adapter>
+unsafe impl<A: Adapter> Sync for CursorOwning<A>
 where
+    LinkedList<A>: Sync,
     A::LinkOps: LinkedListOps,
 {
-    head: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
-    tail: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
-    adapter: A,
 }
 
-impl<A: Adapter> LinkedList<A>
+// Returns the neighbors of the element of `list` at `current`, as shown when
+// formatting a cursor.
+#[allow(clippy::type_complexity)]
+fn cursor_neighbors<A: Adapter>(
+    list: &LinkedList<A>,
+    current: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+) -> (
+    Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+    Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+)
 where
     A::LinkOps: LinkedListOps,
 {
-    #[inline]
-    fn node_from_value(
-        &mut self,
-        val: <A::PointerOps as PointerOps>::Pointer,
-    ) -> <A::LinkOps as link_ops::LinkOps>::LinkPtr {
-        use link_ops::LinkOps;
+    let link_ops = list.adapter.link_ops();
+    match current {
+        Some(current) => unsafe { (link_ops.prev(current), link_ops.next(current)) },
+        None => (list.tail, list.head),
+    }
+}
 
-        unsafe {
-            let raw = self.adapter.pointer_ops().into_raw(val);
-            let link = self.adapter.get_link(raw);
+// Formats a cursor of `list` pointing to `current`, along with its neighbors.
+fn debug_cursor<A: Adapter>(
+    f: &mut fmt::Formatter<'_>,
+    name: &str,
+    list: &LinkedList<A>,
+    current: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+) -> fmt::Result
+where
+    A::LinkOps: LinkedListOps,
+{
+    let (prev, next) = cursor_neighbors(list, current);
+    crate::adapter::debug_cursor(f, name, &list.adapter, current, Some(prev), next)
+}
 
-            if !self.adapter.link_ops_mut().acquire_link(link) {
-                // convert the node back into a pointer
-                self.adapter.pointer_ops().from_raw(raw);
+// Same as `debug_cursor`, for `defmt`.
+#[cfg(feature = "defmt")]
+fn defmt_cursor<A: Adapter>(
+    f: defmt::Formatter<'_>,
+    name: &str,
+    list: &LinkedList<A>,
+    current: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+) where
+    A::LinkOps: LinkedListOps,
+{
+    let (prev, next) = cursor_neighbors(list, current);
+    crate::adapter::defmt_cursor(f, name, &list.adapter, current, Some(prev), next)
+}
 
-                panic!("attempted to insert an object that is already linked");
-            }
+impl<'a, A: Adapter> CursorOps for Cursor<'a, A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    type Adapter = A;
 
-            link
-        }
+    #[inline]
+    fn is_null(&self) -> bool {
+        Cursor::is_null(self)
     }
 
-    /// Creates an empty `LinkedList`.
-    #[cfg(not(feature = "nightly"))]
     #[inline]
-    pub fn new(adapter: A) -> LinkedList<A> {
-        LinkedList {
-            head: None,
-            tail: None,
-            adapter,
-        }
+    fn get(&self) -> Option<&<A::PointerOps as PointerOps>::Value> {
+        Cursor::get(self)
     }
 
-    /// Creates an empty `LinkedList`.
-    #[cfg(feature = "nightly")]
     #[inline]
-    pub const fn new(adapter: A) -> LinkedList<A> {
-        LinkedList {
-            head: None,
-            tail: None,
-            adapter,
-        }
+    fn move_next(&mut self) {
+        Cursor::move_next(self)
     }
+}
 
-    /// Returns `true` if the `LinkedList` is empty.
+impl<'a, A: Adapter> DoubleEndedCursorOps for Cursor<'a, A>
+where
+    A::LinkOps: LinkedListOps,
+{
     #[inline]
-    pub fn is_empty(&self) -> bool {
-        self.head.is_none()
+    fn move_prev(&mut self) {
+        Cursor::move_prev(self)
     }
+}
+
+impl<'a, A: Adapter> CursorOps for CursorMut<'a, A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    type Adapter = A;
 
-    /// Returns a null `Cursor` for this list.
     #[inline]
-    pub fn cursor(&self) -> Cursor<'_, A> {
-        Cursor {
-            current: None,
-            list: self,
-        }
+    fn is_null(&self) -> bool {
+        CursorMut::is_null(self)
     }
 
-    /// Returns a null `CursorMut` for this list.
     #[inline]
-    pub fn cursor_mut(&mut self) -> CursorMut<'_, A> {
-        CursorMut {
-            current: None,
-            list: self,
-        }
+    fn get(&self) -> Option<&<A::PointerOps as PointerOps>::Value> {
+        CursorMut::get(self)
     }
 
-    /// Returns a null `CursorOwning` for this list.
     #[inline]
-    pub fn cursor_owning(self) -> CursorOwning<A> {
-        CursorOwning {
-            current: None,
-            list: self,
-        }
+    fn move_next(&mut self) {
+        CursorMut::move_next(self)
     }
+}
 
-    /// Creates a `Cursor` from a pointer to an element.
-    ///
-    /// # Safety
-    ///
-    /// `ptr` must be a pointer to an object that is part of this list.
+impl<'a, A: Adapter> DoubleEndedCursorOps for CursorMut<'a, A>
+where
+    A::LinkOps: LinkedListOps,
+{
     #[inline]
-    pub unsafe fn cursor_from_ptr(
-        &self,
-        ptr: *const <A::PointerOps as PointerOps>::Value,
-    ) -> Cursor<'_, A> {
-        Cursor {
-            current: Some(self.adapter.get_link(ptr)),
-            list: self,
-        }
+    fn move_prev(&mut self) {
+        CursorMut::move_prev(self)
     }
+}
 
-    /// Creates a `CursorMut` from a pointer to an element.
-    ///
-    /// # Safety
-    ///
-    /// `ptr` must be a pointer to an object that is part of this list.
+impl<'a, A: Adapter> CursorMutOps for CursorMut<'a, A>
+where
+    A::LinkOps: LinkedListOps,
+{
     #[inline]
-    pub unsafe fn cursor_mut_from_ptr(
-        &mut self,
-        ptr: *const <A::PointerOps as PointerOps>::Value,
-    ) -> CursorMut<'_, A> {
-        CursorMut {
-            current: Some(self.adapter.get_link(ptr)),
-            list: self,
-        }
+    fn remove(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        CursorMut::remove(self)
     }
 
-    /// Creates a `CursorOwning` from a pointer to an element.
-    ///
-    /// # Safety
-    ///
-    /// `ptr` must be a pointer to an object that is part of this list.
     #[inline]
-    pub unsafe fn cursor_owning_from_ptr(
-        self,
-        ptr: *const <A::PointerOps as PointerOps>::Value,
-    ) -> CursorOwning<A> {
-        CursorOwning {
-            current: Some(self.adapter.get_link(ptr)),
-            list: self,
-        }
+    fn insert_after(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        CursorMut::insert_after(self, val)
     }
+}
 
-    /// Returns a `Cursor` pointing to the first element of the list. If the
-    /// list is empty then a null cursor is returned.
-    #[inline]
-    pub fn front(&self) -> Cursor<'_, A> {
-        let mut cursor = self.cursor();
-        cursor.move_next();
-        cursor
+impl<A: Adapter> fmt::Debug for Cursor<'_, A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_cursor(f, "Cursor", self.list, self.current)
     }
+}
 
-    /// Returns a `CursorMut` pointing to the first element of the list. If the
-    /// the list is empty then a null cursor is returned.
-    #[inline]
-    pub fn front_mut(&mut self) -> CursorMut<'_, A> {
-        let mut cursor = self.cursor_mut();
-        cursor.move_next();
-        cursor
+impl<A: Adapter> fmt::Debug for CursorMut<'_, A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_cursor(f, "CursorMut", self.list, self.current)
     }
+}
 
-    /// Returns a `CursorOwning` pointing to the first element of the list. If the
-    /// the list is empty then a null cursor is returned.
-    #[inline]
-    pub fn front_owning(self) -> CursorOwning<A> {
-        let mut cursor = self.cursor_owning();
-        cursor.with_cursor_mut(|c| c.move_next());
-        cursor
+impl<A: Adapter> fmt::Debug for CursorOwning<A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_cursor(f, "CursorOwning", &self.list, self.current)
     }
+}
 
-    /// Returns a `Cursor` pointing to the last element of the list. If the list
-    /// is empty then a null cursor is returned.
-    #[inline]
-    pub fn back(&self) -> Cursor<'_, A> {
-        let mut cursor = self.cursor();
-        cursor.move_prev();
-        cursor
+#[cfg(feature = "defmt")]
+impl<A: Adapter> defmt::Format for Cursor<'_, A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt_cursor(f, "Cursor", self.list, self.current)
     }
+}
 
-    /// Returns a `CursorMut` pointing to the last element of the list. If the
-    /// list is empty then a null cursor is returned.
-    #[inline]
-    pub fn back_mut(&mut self) -> CursorMut<'_, A> {
-        let mut cursor = self.cursor_mut();
-        cursor.move_prev();
-        cursor
+#[cfg(feature = "defmt")]
+impl<A: Adapter> defmt::Format for CursorMut<'_, A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt_cursor(f, "CursorMut", self.list, self.current)
     }
+}
 
-    /// Returns a `CursorOwning` pointing to the last element of the list. If the
-    /// list is empty then a null cursor is returned.
-    #[inline]
-    pub fn back_owning(self) -> CursorOwning<A> {
-        let mut cursor = self.cursor_owning();
-        cursor.with_cursor_mut(|c| c.move_prev());
-        cursor
+#[cfg(feature = "defmt")]
+impl<A: Adapter> defmt::Format for CursorOwning<A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt_cursor(f, "CursorOwning", &self.list, self.current)
     }
+}
 
-    /// Gets an iterator over the objects in the `LinkedList`.
+// =============================================================================
+// LinkedList
+// =============================================================================
+
+/// An intrusive doubly-linked list.
+///
+/// When this collection is dropped, all elements linked into it will be
+/// converted back to owned pointers and dropped.
+pub struct LinkedList<A: Adapter>
+where
+    A::LinkOps: LinkedListOps,
+{
+    head: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+    tail: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+    adapter: A,
+    #[cfg(debug_assertions)]
+    debug_name: Option<&'static str>,
+    #[cfg(feature = "debug_owner")]
+    owner: usize,
+    #[cfg(feature = "stats")]
+    stats: StatsCounters,
+    #[cfg(feature = "poison")]
+    poison: Poison,
+    #[cfg(feature = "debug_generation")]
+    generation: Generation,
+}
+
+impl<A: Adapter> LinkedList<A>
+where
+    A::LinkOps: LinkedListOps,
+{
     #[inline]
-    pub fn iter(&self) -> Iter<'_, A> {
-        Iter {
-            head: self.head,
-            tail: self.tail,
-            list: self,
+    fn node_from_value(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> <A::LinkOps as link_ops::LinkOps>::LinkPtr {
+        unsafe {
+            let link = self.acquire_node(val);
+            crate::adapter::acquired_on_insert(&mut self.adapter, link);
+            record_stat!(self, insertions);
+
+            link
         }
     }
 
-    /// Gets a mutable iterator over the objects in the `LinkedList`.
-    ///
-    /// # Safety
-    ///
-    /// You must not modify any links that are linked
+    // Same as `node_from_value`, but hands `val` back if it is already linked
+    // instead of panicking.
     #[inline]
-    pub unsafe fn iter_mut(&mut self) -> IterMut<'_, A>
-    where
-        A::PointerOps: ExclusivePointerOps,
-    {
-        IterMut {
-            head: self.head,
-            tail: self.tail,
-            list: self,
+    fn try_node_from_value(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<
+        <A::LinkOps as link_ops::LinkOps>::LinkPtr,
+        InsertError<<A::PointerOps as PointerOps>::Pointer>,
+    > {
+        use link_ops::LinkOps;
+
+        check_poison!(self);
+        unsafe {
+            let raw = self.adapter.pointer_ops().into_raw(val);
+            let link = self.adapter.get_link(raw);
+
+            if !self.adapter.link_ops_mut().acquire_link(link) {
+                let val = self.adapter.pointer_ops().from_raw(raw);
+                return Err(InsertError::AlreadyLinked(val));
+            }
+            #[cfg(feature = "debug_owner")]
+            {
+                let owner = self.owner_id();
+                self.adapter.link_ops_mut().set_owner(link, owner);
+            }
+            crate::adapter::acquired_on_insert(&mut self.adapter, link);
+            record_stat!(self, insertions);
+
+            Ok(link)
         }
     }
 
-    /// Removes all elements from the `LinkedList`.
-    ///
-    /// This will unlink all object currently in the list, which requires
-    /// iterating through all elements in the `LinkedList`. Each element is
-    /// converted back to an owned pointer and then dropped.
+    // Converts a new element into its link and acquires it, without calling
+    // the `on_insert` hook of the adapter.
     #[inline]
-    pub fn clear(&mut self) {
+    unsafe fn acquire_node(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> <A::LinkOps as link_ops::LinkOps>::LinkPtr {
         use link_ops::LinkOps;
 
-        let mut current = self.head;
-        self.head = None;
-        self.tail = None;
-        while let Some(x) = current {
-            unsafe {
-                let next = self.adapter.link_ops().next(x);
-                self.adapter.link_ops_mut().release_link(x);
-                self.adapter
-                    .pointer_ops()
-                    .from_raw(self.adapter.get_value(x));
-                current = next;
-            }
+        check_poison!(self);
+        let raw = self.adapter.pointer_ops().into_raw(val);
+        let link = self.adapter.get_link(raw);
+
+        if !self.adapter.link_ops_mut().acquire_link(link) {
+            // convert the node back into a pointer, after reading the
+            // owner of the link for the panic message
+            let owner = self.owner_of(link);
+            self.adapter.pointer_ops().from_raw(raw);
+
+            crate::adapter::already_linked(&self.adapter, self.debug_name(), raw, owner);
         }
+        #[cfg(feature = "debug_owner")]
+        {
+            let owner = self.owner_id();
+            self.adapter.link_ops_mut().set_owner(link, owner);
+        }
+
+        link
     }
 
-    /// Empties the `LinkedList` without unlinking or freeing objects in it.
-    ///
-    /// Since this does not unlink any objects, any attempts to link these
-    /// objects into another `LinkedList` will fail but will not cause any
-    /// memory unsafety. To unlink those objects manually, you must call the
-    /// `force_unlink` function on them.
+    // Sets the user tag of `link`, used by the cursors.
     #[inline]
-    pub fn fast_clear(&mut self) {
-        self.head = None;
-        self.tail = None;
+    #[track_caller]
+    fn set_tag_of(&self, link: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>, tag: u8) {
+        let link = link.expect("attempted to tag the null object");
+        let link_ops = self.adapter.link_ops();
+        assert!(
+            tag.checked_shr(link_ops.tag_bits()).unwrap_or(0) == 0,
+            "tag does not fit in the link"
+        );
+        unsafe { link_ops.set_tag(link, tag) }
     }
 
-    /// Takes all the elements out of the `LinkedList`, leaving it empty.
-    /// The taken elements are returned as a new `LinkedList`.
+    // Returns the identifier of this list for the `debug_owner` feature,
+    // assigning one if needed.
+    #[cfg(feature = "debug_owner")]
     #[inline]
-    pub fn take(&mut self) -> LinkedList<A>
-    where
-        A: Clone,
-    {
-        let list = LinkedList {
-            head: self.head,
-            tail: self.tail,
-            adapter: self.adapter.clone(),
-        };
-        self.head = None;
-        self.tail = None;
-        list
+    fn owner_id(&mut self) -> usize {
+        if self.owner == 0 {
+            self.owner = link_ops::new_owner_id();
+        }
+        self.owner
     }
 
-    /// Inserts a new element at the start of the `LinkedList`.
-    #[inline]
-    pub fn push_front(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
-        self.cursor_mut().insert_after(val);
+    // Records `owner` as the identifier of this list and the owner of all its
+    // elements, for the `debug_owner` feature.
+    #[cfg(feature = "debug_owner")]
+    fn set_owner_all(&mut self, owner: usize) {
+        self.owner = owner;
+        let mut current = self.head;
+        while let Some(x) = current {
+            unsafe {
+                self.adapter.link_ops_mut().set_owner(x, owner);
+                current = self.adapter.link_ops().next(x);
+            }
+        }
     }
 
-    /// Inserts a new element at the end of the `LinkedList`.
+    // Checks that `link` is in this list, for the `debug_owner` feature.
     #[inline]
-    pub fn push_back(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
-        self.cursor_mut().insert_before(val);
+    #[track_caller]
+    unsafe fn check_owner(&self, link: <A::LinkOps as link_ops::LinkOps>::LinkPtr) {
+        #[cfg(feature = "debug_owner")]
+        if let Some(owner) = self.adapter.link_ops().owner(link) {
+            if owner == 0 || owner != self.owner {
+                crate::adapter::wrong_owner(&self.adapter, self.debug_name());
+            }
+        }
+        #[cfg(not(feature = "debug_owner"))]
+        let _ = link;
     }
 
-    /// Removes the first element of the `LinkedList`.
-    ///
-    /// This returns `None` if the `LinkedList` is empty.
+    // Checks that `link` is in this list by scanning it, for the
+    // `debug-checks` feature.
     #[inline]
-    pub fn pop_front(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
-        self.front_mut().remove()
+    #[track_caller]
+    unsafe fn check_contains(&self, link: <A::LinkOps as link_ops::LinkOps>::LinkPtr) {
+        #[cfg(feature = "debug-checks")]
+        {
+            let mut current = self.head;
+            while let Some(x) = current {
+                if x == link {
+                    return;
+                }
+                current = self.adapter.link_ops().next(x);
+            }
+            let value = self.adapter.get_value(link);
+            crate::adapter::bad_cursor_ptr(
+                &self.adapter,
+                self.debug_name(),
+                value,
+                "which is not in this collection",
+            );
+        }
+        #[cfg(not(feature = "debug-checks"))]
+        let _ = link;
     }
 
-    /// Removes the last element of the `LinkedList`.
-    ///
-    /// This returns `None` if the `LinkedList` is empty.
+    // Returns whether `unlink_for_transfer` moved an element into this list,
+    // and panics if the element was already linked in it. This is done after
+    // both lists are consistent again, so that the panic doesn't poison them.
     #[inline]
-    pub fn pop_back(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
-        self.back_mut().remove()
+    #[track_caller]
+    unsafe fn transferred(&self, result: crate::adapter::Transfer<A>) -> bool {
+        match result {
+            Some(Ok(_)) => true,
+            Some(Err(link)) => self.already_linked(link, self.adapter.get_value(link)),
+            None => false,
+        }
     }
-}
 
-// Allow read-only access to values from multiple threads
-unsafe impl<A: Adapter + Sync> Sync for LinkedList<A>
-where
-    <A::PointerOps as PointerOps>::Value: Sync,
-    A::LinkOps: LinkedListOps,
-{
-}
+    #[cold]
+    unsafe fn already_linked(
+        &self,
+        link: <A::LinkOps as link_ops::LinkOps>::LinkPtr,
+        value: *const <A::PointerOps as PointerOps>::Value,
+    ) -> ! {
+        crate::adapter::already_linked(
+            &self.adapter,
+            self.debug_name(),
+            value,
+            self.owner_of(link),
+        );
+    }
 
-// Allow sending to another thread if the ownership (represented by the <A::PointerOps as PointerOps>::Pointer owned
-// pointer type) can be transferred to another thread.
-unsafe impl<A: Adapter + Send> Send for LinkedList<A>
-where
-    <A::PointerOps as PointerOps>::Pointer: Send,
-    A::LinkOps: LinkedListOps,
-{
-}
+    // Returns the identifier of the collection `link` is in along with the one
+    // of this list, for the panic message of `already_linked`.
+    #[inline]
+    unsafe fn owner_of(
+        &self,
+        link: <A::LinkOps as link_ops::LinkOps>::LinkPtr,
+    ) -> Option<(usize, usize)> {
+        #[cfg(feature = "debug_owner")]
+        {
+            self.adapter
+                .link_ops()
+                .owner(link)
+                .map(|owner| (owner, self.owner))
+        }
+        #[cfg(not(feature = "debug_owner"))]
+        {
+            let _ = link;
+            None
+        }
+    }
 
-// Drop all owned pointers if the collection is dropped
-impl<A: Adapter> Drop for LinkedList<A>
-where
-    A::LinkOps: LinkedListOps,
-{
+    /// Creates an empty `LinkedList`.
+    ///
+    /// This is a `const fn`, which allows the collection to be used in a
+    /// `static` when combined with the `NEW` constant of an adapter.
     #[inline]
-    fn drop(&mut self) {
-        self.clear();
+    pub const fn new(adapter: A) -> LinkedList<A> {
+        LinkedList {
+            head: None,
+            tail: None,
+            adapter,
+            #[cfg(debug_assertions)]
+            debug_name: None,
+            #[cfg(feature = "debug_owner")]
+            owner: 0,
+            #[cfg(feature = "stats")]
+            stats: StatsCounters::new(),
+            #[cfg(feature = "poison")]
+            poison: Poison::new(),
+            #[cfg(feature = "debug_generation")]
+            generation: Generation::new(),
+        }
     }
-}
 
-impl<A: Adapter> IntoIterator for LinkedList<A>
-where
-    A::LinkOps: LinkedListOps,
-{
-    type Item = <A::PointerOps as PointerOps>::Pointer;
-    type IntoIter = IntoIter<A>;
+    /// Sets a name for this `LinkedList` which is used in panic messages.
+    ///
+    /// The name is only stored in builds with debug assertions enabled.
+    #[inline]
+    pub fn set_debug_name(&mut self, name: &'static str) {
+        #[cfg(debug_assertions)]
+        {
+            self.debug_name = Some(name);
+        }
+        #[cfg(not(debug_assertions))]
+        let _ = name;
+    }
 
+    /// Returns the name set by `set_debug_name`.
+    ///
+    /// This always returns `None` in builds without debug assertions.
     #[inline]
-    fn into_iter(self) -> IntoIter<A> {
-        IntoIter { list: self }
+    pub fn debug_name(&self) -> Option<&'static str> {
+        #[cfg(debug_assertions)]
+        return self.debug_name;
+        #[cfg(not(debug_assertions))]
+        None
     }
-}
 
-impl<'a, A: Adapter + 'a> IntoIterator for &'a LinkedList<A>
-where
-    A::LinkOps: LinkedListOps,
-{
-    type Item = &'a <A::PointerOps as PointerOps>::Value;
-    type IntoIter = Iter<'a, A>;
+    #[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+    #[inline]
+    pub(crate) fn adapter(&self) -> &A {
+        &self.adapter
+    }
 
+    /// Returns `true` if the `LinkedList` is empty.
     #[inline]
-    fn into_iter(self) -> Iter<'a, A> {
-        self.iter()
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
     }
-}
 
-impl<A: Adapter + Default> Default for LinkedList<A>
-where
-    A::LinkOps: LinkedListOps,
-{
-    fn default() -> LinkedList<A> {
-        LinkedList::new(A::default())
+    /// Returns the memory used by the `LinkedList` and the links of its elements.
+    ///
+    /// This counts the elements by walking the list, in linear time.
+    #[inline]
+    pub fn memory_overhead(&self) -> MemoryOverhead {
+        MemoryOverhead::new::<A::LinkOps, Self>(self.iter().count())
     }
-}
 
-impl<A: Adapter> fmt::Debug for LinkedList<A>
-where
-    A::LinkOps: LinkedListOps,
-    <A::PointerOps as PointerOps>::Value: fmt::Debug,
-{
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_list().entries(self.iter()).finish()
+    /// Returns statistics about the operations performed on the
+    /// `LinkedList`.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn stats(&self) -> Stats {
+        self.stats.get()
     }
-}
 
-// =============================================================================
-// Iter
-// =============================================================================
+    /// Resets the statistics returned by `stats` to zero.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn reset_stats(&mut self) {
+        self.stats = StatsCounters::new();
+    }
 
-/// An iterator over references to the items of a `LinkedList`.
-pub struct Iter<'a, A: Adapter>
-where
-    A::LinkOps: LinkedListOps,
-{
-    head: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
-    tail: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
-    list: &'a LinkedList<A>,
-}
-impl<'a, A: Adapter + 'a> Iterator for Iter<'a, A>
-where
-    A::LinkOps: LinkedListOps,
-{
-    type Item = &'a <A::PointerOps as PointerOps>::Value;
+    /// Returns `true` if a panic escaped an operation which modified the
+    /// links of the `LinkedList`.
+    ///
+    /// Operations which modify the links of a poisoned list panic, since
+    /// they may be inconsistent. Reading the list is still allowed.
+    /// Panics raised before the links are modified, such as when inserting
+    /// an element which is already linked, don't poison the list.
+    #[cfg(feature = "poison")]
+    #[inline]
+    pub fn is_poisoned(&self) -> bool {
+        self.poison.get()
+    }
 
+    /// Clears the poisoned state of the `LinkedList`.
+    ///
+    /// This should only be done once the list is known to be consistent,
+    /// for example after checking it with `assert_valid`.
+    #[cfg(feature = "poison")]
     #[inline]
-    fn next(&mut self) -> Option<&'a <A::PointerOps as PointerOps>::Value> {
-        let head = self.head?;
+    pub fn clear_poison(&mut self) {
+        self.poison.clear();
+    }
 
-        if Some(head) == self.tail {
-            self.head = None;
-            self.tail = None;
-        } else {
-            self.head = unsafe { self.list.adapter.link_ops().next(head) };
+    /// Checks the integrity of the list and returns the number of elements
+    /// in it.
+    ///
+    /// This walks the whole list and verifies that the `prev` pointer of
+    /// every element points back to the element before it, and that the head
+    /// and tail of the list are its first and last elements. It is meant for
+    /// debugging, for example after manipulating links directly through
+    /// `LinkedListOps`.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the index of the first inconsistent element if the list
+    /// is corrupted.
+    pub fn assert_valid(&self) -> usize {
+        let link_ops = self.adapter.link_ops();
+        let mut prev = None;
+        let mut current = self.head;
+        let mut index = 0;
+        while let Some(link) = current {
+            unsafe {
+                assert!(
+                    link_ops.prev(link) == prev,
+                    "linked list is corrupted at element {}: its prev pointer doesn't point to the previous element",
+                    index,
+                );
+                prev = current;
+                current = link_ops.next(link);
+            }
+            index += 1;
         }
-        Some(unsafe { &*self.list.adapter.get_value(head) })
+        assert!(
+            self.tail == prev,
+            "linked list is corrupted: the tail isn't the last of its {} elements",
+            index,
+        );
+        index
     }
-}
-impl<'a, A: Adapter + 'a> DoubleEndedIterator for Iter<'a, A>
-where
-    A::LinkOps: LinkedListOps,
-{
-    #[inline]
-    fn next_back(&mut self) -> Option<&'a <A::PointerOps as PointerOps>::Value> {
-        let tail = self.tail?;
 
-        if Some(tail) == self.head {
-            self.head = None;
-            self.tail = None;
-        } else {
-            self.tail = unsafe { self.list.adapter.link_ops().prev(tail) };
+    /// Writes the chain of elements of the list to `out`, for post-mortem
+    /// analysis of a corrupted list.
+    ///
+    /// Every element is written on its own line, with its index, its address
+    /// and the addresses of the elements its `prev` and `next` pointers point
+    /// to. Unlike `assert_valid`, this doesn't stop at the first
+    /// inconsistency: an element whose `prev` pointer doesn't point to the
+    /// previous element, which is what happens when elements are linked into
+    /// two lists at once, is reported on the line after it and the walk goes
+    /// on. A `next` pointer leading back to an element which was already
+    /// written is reported as a cycle and ends the walk, so this always
+    /// terminates. The head and tail of the list are written first, and a
+    /// tail which isn't the last element is reported at the end.
+    ///
+    /// The pointers are followed as they are, so the elements they point to
+    /// must still be alive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use intrusive_collections::{intrusive_adapter, LinkedList, LinkedListLink};
+    ///
+    /// struct Obj {
+    ///     link: LinkedListLink,
+    /// }
+    /// intrusive_adapter!(ObjAdapter = Box<Obj>: Obj { link: LinkedListLink });
+    ///
+    /// let mut list = LinkedList::new(ObjAdapter::new());
+    /// list.push_back(Box::new(Obj { link: LinkedListLink::new() }));
+    /// list.push_back(Box::new(Obj { link: LinkedListLink::new() }));
+    ///
+    /// let mut dump = String::new();
+    /// list.dump(&mut dump).unwrap();
+    /// assert!(dump.ends_with("2 elements, no problems found\n"));
+    /// ```
+    #[cfg(feature = "dump")]
+    pub fn dump<W: fmt::Write>(&self, out: &mut W) -> fmt::Result {
+        self.dump_chain(out, None)
+    }
+
+    /// Same as `dump`, but also writes the `Debug` representation of every
+    /// element.
+    #[cfg(feature = "dump")]
+    pub fn dump_values<W: fmt::Write>(&self, out: &mut W) -> fmt::Result
+    where
+        <A::PointerOps as PointerOps>::Value: fmt::Debug,
+    {
+        self.dump_chain(out, Some(|value, out| write!(out, "{:?}", value)))
+    }
+
+    #[cfg(feature = "dump")]
+    fn dump_chain(
+        &self,
+        out: &mut dyn fmt::Write,
+        write_value: Option<dump::WriteValue<<A::PointerOps as PointerOps>::Value>>,
+    ) -> fmt::Result {
+        let link_ops = self.adapter.link_ops();
+        let value =
+            |link: Option<_>| dump::Addr(link.map(|link| unsafe { self.adapter.get_value(link) }));
+        dump::write_header(
+            out,
+            "LinkedList",
+            self.adapter.debug_name(),
+            self.debug_name(),
+        )?;
+        writeln!(out, " head {}, tail {}", value(self.head), value(self.tail))?;
+
+        let cycle = dump::find_cycle(self.head, |link| unsafe { link_ops.next(link) });
+        let mut problems = 0;
+        let mut prev = None;
+        let mut current = self.head;
+        let mut index = 0;
+        while let Some(link) = current {
+            let (link_prev, next) = unsafe { (link_ops.prev(link), link_ops.next(link)) };
+            let ptr = unsafe { self.adapter.get_value(link) };
+            dump::write_element(out, index, ptr)?;
+            write!(out, " prev {}, next {}", value(link_prev), value(next))?;
+            unsafe { dump::write_value(out, ptr, write_value)? };
+            if link_prev != prev {
+                dump::write_problem(
+                    out,
+                    &mut problems,
+                    format_args!(
+                        "prev is {}, but the previous element is {}",
+                        value(link_prev),
+                        value(prev)
+                    ),
+                )?;
+            }
+            index += 1;
+            if let Some((first, len)) = cycle {
+                if index == first + len {
+                    dump::write_problem(
+                        out,
+                        &mut problems,
+                        format_args!("next is #{}, which makes a cycle of length {}", first, len),
+                    )?;
+                    return dump::write_summary(out, index, problems);
+                }
+            }
+            prev = current;
+            current = next;
         }
-        Some(unsafe { &*self.list.adapter.get_value(tail) })
+        if self.tail != prev {
+            dump::write_problem(
+                out,
+                &mut problems,
+                format_args!(
+                    "tail is {}, but the last element is {}",
+                    value(self.tail),
+                    value(prev)
+                ),
+            )?;
+        }
+        dump::write_summary(out, index, problems)
     }
-}
-impl<'a, A: Adapter + 'a> Clone for Iter<'a, A>
-where
-    A::LinkOps: LinkedListOps,
-{
+
+    /// Returns a null `Cursor` for this list.
     #[inline]
-    fn clone(&self) -> Iter<'a, A> {
-        Iter {
-            head: self.head,
-            tail: self.tail,
-            list: self.list,
+    pub fn cursor(&self) -> Cursor<'_, A> {
+        Cursor {
+            current: None,
+            list: self,
+            #[cfg(feature = "debug_generation")]
+            generation: self.generation.get(),
         }
     }
-}
-
-// =============================================================================
-// IterMut
-// =============================================================================
-
-/// An iterator over mutable references to the items of a `LinkedList`.
-pub struct IterMut<'a, A: Adapter>
-where
-    A::LinkOps: LinkedListOps,
-    A::PointerOps: ExclusivePointerOps,
-{
-    head: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
-    tail: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
-    list: &'a mut LinkedList<A>,
-}
-impl<'a, A: Adapter + 'a> Iterator for IterMut<'a, A>
-where
-    A::LinkOps: LinkedListOps,
-    A::PointerOps: ExclusivePointerOps,
-{
-    type Item = &'a mut <A::PointerOps as PointerOps>::Value;
 
+    /// Returns a null `CursorMut` for this list.
     #[inline]
-    fn next(&mut self) -> Option<&'a mut <A::PointerOps as PointerOps>::Value> {
-        let head = self.head?;
-
-        if Some(head) == self.tail {
-            self.head = None;
-            self.tail = None;
-        } else {
-            self.head = unsafe { self.list.adapter.link_ops().next(head) };
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, A> {
+        CursorMut {
+            current: None,
+            list: self,
         }
-        Some(unsafe {
-            &mut *self
-                .list
-                .adapter
-                .pointer_ops()
-                .get_mut(self.list.adapter.get_value(head))
-        })
     }
-}
-impl<'a, A: Adapter + 'a> DoubleEndedIterator for IterMut<'a, A>
-where
-    A::LinkOps: LinkedListOps,
-    A::PointerOps: ExclusivePointerOps,
-{
+
+    /// Returns a null `CursorOwning` for this list.
     #[inline]
-    fn next_back(&mut self) -> Option<&'a mut <A::PointerOps as PointerOps>::Value> {
-        let tail = self.tail?;
+    pub fn cursor_owning(self) -> CursorOwning<A> {
+        CursorOwning {
+            current: None,
+            list: self,
+        }
+    }
 
-        if Some(tail) == self.head {
+    /// Creates a `Cursor` from a pointer to an element.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a pointer to an object that is part of this list.
+    #[inline]
+    pub unsafe fn cursor_from_ptr(
+        &self,
+        ptr: *const <A::PointerOps as PointerOps>::Value,
+    ) -> Cursor<'_, A> {
+        let link = self.adapter.get_link(ptr);
+        self.check_contains(link);
+        self.check_owner(link);
+        Cursor {
+            current: Some(link),
+            list: self,
+            #[cfg(feature = "debug_generation")]
+            generation: self.generation.get(),
+        }
+    }
+
+    /// Creates a `ScopedCursorMut` from a pointer to an element.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a pointer to an object that is part of this list.
+    #[inline]
+    pub unsafe fn cursor_mut_from_ptr(
+        &mut self,
+        ptr: *const <A::PointerOps as PointerOps>::Value,
+    ) -> CursorMut<'_, A> {
+        let link = self.adapter.get_link(ptr);
+        self.check_contains(link);
+        self.check_owner(link);
+        CursorMut {
+            current: Some(link),
+            list: self,
+        }
+    }
+
+    /// Creates a `Cursor` from a pointer to an element and a stamp of its
+    /// `GenerationLink`, or returns `None` if the element has been removed
+    /// from the list since the stamp was taken.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a pointer to a live object, `stamp` must have been taken
+    /// from its link while it was part of this list, and the object must not
+    /// have been moved to another list with `take`, `split_after`,
+    /// `split_before`, `splice_after` or `splice_before` since.
+    #[inline]
+    pub unsafe fn cursor_from_stamp<L>(
+        &self,
+        ptr: *const <A::PointerOps as PointerOps>::Value,
+        stamp: LinkStamp,
+    ) -> Option<Cursor<'_, A>>
+    where
+        A::LinkOps: LinkedListOps<LinkPtr = NonNull<GenerationLink<L>>>,
+    {
+        if self.adapter.get_link(ptr).as_ref().is_current(stamp) {
+            Some(self.cursor_from_ptr(ptr))
+        } else {
+            None
+        }
+    }
+
+    /// Creates a `CursorMut` from a pointer to an element and a stamp of its
+    /// `GenerationLink`, or returns `None` if the element has been removed
+    /// from the list since the stamp was taken.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a pointer to a live object, `stamp` must have been taken
+    /// from its link while it was part of this list, and the object must not
+    /// have been moved to another list with `take`, `split_after`,
+    /// `split_before`, `splice_after` or `splice_before` since.
+    #[inline]
+    pub unsafe fn cursor_mut_from_stamp<L>(
+        &mut self,
+        ptr: *const <A::PointerOps as PointerOps>::Value,
+        stamp: LinkStamp,
+    ) -> Option<CursorMut<'_, A>>
+    where
+        A::LinkOps: LinkedListOps<LinkPtr = NonNull<GenerationLink<L>>>,
+    {
+        if self.adapter.get_link(ptr).as_ref().is_current(stamp) {
+            Some(self.cursor_mut_from_ptr(ptr))
+        } else {
+            None
+        }
+    }
+
+    /// Creates a `CursorOwning` from a pointer to an element.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a pointer to an object that is part of this list.
+    #[inline]
+    pub unsafe fn cursor_owning_from_ptr(
+        self,
+        ptr: *const <A::PointerOps as PointerOps>::Value,
+    ) -> CursorOwning<A> {
+        let link = self.adapter.get_link(ptr);
+        self.check_contains(link);
+        self.check_owner(link);
+        CursorOwning {
+            current: Some(link),
+            list: self,
+        }
+    }
+
+    /// Returns a `Cursor` pointing to the first element of the list. If the
+    /// list is empty then a null cursor is returned.
+    #[inline]
+    pub fn front(&self) -> Cursor<'_, A> {
+        let mut cursor = self.cursor();
+        cursor.move_next();
+        cursor
+    }
+
+    /// Returns a `CursorMut` pointing to the first element of the list. If the
+    /// the list is empty then a null cursor is returned.
+    #[inline]
+    pub fn front_mut(&mut self) -> CursorMut<'_, A> {
+        let mut cursor = self.cursor_mut();
+        cursor.move_next();
+        cursor
+    }
+
+    /// Returns a `CursorOwning` pointing to the first element of the list. If the
+    /// the list is empty then a null cursor is returned.
+    #[inline]
+    pub fn front_owning(self) -> CursorOwning<A> {
+        let mut cursor = self.cursor_owning();
+        cursor.with_cursor_mut(|c| c.move_next());
+        cursor
+    }
+
+    /// Returns a `Cursor` pointing to the last element of the list. If the list
+    /// is empty then a null cursor is returned.
+    #[inline]
+    pub fn back(&self) -> Cursor<'_, A> {
+        let mut cursor = self.cursor();
+        cursor.move_prev();
+        cursor
+    }
+
+    /// Returns a `CursorMut` pointing to the last element of the list. If the
+    /// list is empty then a null cursor is returned.
+    #[inline]
+    pub fn back_mut(&mut self) -> CursorMut<'_, A> {
+        let mut cursor = self.cursor_mut();
+        cursor.move_prev();
+        cursor
+    }
+
+    /// Returns a `CursorOwning` pointing to the last element of the list. If the
+    /// list is empty then a null cursor is returned.
+    #[inline]
+    pub fn back_owning(self) -> CursorOwning<A> {
+        let mut cursor = self.cursor_owning();
+        cursor.with_cursor_mut(|c| c.move_prev());
+        cursor
+    }
+
+    /// Returns a `Cursor` pointing to the element at index `n` of the list,
+    /// counting from 0 at the front. If the list has `n` elements or fewer
+    /// then a null cursor is returned.
+    ///
+    /// This takes time linear in `n`.
+    ///
+    /// The list doesn't store its length, so elements closer to the back are
+    /// best reached with `cursor_at_back` if the length is known.
+    #[inline]
+    pub fn cursor_at(&self, n: usize) -> Cursor<'_, A> {
+        let mut cursor = self.front();
+        for _ in 0..n {
+            if cursor.is_null() {
+                break;
+            }
+            cursor.move_next();
+        }
+        cursor
+    }
+
+    /// Returns a `CursorMut` pointing to the element at index `n` of the list,
+    /// counting from 0 at the front. If the list has `n` elements or fewer
+    /// then a null cursor is returned.
+    ///
+    /// This takes time linear in `n`.
+    #[inline]
+    pub fn cursor_mut_at(&mut self, n: usize) -> CursorMut<'_, A> {
+        let mut cursor = self.front_mut();
+        for _ in 0..n {
+            if cursor.is_null() {
+                break;
+            }
+            cursor.move_next();
+        }
+        cursor
+    }
+
+    /// Returns a `Cursor` pointing to the element at index `n` of the list,
+    /// counting from 0 at the back. If the list has `n` elements or fewer
+    /// then a null cursor is returned.
+    ///
+    /// This takes time linear in `n`. For a list of length `len`, this points
+    /// to the same element as `cursor_at(len - 1 - n)`, so positional access
+    /// can walk from whichever end is closer:
+    ///
+    /// ```
+    /// use intrusive_collections::{intrusive_adapter, LinkedList, LinkedListLink};
+    ///
+    /// struct Track {
+    ///     link: LinkedListLink,
+    ///     id: usize,
+    /// }
+    /// intrusive_adapter!(TrackAdapter = Box<Track>: Track { link: LinkedListLink });
+    ///
+    /// let mut playlist = LinkedList::new(TrackAdapter::new());
+    /// let len = 10;
+    /// for id in 0..len {
+    ///     playlist.push_back(Box::new(Track { link: LinkedListLink::new(), id }));
+    /// }
+    /// let track = |n| {
+    ///     if n < len / 2 {
+    ///         playlist.cursor_at(n)
+    ///     } else {
+    ///         playlist.cursor_at_back(len - 1 - n)
+    ///     }
+    /// };
+    /// assert_eq!(track(2).get().unwrap().id, 2);
+    /// assert_eq!(track(8).get().unwrap().id, 8);
+    /// ```
+    #[inline]
+    pub fn cursor_at_back(&self, n: usize) -> Cursor<'_, A> {
+        let mut cursor = self.back();
+        for _ in 0..n {
+            if cursor.is_null() {
+                break;
+            }
+            cursor.move_prev();
+        }
+        cursor
+    }
+
+    /// Returns a `CursorMut` pointing to the element at index `n` of the list,
+    /// counting from 0 at the back. If the list has `n` elements or fewer
+    /// then a null cursor is returned.
+    ///
+    /// This takes time linear in `n`.
+    #[inline]
+    pub fn cursor_mut_at_back(&mut self, n: usize) -> CursorMut<'_, A> {
+        let mut cursor = self.back_mut();
+        for _ in 0..n {
+            if cursor.is_null() {
+                break;
+            }
+            cursor.move_prev();
+        }
+        cursor
+    }
+
+    /// Gets an iterator over the objects in the `LinkedList`.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, A> {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            list: self,
+            #[cfg(feature = "debug_generation")]
+            generation: self.generation.get(),
+        }
+    }
+
+    /// Calls `f` on each object in the `LinkedList`, until it returns
+    /// `ControlFlow::Break`.
+    ///
+    /// This is faster than `iter` since it doesn't have to keep the state of an
+    /// iterator between calls. The value from `ControlFlow::Break` is returned,
+    /// or `ControlFlow::Continue(())` if `f` never breaks.
+    #[inline]
+    pub fn visit<B, F>(&self, mut f: F) -> ControlFlow<B>
+    where
+        F: FnMut(&<A::PointerOps as PointerOps>::Value) -> ControlFlow<B>,
+    {
+        let mut current = self.head;
+        while let Some(x) = current {
+            unsafe {
+                if let ControlFlow::Break(b) = f(&*self.adapter.get_value(x)) {
+                    return ControlFlow::Break(b);
+                }
+                current = self.adapter.link_ops().next(x);
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    /// Gets an iterator over mutable references to the data of the objects
+    /// in the `LinkedList`, held in the `GhostCell`s returned by the adapter.
+    ///
+    /// Only the `GhostToken` has to be borrowed mutably, so this can be used
+    /// through a shared reference to the `LinkedList`.
+    #[cfg(feature = "ghost-cell")]
+    #[inline]
+    pub fn iter_ghost_mut<'a, 'brand>(
+        &'a self,
+        token: &'a mut GhostToken<'brand>,
+    ) -> GhostIterMut<'a, 'brand, A, Iter<'a, A>>
+    where
+        A: GhostAdapter<'brand>,
+    {
+        unsafe { GhostIterMut::new(self.iter(), &self.adapter, token) }
+    }
+
+    /// Gets a mutable iterator over the objects in the `LinkedList`.
+    ///
+    /// # Safety
+    ///
+    /// You must not modify any links that are linked
+    #[inline]
+    pub unsafe fn iter_mut(&mut self) -> IterMut<'_, A>
+    where
+        A::PointerOps: ExclusivePointerOps,
+    {
+        IterMut {
+            head: self.head,
+            tail: self.tail,
+            list: self,
+        }
+    }
+
+    /// Removes all elements from the `LinkedList`.
+    ///
+    /// This will unlink all object currently in the list, which requires
+    /// iterating through all elements in the `LinkedList`. Each element is
+    /// converted back to an owned pointer and then dropped.
+    #[inline]
+    pub fn clear(&mut self) {
+        use link_ops::LinkOps;
+        structural!(self, {
+            // Elements are unlinked from the front one at a time, so that the
+            // remaining ones are still in the list if dropping one panics.
+            while let Some(x) = self.head {
+                unsafe {
+                    let next = self.adapter.link_ops().next(x);
+                    match next {
+                        Some(next) => self.adapter.link_ops_mut().set_prev(next, None),
+                        None => self.tail = None,
+                    }
+                    self.head = next;
+                    self.adapter.link_ops_mut().release_link(x);
+                    crate::adapter::unlinked_pointer(&self.adapter, x);
+                    record_stat!(self, removals);
+                }
+            }
+        })
+    }
+
+    /// Empties the `LinkedList` without unlinking or freeing objects in it.
+    ///
+    /// Since this does not unlink any objects, any attempts to link these
+    /// objects into another `LinkedList` will fail but will not cause any
+    /// memory unsafety. To unlink those objects manually, you must call the
+    /// `force_unlink` function on them.
+    #[inline]
+    pub fn fast_clear(&mut self) {
+        #[cfg(feature = "debug_generation")]
+        self.generation.bump();
+        self.head = None;
+        self.tail = None;
+    }
+
+    /// Takes all the elements out of the `LinkedList`, leaving it empty.
+    /// The taken elements are returned as a new `LinkedList`.
+    ///
+    /// This takes constant time.
+    #[inline]
+    pub fn take(&mut self) -> LinkedList<A>
+    where
+        A: Clone,
+    {
+        structural!(self, {
+            let list = LinkedList {
+                head: self.head,
+                tail: self.tail,
+                adapter: self.adapter.clone(),
+                #[cfg(debug_assertions)]
+                debug_name: self.debug_name,
+                #[cfg(feature = "debug_owner")]
+                owner: self.owner,
+                #[cfg(feature = "stats")]
+                stats: StatsCounters::new(),
+                #[cfg(feature = "poison")]
+                poison: Poison::new(),
+                #[cfg(feature = "debug_generation")]
+                generation: Generation::new(),
+            };
             self.head = None;
             self.tail = None;
-        } else {
-            self.tail = unsafe { self.list.adapter.link_ops().prev(tail) };
+            #[cfg(feature = "debug_owner")]
+            {
+                self.owner = 0;
+            }
+            list
+        })
+    }
+
+    /// Swaps the elements of this `LinkedList` with those of `other`, along with
+    /// their adapters.
+    ///
+    /// This takes constant time, and is equivalent to `mem::swap`, which can
+    /// also be used since a `LinkedList` doesn't point into itself.
+    #[inline]
+    pub fn swap(&mut self, other: &mut LinkedList<A>) {
+        mem::swap(self, other);
+    }
+
+    /// Lends the `LinkedList` to C code for the duration of `f`, as a
+    /// `struct ic_linked_list` handle for the functions of the `ffi` module.
+    ///
+    /// The changes made through the handle are applied to the `LinkedList`
+    /// once `f` returns or panics, so the handle must not be used after that.
+    #[cfg(feature = "ffi")]
+    pub fn with_c_handle<R>(&mut self, f: impl FnOnce(*mut crate::ffi::LinkedListHandle) -> R) -> R
+    where
+        A: Adapter<LinkOps = LinkOps>,
+    {
+        // Applies the changes even if `f` panics, since C code may already
+        // have linked or unlinked elements.
+        struct WriteBack<'a, A: Adapter<LinkOps = LinkOps>> {
+            list: &'a mut LinkedList<A>,
+            handle: crate::ffi::LinkedListHandle,
+        }
+
+        impl<A: Adapter<LinkOps = LinkOps>> Drop for WriteBack<'_, A> {
+            #[inline]
+            fn drop(&mut self) {
+                self.list.head = self.handle.head;
+                self.list.tail = self.handle.tail;
+                // Elements inserted by C code don't have an owner yet.
+                #[cfg(feature = "debug_owner")]
+                {
+                    let owner = self.list.owner_id();
+                    self.list.set_owner_all(owner);
+                }
+            }
+        }
+
+        structural!(self, {
+            let mut guard = WriteBack {
+                handle: crate::ffi::LinkedListHandle {
+                    head: self.head,
+                    tail: self.tail,
+                },
+                list: &mut *self,
+            };
+            f(&mut guard.handle)
+        })
+    }
+
+    /// Inserts a new element at the start of the `LinkedList`.
+    #[inline]
+    pub fn push_front(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        self.cursor_mut().insert_after(val);
+    }
+
+    /// Inserts a new element at the end of the `LinkedList`.
+    #[inline]
+    pub fn push_back(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        self.cursor_mut().insert_before(val);
+    }
+
+    /// Inserts a new element at the start of the `LinkedList`, without
+    /// panicking.
+    ///
+    /// An error containing `val` is returned if the new element is already
+    /// linked to an intrusive collection.
+    #[inline]
+    pub fn try_push_front(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<(), InsertError<<A::PointerOps as PointerOps>::Pointer>> {
+        self.cursor_mut().try_insert_after(val)
+    }
+
+    /// Inserts a new element at the end of the `LinkedList`, without
+    /// panicking.
+    ///
+    /// An error containing `val` is returned if the new element is already
+    /// linked to an intrusive collection.
+    #[inline]
+    pub fn try_push_back(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<(), InsertError<<A::PointerOps as PointerOps>::Pointer>> {
+        self.cursor_mut().try_insert_before(val)
+    }
+
+    /// Removes the first element of the `LinkedList`.
+    ///
+    /// This returns `None` if the `LinkedList` is empty.
+    #[inline]
+    pub fn pop_front(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        self.front_mut().remove()
+    }
+
+    /// Removes the last element of the `LinkedList`.
+    ///
+    /// This returns `None` if the `LinkedList` is empty.
+    #[inline]
+    pub fn pop_back(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        self.back_mut().remove()
+    }
+
+    /// Removes the first element of the `LinkedList`, converting it back with
+    /// `TryPointerOps::try_from_raw`.
+    ///
+    /// This returns `None` if the `LinkedList` is empty, and the error from
+    /// `try_from_raw` if the element can't be converted back. The element is
+    /// removed from the `LinkedList` either way.
+    #[inline]
+    pub fn pop_front_checked(&mut self) -> Option<TryPointer<A>>
+    where
+        A::PointerOps: TryPointerOps,
+    {
+        self.front_mut().remove_checked()
+    }
+
+    /// Removes the last element of the `LinkedList`, converting it back with
+    /// `TryPointerOps::try_from_raw`.
+    ///
+    /// This returns `None` if the `LinkedList` is empty, and the error from
+    /// `try_from_raw` if the element can't be converted back. The element is
+    /// removed from the `LinkedList` either way.
+    #[inline]
+    pub fn pop_back_checked(&mut self) -> Option<TryPointer<A>>
+    where
+        A::PointerOps: TryPointerOps,
+    {
+        self.back_mut().remove_checked()
+    }
+
+    /// Inserts a new element into the `LinkedList`, keeping the list sorted
+    /// by increasing priority.
+    ///
+    /// The new element is inserted after all elements with a priority less
+    /// than or equal to its own, so elements with equal priorities are kept in
+    /// insertion order. The list must already be sorted for the result to be
+    /// sorted. This has linear time complexity.
+    ///
+    /// Returns a `CursorMut` pointing to the newly added element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new element is already linked to a different intrusive
+    /// collection.
+    #[inline]
+    pub fn insert_sorted(&mut self, val: <A::PointerOps as PointerOps>::Pointer) -> CursorMut<'_, A>
+    where
+        A: PriorityAdapter,
+    {
+        let new = unsafe {
+            let new = self.acquire_node(val);
+
+            // If a priority panics, the guard drops the new element and the
+            // list is left untouched.
+            let guard = crate::adapter::AcquiredLink::new(&mut self.adapter, new);
+            let adapter = guard.adapter();
+            let priority = adapter.get_priority(&*adapter.get_value(new));
+
+            let mut next = self.head;
+            while let Some(node) = next {
+                if priority < adapter.get_priority(&*adapter.get_value(node)) {
+                    break;
+                }
+                next = adapter.link_ops().next(node);
+            }
+            guard.on_insert();
+            guard.disarm();
+            record_stat!(self, insertions);
+
+            structural!(self, {
+                let link_ops = self.adapter.link_ops_mut();
+                if let Some(next) = next {
+                    link_before(link_ops, new, next);
+                } else {
+                    link_between(link_ops, new, self.tail, None);
+                    self.tail = Some(new);
+                }
+                if self.head == next {
+                    self.head = Some(new);
+                }
+            });
+            new
+        };
+
+        CursorMut {
+            current: Some(new),
+            list: self,
+        }
+    }
+}
+
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+impl<A, C> LinkedList<AtomicCountedAdapter<A, C>>
+where
+    A: Adapter,
+    A::LinkOps: LinkedListOps,
+    C: core::ops::Deref<Target = crate::sync::AtomicUsize>,
+{
+    /// Checks the integrity of the list like `assert_valid`, and that the
+    /// counter of the adapter agrees with the number of elements.
+    ///
+    /// This is only meaningful if the counter isn't shared with other
+    /// collections, and no other thread is modifying them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the list is corrupted or if the counter doesn't match its
+    /// length.
+    pub fn assert_valid_count(&self) -> usize {
+        let len = self.assert_valid();
+        let count = self.adapter.len();
+        assert!(
+            count == len,
+            "linked list is corrupted: the counter is {} but the list has {} elements",
+            count,
+            len,
+        );
+        len
+    }
+}
+
+// Allow read-only access to values from multiple threads. Pointers can be
+// cloned through a shared reference with `clone_pointer`, so they must be
+// `Sync` too.
+unsafe impl<A: Adapter + Sync> Sync for LinkedList<A>
+where
+    <A::PointerOps as PointerOps>::Value: Sync,
+    <A::PointerOps as PointerOps>::Pointer: Sync,
+    A::LinkOps: LinkedListOps,
+{
+}
+
+// Allow sending to another thread if the ownership (represented by the <A::PointerOps as PointerOps>::Pointer owned
+// pointer type) can be transferred to another thread.
+unsafe impl<A: Adapter + Send> Send for LinkedList<A>
+where
+    <A::PointerOps as PointerOps>::Pointer: Send,
+    A::LinkOps: LinkedListOps,
+{
+}
+
+// Cursors and iterators get the same thread safety as a reference to the
+// list: shared for `Cursor` and `Iter`, exclusive for the others.
+unsafe impl<A: Adapter> Send for Cursor<'_, A>
+where
+    LinkedList<A>: Sync,
+    A::LinkOps: LinkedListOps,
+{
+}
+
+unsafe impl<A: Adapter> Sync for Cursor<'_, A>
+where
+    LinkedList<A>: Sync,
+    A::LinkOps: LinkedListOps,
+{
+}
+
+unsafe impl<A: Adapter> Send for CursorMut<'_, A>
+where
+    LinkedList<A>: Send,
+    A::LinkOps: LinkedListOps,
+{
+}
+
+unsafe impl<A: Adapter> Sync for CursorMut<'_, A>
+where
+    LinkedList<A>: Sync,
+    A::LinkOps: LinkedListOps,
+{
+}
+
+unsafe impl<A: Adapter> Send for Iter<'_, A>
+where
+    LinkedList<A>: Sync,
+    A::LinkOps: LinkedListOps,
+{
+}
+
+unsafe impl<A: Adapter> Sync for Iter<'_, A>
+where
+    LinkedList<A>: Sync,
+    A::LinkOps: LinkedListOps,
+{
+}
+
+unsafe impl<A: Adapter> Send for IterMut<'_, A>
+where
+    LinkedList<A>: Send,
+    <A::PointerOps as PointerOps>::Value: Send,
+    A::LinkOps: LinkedListOps,
+    A::PointerOps: ExclusivePointerOps,
+{
+}
+
+unsafe impl<A: Adapter> Sync for IterMut<'_, A>
+where
+    LinkedList<A>: Sync,
+    A::LinkOps: LinkedListOps,
+    A::PointerOps: ExclusivePointerOps,
+{
+}
+
+// Drop all owned pointers if the collection is dropped
+impl<A: Adapter> Drop for LinkedList<A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    #[inline]
+    fn drop(&mut self) {
+        // The links of a poisoned list may be inconsistent, so its elements
+        // are leaked instead of being unlinked.
+        #[cfg(feature = "poison")]
+        if self.is_poisoned() {
+            return;
+        }
+        let leaked = crate::adapter::checks_leaks(&self.adapter) && !self.is_empty();
+        self.clear();
+        if leaked {
+            crate::adapter::leaked_on_drop(&self.adapter, self.debug_name());
+        }
+    }
+}
+
+impl<A: Adapter> Collection for LinkedList<A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    type Adapter = A;
+    type Position = Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>;
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        LinkedList::is_empty(self)
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        LinkedList::clear(self);
+    }
+
+    #[inline]
+    fn insert(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        self.push_back(val);
+    }
+
+    #[inline]
+    fn remove_first<F: FnMut(&<A::PointerOps as PointerOps>::Value) -> bool>(
+        &mut self,
+        mut f: F,
+    ) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        let mut cursor = self.front_mut();
+        loop {
+            if f(cursor.get()?) {
+                return cursor.remove();
+            }
+            cursor.move_next();
+        }
+    }
+
+    #[inline]
+    fn take(&mut self) -> LinkedList<A>
+    where
+        A: Clone,
+    {
+        LinkedList::take(self)
+    }
+
+    #[inline]
+    fn swap(&mut self, other: &mut LinkedList<A>) {
+        LinkedList::swap(self, other);
+    }
+
+    #[inline]
+    fn front_position(&self) -> Self::Position {
+        self.head
+    }
+
+    #[inline]
+    unsafe fn value_at(
+        &self,
+        position: Self::Position,
+    ) -> Option<&<A::PointerOps as PointerOps>::Value> {
+        position.map(|x| &*self.adapter.get_value(x))
+    }
+
+    #[inline]
+    unsafe fn next_position(&self, position: Self::Position) -> Self::Position {
+        self.adapter.link_ops().next(position.unwrap_unchecked())
+    }
+}
+
+impl<A: Adapter> IntoIterator for LinkedList<A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    type Item = <A::PointerOps as PointerOps>::Pointer;
+    type IntoIter = IntoIter<A>;
+
+    #[inline]
+    fn into_iter(self) -> IntoIter<A> {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, A: Adapter + 'a> IntoIterator for &'a LinkedList<A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    type Item = &'a <A::PointerOps as PointerOps>::Value;
+    type IntoIter = Iter<'a, A>;
+
+    #[inline]
+    fn into_iter(self) -> Iter<'a, A> {
+        self.iter()
+    }
+}
+
+impl<A: Adapter + Default> Default for LinkedList<A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    fn default() -> LinkedList<A> {
+        LinkedList::new(A::default())
+    }
+}
+
+// Clones the values into new boxes. The links of the clones are new ones,
+// since cloning a link always returns an unlinked one.
+#[cfg(feature = "alloc")]
+impl<A, V> Clone for LinkedList<A>
+where
+    A: Adapter<PointerOps = DefaultPointerOps<Box<V>>> + Clone,
+    A::LinkOps: LinkedListOps,
+    V: Clone,
+{
+    fn clone(&self) -> LinkedList<A> {
+        let mut list = LinkedList::new(self.adapter.clone());
+        for value in self.iter() {
+            list.push_back(Box::new(value.clone()));
+        }
+        list
+    }
+}
+
+unsafe impl<A: Adapter> BundleMember for LinkedList<A>
+where
+    A::LinkOps: LinkedListOps + link_ops::LinkStateOps,
+{
+    type Adapter = A;
+
+    #[inline]
+    fn is_linked(&self, value: &<A::PointerOps as PointerOps>::Value) -> bool {
+        unsafe { crate::adapter::value_is_linked(&self.adapter, value) }
+    }
+
+    #[inline]
+    fn insert_member(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        self.push_back(val);
+    }
+
+    #[inline]
+    unsafe fn remove_member(
+        &mut self,
+        value: &<A::PointerOps as PointerOps>::Value,
+    ) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        if self.is_linked(value) {
+            self.cursor_mut_from_ptr(value).remove()
+        } else {
+            None
+        }
+    }
+}
+
+impl<A: Adapter> fmt::Debug for LinkedList<A>
+where
+    A::LinkOps: LinkedListOps,
+    <A::PointerOps as PointerOps>::Value: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<A: Adapter> defmt::Format for LinkedList<A>
+where
+    A::LinkOps: LinkedListOps,
+    <A::PointerOps as PointerOps>::Value: defmt::Format,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        crate::adapter::defmt_list(f, self.iter())
+    }
+}
+
+// =============================================================================
+// Iter
+// =============================================================================
+
+/// An iterator over references to the items of a `LinkedList`.
+pub struct Iter<'a, A: Adapter>
+where
+    A::LinkOps: LinkedListOps,
+{
+    head: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+    tail: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+    list: &'a LinkedList<A>,
+    #[cfg(feature = "debug_generation")]
+    generation: usize,
+}
+impl<'a, A: Adapter + 'a> Iterator for Iter<'a, A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    type Item = &'a <A::PointerOps as PointerOps>::Value;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a <A::PointerOps as PointerOps>::Value> {
+        #[cfg(feature = "debug_generation")]
+        self.list
+            .generation
+            .check(self.generation, self.list.debug_name());
+        let head = self.head?;
+
+        if Some(head) == self.tail {
+            self.head = None;
+            self.tail = None;
+        } else {
+            self.head = unsafe { self.list.adapter.link_ops().next(head) };
+        }
+        Some(unsafe { &*self.list.adapter.get_value(head) })
+    }
+}
+impl<'a, A: Adapter + 'a> DoubleEndedIterator for Iter<'a, A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a <A::PointerOps as PointerOps>::Value> {
+        #[cfg(feature = "debug_generation")]
+        self.list
+            .generation
+            .check(self.generation, self.list.debug_name());
+        let tail = self.tail?;
+
+        if Some(tail) == self.head {
+            self.head = None;
+            self.tail = None;
+        } else {
+            self.tail = unsafe { self.list.adapter.link_ops().prev(tail) };
+        }
+        Some(unsafe { &*self.list.adapter.get_value(tail) })
+    }
+}
+impl<'a, A: Adapter + 'a> Clone for Iter<'a, A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    #[inline]
+    fn clone(&self) -> Iter<'a, A> {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            list: self.list,
+            #[cfg(feature = "debug_generation")]
+            generation: self.generation,
+        }
+    }
+}
+
+// =============================================================================
+// IterMut
+// =============================================================================
+
+/// An iterator over mutable references to the items of a `LinkedList`.
+pub struct IterMut<'a, A: Adapter>
+where
+    A::LinkOps: LinkedListOps,
+    A::PointerOps: ExclusivePointerOps,
+{
+    head: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+    tail: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+    list: &'a mut LinkedList<A>,
+}
+impl<'a, A: Adapter + 'a> Iterator for IterMut<'a, A>
+where
+    A::LinkOps: LinkedListOps,
+    A::PointerOps: ExclusivePointerOps,
+{
+    type Item = &'a mut <A::PointerOps as PointerOps>::Value;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut <A::PointerOps as PointerOps>::Value> {
+        let head = self.head?;
+
+        if Some(head) == self.tail {
+            self.head = None;
+            self.tail = None;
+        } else {
+            self.head = unsafe { self.list.adapter.link_ops().next(head) };
+        }
+        Some(unsafe {
+            &mut *self
+                .list
+                .adapter
+                .pointer_ops()
+                .get_mut(self.list.adapter.get_value(head))
+        })
+    }
+}
+impl<'a, A: Adapter + 'a> DoubleEndedIterator for IterMut<'a, A>
+where
+    A::LinkOps: LinkedListOps,
+    A::PointerOps: ExclusivePointerOps,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut <A::PointerOps as PointerOps>::Value> {
+        let tail = self.tail?;
+
+        if Some(tail) == self.head {
+            self.head = None;
+            self.tail = None;
+        } else {
+            self.tail = unsafe { self.list.adapter.link_ops().prev(tail) };
+        }
+        Some(unsafe {
+            &mut *self
+                .list
+                .adapter
+                .pointer_ops()
+                .get_mut(self.list.adapter.get_value(tail))
+        })
+    }
+}
+
+// =============================================================================
+// IntoIter
+// =============================================================================
+
+/// An iterator which consumes a `LinkedList`.
+pub struct IntoIter<A: Adapter>
+where
+    A::LinkOps: LinkedListOps,
+{
+    list: LinkedList<A>,
+}
+impl<A: Adapter> Iterator for IntoIter<A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    type Item = <A::PointerOps as PointerOps>::Pointer;
+
+    #[inline]
+    fn next(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        self.list.pop_front()
+    }
+}
+impl<A: Adapter> DoubleEndedIterator for IntoIter<A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        self.list.pop_back()
+    }
+}
+
+// =============================================================================
+// ScopedList
+// =============================================================================
+
+/// Runs `f` with a `LinkedList` which is drained when `f` returns.
+///
+/// This is meant for lists of borrowed elements, such as `&'a T` or
+/// `Pin<&'a T>`: once the scope is over, every element that was inserted in
+/// the list has been unlinked, even if `f` panicked, so they can be inserted
+/// in other lists or moved again. Like `thread::scope`, `f` only gets a
+/// reference to the list, which can't be moved out of the scope or leaked.
+///
+/// The cursors of a scoped list are `ScopedCursorMut`s, which can't split the
+/// list, or move its elements into another list:
+///
+/// ```compile_fail
+/// use intrusive_collections::linked_list;
+/// use intrusive_collections::{intrusive_adapter, LinkedListLink};
+///
+/// struct Task {
+///     link: LinkedListLink,
+/// }
+/// intrusive_adapter!(TaskAdapter<'a> = &'a Task: Task { link: LinkedListLink });
+///
+/// let task = Task { link: LinkedListLink::new() };
+/// let rest = linked_list::scope(TaskAdapter::new(), |list| {
+///     list.push_back(&task);
+///     list.cursor_mut().split_after()
+/// });
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::linked_list;
+/// use intrusive_collections::{intrusive_adapter, LinkedListLink};
+///
+/// struct Task {
+///     link: LinkedListLink,
+///     priority: u32,
+/// }
+/// intrusive_adapter!(TaskAdapter<'a> = &'a Task: Task { link: LinkedListLink });
+///
+/// let tasks: Vec<Task> = (0..4)
+///     .map(|priority| Task { link: LinkedListLink::new(), priority })
+///     .collect();
+///
+/// let sum = linked_list::scope(TaskAdapter::new(), |list| {
+///     for task in tasks.iter().filter(|task| task.priority % 2 == 1) {
+///         list.push_back(task);
+///     }
+///     list.iter().map(|task| task.priority).sum::<u32>()
+/// });
+/// assert_eq!(sum, 4);
+/// assert!(tasks.iter().all(|task| !task.link.is_linked()));
+/// ```
+#[inline]
+pub fn scope<A: Adapter, R, F>(adapter: A, f: F) -> R
+where
+    A::LinkOps: LinkedListOps,
+    F: FnOnce(&mut ScopedList<A>) -> R,
+{
+    // Dropping the list unlinks its elements, including while unwinding.
+    let mut list = ScopedList {
+        list: LinkedList::new(adapter),
+    };
+    f(&mut list)
+}
+
+/// A `LinkedList` which only lives for the duration of a call to `scope`.
+///
+/// It dereferences to the `LinkedList` for reading, and offers the methods
+/// which modify the list in place, but none which would move the elements
+/// into a list outliving the scope.
+pub struct ScopedList<A: Adapter>
+where
+    A::LinkOps: LinkedListOps,
+{
+    list: LinkedList<A>,
+}
+
+impl<A: Adapter> ScopedList<A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    /// Returns a null `ScopedCursorMut` for the list.
+    #[inline]
+    pub fn cursor_mut(&mut self) -> ScopedCursorMut<'_, A> {
+        ScopedCursorMut {
+            cursor: self.list.cursor_mut(),
+        }
+    }
+
+    /// Creates a `CursorMut` from a pointer to an element.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a pointer to an object that is part of this list.
+    #[inline]
+    pub unsafe fn cursor_mut_from_ptr(
+        &mut self,
+        ptr: *const <A::PointerOps as PointerOps>::Value,
+    ) -> ScopedCursorMut<'_, A> {
+        ScopedCursorMut {
+            cursor: self.list.cursor_mut_from_ptr(ptr),
+        }
+    }
+
+    /// Returns a `ScopedCursorMut` pointing to the first element of the list.
+    /// If the list is empty then a null cursor is returned.
+    #[inline]
+    pub fn front_mut(&mut self) -> ScopedCursorMut<'_, A> {
+        ScopedCursorMut {
+            cursor: self.list.front_mut(),
+        }
+    }
+
+    /// Returns a `ScopedCursorMut` pointing to the last element of the list.
+    /// If the list is empty then a null cursor is returned.
+    #[inline]
+    pub fn back_mut(&mut self) -> ScopedCursorMut<'_, A> {
+        ScopedCursorMut {
+            cursor: self.list.back_mut(),
+        }
+    }
+
+    /// Inserts a new element at the start of the list.
+    #[inline]
+    pub fn push_front(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        self.list.push_front(val);
+    }
+
+    /// Inserts a new element at the end of the list.
+    #[inline]
+    pub fn push_back(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        self.list.push_back(val);
+    }
+
+    /// Removes the first element of the list and returns it, or `None` if
+    /// the list is empty.
+    #[inline]
+    pub fn pop_front(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        self.list.pop_front()
+    }
+
+    /// Removes the last element of the list and returns it, or `None` if the
+    /// list is empty.
+    #[inline]
+    pub fn pop_back(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        self.list.pop_back()
+    }
+
+    /// Removes all elements from the list.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.list.clear();
+    }
+}
+
+// The scope drains the list on purpose, so this isn't a leak as far as
+// `debug_leaks` is concerned.
+impl<A: Adapter> Drop for ScopedList<A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    #[inline]
+    fn drop(&mut self) {
+        #[cfg(feature = "poison")]
+        if self.list.is_poisoned() {
+            return;
+        }
+        self.list.clear();
+    }
+}
+
+impl<A: Adapter> Deref for ScopedList<A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    type Target = LinkedList<A>;
+
+    #[inline]
+    fn deref(&self) -> &LinkedList<A> {
+        &self.list
+    }
+}
+
+impl<A: Adapter> fmt::Debug for ScopedList<A>
+where
+    A::LinkOps: LinkedListOps,
+    <A::PointerOps as PointerOps>::Value: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.list.fmt(f)
+    }
+}
+
+/// A `CursorMut` into a `ScopedList`.
+///
+/// It dereferences to the `CursorMut` for reading, and offers the methods
+/// which modify the list in place, but not `split_after`, `split_before`,
+/// `transfer_after`, `transfer_before` or `retire`, which would move elements
+/// into a list outliving the scope.
+pub struct ScopedCursorMut<'a, A: Adapter>
+where
+    A::LinkOps: LinkedListOps,
+{
+    cursor: CursorMut<'a, A>,
+}
+
+impl<'a, A: Adapter> ScopedCursorMut<'a, A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    /// Returns a mutable reference to the object that the cursor is
+    /// currently pointing to.
+    ///
+    /// This returns None if the cursor is currently pointing to the null
+    /// object.
+    ///
+    /// # Safety
+    ///
+    /// You must not modify any links that are linked
+    #[inline]
+    pub unsafe fn get_mut(&mut self) -> Option<&mut <A::PointerOps as PointerOps>::Value>
+    where
+        A::PointerOps: ExclusivePointerOps,
+    {
+        self.cursor.get_mut()
+    }
+
+    /// Moves the cursor to the next element of the list.
+    ///
+    /// If the cursor is pointer to the null object then this will move it to
+    /// the first element of the list. If it is pointing to the last element
+    /// of the list then this will move it to the null object.
+    #[inline]
+    pub fn move_next(&mut self) {
+        self.cursor.move_next();
+    }
+
+    /// Moves the cursor to the previous element of the list.
+    ///
+    /// If the cursor is pointer to the null object then this will move it to
+    /// the last element of the list. If it is pointing to the first element
+    /// of the list then this will move it to the null object.
+    #[inline]
+    pub fn move_prev(&mut self) {
+        self.cursor.move_prev();
+    }
+
+    /// Removes the current element from the list.
+    ///
+    /// A pointer to the element that was removed is returned, and the cursor is
+    /// moved to point to the next element in the list.
+    ///
+    /// If the cursor is currently pointing to the null object then no element
+    /// is removed and `None` is returned.
+    #[inline]
+    pub fn remove(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        self.cursor.remove()
+    }
+
+    /// Removes the current element from the list and inserts another
+    /// object in its place.
+    ///
+    /// A pointer to the element that was removed is returned, and the cursor is
+    /// modified to point to the newly added element.
+    ///
+    /// If the cursor is currently pointing to the null object then an error is
+    /// returned containing the given `val` parameter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new element is already linked to a different intrusive
+    /// collection.
+    #[inline]
+    pub fn replace_with(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<<A::PointerOps as PointerOps>::Pointer, <A::PointerOps as PointerOps>::Pointer>
+    {
+        self.cursor.replace_with(val)
+    }
+
+    /// Inserts a new element into the list after the current one.
+    ///
+    /// If the cursor is pointing at the null object then the new element is
+    /// inserted at the front of the list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new element is already linked to a different intrusive
+    /// collection.
+    #[inline]
+    pub fn insert_after(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        self.cursor.insert_after(val);
+    }
+
+    /// Inserts a new element into the list before the current one.
+    ///
+    /// If the cursor is pointing at the null object then the new element is
+    /// inserted at the end of the list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new element is already linked to a different intrusive
+    /// collection.
+    #[inline]
+    pub fn insert_before(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        self.cursor.insert_before(val);
+    }
+
+    /// Consumes the cursor and returns a reference to the object that the
+    /// cursor is currently pointing to, which lives as long as the borrow of
+    /// the list.
+    ///
+    /// This returns None if the cursor is currently pointing to the null object.
+    #[inline]
+    pub fn into_ref(self) -> Option<&'a <A::PointerOps as PointerOps>::Value> {
+        self.cursor.into_ref()
+    }
+}
+
+impl<'a, A: Adapter> Deref for ScopedCursorMut<'a, A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    type Target = CursorMut<'a, A>;
+
+    #[inline]
+    fn deref(&self) -> &CursorMut<'a, A> {
+        &self.cursor
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use alloc::boxed::Box;
+
+    use crate::UnsafeRef;
+
+    use super::{CursorOwning, Link, LinkedList};
+    use std::fmt;
+    use std::format;
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    struct Obj {
+        link1: Link,
+        link2: Link,
+        value: u32,
+    }
+    impl fmt::Debug for Obj {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.value)
+        }
+    }
+    intrusive_adapter!(ObjAdapter1 = Rc<Obj>: Obj { link1: Link });
+    intrusive_adapter!(ObjAdapter2 = Rc<Obj>: Obj { link2: Link });
+    intrusive_adapter!(UnsafeRefObjAdapter1 = UnsafeRef<Obj>: Obj { link1: Link });
+
+    fn make_rc_obj(value: u32) -> Rc<Obj> {
+        Rc::new(make_obj(value))
+    }
+
+    fn make_obj(value: u32) -> Obj {
+        Obj {
+            link1: Link::new(),
+            link2: Link::default(),
+            value,
+        }
+    }
+
+    #[test]
+    fn test_link() {
+        let a = make_rc_obj(1);
+        assert!(!a.link1.is_linked());
+        assert!(!a.link2.is_linked());
+
+        let mut b = LinkedList::<ObjAdapter1>::default();
+        assert!(b.is_empty());
+
+        b.cursor_mut().insert_after(a.clone());
+        assert!(!b.is_empty());
+        assert!(a.link1.is_linked());
+        assert!(!a.link2.is_linked());
+        assert_eq!(format!("{:?}", a.link1), "linked");
+        assert_eq!(format!("{:?}", a.link2), "unlinked");
+
+        assert_eq!(
+            b.front_mut().remove().unwrap().as_ref() as *const _,
+            a.as_ref() as *const _
+        );
+        assert!(b.is_empty());
+        assert!(!a.link1.is_linked());
+        assert!(!a.link2.is_linked());
+    }
+
+    #[test]
+    fn test_cursor() {
+        let a = make_rc_obj(1);
+        let b = make_rc_obj(2);
+        let c = make_rc_obj(3);
+
+        let mut l = LinkedList::new(ObjAdapter1::new());
+        let mut cur = l.cursor_mut();
+        assert!(cur.is_null());
+        assert!(cur.get().is_none());
+        assert!(cur.remove().is_none());
+        assert_eq!(
+            cur.replace_with(a.clone()).unwrap_err().as_ref() as *const _,
+            a.as_ref() as *const _
+        );
+
+        cur.insert_before(a.clone());
+        cur.insert_before(c.clone());
+        cur.move_prev();
+        cur.insert_before(b.clone());
+        assert!(cur.peek_next().is_null());
+        cur.move_next();
+        assert!(cur.is_null());
+
+        cur.move_next();
+        assert!(cur.peek_prev().is_null());
+        assert!(!cur.is_null());
+        assert_eq!(cur.get().unwrap() as *const _, a.as_ref() as *const _);
+
+        {
+            let mut cur2 = cur.as_cursor();
+            assert_eq!(cur2.get().unwrap() as *const _, a.as_ref() as *const _);
+            assert_eq!(cur2.peek_next().get().unwrap().value, 2);
+            cur2.move_next();
+            assert_eq!(cur2.get().unwrap().value, 2);
+            cur2.move_next();
+            assert_eq!(cur2.peek_prev().get().unwrap().value, 2);
+            assert_eq!(cur2.get().unwrap() as *const _, c.as_ref() as *const _);
+            cur2.move_prev();
+            assert_eq!(cur2.get().unwrap() as *const _, b.as_ref() as *const _);
+            cur2.move_next();
+            assert_eq!(cur2.get().unwrap() as *const _, c.as_ref() as *const _);
+            cur2.move_next();
+            assert!(cur2.is_null());
+            assert!(cur2.clone().get().is_none());
+        }
+        assert_eq!(cur.get().unwrap() as *const _, a.as_ref() as *const _);
+
+        cur.move_next();
+        assert_eq!(
+            cur.remove().unwrap().as_ref() as *const _,
+            b.as_ref() as *const _
+        );
+        assert_eq!(cur.get().unwrap() as *const _, c.as_ref() as *const _);
+        cur.insert_after(b.clone());
+        assert_eq!(cur.get().unwrap() as *const _, c.as_ref() as *const _);
+        cur.move_prev();
+        assert_eq!(cur.get().unwrap() as *const _, a.as_ref() as *const _);
+        assert_eq!(
+            cur.remove().unwrap().as_ref() as *const _,
+            a.as_ref() as *const _
+        );
+        assert!(!a.link1.is_linked());
+        assert!(c.link1.is_linked());
+        assert_eq!(cur.get().unwrap() as *const _, c.as_ref() as *const _);
+        assert_eq!(
+            cur.replace_with(a.clone()).unwrap().as_ref() as *const _,
+            c.as_ref() as *const _
+        );
+        assert!(a.link1.is_linked());
+        assert!(!c.link1.is_linked());
+        assert_eq!(cur.get().unwrap() as *const _, a.as_ref() as *const _);
+        cur.move_next();
+        assert_eq!(
+            cur.replace_with(c.clone()).unwrap().as_ref() as *const _,
+            b.as_ref() as *const _
+        );
+        assert!(a.link1.is_linked());
+        assert!(!b.link1.is_linked());
+        assert!(c.link1.is_linked());
+        assert_eq!(cur.get().unwrap() as *const _, c.as_ref() as *const _);
+    }
+
+    #[test]
+    fn test_cursor_owning() {
+        struct Container {
+            cur: CursorOwning<ObjAdapter1>,
+        }
+
+        let mut l = LinkedList::new(ObjAdapter1::new());
+        l.push_back(make_rc_obj(1));
+        l.push_back(make_rc_obj(2));
+        l.push_back(make_rc_obj(3));
+        l.push_back(make_rc_obj(4));
+        let mut con = Container {
+            cur: l.cursor_owning(),
+        };
+        assert!(con.cur.as_cursor().is_null());
+
+        con.cur = con.cur.into_inner().front_owning();
+        assert_eq!(con.cur.as_cursor().get().unwrap().value, 1);
+
+        con.cur.with_cursor_mut(|c| c.move_next());
+        assert_eq!(con.cur.as_cursor().get().unwrap().value, 2);
+
+        con.cur = con.cur.into_inner().back_owning();
+        assert_eq!(con.cur.as_cursor().get().unwrap().value, 4);
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let a = make_rc_obj(1);
+        let b = make_rc_obj(2);
+        let c = make_rc_obj(3);
+
+        let mut l = LinkedList::new(ObjAdapter1::new());
+        l.push_front(a);
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [1]);
+        l.push_front(b);
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [2, 1]);
+        l.push_back(c);
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [2, 1, 3]);
+        assert_eq!(l.pop_front().unwrap().value, 2);
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3]);
+        assert_eq!(l.pop_back().unwrap().value, 3);
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [1]);
+        assert_eq!(l.pop_front().unwrap().value, 1);
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+        assert!(l.pop_front().is_none());
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+        assert!(l.pop_back().is_none());
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+    }
+
+    #[test]
+    fn test_cursor_at() {
+        let mut l = LinkedList::new(ObjAdapter1::new());
+        assert!(l.cursor_at(0).is_null());
+        for value in 1..5 {
+            l.push_back(make_rc_obj(value));
+        }
+        assert_eq!(l.cursor_at(0).get().unwrap().value, 1);
+        assert_eq!(l.cursor_at(3).get().unwrap().value, 4);
+        assert!(l.cursor_at(4).is_null());
+        assert!(l.cursor_at(usize::MAX).is_null());
+        assert_eq!(l.cursor_mut_at(3).remove().unwrap().value, 4);
+        assert_eq!(l.cursor_at_back(0).get().unwrap().value, 3);
+        assert_eq!(l.cursor_at_back(2).get().unwrap().value, 1);
+        assert!(l.cursor_at_back(3).is_null());
+        assert_eq!(l.cursor_mut_at_back(1).remove().unwrap().value, 2);
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3]);
+    }
+
+    #[test]
+    fn test_into_cursor() {
+        let mut l = LinkedList::new(ObjAdapter1::new());
+        for value in 1..4 {
+            l.push_back(make_rc_obj(value));
+        }
+        let mut cur = l.front_mut();
+        cur.move_next();
+        assert_eq!(cur.as_cursor().peek_next().get().unwrap().value, 3);
+        let mut cur = cur.into_cursor();
+        let c2 = cur.clone();
+        cur.move_next();
+        assert_eq!(cur.get().unwrap().value, 3);
+        assert_eq!(c2.get().unwrap().value, 2);
+    }
+
+    #[test]
+    fn test_clone() {
+        #[derive(Clone)]
+        struct Obj {
+            link: Link,
+            value: u32,
+        }
+        intrusive_adapter!(ObjAdapter = Box<Obj>: Obj { link: Link });
+
+        let mut l = LinkedList::new(ObjAdapter::new());
+        for value in 1..4 {
+            l.push_back(Box::new(Obj {
+                link: Link::new(),
+                value,
+            }));
+        }
+        let mut l2 = l.clone();
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 2, 3]);
+        l.clear();
+        assert!(l2.iter().all(|x| x.link.is_linked()));
+        l2.push_back(Box::new(Obj {
+            link: Link::new(),
+            value: 4,
+        }));
+        assert_eq!(l2.iter().count(), 4);
+    }
+
+    #[test]
+    fn test_visit() {
+        use core::ops::ControlFlow;
+
+        let mut l = LinkedList::new(ObjAdapter1::new());
+        assert_eq!(
+            l.visit(|_| ControlFlow::Break(())),
+            ControlFlow::Continue(())
+        );
+        for value in 1..5 {
+            l.push_back(make_rc_obj(value));
+        }
+        let mut values = Vec::new();
+        let res = l.visit(|x| {
+            values.push(x.value);
+            ControlFlow::<()>::Continue(())
+        });
+        assert_eq!(res, ControlFlow::Continue(()));
+        assert_eq!(values, [1, 2, 3, 4]);
+
+        values.clear();
+        let res = l.visit(|x| {
+            values.push(x.value);
+            if x.value == 2 {
+                ControlFlow::Break(x.value * 10)
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        assert_eq!(res, ControlFlow::Break(20));
+        assert_eq!(values, [1, 2]);
+    }
+
+    #[test]
+    fn test_try_insert() {
+        use crate::InsertError;
+
+        let a = make_rc_obj(1);
+        let b = make_rc_obj(2);
+        let c = make_rc_obj(3);
+
+        let mut l = LinkedList::new(ObjAdapter1::new());
+        let mut l2 = LinkedList::new(ObjAdapter1::new());
+        assert!(l.try_push_back(a.clone()).is_ok());
+        assert!(l.try_push_front(b.clone()).is_ok());
+        match l2.try_push_back(a.clone()) {
+            Err(InsertError::AlreadyLinked(x)) => assert!(Rc::ptr_eq(&x, &a)),
+            _ => panic!("expected an error"),
+        }
+        assert!(l2.is_empty());
+        assert!(matches!(
+            l.front_mut().try_insert_before(a.clone()),
+            Err(InsertError::AlreadyLinked(_))
+        ));
+        assert!(l.back_mut().try_insert_after(c.clone()).is_ok());
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [2, 1, 3]);
+
+        let err = l.cursor_mut().try_replace_with(c.clone()).unwrap_err();
+        assert_eq!(format!("{:?}", err), "NullCursor(..)");
+        assert!(Rc::ptr_eq(&err.into_inner(), &c));
+        let err = l.front_mut().try_replace_with(c.clone()).unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "attempted to insert an object that is already linked"
+        );
+        let d = make_rc_obj(4);
+        assert!(Rc::ptr_eq(&l.front_mut().try_replace_with(d).unwrap(), &b));
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [4, 1, 3]);
+    }
+
+    #[test]
+    fn test_split_splice() {
+        let mut l1 = LinkedList::new(ObjAdapter1::new());
+        let mut l2 = LinkedList::new(ObjAdapter1::new());
+        let mut l3 = LinkedList::new(ObjAdapter1::new());
+
+        let a = make_rc_obj(1);
+        let b = make_rc_obj(2);
+        let c = make_rc_obj(3);
+        let d = make_rc_obj(4);
+        l1.cursor_mut().insert_before(a);
+        l1.cursor_mut().insert_before(b);
+        l1.cursor_mut().insert_before(c);
+        l1.cursor_mut().insert_before(d);
+        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 2, 3, 4]);
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+        {
+            let mut cur = l1.front_mut();
+            cur.move_next();
+            l2 = cur.split_after();
+        }
+        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [3, 4]);
+        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+        {
+            let mut cur = l2.back_mut();
+            l3 = cur.split_before();
+        }
+        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [4]);
+        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), [3]);
+        {
+            let mut cur = l1.front_mut();
+            cur.splice_after(l2.take());
+        }
+        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 4, 2]);
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), [3]);
+        {
+            let mut cur = l1.front_mut();
+            cur.move_next();
+            cur.splice_before(l3.take());
+        }
+        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3, 4, 2]);
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+        {
+            let mut cur = l2.cursor_mut();
+            cur.splice_after(l1.take());
+        }
+        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3, 4, 2]);
+        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+        {
+            let mut cur = l1.cursor_mut();
+            cur.splice_before(l2.take());
+        }
+        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3, 4, 2]);
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+        {
+            let mut cur = l1.cursor_mut();
+            l2 = cur.split_after();
+        }
+        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3, 4, 2]);
+        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+        {
+            let mut cur = l2.cursor_mut();
+            l1 = cur.split_before();
+        }
+        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3, 4, 2]);
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+        {
+            let mut cur = l1.front_mut();
+            l2 = cur.split_before();
+        }
+        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3, 4, 2]);
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+        {
+            let mut cur = l1.back_mut();
+            l2 = cur.split_after();
+        }
+        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3, 4, 2]);
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut l = LinkedList::new(ObjAdapter1::new());
+        let a = make_rc_obj(1);
+        let b = make_rc_obj(2);
+        let c = make_rc_obj(3);
+        let d = make_rc_obj(4);
+        l.cursor_mut().insert_before(a.clone());
+        l.cursor_mut().insert_before(b.clone());
+        l.cursor_mut().insert_before(c.clone());
+        l.cursor_mut().insert_before(d.clone());
+
+        assert_eq!(l.front().get().unwrap().value, 1);
+        assert_eq!(l.back().get().unwrap().value, 4);
+        unsafe {
+            assert_eq!(l.cursor_from_ptr(b.as_ref()).get().unwrap().value, 2);
+            assert_eq!(l.cursor_mut_from_ptr(c.as_ref()).get().unwrap().value, 3);
+        }
+
+        let mut v = Vec::new();
+        for x in &l {
+            v.push(x.value);
+        }
+        assert_eq!(v, [1, 2, 3, 4]);
+        assert_eq!(
+            l.iter().clone().map(|x| x.value).collect::<Vec<_>>(),
+            [1, 2, 3, 4]
+        );
+        assert_eq!(
+            l.iter().rev().map(|x| x.value).collect::<Vec<_>>(),
+            [4, 3, 2, 1]
+        );
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 2, 3, 4]);
+
+        assert_eq!(format!("{:?}", l), "[1, 2, 3, 4]");
+
+        let mut v = Vec::new();
+        for x in l.take() {
+            v.push(x.value);
+        }
+        assert_eq!(v, [1, 2, 3, 4]);
+        assert!(l.is_empty());
+        assert!(!a.link1.is_linked());
+        assert!(!b.link1.is_linked());
+        assert!(!c.link1.is_linked());
+        assert!(!d.link1.is_linked());
+
+        l.cursor_mut().insert_before(a.clone());
+        l.cursor_mut().insert_before(b.clone());
+        l.cursor_mut().insert_before(c.clone());
+        l.cursor_mut().insert_before(d.clone());
+        l.clear();
+        assert!(l.is_empty());
+        assert!(!a.link1.is_linked());
+        assert!(!b.link1.is_linked());
+        assert!(!c.link1.is_linked());
+        assert!(!d.link1.is_linked());
+
+        v.clear();
+        l.cursor_mut().insert_before(a.clone());
+        l.cursor_mut().insert_before(b.clone());
+        l.cursor_mut().insert_before(c.clone());
+        l.cursor_mut().insert_before(d.clone());
+        for x in l.into_iter().rev() {
+            v.push(x.value);
         }
-        Some(unsafe {
-            &mut *self
-                .list
-                .adapter
-                .pointer_ops()
-                .get_mut(self.list.adapter.get_value(tail))
-        })
+        assert_eq!(v, [4, 3, 2, 1]);
+        assert!(!a.link1.is_linked());
+        assert!(!b.link1.is_linked());
+        assert!(!c.link1.is_linked());
+        assert!(!d.link1.is_linked());
     }
-}
 
-// =============================================================================
-// IntoIter
-// =============================================================================
+    #[test]
+    fn test_multi_list() {
+        let mut l1 = LinkedList::new(ObjAdapter1::new());
+        let mut l2 = LinkedList::new(ObjAdapter2::new());
+        let a = make_rc_obj(1);
+        let b = make_rc_obj(2);
+        let c = make_rc_obj(3);
+        let d = make_rc_obj(4);
+        l1.cursor_mut().insert_before(a.clone());
+        l1.cursor_mut().insert_before(b.clone());
+        l1.cursor_mut().insert_before(c.clone());
+        l1.cursor_mut().insert_before(d.clone());
+        l2.cursor_mut().insert_after(a);
+        l2.cursor_mut().insert_after(b);
+        l2.cursor_mut().insert_after(c);
+        l2.cursor_mut().insert_after(d);
+        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 2, 3, 4]);
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [4, 3, 2, 1]);
+    }
 
-/// An iterator which consumes a `LinkedList`.
-pub struct IntoIter<A: Adapter>
-where
-    A::LinkOps: LinkedListOps,
-{
-    list: LinkedList<A>,
-}
-impl<A: Adapter> Iterator for IntoIter<A>
-where
-    A::LinkOps: LinkedListOps,
-{
-    type Item = <A::PointerOps as PointerOps>::Pointer;
+    #[test]
+    fn test_fast_clear_force_unlink() {
+        let mut l = LinkedList::new(UnsafeRefObjAdapter1::new());
+        let a = UnsafeRef::from_box(Box::new(make_obj(1)));
+        let b = UnsafeRef::from_box(Box::new(make_obj(2)));
+        let c = UnsafeRef::from_box(Box::new(make_obj(3)));
+        l.cursor_mut().insert_before(a.clone());
+        l.cursor_mut().insert_before(b.clone());
+        l.cursor_mut().insert_before(c.clone());
 
-    #[inline]
-    fn next(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
-        self.list.pop_front()
-    }
-}
-impl<A: Adapter> DoubleEndedIterator for IntoIter<A>
-where
-    A::LinkOps: LinkedListOps,
-{
-    #[inline]
-    fn next_back(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
-        self.list.pop_back()
-    }
-}
+        l.fast_clear();
+        assert!(l.is_empty());
 
-// =============================================================================
-// Tests
-// =============================================================================
+        unsafe {
+            assert!(a.link1.is_linked());
+            assert!(b.link1.is_linked());
+            assert!(c.link1.is_linked());
 
-#[cfg(test)]
-mod tests {
-    use alloc::boxed::Box;
+            a.link1.force_unlink();
+            b.link1.force_unlink();
+            c.link1.force_unlink();
 
-    use crate::UnsafeRef;
+            assert!(l.is_empty());
 
-    use super::{CursorOwning, Link, LinkedList};
-    use std::fmt;
-    use std::format;
-    use std::rc::Rc;
-    use std::vec::Vec;
+            assert!(!a.link1.is_linked());
+            assert!(!b.link1.is_linked());
+            assert!(!c.link1.is_linked());
+        }
 
-    struct Obj {
-        link1: Link,
-        link2: Link,
-        value: u32,
-    }
-    impl fmt::Debug for Obj {
-        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            write!(f, "{}", self.value)
+        unsafe {
+            UnsafeRef::into_box(a);
+            UnsafeRef::into_box(b);
+            UnsafeRef::into_box(c);
         }
     }
-    intrusive_adapter!(ObjAdapter1 = Rc<Obj>: Obj { link1: Link });
-    intrusive_adapter!(ObjAdapter2 = Rc<Obj>: Obj { link2: Link });
-    intrusive_adapter!(UnsafeRefObjAdapter1 = UnsafeRef<Obj>: Obj { link1: Link });
 
-    fn make_rc_obj(value: u32) -> Rc<Obj> {
-        Rc::new(make_obj(value))
+    #[test]
+    fn test_static() {
+        use std::sync::Mutex;
+
+        struct Task {
+            link: Link,
+            id: u32,
+        }
+        intrusive_adapter!(TaskAdapter = Box<Task>: Task { link: Link });
+
+        static RUN_QUEUE: Mutex<LinkedList<TaskAdapter>> =
+            Mutex::new(LinkedList::new(TaskAdapter::NEW));
+
+        let mut queue = RUN_QUEUE.lock().unwrap();
+        queue.push_back(Box::new(Task {
+            link: Link::new(),
+            id: 1,
+        }));
+        queue.push_back(Box::new(Task {
+            link: Link::new(),
+            id: 2,
+        }));
+        assert_eq!(queue.pop_front().unwrap().id, 1);
+        assert_eq!(queue.pop_front().unwrap().id, 2);
+        assert!(queue.is_empty());
     }
 
-    fn make_obj(value: u32) -> Obj {
-        Obj {
-            link1: Link::new(),
-            link2: Link::default(),
-            value,
+    #[test]
+    fn test_make_mut() {
+        #[derive(Clone)]
+        struct Obj {
+            link: Link,
+            value: u32,
+        }
+        intrusive_adapter!(ObjAdapter = Rc<Obj>: Obj { link: Link });
+
+        let a = Rc::new(Obj {
+            link: Link::new(),
+            value: 1,
+        });
+        let mut l = LinkedList::new(ObjAdapter::new());
+        l.push_back(a.clone());
+        l.push_back(Rc::new(Obj {
+            link: Link::new(),
+            value: 2,
+        }));
+        let unique = l.back().get().unwrap() as *const Obj;
+        unsafe {
+            let mut cur = l.front_mut();
+            while let Some(obj) = cur.make_mut() {
+                obj.value *= 10;
+                cur.move_next();
+            }
         }
+        assert!(!a.link.is_linked());
+        assert_eq!(a.value, 1);
+        assert_eq!(Rc::strong_count(&a), 1);
+        assert_eq!(l.back().get().unwrap() as *const Obj, unique);
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [10, 20]);
     }
 
     #[test]
-    fn test_link() {
-        let a = make_rc_obj(1);
-        assert!(!a.link1.is_linked());
-        assert!(!a.link2.is_linked());
+    fn test_scope() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
 
-        let mut b = LinkedList::<ObjAdapter1>::default();
-        assert!(b.is_empty());
+        intrusive_adapter!(RefAdapter<'a> = &'a Obj: Obj { link1: Link });
 
-        b.cursor_mut().insert_after(a.clone());
-        assert!(!b.is_empty());
-        assert!(a.link1.is_linked());
-        assert!(!a.link2.is_linked());
-        assert_eq!(format!("{:?}", a.link1), "linked");
-        assert_eq!(format!("{:?}", a.link2), "unlinked");
+        let objs: Vec<Obj> = (0..4).map(make_obj).collect();
+        let values = super::scope(RefAdapter::new(), |l| {
+            for obj in &objs {
+                l.push_front(obj);
+            }
+            l.pop_back();
+            l.front_mut().remove();
+            let mut cur = l.back_mut();
+            cur.move_prev();
+            assert_eq!(cur.get().unwrap().value, 2);
+            cur.insert_after(&objs[3]);
+            assert_eq!(cur.peek_next().get().unwrap().value, 3);
+            l.iter().map(|x| x.value).collect::<Vec<_>>()
+        });
+        assert_eq!(values, [2, 3, 1]);
+        assert!(objs.iter().all(|obj| !obj.link1.is_linked()));
+
+        // The elements are unlinked even if the scope panics.
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            super::scope(RefAdapter::new(), |l| {
+                l.push_back(&objs[0]);
+                l.push_back(&objs[1]);
+                panic!("scope");
+            })
+        }));
+        assert!(result.is_err());
+        assert!(objs.iter().all(|obj| !obj.link1.is_linked()));
+    }
 
-        assert_eq!(
-            b.front_mut().remove().unwrap().as_ref() as *const _,
-            a.as_ref() as *const _
-        );
-        assert!(b.is_empty());
-        assert!(!a.link1.is_linked());
-        assert!(!a.link2.is_linked());
+    #[test]
+    fn test_mut_ref() {
+        struct Obj {
+            link: Link,
+            value: u32,
+        }
+        intrusive_adapter!(ObjAdapter<'a> = &'a mut Obj: Obj { link: Link });
+
+        let mut a = Obj {
+            link: Link::new(),
+            value: 1,
+        };
+        let mut b = Obj {
+            link: Link::new(),
+            value: 2,
+        };
+        let mut l = LinkedList::new(ObjAdapter::new());
+        l.push_back(&mut a);
+        l.push_back(&mut b);
+        unsafe {
+            for obj in l.iter_mut() {
+                obj.value *= 10;
+            }
+            l.front_mut().get_mut().unwrap().value += 1;
+        }
+        let a = l.pop_front().unwrap();
+        a.value += 1;
+        assert_eq!(a.value, 12);
+        assert_eq!(l.back().get().unwrap().value, 20);
+        l.clear();
     }
 
     #[test]
-    fn test_cursor() {
+    fn test_transfer() {
         let a = make_rc_obj(1);
         let b = make_rc_obj(2);
         let c = make_rc_obj(3);
-
-        let mut l = LinkedList::new(ObjAdapter1::new());
-        let mut cur = l.cursor_mut();
-        assert!(cur.is_null());
-        assert!(cur.get().is_none());
-        assert!(cur.remove().is_none());
-        assert_eq!(
-            cur.replace_with(a.clone()).unwrap_err().as_ref() as *const _,
-            a.as_ref() as *const _
-        );
-
-        cur.insert_before(a.clone());
-        cur.insert_before(c.clone());
-        cur.move_prev();
-        cur.insert_before(b.clone());
-        assert!(cur.peek_next().is_null());
-        cur.move_next();
-        assert!(cur.is_null());
-
+        let mut l1 = LinkedList::new(ObjAdapter1::new());
+        let mut l2 = LinkedList::new(ObjAdapter1::new());
+        let mut l3 = LinkedList::new(ObjAdapter2::new());
+        l1.push_back(a.clone());
+        l1.push_back(b.clone());
+        l1.push_back(c.clone());
+        assert_eq!(Rc::strong_count(&a), 2);
+
+        // Same link
+        let mut cur = l1.front_mut();
         cur.move_next();
-        assert!(cur.peek_prev().is_null());
-        assert!(!cur.is_null());
-        assert_eq!(cur.get().unwrap() as *const _, a.as_ref() as *const _);
+        assert!(cur.transfer_after(&mut l2.front_mut()));
+        assert_eq!(cur.get().unwrap().value, 3);
+        assert!(cur.transfer_before(&mut l2.cursor_mut()));
+        assert!(cur.get().is_none());
+        assert!(!cur.transfer_after(&mut l2.cursor_mut()));
+        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1]);
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(Rc::strong_count(&b), 2);
+
+        // Different link
+        assert!(l2.back_mut().transfer_before(&mut l3.cursor_mut()));
+        assert!(l1.front_mut().transfer_after(&mut l3.cursor_mut()));
+        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [2]);
+        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3]);
+        assert!(!a.link1.is_linked());
+        assert!(a.link2.is_linked());
+        assert_eq!(Rc::strong_count(&a), 2);
+        assert_eq!(Rc::strong_count(&c), 2);
+    }
 
-        {
-            let mut cur2 = cur.as_cursor();
-            assert_eq!(cur2.get().unwrap() as *const _, a.as_ref() as *const _);
-            assert_eq!(cur2.peek_next().get().unwrap().value, 2);
-            cur2.move_next();
-            assert_eq!(cur2.get().unwrap().value, 2);
-            cur2.move_next();
-            assert_eq!(cur2.peek_prev().get().unwrap().value, 2);
-            assert_eq!(cur2.get().unwrap() as *const _, c.as_ref() as *const _);
-            cur2.move_prev();
-            assert_eq!(cur2.get().unwrap() as *const _, b.as_ref() as *const _);
-            cur2.move_next();
-            assert_eq!(cur2.get().unwrap() as *const _, c.as_ref() as *const _);
-            cur2.move_next();
-            assert!(cur2.is_null());
-            assert!(cur2.clone().get().is_none());
-        }
-        assert_eq!(cur.get().unwrap() as *const _, a.as_ref() as *const _);
+    #[test]
+    fn test_transfer_linked() {
+        use std::panic::{self, AssertUnwindSafe};
 
-        cur.move_next();
-        assert_eq!(
-            cur.remove().unwrap().as_ref() as *const _,
-            b.as_ref() as *const _
-        );
-        assert_eq!(cur.get().unwrap() as *const _, c.as_ref() as *const _);
-        cur.insert_after(b.clone());
-        assert_eq!(cur.get().unwrap() as *const _, c.as_ref() as *const _);
-        cur.move_prev();
-        assert_eq!(cur.get().unwrap() as *const _, a.as_ref() as *const _);
-        assert_eq!(
-            cur.remove().unwrap().as_ref() as *const _,
-            a.as_ref() as *const _
-        );
-        assert!(!a.link1.is_linked());
-        assert!(c.link1.is_linked());
-        assert_eq!(cur.get().unwrap() as *const _, c.as_ref() as *const _);
-        assert_eq!(
-            cur.replace_with(a.clone()).unwrap().as_ref() as *const _,
-            c.as_ref() as *const _
-        );
-        assert!(a.link1.is_linked());
-        assert!(!c.link1.is_linked());
-        assert_eq!(cur.get().unwrap() as *const _, a.as_ref() as *const _);
-        cur.move_next();
-        assert_eq!(
-            cur.replace_with(c.clone()).unwrap().as_ref() as *const _,
-            b.as_ref() as *const _
-        );
-        assert!(a.link1.is_linked());
-        assert!(!b.link1.is_linked());
-        assert!(c.link1.is_linked());
-        assert_eq!(cur.get().unwrap() as *const _, c.as_ref() as *const _);
+        let a = make_rc_obj(1);
+        let b = make_rc_obj(2);
+        let mut l1 = LinkedList::new(ObjAdapter1::new());
+        let mut l2 = LinkedList::new(ObjAdapter2::new());
+        let mut l3 = LinkedList::new(ObjAdapter2::new());
+        l1.push_back(a.clone());
+        l1.push_back(b.clone());
+        l2.push_back(a.clone());
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            l1.front_mut().transfer_after(&mut l3.cursor_mut())
+        }));
+        assert!(result.is_err());
+        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(l1.iter().rev().map(|x| x.value).collect::<Vec<_>>(), [2, 1]);
+        assert!(l3.is_empty());
+        assert_eq!(Rc::strong_count(&a), 3);
+        #[cfg(feature = "poison")]
+        assert!(!l1.is_poisoned() && !l3.is_poisoned());
     }
 
     #[test]
-    fn test_cursor_owning() {
-        struct Container {
-            cur: CursorOwning<ObjAdapter1>,
+    fn test_transfer_projected() {
+        use super::LinkOps;
+        use crate::{offset_of, Adapter, DefaultPointerOps, ProjectedPointerOps};
+        use core::ptr::NonNull;
+        use std::panic::{self, AssertUnwindSafe};
+
+        struct Node {
+            link: Link,
+            value: u32,
+        }
+        struct Pair {
+            a: Node,
+            b: Node,
+        }
+        type PairPointerOps = ProjectedPointerOps<DefaultPointerOps<Rc<Pair>>, Node>;
+        struct NodeAdapter {
+            link_ops: LinkOps,
+            pointer_ops: PairPointerOps,
         }
+        unsafe impl Adapter for NodeAdapter {
+            type LinkOps = LinkOps;
+            type PointerOps = PairPointerOps;
 
-        let mut l = LinkedList::new(ObjAdapter1::new());
-        l.push_back(make_rc_obj(1));
-        l.push_back(make_rc_obj(2));
-        l.push_back(make_rc_obj(3));
-        l.push_back(make_rc_obj(4));
-        let mut con = Container {
-            cur: l.cursor_owning(),
+            unsafe fn get_value(&self, link: NonNull<Link>) -> *const Node {
+                container_of!(link.as_ptr(), Node, link)
+            }
+            unsafe fn get_link(&self, value: *const Node) -> NonNull<Link> {
+                NonNull::new_unchecked(core::ptr::addr_of!((*value).link) as *mut Link)
+            }
+            fn link_ops(&self) -> &LinkOps {
+                &self.link_ops
+            }
+            fn link_ops_mut(&mut self) -> &mut LinkOps {
+                &mut self.link_ops
+            }
+            fn pointer_ops(&self) -> &PairPointerOps {
+                &self.pointer_ops
+            }
+        }
+        let new_list = |offset| {
+            LinkedList::new(NodeAdapter {
+                link_ops: LinkOps,
+                pointer_ops: unsafe { ProjectedPointerOps::new(DefaultPointerOps::new(), offset) },
+            })
         };
-        assert!(con.cur.as_cursor().is_null());
-
-        con.cur = con.cur.into_inner().front_owning();
-        assert_eq!(con.cur.as_cursor().get().unwrap().value, 1);
 
-        con.cur.with_cursor_mut(|c| c.move_next());
-        assert_eq!(con.cur.as_cursor().get().unwrap().value, 2);
+        let pair = Rc::new(Pair {
+            a: Node {
+                link: Link::new(),
+                value: 1,
+            },
+            b: Node {
+                link: Link::new(),
+                value: 2,
+            },
+        });
+        let mut l1 = new_list(offset_of!(Pair, a));
+        let mut l2 = new_list(offset_of!(Pair, a));
+        let mut l3 = new_list(offset_of!(Pair, b));
+        l1.push_back(pair.clone());
+
+        // The destination would convert the element back into a pointer to
+        // the wrong `Pair`, so the element is left where it is.
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            l1.front_mut().transfer_after(&mut l3.cursor_mut())
+        }));
+        assert!(result.is_err());
+        assert_eq!(l1.front().get().unwrap().value, 1);
+        assert!(l3.is_empty());
+        assert!(!pair.b.link.is_linked());
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            l3.cursor_mut().splice_after(new_list(offset_of!(Pair, a)))
+        }));
+        assert!(result.is_err());
+
+        // Different instances with the same offset are fine.
+        assert!(l1.front_mut().transfer_after(&mut l2.cursor_mut()));
+        assert!(l1.is_empty());
+        let popped = l2.pop_front().unwrap();
+        assert!(Rc::ptr_eq(&popped, &pair));
+        assert_eq!(Rc::strong_count(&pair), 2);
+    }
 
-        con.cur = con.cur.into_inner().back_owning();
-        assert_eq!(con.cur.as_cursor().get().unwrap().value, 4);
+    #[test]
+    fn test_split_before_links() {
+        let mut l = LinkedList::new(ObjAdapter1::new());
+        for value in 1..5 {
+            l.push_back(make_rc_obj(value));
+        }
+        let l2 = l.back_mut().split_before();
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(
+            l2.iter().rev().map(|x| x.value).collect::<Vec<_>>(),
+            [3, 2, 1]
+        );
+        assert_eq!(l.iter().rev().map(|x| x.value).collect::<Vec<_>>(), [4]);
     }
 
+    #[cfg(feature = "debug_owner")]
     #[test]
-    fn test_push_pop() {
+    fn test_debug_owner() {
+        use std::panic::{self, AssertUnwindSafe};
+
         let a = make_rc_obj(1);
         let b = make_rc_obj(2);
         let c = make_rc_obj(3);
+        let mut l1 = LinkedList::new(ObjAdapter1::new());
+        let mut l2 = LinkedList::new(ObjAdapter1::new());
+        l1.push_back(a.clone());
+        l1.push_back(b.clone());
+        l2.push_back(c.clone());
+
+        let wrong_list = |l: &mut LinkedList<ObjAdapter1>, obj: &Rc<Obj>| {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| unsafe {
+                l.cursor_mut_from_ptr(&**obj).remove()
+            }));
+            let payload = result.err().unwrap();
+            let message = match payload.downcast_ref::<std::string::String>() {
+                Some(message) => message.as_str(),
+                None => payload.downcast_ref::<&str>().unwrap(),
+            };
+            assert!(message.contains("not in this collection"), "{}", message);
+        };
+        wrong_list(&mut l2, &a);
+        wrong_list(&mut l1, &c);
+        unsafe {
+            assert_eq!(l1.cursor_from_ptr(&*b).get().unwrap().value, 2);
+        }
+
+        // Elements which are moved to another list belong to that list.
+        let mut l3 = l1.take();
+        wrong_list(&mut l1, &a);
+        l2.front_mut().splice_after(l3.take());
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [3, 1, 2]);
+        wrong_list(&mut l3, &b);
+        let mut l4 = unsafe { l2.cursor_mut_from_ptr(&*c).split_after() };
+        wrong_list(&mut l2, &a);
+        unsafe {
+            assert!(Rc::ptr_eq(
+                &l4.cursor_mut_from_ptr(&*a).remove().unwrap(),
+                &a
+            ));
+        }
 
+        // Removed elements don't belong to any list.
+        wrong_list(&mut l4, &a);
+        l2.push_back(a.clone());
+        unsafe {
+            assert!(Rc::ptr_eq(
+                &l2.cursor_mut_from_ptr(&*a).remove().unwrap(),
+                &a
+            ));
+        }
+    }
+
+    #[test]
+    fn test_pin() {
+        use core::marker::PhantomPinned;
+        use core::pin::Pin;
+
+        struct Obj {
+            link: Link,
+            value: u32,
+            _pin: PhantomPinned,
+        }
+        intrusive_adapter!(ObjAdapter = Pin<Box<Obj>>: Obj { link: Link });
+
+        let mut l = LinkedList::new(ObjAdapter::new());
+        l.push_back(Box::pin(Obj {
+            link: Link::new(),
+            value: 1,
+            _pin: PhantomPinned,
+        }));
+        let a: Pin<&Obj> = l.front().get_pin().unwrap();
+        assert_eq!(a.value, 1);
+        unsafe {
+            let mut cur = l.front_mut();
+            cur.get_pin_mut().unwrap().get_unchecked_mut().value = 2;
+        }
+        let a: Pin<Box<Obj>> = l.pop_front().unwrap();
+        assert_eq!(a.value, 2);
+    }
+
+    // With `debug_owner`, `cursor_mut_from_ptr` catches this earlier.
+    #[cfg(all(
+        debug_assertions,
+        not(feature = "debug_owner"),
+        not(any(
+            intrusive_collections_sanitizer = "address",
+            intrusive_collections_sanitizer = "memory"
+        ))
+    ))]
+    #[test]
+    #[should_panic(expected = "link operation on an unlinked link")]
+    fn test_poison() {
+        let a = make_rc_obj(1);
         let mut l = LinkedList::new(ObjAdapter1::new());
-        l.push_front(a);
-        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [1]);
-        l.push_front(b);
-        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [2, 1]);
-        l.push_back(c);
-        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [2, 1, 3]);
-        assert_eq!(l.pop_front().unwrap().value, 2);
-        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3]);
-        assert_eq!(l.pop_back().unwrap().value, 3);
-        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [1]);
-        assert_eq!(l.pop_front().unwrap().value, 1);
-        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), []);
-        assert!(l.pop_front().is_none());
-        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), []);
-        assert!(l.pop_back().is_none());
-        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+        l.push_back(a.clone());
+        l.push_back(make_rc_obj(2));
+        l.pop_front();
+        // A stale cursor to an element which was removed from the list.
+        let mut cur = unsafe { l.cursor_mut_from_ptr(&*a) };
+        cur.move_prev();
     }
 
+    #[cfg(intrusive_collections_sanitizer = "address")]
     #[test]
-    fn test_split_splice() {
-        let mut l1 = LinkedList::new(ObjAdapter1::new());
-        let mut l2 = LinkedList::new(ObjAdapter1::new());
-        let mut l3 = LinkedList::new(ObjAdapter1::new());
+    fn test_sanitizer_poison() {
+        use crate::sanitizer::is_poisoned;
 
         let a = make_rc_obj(1);
-        let b = make_rc_obj(2);
-        let c = make_rc_obj(3);
-        let d = make_rc_obj(4);
-        l1.cursor_mut().insert_before(a);
-        l1.cursor_mut().insert_before(b);
-        l1.cursor_mut().insert_before(c);
-        l1.cursor_mut().insert_before(d);
-        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 2, 3, 4]);
-        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), []);
-        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), []);
-        {
-            let mut cur = l1.front_mut();
-            cur.move_next();
-            l2 = cur.split_after();
+        let mut l = LinkedList::new(ObjAdapter1::new());
+        l.push_back(a.clone());
+        assert!(!is_poisoned(&a.link1.prev));
+        l.pop_front();
+        assert!(is_poisoned(&a.link1.prev));
+        assert!(!is_poisoned(&a.link1.next));
+        l.push_back(a.clone());
+        assert!(!is_poisoned(&a.link1.prev));
+        l.clear();
+    }
+
+    #[test]
+    fn test_memory_overhead() {
+        use crate::{CachePadded, MemoryOverhead};
+        use core::mem::size_of;
+
+        struct PaddedObj {
+            link: CachePadded<Link>,
         }
-        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 2]);
-        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [3, 4]);
-        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), []);
-        {
-            let mut cur = l2.back_mut();
-            l3 = cur.split_before();
+        intrusive_adapter!(PaddedAdapter = Box<PaddedObj>: PaddedObj { link: CachePadded<Link> });
+
+        let mut l = LinkedList::new(ObjAdapter1::new());
+        for value in 0..3 {
+            l.push_back(make_rc_obj(value));
         }
-        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 2]);
-        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [4]);
-        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), [3]);
-        {
-            let mut cur = l1.front_mut();
-            cur.splice_after(l2.take());
+        let overhead = l.memory_overhead();
+        assert_eq!(
+            overhead,
+            MemoryOverhead {
+                link_bytes: Some(size_of::<Link>()),
+                collection_bytes: size_of::<LinkedList<ObjAdapter1>>(),
+                len: 3,
+            }
+        );
+        assert_eq!(
+            overhead.total_bytes(),
+            Some(overhead.collection_bytes + 3 * size_of::<Link>())
+        );
+
+        let mut l = LinkedList::new(PaddedAdapter::new());
+        l.push_back(Box::new(PaddedObj {
+            link: CachePadded::new(Link::new()),
+        }));
+        assert_eq!(l.memory_overhead().link_bytes, Some(64));
+        assert_eq!(
+            LinkedList::new(PaddedAdapter::new())
+                .memory_overhead()
+                .total_bytes(),
+            Some(size_of::<LinkedList<PaddedAdapter>>())
+        );
+    }
+
+    #[test]
+    fn test_cache_padded() {
+        use super::AtomicLink;
+        use crate::CachePadded;
+
+        struct Obj {
+            link: CachePadded<Link>,
+            atomic_link: CachePadded<AtomicLink>,
+            value: u32,
         }
-        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 4, 2]);
-        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), []);
-        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), [3]);
-        {
-            let mut cur = l1.front_mut();
-            cur.move_next();
-            cur.splice_before(l3.take());
+        intrusive_adapter!(ObjAdapter = Rc<Obj>: Obj { link: CachePadded<Link> });
+        intrusive_adapter!(AtomicObjAdapter = Rc<Obj>: Obj { atomic_link: CachePadded<AtomicLink> });
+
+        let objs: Vec<Rc<Obj>> = (0..3)
+            .map(|value| {
+                Rc::new(Obj {
+                    link: CachePadded::new(Link::new()),
+                    atomic_link: CachePadded::default(),
+                    value,
+                })
+            })
+            .collect();
+        assert_eq!(core::mem::size_of::<CachePadded<Link>>(), 64);
+        let mut l = LinkedList::new(ObjAdapter::new());
+        let mut l2 = LinkedList::new(AtomicObjAdapter::new());
+        for obj in &objs {
+            l.push_back(obj.clone());
+            l2.push_front(obj.clone());
         }
-        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3, 4, 2]);
-        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), []);
-        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), []);
-        {
-            let mut cur = l2.cursor_mut();
-            cur.splice_after(l1.take());
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [2, 1, 0]);
+        assert_eq!(l.back().get().unwrap().value, 2);
+        assert_eq!(
+            format!("{:?}", objs[1].link),
+            "CachePadded { link: linked }"
+        );
+
+        let mut cur = l.front_mut();
+        cur.move_next();
+        assert_eq!(cur.remove().unwrap().value, 1);
+        assert!(!objs[1].link.is_linked());
+        assert!(objs[1].atomic_link.is_linked());
+        assert_eq!(l.iter().rev().map(|x| x.value).collect::<Vec<_>>(), [2, 0]);
+        l2.clear();
+        assert!(!objs[0].atomic_link.is_linked());
+    }
+
+    #[test]
+    fn test_generation_link() {
+        use crate::GenerationLink;
+
+        struct Obj {
+            link: GenerationLink<Link>,
+            value: u32,
         }
-        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), []);
-        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3, 4, 2]);
-        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), []);
-        {
-            let mut cur = l1.cursor_mut();
-            cur.splice_before(l2.take());
+        intrusive_adapter!(ObjAdapter = Rc<Obj>: Obj { link: GenerationLink<Link> });
+
+        let a = Rc::new(Obj {
+            link: GenerationLink::new(Link::new()),
+            value: 1,
+        });
+        let b = Rc::new(Obj {
+            link: GenerationLink::default(),
+            value: 2,
+        });
+        let mut l = LinkedList::new(ObjAdapter::new());
+        l.push_back(a.clone());
+        l.push_back(b.clone());
+        let stamp_a = a.link.stamp();
+        let stamp_b = b.link.stamp();
+        assert_eq!(stamp_a.generation(), 0);
+        assert!(!a.link.is_current(stamp_b));
+
+        unsafe {
+            let mut cur = l.cursor_mut_from_stamp(&*a, stamp_a).unwrap();
+            assert_eq!(cur.remove().unwrap().value, 1);
+            assert!(l.cursor_from_stamp(&*a, stamp_a).is_none());
+            assert_eq!(
+                l.cursor_from_stamp(&*b, stamp_b)
+                    .unwrap()
+                    .get()
+                    .unwrap()
+                    .value,
+                2
+            );
         }
-        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3, 4, 2]);
-        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), []);
-        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), []);
-        {
-            let mut cur = l1.cursor_mut();
-            l2 = cur.split_after();
+        assert_eq!(a.link.generation(), 1);
+        assert!(!a.link.is_current(stamp_a));
+
+        // Inserting the object again doesn't revive old stamps.
+        l.push_front(a.clone());
+        assert!(!a.link.is_current(stamp_a));
+        let stamp_a = a.link.stamp();
+        l.clear();
+        assert!(!a.link.is_current(stamp_a));
+        assert!(!b.link.is_current(stamp_b));
+        assert_eq!(a.link.generation(), 2);
+        assert_eq!(b.link.generation(), 1);
+        assert_eq!(a.link.clone().generation(), 0);
+        assert_eq!(
+            format!("{:?}", b.link),
+            "GenerationLink { link: unlinked, generation: 1 }"
+        );
+    }
+
+    #[test]
+    fn test_sentinel_link() {
+        use super::SentinelLink;
+        use crate::LinkSentinel;
+        use core::ptr::NonNull;
+
+        static DUMMY: u8 = 0;
+        struct Dummy;
+        unsafe impl LinkSentinel for Dummy {
+            fn unlinked() -> NonNull<u8> {
+                NonNull::from(&DUMMY)
+            }
         }
-        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), []);
-        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3, 4, 2]);
-        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), []);
-        {
-            let mut cur = l2.cursor_mut();
-            l1 = cur.split_before();
+
+        #[derive(Clone, Debug)]
+        struct Obj {
+            link: SentinelLink<Dummy>,
+            link2: SentinelLink,
+            value: u32,
         }
-        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3, 4, 2]);
-        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), []);
-        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), []);
-        {
-            let mut cur = l1.front_mut();
-            l2 = cur.split_before();
+        intrusive_adapter!(ObjAdapter = Rc<Obj>: Obj { link: SentinelLink<Dummy> });
+        intrusive_adapter!(ObjAdapter2 = Rc<Obj>: Obj { link2: SentinelLink });
+        fn next_word(link: &SentinelLink<Dummy>) -> usize {
+            unsafe { *(link as *const _ as *const usize) }
         }
-        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3, 4, 2]);
-        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), []);
-        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), []);
-        {
-            let mut cur = l1.back_mut();
-            l2 = cur.split_after();
+
+        let objs: Vec<Rc<Obj>> = (0..3)
+            .map(|value| {
+                Rc::new(Obj {
+                    link: SentinelLink::new(),
+                    link2: SentinelLink::default(),
+                    value,
+                })
+            })
+            .collect();
+        assert_eq!(next_word(&objs[0].link), &DUMMY as *const u8 as usize);
+        assert!(!objs[0].link.is_linked());
+        let mut l = LinkedList::new(ObjAdapter::new());
+        let mut l2 = LinkedList::new(ObjAdapter2::new());
+        for obj in &objs {
+            l.push_back(obj.clone());
+            l2.push_front(obj.clone());
         }
-        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3, 4, 2]);
-        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), []);
-        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+        assert!(objs[0].link.is_linked());
+        assert_eq!(next_word(&objs[2].link), 0);
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(
+            l.iter().rev().map(|x| x.value).collect::<Vec<_>>(),
+            [2, 1, 0]
+        );
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [2, 1, 0]);
+        assert_eq!(format!("{:?}", objs[1].link), "linked");
+        assert!(!Obj::clone(&objs[1]).link.is_linked());
+
+        let mut cur = l.front_mut();
+        cur.move_next();
+        assert_eq!(cur.remove().unwrap().value, 1);
+        assert_eq!(next_word(&objs[1].link), &DUMMY as *const u8 as usize);
+        assert_eq!(format!("{:?}", objs[1].link), "unlinked");
+        assert!(objs[1].link2.is_linked());
+        l.fast_clear();
+        unsafe {
+            objs[0].link.force_unlink();
+        }
+        assert!(!objs[0].link.is_linked());
+        l2.clear();
+        assert!(!objs[0].link2.is_linked());
     }
 
+    #[cfg(loom)]
     #[test]
-    fn test_iter() {
-        let mut l = LinkedList::new(ObjAdapter1::new());
-        let a = make_rc_obj(1);
-        let b = make_rc_obj(2);
-        let c = make_rc_obj(3);
-        let d = make_rc_obj(4);
-        l.cursor_mut().insert_before(a.clone());
-        l.cursor_mut().insert_before(b.clone());
-        l.cursor_mut().insert_before(c.clone());
-        l.cursor_mut().insert_before(d.clone());
+    fn test_loom_try_acquire_for_insertion() {
+        use super::AtomicLink;
+        use loom::sync::atomic::{AtomicUsize, Ordering};
+        use loom::thread;
+        use std::sync::Arc;
+
+        struct Obj {
+            link: AtomicLink,
+            value: u32,
+        }
+        intrusive_adapter!(ObjAdapter = Arc<Obj>: Obj { link: AtomicLink });
 
-        assert_eq!(l.front().get().unwrap().value, 1);
-        assert_eq!(l.back().get().unwrap().value, 4);
-        unsafe {
-            assert_eq!(l.cursor_from_ptr(b.as_ref()).get().unwrap().value, 2);
-            assert_eq!(l.cursor_mut_from_ptr(c.as_ref()).get().unwrap().value, 3);
+        loom::model(|| {
+            let obj = Arc::new(Obj {
+                link: AtomicLink::new(),
+                value: 1,
+            });
+            let claimed = Arc::new(AtomicUsize::new(0));
+            let threads: Vec<_> = (0..2)
+                .map(|_| {
+                    let obj = obj.clone();
+                    let claimed = claimed.clone();
+                    thread::spawn(move || {
+                        if obj.link.try_acquire_for_insertion() {
+                            claimed.fetch_add(1, Ordering::Relaxed);
+                        }
+                        assert!(obj.link.is_linked());
+                    })
+                })
+                .collect();
+            for thread in threads {
+                thread.join().unwrap();
+            }
+            assert_eq!(claimed.load(Ordering::Relaxed), 1);
+            let mut list = LinkedList::new(ObjAdapter::new());
+            unsafe {
+                obj.link.force_unlink();
+            }
+            list.push_back(obj.clone());
+            assert_eq!(list.iter().map(|x| x.value).sum::<u32>(), 1);
+            list.clear();
+            assert!(!obj.link.is_linked());
+        });
+    }
+
+    #[cfg(loom)]
+    #[test]
+    fn test_loom_is_linked() {
+        use super::AtomicLink;
+        use loom::sync::atomic::{AtomicUsize, Ordering};
+        use loom::thread;
+        use std::sync::Arc;
+
+        struct Obj {
+            link: AtomicLink,
+            data: AtomicUsize,
         }
+        intrusive_adapter!(ObjAdapter = Arc<Obj>: Obj { link: AtomicLink });
 
-        let mut v = Vec::new();
-        for x in &l {
-            v.push(x.value);
+        loom::model(|| {
+            let obj = Arc::new(Obj {
+                link: AtomicLink::new(),
+                data: AtomicUsize::new(0),
+            });
+            let mut list = LinkedList::new(ObjAdapter::new());
+            list.push_back(obj.clone());
+            let watcher = {
+                let obj = obj.clone();
+                thread::spawn(move || {
+                    // Removing the object releases everything written before.
+                    if !obj.link.is_linked() {
+                        assert_eq!(obj.data.load(Ordering::Relaxed), 1);
+                    }
+                })
+            };
+            obj.data.store(1, Ordering::Relaxed);
+            list.clear();
+            watcher.join().unwrap();
+        });
+    }
+
+    #[test]
+    fn test_try_acquire_for_insertion() {
+        use super::AtomicLink;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::{Arc, Mutex};
+        use std::thread;
+
+        struct Obj {
+            link: AtomicLink,
+            value: u32,
         }
-        assert_eq!(v, [1, 2, 3, 4]);
-        assert_eq!(
-            l.iter().clone().map(|x| x.value).collect::<Vec<_>>(),
-            [1, 2, 3, 4]
-        );
+        intrusive_adapter!(ObjAdapter = Arc<Obj>: Obj { link: AtomicLink });
+
+        let obj = Arc::new(Obj {
+            link: AtomicLink::new(),
+            value: 1,
+        });
+        let list = Arc::new(Mutex::new(LinkedList::new(ObjAdapter::new())));
+        let inserted = Arc::new(AtomicUsize::new(0));
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let obj = obj.clone();
+                let list = list.clone();
+                let inserted = inserted.clone();
+                thread::spawn(move || {
+                    for _ in 0..100 {
+                        if obj.link.try_acquire_for_insertion() {
+                            assert!(obj.link.is_linked());
+                            let mut list = list.lock().unwrap();
+                            // Another thread may claim the object as soon as
+                            // the claim is dropped, making the insertion fail.
+                            unsafe {
+                                obj.link.force_unlink();
+                            }
+                            if list.try_push_back(obj.clone()).is_ok() {
+                                inserted.fetch_add(1, Ordering::Relaxed);
+                                assert_eq!(list.pop_front().unwrap().value, 1);
+                            }
+                        }
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        assert!(inserted.load(Ordering::Relaxed) > 0);
+        assert!(list.lock().unwrap().is_empty());
+
+        let mut l = LinkedList::new(ObjAdapter::new());
+        assert!(obj.link.try_acquire_for_insertion());
+        assert!(!obj.link.try_acquire_for_insertion());
+        assert!(l.try_push_back(obj.clone()).is_err());
+        assert!(obj.link.is_linked());
+        assert!(l.is_empty());
+        unsafe {
+            obj.link.force_unlink();
+        }
+        assert!(!obj.link.is_linked());
+        l.push_back(obj.clone());
+        assert!(!obj.link.try_acquire_for_insertion());
+        l.clear();
+    }
+
+    #[test]
+    fn test_tag() {
+        use crate::GenerationLink;
+
+        let a = make_rc_obj(1);
+        let b = make_rc_obj(2);
+        let c = make_rc_obj(3);
+        let mut l = LinkedList::new(ObjAdapter1::new());
+        l.push_back(b.clone());
+        l.front().set_tag(3);
+        assert_eq!(b.link1.tag(), 3);
+        l.push_front(a.clone());
+        l.push_back(c.clone());
+        a.link1.set_tag(1);
+        assert_eq!(l.front().tag(), Some(1));
+        assert_eq!(l.back().tag(), Some(0));
+        assert_eq!(l.back().peek_prev().tag(), Some(3));
+        assert_eq!(l.front().peek_prev().tag(), None);
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 2, 3]);
         assert_eq!(
             l.iter().rev().map(|x| x.value).collect::<Vec<_>>(),
-            [4, 3, 2, 1]
+            [3, 2, 1]
         );
-        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 2, 3, 4]);
 
-        assert_eq!(format!("{:?}", l), "[1, 2, 3, 4]");
+        // Tags are kept when neighbors change, and cleared on removal.
+        l.front_mut().remove();
+        assert_eq!(a.link1.tag(), 0);
+        assert_eq!(b.link1.tag(), 3);
+        l.front_mut().insert_before(a.clone());
+        assert_eq!(a.link1.tag(), 0);
+        assert_eq!(b.link1.tag(), 3);
+        let mut l2 = l.front_mut().split_after();
+        assert_eq!(l2.front().tag(), Some(3));
+        l.cursor_mut().splice_after(l2.take());
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [2, 3, 1]);
+        assert_eq!(b.link1.tag(), 3);
+
+        let cur = l.back_mut();
+        cur.set_tag(2);
+        assert_eq!(cur.tag(), Some(2));
+        l.clear();
+        l2.clear();
+        assert_eq!(b.link1.tag(), 0);
 
-        let mut v = Vec::new();
-        for x in l.take() {
-            v.push(x.value);
+        struct Obj {
+            link: GenerationLink<Link>,
         }
-        assert_eq!(v, [1, 2, 3, 4]);
-        assert!(l.is_empty());
-        assert!(!a.link1.is_linked());
-        assert!(!b.link1.is_linked());
-        assert!(!c.link1.is_linked());
-        assert!(!d.link1.is_linked());
+        intrusive_adapter!(GenAdapter = Rc<Obj>: Obj { link: GenerationLink<Link> });
+        let mut l = LinkedList::new(GenAdapter::new());
+        l.push_back(Rc::new(Obj {
+            link: GenerationLink::default(),
+        }));
+        l.front().set_tag(2);
+        assert_eq!(l.front().get().unwrap().link.tag(), 2);
+    }
 
-        l.cursor_mut().insert_before(a.clone());
-        l.cursor_mut().insert_before(b.clone());
-        l.cursor_mut().insert_before(c.clone());
-        l.cursor_mut().insert_before(d.clone());
-        l.clear();
-        assert!(l.is_empty());
-        assert!(!a.link1.is_linked());
-        assert!(!b.link1.is_linked());
-        assert!(!c.link1.is_linked());
-        assert!(!d.link1.is_linked());
+    #[test]
+    #[should_panic(expected = "tag does not fit in the link")]
+    fn test_tag_overflow() {
+        let a = make_rc_obj(1);
+        let mut l = LinkedList::new(ObjAdapter1::new());
+        l.push_back(a);
+        l.front().set_tag(4);
+    }
 
-        v.clear();
-        l.cursor_mut().insert_before(a.clone());
-        l.cursor_mut().insert_before(b.clone());
-        l.cursor_mut().insert_before(c.clone());
-        l.cursor_mut().insert_before(d.clone());
-        for x in l.into_iter().rev() {
-            v.push(x.value);
+    #[test]
+    fn test_tag_unsupported() {
+        use super::AtomicLink;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        struct Obj {
+            link: AtomicLink,
         }
-        assert_eq!(v, [4, 3, 2, 1]);
-        assert!(!a.link1.is_linked());
-        assert!(!b.link1.is_linked());
-        assert!(!c.link1.is_linked());
-        assert!(!d.link1.is_linked());
+        intrusive_adapter!(ObjAdapter = Box<Obj>: Obj { link: AtomicLink });
+        let mut l = LinkedList::new(ObjAdapter::new());
+        l.push_back(Box::new(Obj {
+            link: AtomicLink::new(),
+        }));
+        assert_eq!(l.front().tag(), Some(0));
+        l.front().set_tag(0);
+        assert!(catch_unwind(AssertUnwindSafe(|| l.front().set_tag(1))).is_err());
+        assert!(catch_unwind(AssertUnwindSafe(|| l.front().peek_next().set_tag(0))).is_err());
     }
 
     #[test]
-    fn test_multi_list() {
-        let mut l1 = LinkedList::new(ObjAdapter1::new());
-        let mut l2 = LinkedList::new(ObjAdapter2::new());
-        let a = make_rc_obj(1);
-        let b = make_rc_obj(2);
-        let c = make_rc_obj(3);
-        let d = make_rc_obj(4);
-        l1.cursor_mut().insert_before(a.clone());
-        l1.cursor_mut().insert_before(b.clone());
-        l1.cursor_mut().insert_before(c.clone());
-        l1.cursor_mut().insert_before(d.clone());
-        l2.cursor_mut().insert_after(a);
-        l2.cursor_mut().insert_after(b);
-        l2.cursor_mut().insert_after(c);
-        l2.cursor_mut().insert_after(d);
-        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 2, 3, 4]);
-        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [4, 3, 2, 1]);
+    fn test_atomic_link_threads() {
+        use super::AtomicLink;
+        use crate::link_ops::check_atomic_link_threads;
+        use std::sync::Arc;
+
+        struct Obj {
+            link: AtomicLink,
+            value: u32,
+        }
+        intrusive_adapter!(ObjAdapter = Arc<Obj>: Obj { link: AtomicLink });
+
+        let objs: Vec<Arc<Obj>> = (0..4)
+            .map(|value| {
+                Arc::new(Obj {
+                    link: AtomicLink::new(),
+                    value,
+                })
+            })
+            .collect();
+        check_atomic_link_threads(
+            &objs,
+            |x| x.link.is_linked(),
+            || {
+                let mut l = LinkedList::new(ObjAdapter::new());
+                for _ in 0..20 {
+                    for obj in &objs {
+                        l.push_back(obj.clone());
+                    }
+                    l.pop_front();
+                    l.pop_back();
+                    l.clear();
+                }
+                for obj in &objs {
+                    l.push_back(obj.clone());
+                }
+                l
+            },
+            |l| assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [0, 1, 2, 3]),
+        );
     }
 
+    #[cfg(feature = "erasable")]
     #[test]
-    fn test_fast_clear_force_unlink() {
-        let mut l = LinkedList::new(UnsafeRefObjAdapter1::new());
-        let a = UnsafeRef::from_box(Box::new(make_obj(1)));
-        let b = UnsafeRef::from_box(Box::new(make_obj(2)));
-        let c = UnsafeRef::from_box(Box::new(make_obj(3)));
-        l.cursor_mut().insert_before(a.clone());
-        l.cursor_mut().insert_before(b.clone());
-        l.cursor_mut().insert_before(c.clone());
+    fn test_thin() {
+        use super::LinkOps;
+        use crate::{container_of_erased, Adapter, DefaultPointerOps};
+        use core::ptr::{self, NonNull};
+        use erasable::{Erasable, ErasedPtr, Thin};
+        use std::alloc::{alloc, handle_alloc_error, Layout};
+        use std::vec;
+
+        // A slice DST which stores its length in a header.
+        struct Header {
+            link: Link,
+            len: usize,
+        }
+        #[repr(C)]
+        struct Node {
+            header: Header,
+            data: [u32],
+        }
+        unsafe impl Erasable for Node {
+            unsafe fn unerase(this: ErasedPtr) -> NonNull<Node> {
+                let len = (*(this.as_ptr() as *const Header)).len;
+                let data = ptr::slice_from_raw_parts_mut(this.as_ptr() as *mut u32, len);
+                NonNull::new_unchecked(data as *mut Node)
+            }
+            const ACK_1_1_0: bool = true;
+        }
+        fn make_node(data: &[u32]) -> Thin<Box<Node>> {
+            let (layout, offset) = Layout::new::<Header>()
+                .extend(Layout::array::<u32>(data.len()).unwrap())
+                .unwrap();
+            unsafe {
+                let raw = alloc(layout.pad_to_align());
+                if raw.is_null() {
+                    handle_alloc_error(layout);
+                }
+                ptr::write(
+                    raw as *mut Header,
+                    Header {
+                        link: Link::new(),
+                        len: data.len(),
+                    },
+                );
+                ptr::copy_nonoverlapping(data.as_ptr(), raw.add(offset) as *mut u32, data.len());
+                let node = ptr::slice_from_raw_parts_mut(raw as *mut u32, data.len()) as *mut Node;
+                Thin::from(Box::from_raw(node))
+            }
+        }
 
-        l.fast_clear();
-        assert!(l.is_empty());
+        struct NodeAdapter {
+            link_ops: LinkOps,
+            pointer_ops: DefaultPointerOps<Thin<Box<Node>>>,
+        }
+        unsafe impl Adapter for NodeAdapter {
+            type LinkOps = LinkOps;
+            type PointerOps = DefaultPointerOps<Thin<Box<Node>>>;
+            unsafe fn get_value(&self, link: NonNull<Link>) -> *const Node {
+                container_of_erased(link.as_ptr(), crate::offset_of!(Header, link))
+            }
+            unsafe fn get_link(&self, value: *const Node) -> NonNull<Link> {
+                NonNull::new_unchecked(ptr::addr_of!((*value).header.link) as *mut Link)
+            }
+            fn link_ops(&self) -> &LinkOps {
+                &self.link_ops
+            }
+            fn link_ops_mut(&mut self) -> &mut LinkOps {
+                &mut self.link_ops
+            }
+            fn pointer_ops(&self) -> &DefaultPointerOps<Thin<Box<Node>>> {
+                &self.pointer_ops
+            }
+        }
 
+        assert_eq!(
+            core::mem::size_of::<Thin<Box<Node>>>(),
+            core::mem::size_of::<usize>()
+        );
+        let mut l = LinkedList::new(NodeAdapter {
+            link_ops: LinkOps,
+            pointer_ops: DefaultPointerOps::new(),
+        });
+        l.push_back(make_node(&[1, 2, 3]));
+        l.push_back(make_node(&[]));
+        l.push_front(make_node(&[4]));
+        assert_eq!(
+            l.iter().map(|x| x.data.to_vec()).collect::<Vec<_>>(),
+            [vec![4], vec![1, 2, 3], vec![]]
+        );
         unsafe {
-            assert!(a.link1.is_linked());
-            assert!(b.link1.is_linked());
-            assert!(c.link1.is_linked());
+            l.front_mut().get_mut().unwrap().data[0] = 5;
+        }
+        let node = l.pop_front().unwrap();
+        assert_eq!(&node.data, &[5]);
+        assert!(!node.header.link.is_linked());
+        let node = l.pop_front().unwrap();
+        assert_eq!(&node.data, &[1, 2, 3]);
+    }
 
-            a.link1.force_unlink();
-            b.link1.force_unlink();
-            c.link1.force_unlink();
+    #[test]
+    fn test_pop_checked() {
+        use super::LinkOps;
+        use crate::{Adapter, PointerOps, TryPointerOps};
+        use core::cell::Cell;
+        use core::marker::PhantomData;
+        use core::ptr::NonNull;
+
+        // A slot whose generation is bumped when its handles are invalidated.
+        struct Slot {
+            link: Link,
+            generation: Cell<u32>,
+            linked_generation: Cell<u32>,
+            value: u32,
+        }
+        struct Handle<'a> {
+            slot: &'a Slot,
+            generation: u32,
+        }
+        #[derive(Debug, PartialEq)]
+        struct Stale(u32);
+
+        struct SlotPointerOps<'a>(PhantomData<&'a Slot>);
+        unsafe impl<'a> PointerOps for SlotPointerOps<'a> {
+            type Value = Slot;
+            type Pointer = Handle<'a>;
+            unsafe fn from_raw(&self, value: *const Slot) -> Handle<'a> {
+                let slot = &*value;
+                Handle {
+                    slot,
+                    generation: slot.linked_generation.get(),
+                }
+            }
+            fn into_raw(&self, handle: Handle<'a>) -> *const Slot {
+                handle.slot.linked_generation.set(handle.generation);
+                handle.slot
+            }
+        }
+        unsafe impl<'a> TryPointerOps for SlotPointerOps<'a> {
+            type Error = Stale;
+            unsafe fn try_from_raw(&self, value: *const Slot) -> Result<Handle<'a>, Stale> {
+                let handle = self.from_raw(value);
+                if handle.generation == handle.slot.generation.get() {
+                    Ok(handle)
+                } else {
+                    Err(Stale(handle.slot.value))
+                }
+            }
+        }
 
-            assert!(l.is_empty());
+        struct SlotAdapter<'a> {
+            link_ops: LinkOps,
+            pointer_ops: SlotPointerOps<'a>,
+        }
+        unsafe impl<'a> Adapter for SlotAdapter<'a> {
+            type LinkOps = LinkOps;
+            type PointerOps = SlotPointerOps<'a>;
+            unsafe fn get_value(&self, link: NonNull<Link>) -> *const Slot {
+                container_of!(link.as_ptr(), Slot, link)
+            }
+            unsafe fn get_link(&self, value: *const Slot) -> NonNull<Link> {
+                NonNull::new_unchecked(core::ptr::addr_of!((*value).link) as *mut Link)
+            }
+            fn link_ops(&self) -> &LinkOps {
+                &self.link_ops
+            }
+            fn link_ops_mut(&mut self) -> &mut LinkOps {
+                &mut self.link_ops
+            }
+            fn pointer_ops(&self) -> &SlotPointerOps<'a> {
+                &self.pointer_ops
+            }
+        }
 
-            assert!(!a.link1.is_linked());
-            assert!(!b.link1.is_linked());
-            assert!(!c.link1.is_linked());
+        let slots: Vec<Slot> = (0..3)
+            .map(|value| Slot {
+                link: Link::new(),
+                generation: Cell::new(0),
+                linked_generation: Cell::new(0),
+                value,
+            })
+            .collect();
+        let mut l = LinkedList::new(SlotAdapter {
+            link_ops: LinkOps,
+            pointer_ops: SlotPointerOps(PhantomData),
+        });
+        for slot in &slots {
+            l.push_back(Handle {
+                slot,
+                generation: slot.generation.get(),
+            });
         }
+        slots[0].generation.set(1);
+        slots[2].generation.set(1);
+
+        assert_eq!(l.pop_back_checked().unwrap().err(), Some(Stale(2)));
+        assert_eq!(l.pop_front_checked().unwrap().err(), Some(Stale(0)));
+        assert!(!slots[0].link.is_linked());
+        let handle = l.pop_front_checked().unwrap().ok().unwrap();
+        assert_eq!(handle.slot.value, 1);
+        assert_eq!(handle.generation, 0);
+        assert!(l.pop_front_checked().is_none());
+        assert!(l.front_mut().remove_checked().is_none());
+    }
 
+    #[test]
+    fn test_get_mut() {
+        intrusive_adapter!(BoxObjAdapter = Box<Obj>: Obj { link1: Link });
+        let mut l = LinkedList::new(BoxObjAdapter::new());
+        l.push_front(Box::new(make_obj(1)));
         unsafe {
-            UnsafeRef::into_box(a);
-            UnsafeRef::into_box(b);
-            UnsafeRef::into_box(c);
+            l.front_mut().get_mut().unwrap().value = 2;
+            let mut cur = l.front_mut();
+            cur.move_next();
+            assert!(cur.get_mut().is_none());
+        }
+        assert_eq!(l.front().get().unwrap().value, 2);
+
+        let a = make_rc_obj(3);
+        let mut l = LinkedList::new(ObjAdapter1::new());
+        l.push_front(a.clone());
+        unsafe {
+            assert!(l.front_mut().try_get_mut().is_none());
+        }
+        drop(a);
+        unsafe {
+            l.front_mut().try_get_mut().unwrap().value = 4;
         }
+        assert_eq!(l.front().get().unwrap().value, 4);
     }
 
     #[test]
@@ -2146,6 +6099,7 @@ mod tests {
         l.cursor_mut().insert_before(&b);
         assert_eq!(*l.front().get().unwrap().value, 5);
         assert_eq!(*l.back().get().unwrap().value, 5);
+        l.clear();
     }
 
     macro_rules! test_clone_pointer {
@@ -2171,6 +6125,10 @@ mod tests {
             assert_eq!(pointer.value, 5);
             assert_eq!(3, $ptr::strong_count(&a));
 
+            let pointer = l.front_mut().clone_pointer().unwrap();
+            assert_eq!(pointer.value, 5);
+            assert_eq!(4, $ptr::strong_count(&a));
+
             l.front_mut().remove();
             assert!(l.front().clone_pointer().is_none());
         };
@@ -2185,4 +6143,384 @@ mod tests {
     fn test_clone_pointer_arc() {
         test_clone_pointer!(Arc, std::sync::Arc);
     }
+
+    #[test]
+    fn test_insert_sorted() {
+        intrusive_adapter!(PriorityObjAdapter = Rc<Obj>: Obj { link1: Link }
+            priority: u32 = |obj| { obj.value / 10 });
+
+        let mut l = LinkedList::new(PriorityObjAdapter::new());
+        for &value in &[21, 10, 30, 22, 0, 11, 1] {
+            let cur = l.insert_sorted(make_rc_obj(value));
+            assert_eq!(cur.get().unwrap().value, value);
+        }
+        assert_eq!(
+            l.iter().map(|x| x.value).collect::<Vec<_>>(),
+            [0, 1, 10, 11, 21, 22, 30]
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn test_debug_name() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::string::String;
+
+        let mut l1 = LinkedList::new(ObjAdapter1::new());
+        let mut l2 = LinkedList::new(ObjAdapter1::new());
+        assert_eq!(l2.debug_name(), None);
+        l2.set_debug_name("run queue");
+        assert_eq!(l2.debug_name(), Some("run queue"));
+        assert_eq!(l2.take().debug_name(), Some("run queue"));
+
+        let a = make_rc_obj(1);
+        l1.push_back(a.clone());
+        let err = catch_unwind(AssertUnwindSafe(|| l2.push_back(a.clone()))).unwrap_err();
+        let msg = err.downcast::<String>().unwrap();
+        assert!(msg.contains("ObjAdapter1"), "{}", msg);
+        assert!(msg.contains("run queue"), "{}", msg);
+    }
+
+    #[test]
+    fn test_assert_valid() {
+        use super::{LinkOps, LinkedListOps};
+        use core::ptr::NonNull;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::string::String;
+
+        let mut l = LinkedList::new(ObjAdapter1::new());
+        assert_eq!(l.assert_valid(), 0);
+        let objs = (0..4).map(make_rc_obj).collect::<Vec<_>>();
+        for obj in &objs {
+            l.push_back(obj.clone());
+        }
+        assert_eq!(l.assert_valid(), 4);
+
+        // Corrupt the prev pointer of the third element.
+        let mut ops = LinkOps;
+        let link = |i: usize| NonNull::from(&objs[i].link1);
+        unsafe { ops.set_prev(link(2), Some(link(0))) };
+        let err = catch_unwind(AssertUnwindSafe(|| l.assert_valid())).unwrap_err();
+        let msg = err.downcast::<String>().unwrap();
+        assert!(msg.contains("at element 2"), "{}", msg);
+
+        // A tail which isn't the last element.
+        unsafe {
+            ops.set_prev(link(2), Some(link(1)));
+            ops.set_next(link(3), Some(link(0)));
+            ops.set_next(link(2), None);
+        }
+        let err = catch_unwind(AssertUnwindSafe(|| l.assert_valid())).unwrap_err();
+        let msg = err.downcast::<String>().unwrap();
+        assert!(msg.contains("last of its 3 elements"), "{}", msg);
+
+        unsafe {
+            ops.set_next(link(2), Some(link(3)));
+            ops.set_next(link(3), None);
+        }
+        assert_eq!(l.assert_valid(), 4);
+    }
+
+    #[cfg(feature = "dump")]
+    #[test]
+    fn test_dump() {
+        use super::{LinkOps, LinkedListOps};
+        use core::ptr::NonNull;
+        use std::string::String;
+
+        let dump = |l: &LinkedList<ObjAdapter1>| {
+            let mut out = String::new();
+            l.dump_values(&mut out).unwrap();
+            out
+        };
+        let mut l = LinkedList::new(ObjAdapter1::new());
+        assert!(dump(&l).ends_with(": head null, tail null\n0 elements, no problems found\n"));
+
+        let objs = (0..4).map(make_rc_obj).collect::<Vec<_>>();
+        for obj in &objs {
+            l.push_back(obj.clone());
+        }
+        let a = |i: usize| format!("{:p}", &*objs[i]);
+        let out = dump(&l);
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 6, "{}", out);
+        assert!(lines[0].starts_with("LinkedList<"), "{}", out);
+        assert!(lines[0].ends_with(&format!("ObjAdapter1>: head {}, tail {}", a(0), a(3))));
+        assert_eq!(
+            lines[1],
+            format!("  #0 {}: prev null, next {}, value 0", a(0), a(1))
+        );
+        assert_eq!(
+            lines[3],
+            format!("  #2 {}: prev {}, next {}, value 2", a(2), a(1), a(3))
+        );
+        assert_eq!(lines[5], "4 elements, no problems found");
+        let mut plain = String::new();
+        l.dump(&mut plain).unwrap();
+        assert!(plain.contains(&format!("  #3 {}: prev {}, next null\n", a(3), a(2))));
+
+        // An element linked into another list.
+        let mut ops = LinkOps;
+        let link = |i: usize| NonNull::from(&objs[i].link1);
+        unsafe { ops.set_prev(link(2), Some(link(0))) };
+        let out = dump(&l);
+        assert!(out.contains(&format!(
+            "value 2\n  ! prev is {}, but the previous element is {}\n",
+            a(0),
+            a(1)
+        )));
+        assert!(out.ends_with("4 elements, 1 problem found\n"), "{}", out);
+        unsafe { ops.set_prev(link(2), Some(link(1))) };
+
+        // A cycle back to the second element, which also leaves the tail
+        // unreachable.
+        unsafe { ops.set_next(link(3), Some(link(1))) };
+        let out = dump(&l);
+        assert!(out.ends_with(
+            "value 3\n  ! next is #1, which makes a cycle of length 3\n4 elements, 1 problem found\n"
+        ));
+        unsafe { ops.set_next(link(0), Some(link(0))) };
+        let out = dump(&l);
+        assert!(out.ends_with(&format!(
+            "  #0 {}: prev null, next {}, value 0\n  ! next is #0, which makes a cycle of length 1\n1 element, 1 problem found\n",
+            a(0),
+            a(0),
+        )));
+
+        // A tail which isn't the last element.
+        unsafe {
+            ops.set_next(link(0), Some(link(1)));
+            ops.set_next(link(3), None);
+            ops.set_next(link(2), None);
+        }
+        let out = dump(&l);
+        assert!(out.ends_with(&format!(
+            "  ! tail is {}, but the last element is {}\n3 elements, 1 problem found\n",
+            a(3),
+            a(2)
+        )));
+
+        unsafe { ops.set_next(link(2), Some(link(3))) };
+        assert!(dump(&l).ends_with("4 elements, no problems found\n"));
+    }
+
+    #[cfg(feature = "debug_leaks")]
+    #[test]
+    fn test_debug_leaks() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::string::String;
+
+        let mut l = LinkedList::new(ObjAdapter1::new());
+        l.push_back(make_rc_obj(1));
+        drop(l);
+
+        // Empty collections can always be dropped.
+        drop(LinkedList::new(UnsafeRefObjAdapter1::new()));
+
+        let a = make_obj(1);
+        let mut l = LinkedList::new(UnsafeRefObjAdapter1::new());
+        l.set_debug_name("timers");
+        l.push_back(unsafe { UnsafeRef::from_raw(&a) });
+        let err = catch_unwind(AssertUnwindSafe(|| drop(l))).unwrap_err();
+        let msg = err.downcast::<String>().unwrap();
+        assert!(msg.contains("UnsafeRefObjAdapter1"), "{}", msg);
+        #[cfg(debug_assertions)]
+        assert!(msg.contains("timers"), "{}", msg);
+        // The list was cleared before panicking.
+        assert!(!a.link1.is_linked());
+
+        // Dropping the list while unwinding doesn't panic again, which would
+        // abort and lose the original panic.
+        #[cfg(feature = "std")]
+        {
+            let mut l = LinkedList::new(UnsafeRefObjAdapter1::new());
+            l.push_back(unsafe { UnsafeRef::from_raw(&a) });
+            let err = catch_unwind(AssertUnwindSafe(|| {
+                let _l = l;
+                panic!("original panic");
+            }))
+            .unwrap_err();
+            assert_eq!(*err.downcast::<&str>().unwrap(), "original panic");
+            assert!(!a.link1.is_linked());
+        }
+    }
+
+    #[test]
+    fn test_cursor_debug() {
+        let a = make_rc_obj(1);
+        let b = make_rc_obj(2);
+        let mut l = LinkedList::new(ObjAdapter1::new());
+        l.push_back(a.clone());
+        l.push_back(b.clone());
+        let (a, b) = (&*a as *const Obj, &*b as *const Obj);
+
+        assert_eq!(
+            format!("{:?}", l.cursor()),
+            format!(
+                "Cursor {{ current: None, prev: {:?}, next: {:?} }}",
+                Some(b),
+                Some(a)
+            )
+        );
+        assert_eq!(
+            format!("{:?}", l.front_mut()),
+            format!(
+                "CursorMut {{ current: {:?}, prev: None, next: {:?} }}",
+                Some(a),
+                Some(b)
+            )
+        );
+        assert_eq!(
+            format!("{:?}", l.back_owning()),
+            format!(
+                "CursorOwning {{ current: {:?}, prev: {:?}, next: None }}",
+                Some(b),
+                Some(a)
+            )
+        );
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn test_stats() {
+        use crate::Stats;
+
+        let mut l = LinkedList::new(ObjAdapter1::new());
+        for value in 0..4 {
+            l.push_back(make_rc_obj(value));
+        }
+        let mut c = l.front_mut();
+        c.move_next();
+        assert_eq!(c.remove().unwrap().value, 1);
+        c.replace_with(make_rc_obj(5)).unwrap();
+        c.move_prev();
+        let mut c = l.cursor();
+        c.move_prev();
+        assert_eq!(
+            l.stats(),
+            Stats {
+                insertions: 5,
+                removals: 2,
+                rotations: 0,
+                max_depth: 0,
+                // `front_mut` moves to the first element.
+                cursor_moves: 4,
+            }
+        );
+
+        let mut l2 = l.front_mut().split_after();
+        assert_eq!(l2.stats(), Stats::default());
+        l2.clear();
+        assert_eq!(l2.stats().removals, 2);
+        l.reset_stats();
+        assert_eq!(l.stats(), Stats::default());
+    }
+
+    #[cfg(feature = "debug_generation")]
+    #[test]
+    fn test_debug_generation() {
+        use super::{Cursor, Iter};
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::string::String;
+
+        let mut l = LinkedList::new(ObjAdapter1::new());
+        l.set_debug_name("stale");
+        for value in 0..3 {
+            l.push_back(make_rc_obj(value));
+        }
+        let (head, tail, current, generation) = {
+            let mut iter = l.iter();
+            assert_eq!(iter.next().unwrap().value, 0);
+            let mut cursor = l.front();
+            cursor.move_next();
+            assert_eq!(iter.generation, cursor.generation);
+            (iter.head, iter.tail, cursor.current, cursor.generation)
+        };
+        l.pop_back();
+
+        // Rebuild the iterator and the cursor as they were before the list
+        // was modified, as if unsafe code had kept them alive.
+        let mut iter = Iter {
+            head,
+            tail,
+            list: &l,
+            generation,
+        };
+        let cursor = Cursor {
+            current,
+            list: &l,
+            generation,
+        };
+        let err = catch_unwind(AssertUnwindSafe(|| iter.next().is_some())).unwrap_err();
+        let msg = err.downcast::<String>().unwrap();
+        assert!(msg.contains("after its collection was modified"), "{}", msg);
+        #[cfg(debug_assertions)]
+        assert!(msg.contains("collection: stale"), "{}", msg);
+        assert!(catch_unwind(AssertUnwindSafe(|| cursor.get().is_some())).is_err());
+
+        // Iterators and cursors created afterwards are valid.
+        assert_eq!(l.iter().count(), 2);
+        assert_eq!(l.back().get().unwrap().value, 1);
+    }
+
+    #[cfg(feature = "debug-checks")]
+    #[test]
+    fn test_debug_checks() {
+        use std::format;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::string::String;
+
+        let mut l1 = LinkedList::new(ObjAdapter1::new());
+        let mut l2 = LinkedList::new(ObjAdapter1::new());
+        l1.set_debug_name("ready");
+        let a = make_rc_obj(1);
+        l1.push_back(a.clone());
+
+        for l in [&mut l1, &mut l2] {
+            let err = catch_unwind(AssertUnwindSafe(|| l.push_back(a.clone()))).unwrap_err();
+            let msg = err.downcast::<String>().unwrap();
+            assert!(msg.contains("ObjAdapter1"), "{}", msg);
+            assert!(msg.contains(&format!("object: {:p}", &*a)), "{}", msg);
+        }
+        let err = catch_unwind(AssertUnwindSafe(|| l1.push_back(a.clone()))).unwrap_err();
+        let msg = err.downcast::<String>().unwrap();
+        #[cfg(debug_assertions)]
+        assert!(msg.contains("collection: ready"), "{}", msg);
+        #[cfg(feature = "debug_owner")]
+        assert!(msg.contains("linked into: this collection"), "{}", msg);
+        #[cfg(feature = "debug_owner")]
+        {
+            let err = catch_unwind(AssertUnwindSafe(|| l2.push_back(a.clone()))).unwrap_err();
+            let msg = err.downcast::<String>().unwrap();
+            assert!(msg.contains("linked into: collection #"), "{}", msg);
+        }
+    }
+
+    #[cfg(feature = "debug-checks")]
+    #[test]
+    fn test_cursor_from_ptr_checks() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::string::String;
+
+        let mut l1 = LinkedList::new(ObjAdapter1::new());
+        let mut l2 = LinkedList::new(ObjAdapter1::new());
+        let a = make_rc_obj(1);
+        let b = make_rc_obj(2);
+        l1.push_back(a.clone());
+        l2.push_back(b.clone());
+        assert_eq!(unsafe { l1.cursor_from_ptr(&*a) }.get().unwrap().value, 1);
+
+        // An object in another list, and one which was removed.
+        l1.push_back(make_rc_obj(3));
+        let c = l1.pop_back().unwrap();
+        for obj in [&b, &c] {
+            let err = catch_unwind(AssertUnwindSafe(|| unsafe {
+                l1.cursor_mut_from_ptr(&**obj);
+            }))
+            .unwrap_err();
+            let msg = err.downcast::<String>().unwrap();
+            assert!(msg.contains("not in this collection"), "{}", msg);
+            assert!(msg.contains(&format!("object: {:p}", &**obj)), "{}", msg);
+        }
+    }
 }
@@ -0,0 +1,274 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::adapter::Adapter;
+use crate::linked_list::{CursorMut, LinkedList, LinkedListOps};
+use crate::pointer_ops::PointerOps;
+
+/// A pool of preallocated objects, each of which is either free or in use.
+///
+/// The pool keeps every object on exactly one of two `LinkedList`s, and moves
+/// it from one to the other in constant time when it is acquired or
+/// released. The objects need a single `LinkedListLink`, since they are never
+/// on both lists at once.
+///
+/// Releasing an object that isn't in use by this pool, such as one that was
+/// already released, is undefined behavior. In debug builds, `release` panics
+/// instead after scanning the in-use objects, or in constant time with the
+/// `debug_owner` feature.
+///
+/// `acquire` returns a clone of the pointer to the object. With pointers
+/// which can't be cloned, such as `Box`, `acquire_cursor` returns a cursor
+/// pointing to the object instead.
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::{intrusive_adapter, LinkedListLink, ObjectPool};
+/// use std::rc::Rc;
+///
+/// struct Conn {
+///     link: LinkedListLink,
+///     id: u32,
+/// }
+/// intrusive_adapter!(ConnAdapter = Rc<Conn>: Conn { link: LinkedListLink });
+///
+/// let mut pool = ObjectPool::new(ConnAdapter::new());
+/// for id in 0..2 {
+///     pool.add(Rc::new(Conn { link: LinkedListLink::new(), id }));
+/// }
+///
+/// let a = pool.acquire().unwrap();
+/// let b = pool.acquire().unwrap();
+/// assert!(pool.is_exhausted());
+/// assert_eq!((a.id, b.id), (0, 1));
+///
+/// unsafe { pool.release(&a) };
+/// assert_eq!(pool.acquire().unwrap().id, 0);
+/// ```
+pub struct ObjectPool<A: Adapter>
+where
+    A::LinkOps: LinkedListOps,
+{
+    free: LinkedList<A>,
+    in_use: LinkedList<A>,
+}
+
+impl<A: Adapter + Clone> ObjectPool<A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    /// Creates an empty `ObjectPool`.
+    #[inline]
+    pub fn new(adapter: A) -> ObjectPool<A> {
+        ObjectPool {
+            free: LinkedList::new(adapter.clone()),
+            in_use: LinkedList::new(adapter),
+        }
+    }
+}
+
+impl<A: Adapter> ObjectPool<A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    /// Adds a new object to the pool, as a free object.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the object is already linked to an intrusive collection.
+    #[inline]
+    pub fn add(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        self.free.push_back(val);
+    }
+
+    /// Moves the free object which was released first to the in-use objects,
+    /// and returns a clone of the pointer to it.
+    ///
+    /// This returns `None` if the pool is exhausted.
+    #[inline]
+    pub fn acquire(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer>
+    where
+        <A::PointerOps as PointerOps>::Pointer: Clone,
+    {
+        self.acquire_cursor()?.clone_pointer()
+    }
+
+    /// Moves the free object which was released first to the in-use objects,
+    /// and returns a cursor pointing to it.
+    ///
+    /// This returns `None` if the pool is exhausted.
+    #[inline]
+    pub fn acquire_cursor(&mut self) -> Option<CursorMut<'_, A>> {
+        let val = self.free.pop_front()?;
+        self.in_use.push_back(val);
+        Some(self.in_use.back_mut())
+    }
+
+    /// Moves an in-use object back to the free objects.
+    ///
+    /// The pointer returned by `acquire` can be dropped afterwards: the pool
+    /// keeps its own pointer to the object.
+    ///
+    /// # Safety
+    ///
+    /// `val` must be an object which is in use by this pool.
+    #[inline]
+    #[track_caller]
+    pub unsafe fn release(&mut self, val: &<A::PointerOps as PointerOps>::Value) {
+        debug_assert!(
+            !self.in_use.is_empty(),
+            "attempted to release an object while none is in use"
+        );
+        // With `debug_owner`, the cursor checks that the object is in the list
+        // in constant time.
+        #[cfg(all(debug_assertions, not(feature = "debug_owner")))]
+        assert!(
+            self.in_use.iter().any(|x| core::ptr::eq(x, val)),
+            "attempted to release an object which is not in use by this pool"
+        );
+        let val = self.in_use.cursor_mut_from_ptr(val).remove().unwrap();
+        self.free.push_back(val);
+    }
+
+    /// Returns `true` if there are no free objects left in the pool.
+    #[inline]
+    pub fn is_exhausted(&self) -> bool {
+        self.free.is_empty()
+    }
+
+    /// Returns the list of free objects, in the order in which `acquire`
+    /// hands them out.
+    #[inline]
+    pub fn free(&self) -> &LinkedList<A> {
+        &self.free
+    }
+
+    /// Returns the list of in-use objects, in the order in which they were
+    /// acquired.
+    #[inline]
+    pub fn in_use(&self) -> &LinkedList<A> {
+        &self.in_use
+    }
+
+    /// Removes all the objects from the pool, whether they are free or in
+    /// use.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.free.clear();
+        self.in_use.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ObjectPool;
+    use crate::LinkedListLink;
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    struct Obj {
+        link: LinkedListLink,
+        value: u32,
+    }
+    intrusive_adapter!(ObjAdapter = Rc<Obj>: Obj { link: LinkedListLink });
+
+    fn values(pool: &ObjectPool<ObjAdapter>) -> (Vec<u32>, Vec<u32>) {
+        (
+            pool.free().iter().map(|x| x.value).collect(),
+            pool.in_use().iter().map(|x| x.value).collect(),
+        )
+    }
+
+    fn make_obj(value: u32) -> Rc<Obj> {
+        Rc::new(Obj {
+            link: LinkedListLink::new(),
+            value,
+        })
+    }
+
+    #[test]
+    fn test_acquire_release() {
+        let objs = (0..3).map(make_obj).collect::<Vec<_>>();
+        let mut pool = ObjectPool::new(ObjAdapter::new());
+        assert!(pool.is_exhausted());
+        assert!(pool.acquire().is_none());
+        for obj in &objs {
+            pool.add(obj.clone());
+        }
+        assert_eq!(values(&pool), (std::vec![0, 1, 2], std::vec![]));
+
+        let a = pool.acquire().unwrap();
+        let b = pool.acquire().unwrap();
+        assert_eq!((a.value, b.value), (0, 1));
+        assert_eq!(values(&pool), (std::vec![2], std::vec![0, 1]));
+
+        unsafe { pool.release(&a) };
+        assert_eq!(values(&pool), (std::vec![2, 0], std::vec![1]));
+        assert_eq!(pool.acquire().unwrap().value, 2);
+        assert_eq!(pool.acquire().unwrap().value, 0);
+        assert!(pool.is_exhausted());
+        assert!(pool.acquire().is_none());
+
+        unsafe { pool.release(&b) };
+        assert_eq!(values(&pool), (std::vec![1], std::vec![2, 0]));
+        pool.clear();
+        assert!(objs.iter().all(|x| !x.link.is_linked()));
+    }
+
+    #[test]
+    fn test_acquire_cursor() {
+        use alloc::boxed::Box;
+
+        intrusive_adapter!(BoxObjAdapter = Box<Obj>: Obj { link: LinkedListLink });
+
+        let mut pool = ObjectPool::new(BoxObjAdapter::new());
+        for value in 0..2 {
+            pool.add(Box::new(Obj {
+                link: LinkedListLink::new(),
+                value,
+            }));
+        }
+        let mut cursor = pool.acquire_cursor().unwrap();
+        unsafe { cursor.get_mut().unwrap().value = 10 };
+        let obj: *const Obj = cursor.get().unwrap();
+        assert_eq!(pool.in_use().front().get().unwrap().value, 10);
+        unsafe { pool.release(&*obj) };
+        assert_eq!(pool.free().back().get().unwrap().value, 10);
+        assert_eq!(pool.acquire_cursor().unwrap().get().unwrap().value, 1);
+    }
+
+    #[cfg(all(debug_assertions, not(feature = "debug_owner")))]
+    #[test]
+    #[should_panic(expected = "not in use by this pool")]
+    fn test_double_release() {
+        let mut pool = ObjectPool::new(ObjAdapter::new());
+        pool.add(make_obj(0));
+        pool.add(make_obj(1));
+        let obj = pool.acquire().unwrap();
+        pool.acquire().unwrap();
+        unsafe {
+            pool.release(&obj);
+            pool.release(&obj);
+        }
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[test]
+    #[should_panic(expected = "not in this collection")]
+    fn test_double_release_owner() {
+        let mut pool = ObjectPool::new(ObjAdapter::new());
+        pool.add(make_obj(0));
+        pool.add(make_obj(1));
+        let obj = pool.acquire().unwrap();
+        pool.acquire().unwrap();
+        unsafe {
+            pool.release(&obj);
+            pool.release(&obj);
+        }
+    }
+}
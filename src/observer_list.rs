@@ -0,0 +1,546 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Intrusive list of observers which unsubscribe themselves when they are
+//! dropped.
+
+use core::cell::Cell;
+use core::fmt;
+use core::marker::PhantomPinned;
+use core::mem;
+use core::ops::Deref;
+use core::pin::Pin;
+use core::ptr::NonNull;
+
+use crate::link_ops::{self, DefaultLinkOps};
+use crate::pointer_ops::{clone_pointer_from_raw, DefaultPointerOps, PointerOps};
+use crate::Adapter;
+
+// =============================================================================
+// Link
+// =============================================================================
+
+/// Intrusive link that allows an object to subscribe to an `ObserverList`.
+///
+/// As with the link of an `AutoUnlinkList`, the links of an `ObserverList`
+/// form a ring through a sentinel link stored in the list, and dropping a
+/// linked `Link` removes the object from its list. The link is `!Unpin`, so
+/// that an object containing it can only subscribe once it is pinned, and
+/// then stays in place until it is dropped.
+///
+/// The link isn't `Send`, so an object can't be dropped on another thread
+/// while its list is used.
+pub struct Link {
+    next: Cell<Option<NonNull<Link>>>,
+    prev: Cell<Option<NonNull<Link>>>,
+    _pinned: PhantomPinned,
+}
+
+impl Link {
+    /// Creates a new `Link`.
+    #[inline]
+    pub const fn new() -> Link {
+        Link {
+            next: Cell::new(None),
+            prev: Cell::new(None),
+            _pinned: PhantomPinned,
+        }
+    }
+
+    /// Checks whether the `Link` is linked into an `ObserverList`.
+    #[inline]
+    pub fn is_linked(&self) -> bool {
+        self.next.get().is_some()
+    }
+
+    /// Unsubscribes the object from the `ObserverList` it is in, if any.
+    ///
+    /// This is what dropping the link does, and can be called at any time,
+    /// including from the callback of `ObserverList::notify`.
+    #[inline]
+    pub fn unlink(&self) {
+        if let (Some(next), Some(prev)) = (self.next.get(), self.prev.get()) {
+            unsafe {
+                prev.as_ref().next.set(Some(next));
+                next.as_ref().prev.set(Some(prev));
+            }
+            self.next.set(None);
+            self.prev.set(None);
+        }
+    }
+
+    // Links `self` between `prev` and `next`, which are neighbors in a ring.
+    #[inline]
+    unsafe fn link_between(&self, prev: NonNull<Link>, next: NonNull<Link>) {
+        self.prev.set(Some(prev));
+        self.next.set(Some(next));
+        prev.as_ref().next.set(Some(NonNull::from(self)));
+        next.as_ref().prev.set(Some(NonNull::from(self)));
+    }
+}
+
+impl DefaultLinkOps for Link {
+    type Ops = LinkOps;
+
+    const NEW: Self::Ops = LinkOps;
+}
+
+impl Drop for Link {
+    #[inline]
+    fn drop(&mut self) {
+        self.unlink();
+    }
+}
+
+// Provide an implementation of Clone which simply initializes the new link as
+// unlinked. This allows structs containing a link to derive Clone.
+impl Clone for Link {
+    #[inline]
+    fn clone(&self) -> Link {
+        Link::new()
+    }
+}
+
+// Same as above
+impl Default for Link {
+    #[inline]
+    fn default() -> Link {
+        Link::new()
+    }
+}
+
+// Provide an implementation of Debug so that structs containing a link can
+// still derive Debug.
+impl fmt::Debug for Link {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // There isn't anything sensible to print here except whether the link
+        // is currently in a list.
+        if self.is_linked() {
+            write!(f, "linked")
+        } else {
+            write!(f, "unlinked")
+        }
+    }
+}
+
+// =============================================================================
+// LinkOps
+// =============================================================================
+
+/// Default `LinkOps` implementation for `ObserverList`.
+#[derive(Clone, Copy, Default)]
+pub struct LinkOps;
+
+unsafe impl link_ops::LinkOps for LinkOps {
+    type LinkPtr = NonNull<Link>;
+
+    const LINK_SIZE: Option<usize> = Some(mem::size_of::<Link>());
+
+    #[inline]
+    unsafe fn acquire_link(&mut self, ptr: Self::LinkPtr) -> bool {
+        if ptr.as_ref().is_linked() {
+            false
+        } else {
+            // Marks the link as linked until the list sets its neighbors.
+            ptr.as_ref().next.set(Some(ptr));
+            true
+        }
+    }
+
+    #[inline]
+    unsafe fn release_link(&mut self, ptr: Self::LinkPtr) {
+        ptr.as_ref().next.set(None);
+        ptr.as_ref().prev.set(None);
+    }
+}
+
+unsafe impl link_ops::LinkStateOps for LinkOps {
+    #[inline]
+    unsafe fn is_linked(&self, ptr: Self::LinkPtr) -> bool {
+        ptr.as_ref().is_linked()
+    }
+}
+
+// =============================================================================
+// ObserverList
+// =============================================================================
+
+/// An intrusive list of observers, which are unsubscribed when they are
+/// dropped.
+///
+/// The list doesn't hold any pointer to its observers: `subscribe` takes a
+/// pinned pointer to an observer which must stay in place until it is
+/// dropped, and dropping it unlinks it from the list. `notify` calls a
+/// closure on each observer, and keeps a clone of the pointer to it while
+/// the closure runs. This makes the list safe to use with `Rc` pointers,
+/// which is the usual way of sharing observers, or with references.
+///
+/// The closure of `notify` can drop or unsubscribe any observer, and can
+/// subscribe new ones, which are notified too since they are added at the
+/// end of the list. It can't call `notify` on the same list.
+///
+/// The list stores the sentinel link which its first and last observers
+/// point to, so it must be pinned, for example with `Box::pin`. Its methods
+/// take a shared reference, so that it can be used from the callbacks.
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::observer_list::{Link, ObserverList};
+/// use intrusive_collections::intrusive_adapter;
+/// use std::cell::Cell;
+/// use std::rc::Rc;
+///
+/// struct Observer {
+///     link: Link,
+///     seen: Cell<u32>,
+/// }
+/// intrusive_adapter!(ObserverAdapter = Rc<Observer>: Observer { link: Link });
+///
+/// let new_observer = || Rc::pin(Observer { link: Link::new(), seen: Cell::new(0) });
+/// let observers = Box::pin(ObserverList::new(ObserverAdapter::new()));
+/// let a = new_observer();
+/// let b = new_observer();
+/// // `Observer` doesn't implement `Unpin`, so it can't move once pinned.
+/// unsafe {
+///     observers.as_ref().subscribe(&a);
+///     observers.as_ref().subscribe(&b);
+/// }
+///
+/// observers.as_ref().notify(|o| o.seen.set(o.seen.get() + 1));
+/// drop(b);
+/// observers.as_ref().notify(|o| o.seen.set(o.seen.get() + 1));
+/// assert_eq!(a.seen.get(), 2);
+/// assert_eq!(observers.len(), 1);
+/// ```
+pub struct ObserverList<A: Adapter<LinkOps = LinkOps>> {
+    // Points to itself, or is unlinked, while the list is empty.
+    sentinel: Link,
+    // Linked after the observer being notified, so that `notify` can move on
+    // to the next observer whichever ones the callback unsubscribes.
+    cursor: Link,
+    notifying: Cell<bool>,
+    adapter: A,
+}
+
+impl<A: Adapter<LinkOps = LinkOps>> ObserverList<A> {
+    /// Creates an empty `ObserverList`.
+    #[inline]
+    pub const fn new(adapter: A) -> ObserverList<A> {
+        ObserverList {
+            sentinel: Link::new(),
+            cursor: Link::new(),
+            notifying: Cell::new(false),
+            adapter,
+        }
+    }
+
+    #[inline]
+    fn sentinel_ptr(&self) -> NonNull<Link> {
+        NonNull::from(&self.sentinel)
+    }
+
+    // Returns the first observer after `link`, skipping the cursor.
+    #[inline]
+    fn next_observer(&self, link: NonNull<Link>) -> Option<NonNull<Link>> {
+        let mut next = unsafe { link.as_ref().next.get()? };
+        if next == NonNull::from(&self.cursor) {
+            next = unsafe { next.as_ref().next.get()? };
+        }
+        Some(next).filter(|&next| next != self.sentinel_ptr())
+    }
+
+    /// Returns `true` if the `ObserverList` has no observers.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.next_observer(self.sentinel_ptr()).is_none()
+    }
+
+    /// Returns the number of observers in the `ObserverList`.
+    ///
+    /// This walks the whole list, since observers can unsubscribe themselves
+    /// without the list knowing about it.
+    #[inline]
+    pub fn len(&self) -> usize {
+        let mut len = 0;
+        let mut link = self.sentinel_ptr();
+        while let Some(next) = self.next_observer(link) {
+            len += 1;
+            link = next;
+        }
+        len
+    }
+
+    /// Returns a reference to the adapter of the `ObserverList`.
+    #[inline]
+    pub fn adapter(&self) -> &A {
+        &self.adapter
+    }
+}
+
+impl<A, P> ObserverList<A>
+where
+    A: Adapter<LinkOps = LinkOps, PointerOps = DefaultPointerOps<P>>,
+    DefaultPointerOps<P>: PointerOps<Pointer = P>,
+    P: Deref<Target = <DefaultPointerOps<P> as PointerOps>::Value> + Clone,
+{
+    /// Subscribes an observer to the `ObserverList`, after the observers
+    /// which are already in it.
+    ///
+    /// The observer stays in the list until it is dropped or unlinked, or
+    /// the list is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the observer is already linked to an `ObserverList`.
+    ///
+    /// # Safety
+    ///
+    /// The observer must not move until it is dropped or unlinked. `Pin`
+    /// only guarantees this if the type of the observer doesn't implement
+    /// `Unpin`: this is the case for a struct containing a `Link`, unless
+    /// `Unpin` is implemented for it explicitly, since `Pin::new` could then
+    /// pin an observer which is moved afterwards.
+    #[inline]
+    pub unsafe fn subscribe(self: Pin<&Self>, observer: &Pin<P>) {
+        let this = self.get_ref();
+        let value: *const _ = &**observer;
+        let link = this.adapter.get_link(value);
+        if link.as_ref().is_linked() {
+            crate::adapter::already_linked(&this.adapter, None, value, None);
+        }
+        let sentinel = this.sentinel_ptr();
+        let prev = this.sentinel.prev.get().unwrap_or(sentinel);
+        link.as_ref().link_between(prev, sentinel);
+    }
+
+    /// Calls `f` on each observer of the `ObserverList`, in the order in
+    /// which they subscribed.
+    ///
+    /// `f` can unsubscribe or drop any observer, including the one it is
+    /// called on, and the observers it removes before they are reached
+    /// aren't notified.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it is called from `f`, on the same list.
+    pub fn notify<F: FnMut(&<DefaultPointerOps<P> as PointerOps>::Value)>(
+        self: Pin<&Self>,
+        mut f: F,
+    ) {
+        let this = self.get_ref();
+        assert!(
+            !this.notifying.replace(true),
+            "attempted to notify an ObserverList from one of its callbacks"
+        );
+        let _guard = NotifyGuard(this);
+
+        let mut link = this.sentinel_ptr();
+        while let Some(next) = this.next_observer(link) {
+            // The clone keeps the observer alive until `f` returns, and the
+            // cursor tracks the observer after it.
+            let observer = unsafe {
+                clone_pointer_from_raw(this.adapter.pointer_ops(), this.adapter.get_value(next))
+            };
+            this.cursor.unlink();
+            unsafe {
+                let after = next.as_ref().next.get().unwrap();
+                this.cursor.link_between(next, after);
+            }
+            f(&observer);
+            drop(observer);
+            link = NonNull::from(&this.cursor);
+        }
+    }
+}
+
+// Takes the cursor out of the list at the end of `notify`, even if a callback
+// panics.
+struct NotifyGuard<'a, A: Adapter<LinkOps = LinkOps>>(&'a ObserverList<A>);
+
+impl<A: Adapter<LinkOps = LinkOps>> Drop for NotifyGuard<'_, A> {
+    #[inline]
+    fn drop(&mut self) {
+        self.0.cursor.unlink();
+        self.0.notifying.set(false);
+    }
+}
+
+// Unsubscribe all the observers if the list is dropped
+impl<A: Adapter<LinkOps = LinkOps>> Drop for ObserverList<A> {
+    #[inline]
+    fn drop(&mut self) {
+        while let Some(first) = self.next_observer(self.sentinel_ptr()) {
+            unsafe { first.as_ref().unlink() };
+        }
+        self.sentinel.unlink();
+    }
+}
+
+impl<A: Adapter<LinkOps = LinkOps> + Default> Default for ObserverList<A> {
+    fn default() -> ObserverList<A> {
+        ObserverList::new(A::default())
+    }
+}
+
+impl<A: Adapter<LinkOps = LinkOps>> fmt::Debug for ObserverList<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObserverList")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{Link, ObserverList};
+    use core::cell::{Cell, RefCell};
+    use core::pin::Pin;
+    use std::boxed::Box;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    struct Obj {
+        link: Link,
+        value: u32,
+        seen: Cell<u32>,
+    }
+    intrusive_adapter!(ObjAdapter = Rc<Obj>: Obj { link: Link });
+
+    fn make_obj(value: u32) -> Pin<Rc<Obj>> {
+        Rc::pin(Obj {
+            link: Link::new(),
+            value,
+            seen: Cell::new(0),
+        })
+    }
+
+    fn notified(l: Pin<&ObserverList<ObjAdapter>>) -> Vec<u32> {
+        let mut values = Vec::new();
+        l.notify(|x| {
+            x.seen.set(x.seen.get() + 1);
+            values.push(x.value);
+        });
+        values
+    }
+
+    #[test]
+    fn test_subscribe_drop() {
+        let l = Box::pin(ObserverList::new(ObjAdapter::new()));
+        assert!(l.is_empty());
+        assert!(notified(l.as_ref()).is_empty());
+        let objs: Vec<_> = (0..4).map(make_obj).collect();
+        for obj in &objs {
+            unsafe { l.as_ref().subscribe(obj) };
+        }
+        assert_eq!(l.len(), 4);
+        assert_eq!(notified(l.as_ref()), [0, 1, 2, 3]);
+
+        let mut objs = objs.into_iter();
+        drop(objs.next());
+        let rest: Vec<_> = objs.collect();
+        rest[1].link.unlink();
+        assert_eq!(notified(l.as_ref()), [1, 3]);
+        assert_eq!(rest[0].seen.get(), 2);
+        assert_eq!(rest[1].seen.get(), 1);
+
+        // Observers can subscribe again once unlinked.
+        unsafe { l.as_ref().subscribe(&rest[1]) };
+        assert_eq!(notified(l.as_ref()), [1, 3, 2]);
+        drop(rest);
+        assert!(l.is_empty());
+    }
+
+    #[test]
+    fn test_unsubscribe_during_notify() {
+        let l = Box::pin(ObserverList::new(ObjAdapter::new()));
+        let objs = RefCell::new((0..6).map(|x| Some(make_obj(x))).collect::<Vec<_>>());
+        for obj in objs.borrow().iter() {
+            unsafe { l.as_ref().subscribe(obj.as_ref().unwrap()) };
+        }
+        let late = make_obj(6);
+        let mut values = Vec::new();
+        l.as_ref().notify(|x| {
+            values.push(x.value);
+            match x.value {
+                // Drops itself, and the observer after it.
+                0 => {
+                    objs.borrow_mut()[0] = None;
+                    objs.borrow_mut()[1] = None;
+                }
+                // Unlinks itself and an observer before it.
+                2 => {
+                    x.link.unlink();
+                    objs.borrow()[0..2]
+                        .iter()
+                        .flatten()
+                        .for_each(|x| x.link.unlink());
+                }
+                3 => {
+                    objs.borrow()[4].as_ref().unwrap().link.unlink();
+                    unsafe { l.as_ref().subscribe(&late) };
+                }
+                _ => {}
+            }
+        });
+        assert_eq!(values, [0, 2, 3, 5, 6]);
+        assert!(!l.cursor.is_linked());
+        assert_eq!(notified(l.as_ref()), [3, 5, 6]);
+    }
+
+    #[test]
+    fn test_drop_list() {
+        let a = make_obj(1);
+        let b = make_obj(2);
+        {
+            let l = Box::pin(ObserverList::new(ObjAdapter::new()));
+            unsafe { l.as_ref().subscribe(&a) };
+            unsafe { l.as_ref().subscribe(&b) };
+        }
+        assert!(!a.link.is_linked());
+        assert!(!b.link.is_linked());
+    }
+
+    #[test]
+    fn test_panicking_callback() {
+        let l = Box::pin(ObserverList::new(ObjAdapter::new()));
+        let objs: Vec<_> = (0..3).map(make_obj).collect();
+        for obj in &objs {
+            unsafe { l.as_ref().subscribe(obj) };
+        }
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            l.as_ref().notify(|x| assert_ne!(x.value, 1));
+        }));
+        assert!(result.is_err());
+        assert_eq!(l.len(), 3);
+        assert_eq!(notified(l.as_ref()), [0, 1, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "attempted to notify an ObserverList from one of its callbacks")]
+    fn test_nested_notify() {
+        let l = Box::pin(ObserverList::new(ObjAdapter::new()));
+        let a = make_obj(1);
+        unsafe { l.as_ref().subscribe(&a) };
+        l.as_ref().notify(|_| l.as_ref().notify(|_| {}));
+    }
+
+    #[test]
+    #[should_panic(expected = "attempted to insert an object that is already linked")]
+    fn test_already_linked() {
+        let a = make_obj(1);
+        let l = Box::pin(ObserverList::new(ObjAdapter::new()));
+        unsafe { l.as_ref().subscribe(&a) };
+        unsafe { l.as_ref().subscribe(&a) };
+    }
+}
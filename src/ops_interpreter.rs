@@ -0,0 +1,549 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Interpreter running sequences of operations on collections.
+//!
+//! The operations are decoded from a stream of bytes, which makes this
+//! module suitable as the body of a fuzz target: the harness in the `fuzz/`
+//! directory of the repository just passes its input to the `run_*`
+//! functions. The operations are performed through the wrappers of the
+//! `differential` module, so every operation is also performed on a `std`
+//! container, and after each step the contents of the collection and the
+//! position of the cursor are checked against it, along with the structure
+//! of the links of a `LinkedList` or an `RBTree`.
+//!
+//! The same operations are generated randomly by the `proptest` module.
+//!
+//! # Examples
+//!
+//! ```
+//! use intrusive_collections::ops_interpreter::{decode_list_ops, ListOp};
+//!
+//! // Push 3 to the back, move the cursor to it and remove it.
+//! let ops = decode_list_ops(&[1, 3, 4, 8]);
+//! assert_eq!(ops, [ListOp::PushBack(3), ListOp::MoveNext, ListOp::Remove]);
+//! ```
+
+use core::ops::Deref;
+use std::vec::Vec;
+
+use crate::differential::{DiffLinkedList, DiffRBTree, DiffSinglyLinkedList, DiffXorLinkedList};
+use crate::linked_list::{LinkedList, LinkedListOps};
+use crate::pointer_ops::PointerOps;
+use crate::rbtree::RBTreeOps;
+use crate::singly_linked_list::{SinglyLinkedList, SinglyLinkedListOps};
+use crate::xor_linked_list::{XorLinkedList, XorLinkedListOps};
+use crate::{Adapter, KeyAdapter};
+
+// =============================================================================
+// ListOp
+// =============================================================================
+
+/// An operation on a `LinkedList` or an `XorLinkedList`.
+///
+/// Cursor operations apply to a cursor which is kept across operations and
+/// starts at the null object. The cursor moves to the null object if the
+/// element it points to is removed by `PopFront` or `PopBack`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ListOp {
+    /// `push_front`
+    PushFront(u32),
+    /// `push_back`
+    PushBack(u32),
+    /// `pop_front`
+    PopFront,
+    /// `pop_back`
+    PopBack,
+    /// `CursorMut::move_next`
+    MoveNext,
+    /// `CursorMut::move_prev`
+    MovePrev,
+    /// `CursorMut::insert_after`
+    InsertAfter(u32),
+    /// `CursorMut::insert_before`
+    InsertBefore(u32),
+    /// `CursorMut::remove`
+    Remove,
+    /// `CursorMut::splice_after` with a list of new elements
+    SpliceAfter(Vec<u32>),
+    /// `CursorMut::splice_before` with a list of new elements
+    SpliceBefore(Vec<u32>),
+    /// `CursorMut::split_after`, dropping the split off elements
+    SplitAfter,
+    /// `CursorMut::split_before`, dropping the split off elements
+    SplitBefore,
+    /// `clear`
+    Clear,
+}
+
+// The operations of `LinkedList` and `XorLinkedList` are the same, so both
+// checkers are generated from the same code.
+macro_rules! check_list {
+    ($diff_type:ident, $list_type:ident, $adapter:ident, $ops:ident, $make:ident, $get:ident) => {{
+        let mut list = $diff_type::new($adapter.clone(), $get);
+        for op in $ops {
+            match *op {
+                ListOp::PushFront(value) => list.push_front($make(value)),
+                ListOp::PushBack(value) => list.push_back($make(value)),
+                ListOp::PopFront => drop(list.pop_front()),
+                ListOp::PopBack => drop(list.pop_back()),
+                ListOp::MoveNext => list.move_next(),
+                ListOp::MovePrev => list.move_prev(),
+                ListOp::InsertAfter(value) => list.insert_after($make(value)),
+                ListOp::InsertBefore(value) => list.insert_before($make(value)),
+                ListOp::Remove => drop(list.remove()),
+                ListOp::SpliceAfter(ref values) | ListOp::SpliceBefore(ref values) => {
+                    let mut spliced = $list_type::new($adapter.clone());
+                    for &value in values {
+                        spliced.push_back($make(value));
+                    }
+                    if let ListOp::SpliceAfter(_) = *op {
+                        list.splice_after(spliced);
+                    } else {
+                        list.splice_before(spliced);
+                    }
+                }
+                ListOp::SplitAfter => list.split_after().clear(),
+                ListOp::SplitBefore => list.split_before().clear(),
+                ListOp::Clear => list.clear(),
+            }
+        }
+        list.clear();
+    }};
+}
+
+/// Runs `ops` on a `LinkedList` using `adapter`, and checks the result of
+/// every operation against a `Vec`.
+///
+/// `make` creates an element holding the given value, which `get` must
+/// return.
+///
+/// # Panics
+///
+/// Panics if the list doesn't behave like the `Vec`, or if `assert_valid`
+/// finds that it is corrupted.
+pub fn check_linked_list<A>(
+    adapter: A,
+    ops: &[ListOp],
+    mut make: impl FnMut(u32) -> <A::PointerOps as PointerOps>::Pointer,
+    get: impl Fn(&<A::PointerOps as PointerOps>::Value) -> u32,
+) where
+    A: Adapter + Clone,
+    A::LinkOps: LinkedListOps,
+    <A::PointerOps as PointerOps>::Pointer: Deref<Target = <A::PointerOps as PointerOps>::Value>,
+{
+    check_list!(DiffLinkedList, LinkedList, adapter, ops, make, get)
+}
+
+/// Runs `ops` on an `XorLinkedList` using `adapter`, and checks the result
+/// of every operation against a `Vec`.
+///
+/// `make` creates an element holding the given value, which `get` must
+/// return.
+///
+/// # Panics
+///
+/// Panics if the list doesn't behave like the `Vec`.
+pub fn check_xor_linked_list<A>(
+    adapter: A,
+    ops: &[ListOp],
+    mut make: impl FnMut(u32) -> <A::PointerOps as PointerOps>::Pointer,
+    get: impl Fn(&<A::PointerOps as PointerOps>::Value) -> u32,
+) where
+    A: Adapter + Clone,
+    A::LinkOps: XorLinkedListOps,
+    <A::PointerOps as PointerOps>::Pointer: Deref<Target = <A::PointerOps as PointerOps>::Value>,
+{
+    check_list!(DiffXorLinkedList, XorLinkedList, adapter, ops, make, get)
+}
+
+// =============================================================================
+// SinglyListOp
+// =============================================================================
+
+/// An operation on a `SinglyLinkedList`.
+///
+/// Cursor operations apply to a cursor which is kept across operations and
+/// starts at the null object. The cursor moves to the null object if the
+/// element it points to is removed by `PopFront`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SinglyListOp {
+    /// `push_front`
+    PushFront(u32),
+    /// `pop_front`
+    PopFront,
+    /// `CursorMut::move_next`
+    MoveNext,
+    /// `CursorMut::insert_after`
+    InsertAfter(u32),
+    /// `CursorMut::remove_next`
+    RemoveNext,
+    /// `CursorMut::splice_after` with a list of new elements
+    SpliceAfter(Vec<u32>),
+    /// `CursorMut::split_after`, dropping the split off elements
+    SplitAfter,
+    /// `clear`
+    Clear,
+}
+
+/// Runs `ops` on a `SinglyLinkedList` using `adapter`, and checks the result
+/// of every operation against a `Vec`.
+///
+/// `make` creates an element holding the given value, which `get` must
+/// return.
+///
+/// # Panics
+///
+/// Panics if the list doesn't behave like the `Vec`.
+pub fn check_singly_linked_list<A>(
+    adapter: A,
+    ops: &[SinglyListOp],
+    mut make: impl FnMut(u32) -> <A::PointerOps as PointerOps>::Pointer,
+    get: impl Fn(&<A::PointerOps as PointerOps>::Value) -> u32,
+) where
+    A: Adapter + Clone,
+    A::LinkOps: SinglyLinkedListOps,
+    <A::PointerOps as PointerOps>::Pointer: Deref<Target = <A::PointerOps as PointerOps>::Value>,
+{
+    let mut list = DiffSinglyLinkedList::new(adapter.clone(), get);
+    for op in ops {
+        match *op {
+            SinglyListOp::PushFront(value) => list.push_front(make(value)),
+            SinglyListOp::PopFront => drop(list.pop_front()),
+            SinglyListOp::MoveNext => list.move_next(),
+            SinglyListOp::InsertAfter(value) => list.insert_after(make(value)),
+            SinglyListOp::RemoveNext => drop(list.remove_next()),
+            SinglyListOp::SpliceAfter(ref values) => {
+                let mut spliced = SinglyLinkedList::new(adapter.clone());
+                for &value in values.iter().rev() {
+                    spliced.push_front(make(value));
+                }
+                list.splice_after(spliced);
+            }
+            SinglyListOp::SplitAfter => list.split_after().clear(),
+            SinglyListOp::Clear => list.clear(),
+        }
+    }
+    list.clear();
+}
+
+// =============================================================================
+// TreeOp
+// =============================================================================
+
+/// An operation on an `RBTree` whose keys are `u32`.
+///
+/// Cursor operations apply to a cursor which is kept across operations and
+/// starts at the null object. The cursor moves to the null object if the
+/// element it points to is removed by another operation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TreeOp {
+    /// `insert`
+    Insert(u32),
+    /// `find_mut` followed by `CursorMut::remove`
+    Remove(u32),
+    /// `front_mut` followed by `CursorMut::remove`
+    RemoveFront,
+    /// `back_mut` followed by `CursorMut::remove`
+    RemoveBack,
+    /// `lower_bound` and `upper_bound` with inclusive bounds
+    Bounds(u32),
+    /// `CursorMut::move_next`
+    MoveNext,
+    /// `CursorMut::move_prev`
+    MovePrev,
+    /// `CursorMut::remove`
+    RemoveCurrent,
+    /// `clear`
+    Clear,
+}
+
+/// Runs `ops` on an `RBTree` using `adapter`, and checks the result of every
+/// operation against a `BTreeMap` counting the elements with each key.
+///
+/// `make` creates an element with the given key.
+///
+/// # Panics
+///
+/// Panics if the tree doesn't behave like the `BTreeMap`, or if
+/// `assert_valid` finds that it is corrupted.
+pub fn check_rbtree<A>(
+    adapter: A,
+    ops: &[TreeOp],
+    mut make: impl FnMut(u32) -> <A::PointerOps as PointerOps>::Pointer,
+) where
+    A: Adapter + Clone + for<'a> KeyAdapter<'a, Key = u32>,
+    A::LinkOps: RBTreeOps,
+    <A::PointerOps as PointerOps>::Pointer: Deref<Target = <A::PointerOps as PointerOps>::Value>,
+{
+    let mut tree = DiffRBTree::new(adapter);
+    for op in ops {
+        match *op {
+            TreeOp::Insert(k) => tree.insert(make(k)),
+            TreeOp::Remove(k) => drop(tree.remove(&k)),
+            TreeOp::RemoveFront => drop(tree.pop_front()),
+            TreeOp::RemoveBack => drop(tree.pop_back()),
+            TreeOp::Bounds(k) => tree.check_key(&k),
+            TreeOp::MoveNext => tree.move_next(),
+            TreeOp::MovePrev => tree.move_prev(),
+            TreeOp::RemoveCurrent => drop(tree.remove_current()),
+            TreeOp::Clear => tree.clear(),
+        }
+    }
+    tree.clear();
+}
+
+// =============================================================================
+// Decoding
+// =============================================================================
+
+// Reads operations from the front of a byte stream. Every operation starts
+// with a byte selecting its kind, followed by the bytes of its arguments.
+struct Decoder<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn byte(&mut self) -> Option<u8> {
+        let (&byte, rest) = self.data.split_first()?;
+        self.data = rest;
+        Some(byte)
+    }
+
+    // Values use the same small range as the `proptest` strategies.
+    fn value(&mut self) -> Option<u32> {
+        self.byte().map(|byte| u32::from(byte % 64))
+    }
+
+    fn values(&mut self) -> Option<Vec<u32>> {
+        let len = self.byte()? % 4;
+        (0..len).map(|_| self.value()).collect()
+    }
+
+    // Decodes operations until the end of the stream, dropping a truncated
+    // last operation.
+    fn decode_all<T>(data: &[u8], mut decode: impl FnMut(&mut Decoder<'_>) -> Option<T>) -> Vec<T> {
+        let mut decoder = Decoder { data };
+        let mut ops = Vec::new();
+        while let Some(op) = decode(&mut decoder) {
+            ops.push(op);
+        }
+        ops
+    }
+}
+
+impl ListOp {
+    fn decode(decoder: &mut Decoder<'_>) -> Option<ListOp> {
+        Some(match decoder.byte()? % 14 {
+            0 => ListOp::PushFront(decoder.value()?),
+            1 => ListOp::PushBack(decoder.value()?),
+            2 => ListOp::PopFront,
+            3 => ListOp::PopBack,
+            4 => ListOp::MoveNext,
+            5 => ListOp::MovePrev,
+            6 => ListOp::InsertAfter(decoder.value()?),
+            7 => ListOp::InsertBefore(decoder.value()?),
+            8 => ListOp::Remove,
+            9 => ListOp::SpliceAfter(decoder.values()?),
+            10 => ListOp::SpliceBefore(decoder.values()?),
+            11 => ListOp::SplitAfter,
+            12 => ListOp::SplitBefore,
+            _ => ListOp::Clear,
+        })
+    }
+}
+
+impl SinglyListOp {
+    fn decode(decoder: &mut Decoder<'_>) -> Option<SinglyListOp> {
+        Some(match decoder.byte()? % 8 {
+            0 => SinglyListOp::PushFront(decoder.value()?),
+            1 => SinglyListOp::PopFront,
+            2 => SinglyListOp::MoveNext,
+            3 => SinglyListOp::InsertAfter(decoder.value()?),
+            4 => SinglyListOp::RemoveNext,
+            5 => SinglyListOp::SpliceAfter(decoder.values()?),
+            6 => SinglyListOp::SplitAfter,
+            _ => SinglyListOp::Clear,
+        })
+    }
+}
+
+impl TreeOp {
+    fn decode(decoder: &mut Decoder<'_>) -> Option<TreeOp> {
+        Some(match decoder.byte()? % 9 {
+            0 => TreeOp::Insert(decoder.value()?),
+            1 => TreeOp::Remove(decoder.value()?),
+            2 => TreeOp::RemoveFront,
+            3 => TreeOp::RemoveBack,
+            4 => TreeOp::Bounds(decoder.value()?),
+            5 => TreeOp::MoveNext,
+            6 => TreeOp::MovePrev,
+            7 => TreeOp::RemoveCurrent,
+            _ => TreeOp::Clear,
+        })
+    }
+}
+
+/// Decodes a sequence of `ListOp` from `data`.
+///
+/// Every byte sequence is valid: the kind of each operation is its first
+/// byte modulo the number of kinds, and values are bytes modulo 64. An
+/// operation truncated by the end of `data` is ignored.
+pub fn decode_list_ops(data: &[u8]) -> Vec<ListOp> {
+    Decoder::decode_all(data, ListOp::decode)
+}
+
+/// Decodes a sequence of `SinglyListOp` from `data`, in the same way as
+/// `decode_list_ops`.
+pub fn decode_singly_list_ops(data: &[u8]) -> Vec<SinglyListOp> {
+    Decoder::decode_all(data, SinglyListOp::decode)
+}
+
+/// Decodes a sequence of `TreeOp` from `data`, in the same way as
+/// `decode_list_ops`.
+pub fn decode_tree_ops(data: &[u8]) -> Vec<TreeOp> {
+    Decoder::decode_all(data, TreeOp::decode)
+}
+
+// =============================================================================
+// Running
+// =============================================================================
+
+// Element used by the `run_*` functions, which can be in every collection.
+#[cfg(feature = "fuzzing")]
+mod obj {
+    use crate::{KeyAdapter, LinkedListLink, RBTreeLink, SinglyLinkedListLink, XorLinkedListLink};
+    use alloc::boxed::Box;
+
+    pub struct Obj {
+        link: LinkedListLink,
+        singly_link: SinglyLinkedListLink,
+        xor_link: XorLinkedListLink,
+        tree_link: RBTreeLink,
+        pub value: u32,
+    }
+    intrusive_adapter!(pub ObjAdapter = Box<Obj>: Obj { link: LinkedListLink });
+    intrusive_adapter!(pub ObjSinglyAdapter = Box<Obj>: Obj { singly_link: SinglyLinkedListLink });
+    intrusive_adapter!(pub ObjXorAdapter = Box<Obj>: Obj { xor_link: XorLinkedListLink });
+    intrusive_adapter!(pub ObjTreeAdapter = Box<Obj>: Obj { tree_link: RBTreeLink });
+    impl<'a> KeyAdapter<'a> for ObjTreeAdapter {
+        type Key = u32;
+        fn get_key(&self, value: &'a Obj) -> u32 {
+            value.value
+        }
+    }
+
+    pub fn make_obj(value: u32) -> Box<Obj> {
+        Box::new(Obj {
+            link: LinkedListLink::new(),
+            singly_link: SinglyLinkedListLink::new(),
+            xor_link: XorLinkedListLink::new(),
+            tree_link: RBTreeLink::new(),
+            value,
+        })
+    }
+}
+
+/// Decodes `data` with `decode_list_ops` and checks the operations on a
+/// `LinkedList` of boxed elements with `check_linked_list`.
+#[cfg(feature = "fuzzing")]
+pub fn run_linked_list(data: &[u8]) {
+    use self::obj::{make_obj, ObjAdapter};
+    check_linked_list(ObjAdapter::new(), &decode_list_ops(data), make_obj, |obj| {
+        obj.value
+    });
+}
+
+/// Decodes `data` with `decode_list_ops` and checks the operations on an
+/// `XorLinkedList` of boxed elements with `check_xor_linked_list`.
+#[cfg(feature = "fuzzing")]
+pub fn run_xor_linked_list(data: &[u8]) {
+    use self::obj::{make_obj, ObjXorAdapter};
+    check_xor_linked_list(
+        ObjXorAdapter::new(),
+        &decode_list_ops(data),
+        make_obj,
+        |obj| obj.value,
+    );
+}
+
+/// Decodes `data` with `decode_singly_list_ops` and checks the operations on
+/// a `SinglyLinkedList` of boxed elements with `check_singly_linked_list`.
+#[cfg(feature = "fuzzing")]
+pub fn run_singly_linked_list(data: &[u8]) {
+    use self::obj::{make_obj, ObjSinglyAdapter};
+    check_singly_linked_list(
+        ObjSinglyAdapter::new(),
+        &decode_singly_list_ops(data),
+        make_obj,
+        |obj| obj.value,
+    );
+}
+
+/// Decodes `data` with `decode_tree_ops` and checks the operations on an
+/// `RBTree` of boxed elements with `check_rbtree`.
+#[cfg(feature = "fuzzing")]
+pub fn run_rbtree(data: &[u8]) {
+    use self::obj::{make_obj, ObjTreeAdapter};
+    check_rbtree(ObjTreeAdapter::new(), &decode_tree_ops(data), make_obj);
+}
+
+/// Runs one of the other `run_*` functions on `data`, selected by its first
+/// byte.
+#[cfg(feature = "fuzzing")]
+pub fn run(data: &[u8]) {
+    if let Some((&collection, data)) = data.split_first() {
+        match collection % 4 {
+            0 => run_linked_list(data),
+            1 => run_xor_linked_list(data),
+            2 => run_singly_linked_list(data),
+            _ => run_rbtree(data),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "fuzzing"))]
+mod tests {
+    use super::{
+        decode_list_ops, decode_singly_list_ops, decode_tree_ops, run, ListOp, SinglyListOp, TreeOp,
+    };
+    use rand::{Rng, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+    use std::vec;
+    use std::vec::Vec;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(
+            decode_list_ops(&[0, 65, 9, 2, 1, 2, 14, 13, 6]),
+            [
+                ListOp::PushFront(1),
+                ListOp::SpliceAfter(vec![1, 2]),
+                ListOp::PushFront(13),
+            ]
+        );
+        assert_eq!(
+            decode_singly_list_ops(&[4, 7, 3]),
+            [SinglyListOp::RemoveNext, SinglyListOp::Clear]
+        );
+        assert_eq!(
+            decode_tree_ops(&[4, 200, 7, 8]),
+            [TreeOp::Bounds(8), TreeOp::RemoveCurrent, TreeOp::Clear]
+        );
+        assert_eq!(decode_tree_ops(&[]), []);
+    }
+
+    #[test]
+    fn test_run_random() {
+        let mut rng = XorShiftRng::seed_from_u64(0);
+        for _ in 0..200 {
+            let len = rng.gen_range(0..256);
+            let data = (0..len).map(|_| rng.gen()).collect::<Vec<u8>>();
+            run(&data);
+        }
+    }
+}
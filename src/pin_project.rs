@@ -0,0 +1,309 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+/// Macro to declare a `!Unpin` struct containing links, along with a
+/// projection of `Pin<&mut T>` to its fields.
+///
+/// A value which is in a collection through a pinned pointer such as
+/// `Pin<Box<T>>` must not move until it is removed, which is what makes it
+/// possible to embed links in futures and other self-referential types. This
+/// macro declares such a struct and gives it a `project` method, which turns
+/// `Pin<&mut Self>` into a struct of references to the fields (named by the
+/// `#[project = ...]` attribute, which is required):
+///
+/// - Fields marked with `#[link]` are projected to shared references, since
+///   links can be used through a shared reference and must not be moved or
+///   replaced while they are in a collection.
+/// - Fields marked with `#[pin]` are structurally pinned, and are projected
+///   to `Pin<&mut T>`.
+/// - Other fields are projected to `&mut T`.
+///
+/// The markers must come before any other attribute of their field. The
+/// struct never implements `Unpin`. It can't implement `Drop` either, since
+/// `drop` could move pinned fields: an `impl PinnedDrop` block can be written
+/// inside of the macro instead, which receives `Pin<&mut Self>`.
+///
+/// The struct may have type parameters with at most one trait bound each,
+/// but no lifetime parameters or `where` clause.
+///
+/// # Examples
+///
+/// ```
+/// use core::pin::Pin;
+/// use intrusive_collections::{intrusive_adapter, intrusive_pin_project};
+/// use intrusive_collections::{LinkedList, LinkedListLink};
+///
+/// intrusive_pin_project! {
+///     #[project = WaiterProj]
+///     struct Waiter<F: Unpin> {
+///         #[link]
+///         link: LinkedListLink,
+///         #[pin]
+///         fut: core::future::Ready<F>,
+///         polls: u32,
+///     }
+///     impl PinnedDrop for Waiter<F> {
+///         fn drop(this: Pin<&mut Self>) {
+///             // The list owns its elements, so they are always unlinked
+///             // by the time they are dropped.
+///             assert!(!this.link.is_linked());
+///         }
+///     }
+/// }
+///
+/// intrusive_adapter!(WaiterAdapter = Pin<Box<Waiter<u32>>>: Waiter<u32> { link: LinkedListLink });
+///
+/// let mut list = LinkedList::new(WaiterAdapter::new());
+/// list.push_back(Box::pin(Waiter {
+///     link: LinkedListLink::new(),
+///     fut: core::future::ready(1),
+///     polls: 0,
+/// }));
+/// let mut cursor = list.front_mut();
+/// let waiter = unsafe { cursor.get_pin_mut().unwrap() };
+/// let proj = waiter.project();
+/// assert!(proj.link.is_linked());
+/// *proj.polls += 1;
+/// let _fut: Pin<&mut core::future::Ready<u32>> = proj.fut;
+/// assert_eq!(list.front().get().unwrap().polls, 1);
+/// ```
+///
+/// Implementing `Drop` directly is rejected:
+///
+/// ```compile_fail
+/// use intrusive_collections::{intrusive_pin_project, LinkedListLink};
+///
+/// intrusive_pin_project! {
+///     #[project = WaiterProj]
+///     struct Waiter {
+///         #[link]
+///         link: LinkedListLink,
+///     }
+/// }
+///
+/// impl Drop for Waiter {
+///     fn drop(&mut self) {}
+/// }
+/// ```
+#[macro_export]
+macro_rules! intrusive_pin_project {
+    (
+        #[project = $proj:ident]
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident $(<$($param:ident $(: $bound:path)?),+ $(,)?>)? {
+            $($fields:tt)*
+        }
+        $(
+            impl PinnedDrop for $drop_ty:ty {
+                fn drop($this:ident: Pin<&mut Self>) $drop_body:block
+            }
+        )?
+    ) => {
+        $crate::intrusive_pin_project!(@fields
+            [$proj [$(#[$attr])*] [$vis] $name [$($($param $(: $bound)?),+)?]]
+            [$($this $drop_body)?]
+            [] [] [] []
+            $($fields)*
+        );
+    };
+
+    // Sorts the fields by their marker, keeping their declarations in order.
+    (@fields $head:tt $drop:tt [$($all:tt)*] [$($link:tt)*] [$($pin:tt)*] [$($other:tt)*]
+        #[link] $(#[$fattr:meta])* $fvis:vis $field:ident: $ty:ty $(, $($rest:tt)*)?
+    ) => {
+        $crate::intrusive_pin_project!(@fields $head $drop
+            [$($all)* [$(#[$fattr])* $fvis $field: $ty]]
+            [$($link)* [$fvis $field: $ty]] [$($pin)*] [$($other)*]
+            $($($rest)*)?
+        );
+    };
+    (@fields $head:tt $drop:tt [$($all:tt)*] [$($link:tt)*] [$($pin:tt)*] [$($other:tt)*]
+        #[pin] $(#[$fattr:meta])* $fvis:vis $field:ident: $ty:ty $(, $($rest:tt)*)?
+    ) => {
+        $crate::intrusive_pin_project!(@fields $head $drop
+            [$($all)* [$(#[$fattr])* $fvis $field: $ty]]
+            [$($link)*] [$($pin)* [$fvis $field: $ty]] [$($other)*]
+            $($($rest)*)?
+        );
+    };
+    (@fields $head:tt $drop:tt [$($all:tt)*] [$($link:tt)*] [$($pin:tt)*] [$($other:tt)*]
+        $(#[$fattr:meta])* $fvis:vis $field:ident: $ty:ty $(, $($rest:tt)*)?
+    ) => {
+        $crate::intrusive_pin_project!(@fields $head $drop
+            [$($all)* [$(#[$fattr])* $fvis $field: $ty]]
+            [$($link)*] [$($pin)*] [$($other)* [$fvis $field: $ty]]
+            $($($rest)*)?
+        );
+    };
+
+    (@fields
+        [$proj:ident [$(#[$attr:meta])*] [$vis:vis] $name:ident [$($param:ident $(: $bound:path)?),*]]
+        [$($this:ident $drop_body:block)?]
+        [$([$(#[$fattr:meta])* $fvis:vis $field:ident: $ty:ty])*]
+        [$([$lvis:vis $lfield:ident: $lty:ty])*]
+        [$([$pvis:vis $pfield:ident: $pty:ty])*]
+        [$([$ovis:vis $ofield:ident: $oty:ty])*]
+    ) => {
+        $(#[$attr])*
+        $vis struct $name<$($param $(: $bound)?),*> {
+            $($(#[$fattr])* $fvis $field: $ty,)*
+        }
+
+        /// Projection of a pinned reference, created by `project`.
+        #[allow(dead_code)]
+        $vis struct $proj<'__pin, $($param $(: $bound)?),*> {
+            $($lvis $lfield: &'__pin $lty,)*
+            $($pvis $pfield: core::pin::Pin<&'__pin mut $pty>,)*
+            $($ovis $ofield: &'__pin mut $oty,)*
+        }
+
+        #[allow(dead_code)]
+        impl<$($param $(: $bound)?),*> $name<$($param),*> {
+            /// Projects a pinned reference to references to the fields.
+            #[inline]
+            $vis fn project<'__pin>(
+                self: core::pin::Pin<&'__pin mut Self>,
+            ) -> $proj<'__pin, $($param),*> {
+                // Only the fields marked with `#[pin]` are handed out as
+                // mutable references which could move them, wrapped in `Pin`.
+                let Self { $($field),* } = unsafe { self.get_unchecked_mut() };
+                $proj {
+                    $($lfield: &*$lfield,)*
+                    $($pfield: unsafe { core::pin::Pin::new_unchecked($pfield) },)*
+                    $($ofield,)*
+                }
+            }
+        }
+
+        const _: () = {
+            // The struct is never `Unpin`, since its links must not move while
+            // they are in a collection. The lifetime keeps the bound from
+            // being trivially false.
+            #[allow(dead_code)]
+            struct __Origin<'__pin> {
+                _lifetime: core::marker::PhantomData<&'__pin ()>,
+                _pinned: core::marker::PhantomPinned,
+            }
+            impl<'__pin, $($param $(: $bound)?),*> core::marker::Unpin for $name<$($param),*>
+            where
+                __Origin<'__pin>: core::marker::Unpin,
+            {
+            }
+
+            $crate::intrusive_pin_project!(@drop $name [$($param $(: $bound)?),*] $($this $drop_body)?);
+        };
+    };
+
+    // Without `PinnedDrop`, a conflicting implementation of this trait
+    // rejects any `Drop` implementation of the struct.
+    (@drop $name:ident [$($param:ident $(: $bound:path)?),*]) => {
+        #[allow(dead_code)]
+        trait MustNotImplDrop {}
+        #[allow(drop_bounds)]
+        impl<T: core::ops::Drop> MustNotImplDrop for T {}
+        impl<$($param $(: $bound)?),*> MustNotImplDrop for $name<$($param),*> {}
+    };
+    (@drop $name:ident [$($param:ident $(: $bound:path)?),*] $this:ident $drop_body:block) => {
+        impl<$($param $(: $bound)?),*> $name<$($param),*> {
+            fn __pinned_drop($this: core::pin::Pin<&mut Self>) $drop_body
+        }
+        impl<$($param $(: $bound)?),*> core::ops::Drop for $name<$($param),*> {
+            fn drop(&mut self) {
+                // The value is never moved again once it is being dropped.
+                Self::__pinned_drop(unsafe { core::pin::Pin::new_unchecked(self) });
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{LinkedList, LinkedListLink, RBTreeLink};
+    use core::cell::Cell;
+    use core::marker::PhantomPinned;
+    use core::pin::Pin;
+    use std::boxed::Box;
+    use std::vec::Vec;
+
+    struct Pinned(u32, PhantomPinned);
+
+    intrusive_pin_project! {
+        #[project = ObjProj]
+        struct Obj<T: Copy> {
+            #[link]
+            list_link: LinkedListLink,
+            #[link]
+            tree_link: RBTreeLink,
+            #[pin]
+            pinned: Pinned,
+            /// Not pinned.
+            value: T,
+        }
+    }
+    intrusive_adapter!(ListAdapter = Pin<Box<Obj<u32>>>: Obj<u32> { list_link: LinkedListLink });
+
+    fn make_obj(value: u32) -> Pin<Box<Obj<u32>>> {
+        Box::pin(Obj {
+            list_link: LinkedListLink::new(),
+            tree_link: RBTreeLink::new(),
+            pinned: Pinned(value, PhantomPinned),
+            value,
+        })
+    }
+
+    #[test]
+    fn test_project() {
+        let mut l = LinkedList::new(ListAdapter::new());
+        l.push_back(make_obj(1));
+        l.push_back(make_obj(2));
+        let mut cur = l.front_mut();
+        while let Some(obj) = unsafe { cur.get_pin_mut() } {
+            let proj = obj.project();
+            assert!(proj.list_link.is_linked());
+            assert!(!proj.tree_link.is_linked());
+            let pinned: Pin<&mut Pinned> = proj.pinned;
+            *proj.value += pinned.0 * 10;
+            cur.move_next();
+        }
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [11, 22]);
+    }
+
+    std::thread_local! {
+        static DROPPED: Cell<u32> = const { Cell::new(0) };
+    }
+
+    intrusive_pin_project! {
+        #[project = DroppedProj]
+        struct Dropped {
+            #[link]
+            link: LinkedListLink,
+            value: u32,
+        }
+        impl PinnedDrop for Dropped {
+            fn drop(this: Pin<&mut Self>) {
+                let value = *this.project().value;
+                DROPPED.with(|dropped| dropped.set(dropped.get() + value));
+            }
+        }
+    }
+    intrusive_adapter!(DroppedAdapter = Pin<Box<Dropped>>: Dropped { link: LinkedListLink });
+
+    #[test]
+    fn test_pinned_drop() {
+        let mut l = LinkedList::new(DroppedAdapter::new());
+        for value in [1, 2] {
+            l.push_back(Box::pin(Dropped {
+                link: LinkedListLink::new(),
+                value,
+            }));
+        }
+        drop(l.pop_front());
+        assert_eq!(DROPPED.with(Cell::get), 1);
+        l.clear();
+        assert_eq!(DROPPED.with(Cell::get), 3);
+    }
+}
@@ -8,14 +8,19 @@
 #[cfg(feature = "alloc")]
 use crate::alloc::boxed::Box;
 #[cfg(feature = "alloc")]
-use crate::alloc::rc::Rc;
+use crate::alloc::rc::{self, Rc};
 #[cfg(feature = "alloc")]
-use crate::alloc::sync::Arc;
+use crate::alloc::sync::{self, Arc};
+#[cfg(feature = "alloc")]
+use crate::arena::ArenaBox;
+#[cfg(feature = "static_pool")]
+use crate::static_pool::{PoolBox, StaticPool};
 use crate::{UnsafeMut, UnsafeRef};
 use core::marker::PhantomData;
-use core::mem::ManuallyDrop;
+use core::mem::{self, ManuallyDrop};
 use core::ops::Deref;
 use core::pin::Pin;
+use core::ptr::NonNull;
 
 /// Trait for pointer conversion operations.
 ///
@@ -25,12 +30,33 @@ use core::pin::Pin;
 /// `Pointer` is a pointer type which "owns" an object of type `Value`.
 /// Operations which insert an element into an intrusive collection will accept
 /// such a pointer and operations which remove an element will return this type.
+///
+/// # Safety
+///
+/// `into_raw` and `from_raw` must be inverses of each other, and the object
+/// pointed to by a raw pointer returned by `into_raw` must remain valid and
+/// must not move until it is passed back to `from_raw`.
+///
+/// Since the collections create references to the object from the raw
+/// pointer, it is always aligned for `Value`. Its low bits are therefore free
+/// to hold a tag while the pointer isn't in a collection, see
+/// [into_tagged_raw] and [from_tagged_raw].
+#[allow(clippy::wrong_self_convention)]
 pub unsafe trait PointerOps {
     /// Object type which is inserted into an intrusive collection.
     type Value: ?Sized;
     /// Pointer type which owns an instance of a value.
     type Pointer;
 
+    /// Whether dropping a `Pointer` can free the object it points to, as for
+    /// `Box` or `Rc`.
+    ///
+    /// This is `false` for references and for unmanaged pointers such as
+    /// `UnsafeRef`, for which dropping a collection which still contains
+    /// objects usually means that they are leaked or will dangle. The
+    /// `debug_leaks` feature makes collections panic in that case.
+    const OWNS_VALUE: bool = true;
+
     /// Constructs an owned pointer from a raw pointer.
     ///
     /// # Safety
@@ -41,23 +67,72 @@ pub unsafe trait PointerOps {
 
     /// Consumes the owned pointer and returns a raw pointer to the owned object.
     fn into_raw(&self, ptr: Self::Pointer) -> *const Self::Value;
+
+    /// Called by collections once they have acquired the link of an object
+    /// which was passed to `into_raw`, before the `on_insert` hook of the
+    /// adapter. If the link is already in use, the object is passed back to
+    /// `from_raw` without calling this.
+    ///
+    /// The default implementation does nothing.
+    ///
+    /// # Safety
+    /// `value` must have been returned by `into_raw` and not yet passed to
+    /// `from_raw`.
+    #[inline]
+    unsafe fn on_link_acquired(&self, value: *const Self::Value) {
+        let _ = value;
+    }
+
+    /// Returns `true` if `self` and `other` convert pointers the same way, so
+    /// that an object passed to `into_raw` of one can be passed to `from_raw`
+    /// of the other. Collections check this before moving an element into
+    /// another collection without converting it, as `transfer_after` does.
+    ///
+    /// The default implementation returns `true` only if `Self` is
+    /// zero-sized, since it then has no state. Implementations holding state,
+    /// such as a storage or an offset, should compare it.
+    #[inline]
+    fn same_state(&self, other: &Self) -> bool
+    where
+        Self: Sized,
+    {
+        let _ = other;
+        mem::size_of::<Self>() == 0
+    }
 }
 
 /// Trait for conditional exclusive access to the object managed by the collection.
+///
+/// # Safety
+///
+/// A pointer returned by `try_get_mut` must be the only way to access the
+/// value for as long as it is in use.
 pub unsafe trait TryExclusivePointerOps: PointerOps {
     /// Try to get unique access to the value.
     /// Returns Some if the value is uniquely owned by the collection. None if not.
+    ///
+    /// # Safety
+    /// `value` must have been returned by `into_raw` and not yet passed to
+    /// `from_raw`.
     unsafe fn try_get_mut(&self, value: *const Self::Value) -> Option<*mut Self::Value>;
 }
 
 /// Trait for exclusive access to the object managed by the collection.
 ///
 /// Implementing this type automatically implements [TryExclusivePointerOps]
-/// 
+///
 /// The default implementation of [get_mut](Self::get_mut) simply casts `*const Self::Value` to `*mut Self::Value`
+///
+/// # Safety
+///
+/// The pointer type must have exclusive ownership of the value it points to.
 pub unsafe trait ExclusivePointerOps: TryExclusivePointerOps {
     /// Get unique access to the value
     /// This function always succeeds, if you want to conditionally allow exclusive access, implement [TryExclusivePointerOps] instead.
+    ///
+    /// # Safety
+    /// `value` must have been returned by `into_raw` and not yet passed to
+    /// `from_raw`.
     #[inline]
     unsafe fn get_mut(&self, value: *const Self::Value) -> *mut Self::Value {
         value as *mut Self::Value
@@ -69,6 +144,230 @@ unsafe impl<E: ExclusivePointerOps> TryExclusivePointerOps for E {
     }
 }
 
+/// Marker trait for pointer operations which keep the objects managed by the
+/// collection pinned.
+///
+/// This is implemented for `DefaultPointerOps<Pin<P>>`, so that collections
+/// of `Pin<Box<T>>`, `Pin<Rc<T>>` and so on can hand out pinned references to
+/// their elements through `Cursor::get_pin` and `CursorMut::get_pin_mut`.
+/// Objects which are `!Unpin` should be stored through such pointer
+/// operations: then they are inserted and removed as `Pin` pointers, and are
+/// only accessible through `Pin` references while in the collection.
+///
+/// The `Pin` pointer operations only implement `ExclusivePointerOps` and
+/// `TryExclusivePointerOps` if `T: Unpin`, so the methods giving out plain
+/// mutable references can't be used on pinned objects:
+///
+/// ```compile_fail
+/// use core::marker::PhantomPinned;
+/// use core::pin::Pin;
+/// use intrusive_collections::{intrusive_adapter, LinkedList, LinkedListLink};
+///
+/// struct Value {
+///     link: LinkedListLink,
+///     _pin: PhantomPinned,
+/// }
+/// intrusive_adapter!(ValueAdapter = Pin<Box<Value>>: Value { link: LinkedListLink });
+///
+/// let mut list = LinkedList::new(ValueAdapter::new());
+/// list.push_back(Box::pin(Value { link: LinkedListLink::new(), _pin: PhantomPinned }));
+/// let value: &mut Value = unsafe { list.front_mut().get_mut().unwrap() };
+/// ```
+///
+/// # Safety
+///
+/// Every object managed by the pointer operations must be pinned, in the sense
+/// of `core::pin`, from the time it is passed to `into_raw`. The pointer
+/// operations must not implement `TryExclusivePointerOps` unless `Value` is
+/// `Unpin`.
+pub unsafe trait PinnedPointerOps: PointerOps {}
+
+/// Trait for exclusive pinned access to the objects managed by the
+/// collection, through `CursorMut::get_pin_mut`.
+///
+/// The default implementation of [get_pin_mut](Self::get_pin_mut) simply
+/// casts `*const Self::Value` to `*mut Self::Value`.
+///
+/// # Safety
+///
+/// The pointer type must have exclusive ownership of the value it points to.
+pub unsafe trait ExclusivePinnedPointerOps: PinnedPointerOps {
+    /// Get unique access to the value, which must stay pinned.
+    ///
+    /// # Safety
+    /// `value` must have been returned by `into_raw` and not yet passed to
+    /// `from_raw`.
+    #[inline]
+    unsafe fn get_pin_mut(&self, value: *const Self::Value) -> *mut Self::Value {
+        value as *mut Self::Value
+    }
+}
+
+unsafe impl<P: Deref> PinnedPointerOps for DefaultPointerOps<Pin<P>> where
+    DefaultPointerOps<Pin<P>>: PointerOps
+{
+}
+
+/// Trait for pointer operations whose conversion back from a raw pointer can
+/// fail.
+///
+/// This is meant for handle-based pointers, such as slot map keys with a
+/// generation counter (see `SlotPointerOps`), where the object behind a raw
+/// pointer may have been invalidated while it was in the collection. Collections expose this
+/// through methods like `LinkedList::pop_front_checked`, which return the error
+/// from `try_from_raw` instead of a pointer.
+///
+/// `from_raw` must still be implemented, and is used by the rest of the
+/// collection API without any checks.
+///
+/// # Safety
+///
+/// A successful `try_from_raw` must return the same pointer `from_raw` would
+/// have returned.
+pub unsafe trait TryPointerOps: PointerOps {
+    /// The error returned when a raw pointer can't be converted back.
+    type Error;
+
+    /// Attempts to construct an owned pointer from a raw pointer.
+    ///
+    /// The object is no longer in the collection when this is called, whether
+    /// or not the conversion succeeds.
+    ///
+    /// # Safety
+    /// `value` must be a pointer returned by `into_raw` for the same pointer
+    /// type, and must not have been passed to `from_raw` or `try_from_raw`
+    /// since.
+    unsafe fn try_from_raw(&self, value: *const Self::Value) -> Result<Self::Pointer, Self::Error>;
+}
+
+/// Trait for copy-on-write access to the object managed by the collection.
+///
+/// An object in an intrusive collection can't be cloned in place, since the
+/// clone lives at a different address and the collection only knows about the
+/// original. Instead, a cursor's `make_mut` uses `clone_value` to create a
+/// uniquely owned copy of a shared object and swaps it into the collection
+/// with `replace_with`, the same way `Arc::make_mut` swaps in a new
+/// allocation.
+///
+/// # Safety
+///
+/// `try_get_mut` must succeed for a pointer returned by `clone_value` until it
+/// is cloned or shared in some other way.
+pub unsafe trait CowPointerOps: TryExclusivePointerOps {
+    /// Returns a uniquely owned pointer to a clone of `value`.
+    ///
+    /// The clone must not be linked to any collection, which holds for the
+    /// links in this crate since cloning a link returns an unlinked one.
+    fn clone_value(&self, value: &Self::Value) -> Self::Pointer;
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T: Clone> CowPointerOps for DefaultPointerOps<Rc<T>> {
+    #[inline]
+    fn clone_value(&self, value: &T) -> Rc<T> {
+        Rc::new(value.clone())
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T: Clone> CowPointerOps for DefaultPointerOps<Arc<T>> {
+    #[inline]
+    fn clone_value(&self, value: &T) -> Arc<T> {
+        Arc::new(value.clone())
+    }
+}
+
+/// Trait for pointer types which have a weak counterpart, such as `Rc` and
+/// `Arc`.
+///
+/// This is used by `WeakList` to hold weak references to its elements. A weak
+/// reference keeps the allocation of an object alive, and therefore its link,
+/// but not the object itself.
+///
+/// # Safety
+///
+/// `weak_into_raw` and `weak_from_raw` must be inverses of each other, and the
+/// memory pointed to by a raw pointer returned by `weak_into_raw` must remain
+/// allocated until it is passed back to `weak_from_raw`, even if the object
+/// itself is dropped. `upgrade` must only return a pointer if the object is
+/// still alive.
+pub unsafe trait DowngradePointerOps: PointerOps {
+    /// Weak pointer type.
+    type WeakPointer;
+
+    /// Creates a weak pointer to the object owned by `ptr`.
+    fn downgrade(&self, ptr: &Self::Pointer) -> Self::WeakPointer;
+
+    /// Consumes the weak pointer and returns a raw pointer to the object.
+    fn weak_into_raw(&self, weak: Self::WeakPointer) -> *const Self::Value;
+
+    /// Constructs a weak pointer from a raw pointer.
+    ///
+    /// # Safety
+    /// The raw pointer must have been previously returned by `weak_into_raw`.
+    unsafe fn weak_from_raw(&self, value: *const Self::Value) -> Self::WeakPointer;
+
+    /// Attempts to get an owned pointer to the object from a raw weak
+    /// pointer, returning `None` if the object has been dropped.
+    ///
+    /// The raw weak pointer is not consumed.
+    ///
+    /// # Safety
+    /// The raw pointer must have been previously returned by `weak_into_raw`
+    /// and not yet passed to `weak_from_raw`.
+    unsafe fn upgrade(&self, value: *const Self::Value) -> Option<Self::Pointer>;
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T> DowngradePointerOps for DefaultPointerOps<Rc<T>> {
+    type WeakPointer = rc::Weak<T>;
+
+    #[inline]
+    fn downgrade(&self, ptr: &Rc<T>) -> rc::Weak<T> {
+        Rc::downgrade(ptr)
+    }
+
+    #[inline]
+    fn weak_into_raw(&self, weak: rc::Weak<T>) -> *const T {
+        weak.into_raw()
+    }
+
+    #[inline]
+    unsafe fn weak_from_raw(&self, value: *const T) -> rc::Weak<T> {
+        rc::Weak::from_raw(value)
+    }
+
+    #[inline]
+    unsafe fn upgrade(&self, value: *const T) -> Option<Rc<T>> {
+        ManuallyDrop::new(rc::Weak::from_raw(value)).upgrade()
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T> DowngradePointerOps for DefaultPointerOps<Arc<T>> {
+    type WeakPointer = sync::Weak<T>;
+
+    #[inline]
+    fn downgrade(&self, ptr: &Arc<T>) -> sync::Weak<T> {
+        Arc::downgrade(ptr)
+    }
+
+    #[inline]
+    fn weak_into_raw(&self, weak: sync::Weak<T>) -> *const T {
+        weak.into_raw()
+    }
+
+    #[inline]
+    unsafe fn weak_from_raw(&self, value: *const T) -> sync::Weak<T> {
+        sync::Weak::from_raw(value)
+    }
+
+    #[inline]
+    unsafe fn upgrade(&self, value: *const T) -> Option<Arc<T>> {
+        ManuallyDrop::new(sync::Weak::from_raw(value)).upgrade()
+    }
+}
+
 /// The `PointerOps` type used by an `Adapter` generated by `intrusive_adapter!`.
 pub struct DefaultPointerOps<Pointer>(PhantomData<Pointer>);
 
@@ -99,6 +398,7 @@ impl<Pointer> Default for DefaultPointerOps<Pointer> {
 unsafe impl<'a, T: ?Sized> PointerOps for DefaultPointerOps<&'a T> {
     type Value = T;
     type Pointer = &'a T;
+    const OWNS_VALUE: bool = false;
 
     #[inline]
     unsafe fn from_raw(&self, raw: *const T) -> &'a T {
@@ -114,6 +414,7 @@ unsafe impl<'a, T: ?Sized> PointerOps for DefaultPointerOps<&'a T> {
 unsafe impl<'a, T: ?Sized> PointerOps for DefaultPointerOps<Pin<&'a T>> {
     type Value = T;
     type Pointer = Pin<&'a T>;
+    const OWNS_VALUE: bool = false;
 
     #[inline]
     unsafe fn from_raw(&self, raw: *const T) -> Pin<&'a T> {
@@ -126,9 +427,48 @@ unsafe impl<'a, T: ?Sized> PointerOps for DefaultPointerOps<Pin<&'a T>> {
     }
 }
 
+unsafe impl<'a, T: ?Sized> PointerOps for DefaultPointerOps<&'a mut T> {
+    type Value = T;
+    type Pointer = &'a mut T;
+    const OWNS_VALUE: bool = false;
+
+    #[inline]
+    unsafe fn from_raw(&self, raw: *const T) -> &'a mut T {
+        &mut *(raw as *mut T)
+    }
+
+    #[inline]
+    fn into_raw(&self, ptr: &'a mut T) -> *const T {
+        ptr as *mut T as *const T
+    }
+}
+
+unsafe impl<T: ?Sized> ExclusivePointerOps for DefaultPointerOps<&mut T> {}
+
+unsafe impl<'a, T: ?Sized> PointerOps for DefaultPointerOps<Pin<&'a mut T>> {
+    type Value = T;
+    type Pointer = Pin<&'a mut T>;
+    const OWNS_VALUE: bool = false;
+
+    #[inline]
+    unsafe fn from_raw(&self, raw: *const T) -> Pin<&'a mut T> {
+        Pin::new_unchecked(&mut *(raw as *mut T))
+    }
+
+    #[inline]
+    fn into_raw(&self, ptr: Pin<&'a mut T>) -> *const T {
+        unsafe { Pin::into_inner_unchecked(ptr) as *mut T as *const T }
+    }
+}
+
+unsafe impl<T: ?Sized + Unpin> ExclusivePointerOps for DefaultPointerOps<Pin<&mut T>> {}
+
+unsafe impl<T: ?Sized> ExclusivePinnedPointerOps for DefaultPointerOps<Pin<&mut T>> {}
+
 unsafe impl<T: ?Sized> PointerOps for DefaultPointerOps<UnsafeRef<T>> {
     type Value = T;
     type Pointer = UnsafeRef<T>;
+    const OWNS_VALUE: bool = false;
 
     #[inline]
     unsafe fn from_raw(&self, raw: *const T) -> UnsafeRef<T> {
@@ -144,6 +484,7 @@ unsafe impl<T: ?Sized> PointerOps for DefaultPointerOps<UnsafeRef<T>> {
 unsafe impl<T: ?Sized> PointerOps for DefaultPointerOps<Pin<UnsafeRef<T>>> {
     type Value = T;
     type Pointer = Pin<UnsafeRef<T>>;
+    const OWNS_VALUE: bool = false;
 
     #[inline]
     unsafe fn from_raw(&self, raw: *const T) -> Pin<UnsafeRef<T>> {
@@ -159,6 +500,7 @@ unsafe impl<T: ?Sized> PointerOps for DefaultPointerOps<Pin<UnsafeRef<T>>> {
 unsafe impl<T: ?Sized> PointerOps for DefaultPointerOps<UnsafeMut<T>> {
     type Value = T;
     type Pointer = UnsafeMut<T>;
+    const OWNS_VALUE: bool = false;
 
     #[inline]
     unsafe fn from_raw(&self, raw: *const T) -> UnsafeMut<T> {
@@ -176,6 +518,7 @@ unsafe impl<T: ?Sized> ExclusivePointerOps for DefaultPointerOps<UnsafeMut<T>> {
 unsafe impl<T: ?Sized> PointerOps for DefaultPointerOps<Pin<UnsafeMut<T>>> {
     type Value = T;
     type Pointer = Pin<UnsafeMut<T>>;
+    const OWNS_VALUE: bool = false;
 
     #[inline]
     unsafe fn from_raw(&self, raw: *const T) -> Pin<UnsafeMut<T>> {
@@ -188,7 +531,127 @@ unsafe impl<T: ?Sized> PointerOps for DefaultPointerOps<Pin<UnsafeMut<T>>> {
     }
 }
 
-unsafe impl<T: ?Sized> ExclusivePointerOps for DefaultPointerOps<Pin<UnsafeMut<T>>> {}
+unsafe impl<T: ?Sized + Unpin> ExclusivePointerOps for DefaultPointerOps<Pin<UnsafeMut<T>>> {}
+
+unsafe impl<T: ?Sized> ExclusivePinnedPointerOps for DefaultPointerOps<Pin<UnsafeMut<T>>> {}
+
+/// The `PointerOps` type for raw pointers: `NonNull<T>` and `*const T`.
+///
+/// Raw pointers follow the same rules as `UnsafeRef`: an object must not be
+/// moved, dropped or accessed through a mutable reference while it is in a
+/// collection. Since a raw pointer can be created without `unsafe`, the
+/// promise is made once, when constructing the `RawPointerOps`, instead of
+/// for every pointer. This is useful for FFI code which already deals in raw
+/// pointers, where wrapping every element in an `UnsafeRef` is just noise.
+///
+/// Since `intrusive_adapter!` always uses `DefaultPointerOps`, an adapter
+/// using `RawPointerOps` has to implement `Adapter` manually.
+///
+/// # Examples
+///
+/// ```
+/// use core::ptr::NonNull;
+/// use intrusive_collections::linked_list::{Link, LinkOps};
+/// use intrusive_collections::{container_of, Adapter, LinkedList, RawPointerOps};
+///
+/// struct Value {
+///     link: Link,
+///     value: u32,
+/// }
+///
+/// struct ValueAdapter {
+///     link_ops: LinkOps,
+///     pointer_ops: RawPointerOps<NonNull<Value>>,
+/// }
+///
+/// unsafe impl Adapter for ValueAdapter {
+///     type LinkOps = LinkOps;
+///     type PointerOps = RawPointerOps<NonNull<Value>>;
+///
+///     unsafe fn get_value(&self, link: NonNull<Link>) -> *const Value {
+///         container_of!(link.as_ptr(), Value, link)
+///     }
+///     unsafe fn get_link(&self, value: *const Value) -> NonNull<Link> {
+///         NonNull::new_unchecked(core::ptr::addr_of!((*value).link) as *mut Link)
+///     }
+///     fn link_ops(&self) -> &LinkOps {
+///         &self.link_ops
+///     }
+///     fn link_ops_mut(&mut self) -> &mut LinkOps {
+///         &mut self.link_ops
+///     }
+///     fn pointer_ops(&self) -> &RawPointerOps<NonNull<Value>> {
+///         &self.pointer_ops
+///     }
+/// }
+///
+/// let value = Value { link: Link::new(), value: 1 };
+/// // Safe because `value` outlives the list and isn't moved while linked.
+/// let adapter = ValueAdapter {
+///     link_ops: LinkOps,
+///     pointer_ops: unsafe { RawPointerOps::new() },
+/// };
+/// let mut list = LinkedList::new(adapter);
+/// list.push_back(NonNull::from(&value));
+/// assert_eq!(list.front().get().unwrap().value, 1);
+/// # list.clear();
+/// ```
+pub struct RawPointerOps<Pointer>(PhantomData<Pointer>);
+
+impl<Pointer> RawPointerOps<Pointer> {
+    /// Constructs an instance of `RawPointerOps`.
+    ///
+    /// # Safety
+    ///
+    /// Every pointer passed to `into_raw` must point to a valid object, and
+    /// the guarantees of `UnsafeRef` must be upheld for that object until the
+    /// pointer is returned by `from_raw`.
+    #[inline]
+    pub const unsafe fn new() -> RawPointerOps<Pointer> {
+        RawPointerOps(PhantomData)
+    }
+}
+
+impl<Pointer> Clone for RawPointerOps<Pointer> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Pointer> Copy for RawPointerOps<Pointer> {}
+
+unsafe impl<T: ?Sized> PointerOps for RawPointerOps<NonNull<T>> {
+    type Value = T;
+    type Pointer = NonNull<T>;
+    const OWNS_VALUE: bool = false;
+
+    #[inline]
+    unsafe fn from_raw(&self, raw: *const T) -> NonNull<T> {
+        NonNull::new_unchecked(raw as *mut T)
+    }
+
+    #[inline]
+    fn into_raw(&self, ptr: NonNull<T>) -> *const T {
+        ptr.as_ptr() as *const T
+    }
+}
+
+unsafe impl<T: ?Sized> PointerOps for RawPointerOps<*const T> {
+    type Value = T;
+    type Pointer = *const T;
+    const OWNS_VALUE: bool = false;
+
+    #[inline]
+    unsafe fn from_raw(&self, raw: *const T) -> *const T {
+        raw
+    }
+
+    #[inline]
+    fn into_raw(&self, ptr: *const T) -> *const T {
+        ptr
+    }
+}
 
 #[cfg(feature = "alloc")]
 unsafe impl<T: ?Sized> PointerOps for DefaultPointerOps<Box<T>> {
@@ -226,7 +689,10 @@ unsafe impl<T: ?Sized> PointerOps for DefaultPointerOps<Pin<Box<T>>> {
 }
 
 #[cfg(feature = "alloc")]
-unsafe impl<T: ?Sized> ExclusivePointerOps for DefaultPointerOps<Pin<Box<T>>> {}
+unsafe impl<T: ?Sized + Unpin> ExclusivePointerOps for DefaultPointerOps<Pin<Box<T>>> {}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T: ?Sized> ExclusivePinnedPointerOps for DefaultPointerOps<Pin<Box<T>>> {}
 
 #[cfg(feature = "alloc")]
 unsafe impl<T: ?Sized> PointerOps for DefaultPointerOps<Rc<T>> {
@@ -250,7 +716,7 @@ unsafe impl<T: ?Sized> TryExclusivePointerOps for DefaultPointerOps<Rc<T>> {
     unsafe fn try_get_mut(&self, value: *const Self::Value) -> Option<*mut Self::Value> {
         let mut rc = Rc::from_raw(value);
         let ptr = Rc::get_mut(&mut rc).map(|ptr| ptr as *mut Self::Value);
-        Rc::into_raw(rc);
+        let _ = Rc::into_raw(rc);
         ptr
     }
 }
@@ -272,12 +738,12 @@ unsafe impl<T: ?Sized> PointerOps for DefaultPointerOps<Pin<Rc<T>>> {
 }
 
 #[cfg(feature = "alloc")]
-unsafe impl<T: ?Sized> TryExclusivePointerOps for DefaultPointerOps<Pin<Rc<T>>> {
+unsafe impl<T: ?Sized + Unpin> TryExclusivePointerOps for DefaultPointerOps<Pin<Rc<T>>> {
     #[inline]
     unsafe fn try_get_mut(&self, value: *const Self::Value) -> Option<*mut Self::Value> {
         let mut rc = Rc::from_raw(value);
         let ptr = Rc::get_mut(&mut rc).map(|ptr| ptr as *mut Self::Value);
-        Rc::into_raw(rc);
+        let _ = Rc::into_raw(rc);
         ptr
     }
 }
@@ -326,7 +792,7 @@ unsafe impl<T: ?Sized> PointerOps for DefaultPointerOps<Pin<Arc<T>>> {
 }
 
 #[cfg(feature = "alloc")]
-unsafe impl<T: ?Sized> TryExclusivePointerOps for DefaultPointerOps<Pin<Arc<T>>> {
+unsafe impl<T: ?Sized + Unpin> TryExclusivePointerOps for DefaultPointerOps<Pin<Arc<T>>> {
     #[inline]
     unsafe fn try_get_mut(&self, value: *const Self::Value) -> Option<*mut Self::Value> {
         let mut arc = Arc::from_raw(value);
@@ -336,81 +802,949 @@ unsafe impl<T: ?Sized> TryExclusivePointerOps for DefaultPointerOps<Pin<Arc<T>>>
     }
 }
 
-/// Clones a `PointerOps::Pointer` from a `*const PointerOps::Value`
-///
-/// This method is only safe to call if the raw pointer is known to be
-/// managed by the provided `PointerOps` type.
-#[inline]
-pub(crate) unsafe fn clone_pointer_from_raw<T: PointerOps>(
-    pointer_ops: &T,
-    ptr: *const T::Value,
-) -> T::Pointer
-where
-    T::Pointer: Clone,
-{
-    /// Guard which converts an pointer back into its raw version
-    /// when it gets dropped. This makes sure we also perform a full
-    /// `from_raw` and `into_raw` round trip - even in the case of panics.
-    struct PointerGuard<'a, T: PointerOps> {
-        pointer: ManuallyDrop<T::Pointer>,
-        pointer_ops: &'a T,
+#[cfg(feature = "triomphe")]
+unsafe impl<T: ?Sized> PointerOps for DefaultPointerOps<triomphe::Arc<T>> {
+    type Value = T;
+    type Pointer = triomphe::Arc<T>;
+
+    #[inline]
+    unsafe fn from_raw(&self, raw: *const T) -> triomphe::Arc<T> {
+        triomphe::Arc::from_raw(raw)
     }
 
-    impl<'a, T: PointerOps> Drop for PointerGuard<'a, T> {
-        #[inline]
-        fn drop(&mut self) {
-            // Prevent shared pointers from being released by converting them
-            // back into the raw pointers
-            // SAFETY: `pointer` is never dropped. `ManuallyDrop::take` is not stable until 1.42.0.
-            let _ = self
-                .pointer_ops
-                .into_raw(unsafe { core::ptr::read(&*self.pointer) });
-        }
+    #[inline]
+    fn into_raw(&self, ptr: triomphe::Arc<T>) -> *const T {
+        triomphe::Arc::into_raw(ptr)
     }
+}
 
-    let holder = PointerGuard {
-        pointer: ManuallyDrop::new(pointer_ops.from_raw(ptr)),
-        pointer_ops,
-    };
-    holder.pointer.deref().clone()
+#[cfg(feature = "triomphe")]
+unsafe impl<T: ?Sized> TryExclusivePointerOps for DefaultPointerOps<triomphe::Arc<T>> {
+    #[inline]
+    unsafe fn try_get_mut(&self, value: *const Self::Value) -> Option<*mut Self::Value> {
+        let mut arc = triomphe::Arc::from_raw(value);
+        let ptr = triomphe::Arc::get_mut(&mut arc).map(|ptr| ptr as *mut Self::Value);
+        let _ = triomphe::Arc::into_raw(arc);
+        ptr
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::{DefaultPointerOps, PointerOps};
-    use std::boxed::Box;
-    use std::fmt::Debug;
-    use std::mem;
-    use std::pin::Pin;
-    use std::rc::Rc;
-    use std::sync::Arc;
+#[cfg(feature = "allocator-api2")]
+unsafe impl<T: ?Sized> PointerOps for DefaultPointerOps<allocator_api2::boxed::Box<T>> {
+    type Value = T;
+    type Pointer = allocator_api2::boxed::Box<T>;
 
-    #[test]
-    fn test_box() {
-        unsafe {
-            let pointer_ops = DefaultPointerOps::<Box<_>>::new();
-            let p = Box::new(1);
-            let a: *const i32 = &*p;
-            let r = pointer_ops.into_raw(p);
-            assert_eq!(a, r);
-            let p2: Box<i32> = pointer_ops.from_raw(r);
-            let a2: *const i32 = &*p2;
-            assert_eq!(a, a2);
-        }
+    #[inline]
+    unsafe fn from_raw(&self, raw: *const T) -> allocator_api2::boxed::Box<T> {
+        allocator_api2::boxed::Box::from_raw(raw as *mut T)
     }
 
-    #[test]
-    fn test_rc() {
-        unsafe {
-            let pointer_ops = DefaultPointerOps::<Rc<_>>::new();
-            let p = Rc::new(1);
-            let a: *const i32 = &*p;
-            let r = pointer_ops.into_raw(p);
-            assert_eq!(a, r);
-            let p2: Rc<i32> = pointer_ops.from_raw(r);
-            let a2: *const i32 = &*p2;
-            assert_eq!(a, a2);
-        }
+    #[inline]
+    fn into_raw(&self, ptr: allocator_api2::boxed::Box<T>) -> *const T {
+        allocator_api2::boxed::Box::into_raw(ptr) as *const T
+    }
+}
+
+#[cfg(feature = "allocator-api2")]
+unsafe impl<T: ?Sized> ExclusivePointerOps for DefaultPointerOps<allocator_api2::boxed::Box<T>> {}
+
+/// The `PointerOps` type for `Box<T, A>` with a custom allocator.
+///
+/// A `Box` only records its allocator in the `Box` itself, not in the object
+/// it points to, so the allocator is lost when the `Box` is converted to a
+/// raw pointer. `BoxPointerOps` keeps its own handle to the allocator and
+/// gives a clone of it to every `Box` it reconstructs in `from_raw`. Handles
+/// such as `&Bump` or an `Rc` of a pool are cheap to clone, and all the
+/// clones refer to the same allocator.
+///
+/// Since `intrusive_adapter!` always uses `DefaultPointerOps`, an adapter
+/// using `BoxPointerOps` has to implement `Adapter` manually.
+///
+/// # Examples
+///
+/// ```
+/// use allocator_api2::alloc::Global;
+/// use allocator_api2::boxed::Box;
+/// use core::ptr::NonNull;
+/// use intrusive_collections::linked_list::{Link, LinkOps};
+/// use intrusive_collections::{container_of, Adapter, BoxPointerOps, LinkedList};
+///
+/// struct Value {
+///     link: Link,
+///     value: u32,
+/// }
+///
+/// struct ValueAdapter {
+///     link_ops: LinkOps,
+///     pointer_ops: BoxPointerOps<Value, Global>,
+/// }
+///
+/// unsafe impl Adapter for ValueAdapter {
+///     type LinkOps = LinkOps;
+///     type PointerOps = BoxPointerOps<Value, Global>;
+///
+///     unsafe fn get_value(&self, link: NonNull<Link>) -> *const Value {
+///         container_of!(link.as_ptr(), Value, link)
+///     }
+///     unsafe fn get_link(&self, value: *const Value) -> NonNull<Link> {
+///         NonNull::new_unchecked(core::ptr::addr_of!((*value).link) as *mut Link)
+///     }
+///     fn link_ops(&self) -> &LinkOps {
+///         &self.link_ops
+///     }
+///     fn link_ops_mut(&mut self) -> &mut LinkOps {
+///         &mut self.link_ops
+///     }
+///     fn pointer_ops(&self) -> &BoxPointerOps<Value, Global> {
+///         &self.pointer_ops
+///     }
+/// }
+///
+/// let adapter = ValueAdapter {
+///     link_ops: LinkOps,
+///     pointer_ops: unsafe { BoxPointerOps::new_in(Global) },
+/// };
+/// let mut list = LinkedList::new(adapter);
+/// list.push_back(Box::new_in(Value { link: Link::new(), value: 1 }, Global));
+/// assert_eq!(list.pop_front().unwrap().value, 1);
+/// ```
+#[cfg(feature = "allocator-api2")]
+pub struct BoxPointerOps<T: ?Sized, A: allocator_api2::alloc::Allocator> {
+    alloc: A,
+    marker: PhantomData<allocator_api2::boxed::Box<T, A>>,
+}
+
+#[cfg(feature = "allocator-api2")]
+impl<T: ?Sized, A: allocator_api2::alloc::Allocator> BoxPointerOps<T, A> {
+    /// Constructs an instance of `BoxPointerOps` which reconstructs boxes
+    /// using `alloc`.
+    ///
+    /// # Safety
+    /// Every `Box` passed to `into_raw` must have been allocated by `alloc` or
+    /// by an allocator which `alloc` is a clone of.
+    #[inline]
+    pub const unsafe fn new_in(alloc: A) -> BoxPointerOps<T, A> {
+        BoxPointerOps {
+            alloc,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the allocator.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+}
+
+#[cfg(feature = "allocator-api2")]
+impl<T: ?Sized, A: allocator_api2::alloc::Allocator + Clone> Clone for BoxPointerOps<T, A> {
+    #[inline]
+    fn clone(&self) -> Self {
+        BoxPointerOps {
+            alloc: self.alloc.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "allocator-api2")]
+unsafe impl<T: ?Sized, A: allocator_api2::alloc::Allocator + Clone> PointerOps
+    for BoxPointerOps<T, A>
+{
+    type Value = T;
+    type Pointer = allocator_api2::boxed::Box<T, A>;
+
+    #[inline]
+    unsafe fn from_raw(&self, raw: *const T) -> allocator_api2::boxed::Box<T, A> {
+        allocator_api2::boxed::Box::from_raw_in(raw as *mut T, self.alloc.clone())
+    }
+
+    #[inline]
+    fn into_raw(&self, ptr: allocator_api2::boxed::Box<T, A>) -> *const T {
+        allocator_api2::boxed::Box::into_raw_with_allocator(ptr).0 as *const T
+    }
+}
+
+#[cfg(feature = "allocator-api2")]
+unsafe impl<T: ?Sized, A: allocator_api2::alloc::Allocator + Clone> ExclusivePointerOps
+    for BoxPointerOps<T, A>
+{
+}
+
+#[cfg(feature = "bumpalo")]
+unsafe impl<'a, T: ?Sized> PointerOps for DefaultPointerOps<bumpalo::boxed::Box<'a, T>> {
+    type Value = T;
+    type Pointer = bumpalo::boxed::Box<'a, T>;
+
+    #[inline]
+    unsafe fn from_raw(&self, raw: *const T) -> bumpalo::boxed::Box<'a, T> {
+        bumpalo::boxed::Box::from_raw(raw as *mut T)
+    }
+
+    #[inline]
+    fn into_raw(&self, ptr: bumpalo::boxed::Box<'a, T>) -> *const T {
+        bumpalo::boxed::Box::into_raw(ptr) as *const T
+    }
+}
+
+#[cfg(feature = "bumpalo")]
+unsafe impl<T: ?Sized> ExclusivePointerOps for DefaultPointerOps<bumpalo::boxed::Box<'_, T>> {}
+
+/// Pointer operations for references to objects allocated from a
+/// `bumpalo::Bump`, which keep a handle to the arena.
+///
+/// The pointers are `&'a T` or `&'a mut T`, borrowed from the arena for as
+/// long as the handle. Shared references can be cloned with `clone_pointer`,
+/// so an object can be in several collections at once, while mutable
+/// references give the collection exclusive access to its objects through
+/// `ExclusivePointerOps`. Neither drops the objects, which are freed with the
+/// arena.
+///
+/// In debug builds, `into_raw` panics if the object wasn't allocated from
+/// the arena of the handle, which catches objects inserted into a
+/// collection of the wrong arena.
+///
+/// Since `intrusive_adapter!` always uses `DefaultPointerOps`, an adapter
+/// using `BumpPointerOps` has to implement `Adapter` manually.
+///
+/// # Examples
+///
+/// ```
+/// use bumpalo::Bump;
+/// use core::ptr::NonNull;
+/// use intrusive_collections::linked_list::{Link, LinkOps};
+/// use intrusive_collections::{container_of, Adapter, BumpPointerOps, LinkedList};
+///
+/// struct Value {
+///     link: Link,
+///     value: u32,
+/// }
+///
+/// struct ValueAdapter<'a> {
+///     link_ops: LinkOps,
+///     pointer_ops: BumpPointerOps<'a, &'a Value>,
+/// }
+///
+/// unsafe impl<'a> Adapter for ValueAdapter<'a> {
+///     type LinkOps = LinkOps;
+///     type PointerOps = BumpPointerOps<'a, &'a Value>;
+///
+///     unsafe fn get_value(&self, link: NonNull<Link>) -> *const Value {
+///         container_of!(link.as_ptr(), Value, link)
+///     }
+///     unsafe fn get_link(&self, value: *const Value) -> NonNull<Link> {
+///         NonNull::new_unchecked(core::ptr::addr_of!((*value).link) as *mut Link)
+///     }
+///     fn link_ops(&self) -> &LinkOps {
+///         &self.link_ops
+///     }
+///     fn link_ops_mut(&mut self) -> &mut LinkOps {
+///         &mut self.link_ops
+///     }
+///     fn pointer_ops(&self) -> &BumpPointerOps<'a, &'a Value> {
+///         &self.pointer_ops
+///     }
+/// }
+///
+/// let bump = Bump::new();
+/// let mut list = LinkedList::new(ValueAdapter {
+///     link_ops: LinkOps,
+///     pointer_ops: BumpPointerOps::new(&bump),
+/// });
+/// for value in 1..4 {
+///     list.push_back(bump.alloc(Value { link: Link::new(), value }));
+/// }
+/// let first = list.front().clone_pointer().unwrap();
+/// assert_eq!(first.value, 1);
+/// assert_eq!(list.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 2, 3]);
+/// # list.fast_clear();
+/// ```
+#[cfg(feature = "bumpalo")]
+pub struct BumpPointerOps<'a, Pointer> {
+    bump: &'a bumpalo::Bump,
+    marker: PhantomData<Pointer>,
+}
+
+#[cfg(feature = "bumpalo")]
+impl<'a, Pointer> BumpPointerOps<'a, Pointer> {
+    /// Constructs an instance of `BumpPointerOps` for objects allocated from
+    /// `bump`.
+    #[inline]
+    pub const fn new(bump: &'a bumpalo::Bump) -> BumpPointerOps<'a, Pointer> {
+        BumpPointerOps {
+            bump,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the arena.
+    #[inline]
+    pub fn bump(&self) -> &'a bumpalo::Bump {
+        self.bump
+    }
+
+    // Panics in debug builds if `value` wasn't allocated from the arena.
+    #[inline]
+    fn check_allocated<T: ?Sized>(&self, value: &T) {
+        if cfg!(debug_assertions) && core::mem::size_of_val(value) != 0 {
+            let addr = value as *const T as *const u8 as usize;
+            // Nothing is allocated from the arena while the chunks are
+            // iterated, and their contents aren't read.
+            let allocated = unsafe { self.bump.iter_allocated_chunks_raw() }
+                .any(|(start, len)| (start as usize..start as usize + len).contains(&addr));
+            assert!(
+                allocated,
+                "attempted to insert an object which wasn't allocated from the arena of the adapter"
+            );
+        }
+    }
+}
+
+#[cfg(feature = "bumpalo")]
+impl<Pointer> Clone for BumpPointerOps<'_, Pointer> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[cfg(feature = "bumpalo")]
+impl<Pointer> Copy for BumpPointerOps<'_, Pointer> {}
+
+#[cfg(feature = "bumpalo")]
+unsafe impl<'a, T: ?Sized> PointerOps for BumpPointerOps<'a, &'a T> {
+    type Value = T;
+    type Pointer = &'a T;
+    const OWNS_VALUE: bool = false;
+
+    #[inline]
+    unsafe fn from_raw(&self, raw: *const T) -> &'a T {
+        &*raw
+    }
+
+    #[inline]
+    fn into_raw(&self, ptr: &'a T) -> *const T {
+        self.check_allocated(ptr);
+        ptr
+    }
+
+    #[inline]
+    fn same_state(&self, other: &Self) -> bool {
+        core::ptr::eq(self.bump, other.bump)
+    }
+}
+
+#[cfg(feature = "bumpalo")]
+unsafe impl<'a, T: ?Sized> PointerOps for BumpPointerOps<'a, &'a mut T> {
+    type Value = T;
+    type Pointer = &'a mut T;
+    const OWNS_VALUE: bool = false;
+
+    #[inline]
+    unsafe fn from_raw(&self, raw: *const T) -> &'a mut T {
+        &mut *(raw as *mut T)
+    }
+
+    #[inline]
+    fn into_raw(&self, ptr: &'a mut T) -> *const T {
+        self.check_allocated(ptr);
+        ptr
+    }
+
+    #[inline]
+    fn same_state(&self, other: &Self) -> bool {
+        core::ptr::eq(self.bump, other.bump)
+    }
+}
+
+#[cfg(feature = "bumpalo")]
+unsafe impl<'a, T: ?Sized> ExclusivePointerOps for BumpPointerOps<'a, &'a mut T> {}
+
+#[cfg(feature = "alloc")]
+unsafe impl<'a, T> PointerOps for DefaultPointerOps<ArenaBox<'a, T>> {
+    type Value = T;
+    type Pointer = ArenaBox<'a, T>;
+
+    #[inline]
+    unsafe fn from_raw(&self, raw: *const T) -> ArenaBox<'a, T> {
+        ArenaBox::from_raw(raw as *mut T)
+    }
+
+    #[inline]
+    fn into_raw(&self, ptr: ArenaBox<'a, T>) -> *const T {
+        ArenaBox::into_raw(ptr) as *const T
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T> ExclusivePointerOps for DefaultPointerOps<ArenaBox<'_, T>> {}
+
+#[cfg(feature = "static_pool")]
+unsafe impl<P: StaticPool> PointerOps for DefaultPointerOps<PoolBox<P>> {
+    type Value = P::Value;
+    type Pointer = PoolBox<P>;
+
+    #[inline]
+    unsafe fn from_raw(&self, raw: *const P::Value) -> PoolBox<P> {
+        PoolBox::from_raw(raw as *mut P::Value)
+    }
+
+    #[inline]
+    fn into_raw(&self, ptr: PoolBox<P>) -> *const P::Value {
+        PoolBox::into_raw(ptr) as *const P::Value
+    }
+}
+
+#[cfg(feature = "static_pool")]
+unsafe impl<P: StaticPool> ExclusivePointerOps for DefaultPointerOps<PoolBox<P>> {}
+
+#[cfg(feature = "erasable")]
+unsafe impl<P: erasable::ErasablePtr + Deref> PointerOps for DefaultPointerOps<erasable::Thin<P>>
+where
+    P::Target: erasable::Erasable,
+{
+    type Value = P::Target;
+    type Pointer = erasable::Thin<P>;
+
+    #[inline]
+    unsafe fn from_raw(&self, raw: *const P::Target) -> erasable::Thin<P> {
+        let erased = erasable::erase(NonNull::new_unchecked(raw as *mut P::Target));
+        erasable::Thin::from(P::unerase(erased))
+    }
+
+    #[inline]
+    fn into_raw(&self, ptr: erasable::Thin<P>) -> *const P::Target {
+        let erased = P::erase(erasable::Thin::into_inner(ptr));
+        unsafe { <P::Target as erasable::Erasable>::unerase(erased).as_ptr() }
+    }
+}
+
+#[cfg(all(feature = "erasable", feature = "alloc"))]
+unsafe impl<T: ?Sized + erasable::Erasable> ExclusivePointerOps
+    for DefaultPointerOps<erasable::Thin<Box<T>>>
+{
+}
+
+/// Smart pointers which can be converted to and from a raw pointer to their
+/// target.
+///
+/// This is the only thing `StablePointerOps` needs to know about a pointer
+/// type beyond `StableDeref`, so a smart pointer from another crate can be
+/// used in a collection by implementing this trait, instead of implementing
+/// `PointerOps` for its own `PointerOps` type.
+///
+/// # Safety
+///
+/// `into_raw` must return the address that the pointer dereferences to, and
+/// `into_raw` and `from_raw` must be inverses of each other.
+#[cfg(feature = "stable_deref_trait")]
+pub unsafe trait IntoRawPointer: stable_deref_trait::StableDeref {
+    /// Consumes the pointer and returns a raw pointer to its target.
+    fn into_raw(this: Self) -> *const Self::Target;
+
+    /// Constructs the pointer from a raw pointer.
+    ///
+    /// # Safety
+    /// The raw pointer must have been previously returned by `into_raw`.
+    unsafe fn from_raw(ptr: *const Self::Target) -> Self;
+}
+
+#[cfg(feature = "stable_deref_trait")]
+unsafe impl<T: ?Sized> IntoRawPointer for &T {
+    #[inline]
+    fn into_raw(this: Self) -> *const T {
+        this
+    }
+
+    #[inline]
+    unsafe fn from_raw(ptr: *const T) -> Self {
+        &*ptr
+    }
+}
+
+#[cfg(feature = "stable_deref_trait")]
+unsafe impl<T: ?Sized> IntoRawPointer for &mut T {
+    #[inline]
+    fn into_raw(this: Self) -> *const T {
+        this as *mut T as *const T
+    }
+
+    #[inline]
+    unsafe fn from_raw(ptr: *const T) -> Self {
+        &mut *(ptr as *mut T)
+    }
+}
+
+#[cfg(all(feature = "stable_deref_trait", feature = "alloc"))]
+unsafe impl<T: ?Sized> IntoRawPointer for Box<T> {
+    #[inline]
+    fn into_raw(this: Self) -> *const T {
+        Box::into_raw(this) as *const T
+    }
+
+    #[inline]
+    unsafe fn from_raw(ptr: *const T) -> Self {
+        Box::from_raw(ptr as *mut T)
+    }
+}
+
+#[cfg(all(feature = "stable_deref_trait", feature = "alloc"))]
+unsafe impl<T: ?Sized> IntoRawPointer for Rc<T> {
+    #[inline]
+    fn into_raw(this: Self) -> *const T {
+        Rc::into_raw(this)
+    }
+
+    #[inline]
+    unsafe fn from_raw(ptr: *const T) -> Self {
+        Rc::from_raw(ptr)
+    }
+}
+
+#[cfg(all(feature = "stable_deref_trait", feature = "alloc"))]
+unsafe impl<T: ?Sized> IntoRawPointer for Arc<T> {
+    #[inline]
+    fn into_raw(this: Self) -> *const T {
+        Arc::into_raw(this)
+    }
+
+    #[inline]
+    unsafe fn from_raw(ptr: *const T) -> Self {
+        Arc::from_raw(ptr)
+    }
+}
+
+/// The `PointerOps` type for any pointer implementing `StableDeref` and
+/// `IntoRawPointer`.
+///
+/// Since `intrusive_adapter!` always uses `DefaultPointerOps`, an adapter
+/// using `StablePointerOps` has to implement `Adapter` manually.
+///
+/// # Examples
+///
+/// ```
+/// use core::ops::Deref;
+/// use intrusive_collections::{IntoRawPointer, PointerOps, StablePointerOps};
+/// use stable_deref_trait::StableDeref;
+///
+/// pub struct MyBox<T>(Box<T>);
+///
+/// impl<T> Deref for MyBox<T> {
+///     type Target = T;
+///     fn deref(&self) -> &T {
+///         &self.0
+///     }
+/// }
+///
+/// unsafe impl<T> StableDeref for MyBox<T> {}
+///
+/// unsafe impl<T> IntoRawPointer for MyBox<T> {
+///     fn into_raw(this: Self) -> *const T {
+///         Box::into_raw(this.0)
+///     }
+///     unsafe fn from_raw(ptr: *const T) -> Self {
+///         MyBox(Box::from_raw(ptr as *mut T))
+///     }
+/// }
+///
+/// let pointer_ops = StablePointerOps::<MyBox<u32>>::new();
+/// let raw = pointer_ops.into_raw(MyBox(Box::new(5)));
+/// assert_eq!(*unsafe { pointer_ops.from_raw(raw) }, 5);
+/// ```
+#[cfg(feature = "stable_deref_trait")]
+pub struct StablePointerOps<Pointer>(PhantomData<Pointer>);
+
+#[cfg(feature = "stable_deref_trait")]
+impl<Pointer> StablePointerOps<Pointer> {
+    /// Constructs an instance of `StablePointerOps`.
+    #[inline]
+    pub const fn new() -> StablePointerOps<Pointer> {
+        StablePointerOps(PhantomData)
+    }
+}
+
+#[cfg(feature = "stable_deref_trait")]
+impl<Pointer> Clone for StablePointerOps<Pointer> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[cfg(feature = "stable_deref_trait")]
+impl<Pointer> Copy for StablePointerOps<Pointer> {}
+
+#[cfg(feature = "stable_deref_trait")]
+impl<Pointer> Default for StablePointerOps<Pointer> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "stable_deref_trait")]
+unsafe impl<P: IntoRawPointer> PointerOps for StablePointerOps<P> {
+    type Value = P::Target;
+    type Pointer = P;
+
+    #[inline]
+    unsafe fn from_raw(&self, raw: *const P::Target) -> P {
+        P::from_raw(raw)
+    }
+
+    #[inline]
+    fn into_raw(&self, ptr: P) -> *const P::Target {
+        P::into_raw(ptr)
+    }
+}
+
+/// `PointerOps` which owns a containing object through another `PointerOps`,
+/// while exposing one of its fields as the `Value` of the collection.
+///
+/// For example, with `Arc<Outer>` as the pointer type, the collection can
+/// manage the `Inner` component of `Outer` where the link lives. Elements
+/// are inserted and removed as `Arc<Outer>`, so the whole object is kept
+/// alive, but cursors and `get_value` only see the `Inner` field. The offset
+/// of the field is stored in the `ProjectedPointerOps`.
+///
+/// Since `intrusive_adapter!` always uses `DefaultPointerOps`, an adapter
+/// using `ProjectedPointerOps` has to implement `Adapter` manually.
+///
+/// # Examples
+///
+/// ```
+/// use core::ptr::NonNull;
+/// use intrusive_collections::linked_list::{Link, LinkOps};
+/// use intrusive_collections::{
+///     container_of, offset_of, Adapter, DefaultPointerOps, LinkedList, ProjectedPointerOps,
+/// };
+/// use std::sync::Arc;
+///
+/// struct Node {
+///     link: Link,
+///     id: u32,
+/// }
+///
+/// struct Task {
+///     name: &'static str,
+///     node: Node,
+/// }
+///
+/// type TaskPointerOps = ProjectedPointerOps<DefaultPointerOps<Arc<Task>>, Node>;
+///
+/// struct NodeAdapter {
+///     link_ops: LinkOps,
+///     pointer_ops: TaskPointerOps,
+/// }
+///
+/// unsafe impl Adapter for NodeAdapter {
+///     type LinkOps = LinkOps;
+///     type PointerOps = TaskPointerOps;
+///
+///     unsafe fn get_value(&self, link: NonNull<Link>) -> *const Node {
+///         container_of!(link.as_ptr(), Node, link)
+///     }
+///     unsafe fn get_link(&self, value: *const Node) -> NonNull<Link> {
+///         NonNull::new_unchecked(core::ptr::addr_of!((*value).link) as *mut Link)
+///     }
+///     fn link_ops(&self) -> &LinkOps {
+///         &self.link_ops
+///     }
+///     fn link_ops_mut(&mut self) -> &mut LinkOps {
+///         &mut self.link_ops
+///     }
+///     fn pointer_ops(&self) -> &TaskPointerOps {
+///         &self.pointer_ops
+///     }
+/// }
+///
+/// let adapter = NodeAdapter {
+///     link_ops: LinkOps,
+///     pointer_ops: unsafe {
+///         ProjectedPointerOps::new(DefaultPointerOps::new(), offset_of!(Task, node))
+///     },
+/// };
+/// let mut list = LinkedList::new(adapter);
+/// list.push_back(Arc::new(Task {
+///     name: "task",
+///     node: Node { link: Link::new(), id: 1 },
+/// }));
+/// assert_eq!(list.front().get().unwrap().id, 1);
+/// let task: Arc<Task> = list.pop_front().unwrap();
+/// assert_eq!(task.name, "task");
+/// ```
+pub struct ProjectedPointerOps<P, T> {
+    pointer_ops: P,
+    offset: usize,
+    marker: PhantomData<fn() -> *const T>,
+}
+
+impl<P, T> ProjectedPointerOps<P, T> {
+    /// Constructs an instance of `ProjectedPointerOps` exposing the field
+    /// located `offset` bytes into the objects owned by `pointer_ops`.
+    ///
+    /// # Safety
+    ///
+    /// `offset` must be the offset of a field of type `T` in `P::Value`, as
+    /// returned by `offset_of!`.
+    #[inline]
+    pub const unsafe fn new(pointer_ops: P, offset: usize) -> ProjectedPointerOps<P, T> {
+        ProjectedPointerOps {
+            pointer_ops,
+            offset,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the `PointerOps` owning the containing objects.
+    #[inline]
+    pub fn inner(&self) -> &P {
+        &self.pointer_ops
+    }
+
+    /// Returns the offset of the exposed field.
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl<P: Clone, T> Clone for ProjectedPointerOps<P, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        ProjectedPointerOps {
+            pointer_ops: self.pointer_ops.clone(),
+            offset: self.offset,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<P: Copy, T> Copy for ProjectedPointerOps<P, T> {}
+
+unsafe impl<P: PointerOps, T> PointerOps for ProjectedPointerOps<P, T>
+where
+    P::Value: Sized,
+{
+    type Value = T;
+    type Pointer = P::Pointer;
+    const OWNS_VALUE: bool = P::OWNS_VALUE;
+
+    #[inline]
+    unsafe fn from_raw(&self, value: *const T) -> P::Pointer {
+        self.pointer_ops
+            .from_raw(crate::container_of_ptr(value, self.offset))
+    }
+
+    #[inline]
+    fn into_raw(&self, ptr: P::Pointer) -> *const T {
+        unsafe { crate::field_ptr(self.pointer_ops.into_raw(ptr), self.offset) }
+    }
+
+    #[inline]
+    unsafe fn on_link_acquired(&self, value: *const T) {
+        self.pointer_ops
+            .on_link_acquired(crate::container_of_ptr(value, self.offset));
+    }
+
+    #[inline]
+    fn same_state(&self, other: &Self) -> bool {
+        self.offset == other.offset && self.pointer_ops.same_state(&other.pointer_ops)
+    }
+}
+
+unsafe impl<P: TryExclusivePointerOps, T> TryExclusivePointerOps for ProjectedPointerOps<P, T>
+where
+    P::Value: Sized,
+{
+    #[inline]
+    unsafe fn try_get_mut(&self, value: *const T) -> Option<*mut T> {
+        let container = self
+            .pointer_ops
+            .try_get_mut(crate::container_of_ptr(value, self.offset))?;
+        Some(crate::field_ptr::<P::Value, T>(container, self.offset) as *mut T)
+    }
+}
+
+/// Clones a `PointerOps::Pointer` from a `*const PointerOps::Value`
+///
+/// This is what the `clone_pointer` methods of cursors use, and is useful
+/// for collections implemented outside this crate. The raw pointer is
+/// converted back with `from_raw` and `into_raw`, so ownership stays with
+/// the raw pointer even if cloning panics.
+///
+/// # Safety
+///
+/// This method is only safe to call if the raw pointer is known to be
+/// managed by the provided `PointerOps` type.
+#[inline]
+pub unsafe fn clone_pointer_from_raw<T: PointerOps>(
+    pointer_ops: &T,
+    ptr: *const T::Value,
+) -> T::Pointer
+where
+    T::Pointer: Clone,
+{
+    /// Guard which converts an pointer back into its raw version
+    /// when it gets dropped. This makes sure we also perform a full
+    /// `from_raw` and `into_raw` round trip - even in the case of panics.
+    struct PointerGuard<'a, T: PointerOps> {
+        pointer: ManuallyDrop<T::Pointer>,
+        pointer_ops: &'a T,
+    }
+
+    impl<'a, T: PointerOps> Drop for PointerGuard<'a, T> {
+        #[inline]
+        fn drop(&mut self) {
+            // Prevent shared pointers from being released by converting them
+            // back into the raw pointers
+            // SAFETY: `pointer` is never dropped. `ManuallyDrop::take` is not stable until 1.42.0.
+            let _ = self
+                .pointer_ops
+                .into_raw(unsafe { core::ptr::read(&*self.pointer) });
+        }
+    }
+
+    let holder = PointerGuard {
+        pointer: ManuallyDrop::new(pointer_ops.from_raw(ptr)),
+        pointer_ops,
+    };
+    holder.pointer.deref().clone()
+}
+
+/// Returns the mask of the low bits which are always zero in a raw pointer to
+/// `T`, and so can be used by [into_tagged_raw] to store a tag.
+#[inline]
+pub const fn tag_mask<T>() -> usize {
+    core::mem::align_of::<T>() - 1
+}
+
+/// Consumes the owned pointer and returns a raw pointer to the owned object
+/// with `tag` stored in its low bits.
+///
+/// This is for lock-free algorithms which need to mark raw pointers. The
+/// tagged pointer must not be inserted into a collection or passed to
+/// `from_raw` directly, use [from_tagged_raw] to get the original raw pointer
+/// and tag back.
+///
+/// # Panics
+///
+/// Panics if `tag` doesn't fit in [tag_mask] for the value type.
+#[inline]
+pub fn into_tagged_raw<T: PointerOps>(
+    pointer_ops: &T,
+    ptr: T::Pointer,
+    tag: usize,
+) -> *const T::Value
+where
+    T::Value: Sized,
+{
+    assert!(
+        tag & !tag_mask::<T::Value>() == 0,
+        "tag does not fit in the alignment of the value"
+    );
+    let raw = pointer_ops.into_raw(ptr);
+    (raw as *const u8).wrapping_add(tag) as *const T::Value
+}
+
+/// Constructs an owned pointer from a raw pointer returned by
+/// [into_tagged_raw], and returns it with the tag that was stored in it.
+///
+/// # Safety
+///
+/// The raw pointer must have been previously returned by `into_tagged_raw`
+/// for the same `PointerOps`, and must not have been converted back since.
+#[inline]
+pub unsafe fn from_tagged_raw<T: PointerOps>(
+    pointer_ops: &T,
+    ptr: *const T::Value,
+) -> (T::Pointer, usize)
+where
+    T::Value: Sized,
+{
+    let tag = ptr as usize & tag_mask::<T::Value>();
+    let raw = (ptr as *const u8).wrapping_sub(tag) as *const T::Value;
+    (pointer_ops.from_raw(raw), tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DefaultPointerOps, PointerOps};
+    use std::boxed::Box;
+    use std::fmt::Debug;
+    use std::mem;
+    use std::pin::Pin;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_tagged() {
+        use super::{from_tagged_raw, into_tagged_raw, tag_mask};
+
+        assert_eq!(tag_mask::<u8>(), 0);
+        assert_eq!(tag_mask::<u64>(), mem::align_of::<u64>() - 1);
+        unsafe {
+            let pointer_ops = DefaultPointerOps::<Box<u32>>::new();
+            let p = Box::new(1);
+            let a: *const u32 = &*p;
+            let r = into_tagged_raw(&pointer_ops, p, 3);
+            assert_eq!(r as usize, a as usize | 3);
+            let (p, tag) = from_tagged_raw(&pointer_ops, r);
+            assert_eq!(tag, 3);
+            assert_eq!(&*p as *const u32, a);
+            assert_eq!(*p, 1);
+
+            let pointer_ops = DefaultPointerOps::<Rc<u32>>::new();
+            let p = Rc::new(2);
+            let r = into_tagged_raw(&pointer_ops, p.clone(), 0);
+            assert_eq!(r, &*p as *const u32);
+            let (p2, tag) = from_tagged_raw(&pointer_ops, r);
+            assert_eq!(tag, 0);
+            assert!(Rc::ptr_eq(&p, &p2));
+
+            let pointer_ops = DefaultPointerOps::<Arc<u32>>::new();
+            let r = into_tagged_raw(&pointer_ops, Arc::new(3), 1);
+            let (p, tag) = from_tagged_raw(&pointer_ops, r);
+            assert_eq!(tag, 1);
+            assert_eq!(Arc::strong_count(&p), 1);
+            assert_eq!(*p, 3);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "tag does not fit")]
+    fn test_tagged_overflow() {
+        let pointer_ops = DefaultPointerOps::<Box<u32>>::new();
+        super::into_tagged_raw(&pointer_ops, Box::new(1), 4);
+    }
+
+    #[test]
+    fn test_box() {
+        unsafe {
+            let pointer_ops = DefaultPointerOps::<Box<_>>::new();
+            let p = Box::new(1);
+            let a: *const i32 = &*p;
+            let r = pointer_ops.into_raw(p);
+            assert_eq!(a, r);
+            let p2: Box<i32> = pointer_ops.from_raw(r);
+            let a2: *const i32 = &*p2;
+            assert_eq!(a, a2);
+        }
+    }
+
+    #[test]
+    fn test_rc() {
+        unsafe {
+            let pointer_ops = DefaultPointerOps::<Rc<_>>::new();
+            let p = Rc::new(1);
+            let a: *const i32 = &*p;
+            let r = pointer_ops.into_raw(p);
+            assert_eq!(a, r);
+            let p2: Rc<i32> = pointer_ops.from_raw(r);
+            let a2: *const i32 = &*p2;
+            assert_eq!(a, a2);
+        }
     }
 
     #[test]
@@ -427,20 +1761,312 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "triomphe")]
+    #[test]
+    fn test_triomphe_arc() {
+        use super::TryExclusivePointerOps;
+        unsafe {
+            let pointer_ops = DefaultPointerOps::<triomphe::Arc<_>>::new();
+            let p = triomphe::Arc::new(1);
+            let a: *const i32 = &*p;
+            let r = pointer_ops.into_raw(p);
+            assert_eq!(a, r);
+            assert_eq!(pointer_ops.try_get_mut(r), Some(a as *mut i32));
+            let p2: triomphe::Arc<i32> = pointer_ops.from_raw(r);
+            let p3 = p2.clone();
+            let r = pointer_ops.into_raw(p2);
+            assert_eq!(pointer_ops.try_get_mut(r), None);
+            drop(p3);
+            let p2: triomphe::Arc<i32> = pointer_ops.from_raw(r);
+            let a2: *const i32 = &*p2;
+            assert_eq!(a, a2);
+        }
+    }
+
+    #[cfg(feature = "allocator-api2")]
+    #[test]
+    fn test_box_in() {
+        use super::BoxPointerOps;
+        use allocator_api2::alloc::{AllocError, Allocator, Global, Layout};
+        use core::cell::Cell;
+        use core::ptr::NonNull;
+
+        #[derive(Default)]
+        struct CountingAlloc {
+            live: Cell<usize>,
+        }
+        unsafe impl Allocator for CountingAlloc {
+            fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                self.live.set(self.live.get() + 1);
+                Global.allocate(layout)
+            }
+            unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+                self.live.set(self.live.get() - 1);
+                Global.deallocate(ptr, layout)
+            }
+        }
+
+        let alloc = CountingAlloc::default();
+        unsafe {
+            let pointer_ops = BoxPointerOps::<_, &CountingAlloc>::new_in(&alloc);
+            let p = allocator_api2::boxed::Box::new_in(1, &alloc);
+            let a: *const i32 = &*p;
+            let r = pointer_ops.into_raw(p);
+            assert_eq!(a, r);
+            assert_eq!(alloc.live.get(), 1);
+            let p2: allocator_api2::boxed::Box<i32, &CountingAlloc> = pointer_ops.from_raw(r);
+            let a2: *const i32 = &*p2;
+            assert_eq!(a, a2);
+            drop(p2);
+            assert_eq!(alloc.live.get(), 0);
+        }
+    }
+
+    #[test]
+    fn test_mut_ref() {
+        use super::ExclusivePointerOps;
+        unsafe {
+            let pointer_ops = DefaultPointerOps::<&mut _>::new();
+            let mut x = 1;
+            let a: *const i32 = &x;
+            let r = pointer_ops.into_raw(&mut x);
+            assert_eq!(a, r);
+            *pointer_ops.get_mut(r) = 2;
+            let p2: &mut i32 = pointer_ops.from_raw(r);
+            let a2: *const i32 = &*p2;
+            assert_eq!(a, a2);
+            assert_eq!(*p2, 2);
+        }
+    }
+
+    #[test]
+    fn test_pin_mut_ref() {
+        unsafe {
+            let pointer_ops = DefaultPointerOps::<Pin<&mut _>>::new();
+            let mut x = 1;
+            let a: *const i32 = &x;
+            let r = pointer_ops.into_raw(Pin::new(&mut x));
+            assert_eq!(a, r);
+            let p2: Pin<&mut i32> = pointer_ops.from_raw(r);
+            let a2: *const i32 = &*p2;
+            assert_eq!(a, a2);
+        }
+    }
+
+    #[test]
+    fn test_raw() {
+        use super::RawPointerOps;
+        use core::ptr::NonNull;
+        unsafe {
+            let pointer_ops = RawPointerOps::<NonNull<_>>::new();
+            let x = 1;
+            let a: *const i32 = &x;
+            let r = pointer_ops.into_raw(NonNull::from(&x));
+            assert_eq!(a, r);
+            let p2: NonNull<i32> = pointer_ops.from_raw(r);
+            assert_eq!(a, p2.as_ptr() as *const i32);
+
+            let pointer_ops = RawPointerOps::<*const _>::new();
+            let r = pointer_ops.into_raw(a);
+            assert_eq!(a, r);
+            let p2: *const i32 = pointer_ops.from_raw(r);
+            assert_eq!(a, p2);
+        }
+    }
+
+    #[cfg(feature = "stable_deref_trait")]
+    #[test]
+    fn test_stable() {
+        use super::StablePointerOps;
+        unsafe {
+            let pointer_ops = StablePointerOps::<Rc<_>>::new();
+            let p = Rc::new(1);
+            let a: *const i32 = &*p;
+            let r = pointer_ops.into_raw(p);
+            assert_eq!(a, r);
+            let p2: Rc<i32> = pointer_ops.from_raw(r);
+            let a2: *const i32 = &*p2;
+            assert_eq!(a, a2);
+
+            let pointer_ops = StablePointerOps::<Box<_>>::new();
+            let p = Box::new(1) as Box<dyn Debug>;
+            let a: *const dyn Debug = &*p;
+            let r = pointer_ops.into_raw(p);
+            assert_eq!(a, r);
+            let p2: Box<dyn Debug> = pointer_ops.from_raw(r);
+            let a2: *const dyn Debug = &*p2;
+            assert_eq!(a, a2);
+        }
+    }
+
+    #[cfg(feature = "bumpalo")]
+    #[test]
+    fn test_bumpalo_box() {
+        use super::ExclusivePointerOps;
+        use bumpalo::{boxed::Box, Bump};
+        use core::cell::Cell;
+
+        struct Obj<'a>(&'a Cell<u32>);
+        impl Drop for Obj<'_> {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let drops = Cell::new(0);
+        let bump = Bump::new();
+        unsafe {
+            let pointer_ops = DefaultPointerOps::<Box<'_, _>>::new();
+            let p = Box::new_in(Obj(&drops), &bump);
+            let a: *const Obj<'_> = &*p;
+            let r = pointer_ops.into_raw(p);
+            assert_eq!(a, r);
+            assert_eq!(pointer_ops.get_mut(r) as *const _, a);
+            let p2: Box<'_, Obj<'_>> = pointer_ops.from_raw(r);
+            let a2: *const Obj<'_> = &*p2;
+            assert_eq!(a, a2);
+            assert_eq!(drops.get(), 0);
+        }
+        assert_eq!(drops.get(), 1);
+    }
+
+    #[cfg(feature = "bumpalo")]
+    #[test]
+    fn test_bump_pointer_ops() {
+        use super::{clone_pointer_from_raw, BumpPointerOps, ExclusivePointerOps};
+        use bumpalo::Bump;
+
+        let bump = Bump::new();
+        unsafe {
+            let pointer_ops = BumpPointerOps::<&u32>::new(&bump);
+            let p: &u32 = bump.alloc(1);
+            let r = pointer_ops.into_raw(p);
+            assert_eq!(r, p as *const u32);
+            let p2 = clone_pointer_from_raw(&pointer_ops, r);
+            assert_eq!(p2 as *const u32, r);
+            assert_eq!(*pointer_ops.from_raw(r), 1);
+
+            let pointer_ops = BumpPointerOps::<&mut u32>::new(&bump);
+            let r = pointer_ops.into_raw(bump.alloc(2));
+            *pointer_ops.get_mut(r) = 3;
+            assert_eq!(*pointer_ops.from_raw(r), 3);
+        }
+        assert!(core::ptr::eq(
+            BumpPointerOps::<&()>::new(&bump).bump(),
+            &bump
+        ));
+    }
+
+    #[cfg(all(feature = "bumpalo", debug_assertions))]
+    #[test]
+    #[should_panic(expected = "wasn't allocated from the arena of the adapter")]
+    fn test_bump_pointer_ops_other_arena() {
+        use super::BumpPointerOps;
+        use bumpalo::Bump;
+
+        let bump = Bump::new();
+        let other = Bump::new();
+        let pointer_ops = BumpPointerOps::<&u32>::new(&bump);
+        pointer_ops.into_raw(other.alloc(1));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_arena_box() {
+        use super::ExclusivePointerOps;
+        use crate::arena::{Arena, ArenaBox};
+        use core::cell::Cell;
+
+        struct Obj<'a>(&'a Cell<u32>);
+        impl Drop for Obj<'_> {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let drops = Cell::new(0);
+        let arena = Arena::new();
+        unsafe {
+            let pointer_ops = DefaultPointerOps::<ArenaBox<'_, _>>::new();
+            let p = arena.alloc(Obj(&drops));
+            let a: *const Obj<'_> = &*p;
+            let r = pointer_ops.into_raw(p);
+            assert_eq!(a, r);
+            assert_eq!(pointer_ops.get_mut(r) as *const _, a);
+            let p2: ArenaBox<'_, Obj<'_>> = pointer_ops.from_raw(r);
+            let a2: *const Obj<'_> = &*p2;
+            assert_eq!(a, a2);
+            assert_eq!(drops.get(), 0);
+        }
+        assert_eq!(drops.get(), 1);
+    }
+
+    #[test]
+    fn test_projected() {
+        use super::{ProjectedPointerOps, TryExclusivePointerOps};
+        use crate::offset_of;
+
+        struct Outer {
+            _a: u64,
+            inner: u32,
+        }
+        unsafe {
+            let pointer_ops: ProjectedPointerOps<_, u32> = ProjectedPointerOps::new(
+                DefaultPointerOps::<Rc<Outer>>::new(),
+                offset_of!(Outer, inner),
+            );
+            let p = Rc::new(Outer { _a: 1, inner: 2 });
+            let a: *const u32 = &p.inner;
+            let r = pointer_ops.into_raw(p);
+            assert_eq!(a, r);
+            assert_eq!(pointer_ops.try_get_mut(r), Some(a as *mut u32));
+            let p2: Rc<Outer> = pointer_ops.from_raw(r);
+            let p3 = p2.clone();
+            let r = pointer_ops.into_raw(p2);
+            assert_eq!(pointer_ops.try_get_mut(r), None);
+            let p2: Rc<Outer> = pointer_ops.from_raw(r);
+            assert!(Rc::ptr_eq(&p2, &p3));
+
+            let same: ProjectedPointerOps<_, u32> = ProjectedPointerOps::new(
+                DefaultPointerOps::<Rc<Outer>>::new(),
+                offset_of!(Outer, inner),
+            );
+            let other: ProjectedPointerOps<_, u32> =
+                ProjectedPointerOps::new(DefaultPointerOps::<Rc<Outer>>::new(), 0);
+            assert!(pointer_ops.same_state(&same));
+            assert!(!pointer_ops.same_state(&other));
+        }
+    }
+
+    #[test]
+    fn test_pinned() {
+        use super::{ExclusivePinnedPointerOps, PinnedPointerOps};
+        use core::marker::PhantomPinned;
+
+        fn assert_pinned<T: PinnedPointerOps>() {}
+        assert_pinned::<DefaultPointerOps<Pin<Box<i32>>>>();
+        assert_pinned::<DefaultPointerOps<Pin<Rc<i32>>>>();
+        assert_pinned::<DefaultPointerOps<Pin<&mut i32>>>();
+
+        fn assert_exclusive_pinned<T: ExclusivePinnedPointerOps>() {}
+        assert_exclusive_pinned::<DefaultPointerOps<Pin<Box<PhantomPinned>>>>();
+        assert_exclusive_pinned::<DefaultPointerOps<Pin<&mut PhantomPinned>>>();
+    }
+
     #[test]
     fn test_box_unsized() {
         unsafe {
             let pointer_ops = DefaultPointerOps::<Box<_>>::new();
             let p = Box::new(1) as Box<dyn Debug>;
             let a: *const dyn Debug = &*p;
-            let b: (usize, usize) = mem::transmute(a);
+            let b: (usize, usize) = mem::transmute::<*const dyn Debug, _>(a);
             let r = pointer_ops.into_raw(p);
             assert_eq!(a, r);
-            assert_eq!(b, mem::transmute(r));
+            assert_eq!(b, mem::transmute::<*const dyn Debug, (usize, usize)>(r));
             let p2: Box<dyn Debug> = pointer_ops.from_raw(r);
             let a2: *const dyn Debug = &*p2;
             assert_eq!(a, a2);
-            assert_eq!(b, mem::transmute(a2));
+            assert_eq!(b, mem::transmute::<*const dyn Debug, (usize, usize)>(a2));
         }
     }
 
@@ -450,14 +2076,14 @@ mod tests {
             let pointer_ops = DefaultPointerOps::<Rc<_>>::new();
             let p = Rc::new(1) as Rc<dyn Debug>;
             let a: *const dyn Debug = &*p;
-            let b: (usize, usize) = mem::transmute(a);
+            let b: (usize, usize) = mem::transmute::<*const dyn Debug, _>(a);
             let r = pointer_ops.into_raw(p);
             assert_eq!(a, r);
-            assert_eq!(b, mem::transmute(r));
+            assert_eq!(b, mem::transmute::<*const dyn Debug, (usize, usize)>(r));
             let p2: Rc<dyn Debug> = pointer_ops.from_raw(r);
             let a2: *const dyn Debug = &*p2;
             assert_eq!(a, a2);
-            assert_eq!(b, mem::transmute(a2));
+            assert_eq!(b, mem::transmute::<*const dyn Debug, (usize, usize)>(a2));
         }
     }
 
@@ -467,14 +2093,14 @@ mod tests {
             let pointer_ops = DefaultPointerOps::<Arc<_>>::new();
             let p = Arc::new(1) as Arc<dyn Debug>;
             let a: *const dyn Debug = &*p;
-            let b: (usize, usize) = mem::transmute(a);
+            let b: (usize, usize) = mem::transmute::<*const dyn Debug, _>(a);
             let r = pointer_ops.into_raw(p);
             assert_eq!(a, r);
-            assert_eq!(b, mem::transmute(r));
+            assert_eq!(b, mem::transmute::<*const dyn Debug, (usize, usize)>(r));
             let p2: Arc<dyn Debug> = pointer_ops.from_raw(r);
             let a2: *const dyn Debug = &*p2;
             assert_eq!(a, a2);
-            assert_eq!(b, mem::transmute(a2));
+            assert_eq!(b, mem::transmute::<*const dyn Debug, (usize, usize)>(a2));
         }
     }
 
@@ -550,14 +2176,14 @@ mod tests {
             let pointer_ops = DefaultPointerOps::<Pin<Box<_>>>::new();
             let p = Pin::new(Box::new(1)) as Pin<Box<dyn Debug>>;
             let a: *const dyn Debug = &*p;
-            let b: (usize, usize) = mem::transmute(a);
+            let b: (usize, usize) = mem::transmute::<*const dyn Debug, _>(a);
             let r = pointer_ops.into_raw(p);
             assert_eq!(a, r);
-            assert_eq!(b, mem::transmute(r));
+            assert_eq!(b, mem::transmute::<*const dyn Debug, (usize, usize)>(r));
             let p2: Pin<Box<dyn Debug>> = pointer_ops.from_raw(r);
             let a2: *const dyn Debug = &*p2;
             assert_eq!(a, a2);
-            assert_eq!(b, mem::transmute(a2));
+            assert_eq!(b, mem::transmute::<*const dyn Debug, (usize, usize)>(a2));
         }
     }
 
@@ -567,14 +2193,14 @@ mod tests {
             let pointer_ops = DefaultPointerOps::<Pin<Rc<_>>>::new();
             let p = Pin::new(Rc::new(1)) as Pin<Rc<dyn Debug>>;
             let a: *const dyn Debug = &*p;
-            let b: (usize, usize) = mem::transmute(a);
+            let b: (usize, usize) = mem::transmute::<*const dyn Debug, _>(a);
             let r = pointer_ops.into_raw(p);
             assert_eq!(a, r);
-            assert_eq!(b, mem::transmute(r));
+            assert_eq!(b, mem::transmute::<*const dyn Debug, (usize, usize)>(r));
             let p2: Pin<Rc<dyn Debug>> = pointer_ops.from_raw(r);
             let a2: *const dyn Debug = &*p2;
             assert_eq!(a, a2);
-            assert_eq!(b, mem::transmute(a2));
+            assert_eq!(b, mem::transmute::<*const dyn Debug, (usize, usize)>(a2));
         }
     }
 
@@ -584,14 +2210,14 @@ mod tests {
             let pointer_ops = DefaultPointerOps::<Pin<Arc<_>>>::new();
             let p = Pin::new(Arc::new(1)) as Pin<Arc<dyn Debug>>;
             let a: *const dyn Debug = &*p;
-            let b: (usize, usize) = mem::transmute(a);
+            let b: (usize, usize) = mem::transmute::<*const dyn Debug, _>(a);
             let r = pointer_ops.into_raw(p);
             assert_eq!(a, r);
-            assert_eq!(b, mem::transmute(r));
+            assert_eq!(b, mem::transmute::<*const dyn Debug, (usize, usize)>(r));
             let p2: Pin<Arc<dyn Debug>> = pointer_ops.from_raw(r);
             let a2: *const dyn Debug = &*p2;
             assert_eq!(a, a2);
-            assert_eq!(b, mem::transmute(a2));
+            assert_eq!(b, mem::transmute::<*const dyn Debug, (usize, usize)>(a2));
         }
     }
 
@@ -0,0 +1,109 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+// Runs `$body`, which modifies the links of `$collection`. If the `poison`
+// feature is enabled, this panics if the collection is poisoned, and
+// otherwise marks it as poisoned until `$body` is done, so that it stays
+// poisoned if a panic escapes `$body`. `$body` must not return early, and
+// must only hold the modification itself: the preconditions of the operation,
+// such as the new element not being linked yet, are checked before it, so
+// that their panics leave the collection usable. With `debug_generation`,
+// this also invalidates the iterators and cursors of the collection.
+macro_rules! structural {
+    ($collection:expr, $body:expr) => {
+        poisoning!($collection, {
+            #[cfg(feature = "debug_generation")]
+            $collection.generation.bump();
+            $body
+        })
+    };
+}
+
+// Same as `structural!`, for the collections which don't keep a generation
+// for `debug_generation`.
+macro_rules! poisoning {
+    ($collection:expr, $body:expr) => {{
+        #[cfg(feature = "poison")]
+        {
+            let name = $collection.debug_name();
+            $collection.poison.enter(name);
+        }
+        let result = $body;
+        #[cfg(feature = "poison")]
+        $collection.poison.exit();
+        result
+    }};
+}
+
+// Panics if `$collection` is poisoned, without marking it. This is done
+// before acquiring the link of a new element outside of `structural!`, so
+// that a poisoned collection doesn't take ownership of an element it can't
+// link.
+macro_rules! check_poison {
+    ($collection:expr) => {
+        #[cfg(feature = "poison")]
+        {
+            let name = $collection.debug_name();
+            $collection.poison.check(name);
+        }
+    };
+}
+
+// Poison flag of a collection, set while a structural operation is running
+// on it. There is no way to tell whether a thread is panicking without `std`,
+// so the flag is set before the operation and cleared once it returns
+// instead of being set by a drop guard.
+#[cfg(feature = "poison")]
+pub(crate) struct Poison(bool);
+
+#[cfg(feature = "poison")]
+impl Poison {
+    #[inline]
+    pub(crate) const fn new() -> Poison {
+        Poison(false)
+    }
+
+    #[inline]
+    pub(crate) fn get(&self) -> bool {
+        self.0
+    }
+
+    #[inline]
+    pub(crate) fn clear(&mut self) {
+        self.0 = false;
+    }
+
+    #[inline]
+    #[track_caller]
+    pub(crate) fn check(&self, collection: Option<&'static str>) {
+        if self.0 {
+            poisoned(collection);
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub(crate) fn enter(&mut self, collection: Option<&'static str>) {
+        self.check(collection);
+        self.0 = true;
+    }
+
+    #[inline]
+    pub(crate) fn exit(&mut self) {
+        self.0 = false;
+    }
+}
+
+#[cfg(feature = "poison")]
+#[cold]
+#[track_caller]
+fn poisoned(collection: Option<&'static str>) -> ! {
+    panic!(
+        "attempted to modify a collection poisoned by a panic (collection: {})",
+        collection.unwrap_or("<unnamed>"),
+    );
+}
@@ -0,0 +1,52 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::adapter::Adapter;
+use crate::pointer_ops::PointerOps;
+
+/// Extension of the `Adapter` trait to provide a way of extracting a priority
+/// from an object. This priority is used by collections which keep their
+/// elements ordered, such as the `insert_sorted` methods of `LinkedList`,
+/// `SinglyLinkedList` and `XorLinkedList`.
+///
+/// Unlike `KeyAdapter`, the priority is always returned by value, since it is
+/// usually a small integer or tuple which is compared often.
+///
+/// An implementation can be generated by `intrusive_adapter!` with a
+/// `priority` clause.
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::intrusive_adapter;
+/// use intrusive_collections::{LinkedListLink, PriorityAdapter};
+///
+/// struct Task {
+///     link: LinkedListLink,
+///     priority: u8,
+///     name: &'static str,
+/// }
+///
+/// // Adapter with a manual implementation
+/// intrusive_adapter!(TaskAdapter = Box<Task>: Task { link: LinkedListLink });
+/// impl PriorityAdapter for TaskAdapter {
+///     type Priority = u8;
+///     fn get_priority(&self, task: &Task) -> u8 { task.priority }
+/// }
+///
+/// // Adapter with an implementation generated by the macro. Higher priority
+/// // tasks sort first.
+/// intrusive_adapter!(TaskAdapter2 = Box<Task>: Task { link: LinkedListLink }
+///     priority: core::cmp::Reverse<u8> = |task| { core::cmp::Reverse(task.priority) });
+/// ```
+pub trait PriorityAdapter: Adapter {
+    /// Type of the priority returned by `get_priority`.
+    type Priority: Ord;
+
+    /// Gets the priority for the given object.
+    fn get_priority(&self, value: &<Self::PointerOps as PointerOps>::Value) -> Self::Priority;
+}
@@ -0,0 +1,212 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Property testing of collections with `proptest`.
+//!
+//! This module provides strategies which generate random sequences of
+//! operations on each collection, and functions which run them on a
+//! collection while performing the same operations on a `std` container.
+//! After every operation the contents of the collection and the position of
+//! the cursor are compared with those of the container, so any bug in an
+//! adapter or in the links it uses shows up as a panic which `proptest` can
+//! shrink.
+//!
+//! The operations and the checkers are those of the `ops_interpreter`
+//! module, which decodes them from bytes instead.
+//!
+//! Elements are identified by a `u32`: the checkers take a function which
+//! creates an element from a `u32` and one which reads it back.
+//!
+//! # Examples
+//!
+//! ```
+//! use intrusive_collections::proptest::{check_linked_list, list_ops};
+//! use intrusive_collections::{intrusive_adapter, LinkedListLink};
+//! use proptest::proptest;
+//!
+//! struct Obj {
+//!     link: LinkedListLink,
+//!     value: u32,
+//! }
+//! intrusive_adapter!(ObjAdapter = Box<Obj>: Obj { link: LinkedListLink });
+//!
+//! proptest! {
+//!     fn test_obj_adapter(ops in list_ops(0..32)) {
+//!         check_linked_list(
+//!             ObjAdapter::new(),
+//!             &ops,
+//!             |value| Box::new(Obj { link: LinkedListLink::new(), value }),
+//!             |obj| obj.value,
+//!         );
+//!     }
+//! }
+//! # test_obj_adapter();
+//! ```
+
+use std::vec;
+use std::vec::Vec;
+
+use ::proptest::collection::{self, SizeRange};
+use ::proptest::prelude::*;
+
+pub use crate::ops_interpreter::{
+    check_linked_list, check_rbtree, check_singly_linked_list, check_xor_linked_list, ListOp,
+    SinglyListOp, TreeOp,
+};
+
+// Values of the elements in generated operations. Elements are told apart by
+// their position, so a small range is enough and makes keys collide in trees.
+fn value() -> impl Strategy<Value = u32> {
+    0..64u32
+}
+
+fn values() -> impl Strategy<Value = Vec<u32>> {
+    collection::vec(value(), 0..4)
+}
+
+impl Arbitrary for ListOp {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<ListOp>;
+
+    fn arbitrary_with(_: ()) -> BoxedStrategy<ListOp> {
+        prop_oneof![
+            4 => value().prop_map(ListOp::PushFront),
+            4 => value().prop_map(ListOp::PushBack),
+            2 => Just(ListOp::PopFront),
+            2 => Just(ListOp::PopBack),
+            4 => Just(ListOp::MoveNext),
+            4 => Just(ListOp::MovePrev),
+            3 => value().prop_map(ListOp::InsertAfter),
+            3 => value().prop_map(ListOp::InsertBefore),
+            3 => Just(ListOp::Remove),
+            1 => values().prop_map(ListOp::SpliceAfter),
+            1 => values().prop_map(ListOp::SpliceBefore),
+            1 => Just(ListOp::SplitAfter),
+            1 => Just(ListOp::SplitBefore),
+            1 => Just(ListOp::Clear),
+        ]
+        .boxed()
+    }
+}
+
+/// Returns a strategy generating sequences of `ListOp` whose length is in
+/// `len`.
+pub fn list_ops(len: impl Into<SizeRange>) -> impl Strategy<Value = Vec<ListOp>> {
+    collection::vec(any::<ListOp>(), len)
+}
+
+impl Arbitrary for SinglyListOp {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<SinglyListOp>;
+
+    fn arbitrary_with(_: ()) -> BoxedStrategy<SinglyListOp> {
+        prop_oneof![
+            4 => value().prop_map(SinglyListOp::PushFront),
+            2 => Just(SinglyListOp::PopFront),
+            4 => Just(SinglyListOp::MoveNext),
+            4 => value().prop_map(SinglyListOp::InsertAfter),
+            3 => Just(SinglyListOp::RemoveNext),
+            1 => values().prop_map(SinglyListOp::SpliceAfter),
+            1 => Just(SinglyListOp::SplitAfter),
+            1 => Just(SinglyListOp::Clear),
+        ]
+        .boxed()
+    }
+}
+
+/// Returns a strategy generating sequences of `SinglyListOp` whose length is
+/// in `len`.
+pub fn singly_list_ops(len: impl Into<SizeRange>) -> impl Strategy<Value = Vec<SinglyListOp>> {
+    collection::vec(any::<SinglyListOp>(), len)
+}
+
+impl Arbitrary for TreeOp {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<TreeOp>;
+
+    fn arbitrary_with(_: ()) -> BoxedStrategy<TreeOp> {
+        prop_oneof![
+            6 => value().prop_map(TreeOp::Insert),
+            3 => value().prop_map(TreeOp::Remove),
+            1 => Just(TreeOp::RemoveFront),
+            1 => Just(TreeOp::RemoveBack),
+            3 => value().prop_map(TreeOp::Bounds),
+            2 => Just(TreeOp::MoveNext),
+            2 => Just(TreeOp::MovePrev),
+            1 => Just(TreeOp::RemoveCurrent),
+            1 => Just(TreeOp::Clear),
+        ]
+        .boxed()
+    }
+}
+
+/// Returns a strategy generating sequences of `TreeOp` whose length is in
+/// `len`.
+pub fn tree_ops(len: impl Into<SizeRange>) -> impl Strategy<Value = Vec<TreeOp>> {
+    collection::vec(any::<TreeOp>(), len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_linked_list, check_rbtree, check_singly_linked_list, check_xor_linked_list, list_ops,
+        singly_list_ops, tree_ops,
+    };
+    use crate::{KeyAdapter, LinkedListLink, RBTreeLink, SinglyLinkedListLink, XorLinkedListLink};
+    use ::proptest::proptest;
+    use std::boxed::Box;
+
+    struct Obj {
+        link: LinkedListLink,
+        singly_link: SinglyLinkedListLink,
+        xor_link: XorLinkedListLink,
+        tree_link: RBTreeLink,
+        value: u32,
+    }
+    intrusive_adapter!(ObjAdapter = Box<Obj>: Obj { link: LinkedListLink });
+    intrusive_adapter!(ObjSinglyAdapter = Box<Obj>: Obj { singly_link: SinglyLinkedListLink });
+    intrusive_adapter!(ObjXorAdapter = Box<Obj>: Obj { xor_link: XorLinkedListLink });
+    intrusive_adapter!(ObjTreeAdapter = Box<Obj>: Obj { tree_link: RBTreeLink });
+    impl<'a> KeyAdapter<'a> for ObjTreeAdapter {
+        type Key = u32;
+        fn get_key(&self, value: &'a Obj) -> u32 {
+            value.value
+        }
+    }
+
+    fn make_obj(value: u32) -> Box<Obj> {
+        Box::new(Obj {
+            link: LinkedListLink::new(),
+            singly_link: SinglyLinkedListLink::new(),
+            xor_link: XorLinkedListLink::new(),
+            tree_link: RBTreeLink::new(),
+            value,
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_linked_list(ops in list_ops(0..64)) {
+            check_linked_list(ObjAdapter::new(), &ops, make_obj, |obj| obj.value);
+        }
+
+        #[test]
+        fn test_singly_linked_list(ops in singly_list_ops(0..64)) {
+            check_singly_linked_list(ObjSinglyAdapter::new(), &ops, make_obj, |obj| obj.value);
+        }
+
+        #[test]
+        fn test_xor_linked_list(ops in list_ops(0..64)) {
+            check_xor_linked_list(ObjXorAdapter::new(), &ops, make_obj, |obj| obj.value);
+        }
+
+        #[test]
+        fn test_rbtree(ops in tree_ops(0..64)) {
+            check_rbtree(ObjTreeAdapter::new(), &ops, make_obj);
+        }
+    }
+}
@@ -13,19 +13,45 @@ use core::cell::Cell;
 use core::cmp::Ordering;
 use core::fmt;
 use core::mem;
+use core::ops::ControlFlow;
+use core::pin::Pin;
 use core::ptr::NonNull;
-use core::sync::atomic::{self, AtomicUsize};
+
+#[cfg(feature = "ghost-cell")]
+use ghost_cell::GhostToken;
 
 use crate::Bound::{self, Excluded, Included, Unbounded};
 
-use crate::link_ops::{self, DefaultLinkOps};
+use crate::adapter::TryPointer;
+use crate::bundle::BundleMember;
+use crate::collection::Collection;
+use crate::cursor_ops::{CursorMutOps, CursorOps, DoubleEndedCursorOps};
+use crate::footprint::MemoryOverhead;
+#[cfg(feature = "debug_generation")]
+use crate::generation::Generation;
+#[cfg(feature = "ghost-cell")]
+use crate::ghost_adapter::{GhostAdapter, GhostIterMut};
+use crate::insert_error::InsertError;
+use crate::link_ops::{
+    self, check_link_ptr, poison_ptr, wrapper_ptr, DefaultLinkOps, LinkWrapper, WrapperLinkOps,
+};
 use crate::linked_list::LinkedListOps;
-use crate::pointer_ops::PointerOps;
+use crate::pointer_ops::{PointerOps, TryPointerOps};
+#[cfg(feature = "poison")]
+use crate::poison::Poison;
+use crate::sanitizer;
 use crate::singly_linked_list::SinglyLinkedListOps;
+#[cfg(feature = "stats")]
+use crate::stats::{Stats, StatsCounters};
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+use crate::sync::{self as atomic, AtomicUsize};
 use crate::xor_linked_list::XorLinkedListOps;
 use crate::Adapter;
+use crate::CowPointerOps;
+use crate::ExclusivePinnedPointerOps;
 use crate::ExclusivePointerOps;
 use crate::KeyAdapter;
+use crate::PinnedPointerOps;
 use crate::TryExclusivePointerOps;
 // Necessary for Rust 1.56 compatability
 #[allow(unused_imports)]
@@ -44,6 +70,11 @@ pub enum Color {
 }
 
 /// Link operations for `RBTree`.
+///
+/// # Safety
+///
+/// Implementations must correctly maintain the links of the objects passed
+/// to them, since collections rely on these operations for memory safety.
 pub unsafe trait RBTreeOps: link_ops::LinkOps {
     /// Returns the left child of `ptr`.
     ///
@@ -92,6 +123,87 @@ pub unsafe trait RBTreeOps: link_ops::LinkOps {
     /// # Safety
     /// An implementation of `set_color` must not panic.
     unsafe fn set_color(&mut self, ptr: Self::LinkPtr, color: Color);
+
+    /// Records the identifier of the `RBTree` that `ptr` was inserted
+    /// into, for the `debug_owner` feature.
+    ///
+    /// Link types which don't store an owner can leave this unimplemented.
+    ///
+    /// # Safety
+    /// An implementation of `set_owner` must not panic.
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn set_owner(&mut self, ptr: Self::LinkPtr, owner: usize) {
+        let _ = (ptr, owner);
+    }
+
+    /// Returns the identifier recorded by `set_owner`, 0 if the link was
+    /// released since, or `None` if the link type doesn't store an owner.
+    ///
+    /// # Safety
+    /// An implementation of `owner` must not panic.
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn owner(&self, ptr: Self::LinkPtr) -> Option<usize> {
+        let _ = ptr;
+        None
+    }
+}
+
+unsafe impl<W: LinkWrapper, O: RBTreeOps<LinkPtr = NonNull<W::Link>>> RBTreeOps
+    for WrapperLinkOps<W, O>
+{
+    #[inline]
+    unsafe fn left(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
+        wrapper_ptr(self.ops.left(ptr.cast()))
+    }
+
+    #[inline]
+    unsafe fn right(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
+        wrapper_ptr(self.ops.right(ptr.cast()))
+    }
+
+    #[inline]
+    unsafe fn parent(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
+        wrapper_ptr(self.ops.parent(ptr.cast()))
+    }
+
+    #[inline]
+    unsafe fn color(&self, ptr: Self::LinkPtr) -> Color {
+        self.ops.color(ptr.cast())
+    }
+
+    #[inline]
+    unsafe fn set_left(&mut self, ptr: Self::LinkPtr, left: Option<Self::LinkPtr>) {
+        self.ops.set_left(ptr.cast(), left.map(NonNull::cast));
+    }
+
+    #[inline]
+    unsafe fn set_right(&mut self, ptr: Self::LinkPtr, right: Option<Self::LinkPtr>) {
+        self.ops.set_right(ptr.cast(), right.map(NonNull::cast));
+    }
+
+    #[inline]
+    unsafe fn set_parent(&mut self, ptr: Self::LinkPtr, parent: Option<Self::LinkPtr>) {
+        self.ops.set_parent(ptr.cast(), parent.map(NonNull::cast));
+    }
+
+    #[inline]
+    unsafe fn set_color(&mut self, ptr: Self::LinkPtr, color: Color) {
+        self.ops.set_color(ptr.cast(), color);
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn set_owner(&mut self, ptr: Self::LinkPtr, owner: usize) {
+        self.ops.set_owner(ptr.cast(), owner);
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn owner(&self, ptr: Self::LinkPtr) -> Option<usize> {
+        self.ops.owner(ptr.cast())
+    }
 }
 
 // =============================================================================
@@ -100,17 +212,30 @@ pub unsafe trait RBTreeOps: link_ops::LinkOps {
 
 /// Intrusive link that allows an object to be inserted into a
 /// `RBTree`.
-#[repr(align(2))]
+///
+/// The link is `repr(C)` with the layout of `struct ic_rbtree_link` in the
+/// header written by `c_header::write_c_header`: `left` and `right` child
+/// pointers followed by a `parent_color` word holding the parent address with
+/// the color in its lowest bit (1 for black), set to 0 while unlinked. The
+/// `debug_owner` feature appends an `owner` field.
+#[repr(C, align(2))]
 pub struct Link {
     left: Cell<Option<NonNull<Link>>>,
     right: Cell<Option<NonNull<Link>>>,
     parent_color: Cell<usize>,
+    #[cfg(feature = "debug_owner")]
+    owner: Cell<usize>,
 }
 
 // Use a special value to indicate an unlinked node. This value represents a
 // red root node, which is impossible in a valid red-black tree.
 const UNLINKED_MARKER: usize = 0;
 
+// Marks a link claimed by `try_acquire_for_insertion`. This would be a black
+// node whose parent is at the very last address, which can't hold a link.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+pub(crate) const CLAIMED_MARKER: usize = usize::MAX;
+
 impl Link {
     /// Creates a new `Link`.
     #[inline]
@@ -119,6 +244,8 @@ impl Link {
             left: Cell::new(None),
             right: Cell::new(None),
             parent_color: Cell::new(UNLINKED_MARKER),
+            #[cfg(feature = "debug_owner")]
+            owner: Cell::new(0),
         }
     }
 
@@ -183,6 +310,19 @@ impl fmt::Debug for Link {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for Link {
+    #[inline]
+    fn format(&self, f: defmt::Formatter<'_>) {
+        let state = if self.is_linked() {
+            "linked"
+        } else {
+            "unlinked"
+        };
+        defmt::write!(f, "{=str}", state)
+    }
+}
+
 // =============================================================================
 // LinkOps
 // =============================================================================
@@ -212,11 +352,15 @@ impl LinkOps {
 unsafe impl link_ops::LinkOps for LinkOps {
     type LinkPtr = NonNull<Link>;
 
+    const LINK_SIZE: Option<usize> = Some(mem::size_of::<Link>());
+
     #[inline]
     unsafe fn acquire_link(&mut self, ptr: Self::LinkPtr) -> bool {
         if ptr.as_ref().is_linked() {
             false
         } else {
+            sanitizer::unpoison(&ptr.as_ref().left);
+            sanitizer::unpoison(&ptr.as_ref().right);
             self.set_parent_color(ptr, None, Color::Black);
             true
         }
@@ -224,19 +368,34 @@ unsafe impl link_ops::LinkOps for LinkOps {
 
     #[inline]
     unsafe fn release_link(&mut self, ptr: Self::LinkPtr) {
+        if cfg!(debug_assertions) {
+            ptr.as_ref().left.set(poison_ptr());
+            ptr.as_ref().right.set(poison_ptr());
+        }
+        sanitizer::poison(&ptr.as_ref().left);
+        sanitizer::poison(&ptr.as_ref().right);
+        #[cfg(feature = "debug_owner")]
+        ptr.as_ref().owner.set(0);
         ptr.as_ref().parent_color.set(UNLINKED_MARKER);
     }
 }
 
+unsafe impl link_ops::LinkStateOps for LinkOps {
+    #[inline]
+    unsafe fn is_linked(&self, ptr: Self::LinkPtr) -> bool {
+        ptr.as_ref().is_linked()
+    }
+}
+
 unsafe impl RBTreeOps for LinkOps {
     #[inline]
     unsafe fn left(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
-        ptr.as_ref().left.get()
+        check_link_ptr(ptr.as_ref().left.get())
     }
 
     #[inline]
     unsafe fn right(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
-        ptr.as_ref().right.get()
+        check_link_ptr(ptr.as_ref().right.get())
     }
 
     #[inline]
@@ -273,6 +432,18 @@ unsafe impl RBTreeOps for LinkOps {
     unsafe fn set_color(&mut self, ptr: Self::LinkPtr, color: Color) {
         self.set_parent_color(ptr, self.parent(ptr), color);
     }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn set_owner(&mut self, ptr: Self::LinkPtr, owner: usize) {
+        ptr.as_ref().owner.set(owner);
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn owner(&self, ptr: Self::LinkPtr) -> Option<usize> {
+        Some(ptr.as_ref().owner.get())
+    }
 }
 
 unsafe impl SinglyLinkedListOps for LinkOps {
@@ -369,29 +540,70 @@ unsafe impl XorLinkedListOps for LinkOps {
 
 /// Intrusive link that allows an object to be inserted into a
 /// `RBTree`. This link allows the structure to be shared between threads.
-
-#[repr(align(2))]
+///
+/// Whether the link is in a tree is tracked atomically, so `is_linked` can be
+/// called from any thread while another thread owns the tree.
+///
+/// The link has the same layout as `Link`, described in the header written by
+/// `c_header::write_c_header` as `struct ic_rbtree_atomic_link`. C code must
+/// access its `parent_color` field atomically.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+#[repr(C, align(2))]
 pub struct AtomicLink {
     left: Cell<Option<NonNull<AtomicLink>>>,
     right: Cell<Option<NonNull<AtomicLink>>>,
     parent_color: AtomicUsize,
+    // Atomic since it is read when checking a link which may be owned by a
+    // tree on another thread.
+    #[cfg(feature = "debug_owner")]
+    owner: AtomicUsize,
 }
 
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 impl AtomicLink {
-    #[inline]
-    /// Creates a new `AtomicLink`.
-    pub const fn new() -> AtomicLink {
-        AtomicLink {
-            left: Cell::new(None),
-            right: Cell::new(None),
-            parent_color: AtomicUsize::new(UNLINKED_MARKER),
+    loom_const_fn! {
+        #[inline]
+        /// Creates a new `AtomicLink`.
+        pub fn new() -> AtomicLink {
+            AtomicLink {
+                left: Cell::new(None),
+                right: Cell::new(None),
+                parent_color: AtomicUsize::new(UNLINKED_MARKER),
+                #[cfg(feature = "debug_owner")]
+                owner: AtomicUsize::new(0),
+            }
         }
     }
 
     /// Checks whether the `AtomicLink` is linked into a `RBTree`.
+    ///
+    /// This can be called from any thread. The load has acquire ordering, so
+    /// if it returns `false`, everything the thread which removed the object
+    /// did before removing it is visible to the caller. An object claimed
+    /// with `try_acquire_for_insertion` counts as linked.
     #[inline]
     pub fn is_linked(&self) -> bool {
-        self.parent_color.load(atomic::Ordering::Relaxed) != UNLINKED_MARKER
+        self.parent_color.load(atomic::Ordering::Acquire) != UNLINKED_MARKER
+    }
+
+    /// Atomically marks an unlinked `AtomicLink` as about to be inserted into a
+    /// `RBTree`, returning `false` if it is linked or already claimed.
+    ///
+    /// This lets several threads race to queue the same object: only the one
+    /// which got `true` goes on to insert it, and `is_linked` returns `true`
+    /// from the moment it is claimed. Collections treat a claimed object as
+    /// linked, so the claim must be dropped with `force_unlink` before the
+    /// object is inserted.
+    #[inline]
+    pub fn try_acquire_for_insertion(&self) -> bool {
+        self.parent_color
+            .compare_exchange(
+                UNLINKED_MARKER,
+                CLAIMED_MARKER,
+                atomic::Ordering::Acquire,
+                atomic::Ordering::Relaxed,
+            )
+            .is_ok()
     }
 
     /// Forcibly unlinks an object from a `RBTree`.
@@ -402,24 +614,42 @@ impl AtomicLink {
     /// `RBTree`. The only situation where this function is useful is
     /// after calling `fast_clear` on a `RBTree`, since this clears
     /// the collection without marking the nodes as unlinked.
+    ///
+    /// It can also be used to drop a claim taken with
+    /// `try_acquire_for_insertion`, before inserting the object or if it
+    /// won't be inserted.
     #[inline]
     pub unsafe fn force_unlink(&self) {
         self.parent_color
             .store(UNLINKED_MARKER, atomic::Ordering::Release);
     }
 
-    /// Access `parent_color` in an exclusive context.
+    /// Reads `parent_color` in an exclusive context.
+    ///
+    /// The value is still accessed atomically, since other threads may call
+    /// `is_linked` while the tree is being modified.
+    ///
+    /// # Safety
+    ///
+    /// This can only be called after `acquire_link` has been succesfully called.
+    #[inline]
+    unsafe fn load_parent_color(&self) -> usize {
+        self.parent_color.load(atomic::Ordering::Relaxed)
+    }
+
+    /// Writes `parent_color` in an exclusive context.
     ///
     /// # Safety
     ///
     /// This can only be called after `acquire_link` has been succesfully called.
     #[inline]
-    unsafe fn parent_color_exclusive(&self) -> &Cell<usize> {
-        // This is safe because currently AtomicUsize has the same representation Cell<usize>.
-        core::mem::transmute(&self.parent_color)
+    unsafe fn store_parent_color(&self, parent_color: usize) {
+        self.parent_color
+            .store(parent_color, atomic::Ordering::Relaxed);
     }
 }
 
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 impl DefaultLinkOps for AtomicLink {
     type Ops = AtomicLinkOps;
 
@@ -427,11 +657,14 @@ impl DefaultLinkOps for AtomicLink {
 }
 
 // An object containing a link can be sent to another thread since `acquire_link` is atomic.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 unsafe impl Send for AtomicLink {}
 
 // An object containing a link can be shared between threads since `acquire_link` is atomic.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 unsafe impl Sync for AtomicLink {}
 
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 impl Clone for AtomicLink {
     #[inline]
     fn clone(&self) -> AtomicLink {
@@ -439,6 +672,7 @@ impl Clone for AtomicLink {
     }
 }
 
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 impl Default for AtomicLink {
     #[inline]
     fn default() -> AtomicLink {
@@ -448,6 +682,7 @@ impl Default for AtomicLink {
 
 // Provide an implementation of Debug so that structs containing a link can
 // still derive Debug.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 impl fmt::Debug for AtomicLink {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -461,14 +696,29 @@ impl fmt::Debug for AtomicLink {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for AtomicLink {
+    #[inline]
+    fn format(&self, f: defmt::Formatter<'_>) {
+        let state = if self.is_linked() {
+            "linked"
+        } else {
+            "unlinked"
+        };
+        defmt::write!(f, "{=str}", state)
+    }
+}
+
 // =============================================================================
 // AtomicLinkOps
 // =============================================================================
 
 /// Default `LinkOps` implementation for `RBTree`.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 #[derive(Clone, Copy, Default)]
 pub struct AtomicLinkOps;
 
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 impl AtomicLinkOps {
     #[inline]
     unsafe fn set_parent_color(
@@ -483,21 +733,23 @@ impl AtomicLinkOps {
             Color::Black => 1,
         };
         let parent_usize = parent.map(|x| x.as_ptr() as usize).unwrap_or(0);
-        ptr.as_ref()
-            .parent_color_exclusive()
-            .set((parent_usize & !1) | bit);
+        ptr.as_ref().store_parent_color((parent_usize & !1) | bit);
     }
 }
 
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 const LINKED_DEFAULT_VALUE: usize = 1;
 
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 unsafe impl link_ops::LinkOps for AtomicLinkOps {
     type LinkPtr = NonNull<AtomicLink>;
 
+    const LINK_SIZE: Option<usize> = Some(mem::size_of::<AtomicLink>());
+
     #[inline]
     unsafe fn acquire_link(&mut self, ptr: Self::LinkPtr) -> bool {
-        ptr.as_ref()
-            .parent_color
+        let parent_color = &ptr.as_ref().parent_color;
+        parent_color
             .compare_exchange(
                 UNLINKED_MARKER,
                 LINKED_DEFAULT_VALUE,
@@ -509,12 +761,22 @@ unsafe impl link_ops::LinkOps for AtomicLinkOps {
 
     #[inline]
     unsafe fn release_link(&mut self, ptr: Self::LinkPtr) {
+        #[cfg(feature = "debug_owner")]
+        ptr.as_ref().owner.store(0, atomic::Ordering::Relaxed);
         ptr.as_ref()
             .parent_color
             .store(UNLINKED_MARKER, atomic::Ordering::Release)
     }
 }
 
+unsafe impl link_ops::LinkStateOps for AtomicLinkOps {
+    #[inline]
+    unsafe fn is_linked(&self, ptr: Self::LinkPtr) -> bool {
+        ptr.as_ref().is_linked()
+    }
+}
+
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 unsafe impl RBTreeOps for AtomicLinkOps {
     #[inline]
     unsafe fn left(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
@@ -528,13 +790,13 @@ unsafe impl RBTreeOps for AtomicLinkOps {
 
     #[inline]
     unsafe fn parent(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
-        let parent_usize = ptr.as_ref().parent_color_exclusive().get() & !1;
+        let parent_usize = ptr.as_ref().load_parent_color() & !1;
         NonNull::new(parent_usize as *mut AtomicLink)
     }
 
     #[inline]
     unsafe fn color(&self, ptr: Self::LinkPtr) -> Color {
-        if ptr.as_ref().parent_color_exclusive().get() & 1 == 1 {
+        if ptr.as_ref().load_parent_color() & 1 == 1 {
             Color::Black
         } else {
             Color::Red
@@ -560,8 +822,21 @@ unsafe impl RBTreeOps for AtomicLinkOps {
     unsafe fn set_color(&mut self, ptr: Self::LinkPtr, color: Color) {
         self.set_parent_color(ptr, self.parent(ptr), color);
     }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn set_owner(&mut self, ptr: Self::LinkPtr, owner: usize) {
+        ptr.as_ref().owner.store(owner, atomic::Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn owner(&self, ptr: Self::LinkPtr) -> Option<usize> {
+        Some(ptr.as_ref().owner.load(atomic::Ordering::Relaxed))
+    }
 }
 
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 unsafe impl SinglyLinkedListOps for AtomicLinkOps {
     #[inline]
     unsafe fn next(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
@@ -574,6 +849,7 @@ unsafe impl SinglyLinkedListOps for AtomicLinkOps {
     }
 }
 
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 unsafe impl LinkedListOps for AtomicLinkOps {
     #[inline]
     unsafe fn next(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
@@ -596,6 +872,7 @@ unsafe impl LinkedListOps for AtomicLinkOps {
     }
 }
 
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 unsafe impl XorLinkedListOps for AtomicLinkOps {
     #[inline]
     unsafe fn next(
@@ -656,7 +933,7 @@ unsafe fn is_left_child<T: RBTreeOps>(link_ops: &T, ptr: T::LinkPtr, parent: T::
 }
 
 #[inline]
-unsafe fn first_child<T: RBTreeOps>(link_ops: &T, ptr: T::LinkPtr) -> T::LinkPtr {
+pub(crate) unsafe fn first_child<T: RBTreeOps>(link_ops: &T, ptr: T::LinkPtr) -> T::LinkPtr {
     let mut x = ptr;
     while let Some(y) = link_ops.left(x) {
         x = y;
@@ -665,7 +942,7 @@ unsafe fn first_child<T: RBTreeOps>(link_ops: &T, ptr: T::LinkPtr) -> T::LinkPtr
 }
 
 #[inline]
-unsafe fn last_child<T: RBTreeOps>(link_ops: &T, ptr: T::LinkPtr) -> T::LinkPtr {
+pub(crate) unsafe fn last_child<T: RBTreeOps>(link_ops: &T, ptr: T::LinkPtr) -> T::LinkPtr {
     let mut x = ptr;
     while let Some(y) = link_ops.right(x) {
         x = y;
@@ -674,7 +951,7 @@ unsafe fn last_child<T: RBTreeOps>(link_ops: &T, ptr: T::LinkPtr) -> T::LinkPtr
 }
 
 #[inline]
-unsafe fn next<T: RBTreeOps>(link_ops: &T, ptr: T::LinkPtr) -> Option<T::LinkPtr> {
+pub(crate) unsafe fn next<T: RBTreeOps>(link_ops: &T, ptr: T::LinkPtr) -> Option<T::LinkPtr> {
     if let Some(right) = link_ops.right(ptr) {
         Some(first_child(link_ops, right))
     } else {
@@ -694,7 +971,7 @@ unsafe fn next<T: RBTreeOps>(link_ops: &T, ptr: T::LinkPtr) -> Option<T::LinkPtr
 }
 
 #[inline]
-unsafe fn prev<T: RBTreeOps>(link_ops: &T, ptr: T::LinkPtr) -> Option<T::LinkPtr> {
+pub(crate) unsafe fn prev<T: RBTreeOps>(link_ops: &T, ptr: T::LinkPtr) -> Option<T::LinkPtr> {
     if let Some(left) = link_ops.left(ptr) {
         Some(last_child(link_ops, left))
     } else {
@@ -713,6 +990,31 @@ unsafe fn prev<T: RBTreeOps>(link_ops: &T, ptr: T::LinkPtr) -> Option<T::LinkPtr
     }
 }
 
+// Calls `f` on each node of the subtree rooted at `ptr` in order. Only the
+// left children are visited recursively, so the recursion depth is at most the
+// height of the tree.
+unsafe fn visit_subtree<T: RBTreeOps, B>(
+    link_ops: &T,
+    ptr: T::LinkPtr,
+    f: &mut impl FnMut(T::LinkPtr) -> ControlFlow<B>,
+) -> ControlFlow<B> {
+    let mut x = ptr;
+    loop {
+        if let Some(left) = link_ops.left(x) {
+            if let ControlFlow::Break(b) = visit_subtree(link_ops, left, f) {
+                return ControlFlow::Break(b);
+            }
+        }
+        if let ControlFlow::Break(b) = f(x) {
+            return ControlFlow::Break(b);
+        }
+        match link_ops.right(x) {
+            Some(right) => x = right,
+            None => return ControlFlow::Continue(()),
+        }
+    }
+}
+
 #[inline]
 unsafe fn replace_with<T: RBTreeOps>(
     link_ops: &mut T,
@@ -743,40 +1045,42 @@ unsafe fn replace_with<T: RBTreeOps>(
 }
 
 #[inline]
-unsafe fn insert_left<T: RBTreeOps>(
+pub(crate) unsafe fn insert_left<T: RBTreeOps>(
     link_ops: &mut T,
     ptr: T::LinkPtr,
     new: T::LinkPtr,
     root: &mut Option<T::LinkPtr>,
-) {
+) -> usize {
     link_ops.set_parent(new, Some(ptr));
     link_ops.set_color(new, Color::Red);
     link_ops.set_left(new, None);
     link_ops.set_right(new, None);
     link_ops.set_left(ptr, Some(new));
-    post_insert(link_ops, new, root);
+    post_insert(link_ops, new, root)
 }
 
 #[inline]
-unsafe fn insert_right<T: RBTreeOps>(
+pub(crate) unsafe fn insert_right<T: RBTreeOps>(
     link_ops: &mut T,
     ptr: T::LinkPtr,
     new: T::LinkPtr,
     root: &mut Option<T::LinkPtr>,
-) {
+) -> usize {
     link_ops.set_parent(new, Some(ptr));
     link_ops.set_color(new, Color::Red);
     link_ops.set_left(new, None);
     link_ops.set_right(new, None);
     link_ops.set_right(ptr, Some(new));
-    post_insert(link_ops, new, root);
+    post_insert(link_ops, new, root)
 }
 
 unsafe fn rotate_left<T: RBTreeOps>(
     link_ops: &mut T,
     ptr: T::LinkPtr,
     root: &mut Option<T::LinkPtr>,
+    rotations: &mut usize,
 ) {
+    *rotations += 1;
     let y = link_ops.right(ptr).unwrap_unchecked();
     link_ops.set_right(ptr, link_ops.left(y));
     if let Some(right) = link_ops.right(ptr) {
@@ -800,7 +1104,9 @@ unsafe fn rotate_right<T: RBTreeOps>(
     link_ops: &mut T,
     ptr: T::LinkPtr,
     root: &mut Option<T::LinkPtr>,
+    rotations: &mut usize,
 ) {
+    *rotations += 1;
     let y = link_ops.left(ptr).unwrap_unchecked();
     link_ops.set_left(ptr, link_ops.right(y));
     if let Some(left) = link_ops.left(ptr) {
@@ -821,11 +1127,14 @@ unsafe fn rotate_right<T: RBTreeOps>(
 }
 
 // This code is based on the red-black tree implementation in libc++
+//
+// Returns the number of rotations performed to rebalance the tree.
 unsafe fn post_insert<T: RBTreeOps>(
     link_ops: &mut T,
     ptr: T::LinkPtr,
     root: &mut Option<T::LinkPtr>,
-) {
+) -> usize {
+    let mut rotations = 0;
     let mut x = ptr;
     while let Some(parent) = link_ops.parent(x) {
         if link_ops.color(parent) != Color::Red {
@@ -853,13 +1162,13 @@ unsafe fn post_insert<T: RBTreeOps>(
             }
             if !is_left_child(link_ops, x, parent) {
                 x = parent;
-                rotate_left(link_ops, x, root);
+                rotate_left(link_ops, x, root, &mut rotations);
             }
             x = link_ops.parent(x).unwrap_unchecked();
             link_ops.set_color(x, Color::Black);
             x = link_ops.parent(x).unwrap_unchecked();
             link_ops.set_color(x, Color::Red);
-            rotate_right(link_ops, x, root);
+            rotate_right(link_ops, x, root, &mut rotations);
         } else {
             let y = link_ops.left(grandparent);
             if let Some(y) = y {
@@ -878,20 +1187,28 @@ unsafe fn post_insert<T: RBTreeOps>(
             }
             if is_left_child(link_ops, x, parent) {
                 x = parent;
-                rotate_right(link_ops, x, root);
+                rotate_right(link_ops, x, root, &mut rotations);
             }
             x = link_ops.parent(x).unwrap_unchecked();
             link_ops.set_color(x, Color::Black);
             x = link_ops.parent(x).unwrap_unchecked();
             link_ops.set_color(x, Color::Red);
-            rotate_left(link_ops, x, root);
+            rotate_left(link_ops, x, root, &mut rotations);
         }
         break;
     }
+    rotations
 }
 
 // This code is based on the red-black tree implementation in libc++
-unsafe fn remove<T: RBTreeOps>(link_ops: &mut T, ptr: T::LinkPtr, root: &mut Option<T::LinkPtr>) {
+//
+// Returns the number of rotations performed to rebalance the tree.
+pub(crate) unsafe fn remove<T: RBTreeOps>(
+    link_ops: &mut T,
+    ptr: T::LinkPtr,
+    root: &mut Option<T::LinkPtr>,
+) -> usize {
+    let mut rotations = 0;
     let y = if link_ops.left(ptr).is_none() || link_ops.right(ptr).is_none() {
         ptr
     } else {
@@ -949,7 +1266,7 @@ unsafe fn remove<T: RBTreeOps>(link_ops: &mut T, ptr: T::LinkPtr, root: &mut Opt
                     if link_ops.color(w) == Color::Red {
                         link_ops.set_color(w, Color::Black);
                         link_ops.set_color(w_parent, Color::Red);
-                        rotate_left(link_ops, w_parent, root);
+                        rotate_left(link_ops, w_parent, root, &mut rotations);
                         w = link_ops
                             .right(link_ops.left(w).unwrap_unchecked())
                             .unwrap_unchecked();
@@ -976,21 +1293,21 @@ unsafe fn remove<T: RBTreeOps>(link_ops: &mut T, ptr: T::LinkPtr, root: &mut Opt
                         if link_ops.right(w).map(|x| link_ops.color(x)) != Some(Color::Red) {
                             link_ops.set_color(link_ops.left(w).unwrap_unchecked(), Color::Black);
                             link_ops.set_color(w, Color::Red);
-                            rotate_right(link_ops, w, root);
+                            rotate_right(link_ops, w, root, &mut rotations);
                             w = link_ops.parent(w).unwrap_unchecked();
                             w_parent = link_ops.parent(w).unwrap_unchecked();
                         }
                         link_ops.set_color(w, link_ops.color(w_parent));
                         link_ops.set_color(w_parent, Color::Black);
                         link_ops.set_color(link_ops.right(w).unwrap_unchecked(), Color::Black);
-                        rotate_left(link_ops, w_parent, root);
+                        rotate_left(link_ops, w_parent, root, &mut rotations);
                         break;
                     }
                 } else {
                     if link_ops.color(w) == Color::Red {
                         link_ops.set_color(w, Color::Black);
                         link_ops.set_color(w_parent, Color::Red);
-                        rotate_right(link_ops, w_parent, root);
+                        rotate_right(link_ops, w_parent, root, &mut rotations);
                         w = link_ops
                             .left(link_ops.right(w).unwrap_unchecked())
                             .unwrap_unchecked();
@@ -1023,14 +1340,14 @@ unsafe fn remove<T: RBTreeOps>(link_ops: &mut T, ptr: T::LinkPtr, root: &mut Opt
                         if link_ops.left(w).map(|x| link_ops.color(x)) != Some(Color::Red) {
                             link_ops.set_color(link_ops.right(w).unwrap_unchecked(), Color::Black);
                             link_ops.set_color(w, Color::Red);
-                            rotate_left(link_ops, w, root);
+                            rotate_left(link_ops, w, root, &mut rotations);
                             w = link_ops.parent(w).unwrap_unchecked();
                             w_parent = link_ops.parent(w).unwrap_unchecked();
                         }
                         link_ops.set_color(w, link_ops.color(w_parent));
                         link_ops.set_color(w_parent, Color::Black);
                         link_ops.set_color(link_ops.left(w).unwrap_unchecked(), Color::Black);
-                        rotate_right(link_ops, w_parent, root);
+                        rotate_right(link_ops, w_parent, root, &mut rotations);
                         break;
                     }
                 }
@@ -1038,6 +1355,7 @@ unsafe fn remove<T: RBTreeOps>(link_ops: &mut T, ptr: T::LinkPtr, root: &mut Opt
         }
     }
     link_ops.release_link(ptr);
+    rotations
 }
 
 // =============================================================================
@@ -1051,6 +1369,8 @@ where
 {
     current: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
     tree: &'a RBTree<A>,
+    #[cfg(feature = "debug_generation")]
+    generation: usize,
 }
 
 impl<'a, A: Adapter> Clone for Cursor<'a, A>
@@ -1062,6 +1382,8 @@ where
         Cursor {
             current: self.current,
             tree: self.tree,
+            #[cfg(feature = "debug_generation")]
+            generation: self.generation,
         }
     }
 }
@@ -1083,9 +1405,26 @@ where
     /// object.
     #[inline]
     pub fn get(&self) -> Option<&'a <A::PointerOps as PointerOps>::Value> {
+        #[cfg(feature = "debug_generation")]
+        self.tree
+            .generation
+            .check(self.generation, self.tree.debug_name());
         Some(unsafe { &*self.tree.adapter.get_value(self.current?) })
     }
 
+    /// Returns a pinned reference to the object that the cursor is currently
+    /// pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the null
+    /// object.
+    #[inline]
+    pub fn get_pin(&self) -> Option<Pin<&'a <A::PointerOps as PointerOps>::Value>>
+    where
+        A::PointerOps: PinnedPointerOps,
+    {
+        Some(unsafe { Pin::new_unchecked(self.get()?) })
+    }
+
     /// Clones and returns the pointer that points to the element that the
     /// cursor is referencing.
     ///
@@ -1096,6 +1435,10 @@ where
     where
         <A::PointerOps as PointerOps>::Pointer: Clone,
     {
+        #[cfg(feature = "debug_generation")]
+        self.tree
+            .generation
+            .check(self.generation, self.tree.debug_name());
         let raw_pointer = unsafe { self.tree.adapter.get_value(self.current?) };
         Some(unsafe {
             crate::pointer_ops::clone_pointer_from_raw(self.tree.adapter.pointer_ops(), raw_pointer)
@@ -1109,6 +1452,11 @@ where
     /// element of the `RBTree` then this will move it to the null object.
     #[inline]
     pub fn move_next(&mut self) {
+        #[cfg(feature = "debug_generation")]
+        self.tree
+            .generation
+            .check(self.generation, self.tree.debug_name());
+        record_stat!(self.tree, cursor_moves);
         if let Some(current) = self.current {
             self.current = unsafe { next(self.tree.adapter.link_ops(), current) };
         } else if let Some(root) = self.tree.root {
@@ -1125,6 +1473,11 @@ where
     /// element of the `RBTree` then this will move it to the null object.
     #[inline]
     pub fn move_prev(&mut self) {
+        #[cfg(feature = "debug_generation")]
+        self.tree
+            .generation
+            .check(self.generation, self.tree.debug_name());
+        record_stat!(self.tree, cursor_moves);
         if let Some(current) = self.current {
             self.current = unsafe { prev(self.tree.adapter.link_ops(), current) };
         } else if let Some(root) = self.tree.root {
@@ -1188,6 +1541,22 @@ where
         Some(unsafe { &*self.tree.adapter.get_value(self.current?) })
     }
 
+    /// Clones and returns the pointer that points to the element that the
+    /// cursor is referencing.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the null
+    /// object.
+    #[inline]
+    pub fn clone_pointer(&self) -> Option<<A::PointerOps as PointerOps>::Pointer>
+    where
+        <A::PointerOps as PointerOps>::Pointer: Clone,
+    {
+        let raw_pointer = unsafe { self.tree.adapter.get_value(self.current?) };
+        Some(unsafe {
+            crate::pointer_ops::clone_pointer_from_raw(self.tree.adapter.pointer_ops(), raw_pointer)
+        })
+    }
+
     /// Returns a mutable reference to the object that the cursor is currently
     /// pointing to.
     ///
@@ -1234,6 +1603,56 @@ where
         )
     }
 
+    /// Returns a pinned mutable reference to the object that the cursor is
+    /// currently pointing to.
+    ///
+    /// This returns None if the cursor is currently pointing to the null
+    /// object.
+    ///
+    /// # Safety
+    ///
+    /// You must not modify any links that are linked
+    #[inline]
+    pub unsafe fn get_pin_mut(&mut self) -> Option<Pin<&mut <A::PointerOps as PointerOps>::Value>>
+    where
+        A::PointerOps: ExclusivePinnedPointerOps,
+    {
+        let value = self.tree.adapter.get_value(self.current?);
+        Some(Pin::new_unchecked(
+            &mut *self.tree.adapter.pointer_ops().get_pin_mut(value),
+        ))
+    }
+
+    /// Returns a mutable reference to the object that the cursor is currently
+    /// pointing to, cloning it first if it is shared.
+    ///
+    /// If the object is not uniquely owned by the collection, it is replaced
+    /// in the `RBTree` by a clone and the collection's pointer to the
+    /// original object is dropped. Other pointers to the original object keep
+    /// seeing the old value.
+    ///
+    /// This returns None if the cursor is currently pointing to the null
+    /// object.
+    ///
+    /// The clone must compare equal to the original object, so that the
+    /// elements of the `RBTree` stay in order.
+    ///
+    /// # Safety
+    ///
+    /// You must not modify any links that are linked
+    #[inline]
+    pub unsafe fn make_mut(&mut self) -> Option<&mut <A::PointerOps as PointerOps>::Value>
+    where
+        A::PointerOps: CowPointerOps,
+    {
+        let value = self.tree.adapter.get_value(self.current?);
+        if self.tree.adapter.pointer_ops().try_get_mut(value).is_none() {
+            let clone = self.tree.adapter.pointer_ops().clone_value(&*value);
+            let _ = self.replace_with(clone);
+        }
+        self.try_get_mut()
+    }
+
     /// Returns a read-only cursor pointing to the current element.
     ///
     /// The lifetime of the returned `Cursor` is bound to that of the
@@ -1244,6 +1663,21 @@ where
         Cursor {
             current: self.current,
             tree: self.tree,
+            #[cfg(feature = "debug_generation")]
+            generation: self.tree.generation.get(),
+        }
+    }
+
+    /// Consumes `CursorMut` and returns a read-only cursor pointing to the
+    /// current element. Unlike [as_cursor](Self::as_cursor), the returned
+    /// cursor's lifetime is tied to `RBTree`'s lifetime.
+    #[inline]
+    pub fn into_cursor(self) -> Cursor<'a, A> {
+        Cursor {
+            current: self.current,
+            tree: self.tree,
+            #[cfg(feature = "debug_generation")]
+            generation: self.tree.generation.get(),
         }
     }
 
@@ -1254,6 +1688,7 @@ where
     /// element of the `RBTree` then this will move it to the null object.
     #[inline]
     pub fn move_next(&mut self) {
+        record_stat!(self.tree, cursor_moves);
         if let Some(current) = self.current {
             self.current = unsafe { next(self.tree.adapter.link_ops(), current) };
         } else if let Some(root) = self.tree.root {
@@ -1270,6 +1705,7 @@ where
     /// element of the `RBTree` then this will move it to the null object.
     #[inline]
     pub fn move_prev(&mut self) {
+        record_stat!(self.tree, cursor_moves);
         if let Some(current) = self.current {
             self.current = unsafe { prev(self.tree.adapter.link_ops(), current) };
         } else if let Some(root) = self.tree.root {
@@ -1312,28 +1748,60 @@ where
     /// is removed and `None` is returned.
     #[inline]
     pub fn remove(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
-        unsafe {
-            if let Some(current) = self.current {
-                let next = next(self.tree.adapter.link_ops(), current);
-                let result = current;
-                remove(
-                    self.tree.adapter.link_ops_mut(),
-                    current,
-                    &mut self.tree.root,
-                );
-                self.current = next;
-                Some(
-                    self.tree
-                        .adapter
-                        .pointer_ops()
-                        .from_raw(self.tree.adapter.get_value(result)),
-                )
-            } else {
-                None
-            }
+        self.check_current_owner();
+        structural!(self.tree, unsafe {
+            self.unlink_current()
+                .map(|current| crate::adapter::unlinked_pointer(&self.tree.adapter, current))
+        })
+    }
+
+    /// Removes the current element from the `RBTree`, converting it back with
+    /// `TryPointerOps::try_from_raw`.
+    ///
+    /// This is the same as `remove`, except that the conversion back into a
+    /// pointer may fail, in which case the error is returned. The element is
+    /// removed from the `RBTree` either way.
+    ///
+    /// If the cursor is currently pointing to the null object then no element
+    /// is removed and `None` is returned.
+    #[inline]
+    pub fn remove_checked(&mut self) -> Option<TryPointer<A>>
+    where
+        A::PointerOps: TryPointerOps,
+    {
+        self.check_current_owner();
+        structural!(self.tree, unsafe {
+            self.unlink_current()
+                .map(|current| crate::adapter::try_unlinked_pointer(&self.tree.adapter, current))
+        })
+    }
+
+    // Checks that the current element is in this tree, for the `debug_owner`
+    // feature. This is done before modifying the tree, so that the panic
+    // doesn't poison it.
+    #[inline]
+    #[track_caller]
+    fn check_current_owner(&self) {
+        if let Some(current) = self.current {
+            unsafe { self.tree.check_owner(current) };
         }
     }
 
+    // Unlinks the current element and moves the cursor to the next one,
+    // returning the unlinked element.
+    #[inline]
+    unsafe fn unlink_current(&mut self) -> Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr> {
+        let current = self.current?;
+        self.current = next(self.tree.adapter.link_ops(), current);
+        let rotations = remove(
+            self.tree.adapter.link_ops_mut(),
+            current,
+            &mut self.tree.root,
+        );
+        self.tree.record_remove(rotations);
+        Some(current)
+    }
+
     /// Removes the current element from the `RBTree` and inserts another
     /// object in its place.
     ///
@@ -1360,68 +1828,86 @@ where
     {
         unsafe {
             if let Some(current) = self.current {
+                self.tree.check_owner(current);
                 let new = self.tree.node_from_value(val);
-                let result = current;
-                replace_with(
-                    self.tree.adapter.link_ops_mut(),
-                    current,
-                    new,
-                    &mut self.tree.root,
-                );
-                self.current = Some(new);
-                Ok(self
-                    .tree
-                    .adapter
-                    .pointer_ops()
-                    .from_raw(self.tree.adapter.get_value(result)))
+                Ok(structural!(self.tree, self.replace_current(current, new)))
             } else {
                 Err(val)
             }
         }
     }
 
-    /// Inserts a new element into the `RBTree` after the current one.
-    ///
-    /// When using this function you must ensure that the elements in the
-    /// collection are maintained in increasing order. Failure to do this may
-    /// lead to `find`, `upper_bound`, `lower_bound` and `range` returning
-    /// incorrect results.
+    /// Removes the current element from the `RBTree` and inserts another
+    /// object in its place, without panicking.
     ///
-    /// If the cursor is pointing at the null object then the new element is
-    /// inserted at the start of the `RBTree`.
+    /// This is the same as `replace_with`, except that an error containing
+    /// `val` is returned if the new element is already linked to an intrusive
+    /// collection, in which case the `RBTree` is left unchanged.
     ///
     /// # Panics
     ///
-    /// Panics if the new element is already linked to a different intrusive
-    /// collection.
+    /// With the `debug_owner` feature, panics if the current element was
+    /// linked by a different `RBTree`, like `replace_with`.
     #[inline]
-    pub fn insert_after(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+    pub fn try_replace_with(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<
+        <A::PointerOps as PointerOps>::Pointer,
+        InsertError<<A::PointerOps as PointerOps>::Pointer>,
+    > {
         unsafe {
-            let new = self.tree.node_from_value(val);
-            let link_ops = self.tree.adapter.link_ops_mut();
-
-            if let Some(root) = self.tree.root {
-                if let Some(current) = self.current {
-                    if link_ops.right(current).is_some() {
-                        let next = next(link_ops, current).unwrap_unchecked();
-                        insert_left(link_ops, next, new, &mut self.tree.root);
-                    } else {
-                        insert_right(link_ops, current, new, &mut self.tree.root);
-                    }
-                } else {
-                    insert_left(
-                        link_ops,
-                        first_child(link_ops, root),
-                        new,
-                        &mut self.tree.root,
-                    );
+            match self.current {
+                Some(current) => {
+                    self.tree.check_owner(current);
+                    self.tree
+                        .try_node_from_value(val)
+                        .map(|new| structural!(self.tree, self.replace_current(current, new)))
                 }
-            } else {
-                self.tree.insert_root(new);
+                None => Err(InsertError::NullCursor(val)),
             }
         }
     }
 
+    #[inline]
+    unsafe fn replace_current(
+        &mut self,
+        current: <A::LinkOps as link_ops::LinkOps>::LinkPtr,
+        new: <A::LinkOps as link_ops::LinkOps>::LinkPtr,
+    ) -> <A::PointerOps as PointerOps>::Pointer {
+        replace_with(
+            self.tree.adapter.link_ops_mut(),
+            current,
+            new,
+            &mut self.tree.root,
+        );
+        self.current = Some(new);
+        record_stat!(self.tree, removals);
+        crate::adapter::unlinked_pointer(&self.tree.adapter, current)
+    }
+
+    /// Inserts a new element into the `RBTree` after the current one.
+    ///
+    /// When using this function you must ensure that the elements in the
+    /// collection are maintained in increasing order. Failure to do this may
+    /// lead to `find`, `upper_bound`, `lower_bound` and `range` returning
+    /// incorrect results.
+    ///
+    /// If the cursor is pointing at the null object then the new element is
+    /// inserted at the start of the `RBTree`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new element is already linked to a different intrusive
+    /// collection.
+    #[inline]
+    pub fn insert_after(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        unsafe {
+            let new = self.tree.node_from_value(val);
+            structural!(self.tree, self.link_node_after(new));
+        }
+    }
+
     /// Inserts a new element into the `RBTree` before the current one.
     ///
     /// When using this function you must ensure that the elements in the
@@ -1440,28 +1926,227 @@ where
     pub fn insert_before(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
         unsafe {
             let new = self.tree.node_from_value(val);
-            let link_ops = self.tree.adapter.link_ops_mut();
+            structural!(self.tree, self.link_node_before(new));
+        }
+    }
 
-            if let Some(root) = self.tree.root {
-                if let Some(current) = self.current {
-                    if link_ops.left(current).is_some() {
-                        let prev = prev(link_ops, current).unwrap_unchecked();
-                        insert_right(link_ops, prev, new, &mut self.tree.root);
-                    } else {
-                        insert_left(link_ops, current, new, &mut self.tree.root);
-                    }
+    /// Inserts a new element into the `RBTree` after the current one, without
+    /// panicking.
+    ///
+    /// This is the same as `insert_after`, except that an error containing
+    /// `val` is returned if the new element is already linked to an intrusive
+    /// collection.
+    #[inline]
+    pub fn try_insert_after(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<(), InsertError<<A::PointerOps as PointerOps>::Pointer>> {
+        unsafe {
+            self.tree
+                .try_node_from_value(val)
+                .map(|new| structural!(self.tree, self.link_node_after(new)))
+        }
+    }
+
+    /// Inserts a new element into the `RBTree` before the current one,
+    /// without panicking.
+    ///
+    /// This is the same as `insert_before`, except that an error containing
+    /// `val` is returned if the new element is already linked to an intrusive
+    /// collection.
+    #[inline]
+    pub fn try_insert_before(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<(), InsertError<<A::PointerOps as PointerOps>::Pointer>> {
+        unsafe {
+            self.tree
+                .try_node_from_value(val)
+                .map(|new| structural!(self.tree, self.link_node_before(new)))
+        }
+    }
+
+    #[inline]
+    unsafe fn link_node_after(&mut self, new: <A::LinkOps as link_ops::LinkOps>::LinkPtr) {
+        let link_ops = self.tree.adapter.link_ops_mut();
+
+        let rotations = if let Some(root) = self.tree.root {
+            if let Some(current) = self.current {
+                if link_ops.right(current).is_some() {
+                    let next = next(link_ops, current).unwrap_unchecked();
+                    insert_left(link_ops, next, new, &mut self.tree.root)
                 } else {
-                    insert_right(
-                        link_ops,
-                        last_child(link_ops, root),
-                        new,
-                        &mut self.tree.root,
-                    );
+                    insert_right(link_ops, current, new, &mut self.tree.root)
                 }
             } else {
-                self.tree.insert_root(new);
+                insert_left(
+                    link_ops,
+                    first_child(link_ops, root),
+                    new,
+                    &mut self.tree.root,
+                )
             }
+        } else {
+            self.tree.insert_root(new);
+            0
+        };
+        self.tree.record_insert(new, rotations);
+    }
+
+    #[inline]
+    unsafe fn link_node_before(&mut self, new: <A::LinkOps as link_ops::LinkOps>::LinkPtr) {
+        let link_ops = self.tree.adapter.link_ops_mut();
+
+        let rotations = if let Some(root) = self.tree.root {
+            if let Some(current) = self.current {
+                if link_ops.left(current).is_some() {
+                    let prev = prev(link_ops, current).unwrap_unchecked();
+                    insert_right(link_ops, prev, new, &mut self.tree.root)
+                } else {
+                    insert_left(link_ops, current, new, &mut self.tree.root)
+                }
+            } else {
+                insert_right(
+                    link_ops,
+                    last_child(link_ops, root),
+                    new,
+                    &mut self.tree.root,
+                )
+            }
+        } else {
+            self.tree.insert_root(new);
+            0
+        };
+        self.tree.record_insert(new, rotations);
+    }
+
+    /// Moves the current element into another `RBTree`, inserting it after
+    /// the element that `dest` is pointing to.
+    ///
+    /// The element is moved without converting it back into an owned pointer,
+    /// so reference counts are left untouched. The two trees may use different
+    /// links in the object, or the same one. The cursor is moved to the next
+    /// element, as with `remove`. This returns `false` if the cursor is
+    /// currently pointing to the null object.
+    ///
+    /// When using this function you must ensure that the elements in `dest`
+    /// are maintained in increasing order. Failure to do this may lead to
+    /// `find`, `upper_bound`, `lower_bound` and `range` returning incorrect
+    /// results.
+    ///
+    /// If `dest` is pointing at the null object then the element is inserted
+    /// at the start of its `RBTree`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element is already linked to a different intrusive
+    /// collection through the link used by `dest`. The element is left in
+    /// this tree in that case.
+    ///
+    /// Also panics if the `PointerOps` of the two trees don't have the same
+    /// state (see `PointerOps::same_state`), in which case neither tree is
+    /// modified.
+    #[inline]
+    pub fn transfer_after<B>(&mut self, dest: &mut CursorMut<'_, B>) -> bool
+    where
+        B: Adapter<PointerOps = A::PointerOps>,
+        B::LinkOps: RBTreeOps,
+    {
+        crate::adapter::check_transfer(&self.tree.adapter, &dest.tree.adapter);
+        self.check_current_owner();
+        let result = structural!(
+            self.tree,
+            structural!(dest.tree, unsafe {
+                let result = self.unlink_for_transfer(dest.tree);
+                if let Some(Ok(new)) = result {
+                    dest.link_node_after(new);
+                }
+                result
+            })
+        );
+        unsafe { dest.tree.transferred(result) }
+    }
+
+    /// Moves the current element into another `RBTree`, inserting it before
+    /// the element that `dest` is pointing to.
+    ///
+    /// This is the same as `transfer_after`, except that if `dest` is
+    /// pointing at the null object then the element is inserted at the end of
+    /// its `RBTree`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element is already linked to a different intrusive
+    /// collection through the link used by `dest`. The element is left in
+    /// this tree in that case.
+    ///
+    /// Also panics if the `PointerOps` of the two trees don't have the same
+    /// state (see `PointerOps::same_state`), in which case neither tree is
+    /// modified.
+    #[inline]
+    pub fn transfer_before<B>(&mut self, dest: &mut CursorMut<'_, B>) -> bool
+    where
+        B: Adapter<PointerOps = A::PointerOps>,
+        B::LinkOps: RBTreeOps,
+    {
+        crate::adapter::check_transfer(&self.tree.adapter, &dest.tree.adapter);
+        self.check_current_owner();
+        let result = structural!(
+            self.tree,
+            structural!(dest.tree, unsafe {
+                let result = self.unlink_for_transfer(dest.tree);
+                if let Some(Ok(new)) = result {
+                    dest.link_node_before(new);
+                }
+                result
+            })
+        );
+        unsafe { dest.tree.transferred(result) }
+    }
+
+    // Unlinks the current element and acquires its link in `dest`. The
+    // element is put back if the link can't be acquired, which happens after
+    // unlinking so that both trees can use the same link, and its link in
+    // `dest` is returned as an error.
+    #[inline]
+    unsafe fn unlink_for_transfer<B>(&mut self, dest: &mut RBTree<B>) -> crate::adapter::Transfer<B>
+    where
+        B: Adapter<PointerOps = A::PointerOps>,
+        B::LinkOps: RBTreeOps,
+    {
+        use link_ops::LinkOps;
+
+        let current = self.current?;
+        let value = self.tree.adapter.get_value(current);
+        self.current = next(self.tree.adapter.link_ops(), current);
+        let rotations = remove(
+            self.tree.adapter.link_ops_mut(),
+            current,
+            &mut self.tree.root,
+        );
+
+        let new = dest.adapter.get_link(value);
+        if !dest.adapter.link_ops_mut().acquire_link(new) {
+            let acquired = self.tree.adapter.link_ops_mut().acquire_link(current);
+            debug_assert!(acquired);
+            self.link_node_before(current);
+            self.current = Some(current);
+            return Some(Err(new));
+        }
+        #[cfg(feature = "debug_owner")]
+        {
+            let owner = dest.owner_id();
+            dest.adapter.link_ops_mut().set_owner(new, owner);
         }
+        // If a hook panics, the guard drops the element, which is then in
+        // neither tree.
+        let guard = crate::adapter::AcquiredLink::new(&mut dest.adapter, new);
+        self.tree.adapter.on_remove(&*value);
+        guard.on_insert();
+        guard.disarm();
+        self.tree.record_remove(rotations);
+        record_stat!(dest, insertions);
+        Some(Ok(new))
     }
 
     /// Consumes `CursorMut` and returns a reference to the object that
@@ -1530,6 +2215,8 @@ where
         Cursor {
             current: self.current,
             tree: &self.tree,
+            #[cfg(feature = "debug_generation")]
+            generation: self.tree.generation.get(),
         }
     }
 
@@ -1553,6 +2240,202 @@ where
     A::LinkOps: RBTreeOps,
 {
 }
+unsafe impl<A: Adapter> Sync for CursorOwning<A>
+where
+    RBTree<A>: Sync,
+    A::LinkOps: RBTreeOps,
+{
+}
+
+// Formats a cursor of `tree` pointing to `current`, along with its neighbors
+// in the order of the tree.
+fn debug_cursor<A: Adapter>(
+    f: &mut fmt::Formatter<'_>,
+    name: &str,
+    tree: &RBTree<A>,
+    current: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+) -> fmt::Result
+where
+    A::LinkOps: RBTreeOps,
+{
+    let (prev, next) = cursor_neighbors(tree, current);
+    crate::adapter::debug_cursor(f, name, &tree.adapter, current, Some(prev), next)
+}
+
+// Same as `debug_cursor`, for `defmt`.
+#[cfg(feature = "defmt")]
+fn defmt_cursor<A: Adapter>(
+    f: defmt::Formatter<'_>,
+    name: &str,
+    tree: &RBTree<A>,
+    current: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+) where
+    A::LinkOps: RBTreeOps,
+{
+    let (prev, next) = cursor_neighbors(tree, current);
+    crate::adapter::defmt_cursor(f, name, &tree.adapter, current, Some(prev), next)
+}
+
+// Returns the neighbors of the element of `tree` at `current`, as shown when
+// formatting a cursor.
+#[allow(clippy::type_complexity)]
+fn cursor_neighbors<A: Adapter>(
+    tree: &RBTree<A>,
+    current: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+) -> (
+    Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+    Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+)
+where
+    A::LinkOps: RBTreeOps,
+{
+    let link_ops = tree.adapter.link_ops();
+    unsafe {
+        match current {
+            Some(current) => (prev(link_ops, current), next(link_ops, current)),
+            None => (
+                tree.root.map(|root| last_child(link_ops, root)),
+                tree.root.map(|root| first_child(link_ops, root)),
+            ),
+        }
+    }
+}
+
+impl<'a, A: Adapter> CursorOps for Cursor<'a, A>
+where
+    A::LinkOps: RBTreeOps,
+{
+    type Adapter = A;
+
+    #[inline]
+    fn is_null(&self) -> bool {
+        Cursor::is_null(self)
+    }
+
+    #[inline]
+    fn get(&self) -> Option<&<A::PointerOps as PointerOps>::Value> {
+        Cursor::get(self)
+    }
+
+    #[inline]
+    fn move_next(&mut self) {
+        Cursor::move_next(self)
+    }
+}
+
+impl<'a, A: Adapter> DoubleEndedCursorOps for Cursor<'a, A>
+where
+    A::LinkOps: RBTreeOps,
+{
+    #[inline]
+    fn move_prev(&mut self) {
+        Cursor::move_prev(self)
+    }
+}
+
+impl<'a, A: Adapter> CursorOps for CursorMut<'a, A>
+where
+    A::LinkOps: RBTreeOps,
+{
+    type Adapter = A;
+
+    #[inline]
+    fn is_null(&self) -> bool {
+        CursorMut::is_null(self)
+    }
+
+    #[inline]
+    fn get(&self) -> Option<&<A::PointerOps as PointerOps>::Value> {
+        CursorMut::get(self)
+    }
+
+    #[inline]
+    fn move_next(&mut self) {
+        CursorMut::move_next(self)
+    }
+}
+
+impl<'a, A: Adapter> DoubleEndedCursorOps for CursorMut<'a, A>
+where
+    A::LinkOps: RBTreeOps,
+{
+    #[inline]
+    fn move_prev(&mut self) {
+        CursorMut::move_prev(self)
+    }
+}
+
+impl<'a, A: Adapter> CursorMutOps for CursorMut<'a, A>
+where
+    A::LinkOps: RBTreeOps,
+{
+    #[inline]
+    fn remove(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        CursorMut::remove(self)
+    }
+
+    #[inline]
+    fn insert_after(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        CursorMut::insert_after(self, val)
+    }
+}
+
+impl<A: Adapter> fmt::Debug for Cursor<'_, A>
+where
+    A::LinkOps: RBTreeOps,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_cursor(f, "Cursor", self.tree, self.current)
+    }
+}
+
+impl<A: Adapter> fmt::Debug for CursorMut<'_, A>
+where
+    A::LinkOps: RBTreeOps,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_cursor(f, "CursorMut", self.tree, self.current)
+    }
+}
+
+impl<A: Adapter> fmt::Debug for CursorOwning<A>
+where
+    A::LinkOps: RBTreeOps,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_cursor(f, "CursorOwning", &self.tree, self.current)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<A: Adapter> defmt::Format for Cursor<'_, A>
+where
+    A::LinkOps: RBTreeOps,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt_cursor(f, "Cursor", self.tree, self.current)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<A: Adapter> defmt::Format for CursorMut<'_, A>
+where
+    A::LinkOps: RBTreeOps,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt_cursor(f, "CursorMut", self.tree, self.current)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<A: Adapter> defmt::Format for CursorOwning<A>
+where
+    A::LinkOps: RBTreeOps,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt_cursor(f, "CursorOwning", &self.tree, self.current)
+    }
+}
 
 // =============================================================================
 // RBTree
@@ -1575,6 +2458,16 @@ where
 {
     root: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
     adapter: A,
+    #[cfg(debug_assertions)]
+    debug_name: Option<&'static str>,
+    #[cfg(feature = "stats")]
+    stats: StatsCounters,
+    #[cfg(feature = "poison")]
+    poison: Poison,
+    #[cfg(feature = "debug_generation")]
+    generation: Generation,
+    #[cfg(feature = "debug_owner")]
+    owner: usize,
 }
 
 impl<A: Adapter> RBTree<A>
@@ -1586,41 +2479,218 @@ where
         &mut self,
         val: <A::PointerOps as PointerOps>::Pointer,
     ) -> <A::LinkOps as link_ops::LinkOps>::LinkPtr {
-        use link_ops::LinkOps;
+        unsafe {
+            let link = self.acquire_node(val);
+            crate::adapter::acquired_on_insert(&mut self.adapter, link);
+            record_stat!(self, insertions);
+
+            link
+        }
+    }
 
+    // Same as `node_from_value`, but hands `val` back if it is already linked
+    // instead of panicking.
+    #[inline]
+    fn try_node_from_value(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<
+        <A::LinkOps as link_ops::LinkOps>::LinkPtr,
+        InsertError<<A::PointerOps as PointerOps>::Pointer>,
+    > {
         unsafe {
-            let raw = self.adapter.pointer_ops().into_raw(val);
-            let link = self.adapter.get_link(raw);
+            let link = self.try_acquire_node(val)?;
+            crate::adapter::acquired_on_insert(&mut self.adapter, link);
+            record_stat!(self, insertions);
 
-            if !self.adapter.link_ops_mut().acquire_link(link) {
-                // convert the node back into a pointer
-                self.adapter.pointer_ops().from_raw(raw);
+            Ok(link)
+        }
+    }
 
-                panic!("attempted to insert an object that is already linked");
+    // Same as `acquire_node`, but hands `val` back if it is already linked
+    // instead of panicking.
+    #[inline]
+    unsafe fn try_acquire_node(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<
+        <A::LinkOps as link_ops::LinkOps>::LinkPtr,
+        InsertError<<A::PointerOps as PointerOps>::Pointer>,
+    > {
+        use link_ops::LinkOps;
+
+        check_poison!(self);
+        let raw = self.adapter.pointer_ops().into_raw(val);
+        let link = self.adapter.get_link(raw);
+
+        if !self.adapter.link_ops_mut().acquire_link(link) {
+            let val = self.adapter.pointer_ops().from_raw(raw);
+            return Err(InsertError::AlreadyLinked(val));
+        }
+        #[cfg(feature = "debug_owner")]
+        {
+            let owner = self.owner_id();
+            self.adapter.link_ops_mut().set_owner(link, owner);
+        }
+
+        Ok(link)
+    }
+
+    // Converts a new element into its link and acquires it, without calling
+    // the `on_insert` hook of the adapter.
+    #[inline]
+    unsafe fn acquire_node(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> <A::LinkOps as link_ops::LinkOps>::LinkPtr {
+        use link_ops::LinkOps;
+
+        check_poison!(self);
+        let raw = self.adapter.pointer_ops().into_raw(val);
+        let link = self.adapter.get_link(raw);
+
+        if !self.adapter.link_ops_mut().acquire_link(link) {
+            // convert the node back into a pointer, after reading the
+            // owner of the link for the panic message
+            let owner = self.owner_of(link);
+            self.adapter.pointer_ops().from_raw(raw);
+
+            crate::adapter::already_linked(&self.adapter, self.debug_name(), raw, owner);
+        }
+        #[cfg(feature = "debug_owner")]
+        {
+            let owner = self.owner_id();
+            self.adapter.link_ops_mut().set_owner(link, owner);
+        }
+
+        link
+    }
+
+    // Returns whether `unlink_for_transfer` moved an element into this tree,
+    // and panics if the element was already linked in it. This is done after
+    // both trees are consistent again, so that the panic doesn't poison them.
+    #[inline]
+    #[track_caller]
+    unsafe fn transferred(&self, result: crate::adapter::Transfer<A>) -> bool {
+        match result {
+            Some(Ok(_)) => true,
+            Some(Err(link)) => crate::adapter::already_linked(
+                &self.adapter,
+                self.debug_name(),
+                self.adapter.get_value(link),
+                self.owner_of(link),
+            ),
+            None => false,
+        }
+    }
+
+    // Returns the identifier of this tree for the `debug_owner` feature,
+    // assigning one if needed.
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    fn owner_id(&mut self) -> usize {
+        if self.owner == 0 {
+            self.owner = link_ops::new_owner_id();
+        }
+        self.owner
+    }
+
+    // Records `owner` as the identifier of this tree and the owner of all its
+    // elements, for the `debug_owner` feature. Only needed for the elements
+    // inserted by C code.
+    #[cfg(all(feature = "debug_owner", feature = "ffi"))]
+    fn set_owner_all(&mut self, owner: usize) {
+        self.owner = owner;
+        let mut current = self
+            .root
+            .map(|root| unsafe { first_child(self.adapter.link_ops(), root) });
+        while let Some(x) = current {
+            unsafe {
+                self.adapter.link_ops_mut().set_owner(x, owner);
+                current = next(self.adapter.link_ops(), x);
             }
+        }
+    }
 
-            link
+    // Checks that `link` is in this tree, for the `debug_owner` feature.
+    #[inline]
+    #[track_caller]
+    unsafe fn check_owner(&self, link: <A::LinkOps as link_ops::LinkOps>::LinkPtr) {
+        #[cfg(feature = "debug_owner")]
+        if let Some(owner) = self.adapter.link_ops().owner(link) {
+            if owner == 0 || owner != self.owner {
+                crate::adapter::wrong_owner(&self.adapter, self.debug_name());
+            }
         }
+        #[cfg(not(feature = "debug_owner"))]
+        let _ = link;
     }
 
-    /// Creates an empty `RBTree`.
-    #[cfg(not(feature = "nightly"))]
+    // Returns the identifier of the collection `link` is in along with the one
+    // of this tree, for the panic message of `already_linked`.
     #[inline]
-    pub fn new(adapter: A) -> RBTree<A> {
-        RBTree {
-            root: None,
-            adapter,
+    unsafe fn owner_of(
+        &self,
+        link: <A::LinkOps as link_ops::LinkOps>::LinkPtr,
+    ) -> Option<(usize, usize)> {
+        #[cfg(feature = "debug_owner")]
+        {
+            self.adapter
+                .link_ops()
+                .owner(link)
+                .map(|owner| (owner, self.owner))
+        }
+        #[cfg(not(feature = "debug_owner"))]
+        {
+            let _ = link;
+            None
         }
     }
 
     /// Creates an empty `RBTree`.
-    #[cfg(feature = "nightly")]
+    ///
+    /// This is a `const fn`, which allows the collection to be used in a
+    /// `static` when combined with the `NEW` constant of an adapter.
     #[inline]
     pub const fn new(adapter: A) -> RBTree<A> {
         RBTree {
             root: None,
             adapter,
+            #[cfg(debug_assertions)]
+            debug_name: None,
+            #[cfg(feature = "stats")]
+            stats: StatsCounters::new(),
+            #[cfg(feature = "poison")]
+            poison: Poison::new(),
+            #[cfg(feature = "debug_generation")]
+            generation: Generation::new(),
+            #[cfg(feature = "debug_owner")]
+            owner: 0,
+        }
+    }
+
+    /// Sets a name for this `RBTree` which is used in panic messages.
+    ///
+    /// The name is only stored in builds with debug assertions enabled.
+    #[inline]
+    pub fn set_debug_name(&mut self, name: &'static str) {
+        #[cfg(debug_assertions)]
+        {
+            self.debug_name = Some(name);
         }
+        #[cfg(not(debug_assertions))]
+        let _ = name;
+    }
+
+    /// Returns the name set by `set_debug_name`.
+    ///
+    /// This always returns `None` in builds without debug assertions.
+    #[inline]
+    pub fn debug_name(&self) -> Option<&'static str> {
+        #[cfg(debug_assertions)]
+        return self.debug_name;
+        #[cfg(not(debug_assertions))]
+        None
     }
 
     /// Returns `true` if the `RBTree` is empty.
@@ -1629,12 +2699,127 @@ where
         self.root.is_none()
     }
 
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub(crate) fn adapter(&self) -> &A {
+        &self.adapter
+    }
+
+    /// Returns the memory used by the `RBTree` and the links of its elements.
+    ///
+    /// This counts the elements by walking the tree, in linear time.
+    #[inline]
+    pub fn memory_overhead(&self) -> MemoryOverhead {
+        MemoryOverhead::new::<A::LinkOps, Self>(self.iter().count())
+    }
+
+    /// Returns statistics about the operations performed on the `RBTree`.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn stats(&self) -> Stats {
+        self.stats.get()
+    }
+
+    /// Resets the statistics returned by `stats` to zero.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn reset_stats(&mut self) {
+        self.stats = StatsCounters::new();
+    }
+
+    /// Returns `true` if a panic escaped an operation which modified the
+    /// links of the `RBTree`.
+    ///
+    /// Operations which modify the links of a poisoned tree panic, since
+    /// they may be inconsistent. Reading the tree is still allowed.
+    /// Panics raised before the links are modified, such as when inserting
+    /// an element which is already linked, don't poison the tree.
+    #[cfg(feature = "poison")]
+    #[inline]
+    pub fn is_poisoned(&self) -> bool {
+        self.poison.get()
+    }
+
+    /// Clears the poisoned state of the `RBTree`.
+    ///
+    /// This should only be done once the tree is known to be consistent,
+    /// for example after checking it with `assert_valid`.
+    #[cfg(feature = "poison")]
+    #[inline]
+    pub fn clear_poison(&mut self) {
+        self.poison.clear();
+    }
+
+    /// Checks the integrity of the tree and returns the number of elements
+    /// in it.
+    ///
+    /// This walks the whole tree and verifies that the `parent` pointer of
+    /// every element points back to the element above it, that the root is
+    /// black, that no red element has a red child, and that every path from
+    /// the root to a leaf goes through the same number of black elements. It
+    /// is meant for debugging, for example after manipulating links directly
+    /// through `RBTreeOps`. The order of the keys isn't checked, since that
+    /// requires a `KeyAdapter`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tree is corrupted.
+    pub fn assert_valid(&self) -> usize {
+        // Returns the number of elements and the black height of the subtree
+        // rooted at `link`.
+        fn check_subtree<T: RBTreeOps>(
+            link_ops: &T,
+            link: Option<T::LinkPtr>,
+            parent: Option<T::LinkPtr>,
+        ) -> (usize, usize) {
+            let link = match link {
+                Some(link) => link,
+                None => return (0, 1),
+            };
+            unsafe {
+                assert!(
+                    link_ops.parent(link) == parent,
+                    "red-black tree is corrupted: the parent pointer of an element doesn't point to the element above it",
+                );
+                let color = link_ops.color(link);
+                if color == Color::Red {
+                    assert!(
+                        parent.map_or(true, |parent| link_ops.color(parent) == Color::Black),
+                        "red-black tree is corrupted: a red element has a red child",
+                    );
+                }
+                let (left_len, left_height) =
+                    check_subtree(link_ops, link_ops.left(link), Some(link));
+                let (right_len, right_height) =
+                    check_subtree(link_ops, link_ops.right(link), Some(link));
+                assert!(
+                    left_height == right_height,
+                    "red-black tree is corrupted: the black heights of the subtrees of an element differ ({} and {})",
+                    left_height,
+                    right_height,
+                );
+                let height = left_height + (color == Color::Black) as usize;
+                (left_len + right_len + 1, height)
+            }
+        }
+
+        if let Some(root) = self.root {
+            assert!(
+                unsafe { self.adapter.link_ops().color(root) } == Color::Black,
+                "red-black tree is corrupted: the root is red",
+            );
+        }
+        check_subtree(self.adapter.link_ops(), self.root, None).0
+    }
+
     /// Returns a null `Cursor` for this tree.
     #[inline]
     pub fn cursor(&self) -> Cursor<'_, A> {
         Cursor {
             current: None,
             tree: self,
+            #[cfg(feature = "debug_generation")]
+            generation: self.generation.get(),
         }
     }
 
@@ -1656,6 +2841,33 @@ where
         }
     }
 
+    // Checks that `link` is in this tree by scanning it, for the
+    // `debug-checks` feature.
+    #[inline]
+    unsafe fn check_contains(&self, link: <A::LinkOps as link_ops::LinkOps>::LinkPtr) {
+        #[cfg(feature = "debug-checks")]
+        {
+            let mut current = self
+                .root
+                .map(|root| first_child(self.adapter.link_ops(), root));
+            while let Some(x) = current {
+                if x == link {
+                    return;
+                }
+                current = next(self.adapter.link_ops(), x);
+            }
+            let value = self.adapter.get_value(link);
+            crate::adapter::bad_cursor_ptr(
+                &self.adapter,
+                self.debug_name(),
+                value,
+                "which is not in this collection",
+            );
+        }
+        #[cfg(not(feature = "debug-checks"))]
+        let _ = link;
+    }
+
     /// Creates a `Cursor` from a pointer to an element.
     ///
     /// # Safety
@@ -1666,9 +2878,14 @@ where
         &self,
         ptr: *const <A::PointerOps as PointerOps>::Value,
     ) -> Cursor<'_, A> {
+        let link = self.adapter.get_link(ptr);
+        self.check_contains(link);
+        self.check_owner(link);
         Cursor {
-            current: Some(self.adapter.get_link(ptr)),
+            current: Some(link),
             tree: self,
+            #[cfg(feature = "debug_generation")]
+            generation: self.generation.get(),
         }
     }
 
@@ -1682,8 +2899,11 @@ where
         &mut self,
         ptr: *const <A::PointerOps as PointerOps>::Value,
     ) -> CursorMut<'_, A> {
+        let link = self.adapter.get_link(ptr);
+        self.check_contains(link);
+        self.check_owner(link);
         CursorMut {
-            current: Some(self.adapter.get_link(ptr)),
+            current: Some(link),
             tree: self,
         }
     }
@@ -1698,8 +2918,11 @@ where
         self,
         ptr: *const <A::PointerOps as PointerOps>::Value,
     ) -> CursorOwning<A> {
+        let link = self.adapter.get_link(ptr);
+        self.check_contains(link);
+        self.check_owner(link);
         CursorOwning {
-            current: Some(self.adapter.get_link(ptr)),
+            current: Some(link),
             tree: self,
         }
     }
@@ -1758,6 +2981,39 @@ where
         cursor
     }
 
+    // Updates the statistics after `node` was inserted into the tree, which
+    // took `rotations` rotations to rebalance.
+    #[inline]
+    unsafe fn record_insert(
+        &self,
+        node: <A::LinkOps as link_ops::LinkOps>::LinkPtr,
+        rotations: usize,
+    ) {
+        #[cfg(feature = "stats")]
+        {
+            let mut depth = 1;
+            let mut x = node;
+            while let Some(parent) = self.adapter.link_ops().parent(x) {
+                depth += 1;
+                x = parent;
+            }
+            self.stats.rotations.add(rotations);
+            self.stats.max_depth.max(depth);
+        }
+        #[cfg(not(feature = "stats"))]
+        let _ = (node, rotations);
+    }
+
+    // Updates the statistics after an element was removed from the tree with
+    // `remove`, which took `rotations` rotations to rebalance.
+    #[inline]
+    fn record_remove(&self, rotations: usize) {
+        record_stat!(self, removals);
+        record_stat!(self, rotations, rotations);
+        #[cfg(not(feature = "stats"))]
+        let _ = rotations;
+    }
+
     #[inline]
     unsafe fn insert_root(&mut self, node: <A::LinkOps as link_ops::LinkOps>::LinkPtr) {
         self.adapter.link_ops_mut().set_parent(node, None);
@@ -1777,16 +3033,58 @@ where
                 head: Some(unsafe { first_child(link_ops, root) }),
                 tail: Some(unsafe { last_child(link_ops, root) }),
                 tree: self,
+                #[cfg(feature = "debug_generation")]
+                generation: self.generation.get(),
             }
         } else {
             Iter {
                 head: None,
                 tail: None,
                 tree: self,
+                #[cfg(feature = "debug_generation")]
+                generation: self.generation.get(),
             }
         }
     }
 
+    /// Calls `f` on each object in the `RBTree` in order, until it returns
+    /// `ControlFlow::Break`.
+    ///
+    /// This is faster than `iter` since it doesn't have to keep the state of an
+    /// iterator between calls. The value from `ControlFlow::Break` is returned,
+    /// or `ControlFlow::Continue(())` if `f` never breaks.
+    #[inline]
+    pub fn visit<B, F>(&self, mut f: F) -> ControlFlow<B>
+    where
+        F: FnMut(&<A::PointerOps as PointerOps>::Value) -> ControlFlow<B>,
+    {
+        match self.root {
+            Some(root) => unsafe {
+                visit_subtree(self.adapter.link_ops(), root, &mut |x| {
+                    f(&*self.adapter.get_value(x))
+                })
+            },
+            None => ControlFlow::Continue(()),
+        }
+    }
+
+    /// Gets an iterator over mutable references to the data of the objects
+    /// in the `RBTree`, held in the `GhostCell`s returned by the adapter.
+    ///
+    /// Only the `GhostToken` has to be borrowed mutably, so this can be used
+    /// through a shared reference to the `RBTree`.
+    #[cfg(feature = "ghost-cell")]
+    #[inline]
+    pub fn iter_ghost_mut<'a, 'brand>(
+        &'a self,
+        token: &'a mut GhostToken<'brand>,
+    ) -> GhostIterMut<'a, 'brand, A, Iter<'a, A>>
+    where
+        A: GhostAdapter<'brand>,
+    {
+        unsafe { GhostIterMut::new(self.iter(), &self.adapter, token) }
+    }
+
     /// Gets a mutable iterator over the objects in the `RBTree`.
     ///
     /// # Safety
@@ -1827,9 +3125,8 @@ where
                 self.clear_recurse(left);
                 self.clear_recurse(right);
                 self.adapter.link_ops_mut().release_link(current);
-                self.adapter
-                    .pointer_ops()
-                    .from_raw(self.adapter.get_value(current));
+                crate::adapter::unlinked_pointer(&self.adapter, current);
+                record_stat!(self, removals);
             }
         }
     }
@@ -1841,8 +3138,10 @@ where
     /// converted back to an owned pointer and then dropped.
     #[inline]
     pub fn clear(&mut self) {
-        let root = self.root.take();
-        self.clear_recurse(root);
+        structural!(self, {
+            let root = self.root.take();
+            self.clear_recurse(root);
+        })
     }
 
     /// Empties the `RBTree` without unlinking or freeing objects in it.
@@ -1853,22 +3152,92 @@ where
     /// `force_unlink` function on them.
     #[inline]
     pub fn fast_clear(&mut self) {
+        #[cfg(feature = "debug_generation")]
+        self.generation.bump();
         self.root = None;
     }
 
     /// Takes all the elements out of the `RBTree`, leaving it empty. The
     /// taken elements are returned as a new `RBTree`.
+    ///
+    /// This takes constant time.
     #[inline]
     pub fn take(&mut self) -> RBTree<A>
     where
         A: Clone,
     {
-        let tree = RBTree {
-            root: self.root,
-            adapter: self.adapter.clone(),
-        };
-        self.root = None;
-        tree
+        structural!(self, {
+            let tree = RBTree {
+                root: self.root,
+                adapter: self.adapter.clone(),
+                #[cfg(debug_assertions)]
+                debug_name: self.debug_name,
+                #[cfg(feature = "stats")]
+                stats: StatsCounters::new(),
+                #[cfg(feature = "poison")]
+                poison: Poison::new(),
+                #[cfg(feature = "debug_generation")]
+                generation: Generation::new(),
+                #[cfg(feature = "debug_owner")]
+                owner: self.owner,
+            };
+            self.root = None;
+            #[cfg(feature = "debug_owner")]
+            {
+                self.owner = 0;
+            }
+            tree
+        })
+    }
+
+    /// Swaps the elements of this `RBTree` with those of `other`, along with
+    /// their adapters.
+    ///
+    /// This takes constant time, and is equivalent to `mem::swap`, which can
+    /// also be used since an `RBTree` doesn't point into itself.
+    #[inline]
+    pub fn swap(&mut self, other: &mut RBTree<A>) {
+        mem::swap(self, other);
+    }
+
+    /// Lends the `RBTree` to C code for the duration of `f`, as a
+    /// `struct ic_rbtree` handle for the functions of the `ffi` module.
+    ///
+    /// The changes made through the handle are applied to the `RBTree` once
+    /// `f` returns or panics, so the handle must not be used after that. C
+    /// code must keep the elements ordered by the keys of the adapter.
+    #[cfg(feature = "ffi")]
+    pub fn with_c_handle<R>(&mut self, f: impl FnOnce(*mut crate::ffi::RBTreeHandle) -> R) -> R
+    where
+        A: Adapter<LinkOps = LinkOps>,
+    {
+        // Applies the changes even if `f` panics, since C code may already
+        // have linked or unlinked elements.
+        struct WriteBack<'a, A: Adapter<LinkOps = LinkOps>> {
+            tree: &'a mut RBTree<A>,
+            handle: crate::ffi::RBTreeHandle,
+        }
+
+        impl<A: Adapter<LinkOps = LinkOps>> Drop for WriteBack<'_, A> {
+            #[inline]
+            fn drop(&mut self) {
+                self.tree.root = self.handle.root;
+                // Elements inserted by C code don't have an owner yet.
+                #[cfg(feature = "debug_owner")]
+                {
+                    let owner = self.tree.owner_id();
+                    self.tree.set_owner_all(owner);
+                }
+            }
+        }
+
+        structural!(self, {
+            let mut guard = WriteBack {
+                handle: crate::ffi::RBTreeHandle { root: self.root },
+                tree: &mut *self,
+            };
+            f(&mut guard.handle)
+        })
     }
 }
 
@@ -1913,6 +3282,8 @@ where
         Cursor {
             current: self.find_internal(key),
             tree: self,
+            #[cfg(feature = "debug_generation")]
+            generation: self.generation.get(),
         }
     }
 
@@ -1992,6 +3363,8 @@ where
         Cursor {
             current: self.lower_bound_internal(bound),
             tree: self,
+            #[cfg(feature = "debug_generation")]
+            generation: self.generation.get(),
         }
     }
 
@@ -2070,6 +3443,8 @@ where
         Cursor {
             current: self.upper_bound_internal(bound),
             tree: self,
+            #[cfg(feature = "debug_generation")]
+            generation: self.generation.get(),
         }
     }
 
@@ -2118,43 +3493,98 @@ where
     /// Panics if the new element is already linked to a different intrusive
     /// collection.
     #[inline]
-    pub fn insert<'a>(&'a mut self, val: <A::PointerOps as PointerOps>::Pointer) -> CursorMut<'_, A>
+    pub fn insert<'a>(&'a mut self, val: <A::PointerOps as PointerOps>::Pointer) -> CursorMut<'a, A>
     where
         <A as KeyAdapter<'a>>::Key: Ord,
     {
-        unsafe {
-            let new = self.node_from_value(val);
-            let raw = self.adapter.get_value(new);
-            if let Some(root) = self.root {
-                let key = self.adapter.get_key(&*raw);
-                let mut tree = root;
-                loop {
-                    let current = &*self.adapter.get_value(tree);
-                    if key < self.adapter.get_key(current) {
-                        if let Some(left) = self.adapter.link_ops().left(tree) {
-                            tree = left;
-                        } else {
-                            insert_left(self.adapter.link_ops_mut(), tree, new, &mut self.root);
-                            break;
-                        }
-                    } else {
-                        if let Some(right) = self.adapter.link_ops().right(tree) {
-                            tree = right;
-                        } else {
-                            insert_right(self.adapter.link_ops_mut(), tree, new, &mut self.root);
-                            break;
-                        }
+        let new = unsafe {
+            let new = self.acquire_node(val);
+            self.link_node(new)
+        };
+
+        CursorMut {
+            current: Some(new),
+            tree: self,
+        }
+    }
+
+    /// Inserts a new element into the `RBTree`, without panicking.
+    ///
+    /// This is the same as `insert`, except that an error containing `val` is
+    /// returned if the new element is already linked to an intrusive
+    /// collection.
+    #[inline]
+    pub fn try_insert<'a>(
+        &'a mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<CursorMut<'a, A>, InsertError<<A::PointerOps as PointerOps>::Pointer>>
+    where
+        <A as KeyAdapter<'a>>::Key: Ord,
+    {
+        let new = unsafe { self.try_acquire_node(val).map(|new| self.link_node(new)) }?;
+
+        Ok(CursorMut {
+            current: Some(new),
+            tree: self,
+        })
+    }
+
+    // Inserts an acquired element at the position given by its key, and calls
+    // the `on_insert` hook of the adapter.
+    #[inline]
+    unsafe fn link_node<'a>(
+        &mut self,
+        new: <A::LinkOps as link_ops::LinkOps>::LinkPtr,
+    ) -> <A::LinkOps as link_ops::LinkOps>::LinkPtr
+    where
+        <A as KeyAdapter<'a>>::Key: Ord,
+        Self: 'a,
+    {
+        let raw = self.adapter.get_value(new);
+
+        // Find the parent of the new element, and whether it goes to its
+        // left, before modifying the tree: if a key comparison panics, the
+        // guard drops the new element and the tree is left untouched.
+        let guard = crate::adapter::AcquiredLink::new(&mut self.adapter, new);
+        let adapter = guard.adapter();
+        let parent = self.root.map(|root| {
+            let key = adapter.get_key(&*raw);
+            let mut tree = root;
+            loop {
+                let current = &*adapter.get_value(tree);
+                if key < adapter.get_key(current) {
+                    match adapter.link_ops().left(tree) {
+                        Some(left) => tree = left,
+                        None => break (tree, true),
+                    }
+                } else {
+                    match adapter.link_ops().right(tree) {
+                        Some(right) => tree = right,
+                        None => break (tree, false),
                     }
                 }
-            } else {
-                self.insert_root(new);
-            }
-
-            CursorMut {
-                current: Some(new),
-                tree: self,
             }
-        }
+        });
+        guard.on_insert();
+        guard.disarm();
+        record_stat!(self, insertions);
+
+        structural!(self, {
+            let rotations = match parent {
+                Some((parent, true)) => {
+                    insert_left(self.adapter.link_ops_mut(), parent, new, &mut self.root)
+                }
+                Some((parent, false)) => {
+                    insert_right(self.adapter.link_ops_mut(), parent, new, &mut self.root)
+                }
+                None => {
+                    self.insert_root(new);
+                    0
+                }
+            };
+            self.record_insert(new, rotations);
+        });
+        new
     }
 
     /// Returns an `Entry` for the given key which contains a `CursorMut` to an
@@ -2244,6 +3674,8 @@ where
                     head: Some(lower),
                     tail: Some(upper),
                     tree: self,
+                    #[cfg(feature = "debug_generation")]
+                    generation: self.generation.get(),
                 };
             }
         }
@@ -2251,14 +3683,75 @@ where
             head: None,
             tail: None,
             tree: self,
+            #[cfg(feature = "debug_generation")]
+            generation: self.generation.get(),
+        }
+    }
+
+    /// Gets an iterator over the elements of the tree, starting from those
+    /// closest to `key` and alternating between the elements after and
+    /// before it.
+    ///
+    /// The elements whose key is equal to `key` come first, in order. They
+    /// are followed by the lowest element whose key is above `key`, then the
+    /// highest one whose key is below `key`, then the second lowest above it
+    /// and so on. Once one side runs out, the rest of the other side is
+    /// yielded in order.
+    ///
+    /// Keys are only ordered, so the iterator can't compare distances across
+    /// the two sides. If they have a distance, the `k` nearest elements are
+    /// among the first `2 * k` yielded:
+    ///
+    /// ```
+    /// use intrusive_collections::{intrusive_adapter, KeyAdapter, RBTree, RBTreeLink};
+    ///
+    /// struct Node {
+    ///     link: RBTreeLink,
+    ///     id: u32,
+    /// }
+    /// intrusive_adapter!(NodeAdapter = Box<Node>: Node { link: RBTreeLink });
+    /// impl<'a> KeyAdapter<'a> for NodeAdapter {
+    ///     type Key = u32;
+    ///     fn get_key(&self, node: &'a Node) -> u32 {
+    ///         node.id
+    ///     }
+    /// }
+    ///
+    /// let mut nodes = RBTree::new(NodeAdapter::new());
+    /// for &id in &[1, 4, 9, 12, 20] {
+    ///     nodes.insert(Box::new(Node { link: RBTreeLink::new(), id }));
+    /// }
+    /// let ids = nodes.neighbors(&10).map(|node| node.id).collect::<Vec<_>>();
+    /// assert_eq!(ids, [12, 9, 20, 4, 1]);
+    ///
+    /// let mut nearest = nodes.neighbors(&10).take(4).map(|node| node.id).collect::<Vec<_>>();
+    /// nearest.sort_by_key(|&id| (id as i32 - 10).abs());
+    /// assert_eq!(nearest[..2], [9, 12]);
+    /// ```
+    #[inline]
+    pub fn neighbors<'a, Q: ?Sized + Ord>(&'a self, key: &Q) -> Neighbors<'a, A>
+    where
+        <A as KeyAdapter<'a>>::Key: Borrow<Q>,
+    {
+        Neighbors {
+            after: self.lower_bound_internal(Included(key)),
+            above: self.lower_bound_internal(Excluded(key)),
+            before: self.upper_bound_internal(Excluded(key)),
+            after_next: true,
+            tree: self,
+            #[cfg(feature = "debug_generation")]
+            generation: self.generation.get(),
         }
     }
 }
 
-// Allow read-only access to values from multiple threads
+// Allow read-only access to values from multiple threads. Pointers can be
+// cloned through a shared reference with `clone_pointer`, so they must be
+// `Sync` too.
 unsafe impl<A: Adapter + Sync> Sync for RBTree<A>
 where
     <A::PointerOps as PointerOps>::Value: Sync,
+    <A::PointerOps as PointerOps>::Pointer: Sync,
     A::LinkOps: RBTreeOps,
 {
 }
@@ -2272,6 +3765,96 @@ where
 {
 }
 
+// Cursors and iterators get the same thread safety as a reference to the
+// tree: shared for `Cursor` and `Iter`, exclusive for the others. `IntoIter`
+// owns the tree, so it gets the same thread safety as the tree itself.
+unsafe impl<A: Adapter> Send for Cursor<'_, A>
+where
+    RBTree<A>: Sync,
+    A::LinkOps: RBTreeOps,
+{
+}
+
+unsafe impl<A: Adapter> Sync for Cursor<'_, A>
+where
+    RBTree<A>: Sync,
+    A::LinkOps: RBTreeOps,
+{
+}
+
+unsafe impl<A: Adapter> Send for CursorMut<'_, A>
+where
+    RBTree<A>: Send,
+    A::LinkOps: RBTreeOps,
+{
+}
+
+unsafe impl<A: Adapter> Sync for CursorMut<'_, A>
+where
+    RBTree<A>: Sync,
+    A::LinkOps: RBTreeOps,
+{
+}
+
+unsafe impl<A: Adapter> Send for InsertCursor<'_, A>
+where
+    RBTree<A>: Send,
+    A::LinkOps: RBTreeOps,
+{
+}
+
+unsafe impl<A: Adapter> Sync for InsertCursor<'_, A>
+where
+    RBTree<A>: Sync,
+    A::LinkOps: RBTreeOps,
+{
+}
+
+unsafe impl<A: Adapter> Send for Iter<'_, A>
+where
+    RBTree<A>: Sync,
+    A::LinkOps: RBTreeOps,
+{
+}
+
+unsafe impl<A: Adapter> Sync for Iter<'_, A>
+where
+    RBTree<A>: Sync,
+    A::LinkOps: RBTreeOps,
+{
+}
+
+unsafe impl<A: Adapter> Send for IterMut<'_, A>
+where
+    RBTree<A>: Send,
+    <A::PointerOps as PointerOps>::Value: Send,
+    A::LinkOps: RBTreeOps,
+    A::PointerOps: ExclusivePointerOps,
+{
+}
+
+unsafe impl<A: Adapter> Sync for IterMut<'_, A>
+where
+    RBTree<A>: Sync,
+    A::LinkOps: RBTreeOps,
+    A::PointerOps: ExclusivePointerOps,
+{
+}
+
+unsafe impl<A: Adapter> Send for IntoIter<A>
+where
+    RBTree<A>: Send,
+    A::LinkOps: RBTreeOps,
+{
+}
+
+unsafe impl<A: Adapter> Sync for IntoIter<A>
+where
+    RBTree<A>: Sync,
+    A::LinkOps: RBTreeOps,
+{
+}
+
 // Drop all owned pointers if the collection is dropped
 impl<A: Adapter> Drop for RBTree<A>
 where
@@ -2279,7 +3862,88 @@ where
 {
     #[inline]
     fn drop(&mut self) {
+        // The links of a poisoned tree may be inconsistent, so its elements
+        // are leaked instead of being unlinked.
+        #[cfg(feature = "poison")]
+        if self.is_poisoned() {
+            return;
+        }
+        let leaked = crate::adapter::checks_leaks(&self.adapter) && !self.is_empty();
         self.clear();
+        if leaked {
+            crate::adapter::leaked_on_drop(&self.adapter, self.debug_name());
+        }
+    }
+}
+
+impl<A: Adapter> Collection for RBTree<A>
+where
+    A::LinkOps: RBTreeOps,
+    A: for<'a> KeyAdapter<'a>,
+    for<'a> <A as KeyAdapter<'a>>::Key: Ord,
+{
+    type Adapter = A;
+    type Position = Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>;
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        RBTree::is_empty(self)
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        RBTree::clear(self);
+    }
+
+    #[inline]
+    fn insert(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        RBTree::insert(self, val);
+    }
+
+    #[inline]
+    fn remove_first<F: FnMut(&<A::PointerOps as PointerOps>::Value) -> bool>(
+        &mut self,
+        mut f: F,
+    ) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        let mut cursor = self.front_mut();
+        loop {
+            if f(cursor.get()?) {
+                return cursor.remove();
+            }
+            cursor.move_next();
+        }
+    }
+
+    #[inline]
+    fn take(&mut self) -> RBTree<A>
+    where
+        A: Clone,
+    {
+        RBTree::take(self)
+    }
+
+    #[inline]
+    fn swap(&mut self, other: &mut RBTree<A>) {
+        RBTree::swap(self, other);
+    }
+
+    #[inline]
+    fn front_position(&self) -> Self::Position {
+        self.root
+            .map(|root| unsafe { first_child(self.adapter.link_ops(), root) })
+    }
+
+    #[inline]
+    unsafe fn value_at(
+        &self,
+        position: Self::Position,
+    ) -> Option<&<A::PointerOps as PointerOps>::Value> {
+        position.map(|x| &*self.adapter.get_value(x))
+    }
+
+    #[inline]
+    unsafe fn next_position(&self, position: Self::Position) -> Self::Position {
+        next(self.adapter.link_ops(), position.unwrap_unchecked())
     }
 }
 
@@ -2332,6 +3996,37 @@ where
     }
 }
 
+unsafe impl<A: Adapter> BundleMember for RBTree<A>
+where
+    A: for<'a> KeyAdapter<'a>,
+    for<'a> <A as KeyAdapter<'a>>::Key: Ord,
+    A::LinkOps: RBTreeOps + link_ops::LinkStateOps,
+{
+    type Adapter = A;
+
+    #[inline]
+    fn is_linked(&self, value: &<A::PointerOps as PointerOps>::Value) -> bool {
+        unsafe { crate::adapter::value_is_linked(&self.adapter, value) }
+    }
+
+    #[inline]
+    fn insert_member(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        self.insert(val);
+    }
+
+    #[inline]
+    unsafe fn remove_member(
+        &mut self,
+        value: &<A::PointerOps as PointerOps>::Value,
+    ) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        if self.is_linked(value) {
+            self.cursor_mut_from_ptr(value).remove()
+        } else {
+            None
+        }
+    }
+}
+
 impl<A: Adapter> fmt::Debug for RBTree<A>
 where
     A::LinkOps: RBTreeOps,
@@ -2342,6 +4037,17 @@ where
     }
 }
 
+#[cfg(feature = "defmt")]
+impl<A: Adapter> defmt::Format for RBTree<A>
+where
+    A::LinkOps: RBTreeOps,
+    <A::PointerOps as PointerOps>::Value: defmt::Format,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        crate::adapter::defmt_list(f, self.iter())
+    }
+}
+
 // =============================================================================
 // InsertCursor, Entry
 // =============================================================================
@@ -2369,22 +4075,24 @@ where
     /// Panics if the new element is already linked to a different intrusive
     /// collection.
     pub fn insert(self, val: <A::PointerOps as PointerOps>::Pointer) -> CursorMut<'a, A> {
-        unsafe {
-            let new = self.tree.node_from_value(val);
+        let new = self.tree.node_from_value(val);
+        structural!(self.tree, unsafe {
             let link_ops = self.tree.adapter.link_ops_mut();
-            if let Some(parent) = self.parent {
+            let rotations = if let Some(parent) = self.parent {
                 if self.insert_left {
-                    insert_left(link_ops, parent, new, &mut self.tree.root);
+                    insert_left(link_ops, parent, new, &mut self.tree.root)
                 } else {
-                    insert_right(link_ops, parent, new, &mut self.tree.root);
+                    insert_right(link_ops, parent, new, &mut self.tree.root)
                 }
             } else {
                 self.tree.insert_root(new);
-            }
-            CursorMut {
-                current: Some(new),
-                tree: self.tree,
-            }
+                0
+            };
+            self.tree.record_insert(new, rotations);
+        });
+        CursorMut {
+            current: Some(new),
+            tree: self.tree,
         }
     }
 }
@@ -2442,6 +4150,23 @@ where
     }
 }
 
+// Shows the element under which the new element would be inserted, and on
+// which side.
+impl<A: Adapter> fmt::Debug for InsertCursor<'_, A>
+where
+    A::LinkOps: RBTreeOps,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parent = self
+            .parent
+            .map(|parent| unsafe { self.tree.adapter.get_value(parent) });
+        f.debug_struct("InsertCursor")
+            .field("parent", &parent)
+            .field("insert_left", &self.insert_left)
+            .finish()
+    }
+}
+
 // =============================================================================
 // Iter
 // =============================================================================
@@ -2454,6 +4179,8 @@ where
     head: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
     tail: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
     tree: &'a RBTree<A>,
+    #[cfg(feature = "debug_generation")]
+    generation: usize,
 }
 impl<'a, A: Adapter + 'a> Iterator for Iter<'a, A>
 where
@@ -2463,6 +4190,10 @@ where
 
     #[inline]
     fn next(&mut self) -> Option<&'a <A::PointerOps as PointerOps>::Value> {
+        #[cfg(feature = "debug_generation")]
+        self.tree
+            .generation
+            .check(self.generation, self.tree.debug_name());
         let head = self.head?;
 
         if Some(head) == self.tail {
@@ -2480,6 +4211,10 @@ where
 {
     #[inline]
     fn next_back(&mut self) -> Option<&'a <A::PointerOps as PointerOps>::Value> {
+        #[cfg(feature = "debug_generation")]
+        self.tree
+            .generation
+            .check(self.generation, self.tree.debug_name());
         let tail = self.tail?;
 
         if Some(tail) == self.head {
@@ -2491,16 +4226,95 @@ where
         Some(unsafe { &*self.tree.adapter.get_value(tail) })
     }
 }
-impl<'a, A: Adapter + 'a> Clone for Iter<'a, A>
+impl<'a, A: Adapter + 'a> Clone for Iter<'a, A>
+where
+    A::LinkOps: RBTreeOps,
+{
+    #[inline]
+    fn clone(&self) -> Iter<'a, A> {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            tree: self.tree,
+            #[cfg(feature = "debug_generation")]
+            generation: self.generation,
+        }
+    }
+}
+
+// =============================================================================
+// Neighbors
+// =============================================================================
+
+/// An iterator over references to the items of a `RBTree`, from the nearest
+/// to the furthest from a key.
+///
+/// See the documentation for `RBTree::neighbors`.
+pub struct Neighbors<'a, A: Adapter>
+where
+    A::LinkOps: RBTreeOps,
+{
+    after: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+    // The first element whose key is above the key, which moves along with
+    // `after` once it is reached. The elements from `after` up to it have an
+    // equal key, and are yielded before alternating.
+    above: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+    before: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+    after_next: bool,
+    tree: &'a RBTree<A>,
+    #[cfg(feature = "debug_generation")]
+    generation: usize,
+}
+impl<'a, A: Adapter + 'a> Iterator for Neighbors<'a, A>
+where
+    A::LinkOps: RBTreeOps,
+{
+    type Item = &'a <A::PointerOps as PointerOps>::Value;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a <A::PointerOps as PointerOps>::Value> {
+        #[cfg(feature = "debug_generation")]
+        self.tree
+            .generation
+            .check(self.generation, self.tree.debug_name());
+        let link_ops = self.tree.adapter.link_ops();
+        let take_after = match (self.after, self.before) {
+            _ if self.after != self.above => true,
+            (Some(_), Some(_)) => {
+                self.after_next = !self.after_next;
+                !self.after_next
+            }
+            (after, before) => after.is_some() || before.is_none(),
+        };
+        let x = if take_after {
+            let after = self.after?;
+            self.after = unsafe { next(link_ops, after) };
+            if self.above == Some(after) {
+                self.above = self.after;
+            }
+            after
+        } else {
+            let before = self.before?;
+            self.before = unsafe { prev(link_ops, before) };
+            before
+        };
+        Some(unsafe { &*self.tree.adapter.get_value(x) })
+    }
+}
+impl<'a, A: Adapter + 'a> Clone for Neighbors<'a, A>
 where
     A::LinkOps: RBTreeOps,
 {
     #[inline]
-    fn clone(&self) -> Iter<'a, A> {
-        Iter {
-            head: self.head,
-            tail: self.tail,
+    fn clone(&self) -> Neighbors<'a, A> {
+        Neighbors {
+            after: self.after,
+            above: self.above,
+            before: self.before,
+            after_next: self.after_next,
             tree: self.tree,
+            #[cfg(feature = "debug_generation")]
+            generation: self.generation,
         }
     }
 }
@@ -2594,8 +4408,8 @@ where
         use link_ops::LinkOps;
 
         let head = self.head?;
-        let link_ops = self.tree.adapter.link_ops_mut();
-        unsafe {
+        structural!(self.tree, unsafe {
+            let link_ops = self.tree.adapter.link_ops_mut();
             // Remove the node from the tree. Since head is always the
             // left-most node, we can infer the following:
             // - head.left is null.
@@ -2615,13 +4429,9 @@ where
                 self.head = link_ops.parent(head);
             }
             link_ops.release_link(head);
-            Some(
-                self.tree
-                    .adapter
-                    .pointer_ops()
-                    .from_raw(self.tree.adapter.get_value(head)),
-            )
-        }
+            record_stat!(self.tree, removals);
+            Some(crate::adapter::unlinked_pointer(&self.tree.adapter, head))
+        })
     }
 }
 impl<A: Adapter> DoubleEndedIterator for IntoIter<A>
@@ -2633,8 +4443,8 @@ where
         use link_ops::LinkOps;
 
         let tail = self.tail?;
-        let link_ops = self.tree.adapter.link_ops_mut();
-        unsafe {
+        structural!(self.tree, unsafe {
+            let link_ops = self.tree.adapter.link_ops_mut();
             // Remove the node from the tree. Since tail is always the
             // right-most node, we can infer the following:
             // - tail.right is null.
@@ -2654,13 +4464,9 @@ where
                 self.tail = link_ops.parent(tail);
             }
             link_ops.release_link(tail);
-            Some(
-                self.tree
-                    .adapter
-                    .pointer_ops()
-                    .from_raw(self.tree.adapter.get_value(tail)),
-            )
-        }
+            record_stat!(self.tree, removals);
+            Some(crate::adapter::unlinked_pointer(&self.tree.adapter, tail))
+        })
     }
 }
 
@@ -2921,12 +4727,8 @@ mod tests {
             for i in indices {
                 {
                     let mut c = t.front_mut();
-                    loop {
-                        if let Some(x) = c.get() {
-                            if x.value > v[i].value {
-                                break;
-                            }
-                        } else {
+                    while let Some(x) = c.get() {
+                        if x.value > v[i].value {
                             break;
                         }
                         c.move_next();
@@ -2949,12 +4751,8 @@ mod tests {
             for i in indices {
                 {
                     let mut c = t.back_mut();
-                    loop {
-                        if let Some(x) = c.get() {
-                            if x.value < v[i].value {
-                                break;
-                            }
-                        } else {
+                    while let Some(x) = c.get() {
+                        if x.value < v[i].value {
                             break;
                         }
                         c.move_prev();
@@ -2968,6 +4766,110 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_into_cursor() {
+        let mut l = RBTree::new(RcObjAdapter::new());
+        for value in 1..4 {
+            l.insert(make_rc_obj(value));
+        }
+        let mut cur = l.front_mut();
+        cur.move_next();
+        assert_eq!(cur.as_cursor().peek_next().get().unwrap().value, 3);
+        let mut cur = cur.into_cursor();
+        let c2 = cur.clone();
+        cur.move_next();
+        assert_eq!(cur.get().unwrap().value, 3);
+        assert_eq!(c2.get().unwrap().value, 2);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let mut t = RBTree::new(RcObjAdapter::new());
+        assert!(t.neighbors(&0).next().is_none());
+        for &value in &[1, 3, 3, 5, 7, 9, 11] {
+            t.insert(make_rc_obj(value));
+        }
+        let values = |key| t.neighbors(&key).map(|x| x.value).collect::<Vec<_>>();
+        assert_eq!(values(6), [7, 5, 9, 3, 11, 3, 1]);
+        assert_eq!(values(3), [3, 3, 5, 1, 7, 9, 11]);
+        assert_eq!(values(7), [7, 9, 5, 11, 3, 3, 1]);
+        assert_eq!(values(0), [1, 3, 3, 5, 7, 9, 11]);
+        assert_eq!(values(12), [11, 9, 7, 5, 3, 3, 1]);
+        let mut it = t.neighbors(&8);
+        it.next();
+        assert_eq!(it.clone().next().unwrap().value, 7);
+    }
+
+    #[test]
+    fn test_visit() {
+        use core::ops::ControlFlow;
+
+        let mut t = RBTree::new(RcObjAdapter::new());
+        assert_eq!(
+            t.visit(|_| ControlFlow::Break(())),
+            ControlFlow::Continue(())
+        );
+        for i in 0..100 {
+            t.insert(make_rc_obj(i * 37 % 100));
+        }
+        let mut values = Vec::new();
+        let res = t.visit(|x| {
+            values.push(x.value);
+            ControlFlow::<()>::Continue(())
+        });
+        assert_eq!(res, ControlFlow::Continue(()));
+        assert_eq!(values, (0..100).collect::<Vec<_>>());
+
+        values.clear();
+        let res = t.visit(|x| {
+            values.push(x.value);
+            if x.value == 41 {
+                ControlFlow::Break(x.value)
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        assert_eq!(res, ControlFlow::Break(41));
+        assert_eq!(values, (0..42).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_try_insert() {
+        use crate::InsertError;
+
+        let a = make_rc_obj(1);
+        let b = make_rc_obj(2);
+        let c = make_rc_obj(3);
+
+        let mut t = RBTree::new(RcObjAdapter::new());
+        let mut t2 = RBTree::new(RcObjAdapter::new());
+        assert_eq!(t.try_insert(b.clone()).unwrap().get().unwrap().value, 2);
+        match t2.try_insert(b.clone()) {
+            Err(InsertError::AlreadyLinked(x)) => assert!(Rc::ptr_eq(&x, &b)),
+            _ => panic!("expected an error"),
+        }
+        assert!(t2.is_empty());
+        assert!(t.front_mut().try_insert_before(a.clone()).is_ok());
+        assert!(matches!(
+            t.back_mut().try_insert_after(a.clone()),
+            Err(InsertError::AlreadyLinked(_))
+        ));
+        assert!(t.back_mut().try_insert_after(c.clone()).is_ok());
+        assert_eq!(t.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 2, 3]);
+
+        assert!(matches!(
+            t.cursor_mut().try_replace_with(c.clone()),
+            Err(InsertError::NullCursor(_))
+        ));
+        assert!(matches!(
+            t.back_mut().try_replace_with(a.clone()),
+            Err(InsertError::AlreadyLinked(_))
+        ));
+        let d = make_rc_obj(4);
+        assert!(Rc::ptr_eq(&t.back_mut().try_replace_with(d).unwrap(), &c));
+        assert_eq!(t.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 2, 4]);
+    }
+
     #[test]
     fn test_iter() {
         let v = (0..10).map(|x| make_rc_obj(x * 10)).collect::<Vec<_>>();
@@ -3200,9 +5102,7 @@ mod tests {
         }
         assert_eq!(v2, vec![0, 10, 20, 30, 40, 50, 60, 70, 80, 90]);
         assert!(t.is_empty());
-        for _ in t.take() {
-            unreachable!();
-        }
+        assert!(t.take().into_iter().next().is_none());
 
         for x in v.iter() {
             t.insert(x.clone());
@@ -3424,6 +5324,487 @@ mod tests {
         assert!(f.link.is_linked());
     }
 
+    #[test]
+    fn test_transfer() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        struct Obj2 {
+            link1: Link,
+            link2: Link,
+            value: i32,
+        }
+        intrusive_adapter!(ObjAdapter1 = Rc<Obj2>: Obj2 { link1: Link });
+        intrusive_adapter!(ObjAdapter2 = Rc<Obj2>: Obj2 { link2: Link });
+        let make_obj2 = |value| {
+            Rc::new(Obj2 {
+                link1: Link::new(),
+                link2: Link::new(),
+                value,
+            })
+        };
+
+        let objs: Vec<_> = (0..4).map(make_obj2).collect();
+        let mut t1 = RBTree::new(ObjAdapter1::new());
+        let mut t2 = RBTree::new(ObjAdapter1::new());
+        let mut t3 = RBTree::new(ObjAdapter2::new());
+        let mut cur = t1.cursor_mut();
+        for obj in &objs {
+            cur.insert_before(obj.clone());
+        }
+
+        // Same link
+        let mut cur = t1.front_mut();
+        cur.move_next();
+        assert!(cur.transfer_before(&mut t2.cursor_mut()));
+        assert_eq!(cur.get().unwrap().value, 2);
+        assert!(cur.transfer_before(&mut t2.cursor_mut()));
+        assert_eq!(cur.get().unwrap().value, 3);
+        cur.move_next();
+        assert!(!cur.transfer_after(&mut t2.cursor_mut()));
+        assert_eq!(t1.iter().map(|x| x.value).collect::<Vec<_>>(), [0, 3]);
+        assert_eq!(t2.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(Rc::strong_count(&objs[1]), 2);
+
+        // Different link
+        assert!(t1.back_mut().transfer_after(&mut t3.cursor_mut()));
+        assert!(t1.front_mut().transfer_after(&mut t3.cursor_mut()));
+        assert!(t1.is_empty());
+        assert_eq!(t3.iter().map(|x| x.value).collect::<Vec<_>>(), [0, 3]);
+        assert!(!objs[0].link1.is_linked());
+        assert!(objs[0].link2.is_linked());
+        assert_eq!(Rc::strong_count(&objs[0]), 2);
+
+        // Already linked through the link of the destination
+        let mut cur = t1.cursor_mut();
+        cur.insert_before(make_obj2(-1));
+        cur.insert_before(objs[3].clone());
+        cur.insert_before(make_obj2(4));
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            let mut t4 = RBTree::new(ObjAdapter2::new());
+            let mut cur = t1.front_mut();
+            cur.move_next();
+            cur.transfer_after(&mut t4.cursor_mut())
+        }));
+        assert!(result.is_err());
+        assert_eq!(t1.iter().map(|x| x.value).collect::<Vec<_>>(), [-1, 3, 4]);
+        assert_eq!(t3.iter().map(|x| x.value).collect::<Vec<_>>(), [0, 3]);
+        assert_eq!(Rc::strong_count(&objs[3]), 3);
+        t1.assert_valid();
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[test]
+    fn test_debug_owner() {
+        use std::panic::{self, AssertUnwindSafe};
+
+        let a = make_rc_obj(1);
+        let b = make_rc_obj(2);
+        let c = make_rc_obj(3);
+        let mut t1 = RBTree::new(RcObjAdapter::new());
+        let mut t2 = RBTree::new(RcObjAdapter::new());
+        t1.insert(a.clone());
+        t1.insert(b.clone());
+        t2.insert(c.clone());
+
+        let wrong_tree = |t: &mut RBTree<RcObjAdapter>, obj: &Rc<Obj>| {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| unsafe {
+                t.cursor_mut_from_ptr(&**obj).remove()
+            }));
+            let payload = result.err().unwrap();
+            let message = match payload.downcast_ref::<std::string::String>() {
+                Some(message) => message.as_str(),
+                None => payload.downcast_ref::<&str>().unwrap(),
+            };
+            assert!(message.contains("not in this collection"), "{}", message);
+        };
+        wrong_tree(&mut t2, &a);
+        wrong_tree(&mut t1, &c);
+        unsafe {
+            assert_eq!(t1.cursor_from_ptr(&*b).get().unwrap().value, 2);
+        }
+
+        // Elements which are moved to another tree belong to that tree.
+        let mut t3 = t1.take();
+        wrong_tree(&mut t1, &a);
+        assert!(t3.front_mut().transfer_after(&mut t2.cursor_mut()));
+        wrong_tree(&mut t3, &a);
+        unsafe {
+            assert!(Rc::ptr_eq(
+                &t2.cursor_mut_from_ptr(&*a).remove().unwrap(),
+                &a
+            ));
+        }
+
+        // Removed elements don't belong to any tree.
+        wrong_tree(&mut t2, &a);
+        t3.insert(a.clone());
+        unsafe {
+            assert!(Rc::ptr_eq(
+                &t3.cursor_mut_from_ptr(&*a).remove().unwrap(),
+                &a
+            ));
+        }
+    }
+
+    #[test]
+    fn test_const_new() {
+        const EMPTY: RBTree<RcObjAdapter> = RBTree::new(RcObjAdapter::NEW);
+        let mut t = EMPTY;
+        t.insert(make_rc_obj(1));
+        assert_eq!(t.front().get().unwrap().value, 1);
+    }
+
+    #[test]
+    fn test_make_mut() {
+        let mut t = RBTree::new(RcObjAdapter::new());
+        let a = make_rc_obj(1);
+        let b = make_rc_obj(2);
+        t.insert(a.clone());
+        t.insert(b);
+        unsafe {
+            let mut cur = t.front_mut();
+            cur.make_mut().unwrap().value = 0;
+            cur.move_next();
+            cur.make_mut().unwrap().value = 3;
+            cur.move_next();
+            assert!(cur.make_mut().is_none());
+        }
+        assert!(!a.link.is_linked());
+        assert_eq!(a.value, 1);
+        assert_eq!(t.iter().map(|x| x.value).collect::<Vec<_>>(), [0, 3]);
+    }
+
+    #[cfg(all(
+        debug_assertions,
+        not(feature = "debug-checks"),
+        not(any(
+            intrusive_collections_sanitizer = "address",
+            intrusive_collections_sanitizer = "memory"
+        ))
+    ))]
+    #[test]
+    #[should_panic(expected = "link operation on an unlinked link")]
+    fn test_poison() {
+        let a = make_rc_obj(1);
+        let mut t = RBTree::new(RcObjAdapter::new());
+        t.insert(a.clone());
+        t.insert(make_rc_obj(2));
+        t.find_mut(&1).remove();
+        // A stale cursor to an element which was removed from the tree.
+        let mut cur = unsafe { t.cursor_mut_from_ptr(&*a) };
+        cur.move_next();
+    }
+
+    #[cfg(intrusive_collections_sanitizer = "address")]
+    #[test]
+    fn test_sanitizer_poison() {
+        use crate::sanitizer::is_poisoned;
+
+        let a = make_rc_obj(1);
+        let mut t = RBTree::new(RcObjAdapter::new());
+        t.insert(a.clone());
+        assert!(!is_poisoned(&a.link.left));
+        t.find_mut(&1).remove();
+        assert!(is_poisoned(&a.link.left));
+        assert!(is_poisoned(&a.link.right));
+        assert!(!is_poisoned(&a.link.parent_color));
+        t.insert(a.clone());
+        assert!(!is_poisoned(&a.link.right));
+    }
+
+    #[cfg(feature = "debug-checks")]
+    #[test]
+    #[should_panic(
+        expected = "attempted to create a cursor from an object which is not in this collection"
+    )]
+    fn test_cursor_from_ptr_checks() {
+        let a = make_rc_obj(1);
+        let mut t = RBTree::new(RcObjAdapter::new());
+        t.insert(a.clone());
+        for i in 2..10 {
+            t.insert(make_rc_obj(i));
+        }
+        assert_eq!(unsafe { t.cursor_from_ptr(&*a) }.get().unwrap().value, 1);
+        t.find_mut(&1).remove();
+        // The stale pointer is caught before the cursor is created.
+        unsafe { t.cursor_mut_from_ptr(&*a) };
+    }
+
+    #[test]
+    fn test_cache_padded() {
+        use crate::CachePadded;
+
+        struct PaddedObj {
+            link: CachePadded<Link>,
+            value: i32,
+        }
+        intrusive_adapter!(PaddedObjAdapter = Box<PaddedObj>: PaddedObj { link: CachePadded<Link> });
+        impl<'a> KeyAdapter<'a> for PaddedObjAdapter {
+            type Key = i32;
+            fn get_key(&self, value: &'a PaddedObj) -> i32 {
+                value.value
+            }
+        }
+
+        let mut t = RBTree::new(PaddedObjAdapter::new());
+        for &value in &[5, 1, 4, 2, 3, 0] {
+            t.insert(Box::new(PaddedObj {
+                link: CachePadded::new(Link::new()),
+                value,
+            }));
+        }
+        assert_eq!(
+            t.iter().map(|x| x.value).collect::<Vec<_>>(),
+            [0, 1, 2, 3, 4, 5]
+        );
+        let obj = t.find_mut(&2).remove().unwrap();
+        assert!(!obj.link.is_linked());
+        assert_eq!(
+            t.iter().map(|x| x.value).collect::<Vec<_>>(),
+            [0, 1, 3, 4, 5]
+        );
+        assert_eq!(t.lower_bound(Included(&2)).get().unwrap().value, 3);
+    }
+
+    #[cfg(loom)]
+    #[test]
+    fn test_loom_try_acquire_for_insertion() {
+        use super::AtomicLink;
+        use loom::sync::atomic::{AtomicUsize, Ordering};
+        use loom::thread;
+        use std::sync::Arc;
+
+        struct Obj {
+            link: AtomicLink,
+            value: u32,
+        }
+        intrusive_adapter!(ObjAdapter = Arc<Obj>: Obj { link: AtomicLink });
+        impl<'a> KeyAdapter<'a> for ObjAdapter {
+            type Key = u32;
+            fn get_key(&self, value: &'a Obj) -> u32 {
+                value.value
+            }
+        }
+
+        loom::model(|| {
+            let obj = Arc::new(Obj {
+                link: AtomicLink::new(),
+                value: 1,
+            });
+            let claimed = Arc::new(AtomicUsize::new(0));
+            let threads: Vec<_> = (0..2)
+                .map(|_| {
+                    let obj = obj.clone();
+                    let claimed = claimed.clone();
+                    thread::spawn(move || {
+                        if obj.link.try_acquire_for_insertion() {
+                            claimed.fetch_add(1, Ordering::Relaxed);
+                        }
+                        assert!(obj.link.is_linked());
+                    })
+                })
+                .collect();
+            for thread in threads {
+                thread.join().unwrap();
+            }
+            assert_eq!(claimed.load(Ordering::Relaxed), 1);
+            let mut list = RBTree::new(ObjAdapter::new());
+            unsafe {
+                obj.link.force_unlink();
+            }
+            list.insert(obj.clone());
+            assert_eq!(list.iter().map(|x| x.value).sum::<u32>(), 1);
+            list.clear();
+            assert!(!obj.link.is_linked());
+        });
+    }
+
+    #[cfg(loom)]
+    #[test]
+    fn test_loom_is_linked() {
+        use super::AtomicLink;
+        use loom::sync::atomic::{AtomicUsize, Ordering};
+        use loom::thread;
+        use std::sync::Arc;
+
+        struct Obj {
+            link: AtomicLink,
+            data: AtomicUsize,
+            value: u32,
+        }
+        intrusive_adapter!(ObjAdapter = Arc<Obj>: Obj { link: AtomicLink });
+        impl<'a> KeyAdapter<'a> for ObjAdapter {
+            type Key = u32;
+            fn get_key(&self, value: &'a Obj) -> u32 {
+                value.value
+            }
+        }
+
+        loom::model(|| {
+            let obj = Arc::new(Obj {
+                link: AtomicLink::new(),
+                data: AtomicUsize::new(0),
+                value: 1,
+            });
+            let mut list = RBTree::new(ObjAdapter::new());
+            list.insert(obj.clone());
+            let watcher = {
+                let obj = obj.clone();
+                thread::spawn(move || {
+                    // Removing the object releases everything written before.
+                    if !obj.link.is_linked() {
+                        assert_eq!(obj.data.load(Ordering::Relaxed), 1);
+                    }
+                })
+            };
+            obj.data.store(1, Ordering::Relaxed);
+            list.clear();
+            watcher.join().unwrap();
+        });
+    }
+
+    #[test]
+    fn test_try_acquire_for_insertion() {
+        use super::AtomicLink;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::sync::Arc;
+
+        struct Obj {
+            link: AtomicLink,
+            value: u32,
+        }
+        intrusive_adapter!(ObjAdapter = Arc<Obj>: Obj { link: AtomicLink });
+        impl<'a> KeyAdapter<'a> for ObjAdapter {
+            type Key = u32;
+            fn get_key(&self, value: &'a Obj) -> u32 {
+                value.value
+            }
+        }
+
+        let objs: Vec<Arc<Obj>> = (0..4)
+            .map(|value| {
+                Arc::new(Obj {
+                    link: AtomicLink::new(),
+                    value,
+                })
+            })
+            .collect();
+        let mut t = RBTree::new(ObjAdapter::new());
+        for obj in &objs {
+            assert!(obj.link.try_acquire_for_insertion());
+            assert!(obj.link.is_linked());
+            assert!(!obj.link.try_acquire_for_insertion());
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                RBTree::new(ObjAdapter::new()).insert(obj.clone());
+            }));
+            assert!(result.is_err());
+            assert!(obj.link.is_linked());
+            unsafe {
+                obj.link.force_unlink();
+            }
+            t.insert(obj.clone());
+        }
+        assert_eq!(t.iter().map(|x| x.value).collect::<Vec<_>>(), [0, 1, 2, 3]);
+        assert!(!objs[0].link.try_acquire_for_insertion());
+        t.find_mut(&2).remove();
+        assert!(objs[2].link.try_acquire_for_insertion());
+        unsafe {
+            objs[2].link.force_unlink();
+        }
+        assert!(!objs[2].link.is_linked());
+        t.clear();
+    }
+
+    #[test]
+    fn test_atomic_link_threads() {
+        use super::AtomicLink;
+        use crate::link_ops::check_atomic_link_threads;
+        use std::sync::Arc;
+
+        struct Obj {
+            link: AtomicLink,
+            value: u32,
+        }
+        intrusive_adapter!(ObjAdapter = Arc<Obj>: Obj { link: AtomicLink });
+        impl<'a> KeyAdapter<'a> for ObjAdapter {
+            type Key = u32;
+            fn get_key(&self, value: &'a Obj) -> u32 {
+                value.value
+            }
+        }
+
+        let objs: Vec<Arc<Obj>> = (0..8)
+            .map(|value| {
+                Arc::new(Obj {
+                    link: AtomicLink::new(),
+                    value,
+                })
+            })
+            .collect();
+
+        // Insert in an order which rotates the root, so that the watcher
+        // sees the link of the root change while the tree is rebalanced.
+        let order = [3, 0, 6, 1, 7, 2, 5, 4];
+        check_atomic_link_threads(
+            &objs,
+            |x| x.link.is_linked(),
+            || {
+                let mut t = RBTree::new(ObjAdapter::new());
+                for _ in 0..10 {
+                    for &i in &order {
+                        t.insert(objs[i].clone());
+                    }
+                    for &i in &order[..4] {
+                        t.find_mut(&(i as u32)).remove();
+                    }
+                    t.clear();
+                }
+                for &i in &order {
+                    t.insert(objs[i].clone());
+                }
+                t
+            },
+            |t| {
+                assert_eq!(
+                    t.iter().map(|x| x.value).collect::<Vec<_>>(),
+                    [0, 1, 2, 3, 4, 5, 6, 7]
+                )
+            },
+        );
+    }
+
+    #[test]
+    fn test_get_mut() {
+        intrusive_adapter!(BoxObjAdapter = Box<Obj>: Obj { link: Link });
+        impl<'a> KeyAdapter<'a> for BoxObjAdapter {
+            type Key = i32;
+            fn get_key(&self, value: &'a Obj) -> i32 {
+                value.value
+            }
+        }
+        let mut t = RBTree::new(BoxObjAdapter::new());
+        t.insert(Box::new(make_obj(1)));
+        unsafe {
+            t.front_mut().get_mut().unwrap().value = 2;
+            let mut cur = t.front_mut();
+            cur.move_next();
+            assert!(cur.get_mut().is_none());
+        }
+        assert_eq!(t.front().get().unwrap().value, 2);
+
+        let a = make_rc_obj(3);
+        let mut t = RBTree::new(RcObjAdapter::new());
+        t.insert(a.clone());
+        unsafe {
+            assert!(t.front_mut().try_get_mut().is_none());
+        }
+        drop(a);
+        unsafe {
+            t.front_mut().try_get_mut().unwrap().value = 4;
+        }
+        assert_eq!(t.front().get().unwrap().value, 4);
+    }
+
     #[test]
     fn test_non_static() {
         #[derive(Clone)]
@@ -3450,6 +5831,7 @@ mod tests {
         l.insert(&b);
         assert_eq!(*l.front().get().unwrap().value, 5);
         assert_eq!(*l.back().get().unwrap().value, 5);
+        l.clear();
     }
 
     macro_rules! test_clone_pointer {
@@ -3481,6 +5863,10 @@ mod tests {
             assert_eq!(pointer.value, 5);
             assert_eq!(3, $ptr::strong_count(&a));
 
+            let pointer = l.front_mut().clone_pointer().unwrap();
+            assert_eq!(pointer.value, 5);
+            assert_eq!(4, $ptr::strong_count(&a));
+
             l.front_mut().remove();
             assert!(l.front().clone_pointer().is_none());
         };
@@ -3495,4 +5881,179 @@ mod tests {
     fn test_clone_pointer_arc() {
         test_clone_pointer!(Arc, std::sync::Arc);
     }
+
+    #[test]
+    fn test_cursor_debug() {
+        let objs = (0..3).map(make_rc_obj).collect::<Vec<_>>();
+        let mut t = RBTree::new(RcObjAdapter::new());
+        for obj in &objs {
+            t.insert(obj.clone());
+        }
+        let ptrs = objs.iter().map(|x| &**x as *const Obj).collect::<Vec<_>>();
+
+        assert_eq!(
+            format!("{:?}", t.find(&1)),
+            format!(
+                "Cursor {{ current: {:?}, prev: {:?}, next: {:?} }}",
+                Some(ptrs[1]),
+                Some(ptrs[0]),
+                Some(ptrs[2])
+            )
+        );
+        assert_eq!(
+            format!("{:?}", t.cursor_mut()),
+            format!(
+                "CursorMut {{ current: None, prev: {:?}, next: {:?} }}",
+                Some(ptrs[2]),
+                Some(ptrs[0])
+            )
+        );
+        match t.entry(&3) {
+            Entry::Vacant(c) => assert_eq!(
+                format!("{:?}", c),
+                format!(
+                    "InsertCursor {{ parent: {:?}, insert_left: false }}",
+                    Some(ptrs[2])
+                )
+            ),
+            Entry::Occupied(_) => unreachable!(),
+        }
+        t.clear();
+        assert_eq!(
+            format!("{:?}", t.front_owning()),
+            "CursorOwning { current: None, prev: None, next: None }"
+        );
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn test_stats() {
+        use crate::Stats;
+
+        let mut t = RBTree::new(RcObjAdapter::new());
+        assert_eq!(t.stats(), Stats::default());
+        for value in 0..100 {
+            t.insert(make_rc_obj(value));
+        }
+        let stats = t.stats();
+        assert_eq!(stats.insertions, 100);
+        assert_eq!(stats.removals, 0);
+        // Inserting in order needs rotations to keep the tree balanced.
+        assert!(stats.rotations > 0);
+        assert!(stats.max_depth >= 7 && stats.max_depth <= 14, "{:?}", stats);
+
+        let mut c = t.front();
+        for _ in 0..3 {
+            c.move_next();
+        }
+        c.move_prev();
+        assert_eq!(c.get().unwrap().value, 2);
+        // `front` moves to the first element.
+        assert_eq!(t.stats().cursor_moves, 5);
+
+        assert_eq!(t.find_mut(&50).remove().unwrap().value, 50);
+        t.front_mut().replace_with(make_rc_obj(-1)).unwrap();
+        assert_eq!(t.stats().removals, 2);
+        assert_eq!(t.stats().insertions, 101);
+        assert_eq!(t.take().stats(), Stats::default());
+        t.insert(make_rc_obj(0));
+
+        t.reset_stats();
+        assert_eq!(t.stats(), Stats::default());
+        t.clear();
+        assert_eq!(t.stats().removals, 1);
+    }
+
+    #[test]
+    fn test_assert_valid() {
+        use super::{Color, LinkOps, RBTreeOps};
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::string::String;
+
+        let mut t = RBTree::new(RcObjAdapter::new());
+        assert_eq!(t.assert_valid(), 0);
+        for value in 0..7 {
+            t.insert(make_rc_obj(value));
+        }
+        assert_eq!(t.assert_valid(), 7);
+
+        let check_err = |t: &RBTree<RcObjAdapter>, expected: &str| {
+            let err = catch_unwind(AssertUnwindSafe(|| t.assert_valid())).unwrap_err();
+            let msg = match err.downcast_ref::<String>() {
+                Some(msg) => msg.as_str(),
+                None => err.downcast_ref::<&str>().unwrap(),
+            };
+            assert!(msg.contains(expected), "{}", msg);
+        };
+        let mut ops = LinkOps;
+        let root = t.root.unwrap();
+        unsafe {
+            ops.set_color(root, Color::Red);
+            check_err(&t, "the root is red");
+            ops.set_color(root, Color::Black);
+
+            let left = ops.left(root).unwrap();
+            let right = ops.right(root).unwrap();
+            ops.set_parent(left, Some(right));
+            check_err(&t, "parent pointer");
+            ops.set_parent(left, Some(root));
+
+            let color = ops.color(left);
+            let other = if color == Color::Red {
+                Color::Black
+            } else {
+                Color::Red
+            };
+            ops.set_color(left, other);
+            check_err(&t, "corrupted");
+            ops.set_color(left, color);
+        }
+        assert_eq!(t.assert_valid(), 7);
+    }
+    #[cfg(feature = "ghost-cell")]
+    #[test]
+    fn test_iter_ghost_mut() {
+        use ghost_cell::{GhostCell, GhostToken};
+
+        struct GhostObj<'brand> {
+            link: Link,
+            key: i32,
+            count: GhostCell<'brand, u32>,
+        }
+        intrusive_adapter!(GhostObjAdapter<'brand> = Rc<GhostObj<'brand>>: GhostObj<'brand> { link: Link }
+            ghost { count: GhostCell<'brand, u32> });
+        impl<'a, 'brand> KeyAdapter<'a> for GhostObjAdapter<'brand> {
+            type Key = i32;
+            fn get_key(&self, value: &'a GhostObj<'brand>) -> i32 {
+                value.key
+            }
+        }
+
+        GhostToken::new(|mut token| {
+            let objs = (0..4)
+                .map(|key| {
+                    Rc::new(GhostObj {
+                        link: Link::new(),
+                        key,
+                        count: GhostCell::new(0),
+                    })
+                })
+                .collect::<Vec<_>>();
+            let mut t = RBTree::new(GhostObjAdapter::new());
+            for obj in objs.iter().rev() {
+                t.insert(obj.clone());
+            }
+
+            let t = &t;
+            let mut counts = t.iter_ghost_mut(&mut token).collect::<Vec<_>>();
+            for (i, count) in counts.iter_mut().enumerate() {
+                **count = i as u32 * 2;
+            }
+            for count in t.iter_ghost_mut(&mut token).rev().take(2) {
+                *count += 1;
+            }
+            let counts = objs.iter().map(|obj| *obj.count.borrow(&token));
+            assert!(counts.eq(vec![0, 2, 5, 7]));
+        });
+    }
 }
@@ -0,0 +1,445 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Ordered map of boxed objects, with an interface close to `BTreeMap`.
+
+use alloc::boxed::Box;
+use core::borrow::Borrow;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::ops::{self, RangeBounds};
+
+use crate::key_adapter::KeyAdapter;
+use crate::pointer_ops::{DefaultPointerOps, PointerOps};
+use crate::rbtree::{Entry, Iter, RBTree, RBTreeOps};
+use crate::{Adapter, Bound};
+
+// Converts a bound of a `RangeBounds` into the `Bound` taken by `RBTree`.
+#[inline]
+fn bound<T>(bound: ops::Bound<T>) -> Bound<T> {
+    match bound {
+        ops::Bound::Included(x) => Bound::Included(x),
+        ops::Bound::Excluded(x) => Bound::Excluded(x),
+        ops::Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+// =============================================================================
+// RBTreeMap
+// =============================================================================
+
+/// An ordered map of `Box`ed objects, layered over an `RBTree`.
+///
+/// The keys are extracted from the objects by the `KeyAdapter` of the tree,
+/// so, unlike with `BTreeMap`, the key and the value are a single object.
+/// The map keeps at most one object per key, and tracks its length. The
+/// objects can still have links to other intrusive collections, which is
+/// the reason to use this map over `BTreeMap`.
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::{intrusive_adapter, KeyAdapter, RBTreeLink, RBTreeMap};
+///
+/// struct User {
+///     link: RBTreeLink,
+///     name: String,
+///     age: u32,
+/// }
+/// intrusive_adapter!(UserAdapter = Box<User>: User { link: RBTreeLink });
+/// impl<'a> KeyAdapter<'a> for UserAdapter {
+///     type Key = &'a str;
+///     fn get_key(&self, user: &'a User) -> &'a str {
+///         &user.name
+///     }
+/// }
+///
+/// let new_user = |name: &str, age| Box::new(User {
+///     link: RBTreeLink::new(),
+///     name: name.to_string(),
+///     age,
+/// });
+/// let mut users = RBTreeMap::new(UserAdapter::new());
+/// assert!(users.insert(new_user("bob", 30)).is_none());
+/// assert!(users.insert(new_user("alice", 25)).is_none());
+/// assert_eq!(users.insert(new_user("bob", 31)).unwrap().age, 30);
+///
+/// assert_eq!(users.len(), 2);
+/// assert_eq!(users.get("bob").unwrap().age, 31);
+/// assert_eq!(users.keys().collect::<Vec<_>>(), ["alice", "bob"]);
+/// assert_eq!(users.remove("alice").unwrap().age, 25);
+/// assert!(users.get("alice").is_none());
+/// ```
+pub struct RBTreeMap<A: Adapter>
+where
+    A::LinkOps: RBTreeOps,
+{
+    tree: RBTree<A>,
+    len: usize,
+}
+
+impl<A: Adapter> RBTreeMap<A>
+where
+    A::LinkOps: RBTreeOps,
+{
+    /// Creates an empty `RBTreeMap`.
+    #[inline]
+    pub fn new(adapter: A) -> RBTreeMap<A> {
+        RBTreeMap {
+            tree: RBTree::new(adapter),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of objects in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes and drops all the objects of the map.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.tree.clear();
+        self.len = 0;
+    }
+
+    /// Gets an iterator over the objects of the map, in key order.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, A> {
+        self.tree.iter()
+    }
+
+    /// Gets an iterator over the objects of the map, in key order.
+    ///
+    /// This is the same as `iter`, since the values are the objects.
+    #[inline]
+    pub fn values(&self) -> Iter<'_, A> {
+        self.tree.iter()
+    }
+
+    /// Returns the object with the smallest key, if any.
+    #[inline]
+    pub fn first(&self) -> Option<&<A::PointerOps as PointerOps>::Value> {
+        self.tree.front().get()
+    }
+
+    /// Returns the object with the largest key, if any.
+    #[inline]
+    pub fn last(&self) -> Option<&<A::PointerOps as PointerOps>::Value> {
+        self.tree.back().get()
+    }
+
+    /// Returns the underlying `RBTree`.
+    #[inline]
+    pub fn as_tree(&self) -> &RBTree<A> {
+        &self.tree
+    }
+
+    /// Consumes the map and returns the underlying `RBTree`.
+    #[inline]
+    pub fn into_tree(self) -> RBTree<A> {
+        self.tree
+    }
+}
+
+impl<A: for<'a> KeyAdapter<'a>> RBTreeMap<A>
+where
+    A::LinkOps: RBTreeOps,
+{
+    /// Returns the object with the given key, if any.
+    #[inline]
+    pub fn get<'a, 'b, Q: ?Sized + Ord>(
+        &'a self,
+        key: &Q,
+    ) -> Option<&'a <A::PointerOps as PointerOps>::Value>
+    where
+        <A as KeyAdapter<'b>>::Key: Borrow<Q>,
+        'a: 'b,
+    {
+        self.tree.find(key).get()
+    }
+
+    /// Returns `true` if the map contains an object with the given key.
+    #[inline]
+    pub fn contains_key<'a, 'b, Q: ?Sized + Ord>(&'a self, key: &Q) -> bool
+    where
+        <A as KeyAdapter<'b>>::Key: Borrow<Q>,
+        'a: 'b,
+    {
+        !self.tree.find(key).is_null()
+    }
+
+    /// Gets an iterator over the keys of the map, in order.
+    #[inline]
+    pub fn keys(&self) -> Keys<'_, A> {
+        Keys {
+            iter: self.tree.iter(),
+            adapter: self.tree.adapter(),
+        }
+    }
+
+    /// Gets a double-ended iterator over the objects whose keys are in
+    /// `range`, in key order.
+    ///
+    /// Unlike `BTreeMap::range`, this doesn't panic if the start of the range
+    /// is after its end, and returns an empty iterator instead.
+    #[inline]
+    pub fn range<'a, Q: ?Sized + Ord, R: RangeBounds<Q>>(&'a self, range: R) -> Iter<'a, A>
+    where
+        <A as KeyAdapter<'a>>::Key: Borrow<Q> + Ord,
+    {
+        self.tree
+            .range(bound(range.start_bound()), bound(range.end_bound()))
+    }
+
+    /// Removes the object with the given key from the map and returns it.
+    ///
+    /// This returns `None` if there is no such object.
+    #[inline]
+    pub fn remove<'a, 'b, Q: ?Sized + Ord>(
+        &'a mut self,
+        key: &Q,
+    ) -> Option<<A::PointerOps as PointerOps>::Pointer>
+    where
+        <A as KeyAdapter<'b>>::Key: Borrow<Q>,
+        'a: 'b,
+    {
+        let val = self.tree.find_mut(key).remove()?;
+        self.len -= 1;
+        Some(val)
+    }
+}
+
+impl<A, V> RBTreeMap<A>
+where
+    A: for<'a> KeyAdapter<'a> + Adapter<PointerOps = DefaultPointerOps<Box<V>>>,
+    A::LinkOps: RBTreeOps,
+    for<'a> <A as KeyAdapter<'a>>::Key: Ord,
+{
+    /// Inserts an object into the map.
+    ///
+    /// If the map already has an object with the same key, it is replaced by
+    /// the new one and returned. Otherwise `None` is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new object is already linked to a different intrusive
+    /// collection.
+    #[inline]
+    pub fn insert(&mut self, val: Box<V>) -> Option<Box<V>> {
+        let entry = {
+            // The object stays in place when its `Box` is moved into the
+            // tree, and the key is dropped before that.
+            let key = self
+                .tree
+                .adapter()
+                .get_key(unsafe { &*(&*val as *const V) });
+            self.tree.entry(&key)
+        };
+        match entry {
+            Entry::Occupied(mut cursor) => cursor.replace_with(val).ok(),
+            Entry::Vacant(cursor) => {
+                cursor.insert(val);
+                self.len += 1;
+                None
+            }
+        }
+    }
+}
+
+impl<A: Adapter + Default> Default for RBTreeMap<A>
+where
+    A::LinkOps: RBTreeOps,
+{
+    #[inline]
+    fn default() -> RBTreeMap<A> {
+        RBTreeMap::new(A::default())
+    }
+}
+
+impl<A: Adapter> fmt::Debug for RBTreeMap<A>
+where
+    A::LinkOps: RBTreeOps,
+    <A::PointerOps as PointerOps>::Value: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.tree.fmt(f)
+    }
+}
+
+impl<'a, A: Adapter + 'a> IntoIterator for &'a RBTreeMap<A>
+where
+    A::LinkOps: RBTreeOps,
+{
+    type Item = &'a <A::PointerOps as PointerOps>::Value;
+    type IntoIter = Iter<'a, A>;
+
+    #[inline]
+    fn into_iter(self) -> Iter<'a, A> {
+        self.iter()
+    }
+}
+
+// =============================================================================
+// Keys
+// =============================================================================
+
+/// An iterator over the keys of an `RBTreeMap`, returned by
+/// `RBTreeMap::keys`.
+pub struct Keys<'a, A: Adapter>
+where
+    A::LinkOps: RBTreeOps,
+{
+    iter: Iter<'a, A>,
+    adapter: &'a A,
+}
+
+impl<'a, A: KeyAdapter<'a> + 'a> Iterator for Keys<'a, A>
+where
+    A::LinkOps: RBTreeOps,
+{
+    type Item = <A as KeyAdapter<'a>>::Key;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let val = self.iter.next()?;
+        Some(self.adapter.get_key(val))
+    }
+}
+
+impl<'a, A: KeyAdapter<'a> + 'a> DoubleEndedIterator for Keys<'a, A>
+where
+    A::LinkOps: RBTreeOps,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let val = self.iter.next_back()?;
+        Some(self.adapter.get_key(val))
+    }
+}
+
+impl<'a, A: KeyAdapter<'a> + 'a> FusedIterator for Keys<'a, A> where A::LinkOps: RBTreeOps {}
+
+impl<'a, A: Adapter + 'a> Clone for Keys<'a, A>
+where
+    A::LinkOps: RBTreeOps,
+{
+    #[inline]
+    fn clone(&self) -> Keys<'a, A> {
+        Keys {
+            iter: self.iter.clone(),
+            adapter: self.adapter,
+        }
+    }
+}
+
+impl<A: Adapter> fmt::Debug for Keys<'_, A>
+where
+    A::LinkOps: RBTreeOps,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Keys").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RBTreeMap;
+    use crate::{KeyAdapter, RBTreeLink};
+    use core::ops::Bound;
+    use std::boxed::Box;
+    use std::vec::Vec;
+
+    #[derive(Debug)]
+    struct Obj {
+        link: RBTreeLink,
+        key: u32,
+        value: u32,
+    }
+    intrusive_adapter!(ObjAdapter = Box<Obj>: Obj { link: RBTreeLink });
+    impl<'a> KeyAdapter<'a> for ObjAdapter {
+        type Key = u32;
+        fn get_key(&self, x: &'a Obj) -> u32 {
+            x.key
+        }
+    }
+
+    fn make_obj(key: u32, value: u32) -> Box<Obj> {
+        Box::new(Obj {
+            link: RBTreeLink::new(),
+            key,
+            value,
+        })
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut map = RBTreeMap::new(ObjAdapter::new());
+        assert!(map.is_empty());
+        for &key in &[5, 1, 4, 2, 3] {
+            assert!(map.insert(make_obj(key, key * 10)).is_none());
+        }
+        assert_eq!(map.len(), 5);
+        assert_eq!(map.keys().collect::<Vec<_>>(), [1, 2, 3, 4, 5]);
+        assert_eq!(map.keys().rev().collect::<Vec<_>>(), [5, 4, 3, 2, 1]);
+
+        let old = map.insert(make_obj(3, 33)).unwrap();
+        assert_eq!((old.key, old.value), (3, 30));
+        assert!(!old.link.is_linked());
+        assert_eq!(map.len(), 5);
+        assert_eq!(map.get(&3).unwrap().value, 33);
+        assert!(map.get(&6).is_none());
+        assert!(map.contains_key(&1));
+
+        assert_eq!(map.remove(&1).unwrap().value, 10);
+        assert!(map.remove(&1).is_none());
+        assert!(!map.contains_key(&1));
+        assert_eq!(map.len(), 4);
+        assert_eq!(map.first().unwrap().key, 2);
+        assert_eq!(map.last().unwrap().key, 5);
+        assert_eq!(
+            map.values().map(|x| x.value).collect::<Vec<_>>(),
+            [20, 33, 40, 50]
+        );
+
+        map.clear();
+        assert!(map.is_empty());
+        assert!(map.first().is_none());
+    }
+
+    #[test]
+    fn test_range() {
+        let mut map = RBTreeMap::new(ObjAdapter::new());
+        for key in 0..10 {
+            map.insert(make_obj(key, 0));
+        }
+        let keys =
+            |iter: crate::rbtree::Iter<'_, ObjAdapter>| iter.map(|x| x.key).collect::<Vec<_>>();
+        assert_eq!(keys(map.range(3..6)), [3, 4, 5]);
+        assert_eq!(keys(map.range(..=2)), [0, 1, 2]);
+        assert_eq!(keys(map.range(8..)), [8, 9]);
+        assert_eq!(keys(map.range(..)).len(), 10);
+        assert_eq!(
+            keys(map.range((Bound::Excluded(2), Bound::Included(4)))),
+            [3, 4]
+        );
+        assert_eq!(keys(map.range(12..)), []);
+        assert_eq!(
+            keys(map.range((Bound::Included(6), Bound::Excluded(4)))),
+            []
+        );
+        assert_eq!(map.range(3..6).next_back().unwrap().key, 5);
+        assert_eq!(map.into_tree().iter().count(), 10);
+    }
+}
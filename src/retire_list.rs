@@ -0,0 +1,296 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Deferred release of removed elements, for collections with concurrent
+//! readers.
+
+use core::fmt;
+
+use crate::alloc::vec::Vec;
+use crate::link_ops::LinkOps;
+use crate::pointer_ops::PointerOps;
+use crate::Adapter;
+
+// =============================================================================
+// RetireList
+// =============================================================================
+
+/// A list of elements which were removed from a collection but may still be
+/// in use by concurrent readers.
+///
+/// Readers which traverse a collection while it is modified, for example
+/// through `SeqLocked::read` or an RCU read-side critical section, may still
+/// be looking at an element after it is removed. `linked_list::CursorMut::retire`
+/// and `singly_linked_list::CursorMut::retire_next` remove an element without
+/// touching its links, so readers which are on it can still move past it, and
+/// add it to a `RetireList` instead of converting it back into an owned
+/// pointer.
+///
+/// Once every reader which could have seen the retired elements is done (a
+/// grace period, in RCU terms), `synchronize` or `reclaim` releases their
+/// links and gives back the owned pointers. Elements stay linked until then,
+/// so they can't be inserted into another collection before it is safe to do
+/// so.
+///
+/// The adapter of the `RetireList` must be equivalent to the one of the
+/// collection the elements are retired from. Dropping a `RetireList` which
+/// still holds elements leaks them.
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::retire_list::RetireList;
+/// use intrusive_collections::{intrusive_adapter, LinkedList, LinkedListAtomicLink};
+/// use std::sync::Arc;
+///
+/// struct Obj {
+///     link: LinkedListAtomicLink,
+///     value: u32,
+/// }
+/// intrusive_adapter!(ObjAdapter = Arc<Obj>: Obj { link: LinkedListAtomicLink });
+///
+/// let obj = Arc::new(Obj { link: LinkedListAtomicLink::new(), value: 1 });
+/// let mut list = LinkedList::new(ObjAdapter::new());
+/// let mut retired = RetireList::new(ObjAdapter::new());
+/// list.push_back(obj.clone());
+///
+/// list.front_mut().retire(&mut retired);
+/// assert!(list.is_empty());
+/// assert!(obj.link.is_linked());
+///
+/// // Waits until no reader can still be looking at the element, with
+/// // something like `synchronize_rcu`.
+/// retired.synchronize(|| {});
+/// assert!(!obj.link.is_linked());
+/// assert_eq!(Arc::strong_count(&obj), 1);
+/// ```
+pub struct RetireList<A: Adapter> {
+    retired: Vec<<A::LinkOps as LinkOps>::LinkPtr>,
+    adapter: A,
+}
+
+impl<A: Adapter> RetireList<A> {
+    /// Creates an empty `RetireList`.
+    #[inline]
+    pub const fn new(adapter: A) -> RetireList<A> {
+        RetireList {
+            retired: Vec::new(),
+            adapter,
+        }
+    }
+
+    /// Returns `true` if no element is waiting to be reclaimed.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.retired.is_empty()
+    }
+
+    /// Returns the number of elements waiting to be reclaimed.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.retired.len()
+    }
+
+    /// Adds the link of an element which was unlinked from its neighbors, but
+    /// not released.
+    #[inline]
+    pub(crate) unsafe fn push(&mut self, link: <A::LinkOps as LinkOps>::LinkPtr) {
+        self.retired.push(link);
+    }
+
+    /// Calls `synchronize`, which must wait until no reader can still be
+    /// looking at the retired elements, and then drops them.
+    #[inline]
+    pub fn synchronize(&mut self, synchronize: impl FnOnce()) {
+        synchronize();
+        self.reclaim(drop);
+    }
+
+    /// Releases the links of all the retired elements and passes the owned
+    /// pointers to `f`, without waiting.
+    ///
+    /// This is for callers which know that a grace period has already
+    /// elapsed since the elements were retired, for example from a callback
+    /// run by their RCU implementation.
+    #[inline]
+    pub fn reclaim(&mut self, mut f: impl FnMut(<A::PointerOps as PointerOps>::Pointer)) {
+        // Elements are reclaimed in order, and only removed from the vector
+        // once done, so that the remaining ones are still retired if `f`
+        // panics.
+        let mut reclaimed = Reclaimed {
+            retired: &mut self.retired,
+            len: 0,
+        };
+        while let Some(&link) = reclaimed.retired.get(reclaimed.len) {
+            reclaimed.len += 1;
+            unsafe {
+                self.adapter.link_ops_mut().release_link(link);
+                let value = self.adapter.get_value(link);
+                f(self.adapter.pointer_ops().from_raw(value));
+            }
+        }
+    }
+}
+
+// Removes the first `len` elements of `retired` when dropped, which are the
+// ones `reclaim` is done with.
+struct Reclaimed<'a, T> {
+    retired: &'a mut Vec<T>,
+    len: usize,
+}
+
+impl<T> Drop for Reclaimed<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.retired.drain(..self.len);
+    }
+}
+
+// Allow moving the list to another thread, along with the elements it holds.
+unsafe impl<A: Adapter + Send> Send for RetireList<A> where
+    <A::PointerOps as PointerOps>::Pointer: Send
+{
+}
+
+impl<A: Adapter> fmt::Debug for RetireList<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetireList")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RetireList;
+    use crate::linked_list::LinkOps;
+    use crate::LinkedListOps;
+    use crate::{LinkedList, LinkedListLink, SinglyLinkedList, SinglyLinkedListLink};
+    use core::ptr::NonNull;
+    use std::format;
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    struct Obj {
+        link: LinkedListLink,
+        slink: SinglyLinkedListLink,
+        value: u32,
+    }
+    intrusive_adapter!(ObjAdapter = Rc<Obj>: Obj { link: LinkedListLink });
+    intrusive_adapter!(SObjAdapter = Rc<Obj>: Obj { slink: SinglyLinkedListLink });
+
+    fn make_obj(value: u32) -> Rc<Obj> {
+        Rc::new(Obj {
+            link: LinkedListLink::new(),
+            slink: SinglyLinkedListLink::new(),
+            value,
+        })
+    }
+
+    #[test]
+    fn test_retire() {
+        let objs: Vec<_> = (0..3).map(make_obj).collect();
+        let mut l = LinkedList::new(ObjAdapter::new());
+        let mut retired = RetireList::new(ObjAdapter::new());
+        for obj in &objs {
+            l.push_back(obj.clone());
+        }
+
+        let mut cur = l.front_mut();
+        cur.move_next();
+        assert!(cur.retire(&mut retired));
+        assert_eq!(cur.get().unwrap().value, 2);
+        cur.move_next();
+        assert!(!cur.retire(&mut retired));
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [0, 2]);
+        assert_eq!(retired.len(), 1);
+        assert_eq!(format!("{:?}", retired), "RetireList { len: 1, .. }");
+
+        // The retired element still points to its old neighbors.
+        assert!(objs[1].link.is_linked());
+        let link = |i: usize| NonNull::from(&objs[i].link);
+        unsafe {
+            assert_eq!(LinkOps.next(link(1)), Some(link(2)));
+            assert_eq!(LinkOps.prev(link(1)), Some(link(0)));
+        }
+
+        let mut synchronized = false;
+        retired.synchronize(|| synchronized = true);
+        assert!(synchronized);
+        assert!(retired.is_empty());
+        assert!(!objs[1].link.is_linked());
+        assert_eq!(Rc::strong_count(&objs[1]), 1);
+    }
+
+    #[test]
+    fn test_retire_next() {
+        let objs: Vec<_> = (0..3).map(make_obj).collect();
+        let mut l = SinglyLinkedList::new(SObjAdapter::new());
+        let mut retired = RetireList::new(SObjAdapter::new());
+        for obj in objs.iter().rev() {
+            l.push_front(obj.clone());
+        }
+
+        // Retiring the head, then the element after the new head.
+        assert!(l.cursor_mut().retire_next(&mut retired));
+        let mut cur = l.front_mut();
+        assert!(cur.retire_next(&mut retired));
+        assert!(!cur.retire_next(&mut retired));
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [1]);
+        assert!(objs[0].slink.is_linked());
+        assert!(objs[2].slink.is_linked());
+
+        let mut values = Vec::new();
+        retired.reclaim(|obj| values.push(obj.value));
+        assert_eq!(values, [0, 2]);
+        assert_eq!(Rc::strong_count(&objs[0]), 1);
+        assert_eq!(Rc::strong_count(&objs[2]), 1);
+        assert!(!objs[0].slink.is_linked());
+        assert!(!objs[2].slink.is_linked());
+    }
+
+    #[test]
+    fn test_reclaim_panic() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let objs: Vec<_> = (0..3).map(make_obj).collect();
+        let mut l = LinkedList::new(ObjAdapter::new());
+        let mut retired = RetireList::new(ObjAdapter::new());
+        for obj in &objs {
+            l.push_back(obj.clone());
+        }
+        while l.front_mut().retire(&mut retired) {}
+        assert_eq!(retired.len(), 3);
+
+        // The elements after the one `f` panicked on are still retired.
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            retired.reclaim(|obj| assert!(obj.value != 1));
+        }));
+        assert!(result.is_err());
+        assert_eq!(retired.len(), 1);
+        assert!(!objs[1].link.is_linked());
+        assert!(objs[2].link.is_linked());
+
+        let mut values = Vec::new();
+        retired.reclaim(|obj| values.push(obj.value));
+        assert_eq!(values, [2]);
+        assert_eq!(Rc::strong_count(&objs[2]), 1);
+    }
+
+    #[test]
+    fn test_reinsert_after_reclaim() {
+        let obj = make_obj(0);
+        let mut l = LinkedList::new(ObjAdapter::new());
+        let mut retired = RetireList::new(ObjAdapter::new());
+        l.push_back(obj.clone());
+        l.front_mut().retire(&mut retired);
+        let mut reclaimed = Vec::new();
+        retired.reclaim(|obj| reclaimed.push(obj));
+        l.push_back(reclaimed.pop().unwrap());
+        assert_eq!(l.front().get().unwrap().value, 0);
+    }
+}
@@ -0,0 +1,67 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+// Annotations telling a sanitizer that the fields of a link which aren't used
+// once it is unlinked are dead, so that reading them through a stale cursor or
+// pointer is reported where it happens rather than when the value read leads
+// somewhere wrong.
+//
+// They are enabled by building with
+// `--cfg intrusive_collections_sanitizer="address"` (AddressSanitizer, which
+// reports any access to a poisoned field) or `="memory"` (MemorySanitizer,
+// which reports uses of the value read) along with the matching
+// `-Zsanitizer` flag. This is a `cfg` rather than a Cargo feature since the
+// annotations don't link without the sanitizer runtime. They are no-ops
+// otherwise.
+
+use core::mem;
+
+#[cfg(intrusive_collections_sanitizer = "address")]
+extern "C" {
+    fn __asan_poison_memory_region(addr: *const u8, size: usize);
+    fn __asan_unpoison_memory_region(addr: *const u8, size: usize);
+}
+
+#[cfg(intrusive_collections_sanitizer = "memory")]
+extern "C" {
+    fn __msan_poison(addr: *const u8, size: usize);
+    fn __msan_unpoison(addr: *const u8, size: usize);
+}
+
+// Marks `field` as dead until `unpoison` is called on it. The field must not
+// be accessed at all in between with AddressSanitizer.
+#[inline]
+#[allow(unused_variables)]
+pub(crate) unsafe fn poison<T>(field: &T) {
+    let addr = field as *const T as *const u8;
+    let size = mem::size_of::<T>();
+    #[cfg(intrusive_collections_sanitizer = "address")]
+    __asan_poison_memory_region(addr, size);
+    #[cfg(intrusive_collections_sanitizer = "memory")]
+    __msan_poison(addr, size);
+}
+
+// Makes `field` accessible again before it is written.
+#[inline]
+#[allow(unused_variables)]
+pub(crate) unsafe fn unpoison<T>(field: &T) {
+    let addr = field as *const T as *const u8;
+    let size = mem::size_of::<T>();
+    #[cfg(intrusive_collections_sanitizer = "address")]
+    __asan_unpoison_memory_region(addr, size);
+    #[cfg(intrusive_collections_sanitizer = "memory")]
+    __msan_unpoison(addr, size);
+}
+
+// Checks whether the first byte of `field` is poisoned, for tests.
+#[cfg(all(test, intrusive_collections_sanitizer = "address"))]
+pub(crate) fn is_poisoned<T>(field: &T) -> bool {
+    extern "C" {
+        fn __asan_address_is_poisoned(addr: *const u8) -> i32;
+    }
+    unsafe { __asan_address_is_poisoned(field as *const T as *const u8) != 0 }
+}
@@ -0,0 +1,483 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Linked lists read optimistically through a sequence lock.
+
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::ptr::{self, NonNull};
+
+use crate::linked_list::{AtomicLink, AtomicLinkOps, LinkedList};
+use crate::pointer_ops::PointerOps;
+use crate::sync::{fence, spin_loop, AtomicPtr, AtomicUsize, Ordering};
+use crate::Adapter;
+
+// =============================================================================
+// SeqLocked
+// =============================================================================
+
+/// A `LinkedList` of `LinkedListAtomicLink`s paired with a sequence counter,
+/// so that it can be read from several threads while a writer modifies it.
+///
+/// Writers go through `write`, which gives them exclusive access to the
+/// list. The counter is odd while a write is in progress and is incremented
+/// when it starts and when it ends. Readers don't block the writer: `read`
+/// records the counter before running the reader, and runs it again if a
+/// write started in the meantime. This suits lists which are read far more
+/// often than they are modified, such as routing tables.
+///
+/// Readers never access the `LinkedList` itself, which the writer has a
+/// mutable reference to. They start from a copy of the front of the list
+/// stored in an atomic pointer, and follow the `next` pointers of the links,
+/// which are atomic too. `SeqReader::iter` validates the counter after
+/// loading each pointer and before dereferencing it, so a reader only ever
+/// sees objects which were in the list, but it can see them while the writer
+/// changes the list: the fields of the objects which readers look at must not
+/// be modified while the objects are in the list, unless they are atomic.
+///
+/// Objects removed from the list must also stay allocated until no reader can
+/// still be looking at them, for example by never freeing them or by
+/// deferring their release until all readers are done with
+/// `retire_list::RetireList`. This requirement makes `read` unsafe.
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::seq_locked::SeqLocked;
+/// use intrusive_collections::{intrusive_adapter, LinkedList, LinkedListAtomicLink, UnsafeRef};
+///
+/// struct Route {
+///     link: LinkedListAtomicLink,
+///     port: u16,
+/// }
+/// intrusive_adapter!(RouteAdapter = UnsafeRef<Route>: Route { link: LinkedListAtomicLink });
+///
+/// // The routes are never freed while the table is in use.
+/// let routes: Vec<Route> = (0..4)
+///     .map(|port| Route { link: LinkedListAtomicLink::new(), port })
+///     .collect();
+/// let table = SeqLocked::new(LinkedList::new(RouteAdapter::new()));
+/// table.write(|list| {
+///     for route in &routes {
+///         list.push_back(unsafe { UnsafeRef::from_raw(route) });
+///     }
+/// });
+///
+/// let ports = unsafe { table.read(|reader| reader.iter().map(|r| r.port).collect::<Vec<_>>()) };
+/// assert_eq!(ports, [0, 1, 2, 3]);
+/// # table.write(|list| list.fast_clear());
+/// ```
+pub struct SeqLocked<A: Adapter<LinkOps = AtomicLinkOps>> {
+    seq: AtomicUsize,
+    // The link of the front of the list, or null if it is empty. It is only
+    // updated at the end of a write.
+    head: AtomicPtr<AtomicLink>,
+    // A copy of the adapter of the list, which readers use to find the
+    // objects from their links.
+    adapter: A,
+    list: UnsafeCell<LinkedList<A>>,
+}
+
+impl<A: Adapter<LinkOps = AtomicLinkOps> + Clone> SeqLocked<A> {
+    /// Creates a new `SeqLocked` wrapping the given list.
+    #[inline]
+    pub fn new(list: LinkedList<A>) -> SeqLocked<A> {
+        let lock = SeqLocked {
+            seq: AtomicUsize::new(0),
+            head: AtomicPtr::new(ptr::null_mut()),
+            adapter: list.adapter().clone(),
+            list: UnsafeCell::new(list),
+        };
+        lock.update_head();
+        lock
+    }
+}
+
+impl<A: Adapter<LinkOps = AtomicLinkOps>> SeqLocked<A> {
+    /// Consumes the `SeqLocked` and returns the list.
+    #[inline]
+    pub fn into_inner(self) -> LinkedList<A> {
+        self.list.into_inner()
+    }
+
+    /// Returns the current value of the sequence counter, which is odd while
+    /// a write is in progress.
+    #[inline]
+    pub fn sequence(&self) -> usize {
+        self.seq.load(Ordering::Acquire)
+    }
+
+    // Copies the front of the list for the readers. This must only be called
+    // by the writer, or before the list is shared.
+    #[inline]
+    fn update_head(&self) {
+        let list = unsafe { &*self.list.get() };
+        let head = list.front().get().map_or(ptr::null_mut(), |value| unsafe {
+            self.adapter.get_link(value).as_ptr()
+        });
+        self.head.store(head, Ordering::Relaxed);
+    }
+
+    /// Runs `f` with exclusive access to the list.
+    ///
+    /// Writers are serialized: this waits for any write in progress on
+    /// another thread to finish. Calling `write` from inside `f` deadlocks.
+    #[inline]
+    pub fn write<R>(&self, f: impl FnOnce(&mut LinkedList<A>) -> R) -> R {
+        let mut seq = self.seq.load(Ordering::Relaxed);
+        loop {
+            if seq & 1 != 0 {
+                spin_loop();
+                seq = self.seq.load(Ordering::Relaxed);
+                continue;
+            }
+            match self
+                .seq
+                .compare_exchange_weak(seq, seq + 1, Ordering::Acquire, Ordering::Relaxed)
+            {
+                Ok(_) => break,
+                Err(current) => seq = current,
+            }
+        }
+        // Readers which see any of the writes below must also see the odd
+        // counter when they validate.
+        fence(Ordering::Release);
+
+        // Ends the write even if `f` panics, so that readers and other
+        // writers don't wait forever.
+        struct WriteGuard<'a, A: Adapter<LinkOps = AtomicLinkOps>> {
+            lock: &'a SeqLocked<A>,
+            end: usize,
+        }
+        impl<A: Adapter<LinkOps = AtomicLinkOps>> Drop for WriteGuard<'_, A> {
+            #[inline]
+            fn drop(&mut self) {
+                self.lock.update_head();
+                self.lock.seq.store(self.end, Ordering::Release);
+            }
+        }
+        let _guard = WriteGuard {
+            lock: self,
+            end: seq.wrapping_add(2),
+        };
+
+        f(unsafe { &mut *self.list.get() })
+    }
+
+    /// Runs the reader `f` until it completes without a write happening at
+    /// the same time, and returns its result.
+    ///
+    /// A result returned while a write was in progress is thrown away, and
+    /// `f` is run again once the write is over.
+    ///
+    /// # Safety
+    ///
+    /// Objects which are removed from the list must not be freed while a
+    /// reader which started before the removal may still be running.
+    #[inline]
+    pub unsafe fn read<R>(&self, mut f: impl FnMut(&SeqReader<'_, A>) -> R) -> R {
+        loop {
+            let reader = self.begin_read();
+            let result = f(&reader);
+            if reader.validate() {
+                return result;
+            }
+        }
+    }
+
+    /// Waits for any write in progress to finish and starts a read.
+    #[inline]
+    fn begin_read(&self) -> SeqReader<'_, A> {
+        loop {
+            let seq = self.seq.load(Ordering::Acquire);
+            if seq & 1 == 0 {
+                return SeqReader { lock: self, seq };
+            }
+            spin_loop();
+        }
+    }
+}
+
+// The objects are read from several threads at once, and the list is written
+// from whichever thread calls `write`.
+unsafe impl<A: Adapter<LinkOps = AtomicLinkOps> + Sync> Sync for SeqLocked<A>
+where
+    LinkedList<A>: Send,
+    <A::PointerOps as PointerOps>::Value: Sync,
+{
+}
+
+impl<A: Adapter<LinkOps = AtomicLinkOps> + Clone + Default> Default for SeqLocked<A> {
+    #[inline]
+    fn default() -> SeqLocked<A> {
+        SeqLocked::new(LinkedList::new(A::default()))
+    }
+}
+
+impl<A: Adapter<LinkOps = AtomicLinkOps>> fmt::Debug for SeqLocked<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SeqLocked")
+            .field("sequence", &self.sequence())
+            .finish_non_exhaustive()
+    }
+}
+
+// =============================================================================
+// SeqReader
+// =============================================================================
+
+/// Access to a `SeqLocked` list from a reader.
+///
+/// See `SeqLocked::read` for how it must be used.
+pub struct SeqReader<'a, A: Adapter<LinkOps = AtomicLinkOps>> {
+    lock: &'a SeqLocked<A>,
+    seq: usize,
+}
+
+impl<'a, A: Adapter<LinkOps = AtomicLinkOps>> SeqReader<'a, A> {
+    /// Gets an iterator over the objects in the list, from front to back.
+    ///
+    /// The iterator stops early if a write starts, in which case the result
+    /// of the reader is thrown away.
+    #[inline]
+    pub fn iter(&self) -> SeqIter<'a, A> {
+        SeqIter {
+            next: self.lock.head.load(Ordering::Relaxed),
+            reader: SeqReader {
+                lock: self.lock,
+                seq: self.seq,
+            },
+        }
+    }
+
+    /// Returns `true` if no write started since the read did, meaning that
+    /// everything loaded from the list so far is consistent.
+    ///
+    /// This can be used to give up early on a long computation.
+    #[inline]
+    pub fn validate(&self) -> bool {
+        // Orders the loads from the list before the counter is checked.
+        fence(Ordering::Acquire);
+        self.lock.seq.load(Ordering::Relaxed) == self.seq
+    }
+}
+
+impl<A: Adapter<LinkOps = AtomicLinkOps>> fmt::Debug for SeqReader<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SeqReader")
+            .field("sequence", &self.seq)
+            .finish_non_exhaustive()
+    }
+}
+
+// =============================================================================
+// SeqIter
+// =============================================================================
+
+/// An iterator over the objects of a `SeqLocked` list, returned by
+/// `SeqReader::iter`.
+pub struct SeqIter<'a, A: Adapter<LinkOps = AtomicLinkOps>> {
+    // The link of the next object, which isn't validated yet.
+    next: *mut AtomicLink,
+    reader: SeqReader<'a, A>,
+}
+
+impl<'a, A: Adapter<LinkOps = AtomicLinkOps>> Iterator for SeqIter<'a, A> {
+    type Item = &'a <A::PointerOps as PointerOps>::Value;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a <A::PointerOps as PointerOps>::Value> {
+        let link = NonNull::new(self.next)?;
+        if !self.reader.validate() {
+            self.next = ptr::null_mut();
+            return None;
+        }
+        // No write started since the pointer was loaded, so it points to the
+        // link of an object which is in the list, or was removed since and is
+        // still allocated.
+        unsafe {
+            self.next = link.as_ref().load_next_racy();
+            Some(&*self.reader.lock.adapter.get_value(link))
+        }
+    }
+}
+
+impl<A: Adapter<LinkOps = AtomicLinkOps>> FusedIterator for SeqIter<'_, A> {}
+
+impl<A: Adapter<LinkOps = AtomicLinkOps>> fmt::Debug for SeqIter<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SeqIter").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SeqLocked;
+    use crate::{LinkedList, LinkedListAtomicLink, UnsafeRef};
+    #[cfg(loom)]
+    use std::boxed::Box;
+    use std::vec::Vec;
+
+    struct Obj {
+        link: LinkedListAtomicLink,
+        value: u32,
+    }
+    intrusive_adapter!(ObjAdapter = UnsafeRef<Obj>: Obj { link: LinkedListAtomicLink });
+
+    fn make_objs(n: u32) -> Vec<Obj> {
+        (0..n)
+            .map(|value| Obj {
+                link: LinkedListAtomicLink::new(),
+                value,
+            })
+            .collect()
+    }
+
+    // Sums the values in the list, following the rules of `SeqLocked::read`.
+    fn sum(list: &SeqLocked<ObjAdapter>) -> u32 {
+        unsafe { list.read(|reader| reader.iter().map(|obj| obj.value).sum()) }
+    }
+
+    #[test]
+    fn test_read_write() {
+        let objs = make_objs(4);
+        let list = SeqLocked::new(LinkedList::new(ObjAdapter::new()));
+        assert_eq!(list.sequence(), 0);
+        assert_eq!(sum(&list), 0);
+
+        list.write(|l| {
+            for obj in &objs {
+                l.push_back(unsafe { UnsafeRef::from_raw(obj) });
+            }
+        });
+        assert_eq!(list.sequence(), 2);
+        assert_eq!(sum(&list), 6);
+
+        let mut runs = 0;
+        let len = unsafe {
+            list.read(|reader| {
+                runs += 1;
+                let mut iter = reader.iter();
+                assert!(iter.next().is_some());
+                if runs == 1 {
+                    // Simulates a write happening while reading.
+                    assert!(reader.validate());
+                    list.write(|l| l.pop_front());
+                    assert!(!reader.validate());
+                    assert!(iter.next().is_none());
+                }
+                1 + iter.count()
+            })
+        };
+        assert_eq!(runs, 2);
+        assert_eq!(len, 3);
+        assert_eq!(list.sequence(), 4);
+
+        let mut l = list.into_inner();
+        l.fast_clear();
+    }
+
+    #[test]
+    fn test_write_panic() {
+        let list = SeqLocked::new(LinkedList::new(ObjAdapter::new()));
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            list.write(|_| panic!("oops"));
+        }));
+        assert!(result.is_err());
+        assert_eq!(list.sequence(), 2);
+        assert!(list.write(|l| l.is_empty()));
+    }
+
+    #[test]
+    fn test_front_changes() {
+        let objs = make_objs(3);
+        let mut l = LinkedList::new(ObjAdapter::new());
+        l.push_back(unsafe { UnsafeRef::from_raw(&objs[1]) });
+        let list = SeqLocked::new(l);
+        assert_eq!(sum(&list), 1);
+
+        // Readers see the new front once the write is over.
+        list.write(|l| l.push_front(unsafe { UnsafeRef::from_raw(&objs[2]) }));
+        let values =
+            unsafe { list.read(|reader| reader.iter().map(|x| x.value).collect::<Vec<_>>()) };
+        assert_eq!(values, [2, 1]);
+        list.write(|l| l.clear());
+        assert_eq!(sum(&list), 0);
+
+        let list = SeqLocked::<ObjAdapter>::default();
+        assert_eq!(std::format!("{:?}", list), "SeqLocked { sequence: 0, .. }");
+    }
+
+    #[test]
+    fn test_threads() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::thread;
+
+        let writes = if cfg!(miri) { 100 } else { 10000 };
+        let objs = make_objs(16);
+        let list = SeqLocked::new(LinkedList::new(ObjAdapter::new()));
+        list.write(|l| {
+            for obj in &objs {
+                l.push_back(unsafe { UnsafeRef::from_raw(obj) });
+            }
+        });
+        let total = sum(&list);
+        let done = AtomicBool::new(false);
+
+        thread::scope(|s| {
+            for _ in 0..2 {
+                s.spawn(|| {
+                    while !done.load(Ordering::Acquire) {
+                        assert_eq!(sum(&list), total);
+                    }
+                });
+            }
+            for _ in 0..writes {
+                list.write(|l| {
+                    let obj = l.pop_front().unwrap();
+                    l.push_back(obj);
+                });
+            }
+            done.store(true, Ordering::Release);
+        });
+
+        assert_eq!(list.sequence(), 2 * (writes + 1));
+        list.write(|l| l.fast_clear());
+    }
+
+    #[cfg(loom)]
+    #[test]
+    fn test_loom_read_write() {
+        use loom::thread;
+
+        loom::model(|| {
+            // Loom threads need a `'static` list, and the objects must outlive
+            // the readers.
+            let objs = Box::leak(make_objs(2).into_boxed_slice());
+            let list = SeqLocked::new(LinkedList::new(ObjAdapter::new()));
+            list.write(|l| {
+                for obj in objs.iter() {
+                    l.push_back(unsafe { UnsafeRef::from_raw(obj) });
+                }
+            });
+            let list = &*Box::leak(Box::new(list));
+            let reader = thread::spawn(move || unsafe {
+                list.read(|reader| reader.iter().map(|obj| obj.value).collect::<Vec<_>>())
+            });
+            // Moving the front to the back races with the reader, which must
+            // see the list either before or after the write.
+            list.write(|l| {
+                let obj = l.pop_front().unwrap();
+                l.push_back(obj);
+            });
+            let values = reader.join().unwrap();
+            assert!(values == [0, 1] || values == [1, 0], "{:?}", values);
+            assert_eq!(sum(list), 1);
+        });
+    }
+}
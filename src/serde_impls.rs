@@ -0,0 +1,350 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+// Collections are serialized as a sequence of their elements, in iteration
+// order, like the `std` containers. Collections of `Box` can be deserialized
+// from such a sequence, allocating the elements and inserting them in order.
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use core::fmt;
+#[cfg(feature = "alloc")]
+use core::marker::PhantomData;
+
+#[cfg(feature = "alloc")]
+use serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::{Serialize, Serializer};
+
+use crate::frozen::Frozen;
+#[cfg(feature = "alloc")]
+use crate::key_adapter::KeyAdapter;
+use crate::linked_list::{LinkedList, LinkedListOps};
+#[cfg(feature = "alloc")]
+use crate::pointer_ops::DefaultPointerOps;
+use crate::pointer_ops::PointerOps;
+use crate::rbtree::{RBTree, RBTreeOps};
+use crate::singly_linked_list::{SinglyLinkedList, SinglyLinkedListOps};
+use crate::xor_linked_list::{XorLinkedList, XorLinkedListOps};
+use crate::Adapter;
+
+macro_rules! impl_serialize {
+    ($collection:ident, $ops:ident) => {
+        impl<A: Adapter> Serialize for $collection<A>
+        where
+            A::LinkOps: $ops,
+            <A::PointerOps as PointerOps>::Value: Serialize,
+        {
+            #[inline]
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_seq(self.iter())
+            }
+        }
+    };
+}
+
+impl_serialize!(LinkedList, LinkedListOps);
+impl_serialize!(SinglyLinkedList, SinglyLinkedListOps);
+impl_serialize!(XorLinkedList, XorLinkedListOps);
+impl_serialize!(RBTree, RBTreeOps);
+
+impl<C: ?Sized + Serialize> Serialize for Frozen<'_, C> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.collection.serialize(serializer)
+    }
+}
+
+// Collections which can be built from the elements of a sequence, in the
+// order in which they were serialized. This is public since it appears in the
+// bounds of the `Deserialize` implementations, but it can't be named outside
+// of the crate.
+#[cfg(feature = "alloc")]
+pub trait FromElements: Sized {
+    type Value;
+
+    fn from_elements<'de, S: SeqAccess<'de>>(seq: S) -> Result<Self, S::Error>
+    where
+        Self::Value: Deserialize<'de>;
+}
+
+#[cfg(feature = "alloc")]
+impl<A, V> FromElements for LinkedList<A>
+where
+    A: Adapter<PointerOps = DefaultPointerOps<Box<V>>> + Default,
+    A::LinkOps: LinkedListOps,
+{
+    type Value = V;
+
+    fn from_elements<'de, S: SeqAccess<'de>>(mut seq: S) -> Result<Self, S::Error>
+    where
+        V: Deserialize<'de>,
+    {
+        let mut list = LinkedList::new(A::default());
+        while let Some(value) = seq.next_element()? {
+            list.push_back(Box::new(value));
+        }
+        Ok(list)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A, V> FromElements for SinglyLinkedList<A>
+where
+    A: Adapter<PointerOps = DefaultPointerOps<Box<V>>> + Default,
+    A::LinkOps: SinglyLinkedListOps,
+{
+    type Value = V;
+
+    fn from_elements<'de, S: SeqAccess<'de>>(mut seq: S) -> Result<Self, S::Error>
+    where
+        V: Deserialize<'de>,
+    {
+        let mut list = SinglyLinkedList::new(A::default());
+        let mut cursor = list.cursor_mut();
+        while let Some(value) = seq.next_element()? {
+            cursor.insert_after(Box::new(value));
+            cursor.move_next();
+        }
+        Ok(list)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A, V> FromElements for XorLinkedList<A>
+where
+    A: Adapter<PointerOps = DefaultPointerOps<Box<V>>> + Default,
+    A::LinkOps: XorLinkedListOps,
+{
+    type Value = V;
+
+    fn from_elements<'de, S: SeqAccess<'de>>(mut seq: S) -> Result<Self, S::Error>
+    where
+        V: Deserialize<'de>,
+    {
+        let mut list = XorLinkedList::new(A::default());
+        while let Some(value) = seq.next_element()? {
+            list.push_back(Box::new(value));
+        }
+        Ok(list)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A, V> FromElements for RBTree<A>
+where
+    A: Adapter<PointerOps = DefaultPointerOps<Box<V>>> + Default,
+    A: for<'a> KeyAdapter<'a>,
+    for<'a> <A as KeyAdapter<'a>>::Key: Ord,
+    <A as Adapter>::LinkOps: RBTreeOps,
+{
+    type Value = V;
+
+    fn from_elements<'de, S: SeqAccess<'de>>(mut seq: S) -> Result<Self, S::Error>
+    where
+        V: Deserialize<'de>,
+    {
+        let mut tree = RBTree::new(A::default());
+        while let Some(value) = seq.next_element()? {
+            tree.insert(Box::new(value));
+        }
+        Ok(tree)
+    }
+}
+
+#[cfg(feature = "alloc")]
+struct SeqVisitor<C>(PhantomData<fn() -> C>);
+
+#[cfg(feature = "alloc")]
+impl<'de, C: FromElements> Visitor<'de> for SeqVisitor<C>
+where
+    C::Value: Deserialize<'de>,
+{
+    type Value = C;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a sequence")
+    }
+
+    #[inline]
+    fn visit_seq<S: SeqAccess<'de>>(self, seq: S) -> Result<C, S::Error> {
+        C::from_elements(seq)
+    }
+}
+
+macro_rules! impl_deserialize {
+    ($collection:ident, $ops:ident) => {
+        #[cfg(feature = "alloc")]
+        impl<'de, A: Adapter> Deserialize<'de> for $collection<A>
+        where
+            A::LinkOps: $ops,
+            $collection<A>: FromElements,
+            <$collection<A> as FromElements>::Value: Deserialize<'de>,
+        {
+            #[inline]
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_seq(SeqVisitor(PhantomData))
+            }
+        }
+    };
+}
+
+impl_deserialize!(LinkedList, LinkedListOps);
+impl_deserialize!(SinglyLinkedList, SinglyLinkedListOps);
+impl_deserialize!(XorLinkedList, XorLinkedListOps);
+impl_deserialize!(RBTree, RBTreeOps);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Frozen, KeyAdapter, LinkedList, LinkedListLink, RBTree, RBTreeLink, SinglyLinkedList,
+        SinglyLinkedListLink, XorLinkedList, XorLinkedListLink,
+    };
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde_test::{assert_de_tokens, assert_ser_tokens, Token};
+    use std::boxed::Box;
+    use std::fmt;
+    use std::rc::Rc;
+
+    #[derive(Debug)]
+    struct Obj {
+        ll: LinkedListLink,
+        sll: SinglyLinkedListLink,
+        xll: XorLinkedListLink,
+        rb: RBTreeLink,
+        value: u32,
+    }
+    impl Serialize for Obj {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.value.serialize(serializer)
+        }
+    }
+    impl<'de> Deserialize<'de> for Obj {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Obj, D::Error> {
+            u32::deserialize(deserializer).map(make_obj)
+        }
+    }
+    fn make_obj(value: u32) -> Obj {
+        Obj {
+            ll: LinkedListLink::new(),
+            sll: SinglyLinkedListLink::new(),
+            xll: XorLinkedListLink::new(),
+            rb: RBTreeLink::new(),
+            value,
+        }
+    }
+    intrusive_adapter!(LlAdapter = Rc<Obj>: Obj { ll: LinkedListLink });
+    intrusive_adapter!(SllAdapter = Rc<Obj>: Obj { sll: SinglyLinkedListLink });
+    intrusive_adapter!(XllAdapter = Rc<Obj>: Obj { xll: XorLinkedListLink });
+    intrusive_adapter!(RbAdapter = Rc<Obj>: Obj { rb: RBTreeLink });
+    intrusive_adapter!(LlBoxAdapter = Box<Obj>: Obj { ll: LinkedListLink });
+    intrusive_adapter!(SllBoxAdapter = Box<Obj>: Obj { sll: SinglyLinkedListLink });
+    intrusive_adapter!(XllBoxAdapter = Box<Obj>: Obj { xll: XorLinkedListLink });
+    intrusive_adapter!(RbBoxAdapter = Box<Obj>: Obj { rb: RBTreeLink });
+    impl<'a> KeyAdapter<'a> for RbAdapter {
+        type Key = u32;
+        fn get_key(&self, x: &'a Obj) -> u32 {
+            x.value
+        }
+    }
+    impl<'a> KeyAdapter<'a> for RbBoxAdapter {
+        type Key = u32;
+        fn get_key(&self, x: &'a Obj) -> u32 {
+            x.value
+        }
+    }
+
+    // Collection compared by the values of its elements.
+    struct Values<C>(C);
+    impl<'de, C: Deserialize<'de>> Deserialize<'de> for Values<C> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Values<C>, D::Error> {
+            C::deserialize(deserializer).map(Values)
+        }
+    }
+    impl<C> PartialEq for Values<C>
+    where
+        for<'a> &'a C: IntoIterator<Item = &'a Obj>,
+    {
+        fn eq(&self, other: &Values<C>) -> bool {
+            let values = |c| <&C>::into_iter(c).map(|x| x.value);
+            values(&self.0).eq(values(&other.0))
+        }
+    }
+    impl<C> fmt::Debug for Values<C>
+    where
+        for<'a> &'a C: IntoIterator<Item = &'a Obj>,
+    {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_list().entries(&self.0).finish()
+        }
+    }
+
+    #[test]
+    fn test_serialize() {
+        let mut l = LinkedList::new(LlAdapter::new());
+        let mut sl = SinglyLinkedList::new(SllAdapter::new());
+        let mut xl = XorLinkedList::new(XllAdapter::new());
+        let mut t = RBTree::new(RbAdapter::new());
+        assert_ser_tokens(&l, &[Token::Seq { len: None }, Token::SeqEnd]);
+        for value in [3, 1, 2] {
+            let obj = Rc::new(make_obj(value));
+            l.push_front(obj.clone());
+            sl.push_front(obj.clone());
+            xl.push_front(obj.clone());
+            t.insert(obj);
+        }
+
+        let tokens = [
+            Token::Seq { len: None },
+            Token::U32(2),
+            Token::U32(1),
+            Token::U32(3),
+            Token::SeqEnd,
+        ];
+        assert_ser_tokens(&l, &tokens);
+        assert_ser_tokens(&sl, &tokens);
+        assert_ser_tokens(&xl, &tokens);
+        let tokens = [
+            Token::Seq { len: None },
+            Token::U32(1),
+            Token::U32(2),
+            Token::U32(3),
+            Token::SeqEnd,
+        ];
+        assert_ser_tokens(&t, &tokens);
+        assert_ser_tokens(&Frozen::new(&t), &tokens);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let tokens = [
+            Token::Seq { len: Some(3) },
+            Token::U32(3),
+            Token::U32(1),
+            Token::U32(2),
+            Token::SeqEnd,
+        ];
+        let mut l = LinkedList::new(LlBoxAdapter::new());
+        let mut sl = SinglyLinkedList::new(SllBoxAdapter::new());
+        let mut xl = XorLinkedList::new(XllBoxAdapter::new());
+        let mut t = RBTree::new(RbBoxAdapter::new());
+        for value in [2, 1, 3] {
+            l.push_front(Box::new(make_obj(value)));
+            sl.push_front(Box::new(make_obj(value)));
+            xl.push_front(Box::new(make_obj(value)));
+            t.insert(Box::new(make_obj(value)));
+        }
+        assert_de_tokens(&Values(l), &tokens);
+        assert_de_tokens(&Values(sl), &tokens);
+        assert_de_tokens(&Values(xl), &tokens);
+        assert_de_tokens(&Values(t), &tokens);
+        assert_de_tokens(
+            &Values(SinglyLinkedList::new(SllBoxAdapter::new())),
+            &[Token::Seq { len: Some(0) }, Token::SeqEnd],
+        );
+    }
+}
@@ -0,0 +1,470 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! A set of collections with the elements spread among them, each behind its
+//! own lock.
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::ops::Deref;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::adapter::Adapter;
+use crate::collection::{self, Collection};
+use crate::pointer_ops::PointerOps;
+
+// The value and pointer types of a collection.
+type Value<C> = <<<C as Collection>::Adapter as Adapter>::PointerOps as PointerOps>::Value;
+type Pointer<C> = <<<C as Collection>::Adapter as Adapter>::PointerOps as PointerOps>::Pointer;
+
+// =============================================================================
+// Sharded
+// =============================================================================
+
+/// `N` collections of the same type, called shards, with each element put in
+/// the shard chosen by a routing function.
+///
+/// The routing function returns a shard index, which is taken modulo `N`.
+/// It can hash a key of the element, or return the index of the current CPU
+/// or thread. Each shard has its own `Mutex`, so `insert` only takes a shared
+/// reference and threads inserting into different shards don't contend.
+///
+/// `iter` and `drain` merge the shards: they take a comparison function, and
+/// yield the first element of the shards which compares the lowest, the
+/// earliest shard winning ties. If every shard is ordered by the comparison
+/// function, such as trees ordered by key, the elements come out in order.
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::{intrusive_adapter, LinkedList, LinkedListLink, Sharded};
+/// use std::thread;
+///
+/// struct Job {
+///     link: LinkedListLink,
+///     id: usize,
+/// }
+/// intrusive_adapter!(JobAdapter = Box<Job>: Job { link: LinkedListLink });
+///
+/// let mut jobs: Sharded<_, 2> = Sharded::from_fn(|_| LinkedList::new(JobAdapter::new()), |job| job.id);
+/// thread::scope(|s| {
+///     for first in [0, 3] {
+///         let jobs = &jobs;
+///         s.spawn(move || {
+///             for id in first..first + 3 {
+///                 jobs.insert(Box::new(Job { link: LinkedListLink::new(), id }));
+///             }
+///         });
+///     }
+/// });
+/// assert_eq!(jobs.lock(1).iter().map(|job| job.id).collect::<Vec<_>>(), [1, 3, 5]);
+///
+/// let ids = jobs.iter(|a, b| a.id.cmp(&b.id)).map(|job| job.id);
+/// assert_eq!(ids.collect::<Vec<_>>(), [0, 1, 2, 3, 4, 5]);
+///
+/// assert_eq!(jobs.drain(|_, _| core::cmp::Ordering::Equal).count(), 6);
+/// assert!(jobs.is_empty());
+/// ```
+pub struct Sharded<C: Collection, const N: usize> {
+    shards: [Mutex<C>; N],
+    route: fn(&Value<C>) -> usize,
+}
+
+impl<C: Collection, const N: usize> Sharded<C, N> {
+    /// Creates a `Sharded` from its shards and routing function.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is zero.
+    #[inline]
+    pub fn new(shards: [C; N], route: fn(&Value<C>) -> usize) -> Sharded<C, N> {
+        assert!(N != 0, "a Sharded needs at least one shard");
+        Sharded {
+            shards: shards.map(Mutex::new),
+            route,
+        }
+    }
+
+    /// Creates a `Sharded` whose shards are returned by `make`, which is
+    /// called with the index of each shard.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is zero.
+    #[inline]
+    pub fn from_fn(make: impl FnMut(usize) -> C, route: fn(&Value<C>) -> usize) -> Sharded<C, N> {
+        Sharded::new(core::array::from_fn(make), route)
+    }
+
+    /// Returns the index of the shard which `val` is routed to.
+    #[inline]
+    pub fn shard_of(&self, val: &Value<C>) -> usize {
+        (self.route)(val) % N
+    }
+
+    /// Inserts a new element into the shard it is routed to, and returns the
+    /// index of the shard.
+    ///
+    /// Only the lock of that shard is taken.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new element is already linked to a different intrusive
+    /// collection.
+    #[inline]
+    pub fn insert(&self, val: Pointer<C>) -> usize
+    where
+        Pointer<C>: Deref<Target = Value<C>>,
+    {
+        let index = self.shard_of(&val);
+        self.lock(index).insert(val);
+        index
+    }
+
+    /// Locks the shard at `index` and returns it.
+    ///
+    /// Elements inserted directly into the shard don't have to be routed to
+    /// it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` isn't less than `N`.
+    #[inline]
+    pub fn lock(&self, index: usize) -> MutexGuard<'_, C> {
+        // The collections are never left in an inconsistent state by a
+        // panic, so a poisoned lock can still be used.
+        self.shards[index]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the shard at `index`, without locking it since the `Sharded`
+    /// is borrowed mutably.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` isn't less than `N`.
+    #[inline]
+    pub fn shard_mut(&mut self, index: usize) -> &mut C {
+        self.shards[index]
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Consumes the `Sharded` and returns its shards.
+    #[inline]
+    pub fn into_shards(self) -> [C; N] {
+        self.shards
+            .map(|shard| shard.into_inner().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Returns `true` if all the shards are empty.
+    ///
+    /// The shards are locked one at a time, so elements inserted
+    /// concurrently may or may not be seen.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        (0..N).all(|index| self.lock(index).is_empty())
+    }
+
+    /// Removes all the elements of all the shards.
+    ///
+    /// The shards are locked one at a time.
+    #[inline]
+    pub fn clear(&self) {
+        (0..N).for_each(|index| self.lock(index).clear());
+    }
+
+    // Returns all the shards without locking them.
+    #[inline]
+    fn shards_mut(&mut self) -> [&mut C; N] {
+        let mut shards = self.shards.iter_mut();
+        core::array::from_fn(|_| {
+            shards
+                .next()
+                .unwrap()
+                .get_mut()
+                .unwrap_or_else(PoisonError::into_inner)
+        })
+    }
+
+    /// Gets an iterator over the elements of all the shards, merged by
+    /// `compare`.
+    #[inline]
+    pub fn iter<F>(&mut self, compare: F) -> Iter<'_, C, F, N>
+    where
+        F: FnMut(&Value<C>, &Value<C>) -> Ordering,
+    {
+        Iter {
+            iters: self.shards_mut().map(|shard| (*shard).iter()),
+            compare,
+        }
+    }
+
+    /// Removes the elements of all the shards and returns an iterator over
+    /// them, merged by `compare`.
+    ///
+    /// The elements which the iterator doesn't reach are dropped with it.
+    #[inline]
+    pub fn drain<F>(&mut self, compare: F) -> Drain<'_, C, F, N>
+    where
+        F: FnMut(&Value<C>, &Value<C>) -> Ordering,
+    {
+        Drain {
+            shards: self.shards_mut(),
+            compare,
+        }
+    }
+}
+
+impl<C: Collection, const N: usize> fmt::Debug for Sharded<C, N>
+where
+    C: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sharded")
+            .field("shards", &self.shards)
+            .finish_non_exhaustive()
+    }
+}
+
+// Returns the index of the shard whose first element compares the lowest,
+// given the first element of each shard.
+#[inline]
+fn lowest<'a, T: ?Sized + 'a>(
+    firsts: impl Iterator<Item = Option<&'a T>>,
+    compare: &mut impl FnMut(&T, &T) -> Ordering,
+) -> Option<usize> {
+    let mut lowest: Option<(usize, &T)> = None;
+    for (index, first) in firsts.enumerate() {
+        if let Some(first) = first {
+            if lowest.map_or(true, |(_, x)| compare(first, x) == Ordering::Less) {
+                lowest = Some((index, first));
+            }
+        }
+    }
+    lowest.map(|(index, _)| index)
+}
+
+// =============================================================================
+// Iter
+// =============================================================================
+
+/// An iterator over the elements of a `Sharded`, returned by
+/// `Sharded::iter`.
+pub struct Iter<'a, C: Collection, F, const N: usize> {
+    iters: [collection::Iter<'a, C>; N],
+    compare: F,
+}
+
+impl<'a, C: Collection, F, const N: usize> Iterator for Iter<'a, C, F, N>
+where
+    F: FnMut(&Value<C>, &Value<C>) -> Ordering,
+{
+    type Item = &'a Value<C>;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a Value<C>> {
+        let firsts = self.iters.iter().map(|iter| iter.clone().next());
+        let index = lowest(firsts, &mut self.compare)?;
+        self.iters[index].next()
+    }
+}
+
+impl<C: Collection, F, const N: usize> FusedIterator for Iter<'_, C, F, N> where
+    F: FnMut(&Value<C>, &Value<C>) -> Ordering
+{
+}
+
+impl<C: Collection, F: Clone, const N: usize> Clone for Iter<'_, C, F, N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Iter {
+            iters: self.iters.clone(),
+            compare: self.compare.clone(),
+        }
+    }
+}
+
+impl<C: Collection, F, const N: usize> fmt::Debug for Iter<'_, C, F, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Iter").finish_non_exhaustive()
+    }
+}
+
+// =============================================================================
+// Drain
+// =============================================================================
+
+/// An iterator which removes the elements of a `Sharded`, returned by
+/// `Sharded::drain`.
+pub struct Drain<'a, C: Collection, F, const N: usize> {
+    shards: [&'a mut C; N],
+    compare: F,
+}
+
+impl<C: Collection, F, const N: usize> Iterator for Drain<'_, C, F, N>
+where
+    F: FnMut(&Value<C>, &Value<C>) -> Ordering,
+{
+    type Item = Pointer<C>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Pointer<C>> {
+        let firsts = self.shards.iter().map(|shard| shard.iter().next());
+        let index = lowest(firsts, &mut self.compare)?;
+        self.shards[index].remove_first(|_| true)
+    }
+}
+
+impl<C: Collection, F, const N: usize> FusedIterator for Drain<'_, C, F, N> where
+    F: FnMut(&Value<C>, &Value<C>) -> Ordering
+{
+}
+
+impl<C: Collection, F, const N: usize> Drop for Drain<'_, C, F, N> {
+    #[inline]
+    fn drop(&mut self) {
+        self.shards.iter_mut().for_each(|shard| shard.clear());
+    }
+}
+
+impl<C: Collection, F, const N: usize> fmt::Debug for Drain<'_, C, F, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Drain").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Sharded;
+    use crate::{KeyAdapter, LinkedList, LinkedListLink, RBTree, RBTreeLink};
+    use core::cmp::Ordering;
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    struct Obj {
+        ll: LinkedListLink,
+        rb: RBTreeLink,
+        value: usize,
+    }
+    intrusive_adapter!(LlAdapter = Rc<Obj>: Obj { ll: LinkedListLink });
+    intrusive_adapter!(RbAdapter = Rc<Obj>: Obj { rb: RBTreeLink });
+    impl<'a> KeyAdapter<'a> for RbAdapter {
+        type Key = usize;
+        fn get_key(&self, x: &'a Obj) -> usize {
+            x.value
+        }
+    }
+
+    fn make_objs() -> Vec<Rc<Obj>> {
+        [7, 3, 4, 9, 0, 5]
+            .iter()
+            .map(|&value| {
+                Rc::new(Obj {
+                    ll: LinkedListLink::new(),
+                    rb: RBTreeLink::new(),
+                    value,
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_insert_iter() {
+        let objs = make_objs();
+        let mut s: Sharded<_, 3> =
+            Sharded::from_fn(|_| LinkedList::new(LlAdapter::new()), |x| x.value);
+        assert!(s.is_empty());
+        assert_eq!(s.iter(|_, _| Ordering::Equal).count(), 0);
+        for obj in &objs {
+            assert_eq!(s.insert(obj.clone()), obj.value % 3);
+        }
+        assert!(!s.is_empty());
+        assert_eq!(s.shard_of(&objs[0]), 1);
+        let values = |l: &LinkedList<LlAdapter>| l.iter().map(|x| x.value).collect::<Vec<_>>();
+        assert_eq!(values(&s.lock(0)), [3, 9, 0]);
+        assert_eq!(values(&s.lock(1)), [7, 4]);
+        assert_eq!(values(&s.lock(2)), [5]);
+
+        // Ties go to the earliest shard, so equal elements are concatenated.
+        let all = s.iter(|_, _| Ordering::Equal).map(|x| x.value);
+        assert_eq!(all.collect::<Vec<_>>(), [3, 9, 0, 7, 4, 5]);
+        // The shards aren't sorted, so the merge only looks at their fronts.
+        let merged = s.iter(|a, b| a.value.cmp(&b.value)).map(|x| x.value);
+        assert_eq!(merged.collect::<Vec<_>>(), [3, 5, 7, 4, 9, 0]);
+
+        s.shard_mut(2).pop_front();
+        for index in 0..3 {
+            s.lock(index).pop_front();
+        }
+        let all = s.iter(|_, _| Ordering::Equal).map(|x| x.value);
+        assert_eq!(all.collect::<Vec<_>>(), [9, 0, 4]);
+        s.clear();
+        assert!(s.is_empty());
+        assert!(objs.iter().all(|x| Rc::strong_count(x) == 1));
+    }
+
+    #[test]
+    fn test_drain() {
+        let objs = make_objs();
+        let trees = [RBTree::new(RbAdapter::new()), RBTree::new(RbAdapter::new())];
+        let mut s = Sharded::new(trees, |x| x.value % 2);
+        for obj in &objs {
+            s.insert(obj.clone());
+        }
+        let by_value = |a: &Obj, b: &Obj| a.value.cmp(&b.value);
+        let values = s.iter(by_value).map(|x| x.value).collect::<Vec<_>>();
+        assert_eq!(values, [0, 3, 4, 5, 7, 9]);
+        let drained = s.drain(by_value).map(|x| x.value).collect::<Vec<_>>();
+        assert_eq!(drained, [0, 3, 4, 5, 7, 9]);
+        assert!(s.is_empty());
+
+        // Dropping the iterator removes the elements it didn't reach.
+        for obj in &objs {
+            s.insert(obj.clone());
+        }
+        assert_eq!(s.drain(by_value).nth(3).unwrap().value, 5);
+        assert!(s.is_empty());
+        assert!(objs.iter().all(|x| Rc::strong_count(x) == 1));
+        assert_eq!(s.into_shards().len(), 2);
+    }
+
+    #[test]
+    fn test_threads() {
+        use std::boxed::Box;
+        use std::thread;
+
+        struct Job {
+            link: LinkedListLink,
+            value: usize,
+        }
+        intrusive_adapter!(JobAdapter = Box<Job>: Job { link: LinkedListLink });
+
+        let mut s: Sharded<_, 4> =
+            Sharded::from_fn(|_| LinkedList::new(JobAdapter::new()), |x| x.value);
+        thread::scope(|scope| {
+            for thread in 0..4 {
+                let s = &s;
+                scope.spawn(move || {
+                    for i in 0..100 {
+                        s.insert(Box::new(Job {
+                            link: LinkedListLink::new(),
+                            value: i * 4 + thread,
+                        }));
+                    }
+                });
+            }
+        });
+        for index in 0..4 {
+            assert_eq!(s.lock(index).iter().count(), 100);
+        }
+        let values = s.drain(|a, b| a.value.cmp(&b.value)).map(|x| x.value);
+        assert!(values.eq(0..400));
+    }
+}
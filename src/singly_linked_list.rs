@@ -10,19 +10,60 @@
 
 use core::cell::Cell;
 use core::fmt;
-use core::ptr::{null_mut, NonNull};
-use core::sync::atomic::{AtomicPtr, Ordering};
-
-use crate::link_ops::{self, DefaultLinkOps};
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::ControlFlow;
+use core::pin::Pin;
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+use core::ptr::null_mut;
+use core::ptr::NonNull;
+
+#[cfg(feature = "ghost-cell")]
+use ghost_cell::GhostToken;
+
+use crate::adapter::TryPointer;
+#[cfg(feature = "alloc")]
+use crate::alloc::boxed::Box;
+use crate::collection::Collection;
+use crate::cursor_ops::{CursorMutOps, CursorOps};
+#[cfg(feature = "dump")]
+use crate::dump;
+use crate::footprint::MemoryOverhead;
+#[cfg(feature = "ghost-cell")]
+use crate::ghost_adapter::{GhostAdapter, GhostIterMut};
+use crate::insert_error::InsertError;
+use crate::link_ops::{
+    self, check_link_ptr, wrapper_ptr, DefaultLinkOps, DefaultSentinel, LinkSentinel, LinkWrapper,
+    WrapperLinkOps,
+};
+#[cfg(feature = "alloc")]
+use crate::pointer_ops::DefaultPointerOps;
 use crate::pointer_ops::PointerOps;
+#[cfg(feature = "poison")]
+use crate::poison::Poison;
+use crate::priority_adapter::PriorityAdapter;
+#[cfg(feature = "alloc")]
+use crate::retire_list::RetireList;
+#[cfg(feature = "stats")]
+use crate::stats::{Stats, StatsCounters};
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+use crate::sync::{AtomicPtr, Ordering};
 use crate::xor_linked_list::XorLinkedListOps;
-use crate::{Adapter, ExclusivePointerOps, TryExclusivePointerOps};
+use crate::{
+    Adapter, CowPointerOps, ExclusivePinnedPointerOps, ExclusivePointerOps, PinnedPointerOps,
+    TryExclusivePointerOps, TryPointerOps,
+};
 
 // =============================================================================
 // SinglyLinkedListOps
 // =============================================================================
 
 /// Link operations for `SinglyLinkedList`.
+///
+/// # Safety
+///
+/// Implementations must correctly maintain the links of the objects passed
+/// to them, since collections rely on these operations for memory safety.
 pub unsafe trait SinglyLinkedListOps: link_ops::LinkOps {
     /// Returns the "next" link pointer of `ptr`.
     ///
@@ -35,6 +76,57 @@ pub unsafe trait SinglyLinkedListOps: link_ops::LinkOps {
     /// # Safety
     /// An implementation of `set_next` must not panic.
     unsafe fn set_next(&mut self, ptr: Self::LinkPtr, next: Option<Self::LinkPtr>);
+
+    /// Records the identifier of the `SinglyLinkedList` that `ptr` was inserted
+    /// into, for the `debug_owner` feature.
+    ///
+    /// Link types which don't store an owner can leave this unimplemented.
+    ///
+    /// # Safety
+    /// An implementation of `set_owner` must not panic.
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn set_owner(&mut self, ptr: Self::LinkPtr, owner: usize) {
+        let _ = (ptr, owner);
+    }
+
+    /// Returns the identifier recorded by `set_owner`, 0 if the link was
+    /// released since, or `None` if the link type doesn't store an owner.
+    ///
+    /// # Safety
+    /// An implementation of `owner` must not panic.
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn owner(&self, ptr: Self::LinkPtr) -> Option<usize> {
+        let _ = ptr;
+        None
+    }
+}
+
+unsafe impl<W: LinkWrapper, O: SinglyLinkedListOps<LinkPtr = NonNull<W::Link>>> SinglyLinkedListOps
+    for WrapperLinkOps<W, O>
+{
+    #[inline]
+    unsafe fn next(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
+        wrapper_ptr(self.ops.next(ptr.cast()))
+    }
+
+    #[inline]
+    unsafe fn set_next(&mut self, ptr: Self::LinkPtr, next: Option<Self::LinkPtr>) {
+        self.ops.set_next(ptr.cast(), next.map(NonNull::cast));
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn set_owner(&mut self, ptr: Self::LinkPtr, owner: usize) {
+        self.ops.set_owner(ptr.cast(), owner);
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn owner(&self, ptr: Self::LinkPtr) -> Option<usize> {
+        self.ops.owner(ptr.cast())
+    }
 }
 
 // =============================================================================
@@ -43,9 +135,16 @@ pub unsafe trait SinglyLinkedListOps: link_ops::LinkOps {
 
 /// Intrusive link that allows an object to be inserted into a
 /// `SinglyLinkedList`.
-#[repr(align(2))]
+///
+/// The link is `repr(C)` with the layout of `struct ic_singly_linked_list_link`
+/// in the header written by `c_header::write_c_header`: a single `next`
+/// pointer, set to 1 while unlinked. The `debug_owner` feature appends an
+/// `owner` field.
+#[repr(C, align(2))]
 pub struct Link {
     next: Cell<Option<NonNull<Link>>>,
+    #[cfg(feature = "debug_owner")]
+    owner: Cell<usize>,
 }
 
 // Use a special value to indicate an unlinked node
@@ -58,6 +157,8 @@ impl Link {
     pub const fn new() -> Link {
         Link {
             next: Cell::new(UNLINKED_MARKER),
+            #[cfg(feature = "debug_owner")]
+            owner: Cell::new(0),
         }
     }
 
@@ -122,6 +223,19 @@ impl fmt::Debug for Link {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for Link {
+    #[inline]
+    fn format(&self, f: defmt::Formatter<'_>) {
+        let state = if self.is_linked() {
+            "linked"
+        } else {
+            "unlinked"
+        };
+        defmt::write!(f, "{=str}", state)
+    }
+}
+
 // =============================================================================
 // LinkOps
 // =============================================================================
@@ -133,6 +247,8 @@ pub struct LinkOps;
 unsafe impl link_ops::LinkOps for LinkOps {
     type LinkPtr = NonNull<Link>;
 
+    const LINK_SIZE: Option<usize> = Some(mem::size_of::<Link>());
+
     #[inline]
     unsafe fn acquire_link(&mut self, ptr: Self::LinkPtr) -> bool {
         if ptr.as_ref().is_linked() {
@@ -145,20 +261,41 @@ unsafe impl link_ops::LinkOps for LinkOps {
 
     #[inline]
     unsafe fn release_link(&mut self, ptr: Self::LinkPtr) {
+        #[cfg(feature = "debug_owner")]
+        ptr.as_ref().owner.set(0);
         ptr.as_ref().next.set(UNLINKED_MARKER);
     }
 }
 
+unsafe impl link_ops::LinkStateOps for LinkOps {
+    #[inline]
+    unsafe fn is_linked(&self, ptr: Self::LinkPtr) -> bool {
+        ptr.as_ref().is_linked()
+    }
+}
+
 unsafe impl SinglyLinkedListOps for LinkOps {
     #[inline]
     unsafe fn next(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
-        ptr.as_ref().next.get()
+        check_link_ptr(ptr.as_ref().next.get())
     }
 
     #[inline]
     unsafe fn set_next(&mut self, ptr: Self::LinkPtr, next: Option<Self::LinkPtr>) {
         ptr.as_ref().next.set(next);
     }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn set_owner(&mut self, ptr: Self::LinkPtr, owner: usize) {
+        ptr.as_ref().owner.set(owner);
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn owner(&self, ptr: Self::LinkPtr) -> Option<usize> {
+        Some(ptr.as_ref().owner.get())
+    }
 }
 
 unsafe impl XorLinkedListOps for LinkOps {
@@ -168,10 +305,7 @@ unsafe impl XorLinkedListOps for LinkOps {
         ptr: Self::LinkPtr,
         prev: Option<Self::LinkPtr>,
     ) -> Option<Self::LinkPtr> {
-        let packed = ptr
-            .as_ref()
-            .next
-            .get()
+        let packed = check_link_ptr(ptr.as_ref().next.get())
             .map(|x| x.as_ptr() as usize)
             .unwrap_or(0);
         let raw = packed ^ prev.map(|x| x.as_ptr() as usize).unwrap_or(0);
@@ -185,10 +319,7 @@ unsafe impl XorLinkedListOps for LinkOps {
         ptr: Self::LinkPtr,
         next: Option<Self::LinkPtr>,
     ) -> Option<Self::LinkPtr> {
-        let packed = ptr
-            .as_ref()
-            .next
-            .get()
+        let packed = check_link_ptr(ptr.as_ref().next.get())
             .map(|x| x.as_ptr() as usize)
             .unwrap_or(0);
         let raw = packed ^ next.map(|x| x.as_ptr() as usize).unwrap_or(0);
@@ -216,10 +347,7 @@ unsafe impl XorLinkedListOps for LinkOps {
         old: Option<Self::LinkPtr>,
         new: Option<Self::LinkPtr>,
     ) {
-        let packed = ptr
-            .as_ref()
-            .next
-            .get()
+        let packed = check_link_ptr(ptr.as_ref().next.get())
             .map(|x| x.as_ptr() as usize)
             .unwrap_or(0);
         let new_packed = packed
@@ -231,31 +359,252 @@ unsafe impl XorLinkedListOps for LinkOps {
     }
 }
 
+// =============================================================================
+// SentinelLink
+// =============================================================================
+
+/// Intrusive link that allows an object to be inserted into a
+/// `SinglyLinkedList`, which marks itself as unlinked by storing the address
+/// chosen by `S` in its `next` pointer.
+///
+/// This has the same layout as `Link`, with a different unlinked encoding. It
+/// is meant for links shared with C code which already has a convention for
+/// unlinked nodes. With `DefaultSentinel` it behaves exactly like `Link`,
+/// except that `new` isn't a `const fn`.
+#[repr(C, align(2))]
+pub struct SentinelLink<S: LinkSentinel = DefaultSentinel> {
+    next: Cell<Option<NonNull<SentinelLink<S>>>>,
+    marker: PhantomData<fn() -> S>,
+}
+
+impl<S: LinkSentinel> SentinelLink<S> {
+    /// Creates a new `SentinelLink`.
+    #[inline]
+    pub fn new() -> SentinelLink<S> {
+        SentinelLink {
+            next: Cell::new(Self::unlinked_marker()),
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn unlinked_marker() -> Option<NonNull<SentinelLink<S>>> {
+        Some(S::unlinked().cast())
+    }
+
+    /// Checks whether the `SentinelLink` is linked into a `SinglyLinkedList`.
+    #[inline]
+    pub fn is_linked(&self) -> bool {
+        self.next.get() != Self::unlinked_marker()
+    }
+
+    /// Forcibly unlinks an object from a `SinglyLinkedList`.
+    ///
+    /// # Safety
+    ///
+    /// It is undefined behavior to call this function while still linked into a
+    /// `SinglyLinkedList`. The only situation where this function is useful is
+    /// after calling `fast_clear` on a `SinglyLinkedList`, since this clears
+    /// the collection without marking the nodes as unlinked.
+    #[inline]
+    pub unsafe fn force_unlink(&self) {
+        self.next.set(Self::unlinked_marker());
+    }
+}
+
+impl<S: LinkSentinel> DefaultLinkOps for SentinelLink<S> {
+    type Ops = SentinelLinkOps<S>;
+
+    const NEW: Self::Ops = SentinelLinkOps(PhantomData);
+}
+
+// An object containing a link can be sent to another thread if it is unlinked.
+unsafe impl<S: LinkSentinel> Send for SentinelLink<S> {}
+
+// Provide an implementation of Clone which simply initializes the new link as
+// unlinked. This allows structs containing a link to derive Clone.
+impl<S: LinkSentinel> Clone for SentinelLink<S> {
+    #[inline]
+    fn clone(&self) -> SentinelLink<S> {
+        SentinelLink::new()
+    }
+}
+
+// Same as above
+impl<S: LinkSentinel> Default for SentinelLink<S> {
+    #[inline]
+    fn default() -> SentinelLink<S> {
+        SentinelLink::new()
+    }
+}
+
+// Provide an implementation of Debug so that structs containing a link can
+// still derive Debug.
+impl<S: LinkSentinel> fmt::Debug for SentinelLink<S> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // There isn't anything sensible to print here except whether the link
+        // is currently in a list.
+        if self.is_linked() {
+            write!(f, "linked")
+        } else {
+            write!(f, "unlinked")
+        }
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<S: LinkSentinel> defmt::Format for SentinelLink<S> {
+    #[inline]
+    fn format(&self, f: defmt::Formatter<'_>) {
+        let state = if self.is_linked() {
+            "linked"
+        } else {
+            "unlinked"
+        };
+        defmt::write!(f, "{=str}", state)
+    }
+}
+
+// =============================================================================
+// SentinelLinkOps
+// =============================================================================
+
+/// Default `LinkOps` implementation for `SinglyLinkedList` using
+/// `SentinelLink`.
+pub struct SentinelLinkOps<S>(PhantomData<fn() -> S>);
+
+impl<S> Clone for SentinelLinkOps<S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for SentinelLinkOps<S> {}
+
+impl<S> Default for SentinelLinkOps<S> {
+    #[inline]
+    fn default() -> Self {
+        SentinelLinkOps(PhantomData)
+    }
+}
+
+unsafe impl<S: LinkSentinel> link_ops::LinkOps for SentinelLinkOps<S> {
+    type LinkPtr = NonNull<SentinelLink<S>>;
+
+    const LINK_SIZE: Option<usize> = Some(mem::size_of::<SentinelLink<S>>());
+
+    #[inline]
+    unsafe fn acquire_link(&mut self, ptr: Self::LinkPtr) -> bool {
+        if ptr.as_ref().is_linked() {
+            false
+        } else {
+            ptr.as_ref().next.set(None);
+            true
+        }
+    }
+
+    #[inline]
+    unsafe fn release_link(&mut self, ptr: Self::LinkPtr) {
+        ptr.as_ref().force_unlink();
+    }
+}
+
+unsafe impl<S: LinkSentinel> link_ops::LinkStateOps for SentinelLinkOps<S> {
+    #[inline]
+    unsafe fn is_linked(&self, ptr: Self::LinkPtr) -> bool {
+        ptr.as_ref().is_linked()
+    }
+}
+
+unsafe impl<S: LinkSentinel> SinglyLinkedListOps for SentinelLinkOps<S> {
+    #[inline]
+    unsafe fn next(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
+        ptr.as_ref().next.get()
+    }
+
+    #[inline]
+    unsafe fn set_next(&mut self, ptr: Self::LinkPtr, next: Option<Self::LinkPtr>) {
+        ptr.as_ref().next.set(next);
+    }
+}
+
 // =============================================================================
 // AtomicLink
 // =============================================================================
 
 /// Intrusive link that allows an object to be inserted into a
 /// `SinglyLinkedList`. This link allows the structure to be shared between threads.
-#[repr(align(2))]
+///
+/// Whether the link is in a list is tracked atomically, so `is_linked` can be
+/// called from any thread while another thread owns the list.
+///
+/// The link has the same layout as `Link`, described in the header written by
+/// `c_header::write_c_header` as `struct ic_singly_linked_list_atomic_link`.
+/// C code must access its `next` field atomically.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+#[repr(C, align(2))]
 pub struct AtomicLink {
     next: AtomicPtr<AtomicLink>,
+    // Atomic since it is read when checking a link which may be owned by a
+    // list on another thread.
+    #[cfg(feature = "debug_owner")]
+    owner: crate::sync::AtomicUsize,
 }
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 const ATOMIC_UNLINKED_MARKER: *mut AtomicLink = 1 as *mut AtomicLink;
 
+// Marks a link claimed by `try_acquire_for_insertion`. Links are aligned to
+// 2 bytes, so this is never the address of a link.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+pub(crate) const ATOMIC_CLAIMED_MARKER_ADDR: usize = 3;
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+const ATOMIC_CLAIMED_MARKER: *mut AtomicLink = ATOMIC_CLAIMED_MARKER_ADDR as *mut AtomicLink;
+
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 impl AtomicLink {
-    /// Creates a new `AtomicLink`.
-    #[inline]
-    pub const fn new() -> AtomicLink {
-        AtomicLink {
-            next: AtomicPtr::new(ATOMIC_UNLINKED_MARKER),
+    loom_const_fn! {
+        /// Creates a new `AtomicLink`.
+        #[inline]
+        pub fn new() -> AtomicLink {
+            AtomicLink {
+                next: AtomicPtr::new(ATOMIC_UNLINKED_MARKER),
+                #[cfg(feature = "debug_owner")]
+                owner: crate::sync::AtomicUsize::new(0),
+            }
         }
     }
 
     /// Checks whether the `AtomicLink` is linked into a `SinglyLinkedList`.
+    ///
+    /// This can be called from any thread. The load has acquire ordering, so
+    /// if it returns `false`, everything the thread which removed the object
+    /// did before removing it is visible to the caller. An object claimed
+    /// with `try_acquire_for_insertion` counts as linked.
     #[inline]
     pub fn is_linked(&self) -> bool {
-        self.next.load(Ordering::Relaxed) != ATOMIC_UNLINKED_MARKER
+        self.next.load(Ordering::Acquire) != ATOMIC_UNLINKED_MARKER
+    }
+
+    /// Atomically marks an unlinked `AtomicLink` as about to be inserted into a
+    /// `SinglyLinkedList`, returning `false` if it is linked or already claimed.
+    ///
+    /// This lets several threads race to queue the same object: only the one
+    /// which got `true` goes on to insert it, and `is_linked` returns `true`
+    /// from the moment it is claimed. Collections treat a claimed object as
+    /// linked, so the claim must be dropped with `force_unlink` before the
+    /// object is inserted.
+    #[inline]
+    pub fn try_acquire_for_insertion(&self) -> bool {
+        self.next
+            .compare_exchange(
+                ATOMIC_UNLINKED_MARKER,
+                ATOMIC_CLAIMED_MARKER,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            )
+            .is_ok()
     }
 
     /// Forcibly unlinks an object from a `SinglyLinkedList`.
@@ -266,23 +615,67 @@ impl AtomicLink {
     /// `SinglyLinkedList`. The only situation where this function is useful is
     /// after calling `fast_clear` on a `SinglyLinkedList`, since this clears
     /// the collection without marking the nodes as unlinked.
+    ///
+    /// It can also be used to drop a claim taken with
+    /// `try_acquire_for_insertion`, before inserting the object or if it
+    /// won't be inserted.
     #[inline]
     pub unsafe fn force_unlink(&self) {
         self.next.store(ATOMIC_UNLINKED_MARKER, Ordering::Release);
     }
 
-    /// Access the `next` pointer in an exclusive context.
+    /// Marks an unlinked `AtomicLink` as linked, with a null `next`
+    /// pointer.
+    ///
+    /// This is `acquire_link` through a shared reference, for collections
+    /// such as `AppendOnlyList` which insert objects concurrently.
+    #[inline]
+    pub(crate) fn acquire(&self) -> bool {
+        self.next
+            .compare_exchange(
+                ATOMIC_UNLINKED_MARKER,
+                null_mut(),
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            )
+            .is_ok()
+    }
+
+    /// Returns the `next` pointer, for collections which update it
+    /// concurrently.
+    #[inline]
+    pub(crate) fn next_ptr(&self) -> &AtomicPtr<AtomicLink> {
+        &self.next
+    }
+
+    /// Reads the `next` pointer in an exclusive context.
+    ///
+    /// The pointer is still accessed atomically, since other threads may
+    /// call `is_linked` while the list is being modified.
+    ///
+    /// # Safety
+    ///
+    /// This can only be called after `acquire_link` has been succesfully called.
+    #[inline]
+    unsafe fn load_next(&self) -> Option<NonNull<AtomicLink>> {
+        NonNull::new(self.next.load(Ordering::Relaxed))
+    }
+
+    /// Writes the `next` pointer in an exclusive context.
     ///
     /// # Safety
     ///
     /// This can only be called after `acquire_link` has been succesfully called.
     #[inline]
-    unsafe fn next_exclusive(&self) -> &Cell<Option<NonNull<AtomicLink>>> {
-        // This is safe because currently AtomicPtr<AtomicLink> has the same representation Cell<Option<NonNull<AtomicLink>>>.
-        core::mem::transmute(&self.next)
+    unsafe fn store_next(&self, next: Option<NonNull<AtomicLink>>) {
+        self.next.store(
+            next.map(|x| x.as_ptr()).unwrap_or(null_mut()),
+            Ordering::Relaxed,
+        );
     }
 }
 
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 impl DefaultLinkOps for AtomicLink {
     type Ops = AtomicLinkOps;
 
@@ -290,11 +683,14 @@ impl DefaultLinkOps for AtomicLink {
 }
 
 // An object containing a link can be sent to another thread since `acquire_link` is atomic.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 unsafe impl Send for AtomicLink {}
 
 // An object containing a link can be shared between threads since `acquire_link` is atomic.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 unsafe impl Sync for AtomicLink {}
 
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 impl Clone for AtomicLink {
     #[inline]
     fn clone(&self) -> AtomicLink {
@@ -302,6 +698,7 @@ impl Clone for AtomicLink {
     }
 }
 
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 impl Default for AtomicLink {
     #[inline]
     fn default() -> AtomicLink {
@@ -311,6 +708,7 @@ impl Default for AtomicLink {
 
 // Provide an implementation of Debug so that structs containing a link can
 // still derive Debug.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 impl fmt::Debug for AtomicLink {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -324,50 +722,82 @@ impl fmt::Debug for AtomicLink {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for AtomicLink {
+    #[inline]
+    fn format(&self, f: defmt::Formatter<'_>) {
+        let state = if self.is_linked() {
+            "linked"
+        } else {
+            "unlinked"
+        };
+        defmt::write!(f, "{=str}", state)
+    }
+}
+
 // =============================================================================
 // AtomicLinkOps
 // =============================================================================
 
 /// Default `AtomicLinkOps` implementation for `LinkedList`.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 #[derive(Clone, Copy, Default)]
 pub struct AtomicLinkOps;
 
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 unsafe impl link_ops::LinkOps for AtomicLinkOps {
     type LinkPtr = NonNull<AtomicLink>;
 
+    const LINK_SIZE: Option<usize> = Some(mem::size_of::<AtomicLink>());
+
     #[inline]
     unsafe fn acquire_link(&mut self, ptr: Self::LinkPtr) -> bool {
-        ptr.as_ref()
-            .next
-            .compare_exchange(
-                ATOMIC_UNLINKED_MARKER,
-                null_mut(),
-                Ordering::Acquire,
-                Ordering::Relaxed,
-            )
-            .is_ok()
+        ptr.as_ref().acquire()
     }
 
     #[inline]
     unsafe fn release_link(&mut self, ptr: Self::LinkPtr) {
+        #[cfg(feature = "debug_owner")]
+        ptr.as_ref().owner.store(0, Ordering::Relaxed);
         ptr.as_ref()
             .next
             .store(ATOMIC_UNLINKED_MARKER, Ordering::Release)
     }
 }
 
+unsafe impl link_ops::LinkStateOps for AtomicLinkOps {
+    #[inline]
+    unsafe fn is_linked(&self, ptr: Self::LinkPtr) -> bool {
+        ptr.as_ref().is_linked()
+    }
+}
+
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 unsafe impl SinglyLinkedListOps for AtomicLinkOps {
     #[inline]
     unsafe fn next(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
-        ptr.as_ref().next_exclusive().get()
+        ptr.as_ref().load_next()
     }
 
     #[inline]
     unsafe fn set_next(&mut self, ptr: Self::LinkPtr, next: Option<Self::LinkPtr>) {
-        ptr.as_ref().next_exclusive().set(next);
+        ptr.as_ref().store_next(next);
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn set_owner(&mut self, ptr: Self::LinkPtr, owner: usize) {
+        ptr.as_ref().owner.store(owner, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn owner(&self, ptr: Self::LinkPtr) -> Option<usize> {
+        Some(ptr.as_ref().owner.load(Ordering::Relaxed))
     }
 }
 
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 unsafe impl XorLinkedListOps for AtomicLinkOps {
     #[inline]
     unsafe fn next(
@@ -377,8 +807,7 @@ unsafe impl XorLinkedListOps for AtomicLinkOps {
     ) -> Option<Self::LinkPtr> {
         let packed = ptr
             .as_ref()
-            .next_exclusive()
-            .get()
+            .load_next()
             .map(|x| x.as_ptr() as usize)
             .unwrap_or(0);
         let raw = packed ^ prev.map(|x| x.as_ptr() as usize).unwrap_or(0);
@@ -394,8 +823,7 @@ unsafe impl XorLinkedListOps for AtomicLinkOps {
     ) -> Option<Self::LinkPtr> {
         let packed = ptr
             .as_ref()
-            .next_exclusive()
-            .get()
+            .load_next()
             .map(|x| x.as_ptr() as usize)
             .unwrap_or(0);
         let raw = packed ^ next.map(|x| x.as_ptr() as usize).unwrap_or(0);
@@ -413,7 +841,7 @@ unsafe impl XorLinkedListOps for AtomicLinkOps {
             ^ next.map(|x| x.as_ptr() as usize).unwrap_or(0);
 
         let new_next = NonNull::new(new_packed as *mut _);
-        ptr.as_ref().next_exclusive().set(new_next);
+        ptr.as_ref().store_next(new_next);
     }
 
     #[inline]
@@ -425,8 +853,7 @@ unsafe impl XorLinkedListOps for AtomicLinkOps {
     ) {
         let packed = ptr
             .as_ref()
-            .next_exclusive()
-            .get()
+            .load_next()
             .map(|x| x.as_ptr() as usize)
             .unwrap_or(0);
         let new_packed = packed
@@ -434,7 +861,7 @@ unsafe impl XorLinkedListOps for AtomicLinkOps {
             ^ new.map(|x| x.as_ptr() as usize).unwrap_or(0);
 
         let new_next = NonNull::new(new_packed as *mut _);
-        ptr.as_ref().next_exclusive().set(new_next);
+        ptr.as_ref().store_next(new_next);
     }
 }
 
@@ -542,6 +969,19 @@ where
         Some(unsafe { &*self.list.adapter.get_value(self.current?) })
     }
 
+    /// Returns a pinned reference to the object that the cursor is currently
+    /// pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the null
+    /// object.
+    #[inline]
+    pub fn get_pin(&self) -> Option<Pin<&'a <A::PointerOps as PointerOps>::Value>>
+    where
+        A::PointerOps: PinnedPointerOps,
+    {
+        Some(unsafe { Pin::new_unchecked(self.get()?) })
+    }
+
     /// Clones and returns the pointer that points to the element that the
     /// cursor is referencing.
     ///
@@ -566,6 +1006,7 @@ where
     /// null object.
     #[inline]
     pub fn move_next(&mut self) {
+        record_stat!(self.list, cursor_moves);
         if let Some(current) = self.current {
             self.current = unsafe { self.list.adapter.link_ops().next(current) };
         } else {
@@ -615,15 +1056,31 @@ where
         Some(unsafe { &*self.list.adapter.get_value(self.current?) })
     }
 
-    /// Returns a mutable reference to the object that the cursor is currently
-    /// pointing to.
-    ///
-    /// This returns None if the cursor is currently pointing to the null
-    /// object, or if the object cannot be uniquely accessed.
-    ///
-    /// # Safety
+    /// Clones and returns the pointer that points to the element that the
+    /// cursor is referencing.
     ///
-    /// You must not modify any links that are linked
+    /// This returns `None` if the cursor is currently pointing to the null
+    /// object.
+    #[inline]
+    pub fn clone_pointer(&self) -> Option<<A::PointerOps as PointerOps>::Pointer>
+    where
+        <A::PointerOps as PointerOps>::Pointer: Clone,
+    {
+        let raw_pointer = unsafe { self.list.adapter.get_value(self.current?) };
+        Some(unsafe {
+            crate::pointer_ops::clone_pointer_from_raw(self.list.adapter.pointer_ops(), raw_pointer)
+        })
+    }
+
+    /// Returns a mutable reference to the object that the cursor is currently
+    /// pointing to.
+    ///
+    /// This returns None if the cursor is currently pointing to the null
+    /// object, or if the object cannot be uniquely accessed.
+    ///
+    /// # Safety
+    ///
+    /// You must not modify any links that are linked
     #[inline]
     pub unsafe fn try_get_mut(&mut self) -> Option<&mut <A::PointerOps as PointerOps>::Value>
     where
@@ -661,6 +1118,75 @@ where
         )
     }
 
+    /// Returns a mutable reference to the object that the cursor is currently
+    /// pointing to, cloning it first if it is shared.
+    ///
+    /// If the object is not uniquely owned by the collection, it is replaced
+    /// in the `SinglyLinkedList` by a clone and the collection's pointer to
+    /// the original object is dropped. Other pointers to the original object
+    /// keep seeing the old value.
+    ///
+    /// This returns None if the cursor is currently pointing to the null
+    /// object.
+    ///
+    /// Note that replacing a shared object requires the element before it,
+    /// which is found by scanning the `SinglyLinkedList` from its head. This
+    /// has linear time complexity.
+    ///
+    /// # Safety
+    ///
+    /// You must not modify any links that are linked
+    #[inline]
+    pub unsafe fn make_mut(&mut self) -> Option<&mut <A::PointerOps as PointerOps>::Value>
+    where
+        A::PointerOps: CowPointerOps,
+    {
+        let current = self.current?;
+        let value = self.list.adapter.get_value(current);
+        if self.list.adapter.pointer_ops().try_get_mut(value).is_none() {
+            let mut prev = None;
+            let mut x = self.list.head;
+            while let Some(link) = x {
+                if link == current {
+                    break;
+                }
+                prev = x;
+                x = self.list.adapter.link_ops().next(link);
+            }
+            let clone = self.list.adapter.pointer_ops().clone_value(&*value);
+            let mut cursor = CursorMut {
+                current: prev,
+                list: &mut *self.list,
+            };
+            let _ = cursor.replace_next_with(clone);
+            self.current = match prev {
+                Some(prev) => self.list.adapter.link_ops().next(prev),
+                None => self.list.head,
+            };
+        }
+        self.try_get_mut()
+    }
+
+    /// Returns a pinned mutable reference to the object that the cursor is
+    /// currently pointing to.
+    ///
+    /// This returns None if the cursor is currently pointing to the null
+    /// object.
+    ///
+    /// # Safety
+    ///
+    /// You must not modify any links that are linked
+    #[inline]
+    pub unsafe fn get_pin_mut(&mut self) -> Option<Pin<&mut <A::PointerOps as PointerOps>::Value>>
+    where
+        A::PointerOps: ExclusivePinnedPointerOps,
+    {
+        let value = self.list.adapter.get_value(self.current?);
+        Some(Pin::new_unchecked(
+            &mut *self.list.adapter.pointer_ops().get_pin_mut(value),
+        ))
+    }
+
     /// Returns a read-only cursor pointing to the current element.
     ///
     /// The lifetime of the returned `Cursor` is bound to that of the
@@ -674,6 +1200,17 @@ where
         }
     }
 
+    /// Consumes `CursorMut` and returns a read-only cursor pointing to the
+    /// current element. Unlike [as_cursor](Self::as_cursor), the returned
+    /// cursor's lifetime is tied to `SinglyLinkedList`'s lifetime.
+    #[inline]
+    pub fn into_cursor(self) -> Cursor<'a, A> {
+        Cursor {
+            current: self.current,
+            list: self.list,
+        }
+    }
+
     /// Moves the cursor to the next element of the `SinglyLinkedList`.
     ///
     /// If the cursor is pointer to the null object then this will move it to
@@ -682,6 +1219,7 @@ where
     /// null object.
     #[inline]
     pub fn move_next(&mut self) {
+        record_stat!(self.list, cursor_moves);
         if let Some(current) = self.current {
             self.current = unsafe { self.list.adapter.link_ops().next(current) };
         } else {
@@ -710,25 +1248,103 @@ where
     /// `SinglyLinkedList` then no element is removed and `None` is returned.
     #[inline]
     pub fn remove_next(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
-        unsafe {
-            let next = if let Some(current) = self.current {
-                self.list.adapter.link_ops().next(current)
-            } else {
-                self.list.head
-            }?;
+        self.check_next_owner();
+        poisoning!(self.list, unsafe {
+            self.unlink_next().map(|next| {
+                record_stat!(self.list, removals);
+                crate::adapter::unlinked_pointer(&self.list.adapter, next)
+            })
+        })
+    }
+
+    /// Removes the next element from the `SinglyLinkedList`, converting it
+    /// back with `TryPointerOps::try_from_raw`.
+    ///
+    /// This is the same as `remove_next`, except that the conversion back into
+    /// a pointer may fail, in which case the error is returned. The element is
+    /// removed from the `SinglyLinkedList` either way.
+    ///
+    /// If the cursor is currently pointing to the last element of the
+    /// `SinglyLinkedList` then no element is removed and `None` is returned.
+    #[inline]
+    pub fn remove_next_checked(&mut self) -> Option<TryPointer<A>>
+    where
+        A::PointerOps: TryPointerOps,
+    {
+        self.check_next_owner();
+        poisoning!(self.list, unsafe {
+            self.unlink_next().map(|next| {
+                record_stat!(self.list, removals);
+                crate::adapter::try_unlinked_pointer(&self.list.adapter, next)
+            })
+        })
+    }
+
+    // Returns the element after the cursor.
+    #[inline]
+    fn next_node(&self) -> Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr> {
+        if let Some(current) = self.current {
+            unsafe { self.list.adapter.link_ops().next(current) }
+        } else {
+            self.list.head
+        }
+    }
+
+    // Checks that the element after the cursor is in this list, for the
+    // `debug_owner` feature. This is done before modifying the list, so that
+    // the panic doesn't poison it.
+    #[inline]
+    #[track_caller]
+    fn check_next_owner(&self) {
+        if let Some(next) = self.next_node() {
+            unsafe { self.list.check_owner(next) };
+        }
+    }
+
+    // Unlinks the element after the cursor and releases its link, returning
+    // the unlinked element.
+    #[inline]
+    unsafe fn unlink_next(&mut self) -> Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr> {
+        let next = self.next_node()?;
+        if self.is_null() {
+            self.list.head = self.list.adapter.link_ops().next(next);
+        }
+        remove(self.list.adapter.link_ops_mut(), next, self.current);
+        Some(next)
+    }
 
+    /// Removes the next element from the `SinglyLinkedList` and adds it to
+    /// `retired`, to be reclaimed once concurrent readers are done with it.
+    ///
+    /// The link of the element is left untouched, so a reader which is on it
+    /// can still move to its old successor. The cursor is not moved. See
+    /// `RetireList` for details.
+    ///
+    /// Returns `false` if the cursor is currently pointing to the last
+    /// element of the `SinglyLinkedList`, in which case no element is removed.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn retire_next(&mut self, retired: &mut RetireList<A>) -> bool {
+        self.check_next_owner();
+        let next = match self.next_node() {
+            Some(next) => next,
+            None => return false,
+        };
+        poisoning!(self.list, unsafe {
             if self.is_null() {
                 self.list.head = self.list.adapter.link_ops().next(next);
             }
-            remove(self.list.adapter.link_ops_mut(), next, self.current);
-
-            Some(
-                self.list
-                    .adapter
-                    .pointer_ops()
-                    .from_raw(self.list.adapter.get_value(next)),
-            )
-        }
+            if let Some(current) = self.current {
+                let link_ops = self.list.adapter.link_ops_mut();
+                link_ops.set_next(current, link_ops.next(next));
+            }
+            self.list
+                .adapter
+                .on_remove(&*self.list.adapter.get_value(next));
+            record_stat!(self.list, removals);
+            retired.push(next);
+        });
+        true
     }
 
     /// Removes the next element from the `SinglyLinkedList` and inserts
@@ -751,27 +1367,20 @@ where
         val: <A::PointerOps as PointerOps>::Pointer,
     ) -> Result<<A::PointerOps as PointerOps>::Pointer, <A::PointerOps as PointerOps>::Pointer>
     {
-        unsafe {
-            let next = if let Some(current) = self.current {
-                self.list.adapter.link_ops().next(current)
-            } else {
-                self.list.head
-            };
-            match next {
-                Some(next) => {
-                    let new = self.list.node_from_value(val);
+        match self.next_node() {
+            Some(next) => unsafe {
+                self.list.check_owner(next);
+                let new = self.list.node_from_value(val);
+                Ok(poisoning!(self.list, {
                     if self.is_null() {
                         self.list.head = Some(new);
                     }
                     replace_with(self.list.adapter.link_ops_mut(), next, self.current, new);
-                    Ok(self
-                        .list
-                        .adapter
-                        .pointer_ops()
-                        .from_raw(self.list.adapter.get_value(next)))
-                }
-                None => Err(val),
-            }
+                    record_stat!(self.list, removals);
+                    crate::adapter::unlinked_pointer(&self.list.adapter, next)
+                }))
+            },
+            None => Err(val),
         }
     }
 
@@ -788,15 +1397,121 @@ where
     pub fn insert_after(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
         unsafe {
             let new = self.list.node_from_value(val);
-            if let Some(current) = self.current {
-                link_after(self.list.adapter.link_ops_mut(), new, current);
-            } else {
-                link_between(self.list.adapter.link_ops_mut(), new, None, self.list.head);
-                self.list.head = Some(new);
-            }
+            poisoning!(self.list, self.link_node_after(new));
         }
     }
 
+    /// Inserts a new element into the `SinglyLinkedList` after the current
+    /// one, without panicking.
+    ///
+    /// This is the same as `insert_after`, except that an error containing
+    /// `val` is returned if the new element is already linked to an intrusive
+    /// collection.
+    #[inline]
+    pub fn try_insert_after(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<(), InsertError<<A::PointerOps as PointerOps>::Pointer>> {
+        unsafe {
+            self.list
+                .try_node_from_value(val)
+                .map(|new| poisoning!(self.list, self.link_node_after(new)))
+        }
+    }
+
+    #[inline]
+    unsafe fn link_node_after(&mut self, new: <A::LinkOps as link_ops::LinkOps>::LinkPtr) {
+        if let Some(current) = self.current {
+            link_after(self.list.adapter.link_ops_mut(), new, current);
+        } else {
+            link_between(self.list.adapter.link_ops_mut(), new, None, self.list.head);
+            self.list.head = Some(new);
+        }
+    }
+
+    /// Moves the next element into another `SinglyLinkedList`, inserting it
+    /// after the element that `dest` is pointing to.
+    ///
+    /// The element is moved without converting it back into an owned pointer,
+    /// so reference counts are left untouched. The two lists may use different
+    /// links in the object, or the same one. The cursor is not moved, as with
+    /// `remove_next`. This returns `false` if the cursor is currently pointing
+    /// to the last element.
+    ///
+    /// If `dest` is pointing at the null object then the element is inserted
+    /// at the start of its `SinglyLinkedList`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element is already linked to a different intrusive
+    /// collection through the link used by `dest`. The element is left in
+    /// this list in that case.
+    ///
+    /// Also panics if the `PointerOps` of the two lists don't have the same
+    /// state (see `PointerOps::same_state`), in which case neither list is
+    /// modified.
+    #[inline]
+    pub fn transfer_next_after<B>(&mut self, dest: &mut CursorMut<'_, B>) -> bool
+    where
+        B: Adapter<PointerOps = A::PointerOps>,
+        B::LinkOps: SinglyLinkedListOps,
+    {
+        crate::adapter::check_transfer(&self.list.adapter, &dest.list.adapter);
+        self.check_next_owner();
+        let result = poisoning!(
+            self.list,
+            poisoning!(dest.list, unsafe {
+                let result = self.unlink_for_transfer(dest.list);
+                if let Some(Ok(new)) = result {
+                    dest.link_node_after(new);
+                }
+                result
+            })
+        );
+        unsafe { dest.list.transferred(result) }
+    }
+
+    // Unlinks the next element and acquires its link in `dest`. The element
+    // is put back if the link can't be acquired, which happens after
+    // unlinking so that both lists can use the same link, and its link in
+    // `dest` is returned as an error.
+    #[inline]
+    unsafe fn unlink_for_transfer<B>(
+        &mut self,
+        dest: &mut SinglyLinkedList<B>,
+    ) -> crate::adapter::Transfer<B>
+    where
+        B: Adapter<PointerOps = A::PointerOps>,
+        B::LinkOps: SinglyLinkedListOps,
+    {
+        use link_ops::LinkOps;
+
+        let next = self.unlink_next()?;
+        let value = self.list.adapter.get_value(next);
+
+        let new = dest.adapter.get_link(value);
+        if !dest.adapter.link_ops_mut().acquire_link(new) {
+            let acquired = self.list.adapter.link_ops_mut().acquire_link(next);
+            debug_assert!(acquired);
+            self.link_node_after(next);
+            return Some(Err(new));
+        }
+        #[cfg(feature = "debug_owner")]
+        {
+            let owner = dest.owner_id();
+            dest.adapter.link_ops_mut().set_owner(new, owner);
+        }
+        // If a hook panics, the guard drops the element, which is then in
+        // neither list.
+        let guard = crate::adapter::AcquiredLink::new(&mut dest.adapter, new);
+        self.list.adapter.on_remove(&*value);
+        guard.on_insert();
+        guard.disarm();
+        record_stat!(self.list, removals);
+        record_stat!(dest, insertions);
+        Some(Ok(new))
+    }
+
     /// Inserts the elements from the given `SinglyLinkedList` after the current
     /// one.
     ///
@@ -806,43 +1521,53 @@ where
     /// Note that if the cursor is not pointing to the last element of the
     /// `SinglyLinkedList` then the given list must be scanned to find its last
     /// element. This has linear time complexity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `PointerOps` of the two lists don't have the same state
+    /// (see `PointerOps::same_state`).
     #[inline]
     pub fn splice_after(&mut self, mut list: SinglyLinkedList<A>) {
-        if let Some(head) = list.head {
-            unsafe {
-                let next = if let Some(current) = self.current {
-                    self.list.adapter.link_ops().next(current)
-                } else {
-                    self.list.head
-                };
-                if let Some(next) = next {
-                    let mut tail = head;
-                    while let Some(x) = self.list.adapter.link_ops().next(tail) {
-                        tail = x;
-                    }
-                    splice(
-                        self.list.adapter.link_ops_mut(),
-                        head,
-                        tail,
-                        self.current,
-                        Some(next),
-                    );
-                    if self.is_null() {
-                        self.list.head = list.head;
-                    }
-                } else {
-                    if let Some(current) = self.current {
-                        self.list
-                            .adapter
-                            .link_ops_mut()
-                            .set_next(current, list.head);
+        crate::adapter::check_transfer(&self.list.adapter, &list.adapter);
+        poisoning!(self.list, {
+            if let Some(head) = list.head {
+                #[cfg(feature = "debug_owner")]
+                list.set_owner_all(self.list.owner_id());
+                unsafe {
+                    let next = if let Some(current) = self.current {
+                        self.list.adapter.link_ops().next(current)
+                    } else {
+                        self.list.head
+                    };
+                    if let Some(next) = next {
+                        let mut tail = head;
+                        while let Some(x) = self.list.adapter.link_ops().next(tail) {
+                            tail = x;
+                        }
+                        splice(
+                            self.list.adapter.link_ops_mut(),
+                            head,
+                            tail,
+                            self.current,
+                            Some(next),
+                        );
+                        if self.is_null() {
+                            self.list.head = list.head;
+                        }
                     } else {
-                        self.list.head = list.head;
+                        if let Some(current) = self.current {
+                            self.list
+                                .adapter
+                                .link_ops_mut()
+                                .set_next(current, list.head);
+                        } else {
+                            self.list.head = list.head;
+                        }
                     }
+                    list.head = None;
                 }
-                list.head = None;
             }
-        }
+        })
     }
 
     /// Splits the list into two after the current element. This will return a
@@ -856,23 +1581,48 @@ where
     where
         A: Clone,
     {
-        if let Some(current) = self.current {
-            unsafe {
+        poisoning!(self.list, {
+            if let Some(current) = self.current {
+                unsafe {
+                    #[cfg_attr(not(feature = "debug_owner"), allow(unused_mut))]
+                    let mut list = SinglyLinkedList {
+                        head: self.list.adapter.link_ops().next(current),
+                        adapter: self.list.adapter.clone(),
+                        #[cfg(debug_assertions)]
+                        debug_name: self.list.debug_name,
+                        #[cfg(feature = "stats")]
+                        stats: StatsCounters::new(),
+                        #[cfg(feature = "debug_owner")]
+                        owner: 0,
+                        #[cfg(feature = "poison")]
+                        poison: Poison::new(),
+                    };
+                    self.list.adapter.link_ops_mut().set_next(current, None);
+                    #[cfg(feature = "debug_owner")]
+                    list.set_owner_all(link_ops::new_owner_id());
+                    list
+                }
+            } else {
                 let list = SinglyLinkedList {
-                    head: self.list.adapter.link_ops().next(current),
+                    head: self.list.head,
                     adapter: self.list.adapter.clone(),
+                    #[cfg(debug_assertions)]
+                    debug_name: self.list.debug_name,
+                    #[cfg(feature = "stats")]
+                    stats: StatsCounters::new(),
+                    #[cfg(feature = "debug_owner")]
+                    owner: self.list.owner,
+                    #[cfg(feature = "poison")]
+                    poison: Poison::new(),
                 };
-                self.list.adapter.link_ops_mut().set_next(current, None);
+                self.list.head = None;
+                #[cfg(feature = "debug_owner")]
+                {
+                    self.list.owner = 0;
+                }
                 list
             }
-        } else {
-            let list = SinglyLinkedList {
-                head: self.list.head,
-                adapter: self.list.adapter.clone(),
-            };
-            self.list.head = None;
-            list
-        }
+        })
     }
 
     /// Consumes `CursorMut` and returns a reference to the object that
@@ -940,6 +1690,190 @@ where
     A::LinkOps: SinglyLinkedListOps,
 {
 }
+unsafe impl<A: Adapter> Sync for CursorOwning<A>
+where
+    SinglyLinkedList<A>: Sync,
+    A::LinkOps: SinglyLinkedListOps,
+{
+}
+
+// Formats a cursor of `list` pointing to `current`, along with the next
+// element.
+fn debug_cursor<A: Adapter>(
+    f: &mut fmt::Formatter<'_>,
+    name: &str,
+    list: &SinglyLinkedList<A>,
+    current: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+) -> fmt::Result
+where
+    A::LinkOps: SinglyLinkedListOps,
+{
+    let next = cursor_next(list, current);
+    crate::adapter::debug_cursor(f, name, &list.adapter, current, None, next)
+}
+
+// Same as `debug_cursor`, for `defmt`.
+#[cfg(feature = "defmt")]
+fn defmt_cursor<A: Adapter>(
+    f: defmt::Formatter<'_>,
+    name: &str,
+    list: &SinglyLinkedList<A>,
+    current: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+) where
+    A::LinkOps: SinglyLinkedListOps,
+{
+    let next = cursor_next(list, current);
+    crate::adapter::defmt_cursor(f, name, &list.adapter, current, None, next)
+}
+
+// Returns the element of `list` after `current`, as shown when formatting a
+// cursor.
+fn cursor_next<A: Adapter>(
+    list: &SinglyLinkedList<A>,
+    current: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+) -> Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>
+where
+    A::LinkOps: SinglyLinkedListOps,
+{
+    match current {
+        Some(current) => unsafe { list.adapter.link_ops().next(current) },
+        None => list.head,
+    }
+}
+
+impl<'a, A: Adapter> CursorOps for Cursor<'a, A>
+where
+    A::LinkOps: SinglyLinkedListOps,
+{
+    type Adapter = A;
+
+    #[inline]
+    fn is_null(&self) -> bool {
+        Cursor::is_null(self)
+    }
+
+    #[inline]
+    fn get(&self) -> Option<&<A::PointerOps as PointerOps>::Value> {
+        Cursor::get(self)
+    }
+
+    #[inline]
+    fn move_next(&mut self) {
+        Cursor::move_next(self)
+    }
+}
+
+impl<'a, A: Adapter> CursorOps for CursorMut<'a, A>
+where
+    A::LinkOps: SinglyLinkedListOps,
+{
+    type Adapter = A;
+
+    #[inline]
+    fn is_null(&self) -> bool {
+        CursorMut::is_null(self)
+    }
+
+    #[inline]
+    fn get(&self) -> Option<&<A::PointerOps as PointerOps>::Value> {
+        CursorMut::get(self)
+    }
+
+    #[inline]
+    fn move_next(&mut self) {
+        CursorMut::move_next(self)
+    }
+}
+
+impl<'a, A: Adapter> CursorMutOps for CursorMut<'a, A>
+where
+    A::LinkOps: SinglyLinkedListOps,
+{
+    /// Removes the current element from the `SinglyLinkedList`.
+    ///
+    /// The links only go forward, so this walks the list from the front to
+    /// find the previous element, and takes linear time. `remove_next` should
+    /// be used instead when the previous element is known.
+    #[inline]
+    fn remove(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        let current = self.current?;
+        unsafe {
+            let mut prev = None;
+            let mut x = self.list.head;
+            while x != Some(current) {
+                prev = x;
+                x = self.list.adapter.link_ops().next(x?);
+            }
+            let next = self.list.adapter.link_ops().next(current);
+            self.current = prev;
+            let removed = self.remove_next();
+            self.current = next;
+            removed
+        }
+    }
+
+    #[inline]
+    fn insert_after(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        CursorMut::insert_after(self, val)
+    }
+}
+
+impl<A: Adapter> fmt::Debug for Cursor<'_, A>
+where
+    A::LinkOps: SinglyLinkedListOps,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_cursor(f, "Cursor", self.list, self.current)
+    }
+}
+
+impl<A: Adapter> fmt::Debug for CursorMut<'_, A>
+where
+    A::LinkOps: SinglyLinkedListOps,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_cursor(f, "CursorMut", self.list, self.current)
+    }
+}
+
+impl<A: Adapter> fmt::Debug for CursorOwning<A>
+where
+    A::LinkOps: SinglyLinkedListOps,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_cursor(f, "CursorOwning", &self.list, self.current)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<A: Adapter> defmt::Format for Cursor<'_, A>
+where
+    A::LinkOps: SinglyLinkedListOps,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt_cursor(f, "Cursor", self.list, self.current)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<A: Adapter> defmt::Format for CursorMut<'_, A>
+where
+    A::LinkOps: SinglyLinkedListOps,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt_cursor(f, "CursorMut", self.list, self.current)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<A: Adapter> defmt::Format for CursorOwning<A>
+where
+    A::LinkOps: SinglyLinkedListOps,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt_cursor(f, "CursorOwning", &self.list, self.current)
+    }
+}
 
 // =============================================================================
 // SinglyLinkedList
@@ -955,6 +1889,14 @@ where
 {
     head: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
     adapter: A,
+    #[cfg(debug_assertions)]
+    debug_name: Option<&'static str>,
+    #[cfg(feature = "stats")]
+    stats: StatsCounters,
+    #[cfg(feature = "debug_owner")]
+    owner: usize,
+    #[cfg(feature = "poison")]
+    poison: Poison,
 }
 
 impl<A: Adapter> SinglyLinkedList<A>
@@ -966,49 +1908,337 @@ where
         &mut self,
         val: <A::PointerOps as PointerOps>::Pointer,
     ) -> <A::LinkOps as link_ops::LinkOps>::LinkPtr {
+        unsafe {
+            let link = self.acquire_node(val);
+            crate::adapter::acquired_on_insert(&mut self.adapter, link);
+            record_stat!(self, insertions);
+
+            link
+        }
+    }
+
+    // Same as `node_from_value`, but hands `val` back if it is already linked
+    // instead of panicking.
+    #[inline]
+    fn try_node_from_value(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<
+        <A::LinkOps as link_ops::LinkOps>::LinkPtr,
+        InsertError<<A::PointerOps as PointerOps>::Pointer>,
+    > {
         use link_ops::LinkOps;
 
+        check_poison!(self);
         unsafe {
             let raw = self.adapter.pointer_ops().into_raw(val);
             let link = self.adapter.get_link(raw);
 
             if !self.adapter.link_ops_mut().acquire_link(link) {
-                // convert the node back into a pointer
-                self.adapter.pointer_ops().from_raw(raw);
+                let val = self.adapter.pointer_ops().from_raw(raw);
+                return Err(InsertError::AlreadyLinked(val));
+            }
+            #[cfg(feature = "debug_owner")]
+            {
+                let owner = self.owner_id();
+                self.adapter.link_ops_mut().set_owner(link, owner);
+            }
+            crate::adapter::acquired_on_insert(&mut self.adapter, link);
+            record_stat!(self, insertions);
 
-                panic!("attempted to insert an object that is already linked");
+            Ok(link)
+        }
+    }
+
+    // Converts a new element into its link and acquires it, without calling
+    // the `on_insert` hook of the adapter.
+    #[inline]
+    unsafe fn acquire_node(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> <A::LinkOps as link_ops::LinkOps>::LinkPtr {
+        use link_ops::LinkOps;
+
+        check_poison!(self);
+        let raw = self.adapter.pointer_ops().into_raw(val);
+        let link = self.adapter.get_link(raw);
+
+        if !self.adapter.link_ops_mut().acquire_link(link) {
+            // convert the node back into a pointer, after reading the
+            // owner of the link for the panic message
+            let owner = self.owner_of(link);
+            self.adapter.pointer_ops().from_raw(raw);
+
+            crate::adapter::already_linked(&self.adapter, self.debug_name(), raw, owner);
+        }
+        #[cfg(feature = "debug_owner")]
+        {
+            let owner = self.owner_id();
+            self.adapter.link_ops_mut().set_owner(link, owner);
+        }
+
+        link
+    }
+
+    // Returns whether `unlink_for_transfer` moved an element into this list,
+    // and panics if the element was already linked in it. This is done after
+    // both lists are consistent again, so that the panic doesn't poison them.
+    #[inline]
+    #[track_caller]
+    unsafe fn transferred(&self, result: crate::adapter::Transfer<A>) -> bool {
+        match result {
+            Some(Ok(_)) => true,
+            Some(Err(link)) => crate::adapter::already_linked(
+                &self.adapter,
+                self.debug_name(),
+                self.adapter.get_value(link),
+                self.owner_of(link),
+            ),
+            None => false,
+        }
+    }
+
+    // Returns the identifier of this list for the `debug_owner` feature,
+    // assigning one if needed.
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    fn owner_id(&mut self) -> usize {
+        if self.owner == 0 {
+            self.owner = link_ops::new_owner_id();
+        }
+        self.owner
+    }
+
+    // Records `owner` as the identifier of this list and the owner of all its
+    // elements, for the `debug_owner` feature.
+    #[cfg(feature = "debug_owner")]
+    fn set_owner_all(&mut self, owner: usize) {
+        self.owner = owner;
+        let mut current = self.head;
+        while let Some(x) = current {
+            unsafe {
+                self.adapter.link_ops_mut().set_owner(x, owner);
+                current = self.adapter.link_ops().next(x);
             }
+        }
+    }
 
-            link
+    // Checks that `link` is in this list, for the `debug_owner` feature.
+    #[inline]
+    #[track_caller]
+    unsafe fn check_owner(&self, link: <A::LinkOps as link_ops::LinkOps>::LinkPtr) {
+        #[cfg(feature = "debug_owner")]
+        if let Some(owner) = self.adapter.link_ops().owner(link) {
+            if owner == 0 || owner != self.owner {
+                crate::adapter::wrong_owner(&self.adapter, self.debug_name());
+            }
         }
+        #[cfg(not(feature = "debug_owner"))]
+        let _ = link;
     }
 
-    /// Creates an empty `SinglyLinkedList`.
-    #[cfg(not(feature = "nightly"))]
+    // Returns the identifier of the collection `link` is in along with the one
+    // of this list, for the panic message of `already_linked`.
     #[inline]
-    pub fn new(adapter: A) -> SinglyLinkedList<A> {
-        SinglyLinkedList {
-            head: None,
-            adapter,
+    unsafe fn owner_of(
+        &self,
+        link: <A::LinkOps as link_ops::LinkOps>::LinkPtr,
+    ) -> Option<(usize, usize)> {
+        #[cfg(feature = "debug_owner")]
+        {
+            self.adapter
+                .link_ops()
+                .owner(link)
+                .map(|owner| (owner, self.owner))
+        }
+        #[cfg(not(feature = "debug_owner"))]
+        {
+            let _ = link;
+            None
         }
     }
 
     /// Creates an empty `SinglyLinkedList`.
-    #[cfg(feature = "nightly")]
+    ///
+    /// This is a `const fn`, which allows the collection to be used in a
+    /// `static` when combined with the `NEW` constant of an adapter.
     #[inline]
     pub const fn new(adapter: A) -> SinglyLinkedList<A> {
         SinglyLinkedList {
             head: None,
             adapter,
+            #[cfg(debug_assertions)]
+            debug_name: None,
+            #[cfg(feature = "stats")]
+            stats: StatsCounters::new(),
+            #[cfg(feature = "debug_owner")]
+            owner: 0,
+            #[cfg(feature = "poison")]
+            poison: Poison::new(),
         }
     }
 
+    /// Sets a name for this `SinglyLinkedList` which is used in panic messages.
+    ///
+    /// The name is only stored in builds with debug assertions enabled.
+    #[inline]
+    pub fn set_debug_name(&mut self, name: &'static str) {
+        #[cfg(debug_assertions)]
+        {
+            self.debug_name = Some(name);
+        }
+        #[cfg(not(debug_assertions))]
+        let _ = name;
+    }
+
+    /// Returns the name set by `set_debug_name`.
+    ///
+    /// This always returns `None` in builds without debug assertions.
+    #[inline]
+    pub fn debug_name(&self) -> Option<&'static str> {
+        #[cfg(debug_assertions)]
+        return self.debug_name;
+        #[cfg(not(debug_assertions))]
+        None
+    }
+
     /// Returns `true` if the `SinglyLinkedList` is empty.
     #[inline]
     pub fn is_empty(&self) -> bool {
         self.head.is_none()
     }
 
+    /// Returns the memory used by the `SinglyLinkedList` and the links of its elements.
+    ///
+    /// This counts the elements by walking the list, in linear time.
+    #[inline]
+    pub fn memory_overhead(&self) -> MemoryOverhead {
+        MemoryOverhead::new::<A::LinkOps, Self>(self.iter().count())
+    }
+
+    /// Returns statistics about the operations performed on the
+    /// `SinglyLinkedList`.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn stats(&self) -> Stats {
+        self.stats.get()
+    }
+
+    /// Resets the statistics returned by `stats` to zero.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn reset_stats(&mut self) {
+        self.stats = StatsCounters::new();
+    }
+
+    /// Returns `true` if a panic escaped an operation which modified the
+    /// links of the `SinglyLinkedList`.
+    ///
+    /// Operations which modify the links of a poisoned list panic, since
+    /// they may be inconsistent. Reading the list is still allowed.
+    /// Panics raised before the links are modified, such as when inserting
+    /// an element which is already linked, don't poison the list.
+    #[cfg(feature = "poison")]
+    #[inline]
+    pub fn is_poisoned(&self) -> bool {
+        self.poison.get()
+    }
+
+    /// Clears the poisoned state of the `SinglyLinkedList`.
+    ///
+    /// This should only be done once the list is known to be consistent.
+    #[cfg(feature = "poison")]
+    #[inline]
+    pub fn clear_poison(&mut self) {
+        self.poison.clear();
+    }
+
+    /// Writes the chain of elements of the list to `out`, for post-mortem
+    /// analysis of a corrupted list.
+    ///
+    /// Every element is written on its own line, with its index, its address
+    /// and the address of the element its `next` pointer points to. A `next`
+    /// pointer leading back to an element which was already written is
+    /// reported as a cycle and ends the walk, so this always terminates.
+    ///
+    /// The pointers are followed as they are, so the elements they point to
+    /// must still be alive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use intrusive_collections::{intrusive_adapter, SinglyLinkedList, SinglyLinkedListLink};
+    ///
+    /// struct Obj {
+    ///     link: SinglyLinkedListLink,
+    /// }
+    /// intrusive_adapter!(ObjAdapter = Box<Obj>: Obj { link: SinglyLinkedListLink });
+    ///
+    /// let mut list = SinglyLinkedList::new(ObjAdapter::new());
+    /// list.push_front(Box::new(Obj { link: SinglyLinkedListLink::new() }));
+    ///
+    /// let mut dump = String::new();
+    /// list.dump(&mut dump).unwrap();
+    /// assert!(dump.ends_with("1 element, no problems found\n"));
+    /// ```
+    #[cfg(feature = "dump")]
+    pub fn dump<W: fmt::Write>(&self, out: &mut W) -> fmt::Result {
+        self.dump_chain(out, None)
+    }
+
+    /// Same as `dump`, but also writes the `Debug` representation of every
+    /// element.
+    #[cfg(feature = "dump")]
+    pub fn dump_values<W: fmt::Write>(&self, out: &mut W) -> fmt::Result
+    where
+        <A::PointerOps as PointerOps>::Value: fmt::Debug,
+    {
+        self.dump_chain(out, Some(|value, out| write!(out, "{:?}", value)))
+    }
+
+    #[cfg(feature = "dump")]
+    fn dump_chain(
+        &self,
+        out: &mut dyn fmt::Write,
+        write_value: Option<dump::WriteValue<<A::PointerOps as PointerOps>::Value>>,
+    ) -> fmt::Result {
+        let link_ops = self.adapter.link_ops();
+        let value =
+            |link: Option<_>| dump::Addr(link.map(|link| unsafe { self.adapter.get_value(link) }));
+        dump::write_header(
+            out,
+            "SinglyLinkedList",
+            self.adapter.debug_name(),
+            self.debug_name(),
+        )?;
+        writeln!(out, " head {}", value(self.head))?;
+
+        let cycle = dump::find_cycle(self.head, |link| unsafe { link_ops.next(link) });
+        let mut problems = 0;
+        let mut current = self.head;
+        let mut index = 0;
+        while let Some(link) = current {
+            let next = unsafe { link_ops.next(link) };
+            let ptr = unsafe { self.adapter.get_value(link) };
+            dump::write_element(out, index, ptr)?;
+            write!(out, " next {}", value(next))?;
+            unsafe { dump::write_value(out, ptr, write_value)? };
+            index += 1;
+            if let Some((first, len)) = cycle {
+                if index == first + len {
+                    dump::write_problem(
+                        out,
+                        &mut problems,
+                        format_args!("next is #{}, which makes a cycle of length {}", first, len),
+                    )?;
+                    break;
+                }
+            }
+            current = next;
+        }
+        dump::write_summary(out, index, problems)
+    }
+
     /// Returns a null `Cursor` for this list.
     #[inline]
     pub fn cursor(&self) -> Cursor<'_, A> {
@@ -1036,6 +2266,31 @@ where
         }
     }
 
+    // Checks that `link` is in this list by scanning it, for the
+    // `debug-checks` feature.
+    #[inline]
+    unsafe fn check_contains(&self, link: <A::LinkOps as link_ops::LinkOps>::LinkPtr) {
+        #[cfg(feature = "debug-checks")]
+        {
+            let mut current = self.head;
+            while let Some(x) = current {
+                if x == link {
+                    return;
+                }
+                current = self.adapter.link_ops().next(x);
+            }
+            let value = self.adapter.get_value(link);
+            crate::adapter::bad_cursor_ptr(
+                &self.adapter,
+                self.debug_name(),
+                value,
+                "which is not in this collection",
+            );
+        }
+        #[cfg(not(feature = "debug-checks"))]
+        let _ = link;
+    }
+
     /// Creates a `Cursor` from a pointer to an element.
     ///
     /// # Safety
@@ -1046,8 +2301,11 @@ where
         &self,
         ptr: *const <A::PointerOps as PointerOps>::Value,
     ) -> Cursor<'_, A> {
+        let link = self.adapter.get_link(ptr);
+        self.check_contains(link);
+        self.check_owner(link);
         Cursor {
-            current: Some(self.adapter.get_link(ptr)),
+            current: Some(link),
             list: self,
         }
     }
@@ -1062,8 +2320,11 @@ where
         &mut self,
         ptr: *const <A::PointerOps as PointerOps>::Value,
     ) -> CursorMut<'_, A> {
+        let link = self.adapter.get_link(ptr);
+        self.check_contains(link);
+        self.check_owner(link);
         CursorMut {
-            current: Some(self.adapter.get_link(ptr)),
+            current: Some(link),
             list: self,
         }
     }
@@ -1078,8 +2339,11 @@ where
         self,
         ptr: *const <A::PointerOps as PointerOps>::Value,
     ) -> CursorOwning<A> {
+        let link = self.adapter.get_link(ptr);
+        self.check_contains(link);
+        self.check_owner(link);
         CursorOwning {
-            current: Some(self.adapter.get_link(ptr)),
+            current: Some(link),
             list: self,
         }
     }
@@ -1111,6 +2375,40 @@ where
         cursor
     }
 
+    /// Returns a `Cursor` pointing to the element at index `n` of the list,
+    /// counting from 0 at the front. If the list has `n` elements or fewer
+    /// then a null cursor is returned.
+    ///
+    /// This takes time linear in `n`.
+    #[inline]
+    pub fn cursor_at(&self, n: usize) -> Cursor<'_, A> {
+        let mut cursor = self.front();
+        for _ in 0..n {
+            if cursor.is_null() {
+                break;
+            }
+            cursor.move_next();
+        }
+        cursor
+    }
+
+    /// Returns a `CursorMut` pointing to the element at index `n` of the list,
+    /// counting from 0 at the front. If the list has `n` elements or fewer
+    /// then a null cursor is returned.
+    ///
+    /// This takes time linear in `n`.
+    #[inline]
+    pub fn cursor_mut_at(&mut self, n: usize) -> CursorMut<'_, A> {
+        let mut cursor = self.front_mut();
+        for _ in 0..n {
+            if cursor.is_null() {
+                break;
+            }
+            cursor.move_next();
+        }
+        cursor
+    }
+
     /// Gets an iterator over the objects in the `SinglyLinkedList`.
     #[inline]
     pub fn iter(&self) -> Iter<'_, A> {
@@ -1120,6 +2418,46 @@ where
         }
     }
 
+    /// Calls `f` on each object in the `SinglyLinkedList`, until it returns
+    /// `ControlFlow::Break`.
+    ///
+    /// This is faster than `iter` since it doesn't have to keep the state of an
+    /// iterator between calls. The value from `ControlFlow::Break` is returned,
+    /// or `ControlFlow::Continue(())` if `f` never breaks.
+    #[inline]
+    pub fn visit<B, F>(&self, mut f: F) -> ControlFlow<B>
+    where
+        F: FnMut(&<A::PointerOps as PointerOps>::Value) -> ControlFlow<B>,
+    {
+        let mut current = self.head;
+        while let Some(x) = current {
+            unsafe {
+                if let ControlFlow::Break(b) = f(&*self.adapter.get_value(x)) {
+                    return ControlFlow::Break(b);
+                }
+                current = self.adapter.link_ops().next(x);
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    /// Gets an iterator over mutable references to the data of the objects
+    /// in the `SinglyLinkedList`, held in the `GhostCell`s returned by the adapter.
+    ///
+    /// Only the `GhostToken` has to be borrowed mutably, so this can be used
+    /// through a shared reference to the `SinglyLinkedList`.
+    #[cfg(feature = "ghost-cell")]
+    #[inline]
+    pub fn iter_ghost_mut<'a, 'brand>(
+        &'a self,
+        token: &'a mut GhostToken<'brand>,
+    ) -> GhostIterMut<'a, 'brand, A, Iter<'a, A>>
+    where
+        A: GhostAdapter<'brand>,
+    {
+        unsafe { GhostIterMut::new(self.iter(), &self.adapter, token) }
+    }
+
     /// Gets a mutable iterator over the objects in the `SinglyLinkedList`.
     ///
     /// # Safety
@@ -1144,19 +2482,18 @@ where
     #[inline]
     pub fn clear(&mut self) {
         use link_ops::LinkOps;
-
-        let mut current = self.head;
-        self.head = None;
-        while let Some(x) = current {
-            unsafe {
-                let next = self.adapter.link_ops().next(x);
-                self.adapter.link_ops_mut().release_link(x);
-                self.adapter
-                    .pointer_ops()
-                    .from_raw(self.adapter.get_value(x));
-                current = next;
+        poisoning!(self, {
+            // Elements are unlinked from the front one at a time, so that the
+            // remaining ones are still in the list if dropping one panics.
+            while let Some(x) = self.head {
+                unsafe {
+                    self.head = self.adapter.link_ops().next(x);
+                    self.adapter.link_ops_mut().release_link(x);
+                    record_stat!(self, removals);
+                    crate::adapter::unlinked_pointer(&self.adapter, x);
+                }
             }
-        }
+        })
     }
 
     /// Empties the `SinglyLinkedList` without unlinking or freeing objects in it.
@@ -1172,17 +2509,43 @@ where
 
     /// Takes all the elements out of the `SinglyLinkedList`, leaving it empty.
     /// The taken elements are returned as a new `SinglyLinkedList`.
+    ///
+    /// This takes constant time.
     #[inline]
     pub fn take(&mut self) -> SinglyLinkedList<A>
     where
         A: Clone,
     {
-        let list = SinglyLinkedList {
-            head: self.head,
-            adapter: self.adapter.clone(),
-        };
-        self.head = None;
-        list
+        poisoning!(self, {
+            let list = SinglyLinkedList {
+                head: self.head,
+                adapter: self.adapter.clone(),
+                #[cfg(debug_assertions)]
+                debug_name: self.debug_name,
+                #[cfg(feature = "stats")]
+                stats: StatsCounters::new(),
+                #[cfg(feature = "debug_owner")]
+                owner: self.owner,
+                #[cfg(feature = "poison")]
+                poison: Poison::new(),
+            };
+            self.head = None;
+            #[cfg(feature = "debug_owner")]
+            {
+                self.owner = 0;
+            }
+            list
+        })
+    }
+
+    /// Swaps the elements of this `SinglyLinkedList` with those of `other`, along with
+    /// their adapters.
+    ///
+    /// This takes constant time, and is equivalent to `mem::swap`, which can
+    /// also be used since a `SinglyLinkedList` doesn't point into itself.
+    #[inline]
+    pub fn swap(&mut self, other: &mut SinglyLinkedList<A>) {
+        mem::swap(self, other);
     }
 
     /// Inserts a new element at the start of the `SinglyLinkedList`.
@@ -1191,6 +2554,19 @@ where
         self.cursor_mut().insert_after(val);
     }
 
+    /// Inserts a new element at the start of the `SinglyLinkedList`, without
+    /// panicking.
+    ///
+    /// An error containing `val` is returned if the new element is already
+    /// linked to an intrusive collection.
+    #[inline]
+    pub fn try_push_front(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<(), InsertError<<A::PointerOps as PointerOps>::Pointer>> {
+        self.cursor_mut().try_insert_after(val)
+    }
+
     /// Removes the first element of the `SinglyLinkedList`.
     ///
     /// This returns `None` if the `SinglyLinkedList` is empty.
@@ -1198,22 +2574,155 @@ where
     pub fn pop_front(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
         self.cursor_mut().remove_next()
     }
+
+    /// Removes the first element of the `SinglyLinkedList`, converting it
+    /// back with `TryPointerOps::try_from_raw`.
+    ///
+    /// This returns `None` if the `SinglyLinkedList` is empty, and the error
+    /// from `try_from_raw` if the element can't be converted back. The element
+    /// is removed from the `SinglyLinkedList` either way.
+    #[inline]
+    pub fn pop_front_checked(&mut self) -> Option<TryPointer<A>>
+    where
+        A::PointerOps: TryPointerOps,
+    {
+        self.cursor_mut().remove_next_checked()
+    }
+
+    /// Inserts a new element into the `SinglyLinkedList`, keeping the list
+    /// sorted by increasing priority.
+    ///
+    /// The new element is inserted after all elements with a priority less
+    /// than or equal to its own, so elements with equal priorities are kept in
+    /// insertion order. The list must already be sorted for the result to be
+    /// sorted. This has linear time complexity.
+    ///
+    /// Returns a `CursorMut` pointing to the newly added element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new element is already linked to a different intrusive
+    /// collection.
+    #[inline]
+    pub fn insert_sorted(&mut self, val: <A::PointerOps as PointerOps>::Pointer) -> CursorMut<'_, A>
+    where
+        A: PriorityAdapter,
+    {
+        unsafe {
+            let new = self.acquire_node(val);
+
+            // If a priority panics, the guard drops the new element and the
+            // list is left untouched.
+            let guard = crate::adapter::AcquiredLink::new(&mut self.adapter, new);
+            let adapter = guard.adapter();
+            let priority = adapter.get_priority(&*adapter.get_value(new));
+
+            let mut prev = None;
+            let mut next = self.head;
+            while let Some(node) = next {
+                if priority < adapter.get_priority(&*adapter.get_value(node)) {
+                    break;
+                }
+                prev = Some(node);
+                next = adapter.link_ops().next(node);
+            }
+            guard.on_insert();
+            guard.disarm();
+            record_stat!(self, insertions);
+
+            poisoning!(self, {
+                link_between(self.adapter.link_ops_mut(), new, prev, next);
+                if prev.is_none() {
+                    self.head = Some(new);
+                }
+            });
+
+            CursorMut {
+                current: Some(new),
+                list: self,
+            }
+        }
+    }
+}
+
+// Allow read-only access to values from multiple threads. Pointers can be
+// cloned through a shared reference with `clone_pointer`, so they must be
+// `Sync` too.
+unsafe impl<A: Adapter + Sync> Sync for SinglyLinkedList<A>
+where
+    <A::PointerOps as PointerOps>::Value: Sync,
+    <A::PointerOps as PointerOps>::Pointer: Sync,
+    A::LinkOps: SinglyLinkedListOps,
+{
+}
+
+// Allow sending to another thread if the ownership (represented by the <A::PointerOps as PointerOps>::Pointer owned
+// pointer type) can be transferred to another thread.
+unsafe impl<A: Adapter + Send> Send for SinglyLinkedList<A>
+where
+    <A::PointerOps as PointerOps>::Pointer: Send,
+    A::LinkOps: SinglyLinkedListOps,
+{
+}
+
+// Cursors and iterators get the same thread safety as a reference to the
+// list: shared for `Cursor` and `Iter`, exclusive for the others.
+unsafe impl<A: Adapter> Send for Cursor<'_, A>
+where
+    SinglyLinkedList<A>: Sync,
+    A::LinkOps: SinglyLinkedListOps,
+{
+}
+
+unsafe impl<A: Adapter> Sync for Cursor<'_, A>
+where
+    SinglyLinkedList<A>: Sync,
+    A::LinkOps: SinglyLinkedListOps,
+{
+}
+
+unsafe impl<A: Adapter> Send for CursorMut<'_, A>
+where
+    SinglyLinkedList<A>: Send,
+    A::LinkOps: SinglyLinkedListOps,
+{
+}
+
+unsafe impl<A: Adapter> Sync for CursorMut<'_, A>
+where
+    SinglyLinkedList<A>: Sync,
+    A::LinkOps: SinglyLinkedListOps,
+{
+}
+
+unsafe impl<A: Adapter> Send for Iter<'_, A>
+where
+    SinglyLinkedList<A>: Sync,
+    A::LinkOps: SinglyLinkedListOps,
+{
 }
 
-// Allow read-only access to values from multiple threads
-unsafe impl<A: Adapter + Sync> Sync for SinglyLinkedList<A>
+unsafe impl<A: Adapter> Sync for Iter<'_, A>
 where
-    <A::PointerOps as PointerOps>::Value: Sync,
+    SinglyLinkedList<A>: Sync,
     A::LinkOps: SinglyLinkedListOps,
 {
 }
 
-// Allow sending to another thread if the ownership (represented by the <A::PointerOps as PointerOps>::Pointer owned
-// pointer type) can be transferred to another thread.
-unsafe impl<A: Adapter + Send> Send for SinglyLinkedList<A>
+unsafe impl<A: Adapter> Send for IterMut<'_, A>
 where
-    <A::PointerOps as PointerOps>::Pointer: Send,
+    SinglyLinkedList<A>: Send,
+    <A::PointerOps as PointerOps>::Value: Send,
+    A::LinkOps: SinglyLinkedListOps,
+    A::PointerOps: ExclusivePointerOps,
+{
+}
+
+unsafe impl<A: Adapter> Sync for IterMut<'_, A>
+where
+    SinglyLinkedList<A>: Sync,
     A::LinkOps: SinglyLinkedListOps,
+    A::PointerOps: ExclusivePointerOps,
 {
 }
 
@@ -1224,7 +2733,85 @@ where
 {
     #[inline]
     fn drop(&mut self) {
+        // The links of a poisoned list may be inconsistent, so its elements
+        // are leaked instead of being unlinked.
+        #[cfg(feature = "poison")]
+        if self.is_poisoned() {
+            return;
+        }
+        let leaked = crate::adapter::checks_leaks(&self.adapter) && !self.is_empty();
         self.clear();
+        if leaked {
+            crate::adapter::leaked_on_drop(&self.adapter, self.debug_name());
+        }
+    }
+}
+
+impl<A: Adapter> Collection for SinglyLinkedList<A>
+where
+    A::LinkOps: SinglyLinkedListOps,
+{
+    type Adapter = A;
+    type Position = Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>;
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        SinglyLinkedList::is_empty(self)
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        SinglyLinkedList::clear(self);
+    }
+
+    #[inline]
+    fn insert(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        self.push_front(val);
+    }
+
+    #[inline]
+    fn remove_first<F: FnMut(&<A::PointerOps as PointerOps>::Value) -> bool>(
+        &mut self,
+        mut f: F,
+    ) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        let mut cursor = self.cursor_mut();
+        loop {
+            if f(cursor.peek_next().get()?) {
+                return cursor.remove_next();
+            }
+            cursor.move_next();
+        }
+    }
+
+    #[inline]
+    fn take(&mut self) -> SinglyLinkedList<A>
+    where
+        A: Clone,
+    {
+        SinglyLinkedList::take(self)
+    }
+
+    #[inline]
+    fn swap(&mut self, other: &mut SinglyLinkedList<A>) {
+        SinglyLinkedList::swap(self, other);
+    }
+
+    #[inline]
+    fn front_position(&self) -> Self::Position {
+        self.head
+    }
+
+    #[inline]
+    unsafe fn value_at(
+        &self,
+        position: Self::Position,
+    ) -> Option<&<A::PointerOps as PointerOps>::Value> {
+        position.map(|x| &*self.adapter.get_value(x))
+    }
+
+    #[inline]
+    unsafe fn next_position(&self, position: Self::Position) -> Self::Position {
+        self.adapter.link_ops().next(position.unwrap_unchecked())
     }
 }
 
@@ -1263,6 +2850,26 @@ where
     }
 }
 
+// Clones the values into new boxes. The links of the clones are new ones,
+// since cloning a link always returns an unlinked one.
+#[cfg(feature = "alloc")]
+impl<A, V> Clone for SinglyLinkedList<A>
+where
+    A: Adapter<PointerOps = DefaultPointerOps<Box<V>>> + Clone,
+    A::LinkOps: SinglyLinkedListOps,
+    V: Clone,
+{
+    fn clone(&self) -> SinglyLinkedList<A> {
+        let mut list = SinglyLinkedList::new(self.adapter.clone());
+        let mut cursor = list.cursor_mut();
+        for value in self.iter() {
+            cursor.insert_after(Box::new(value.clone()));
+            cursor.move_next();
+        }
+        list
+    }
+}
+
 impl<A: Adapter> fmt::Debug for SinglyLinkedList<A>
 where
     A::LinkOps: SinglyLinkedListOps,
@@ -1273,6 +2880,17 @@ where
     }
 }
 
+#[cfg(feature = "defmt")]
+impl<A: Adapter> defmt::Format for SinglyLinkedList<A>
+where
+    A::LinkOps: SinglyLinkedListOps,
+    <A::PointerOps as PointerOps>::Value: defmt::Format,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        crate::adapter::defmt_list(f, self.iter())
+    }
+}
+
 // =============================================================================
 // Iter
 // =============================================================================
@@ -1517,6 +3135,120 @@ mod tests {
         assert_eq!(cur.get().unwrap() as *const _, c.as_ref() as *const _);
     }
 
+    #[test]
+    fn test_cursor_at() {
+        let mut l = SinglyLinkedList::new(RcObjAdapter1::new());
+        assert!(l.cursor_at(0).is_null());
+        for value in (1..5).rev() {
+            l.push_front(make_rc_obj(value));
+        }
+        assert_eq!(l.cursor_at(0).get().unwrap().value, 1);
+        assert_eq!(l.cursor_at(3).get().unwrap().value, 4);
+        assert!(l.cursor_at(4).is_null());
+        assert!(l.cursor_at(usize::MAX).is_null());
+        assert_eq!(l.cursor_mut_at(2).remove_next().unwrap().value, 4);
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_into_cursor() {
+        let mut l = SinglyLinkedList::new(RcObjAdapter1::new());
+        for value in 1..4 {
+            l.push_front(make_rc_obj(4 - value));
+        }
+        let mut cur = l.front_mut();
+        cur.move_next();
+        assert_eq!(cur.as_cursor().peek_next().get().unwrap().value, 3);
+        let mut cur = cur.into_cursor();
+        let c2 = cur.clone();
+        cur.move_next();
+        assert_eq!(cur.get().unwrap().value, 3);
+        assert_eq!(c2.get().unwrap().value, 2);
+    }
+
+    #[test]
+    fn test_clone() {
+        #[derive(Clone)]
+        struct Obj {
+            link: Link,
+            value: u32,
+        }
+        intrusive_adapter!(ObjAdapter = Box<Obj>: Obj { link: Link });
+
+        let mut l = SinglyLinkedList::new(ObjAdapter::new());
+        for value in 1..4 {
+            l.push_front(Box::new(Obj {
+                link: Link::new(),
+                value,
+            }));
+        }
+        let mut l2 = l.clone();
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [3, 2, 1]);
+        l.clear();
+        assert!(l2.iter().all(|x| x.link.is_linked()));
+        l2.push_front(Box::new(Obj {
+            link: Link::new(),
+            value: 4,
+        }));
+        assert_eq!(l2.iter().count(), 4);
+    }
+
+    #[test]
+    fn test_visit() {
+        use core::ops::ControlFlow;
+
+        let mut l = SinglyLinkedList::new(RcObjAdapter1::new());
+        assert_eq!(
+            l.visit(|_| ControlFlow::Break(())),
+            ControlFlow::Continue(())
+        );
+        for value in 1..5 {
+            l.push_front(make_rc_obj(value));
+        }
+        let mut values = Vec::new();
+        let res = l.visit(|x| {
+            values.push(x.value);
+            ControlFlow::<()>::Continue(())
+        });
+        assert_eq!(res, ControlFlow::Continue(()));
+        assert_eq!(values, [4, 3, 2, 1]);
+
+        values.clear();
+        let res = l.visit(|x| {
+            values.push(x.value);
+            if x.value == 3 {
+                ControlFlow::Break(x.value * 10)
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        assert_eq!(res, ControlFlow::Break(30));
+        assert_eq!(values, [4, 3]);
+    }
+
+    #[test]
+    fn test_try_insert() {
+        use crate::InsertError;
+
+        let a = make_rc_obj(1);
+        let b = make_rc_obj(2);
+
+        let mut l = SinglyLinkedList::new(RcObjAdapter1::new());
+        let mut l2 = SinglyLinkedList::new(RcObjAdapter1::new());
+        assert!(l.try_push_front(a.clone()).is_ok());
+        assert!(matches!(
+            l2.try_push_front(a.clone()),
+            Err(InsertError::AlreadyLinked(_))
+        ));
+        assert!(l2.is_empty());
+        assert!(matches!(
+            l.front_mut().try_insert_after(a.clone()),
+            Err(InsertError::AlreadyLinked(_))
+        ));
+        assert!(l.front_mut().try_insert_after(b.clone()).is_ok());
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 2]);
+    }
+
     #[test]
     fn test_cursor_owning() {
         struct Container {
@@ -1540,6 +3272,304 @@ mod tests {
         assert_eq!(con.cur.as_cursor().get().unwrap().value, 3);
     }
 
+    #[cfg(feature = "dump")]
+    #[test]
+    fn test_dump() {
+        use super::{LinkOps, SinglyLinkedListOps};
+        use core::ptr::NonNull;
+        use std::string::String;
+
+        let dump = |l: &SinglyLinkedList<RcObjAdapter1>| {
+            let mut out = String::new();
+            l.dump_values(&mut out).unwrap();
+            out
+        };
+        let mut l = SinglyLinkedList::new(RcObjAdapter1::new());
+        assert!(dump(&l).ends_with(": head null\n0 elements, no problems found\n"));
+
+        let objs = (0..3).map(make_rc_obj).collect::<Vec<_>>();
+        for obj in objs.iter().rev() {
+            l.push_front(obj.clone());
+        }
+        let a = |i: usize| format!("{:p}", &*objs[i]);
+        let out = dump(&l);
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 5, "{}", out);
+        assert!(lines[0].starts_with("SinglyLinkedList<"), "{}", out);
+        assert!(lines[0].ends_with(&format!("RcObjAdapter1>: head {}", a(0))));
+        assert_eq!(lines[1], format!("  #0 {}: next {}, value 0", a(0), a(1)));
+        assert_eq!(lines[3], format!("  #2 {}: next null, value 2", a(2)));
+        assert_eq!(lines[4], "3 elements, no problems found");
+        let mut plain = String::new();
+        l.dump(&mut plain).unwrap();
+        assert!(plain.contains(&format!("  #1 {}: next {}\n", a(1), a(2))));
+
+        // A cycle back to the second element.
+        let mut ops = LinkOps;
+        let link = |i: usize| NonNull::from(&objs[i].link1);
+        unsafe { ops.set_next(link(2), Some(link(1))) };
+        let out = dump(&l);
+        assert!(out.ends_with(&format!(
+            "  #2 {}: next {}, value 2\n  ! next is #1, which makes a cycle of length 2\n3 elements, 1 problem found\n",
+            a(2),
+            a(1)
+        )));
+
+        unsafe { ops.set_next(link(2), None) };
+        assert!(dump(&l).ends_with("3 elements, no problems found\n"));
+    }
+
+    #[test]
+    fn test_cursor_debug() {
+        let a = make_rc_obj(1);
+        let mut l = SinglyLinkedList::new(RcObjAdapter1::new());
+        l.push_front(a.clone());
+        let a = &*a as *const Obj;
+
+        assert_eq!(
+            format!("{:?}", l.cursor()),
+            format!("Cursor {{ current: None, next: {:?} }}", Some(a))
+        );
+        assert_eq!(
+            format!("{:?}", l.front_mut()),
+            format!("CursorMut {{ current: {:?}, next: None }}", Some(a))
+        );
+    }
+
+    #[test]
+    fn test_transfer() {
+        use std::panic::{self, AssertUnwindSafe};
+
+        let a = make_rc_obj(1);
+        let b = make_rc_obj(2);
+        let c = make_rc_obj(3);
+        let mut l1 = SinglyLinkedList::new(RcObjAdapter1::new());
+        let mut l2 = SinglyLinkedList::new(RcObjAdapter1::new());
+        let mut l3 = SinglyLinkedList::new(RcObjAdapter2::new());
+        l1.push_front(c.clone());
+        l1.push_front(b.clone());
+        l1.push_front(a.clone());
+
+        // Same link
+        let mut cur = l1.front_mut();
+        assert!(cur.transfer_next_after(&mut l2.cursor_mut()));
+        assert_eq!(cur.get().unwrap().value, 1);
+        assert!(cur.transfer_next_after(&mut l2.front_mut()));
+        assert!(!cur.transfer_next_after(&mut l2.cursor_mut()));
+        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1]);
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(Rc::strong_count(&b), 2);
+
+        // Different link
+        assert!(l1.cursor_mut().transfer_next_after(&mut l3.cursor_mut()));
+        assert!(l2.front_mut().transfer_next_after(&mut l3.front_mut()));
+        assert!(l1.is_empty());
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [2]);
+        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3]);
+        assert!(!a.link1.is_linked());
+        assert!(a.link2.is_linked());
+        assert_eq!(Rc::strong_count(&a), 2);
+        assert_eq!(Rc::strong_count(&c), 2);
+
+        // Already linked through the link of the destination
+        l1.push_front(a.clone());
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut l4 = SinglyLinkedList::new(RcObjAdapter2::new());
+            l1.cursor_mut().transfer_next_after(&mut l4.cursor_mut())
+        }));
+        assert!(result.is_err());
+        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1]);
+        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3]);
+        assert_eq!(Rc::strong_count(&a), 3);
+        #[cfg(feature = "poison")]
+        assert!(!l1.is_poisoned());
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn test_stats() {
+        use crate::Stats;
+
+        let mut l = SinglyLinkedList::new(RcObjAdapter1::new());
+        for value in (0..4).rev() {
+            l.push_front(make_rc_obj(value));
+        }
+        let mut c = l.front_mut();
+        assert_eq!(c.remove_next().unwrap().value, 1);
+        c.replace_next_with(make_rc_obj(5)).unwrap();
+        c.move_next();
+        let mut l2 = SinglyLinkedList::new(RcObjAdapter2::new());
+        assert!(l.cursor_mut().transfer_next_after(&mut l2.cursor_mut()));
+        assert_eq!(
+            l.stats(),
+            Stats {
+                insertions: 5,
+                removals: 3,
+                rotations: 0,
+                max_depth: 0,
+                // `front_mut` moves to the first element.
+                cursor_moves: 2,
+            }
+        );
+        assert_eq!(l2.stats().insertions, 1);
+
+        let mut l3 = l.front_mut().split_after();
+        assert_eq!(l3.stats(), Stats::default());
+        l3.clear();
+        assert_eq!(l3.stats().removals, 1);
+        l.reset_stats();
+        assert_eq!(l.stats(), Stats::default());
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[test]
+    fn test_debug_owner() {
+        use std::panic::{self, AssertUnwindSafe};
+
+        let a = make_rc_obj(1);
+        let b = make_rc_obj(2);
+        let c = make_rc_obj(3);
+        let mut l1 = SinglyLinkedList::new(RcObjAdapter1::new());
+        let mut l2 = SinglyLinkedList::new(RcObjAdapter1::new());
+        l1.push_front(b.clone());
+        l1.push_front(a.clone());
+        l2.push_front(c.clone());
+
+        let wrong_list = |l: &mut SinglyLinkedList<RcObjAdapter1>, obj: &Rc<Obj>| {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| unsafe {
+                l.cursor_mut_from_ptr(&**obj).remove_next()
+            }));
+            let payload = result.err().unwrap();
+            let message = match payload.downcast_ref::<std::string::String>() {
+                Some(message) => message.as_str(),
+                None => payload.downcast_ref::<&str>().unwrap(),
+            };
+            assert!(message.contains("not in this collection"), "{}", message);
+        };
+        wrong_list(&mut l2, &a);
+        wrong_list(&mut l1, &c);
+        unsafe {
+            assert_eq!(l1.cursor_from_ptr(&*b).get().unwrap().value, 2);
+        }
+
+        // Elements which are moved to another list belong to that list.
+        let mut l3 = l1.take();
+        wrong_list(&mut l1, &a);
+        l2.front_mut().splice_after(l3.take());
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [3, 1, 2]);
+        wrong_list(&mut l3, &b);
+        let mut l4 = unsafe { l2.cursor_mut_from_ptr(&*c).split_after() };
+        wrong_list(&mut l2, &a);
+        unsafe {
+            assert!(Rc::ptr_eq(
+                &l4.cursor_mut_from_ptr(&*a).remove_next().unwrap(),
+                &b
+            ));
+        }
+
+        // Removed elements don't belong to any list.
+        wrong_list(&mut l4, &b);
+        l2.front_mut().insert_after(b.clone());
+        unsafe {
+            assert!(Rc::ptr_eq(
+                &l2.cursor_mut_from_ptr(&*c).remove_next().unwrap(),
+                &b
+            ));
+        }
+    }
+
+    #[test]
+    fn test_sentinel_link() {
+        use super::SentinelLink;
+        use crate::LinkSentinel;
+        use core::ptr::NonNull;
+
+        static DUMMY: u8 = 0;
+        struct Dummy;
+        unsafe impl LinkSentinel for Dummy {
+            fn unlinked() -> NonNull<u8> {
+                NonNull::from(&DUMMY)
+            }
+        }
+
+        #[derive(Clone, Debug)]
+        struct Obj {
+            link: SentinelLink<Dummy>,
+            value: u32,
+        }
+        intrusive_adapter!(ObjAdapter = Rc<Obj>: Obj { link: SentinelLink<Dummy> });
+        fn next_word(link: &SentinelLink<Dummy>) -> usize {
+            unsafe { *(link as *const _ as *const usize) }
+        }
+
+        let objs: Vec<Rc<Obj>> = (0..3)
+            .map(|value| {
+                Rc::new(Obj {
+                    link: SentinelLink::new(),
+                    value,
+                })
+            })
+            .collect();
+        assert_eq!(next_word(&objs[0].link), &DUMMY as *const u8 as usize);
+        assert!(!objs[0].link.is_linked());
+        let mut l = SinglyLinkedList::new(ObjAdapter::new());
+        for obj in &objs {
+            l.push_front(obj.clone());
+        }
+        assert!(objs[0].link.is_linked());
+        assert_eq!(next_word(&objs[0].link), 0);
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [2, 1, 0]);
+        assert_eq!(format!("{:?}", objs[1].link), "linked");
+        assert!(!Obj::clone(&objs[1]).link.is_linked());
+
+        assert_eq!(l.front_mut().remove_next().unwrap().value, 1);
+        assert_eq!(next_word(&objs[1].link), &DUMMY as *const u8 as usize);
+        assert_eq!(format!("{:?}", objs[1].link), "unlinked");
+        l.fast_clear();
+        unsafe {
+            objs[0].link.force_unlink();
+            objs[2].link.force_unlink();
+        }
+        assert!(objs.iter().all(|obj| !obj.link.is_linked()));
+    }
+
+    #[test]
+    fn test_make_mut() {
+        #[derive(Clone)]
+        struct Obj {
+            link: Link,
+            value: u32,
+        }
+        intrusive_adapter!(ObjAdapter = Rc<Obj>: Obj { link: Link });
+        let new_obj = |value| {
+            Rc::new(Obj {
+                link: Link::new(),
+                value,
+            })
+        };
+
+        let a = new_obj(1);
+        let b = new_obj(2);
+        let mut l = SinglyLinkedList::new(ObjAdapter::new());
+        l.push_front(new_obj(3));
+        l.push_front(b.clone());
+        l.push_front(a.clone());
+        let unique = l.front().get().unwrap() as *const Obj;
+        drop(a);
+        unsafe {
+            let mut cur = l.front_mut();
+            while let Some(obj) = cur.make_mut() {
+                obj.value *= 10;
+                cur.move_next();
+            }
+        }
+        assert!(!b.link.is_linked());
+        assert_eq!(b.value, 2);
+        assert_eq!(Rc::strong_count(&b), 1);
+        assert_eq!(l.front().get().unwrap() as *const Obj, unique);
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [10, 20, 30]);
+    }
+
     #[test]
     fn test_split_splice() {
         let mut l1 = SinglyLinkedList::new(RcObjAdapter1::new());
@@ -1743,6 +3773,201 @@ mod tests {
         }
     }
 
+    #[cfg(loom)]
+    #[test]
+    fn test_loom_try_acquire_for_insertion() {
+        use super::AtomicLink;
+        use loom::sync::atomic::{AtomicUsize, Ordering};
+        use loom::thread;
+        use std::sync::Arc;
+
+        struct Obj {
+            link: AtomicLink,
+            value: u32,
+        }
+        intrusive_adapter!(ObjAdapter = Arc<Obj>: Obj { link: AtomicLink });
+
+        loom::model(|| {
+            let obj = Arc::new(Obj {
+                link: AtomicLink::new(),
+                value: 1,
+            });
+            let claimed = Arc::new(AtomicUsize::new(0));
+            let threads: Vec<_> = (0..2)
+                .map(|_| {
+                    let obj = obj.clone();
+                    let claimed = claimed.clone();
+                    thread::spawn(move || {
+                        if obj.link.try_acquire_for_insertion() {
+                            claimed.fetch_add(1, Ordering::Relaxed);
+                        }
+                        assert!(obj.link.is_linked());
+                    })
+                })
+                .collect();
+            for thread in threads {
+                thread.join().unwrap();
+            }
+            assert_eq!(claimed.load(Ordering::Relaxed), 1);
+            let mut list = SinglyLinkedList::new(ObjAdapter::new());
+            unsafe {
+                obj.link.force_unlink();
+            }
+            list.push_front(obj.clone());
+            assert_eq!(list.iter().map(|x| x.value).sum::<u32>(), 1);
+            list.clear();
+            assert!(!obj.link.is_linked());
+        });
+    }
+
+    #[cfg(loom)]
+    #[test]
+    fn test_loom_is_linked() {
+        use super::AtomicLink;
+        use loom::sync::atomic::{AtomicUsize, Ordering};
+        use loom::thread;
+        use std::sync::Arc;
+
+        struct Obj {
+            link: AtomicLink,
+            data: AtomicUsize,
+        }
+        intrusive_adapter!(ObjAdapter = Arc<Obj>: Obj { link: AtomicLink });
+
+        loom::model(|| {
+            let obj = Arc::new(Obj {
+                link: AtomicLink::new(),
+                data: AtomicUsize::new(0),
+            });
+            let mut list = SinglyLinkedList::new(ObjAdapter::new());
+            list.push_front(obj.clone());
+            let watcher = {
+                let obj = obj.clone();
+                thread::spawn(move || {
+                    // Removing the object releases everything written before.
+                    if !obj.link.is_linked() {
+                        assert_eq!(obj.data.load(Ordering::Relaxed), 1);
+                    }
+                })
+            };
+            obj.data.store(1, Ordering::Relaxed);
+            list.clear();
+            watcher.join().unwrap();
+        });
+    }
+
+    #[test]
+    fn test_try_acquire_for_insertion() {
+        use super::AtomicLink;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::sync::Arc;
+
+        struct Obj {
+            link: AtomicLink,
+            value: u32,
+        }
+        intrusive_adapter!(ObjAdapter = Arc<Obj>: Obj { link: AtomicLink });
+
+        let a = Arc::new(Obj {
+            link: AtomicLink::new(),
+            value: 1,
+        });
+        let b = Arc::new(Obj {
+            link: AtomicLink::new(),
+            value: 2,
+        });
+        assert!(a.link.try_acquire_for_insertion());
+        assert!(a.link.is_linked());
+        assert!(!a.link.try_acquire_for_insertion());
+
+        let mut l = SinglyLinkedList::new(ObjAdapter::new());
+        let result = catch_unwind(AssertUnwindSafe(|| l.push_front(a.clone())));
+        assert!(result.is_err());
+        assert!(a.link.is_linked());
+        assert!(l.is_empty());
+        unsafe {
+            a.link.force_unlink();
+        }
+        l.push_front(a.clone());
+        l.push_front(b.clone());
+        assert!(!b.link.try_acquire_for_insertion());
+        assert_eq!(l.iter().map(|x| x.value).sum::<u32>(), 3);
+        l.clear();
+        assert!(!a.link.is_linked());
+        assert!(a.link.try_acquire_for_insertion());
+        unsafe {
+            a.link.force_unlink();
+        }
+        assert!(!a.link.is_linked());
+    }
+
+    #[test]
+    fn test_atomic_link_threads() {
+        use super::AtomicLink;
+        use crate::link_ops::check_atomic_link_threads;
+        use std::sync::Arc;
+
+        struct Obj {
+            link: AtomicLink,
+            value: u32,
+        }
+        intrusive_adapter!(ObjAdapter = Arc<Obj>: Obj { link: AtomicLink });
+
+        let objs: Vec<Arc<Obj>> = (0..4)
+            .map(|value| {
+                Arc::new(Obj {
+                    link: AtomicLink::new(),
+                    value,
+                })
+            })
+            .collect();
+        check_atomic_link_threads(
+            &objs,
+            |x| x.link.is_linked(),
+            || {
+                let mut l = SinglyLinkedList::new(ObjAdapter::new());
+                for _ in 0..20 {
+                    for obj in &objs {
+                        l.push_front(obj.clone());
+                    }
+                    l.pop_front();
+                    l.clear();
+                }
+                for obj in &objs {
+                    l.push_front(obj.clone());
+                }
+                l
+            },
+            |l| assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [3, 2, 1, 0]),
+        );
+    }
+
+    #[test]
+    fn test_get_mut() {
+        intrusive_adapter!(BoxObjAdapter = Box<Obj>: Obj { link1: Link });
+        let mut l = SinglyLinkedList::new(BoxObjAdapter::new());
+        l.push_front(Box::new(make_obj(1)));
+        unsafe {
+            l.front_mut().get_mut().unwrap().value = 2;
+            let mut cur = l.front_mut();
+            cur.move_next();
+            assert!(cur.get_mut().is_none());
+        }
+        assert_eq!(l.front().get().unwrap().value, 2);
+
+        let a = make_rc_obj(3);
+        let mut l = SinglyLinkedList::new(RcObjAdapter1::new());
+        l.push_front(a.clone());
+        unsafe {
+            assert!(l.front_mut().try_get_mut().is_none());
+        }
+        drop(a);
+        unsafe {
+            l.front_mut().try_get_mut().unwrap().value = 4;
+        }
+        assert_eq!(l.front().get().unwrap().value, 4);
+    }
+
     #[test]
     fn test_non_static() {
         #[derive(Clone)]
@@ -1763,6 +3988,7 @@ mod tests {
         l.cursor_mut().insert_after(&b);
         assert_eq!(*l.front().get().unwrap().value, 5);
         assert_eq!(*l.front().get().unwrap().value, 5);
+        l.clear();
     }
 
     macro_rules! test_clone_pointer {
@@ -1788,6 +4014,10 @@ mod tests {
             assert_eq!(pointer.value, 5);
             assert_eq!(3, $ptr::strong_count(&a));
 
+            let pointer = l.front_mut().clone_pointer().unwrap();
+            assert_eq!(pointer.value, 5);
+            assert_eq!(4, $ptr::strong_count(&a));
+
             l.clear();
             assert!(l.front().clone_pointer().is_none());
         };
@@ -1802,4 +4032,20 @@ mod tests {
     fn test_clone_pointer_arc() {
         test_clone_pointer!(Arc, std::sync::Arc);
     }
+
+    #[test]
+    fn test_insert_sorted() {
+        intrusive_adapter!(PriorityObjAdapter = Rc<Obj>: Obj { link1: Link }
+            priority: u32 = |obj| { obj.value / 10 });
+
+        let mut l = SinglyLinkedList::new(PriorityObjAdapter::new());
+        for &value in &[21, 10, 30, 22, 0, 11, 1] {
+            let cur = l.insert_sorted(make_rc_obj(value));
+            assert_eq!(cur.get().unwrap().value, value);
+        }
+        assert_eq!(
+            l.iter().map(|x| x.value).collect::<Vec<_>>(),
+            [0, 1, 10, 11, 21, 22, 30]
+        );
+    }
 }
@@ -0,0 +1,518 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use core::cell::Cell;
+use core::fmt;
+
+use crate::pointer_ops::{PointerOps, TryPointerOps};
+
+/// Key of an object in a `SlotStorage`: the index of its slot and the
+/// generation of the slot when the key was created.
+///
+/// A slot's generation changes whenever its object is removed, so keys to
+/// removed objects are recognized as stale even after the slot is reused.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct SlotKey {
+    /// Index of the slot.
+    pub index: u32,
+    /// Generation of the slot when the key was created.
+    pub generation: u32,
+}
+
+/// Storage of objects addressed by a `SlotKey`, such as a slot map.
+///
+/// The links of an object stay in its slot while the object is in a
+/// collection, even after it is removed from the storage: only the generation
+/// of the slot changes, so that `SlotPointerOps` can reject the key when the
+/// collection hands it back.
+///
+/// # Safety
+///
+/// - `slot` must return a pointer to a valid object which doesn't move for as
+///   long as the storage is borrowed, or panic if `index` is out of bounds.
+/// - `index_of` must return the index passed to `slot` to get `value`.
+/// - `generation` must return a different value after the object in a slot is
+///   removed.
+/// - An object must not be moved, dropped or overwritten while its links are
+///   in a collection, even if it was removed from the storage.
+pub unsafe trait SlotStorage {
+    /// Type of the objects in the slots.
+    type Value;
+
+    /// Returns a pointer to the object in slot `index`.
+    fn slot(&self, index: u32) -> *const Self::Value;
+
+    /// Returns the index of the slot containing `value`.
+    ///
+    /// # Safety
+    /// `value` must have been returned by `slot`.
+    unsafe fn index_of(&self, value: *const Self::Value) -> u32;
+
+    /// Returns the current generation of slot `index`.
+    fn generation(&self, index: u32) -> u32;
+
+    /// Returns the cell in which the generation of the key of `value` is kept
+    /// while `value` is in a collection.
+    fn key_generation<'a>(&self, value: &'a Self::Value) -> &'a Cell<u32>;
+}
+
+/// The `PointerOps` type for `SlotKey`s, resolved against a `SlotStorage`.
+///
+/// This gives intrusive collections the use-after-free resistance of slot
+/// maps: an object removed from the storage while it is in a collection isn't
+/// freed, since its links are still in use, and its key is rejected by
+/// `try_from_raw` when it leaves the collection. Methods such as
+/// `LinkedList::pop_front_checked` return the stale key as a `StaleKey` error.
+/// The infallible methods, such as `pop_front`, return stale keys unchanged.
+/// Inserting an object with a stale key panics.
+///
+/// Since `intrusive_adapter!` always uses `DefaultPointerOps`, an adapter
+/// using `SlotPointerOps` has to implement `Adapter` manually.
+///
+/// # Examples
+///
+/// ```
+/// use core::cell::Cell;
+/// use core::ptr::NonNull;
+/// use intrusive_collections::linked_list::{Link, LinkOps};
+/// use intrusive_collections::{
+///     container_of, Adapter, LinkedList, SlotKey, SlotPointerOps, SlotStorage, StaleKey,
+/// };
+///
+/// struct Entry {
+///     link: Link,
+///     key_generation: Cell<u32>,
+///     value: u32,
+/// }
+///
+/// // A fixed set of slots, whose generation is bumped when they are freed.
+/// struct Slots {
+///     entries: Vec<Entry>,
+///     generations: Vec<Cell<u32>>,
+/// }
+///
+/// unsafe impl SlotStorage for Slots {
+///     type Value = Entry;
+///     fn slot(&self, index: u32) -> *const Entry {
+///         &self.entries[index as usize]
+///     }
+///     unsafe fn index_of(&self, value: *const Entry) -> u32 {
+///         value.offset_from(self.entries.as_ptr()) as u32
+///     }
+///     fn generation(&self, index: u32) -> u32 {
+///         self.generations[index as usize].get()
+///     }
+///     fn key_generation<'a>(&self, value: &'a Entry) -> &'a Cell<u32> {
+///         &value.key_generation
+///     }
+/// }
+///
+/// struct EntryAdapter<'a> {
+///     link_ops: LinkOps,
+///     pointer_ops: SlotPointerOps<'a, Slots>,
+/// }
+///
+/// unsafe impl<'a> Adapter for EntryAdapter<'a> {
+///     type LinkOps = LinkOps;
+///     type PointerOps = SlotPointerOps<'a, Slots>;
+///
+///     unsafe fn get_value(&self, link: NonNull<Link>) -> *const Entry {
+///         container_of!(link.as_ptr(), Entry, link)
+///     }
+///     unsafe fn get_link(&self, value: *const Entry) -> NonNull<Link> {
+///         NonNull::new_unchecked(core::ptr::addr_of!((*value).link) as *mut Link)
+///     }
+///     fn link_ops(&self) -> &LinkOps {
+///         &self.link_ops
+///     }
+///     fn link_ops_mut(&mut self) -> &mut LinkOps {
+///         &mut self.link_ops
+///     }
+///     fn pointer_ops(&self) -> &SlotPointerOps<'a, Slots> {
+///         &self.pointer_ops
+///     }
+/// }
+///
+/// let slots = Slots {
+///     entries: (0..2)
+///         .map(|value| Entry { link: Link::new(), key_generation: Cell::new(0), value })
+///         .collect(),
+///     generations: vec![Cell::new(0), Cell::new(0)],
+/// };
+/// let mut list = LinkedList::new(EntryAdapter {
+///     link_ops: LinkOps,
+///     pointer_ops: SlotPointerOps::new(&slots),
+/// });
+/// list.push_back(SlotKey { index: 0, generation: 0 });
+/// list.push_back(SlotKey { index: 1, generation: 0 });
+///
+/// // Free the first slot while it is still in the list.
+/// slots.generations[0].set(1);
+/// let stale = SlotKey { index: 0, generation: 0 };
+/// assert_eq!(list.pop_front_checked().unwrap().err(), Some(StaleKey(stale)));
+/// assert_eq!(list.pop_front_checked().unwrap().ok(), Some(SlotKey { index: 1, generation: 0 }));
+/// ```
+pub struct SlotPointerOps<'a, S> {
+    storage: &'a S,
+}
+
+impl<'a, S: SlotStorage> SlotPointerOps<'a, S> {
+    /// Constructs a `SlotPointerOps` resolving keys against `storage`.
+    #[inline]
+    pub const fn new(storage: &'a S) -> SlotPointerOps<'a, S> {
+        SlotPointerOps { storage }
+    }
+
+    /// Returns the storage which keys are resolved against.
+    #[inline]
+    pub fn storage(&self) -> &'a S {
+        self.storage
+    }
+}
+
+impl<S> Clone for SlotPointerOps<'_, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for SlotPointerOps<'_, S> {}
+
+impl<S> fmt::Debug for SlotPointerOps<'_, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SlotPointerOps")
+            .field("storage", &(self.storage as *const S))
+            .finish()
+    }
+}
+
+unsafe impl<S: SlotStorage> PointerOps for SlotPointerOps<'_, S> {
+    type Value = S::Value;
+    type Pointer = SlotKey;
+    const OWNS_VALUE: bool = false;
+
+    #[inline]
+    unsafe fn from_raw(&self, value: *const S::Value) -> SlotKey {
+        SlotKey {
+            index: self.storage.index_of(value),
+            generation: self.storage.key_generation(&*value).get(),
+        }
+    }
+
+    #[inline]
+    fn into_raw(&self, key: SlotKey) -> *const S::Value {
+        assert!(
+            key.generation == self.storage.generation(key.index),
+            "attempted to insert an object with a stale key"
+        );
+        self.storage.slot(key.index)
+    }
+
+    #[inline]
+    unsafe fn on_link_acquired(&self, value: *const S::Value) {
+        // The key's generation is kept in the object, since the generation of
+        // the slot may change before the object leaves the collection. It is
+        // only written once the link is acquired, so that a failed insertion
+        // doesn't change it for an object which is already in a collection.
+        // `into_raw` checked that the key's generation is the current one.
+        let generation = self.storage.generation(self.storage.index_of(value));
+        self.storage.key_generation(&*value).set(generation);
+    }
+
+    #[inline]
+    fn same_state(&self, other: &Self) -> bool {
+        core::ptr::eq(self.storage, other.storage)
+    }
+}
+
+/// The error returned by `SlotPointerOps::try_from_raw` for the key of an
+/// object which was removed from its `SlotStorage` while it was in a
+/// collection.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct StaleKey(pub SlotKey);
+
+impl fmt::Display for StaleKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "stale key for slot {} (generation {})",
+            self.0.index, self.0.generation
+        )
+    }
+}
+
+unsafe impl<S: SlotStorage> TryPointerOps for SlotPointerOps<'_, S> {
+    type Error = StaleKey;
+
+    #[inline]
+    unsafe fn try_from_raw(&self, value: *const S::Value) -> Result<SlotKey, StaleKey> {
+        let key = self.from_raw(value);
+        if key.generation == self.storage.generation(key.index) {
+            Ok(key)
+        } else {
+            Err(StaleKey(key))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SlotKey, SlotPointerOps, SlotStorage, StaleKey};
+    use crate::linked_list::{Link, LinkOps, LinkedList};
+    use crate::{rbtree, singly_linked_list, xor_linked_list};
+    use crate::{Adapter, RBTree, SinglyLinkedList, XorLinkedList};
+    use core::cell::Cell;
+    use core::ptr::NonNull;
+    use std::vec::Vec;
+
+    struct Entry {
+        link: Link,
+        slink: singly_linked_list::Link,
+        xlink: xor_linked_list::Link,
+        tlink: rbtree::Link,
+        key_generation: Cell<u32>,
+        value: Cell<u32>,
+    }
+
+    // A slot map without a free list: inserting always reuses the first
+    // vacant slot.
+    struct Slots {
+        entries: Vec<Entry>,
+        generations: Vec<Cell<u32>>,
+        occupied: Vec<Cell<bool>>,
+    }
+
+    impl Slots {
+        fn new(len: usize) -> Slots {
+            Slots {
+                entries: (0..len)
+                    .map(|_| Entry {
+                        link: Link::new(),
+                        slink: singly_linked_list::Link::new(),
+                        xlink: xor_linked_list::Link::new(),
+                        tlink: rbtree::Link::new(),
+                        key_generation: Cell::new(0),
+                        value: Cell::new(0),
+                    })
+                    .collect(),
+                generations: (0..len).map(|_| Cell::new(0)).collect(),
+                occupied: (0..len).map(|_| Cell::new(false)).collect(),
+            }
+        }
+
+        // Only reuses slots whose links are free, as `SlotStorage` requires.
+        fn insert(&self, value: u32) -> SlotKey {
+            let index = (0..self.entries.len())
+                .find(|&i| !self.occupied[i].get() && !self.entries[i].is_linked())
+                .unwrap();
+            self.occupied[index].set(true);
+            self.entries[index].value.set(value);
+            SlotKey {
+                index: index as u32,
+                generation: self.generations[index].get(),
+            }
+        }
+
+        fn remove(&self, key: SlotKey) {
+            let index = key.index as usize;
+            assert_eq!(self.generations[index].get(), key.generation);
+            self.occupied[index].set(false);
+            self.generations[index].set(key.generation + 1);
+        }
+    }
+
+    impl Entry {
+        fn is_linked(&self) -> bool {
+            self.link.is_linked()
+                || self.slink.is_linked()
+                || self.xlink.is_linked()
+                || self.tlink.is_linked()
+        }
+    }
+
+    unsafe impl SlotStorage for Slots {
+        type Value = Entry;
+        fn slot(&self, index: u32) -> *const Entry {
+            &self.entries[index as usize]
+        }
+        unsafe fn index_of(&self, value: *const Entry) -> u32 {
+            value.offset_from(self.entries.as_ptr()) as u32
+        }
+        fn generation(&self, index: u32) -> u32 {
+            self.generations[index as usize].get()
+        }
+        fn key_generation<'a>(&self, value: &'a Entry) -> &'a Cell<u32> {
+            &value.key_generation
+        }
+    }
+
+    macro_rules! entry_adapter {
+        ($name:ident = $field:ident: $link:ty, $link_ops:ty) => {
+            struct $name<'a> {
+                link_ops: $link_ops,
+                pointer_ops: SlotPointerOps<'a, Slots>,
+            }
+
+            impl<'a> $name<'a> {
+                fn new(slots: &'a Slots) -> $name<'a> {
+                    $name {
+                        link_ops: Default::default(),
+                        pointer_ops: SlotPointerOps::new(slots),
+                    }
+                }
+            }
+
+            unsafe impl<'a> Adapter for $name<'a> {
+                type LinkOps = $link_ops;
+                type PointerOps = SlotPointerOps<'a, Slots>;
+                unsafe fn get_value(&self, link: NonNull<$link>) -> *const Entry {
+                    container_of!(link.as_ptr(), Entry, $field)
+                }
+                unsafe fn get_link(&self, value: *const Entry) -> NonNull<$link> {
+                    NonNull::new_unchecked(core::ptr::addr_of!((*value).$field) as *mut $link)
+                }
+                fn link_ops(&self) -> &$link_ops {
+                    &self.link_ops
+                }
+                fn link_ops_mut(&mut self) -> &mut $link_ops {
+                    &mut self.link_ops
+                }
+                fn pointer_ops(&self) -> &SlotPointerOps<'a, Slots> {
+                    &self.pointer_ops
+                }
+            }
+        };
+    }
+
+    entry_adapter!(EntryAdapter = link: Link, LinkOps);
+    entry_adapter!(SinglyEntryAdapter = slink: singly_linked_list::Link, singly_linked_list::LinkOps);
+    entry_adapter!(XorEntryAdapter = xlink: xor_linked_list::Link, xor_linked_list::LinkOps);
+    entry_adapter!(TreeEntryAdapter = tlink: rbtree::Link, rbtree::LinkOps);
+
+    #[test]
+    fn test_stale_keys() {
+        let slots = Slots::new(3);
+        let mut list = LinkedList::new(EntryAdapter::new(&slots));
+        let keys: Vec<_> = (0..3).map(|value| slots.insert(value)).collect();
+        for &key in &keys {
+            list.push_back(key);
+        }
+
+        // The slot of 1 can't be reused while its link is in the list.
+        slots.remove(keys[1]);
+        assert!(slots.entries[1].link.is_linked());
+        assert!(list.pop_front_checked().unwrap().is_ok());
+        assert_eq!(
+            list.pop_front_checked().unwrap().err(),
+            Some(StaleKey(keys[1]))
+        );
+        let reused = slots.insert(3);
+        assert_eq!(
+            reused,
+            SlotKey {
+                index: 1,
+                generation: 1
+            }
+        );
+        list.push_front(reused);
+
+        slots.remove(keys[2]);
+        assert_eq!(
+            list.pop_back_checked().unwrap().err(),
+            Some(StaleKey(keys[2]))
+        );
+        let key = list.pop_back_checked().unwrap().ok().unwrap();
+        assert_eq!(key, reused);
+        assert_eq!(slots.entries[key.index as usize].value.get(), 3);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_stale_keys_other_collections() {
+        let slots = Slots::new(4);
+        let keys: Vec<_> = (0..4).map(|value| slots.insert(value)).collect();
+        let mut slist = SinglyLinkedList::new(SinglyEntryAdapter::new(&slots));
+        let mut xlist = XorLinkedList::new(XorEntryAdapter::new(&slots));
+        let mut tree = RBTree::new(TreeEntryAdapter::new(&slots));
+        for &key in &keys {
+            slist.push_front(key);
+            xlist.push_back(key);
+            tree.back_mut().insert_after(key);
+        }
+        slots.remove(keys[0]);
+        slots.remove(keys[3]);
+
+        assert_eq!(
+            slist.pop_front_checked().unwrap().err(),
+            Some(StaleKey(keys[3]))
+        );
+        assert_eq!(
+            slist.front_mut().remove_next_checked().unwrap().ok(),
+            Some(keys[1])
+        );
+        assert_eq!(slist.pop_front_checked().unwrap().ok(), Some(keys[2]));
+        assert_eq!(
+            slist.pop_front_checked().unwrap().err(),
+            Some(StaleKey(keys[0]))
+        );
+        assert!(slist.pop_front_checked().is_none());
+
+        assert_eq!(
+            xlist.pop_back_checked().unwrap().err(),
+            Some(StaleKey(keys[3]))
+        );
+        assert_eq!(
+            xlist.pop_front_checked().unwrap().err(),
+            Some(StaleKey(keys[0]))
+        );
+        assert_eq!(
+            xlist.back_mut().remove_checked().unwrap().ok(),
+            Some(keys[2])
+        );
+        assert_eq!(xlist.pop_front_checked().unwrap().ok(), Some(keys[1]));
+        assert!(xlist.pop_back_checked().is_none());
+
+        let mut cur = tree.front_mut();
+        assert_eq!(cur.remove_checked().unwrap().err(), Some(StaleKey(keys[0])));
+        assert_eq!(cur.remove_checked().unwrap().ok(), Some(keys[1]));
+        cur.move_next();
+        assert_eq!(cur.remove_checked().unwrap().err(), Some(StaleKey(keys[3])));
+        assert!(cur.remove_checked().is_none());
+        assert_eq!(
+            tree.front_mut().remove_checked().unwrap().ok(),
+            Some(keys[2])
+        );
+        assert!(tree.is_empty());
+        assert!(slots.entries.iter().all(|entry| !entry.is_linked()));
+    }
+
+    #[test]
+    fn test_insert_stale_key() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let slots = Slots::new(2);
+        let mut list = LinkedList::new(EntryAdapter::new(&slots));
+        let key = slots.insert(0);
+        list.push_back(key);
+
+        // Neither a stale key nor a second insertion of a linked object
+        // changes the generation kept in the object.
+        slots.remove(key);
+        let result = catch_unwind(AssertUnwindSafe(|| list.push_back(key)));
+        assert!(result.is_err());
+        let current = SlotKey {
+            index: key.index,
+            generation: key.generation + 1,
+        };
+        let result = catch_unwind(AssertUnwindSafe(|| list.push_back(current)));
+        assert!(result.is_err());
+        assert_eq!(slots.entries[0].key_generation.get(), key.generation);
+        assert_eq!(list.pop_front_checked().unwrap().err(), Some(StaleKey(key)));
+        assert!(list.is_empty());
+    }
+}
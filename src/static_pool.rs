@@ -0,0 +1,364 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::{self, MaybeUninit};
+use core::ops::{Deref, DerefMut};
+use core::ptr::{self, NonNull};
+#[cfg(target_has_atomic = "8")]
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// A pool of objects with a static lifetime, from which `PoolBox` pointers
+/// are allocated.
+///
+/// This is implemented by a type naming the pool rather than by the pool
+/// itself, so that `PoolBox` doesn't have to store a reference to its pool.
+/// The `static_pool!` macro declares such a type along with a static
+/// `ArrayPool`, but the trait can also be implemented for other allocators,
+/// such as the memory pool of an RTOS.
+///
+/// # Safety
+///
+/// `alloc` must return a pointer to a slot initialized with `value`, which
+/// stays valid and isn't returned again by `alloc` until it is passed to
+/// `free`.
+pub unsafe trait StaticPool {
+    /// Type of the objects in the pool.
+    type Value;
+
+    /// Moves `value` into a free slot of the pool and returns a pointer to it.
+    ///
+    /// `value` is returned if the pool is exhausted.
+    fn alloc(value: Self::Value) -> Result<NonNull<Self::Value>, Self::Value>;
+
+    /// Returns a slot to the pool.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by `alloc`, and its value must already
+    /// have been dropped or moved out.
+    unsafe fn free(ptr: NonNull<Self::Value>);
+}
+
+/// An owning pointer to an object allocated from a `StaticPool`, which
+/// returns the object to the pool when it is dropped.
+///
+/// This is the equivalent of `Box` for systems without a heap, and can be
+/// used as the pointer type of a collection in the same way.
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::{intrusive_adapter, static_pool};
+/// use intrusive_collections::{LinkedList, LinkedListLink, PoolBox};
+///
+/// struct Msg {
+///     link: LinkedListLink,
+///     id: u32,
+/// }
+///
+/// static_pool!(MsgPool: Msg; 2);
+/// intrusive_adapter!(MsgAdapter = PoolBox<MsgPool>: Msg { link: LinkedListLink });
+///
+/// let new_msg = |id| Msg { link: LinkedListLink::new(), id };
+/// let mut queue = LinkedList::new(MsgAdapter::new());
+/// for id in 0..2 {
+///     queue.push_back(PoolBox::new(new_msg(id)).ok().unwrap());
+/// }
+/// assert!(PoolBox::<MsgPool>::new(new_msg(2)).is_err());
+///
+/// // Dropping a message returns it to the pool.
+/// drop(queue.pop_front());
+/// assert!(PoolBox::<MsgPool>::new(new_msg(2)).is_ok());
+/// ```
+pub struct PoolBox<P: StaticPool> {
+    ptr: NonNull<P::Value>,
+    _pool: PhantomData<P::Value>,
+}
+
+unsafe impl<P: StaticPool> Send for PoolBox<P> where P::Value: Send {}
+unsafe impl<P: StaticPool> Sync for PoolBox<P> where P::Value: Sync {}
+
+impl<P: StaticPool> PoolBox<P> {
+    /// Allocates `value` from the pool `P`.
+    ///
+    /// `value` is returned if the pool is exhausted.
+    #[inline]
+    pub fn new(value: P::Value) -> Result<PoolBox<P>, P::Value> {
+        P::alloc(value).map(|ptr| PoolBox {
+            ptr,
+            _pool: PhantomData,
+        })
+    }
+
+    /// Consumes the `PoolBox` and returns a raw pointer to its object, which
+    /// stays allocated.
+    #[inline]
+    pub fn into_raw(this: PoolBox<P>) -> *mut P::Value {
+        let ptr = this.ptr.as_ptr();
+        mem::forget(this);
+        ptr
+    }
+
+    /// Converts a raw pointer back into a `PoolBox`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by `PoolBox::<P>::into_raw`, and must not
+    /// be converted back more than once.
+    #[inline]
+    pub unsafe fn from_raw(ptr: *mut P::Value) -> PoolBox<P> {
+        PoolBox {
+            ptr: NonNull::new_unchecked(ptr),
+            _pool: PhantomData,
+        }
+    }
+}
+
+impl<P: StaticPool> Deref for PoolBox<P> {
+    type Target = P::Value;
+
+    #[inline]
+    fn deref(&self) -> &P::Value {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<P: StaticPool> DerefMut for PoolBox<P> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut P::Value {
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl<P: StaticPool> Drop for PoolBox<P> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(self.ptr.as_ptr());
+            P::free(self.ptr);
+        }
+    }
+}
+
+impl<P: StaticPool> fmt::Debug for PoolBox<P>
+where
+    P::Value: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// A fixed-size pool of `N` objects of type `T`, which can be placed in a
+/// `static`.
+///
+/// Allocating and freeing a slot is lock-free, and takes time linear in `N`
+/// in the worst case. The objects still in the pool are leaked when it is
+/// dropped.
+#[cfg(target_has_atomic = "8")]
+pub struct ArrayPool<T, const N: usize> {
+    slots: [UnsafeCell<MaybeUninit<T>>; N],
+    used: [AtomicBool; N],
+}
+
+#[cfg(target_has_atomic = "8")]
+unsafe impl<T: Send, const N: usize> Sync for ArrayPool<T, N> {}
+
+#[cfg(target_has_atomic = "8")]
+impl<T, const N: usize> ArrayPool<T, N> {
+    const SLOT: UnsafeCell<MaybeUninit<T>> = UnsafeCell::new(MaybeUninit::uninit());
+    #[allow(clippy::declare_interior_mutable_const)]
+    const UNUSED: AtomicBool = AtomicBool::new(false);
+
+    /// Creates a pool with all of its slots free.
+    #[inline]
+    pub const fn new() -> ArrayPool<T, N> {
+        ArrayPool {
+            slots: [Self::SLOT; N],
+            used: [Self::UNUSED; N],
+        }
+    }
+
+    /// Moves `value` into a free slot of the pool and returns a pointer to it.
+    ///
+    /// `value` is returned if the pool is exhausted.
+    pub fn alloc(&self, value: T) -> Result<NonNull<T>, T> {
+        for (slot, used) in self.slots.iter().zip(&self.used) {
+            if used
+                .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+            {
+                unsafe {
+                    let ptr = slot.get().cast::<T>();
+                    ptr.write(value);
+                    return Ok(NonNull::new_unchecked(ptr));
+                }
+            }
+        }
+        Err(value)
+    }
+
+    /// Returns a slot to the pool.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by `alloc` on this pool, and its value
+    /// must already have been dropped or moved out.
+    pub unsafe fn free(&self, ptr: NonNull<T>) {
+        if mem::size_of::<T>() == 0 {
+            // All the slots have the same address, so any used one is freed.
+            self.used.iter().any(|used| {
+                used.compare_exchange(true, false, Ordering::Release, Ordering::Relaxed)
+                    .is_ok()
+            });
+        } else {
+            let offset = ptr.as_ptr() as usize - self.slots.as_ptr() as usize;
+            self.used[offset / mem::size_of::<T>()].store(false, Ordering::Release);
+        }
+    }
+
+    /// Returns the number of free slots in the pool.
+    pub fn available(&self) -> usize {
+        self.used
+            .iter()
+            .filter(|used| !used.load(Ordering::Relaxed))
+            .count()
+    }
+}
+
+#[cfg(target_has_atomic = "8")]
+impl<T, const N: usize> Default for ArrayPool<T, N> {
+    #[inline]
+    fn default() -> ArrayPool<T, N> {
+        ArrayPool::new()
+    }
+}
+
+/// Macro to declare a `StaticPool` backed by a static `ArrayPool`.
+///
+/// `static_pool!(Name: Type; N)` declares a unit struct `Name` implementing
+/// `StaticPool` for a pool of `N` objects of type `Type`, which must be
+/// `Send`. Its `pool` function returns the `ArrayPool`, for example to get
+/// the number of free slots.
+///
+/// See `PoolBox` for an example.
+#[cfg(target_has_atomic = "8")]
+#[macro_export]
+macro_rules! static_pool {
+    ($(#[$attr:meta])* $vis:vis $name:ident: $ty:ty; $len:expr) => {
+        $(#[$attr])*
+        $vis struct $name;
+
+        #[allow(dead_code)]
+        impl $name {
+            /// Returns the pool from which objects are allocated.
+            #[inline]
+            $vis fn pool() -> &'static $crate::ArrayPool<$ty, { $len }> {
+                static POOL: $crate::ArrayPool<$ty, { $len }> = $crate::ArrayPool::new();
+                &POOL
+            }
+        }
+
+        unsafe impl $crate::StaticPool for $name {
+            type Value = $ty;
+
+            #[inline]
+            fn alloc(value: $ty) -> core::result::Result<core::ptr::NonNull<$ty>, $ty> {
+                Self::pool().alloc(value)
+            }
+
+            #[inline]
+            unsafe fn free(ptr: core::ptr::NonNull<$ty>) {
+                Self::pool().free(ptr)
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PoolBox;
+    use crate::{KeyAdapter, LinkedList, LinkedListLink, RBTree, RBTreeLink};
+    use core::cell::Cell;
+    use std::vec::Vec;
+
+    struct Obj {
+        link: LinkedListLink,
+        value: u32,
+    }
+
+    static_pool!(ObjPool: Obj; 4);
+    intrusive_adapter!(ObjAdapter = PoolBox<ObjPool>: Obj { link: LinkedListLink });
+
+    fn make_obj(value: u32) -> Result<PoolBox<ObjPool>, Obj> {
+        PoolBox::new(Obj {
+            link: LinkedListLink::new(),
+            value,
+        })
+    }
+
+    #[test]
+    fn test_linked_list() {
+        let mut l = LinkedList::new(ObjAdapter::new());
+        for value in 0..4 {
+            l.push_back(make_obj(value).ok().unwrap());
+        }
+        assert_eq!(ObjPool::pool().available(), 0);
+        assert_eq!(make_obj(4).err().unwrap().value, 4);
+
+        let obj = l.pop_front().unwrap();
+        assert_eq!(obj.value, 0);
+        assert_eq!(ObjPool::pool().available(), 0);
+        drop(obj);
+        assert_eq!(ObjPool::pool().available(), 1);
+
+        l.push_front(make_obj(4).ok().unwrap());
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [4, 1, 2, 3]);
+        l.clear();
+        assert_eq!(ObjPool::pool().available(), 4);
+    }
+
+    std::thread_local! {
+        static DROPPED: Cell<u32> = const { Cell::new(0) };
+    }
+
+    struct Dropped {
+        link: RBTreeLink,
+    }
+    impl Drop for Dropped {
+        fn drop(&mut self) {
+            DROPPED.with(|dropped| dropped.set(dropped.get() + 1));
+        }
+    }
+
+    static_pool!(DroppedPool: Dropped; 2);
+    intrusive_adapter!(DroppedAdapter = PoolBox<DroppedPool>: Dropped { link: RBTreeLink });
+    impl<'a> KeyAdapter<'a> for DroppedAdapter {
+        type Key = usize;
+        fn get_key(&self, value: &'a Dropped) -> usize {
+            value as *const Dropped as usize
+        }
+    }
+
+    #[test]
+    fn test_drop() {
+        let mut t = RBTree::new(DroppedAdapter::new());
+        for _ in 0..2 {
+            let obj = PoolBox::new(Dropped {
+                link: RBTreeLink::new(),
+            });
+            t.insert(obj.ok().unwrap());
+        }
+        assert_eq!(DroppedPool::pool().available(), 0);
+        drop(t);
+        assert_eq!(DROPPED.with(Cell::get), 2);
+        assert_eq!(DroppedPool::pool().available(), 2);
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+#[cfg(feature = "stats")]
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+// Adds `$n` (1 by default) to a counter in the statistics of `$collection`,
+// if the `stats` feature is enabled.
+macro_rules! record_stat {
+    ($collection:expr, $counter:ident) => {
+        record_stat!($collection, $counter, 1)
+    };
+    ($collection:expr, $counter:ident, $n:expr) => {
+        #[cfg(feature = "stats")]
+        $collection.stats.$counter.add($n);
+    };
+}
+
+/// Statistics about the operations performed on a collection, returned by
+/// the `stats` method of `LinkedList`, `SinglyLinkedList`, `XorLinkedList`
+/// and `RBTree`.
+///
+/// The counters start at zero when the collection is created, including by
+/// `take` or when splitting another collection, and can be reset with
+/// `reset_stats`.
+#[cfg(feature = "stats")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Stats {
+    /// Number of elements inserted into the collection.
+    ///
+    /// Elements added by splicing another collection aren't counted.
+    pub insertions: usize,
+
+    /// Number of elements removed from the collection, including by `clear`
+    /// and by its owning iterator.
+    ///
+    /// Elements removed by `fast_clear` or by splitting the collection aren't
+    /// counted.
+    pub removals: usize,
+
+    /// Number of rotations performed to rebalance an `RBTree` after
+    /// insertions and removals. This is always zero for lists.
+    pub rotations: usize,
+
+    /// Greatest depth of an element of an `RBTree` just after its
+    /// insertion, the root being at depth 1. This is always zero for lists.
+    pub max_depth: usize,
+
+    /// Number of steps taken by the cursors of the collection, which is the
+    /// total length of the traversals done with cursors.
+    ///
+    /// Every call to `move_next` or `move_prev` is a step, including those
+    /// made internally by `front`, `back`, `peek_next` and `peek_prev`.
+    ///
+    /// The cursors of a collection shared between threads update this
+    /// counter without synchronizing with each other, so some steps may not
+    /// be counted if they move at the same time.
+    pub cursor_moves: usize,
+}
+
+// Counter which can be incremented through a shared reference, since cursors
+// only borrow their collection. Increments are a separate load and store,
+// which are available on every target but can lose concurrent updates.
+#[cfg(feature = "stats")]
+pub(crate) struct Counter(AtomicUsize);
+
+#[cfg(feature = "stats")]
+impl Counter {
+    #[inline]
+    const fn new() -> Counter {
+        Counter(AtomicUsize::new(0))
+    }
+
+    #[inline]
+    fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub(crate) fn add(&self, n: usize) {
+        self.0.store(self.get().wrapping_add(n), Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub(crate) fn max(&self, n: usize) {
+        if n > self.get() {
+            self.0.store(n, Ordering::Relaxed);
+        }
+    }
+}
+
+// Counters backing `Stats`, stored in collections.
+#[cfg(feature = "stats")]
+pub(crate) struct StatsCounters {
+    pub(crate) insertions: Counter,
+    pub(crate) removals: Counter,
+    pub(crate) rotations: Counter,
+    pub(crate) max_depth: Counter,
+    pub(crate) cursor_moves: Counter,
+}
+
+#[cfg(feature = "stats")]
+impl StatsCounters {
+    #[inline]
+    pub(crate) const fn new() -> StatsCounters {
+        StatsCounters {
+            insertions: Counter::new(),
+            removals: Counter::new(),
+            rotations: Counter::new(),
+            max_depth: Counter::new(),
+            cursor_moves: Counter::new(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn get(&self) -> Stats {
+        Stats {
+            insertions: self.insertions.get(),
+            removals: self.removals.get(),
+            rotations: self.rotations.get(),
+            max_depth: self.max_depth.get(),
+            cursor_moves: self.cursor_moves.get(),
+        }
+    }
+}
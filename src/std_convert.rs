@@ -0,0 +1,221 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+// Conversions between the collections of `Box` and the `std` containers,
+// which move the values in and out of their boxes. These are meant for the
+// boundaries of a program, such as loading its state at startup or checking
+// a collection in a test, where the allocations don't matter.
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use crate::key_adapter::KeyAdapter;
+use crate::linked_list::{LinkedList, LinkedListOps};
+use crate::pointer_ops::DefaultPointerOps;
+use crate::rbtree::{RBTree, RBTreeOps};
+use crate::singly_linked_list::{SinglyLinkedList, SinglyLinkedListOps};
+use crate::xor_linked_list::{XorLinkedList, XorLinkedListOps};
+use crate::Adapter;
+
+macro_rules! impl_list_convert {
+    ($collection:ident, $ops:ident) => {
+        impl<A, V> $collection<A>
+        where
+            A: Adapter<PointerOps = DefaultPointerOps<Box<V>>>,
+            A::LinkOps: $ops,
+        {
+            /// Creates a list holding `values` in order, each moved into a new
+            /// `Box`.
+            pub fn from_values(adapter: A, values: impl IntoIterator<Item = V>) -> Self {
+                let mut list = $collection::new(adapter);
+                list.extend_values(values);
+                list
+            }
+
+            /// Consumes the list and returns its values in order, moved out of
+            /// their boxes.
+            pub fn collect_into_vec(mut self) -> Vec<V> {
+                let mut values = Vec::new();
+                self.drain_to(&mut values);
+                values
+            }
+
+            /// Removes all the elements of the list and appends their values to
+            /// `out` in order, moved out of their boxes.
+            pub fn drain_to(&mut self, out: &mut Vec<V>) {
+                while let Some(value) = self.pop_front() {
+                    out.push(*value);
+                }
+            }
+        }
+    };
+}
+
+impl_list_convert!(LinkedList, LinkedListOps);
+impl_list_convert!(SinglyLinkedList, SinglyLinkedListOps);
+impl_list_convert!(XorLinkedList, XorLinkedListOps);
+
+impl<A, V> LinkedList<A>
+where
+    A: Adapter<PointerOps = DefaultPointerOps<Box<V>>>,
+    A::LinkOps: LinkedListOps,
+{
+    fn extend_values(&mut self, values: impl IntoIterator<Item = V>) {
+        for value in values {
+            self.push_back(Box::new(value));
+        }
+    }
+}
+
+impl<A, V> SinglyLinkedList<A>
+where
+    A: Adapter<PointerOps = DefaultPointerOps<Box<V>>>,
+    A::LinkOps: SinglyLinkedListOps,
+{
+    fn extend_values(&mut self, values: impl IntoIterator<Item = V>) {
+        let mut cursor = self.cursor_mut();
+        for value in values {
+            cursor.insert_after(Box::new(value));
+            cursor.move_next();
+        }
+    }
+}
+
+impl<A, V> XorLinkedList<A>
+where
+    A: Adapter<PointerOps = DefaultPointerOps<Box<V>>>,
+    A::LinkOps: XorLinkedListOps,
+{
+    fn extend_values(&mut self, values: impl IntoIterator<Item = V>) {
+        for value in values {
+            self.push_back(Box::new(value));
+        }
+    }
+}
+
+impl<A, V> RBTree<A>
+where
+    A: Adapter<PointerOps = DefaultPointerOps<Box<V>>>,
+    A: for<'a> KeyAdapter<'a>,
+    for<'a> <A as KeyAdapter<'a>>::Key: Ord,
+    <A as Adapter>::LinkOps: RBTreeOps,
+{
+    /// Creates an `RBTree` holding `values`, each moved into a new `Box`.
+    pub fn from_values(adapter: A, values: impl IntoIterator<Item = V>) -> Self {
+        let mut tree = RBTree::new(adapter);
+        for value in values {
+            tree.insert(Box::new(value));
+        }
+        tree
+    }
+
+    /// Creates an `RBTree` holding the values of `map`, each moved into a new
+    /// `Box`.
+    ///
+    /// The keys of `map` are dropped: the elements are ordered by the keys
+    /// that the adapter gets from them.
+    pub fn from_btree_map<K>(adapter: A, map: BTreeMap<K, V>) -> Self {
+        RBTree::from_values(adapter, map.into_values())
+    }
+
+    /// Consumes the `RBTree` and returns its values in order, moved out of
+    /// their boxes.
+    pub fn collect_into_vec(mut self) -> Vec<V> {
+        let mut values = Vec::new();
+        self.drain_to(&mut values);
+        values
+    }
+
+    /// Removes all the elements of the `RBTree` and appends their values to
+    /// `out` in order, moved out of their boxes.
+    pub fn drain_to(&mut self, out: &mut Vec<V>) {
+        let mut cursor = self.front_mut();
+        while let Some(value) = cursor.remove() {
+            out.push(*value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        KeyAdapter, LinkedList, LinkedListLink, RBTree, RBTreeLink, SinglyLinkedList,
+        SinglyLinkedListLink, XorLinkedList, XorLinkedListLink,
+    };
+    use std::boxed::Box;
+    use std::collections::BTreeMap;
+    use std::vec::Vec;
+
+    struct Obj {
+        ll: LinkedListLink,
+        sll: SinglyLinkedListLink,
+        xll: XorLinkedListLink,
+        rb: RBTreeLink,
+        value: u32,
+    }
+    fn make_obj(value: u32) -> Obj {
+        Obj {
+            ll: LinkedListLink::new(),
+            sll: SinglyLinkedListLink::new(),
+            xll: XorLinkedListLink::new(),
+            rb: RBTreeLink::new(),
+            value,
+        }
+    }
+    fn values(objs: &[Obj]) -> Vec<u32> {
+        objs.iter().map(|x| x.value).collect()
+    }
+    intrusive_adapter!(LlAdapter = Box<Obj>: Obj { ll: LinkedListLink });
+    intrusive_adapter!(SllAdapter = Box<Obj>: Obj { sll: SinglyLinkedListLink });
+    intrusive_adapter!(XllAdapter = Box<Obj>: Obj { xll: XorLinkedListLink });
+    intrusive_adapter!(RbAdapter = Box<Obj>: Obj { rb: RBTreeLink });
+    impl<'a> KeyAdapter<'a> for RbAdapter {
+        type Key = u32;
+        fn get_key(&self, x: &'a Obj) -> u32 {
+            x.value
+        }
+    }
+
+    #[test]
+    fn test_lists() {
+        let objs = || [3, 1, 2].iter().map(|&x| make_obj(x));
+
+        let l = LinkedList::from_values(LlAdapter::new(), objs());
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [3, 1, 2]);
+        let objs = l.collect_into_vec();
+        assert_eq!(values(&objs), [3, 1, 2]);
+        assert!(objs.iter().all(|x| !x.ll.is_linked()));
+
+        let l = SinglyLinkedList::from_values(SllAdapter::new(), objs);
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [3, 1, 2]);
+        let objs = l.collect_into_vec();
+
+        let mut l = XorLinkedList::from_values(XllAdapter::new(), objs);
+        let mut out = std::vec![make_obj(0)];
+        l.drain_to(&mut out);
+        assert!(l.is_empty());
+        assert_eq!(values(&out), [0, 3, 1, 2]);
+    }
+
+    #[test]
+    fn test_rbtree() {
+        let map = (1..4)
+            .map(|x| (4 - x, make_obj(x)))
+            .collect::<BTreeMap<_, _>>();
+        let mut t = RBTree::from_btree_map(RbAdapter::new(), map);
+        assert_eq!(t.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 2, 3]);
+
+        let mut out = Vec::new();
+        t.drain_to(&mut out);
+        assert!(t.is_empty());
+        assert_eq!(values(&out), [1, 2, 3]);
+
+        let t = RBTree::from_values(RbAdapter::new(), out.into_iter().rev());
+        assert_eq!(values(&t.collect_into_vec()), [1, 2, 3]);
+    }
+}
@@ -0,0 +1,606 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Intrusive work-stealing deque, for building task schedulers.
+
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::ptr::{null_mut, NonNull};
+
+use crate::adapter::{already_linked, unlinked_pointer, Adapter};
+use crate::link_ops::LinkOps;
+use crate::linked_list::{AtomicLink, AtomicLinkOps, LinkedListOps};
+use crate::pointer_ops::PointerOps;
+use crate::sync::{fence, AtomicPtr, AtomicUsize, Ordering};
+
+// =============================================================================
+// StealDeque
+// =============================================================================
+
+/// An intrusive deque which one thread, the worker, pushes to and pops from
+/// at one end, while other threads steal elements from the other end.
+///
+/// Elements are linked with a `LinkedListAtomicLink`. The deque is used
+/// through the `Worker` and the `Stealer`s returned by `split`: the worker
+/// pushes and pops the newest elements, while the stealers take the oldest
+/// ones. No lock is taken on either side.
+///
+/// The elements are indexed by two counters, as in the Chase-Lev deque: the
+/// worker reserves the elements it touches by moving the bottom counter
+/// before checking the top counter, and a stealer takes the oldest element
+/// by moving the top counter forward with a compare-and-swap, which the
+/// worker also uses when both race for the last element. Since the elements
+/// aren't in an array, the stealer which took an element also moves the
+/// head of the list to the next one, and the top counter marks the head as
+/// busy until it has done so. A stealer which sees the head busy, or loses
+/// the compare-and-swap, gives up instead of waiting.
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::steal_deque::StealDeque;
+/// use intrusive_collections::{intrusive_adapter, LinkedListAtomicLink};
+/// use std::thread;
+///
+/// struct Task {
+///     link: LinkedListAtomicLink,
+///     id: u32,
+/// }
+/// intrusive_adapter!(TaskAdapter = Box<Task>: Task { link: LinkedListAtomicLink });
+///
+/// let mut deque = StealDeque::new(TaskAdapter::new());
+/// let (mut worker, stealer) = deque.split();
+/// for id in 0..100 {
+///     worker.push(Box::new(Task { link: LinkedListAtomicLink::new(), id }));
+/// }
+/// let stolen = thread::scope(|s| {
+///     let thief = s.spawn(|| {
+///         let mut stolen = Vec::new();
+///         while let Some(task) = stealer.steal() {
+///             stolen.push(task.id);
+///         }
+///         stolen
+///     });
+///     let mut done = 0;
+///     while let Some(task) = worker.pop() {
+///         done += 1;
+///         assert!(task.id < 100);
+///     }
+///     let stolen = thief.join().unwrap();
+///     assert_eq!(done + stolen.len(), 100);
+///     stolen
+/// });
+/// // The stealer takes the oldest tasks first.
+/// assert!(stolen.windows(2).all(|w| w[0] < w[1]));
+/// ```
+pub struct StealDeque<A: Adapter<LinkOps = AtomicLinkOps>> {
+    // One past the index of the newest element. Only the worker changes it.
+    bottom: AtomicUsize,
+    // The index of the oldest element, shifted left by one, with `HEAD_BUSY`
+    // set while the stealer which took the element before it moves `head`.
+    top: AtomicUsize,
+    // The oldest element. Only valid while the deque isn't empty and the
+    // head isn't busy.
+    head: AtomicPtr<AtomicLink>,
+    // The newest element, only used by the worker.
+    tail: UnsafeCell<Option<NonNull<AtomicLink>>>,
+    adapter: A,
+}
+
+// The bit of `top` set while a stealer moves the head.
+const HEAD_BUSY: usize = 1;
+
+// One element in `top`.
+const TOP_ONE: usize = 2;
+
+// Elements are moved to other threads by the stealers.
+unsafe impl<A: Adapter<LinkOps = AtomicLinkOps> + Sync> Sync for StealDeque<A> where
+    <A::PointerOps as PointerOps>::Pointer: Send
+{
+}
+
+// Allow sending to another thread if the ownership of the elements can be
+// transferred to another thread.
+unsafe impl<A: Adapter<LinkOps = AtomicLinkOps> + Send> Send for StealDeque<A> where
+    <A::PointerOps as PointerOps>::Pointer: Send
+{
+}
+
+impl<A: Adapter<LinkOps = AtomicLinkOps>> StealDeque<A> {
+    loom_const_fn! {
+        /// Creates an empty `StealDeque`.
+        #[inline]
+        pub fn new(adapter: A) -> StealDeque<A> {
+            StealDeque {
+                bottom: AtomicUsize::new(0),
+                top: AtomicUsize::new(0),
+                head: AtomicPtr::new(null_mut()),
+                tail: UnsafeCell::new(None),
+                adapter,
+            }
+        }
+    }
+
+    /// Returns a reference to the adapter of the deque.
+    #[inline]
+    pub fn adapter(&self) -> &A {
+        &self.adapter
+    }
+
+    /// Returns `true` if the deque doesn't contain any elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.top.load(Ordering::Acquire) / TOP_ONE >= self.bottom.load(Ordering::Acquire)
+    }
+
+    /// Returns the `Worker` of the deque, and a `Stealer` which can be copied
+    /// to other threads.
+    #[inline]
+    pub fn split(&mut self) -> (Worker<'_, A>, Stealer<'_, A>) {
+        (Worker { deque: self }, Stealer { deque: self })
+    }
+
+    /// Removes all elements from the deque.
+    ///
+    /// Each element is converted back to an owned pointer and then dropped,
+    /// the newest first.
+    #[inline]
+    pub fn clear(&mut self) {
+        let mut worker = Worker { deque: self };
+        while worker.pop().is_some() {}
+    }
+}
+
+impl<A: Adapter<LinkOps = AtomicLinkOps>> Drop for StealDeque<A> {
+    #[inline]
+    fn drop(&mut self) {
+        let leaked = crate::adapter::checks_leaks(&self.adapter) && !self.is_empty();
+        self.clear();
+        if leaked {
+            crate::adapter::leaked_on_drop(&self.adapter, None);
+        }
+    }
+}
+
+impl<A: Adapter<LinkOps = AtomicLinkOps> + Default> Default for StealDeque<A> {
+    fn default() -> StealDeque<A> {
+        StealDeque::new(A::default())
+    }
+}
+
+impl<A: Adapter<LinkOps = AtomicLinkOps>> fmt::Debug for StealDeque<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StealDeque")
+            .field("is_empty", &self.is_empty())
+            .finish_non_exhaustive()
+    }
+}
+
+// =============================================================================
+// Worker
+// =============================================================================
+
+/// The end of a `StealDeque` which pushes and pops the newest elements,
+/// returned by `StealDeque::split`.
+pub struct Worker<'a, A: Adapter<LinkOps = AtomicLinkOps>> {
+    deque: &'a StealDeque<A>,
+}
+
+impl<'a, A: Adapter<LinkOps = AtomicLinkOps>> Worker<'a, A> {
+    /// Returns `true` if the deque doesn't contain any elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.deque.is_empty()
+    }
+
+    /// Returns a `Stealer` for the deque.
+    #[inline]
+    pub fn stealer(&self) -> Stealer<'a, A> {
+        Stealer { deque: self.deque }
+    }
+
+    /// Inserts a new element at the worker's end of the deque.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new element is already linked to a different intrusive
+    /// collection.
+    #[inline]
+    pub fn push(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        let deque = self.deque;
+        let mut link_ops = *deque.adapter.link_ops();
+        unsafe {
+            let raw = deque.adapter.pointer_ops().into_raw(val);
+            let link = deque.adapter.get_link(raw);
+            if !link_ops.acquire_link(link) {
+                // convert the node back into a pointer
+                deque.adapter.pointer_ops().from_raw(raw);
+
+                already_linked(&deque.adapter, None, raw, None);
+            }
+            crate::adapter::link_acquired(&deque.adapter, raw);
+            link_ops.set_next(link, None);
+
+            let b = deque.bottom.load(Ordering::Relaxed);
+            if deque.top.load(Ordering::Relaxed) / TOP_ONE != b {
+                // Hide the newest element from the stealers while the new one
+                // is linked after it.
+                deque.bottom.store(b - 1, Ordering::Release);
+                fence(Ordering::SeqCst);
+                let mut top = deque.top.load(Ordering::Relaxed);
+                let tail = (*deque.tail.get()).unwrap();
+                if top / TOP_ONE < b - 1 {
+                    link_ops.set_prev(link, Some(tail));
+                    link_ops.set_next(tail, Some(link));
+                    *deque.tail.get() = Some(link);
+                    // This publishes the link of the element to the stealers.
+                    deque.bottom.store(b + 1, Ordering::Release);
+                    return;
+                }
+
+                // The newest element is also the oldest one, so take it before
+                // a stealer does, and push it back as the head.
+                while top / TOP_ONE == b - 1 {
+                    match deque.top.compare_exchange_weak(
+                        top,
+                        top + TOP_ONE,
+                        Ordering::SeqCst,
+                        Ordering::Relaxed,
+                    ) {
+                        Ok(_) => {
+                            link_ops.set_prev(tail, None);
+                            link_ops.set_next(tail, Some(link));
+                            link_ops.set_prev(link, Some(tail));
+                            deque.head.store(tail.as_ptr(), Ordering::Relaxed);
+                            *deque.tail.get() = Some(link);
+                            deque.bottom.store(b + 2, Ordering::Release);
+                            return;
+                        }
+                        Err(current) => top = current,
+                    }
+                }
+                // A stealer took it, so the deque is empty.
+            }
+
+            link_ops.set_prev(link, None);
+            deque.head.store(link.as_ptr(), Ordering::Relaxed);
+            *deque.tail.get() = Some(link);
+            deque.bottom.store(b + 1, Ordering::Release);
+        }
+    }
+
+    /// Removes the newest element of the deque and returns it.
+    ///
+    /// This returns `None` if the deque is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        let deque = self.deque;
+        let link_ops = *deque.adapter.link_ops();
+        unsafe {
+            let b = deque.bottom.load(Ordering::Relaxed);
+            if deque.top.load(Ordering::Relaxed) / TOP_ONE == b {
+                return None;
+            }
+
+            // Reserve the newest element, which no stealer can take if
+            // another one is left.
+            deque.bottom.store(b - 1, Ordering::Release);
+            fence(Ordering::SeqCst);
+            let mut top = deque.top.load(Ordering::Relaxed);
+            let link = (*deque.tail.get()).unwrap();
+            if top / TOP_ONE < b - 1 {
+                *deque.tail.get() = link_ops.prev(link);
+                return Some(self.unlink(link));
+            }
+
+            // Race with the stealers for the last element.
+            while top / TOP_ONE == b - 1 {
+                match deque.top.compare_exchange_weak(
+                    top,
+                    top + TOP_ONE,
+                    Ordering::SeqCst,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        *deque.tail.get() = None;
+                        deque.bottom.store(b, Ordering::Release);
+                        return Some(self.unlink(link));
+                    }
+                    Err(current) => top = current,
+                }
+            }
+            deque.bottom.store(b, Ordering::Release);
+            None
+        }
+    }
+
+    #[inline]
+    unsafe fn unlink(&self, link: NonNull<AtomicLink>) -> <A::PointerOps as PointerOps>::Pointer {
+        let mut link_ops = *self.deque.adapter.link_ops();
+        link_ops.release_link(link);
+        unlinked_pointer(&self.deque.adapter, link)
+    }
+}
+
+impl<A: Adapter<LinkOps = AtomicLinkOps>> fmt::Debug for Worker<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Worker").finish_non_exhaustive()
+    }
+}
+
+// =============================================================================
+// Stealer
+// =============================================================================
+
+/// The end of a `StealDeque` which takes the oldest elements, returned by
+/// `StealDeque::split` and `Worker::stealer`.
+///
+/// It can be copied and shared between threads.
+pub struct Stealer<'a, A: Adapter<LinkOps = AtomicLinkOps>> {
+    deque: &'a StealDeque<A>,
+}
+
+impl<'a, A: Adapter<LinkOps = AtomicLinkOps>> Stealer<'a, A> {
+    /// Returns `true` if the deque doesn't contain any elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.deque.is_empty()
+    }
+
+    /// Removes the oldest element of the deque and returns it.
+    ///
+    /// This returns `None` if the deque is empty. It can also return `None`
+    /// spuriously when it races with another stealer, or with the worker for
+    /// the last element.
+    #[inline]
+    pub fn steal(&self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        let deque = self.deque;
+        let mut link_ops = *deque.adapter.link_ops();
+        let top = deque.top.load(Ordering::Acquire);
+        if top & HEAD_BUSY != 0 {
+            return None;
+        }
+        // Pairs with the fence of the worker, so that either the worker sees
+        // the elements taken so far or this sees the element it reserved.
+        fence(Ordering::SeqCst);
+        if top / TOP_ONE >= deque.bottom.load(Ordering::Acquire) {
+            return None;
+        }
+        // The worker only sets the head while the deque is empty, or after
+        // taking the oldest element itself, and the compare-and-swap fails if
+        // it did so since `top` was read.
+        let head = deque.head.load(Ordering::Relaxed);
+        if deque
+            .top
+            .compare_exchange(
+                top,
+                top + TOP_ONE + HEAD_BUSY,
+                Ordering::SeqCst,
+                Ordering::Relaxed,
+            )
+            .is_err()
+        {
+            return None;
+        }
+        unsafe {
+            let link = NonNull::new_unchecked(head);
+            // The worker never links an element after one which a stealer
+            // may take, so the next pointer is either the next element, or
+            // a stale one if this was the last element. The worker sets the
+            // head again when it pushes into the empty deque, which makes
+            // this fail if it comes later.
+            if let Some(next) = link_ops.next(link) {
+                let _ = deque.head.compare_exchange(
+                    link.as_ptr(),
+                    next.as_ptr(),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                );
+            }
+            deque.top.fetch_sub(HEAD_BUSY, Ordering::SeqCst);
+            link_ops.release_link(link);
+            Some(unlinked_pointer(&deque.adapter, link))
+        }
+    }
+}
+
+impl<A: Adapter<LinkOps = AtomicLinkOps>> Clone for Stealer<'_, A> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A: Adapter<LinkOps = AtomicLinkOps>> Copy for Stealer<'_, A> {}
+
+impl<A: Adapter<LinkOps = AtomicLinkOps>> fmt::Debug for Stealer<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Stealer").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StealDeque;
+    use crate::LinkedListAtomicLink;
+    use std::boxed::Box;
+    use std::sync::Arc;
+    use std::vec::Vec;
+
+    #[derive(Debug)]
+    struct Obj {
+        link: LinkedListAtomicLink,
+        value: u32,
+    }
+    intrusive_adapter!(ObjAdapter = Box<Obj>: Obj { link: LinkedListAtomicLink });
+    intrusive_adapter!(ArcObjAdapter = Arc<Obj>: Obj { link: LinkedListAtomicLink });
+
+    fn make_obj(value: u32) -> Box<Obj> {
+        Box::new(Obj {
+            link: LinkedListAtomicLink::new(),
+            value,
+        })
+    }
+
+    #[test]
+    fn test_push_pop_steal() {
+        let mut d = StealDeque::new(ObjAdapter::new());
+        let (mut worker, stealer) = d.split();
+        assert!(worker.pop().is_none());
+        assert!(stealer.steal().is_none());
+        for value in 0..6 {
+            worker.push(make_obj(value));
+        }
+        assert!(!stealer.is_empty());
+        assert_eq!(worker.pop().unwrap().value, 5);
+        assert_eq!(stealer.steal().unwrap().value, 0);
+        assert_eq!(worker.pop().unwrap().value, 4);
+        worker.push(make_obj(6));
+        assert_eq!(stealer.steal().unwrap().value, 1);
+        assert_eq!(worker.stealer().steal().unwrap().value, 2);
+        assert_eq!(worker.pop().unwrap().value, 6);
+        let obj = stealer.steal().unwrap();
+        assert_eq!(obj.value, 3);
+        assert!(!obj.link.is_linked());
+        assert!(worker.is_empty());
+        assert!(worker.pop().is_none());
+        assert!(stealer.steal().is_none());
+
+        // The deque works the same once it has been emptied.
+        worker.push(obj);
+        worker.push(make_obj(7));
+        assert_eq!(stealer.steal().unwrap().value, 3);
+        assert_eq!(stealer.steal().unwrap().value, 7);
+        worker.push(make_obj(8));
+        assert_eq!(worker.pop().unwrap().value, 8);
+        worker.push(make_obj(9));
+        d.clear();
+        assert!(d.is_empty());
+    }
+
+    #[test]
+    fn test_drop() {
+        let obj = Arc::new(Obj {
+            link: LinkedListAtomicLink::new(),
+            value: 0,
+        });
+        let mut d = StealDeque::new(ArcObjAdapter::new());
+        let (mut worker, stealer) = d.split();
+        worker.push(obj.clone());
+        for value in 1..5 {
+            worker.push(Arc::new(Obj {
+                link: LinkedListAtomicLink::new(),
+                value,
+            }));
+        }
+        assert_eq!(stealer.steal().unwrap().value, 0);
+        worker.push(obj.clone());
+        drop(d);
+        assert_eq!(Arc::strong_count(&obj), 1);
+    }
+
+    #[test]
+    fn test_concurrent_steal() {
+        const COUNT: u32 = 10_000;
+        let mut d = StealDeque::new(ObjAdapter::new());
+        let (mut worker, stealer) = d.split();
+        let mut values = std::thread::scope(|s| {
+            let thieves = (0..3)
+                .map(|_| {
+                    s.spawn(move || {
+                        let mut values = Vec::new();
+                        let mut misses = 0;
+                        while misses < 1000 {
+                            match stealer.steal() {
+                                Some(obj) => values.push(obj.value),
+                                None => misses += 1,
+                            }
+                        }
+                        values
+                    })
+                })
+                .collect::<Vec<_>>();
+            let mut values = Vec::new();
+            for value in 0..COUNT {
+                worker.push(make_obj(value));
+                if value % 3 == 0 {
+                    values.extend(worker.pop().map(|x| x.value));
+                }
+            }
+            while let Some(obj) = worker.pop() {
+                values.push(obj.value);
+            }
+            for thief in thieves {
+                let stolen = thief.join().unwrap();
+                assert!(stolen.windows(2).all(|w| w[0] < w[1]));
+                values.extend(stolen);
+            }
+            values
+        });
+        values.sort_unstable();
+        assert_eq!(values, (0..COUNT).collect::<Vec<_>>());
+    }
+
+    #[cfg(loom)]
+    #[test]
+    fn test_loom_pop_steal() {
+        use loom::thread;
+
+        loom::model(|| {
+            // Loom threads need a `'static` worker and stealer.
+            let d = Box::leak(Box::new(StealDeque::new(ObjAdapter::new())));
+            let (mut worker, stealer) = d.split();
+            for value in 0..3 {
+                worker.push(make_obj(value));
+            }
+            let thief = thread::spawn(move || {
+                (0..2)
+                    .filter_map(|_| stealer.steal())
+                    .map(|obj| obj.value)
+                    .collect::<Vec<_>>()
+            });
+            let mut values = Vec::new();
+            worker.push(make_obj(3));
+            while let Some(obj) = worker.pop() {
+                values.push(obj.value);
+            }
+            values.extend(thief.join().unwrap());
+            values.sort_unstable();
+            assert_eq!(values, [0, 1, 2, 3]);
+        });
+    }
+
+    #[cfg(loom)]
+    #[test]
+    fn test_loom_push_steal() {
+        use loom::thread;
+
+        // Three threads are too many to explore every interleaving.
+        let mut builder = loom::model::Builder::new();
+        builder.preemption_bound = Some(3);
+        builder.check(|| {
+            let d = Box::leak(Box::new(StealDeque::new(ObjAdapter::new())));
+            let (mut worker, stealer) = d.split();
+            worker.push(make_obj(0));
+            let thieves = (0..2)
+                .map(|_| thread::spawn(move || stealer.steal().map(|obj| obj.value)))
+                .collect::<Vec<_>>();
+            // Pushing into a deque holding one element races with the
+            // stealers for it.
+            worker.push(make_obj(1));
+            let mut values = Vec::new();
+            while let Some(obj) = worker.pop() {
+                values.push(obj.value);
+            }
+            for thief in thieves {
+                values.extend(thief.join().unwrap());
+            }
+            values.sort_unstable();
+            assert_eq!(values, [0, 1]);
+        });
+    }
+}
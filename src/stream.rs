@@ -0,0 +1,365 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Asynchronous streams of elements removed from collections.
+//!
+//! The owning iterators of all collections implement `futures_core::Stream`,
+//! yielding the elements of the collection in the same order as the
+//! iterator. `QueueStream` is a queue which a consumer can wait on while it is
+//! empty.
+
+use core::fmt;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+
+use futures_core::Stream;
+
+use crate::linked_list::{LinkedList, LinkedListOps};
+use crate::pointer_ops::PointerOps;
+use crate::rbtree::RBTreeOps;
+use crate::singly_linked_list::SinglyLinkedListOps;
+use crate::xor_linked_list::XorLinkedListOps;
+use crate::{linked_list, rbtree, singly_linked_list, xor_linked_list, Adapter};
+
+macro_rules! impl_stream {
+    ($module:ident, $ops:ident) => {
+        impl<A: Adapter> Stream for $module::IntoIter<A>
+        where
+            A::LinkOps: $ops,
+        {
+            type Item = <A::PointerOps as PointerOps>::Pointer;
+
+            #[inline]
+            fn poll_next(
+                self: Pin<&mut Self>,
+                _cx: &mut Context<'_>,
+            ) -> Poll<Option<<A::PointerOps as PointerOps>::Pointer>> {
+                // The iterator is never pinned structurally, so it can be
+                // accessed by mutable reference.
+                Poll::Ready(unsafe { self.get_unchecked_mut() }.next())
+            }
+        }
+    };
+}
+
+impl_stream!(linked_list, LinkedListOps);
+impl_stream!(singly_linked_list, SinglyLinkedListOps);
+impl_stream!(xor_linked_list, XorLinkedListOps);
+impl_stream!(rbtree, RBTreeOps);
+
+// =============================================================================
+// QueueStream
+// =============================================================================
+
+/// A first-in, first-out queue of elements which can be consumed as a
+/// `Stream`.
+///
+/// Elements are added with `push_back`. When the queue is empty, polling it
+/// registers the waker of the task and returns `Poll::Pending`, and the next
+/// call to `push_back` or `close` wakes the task. Once the queue is closed
+/// and empty, the stream ends.
+///
+/// The queue itself is not synchronized. A producer and a consumer on the
+/// same thread can share it through a `RefCell` and use `poll_pop_front`,
+/// while producers on other threads need to wrap it in a lock.
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::stream::QueueStream;
+/// use intrusive_collections::{intrusive_adapter, LinkedListLink};
+/// use std::task::{Context, Poll};
+///
+/// struct Job {
+///     link: LinkedListLink,
+///     id: u32,
+/// }
+/// intrusive_adapter!(JobAdapter = Box<Job>: Job { link: LinkedListLink });
+///
+/// let waker = noop_waker();
+/// let mut cx = Context::from_waker(&waker);
+/// let mut queue = QueueStream::new(JobAdapter::new());
+/// assert!(queue.poll_pop_front(&mut cx).is_pending());
+///
+/// queue.push_back(Box::new(Job { link: LinkedListLink::new(), id: 1 }));
+/// queue.close();
+/// match queue.poll_pop_front(&mut cx) {
+///     Poll::Ready(Some(job)) => assert_eq!(job.id, 1),
+///     _ => unreachable!(),
+/// }
+/// assert!(matches!(queue.poll_pop_front(&mut cx), Poll::Ready(None)));
+/// # fn noop_waker() -> std::task::Waker {
+/// #     struct Noop;
+/// #     impl std::task::Wake for Noop {
+/// #         fn wake(self: std::sync::Arc<Self>) {}
+/// #     }
+/// #     std::sync::Arc::new(Noop).into()
+/// # }
+/// ```
+pub struct QueueStream<A: Adapter>
+where
+    A::LinkOps: LinkedListOps,
+{
+    list: LinkedList<A>,
+    waker: Option<Waker>,
+    closed: bool,
+}
+
+impl<A: Adapter> QueueStream<A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    /// Creates an empty, open `QueueStream`.
+    #[inline]
+    pub const fn new(adapter: A) -> QueueStream<A> {
+        QueueStream {
+            list: LinkedList::new(adapter),
+            waker: None,
+            closed: false,
+        }
+    }
+
+    /// Returns `true` if the queue contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Returns `true` if the queue was closed.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Returns a reference to the list holding the queued elements.
+    #[inline]
+    pub fn as_list(&self) -> &LinkedList<A> {
+        &self.list
+    }
+
+    /// Adds an element to the back of the queue, and wakes the task waiting
+    /// on it if there is one.
+    ///
+    /// Elements can still be added after the queue is closed, they are
+    /// yielded before the stream ends.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new element is already linked to a different intrusive
+    /// collection.
+    #[inline]
+    pub fn push_back(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        self.list.push_back(val);
+        self.wake();
+    }
+
+    /// Closes the queue, so that the stream ends once the remaining elements
+    /// are consumed, and wakes the task waiting on it if there is one.
+    #[inline]
+    pub fn close(&mut self) {
+        self.closed = true;
+        self.wake();
+    }
+
+    /// Removes the element at the front of the queue.
+    ///
+    /// If the queue is empty, this returns `Poll::Ready(None)` if it is closed
+    /// and otherwise registers the waker from `cx` to be woken by the next
+    /// `push_back` or `close`. Only the waker of the last call is kept.
+    #[inline]
+    pub fn poll_pop_front(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<<A::PointerOps as PointerOps>::Pointer>> {
+        if let Some(val) = self.list.pop_front() {
+            return Poll::Ready(Some(val));
+        }
+        if self.closed {
+            return Poll::Ready(None);
+        }
+        match &mut self.waker {
+            Some(waker) if waker.will_wake(cx.waker()) => {}
+            waker => *waker = Some(cx.waker().clone()),
+        }
+        Poll::Pending
+    }
+
+    /// Consumes the queue and returns the list holding the queued elements.
+    #[inline]
+    pub fn into_list(self) -> LinkedList<A> {
+        self.list
+    }
+
+    #[inline]
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<A: Adapter> Stream for QueueStream<A>
+where
+    A::LinkOps: LinkedListOps,
+{
+    type Item = <A::PointerOps as PointerOps>::Pointer;
+
+    #[inline]
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<<A::PointerOps as PointerOps>::Pointer>> {
+        // The queue is never pinned structurally, so it can be accessed by
+        // mutable reference.
+        unsafe { self.get_unchecked_mut() }.poll_pop_front(cx)
+    }
+}
+
+impl<A: Adapter> fmt::Debug for QueueStream<A>
+where
+    A::LinkOps: LinkedListOps,
+    <A::PointerOps as PointerOps>::Value: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueueStream")
+            .field("list", &self.list)
+            .field("closed", &self.closed)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::QueueStream;
+    use crate::{LinkedList, LinkedListLink, RBTree, RBTreeLink, UnsafeRef};
+    use core::pin::Pin;
+    use core::task::{Context, Poll};
+    use futures_core::Stream;
+    use std::boxed::Box;
+    use std::cell::RefCell;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::{Wake, Waker};
+    use std::vec::Vec;
+
+    struct Obj {
+        link: LinkedListLink,
+        rb: RBTreeLink,
+        value: u32,
+    }
+    intrusive_adapter!(ObjAdapter = Box<Obj>: Obj { link: LinkedListLink });
+    intrusive_adapter!(RbAdapter = UnsafeRef<Obj>: Obj { rb: RBTreeLink });
+    impl<'a> crate::KeyAdapter<'a> for RbAdapter {
+        type Key = u32;
+        fn get_key(&self, x: &'a Obj) -> u32 {
+            x.value
+        }
+    }
+
+    fn make_obj(value: u32) -> Box<Obj> {
+        Box::new(Obj {
+            link: LinkedListLink::new(),
+            rb: RBTreeLink::new(),
+            value,
+        })
+    }
+
+    // Counts how many times it was woken.
+    struct CountingWaker(AtomicUsize);
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn counting_waker() -> (Arc<CountingWaker>, Waker) {
+        let counter = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        (counter.clone(), counter.into())
+    }
+
+    // Polls the stream until it ends, which it must do without waiting.
+    fn collect<S: Stream + Unpin>(mut stream: S) -> Vec<S::Item> {
+        let (_, waker) = counting_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut items = Vec::new();
+        loop {
+            match Pin::new(&mut stream).poll_next(&mut cx) {
+                Poll::Ready(Some(item)) => items.push(item),
+                Poll::Ready(None) => return items,
+                Poll::Pending => panic!("stream is pending"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_into_iter_stream() {
+        let mut l = LinkedList::new(ObjAdapter::new());
+        for value in 0..3 {
+            l.push_back(make_obj(value));
+        }
+        let values: Vec<_> = collect(l.into_iter()).iter().map(|x| x.value).collect();
+        assert_eq!(values, [0, 1, 2]);
+
+        let objs: Vec<_> = [2, 0, 1].iter().map(|&value| make_obj(value)).collect();
+        let mut t = RBTree::new(RbAdapter::new());
+        for obj in &objs {
+            t.insert(unsafe { UnsafeRef::from_raw(&**obj) });
+        }
+        let values: Vec<_> = collect(t.into_iter()).iter().map(|x| x.value).collect();
+        assert_eq!(values, [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_queue_stream() {
+        let (counter, waker) = counting_waker();
+        let mut cx = Context::from_waker(&waker);
+        let queue = RefCell::new(QueueStream::new(ObjAdapter::new()));
+
+        assert!(queue.borrow_mut().poll_pop_front(&mut cx).is_pending());
+        assert!(queue.borrow_mut().poll_pop_front(&mut cx).is_pending());
+        assert_eq!(counter.0.load(Ordering::Relaxed), 0);
+
+        queue.borrow_mut().push_back(make_obj(1));
+        assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+        queue.borrow_mut().push_back(make_obj(2));
+        // The waker was consumed by the first push.
+        assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+
+        match queue.borrow_mut().poll_pop_front(&mut cx) {
+            Poll::Ready(Some(obj)) => assert_eq!(obj.value, 1),
+            _ => panic!("expected an element"),
+        }
+        queue.borrow_mut().close();
+        assert!(queue.borrow().is_closed());
+        assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+
+        let values: Vec<_> = collect(queue.into_inner())
+            .iter()
+            .map(|x| x.value)
+            .collect();
+        assert_eq!(values, [2]);
+    }
+
+    #[test]
+    fn test_queue_stream_close_wakes() {
+        let (counter, waker) = counting_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut queue = QueueStream::new(ObjAdapter::new());
+        assert!(Pin::new(&mut queue).poll_next(&mut cx).is_pending());
+        queue.close();
+        assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+        assert!(matches!(
+            Pin::new(&mut queue).poll_next(&mut cx),
+            Poll::Ready(None)
+        ));
+
+        queue.push_back(make_obj(3));
+        assert!(!queue.is_empty());
+        assert_eq!(queue.as_list().front().get().unwrap().value, 3);
+        assert_eq!(queue.into_list().pop_front().unwrap().value, 3);
+    }
+}
@@ -0,0 +1,58 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+// Atomic types used by the atomic links. Building with `--cfg loom` replaces
+// them with the ones from loom so that the links can be model checked:
+//
+//     RUSTFLAGS="--cfg loom" cargo test --release --lib loom
+//
+// The `portable-atomic` feature replaces them with the ones from
+// portable-atomic, which provide compare-and-swap on targets that lack it.
+
+#[cfg(loom)]
+pub(crate) use loom::sync::atomic::{fence, AtomicPtr, AtomicUsize, Ordering};
+
+#[cfg(all(not(loom), feature = "portable-atomic"))]
+pub(crate) use portable_atomic::{fence, AtomicPtr, AtomicUsize, Ordering};
+
+// Without compare-and-swap only `AtomicUsize` is used, by `GenerationLink`.
+#[cfg(all(not(loom), not(feature = "portable-atomic")))]
+#[allow(unused_imports)]
+pub(crate) use core::sync::atomic::{fence, AtomicPtr, AtomicUsize, Ordering};
+
+// Spin loops must yield to the other threads under loom, or it can't make
+// progress in the thread they wait for.
+#[cfg(loom)]
+pub(crate) use loom::hint::spin_loop;
+
+// `SeqLocked`, which spins, needs atomic compare-and-swap.
+#[cfg(not(loom))]
+#[allow(unused_imports)]
+pub(crate) use core::hint::spin_loop;
+
+// Loom atomics can't be created in a constant expression, so this defines a
+// `const fn` normally and a plain `fn` under loom.
+macro_rules! loom_const_fn {
+    ($(#[$attr:meta])* $vis:vis fn $name:ident() -> $ret:ty $body:block) => {
+        #[cfg(not(loom))]
+        $(#[$attr])*
+        $vis const fn $name() -> $ret $body
+
+        #[cfg(loom)]
+        $(#[$attr])*
+        $vis fn $name() -> $ret $body
+    };
+    ($(#[$attr:meta])* $vis:vis fn $name:ident($arg:ident: $ty:ty) -> $ret:ty $body:block) => {
+        #[cfg(not(loom))]
+        $(#[$attr])*
+        $vis const fn $name($arg: $ty) -> $ret $body
+
+        #[cfg(loom)]
+        $(#[$attr])*
+        $vis fn $name($arg: $ty) -> $ret $body
+    };
+}
@@ -0,0 +1,269 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use core::fmt;
+
+use crate::adapter::Adapter;
+use crate::key_adapter::KeyAdapter;
+use crate::link_ops::LinkOps;
+use crate::pointer_ops::{DefaultPointerOps, PointerOps};
+use crate::priority_adapter::PriorityAdapter;
+
+/// Trait for `#[repr(transparent)]` wrappers around another type.
+///
+/// This allows `TransparentAdapter` to reuse the adapter of the inner type for
+/// the wrapper.
+///
+/// # Safety
+///
+/// `Self` must be a `#[repr(transparent)]` struct whose only non-zero-sized
+/// field has type `Inner`.
+pub unsafe trait TransparentWrapper {
+    /// The wrapped type.
+    type Inner;
+}
+
+/// Adapter for a `#[repr(transparent)]` wrapper type, built from the adapter
+/// of the wrapped type.
+///
+/// `A` is the adapter of the inner type and `P` is the pointer type to the
+/// wrapper, for example `Box<Wrapper>`. The links used are the same as for
+/// `A`, so a collection using this adapter and one using `A` cannot contain
+/// the same object at the same time. `KeyAdapter` and `PriorityAdapter` are
+/// forwarded to `A` if it implements them.
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::{intrusive_adapter, LinkedList, LinkedListLink};
+/// use intrusive_collections::{TransparentAdapter, TransparentWrapper};
+///
+/// struct Job {
+///     link: LinkedListLink,
+///     id: u32,
+/// }
+/// intrusive_adapter!(JobAdapter = Box<Job>: Job { link: LinkedListLink });
+///
+/// // A job which has been validated
+/// #[repr(transparent)]
+/// struct ValidJob(Job);
+/// unsafe impl TransparentWrapper for ValidJob {
+///     type Inner = Job;
+/// }
+/// type ValidJobAdapter = TransparentAdapter<JobAdapter, Box<ValidJob>>;
+///
+/// let mut list = LinkedList::new(ValidJobAdapter::new(JobAdapter::new()));
+/// list.push_back(Box::new(ValidJob(Job { link: LinkedListLink::new(), id: 1 })));
+/// assert_eq!(list.front().get().unwrap().0.id, 1);
+/// ```
+pub struct TransparentAdapter<A, P> {
+    inner: A,
+    pointer_ops: DefaultPointerOps<P>,
+}
+
+impl<A, P> TransparentAdapter<A, P> {
+    /// Creates a new `TransparentAdapter` from the adapter of the inner type.
+    #[inline]
+    pub const fn new(inner: A) -> TransparentAdapter<A, P> {
+        TransparentAdapter {
+            inner,
+            pointer_ops: DefaultPointerOps::new(),
+        }
+    }
+
+    /// Returns a reference to the adapter of the inner type.
+    #[inline]
+    pub fn inner(&self) -> &A {
+        &self.inner
+    }
+}
+
+impl<A: Default, P> Default for TransparentAdapter<A, P> {
+    #[inline]
+    fn default() -> Self {
+        TransparentAdapter::new(A::default())
+    }
+}
+
+impl<A: Clone, P> Clone for TransparentAdapter<A, P> {
+    #[inline]
+    fn clone(&self) -> Self {
+        TransparentAdapter::new(self.inner.clone())
+    }
+}
+
+impl<A: Copy, P> Copy for TransparentAdapter<A, P> {}
+
+impl<A: fmt::Debug, P> fmt::Debug for TransparentAdapter<A, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TransparentAdapter")
+            .field(&self.inner)
+            .finish()
+    }
+}
+
+// The pointer type is only used as a marker.
+unsafe impl<A: Send, P> Send for TransparentAdapter<A, P> {}
+unsafe impl<A: Sync, P> Sync for TransparentAdapter<A, P> {}
+
+type Wrapper<P> = <DefaultPointerOps<P> as PointerOps>::Value;
+
+#[inline]
+fn inner_ref<W: TransparentWrapper>(value: &W) -> &W::Inner {
+    unsafe { &*(value as *const W as *const W::Inner) }
+}
+
+unsafe impl<A, P> Adapter for TransparentAdapter<A, P>
+where
+    A: Adapter,
+    <A::PointerOps as PointerOps>::Value: Sized,
+    DefaultPointerOps<P>: PointerOps,
+    Wrapper<P>: TransparentWrapper<Inner = <A::PointerOps as PointerOps>::Value> + Sized,
+{
+    type LinkOps = A::LinkOps;
+    type PointerOps = DefaultPointerOps<P>;
+
+    #[inline]
+    unsafe fn get_value(
+        &self,
+        link: <Self::LinkOps as LinkOps>::LinkPtr,
+    ) -> *const <Self::PointerOps as PointerOps>::Value {
+        self.inner.get_value(link) as *const _
+    }
+
+    #[inline]
+    unsafe fn get_link(
+        &self,
+        value: *const <Self::PointerOps as PointerOps>::Value,
+    ) -> <Self::LinkOps as LinkOps>::LinkPtr {
+        self.inner.get_link(value as *const _)
+    }
+
+    #[inline]
+    fn link_ops(&self) -> &Self::LinkOps {
+        self.inner.link_ops()
+    }
+
+    #[inline]
+    fn link_ops_mut(&mut self) -> &mut Self::LinkOps {
+        self.inner.link_ops_mut()
+    }
+
+    #[inline]
+    fn pointer_ops(&self) -> &Self::PointerOps {
+        &self.pointer_ops
+    }
+
+    #[inline]
+    fn on_insert(&self, value: &<Self::PointerOps as PointerOps>::Value) {
+        self.inner.on_insert(inner_ref(value));
+    }
+
+    #[inline]
+    fn on_remove(&self, value: &<Self::PointerOps as PointerOps>::Value) {
+        self.inner.on_remove(inner_ref(value));
+    }
+}
+
+impl<'a, A, P> KeyAdapter<'a> for TransparentAdapter<A, P>
+where
+    A: KeyAdapter<'a>,
+    <A::PointerOps as PointerOps>::Value: Sized,
+    DefaultPointerOps<P>: PointerOps,
+    Wrapper<P>: TransparentWrapper<Inner = <A::PointerOps as PointerOps>::Value> + Sized + 'a,
+    <A::PointerOps as PointerOps>::Value: 'a,
+{
+    type Key = A::Key;
+
+    #[inline]
+    fn get_key(&self, value: &'a <Self::PointerOps as PointerOps>::Value) -> A::Key {
+        self.inner.get_key(inner_ref(value))
+    }
+}
+
+impl<A, P> PriorityAdapter for TransparentAdapter<A, P>
+where
+    A: PriorityAdapter,
+    <A::PointerOps as PointerOps>::Value: Sized,
+    DefaultPointerOps<P>: PointerOps,
+    Wrapper<P>: TransparentWrapper<Inner = <A::PointerOps as PointerOps>::Value> + Sized,
+{
+    type Priority = A::Priority;
+
+    #[inline]
+    fn get_priority(&self, value: &<Self::PointerOps as PointerOps>::Value) -> A::Priority {
+        self.inner.get_priority(inner_ref(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TransparentAdapter, TransparentWrapper};
+    use crate::{KeyAdapter, LinkedList, LinkedListLink, RBTree, RBTreeLink, UnsafeRef};
+    use std::boxed::Box;
+    use std::vec::Vec;
+
+    struct Obj {
+        link: LinkedListLink,
+        tree_link: RBTreeLink,
+        value: u32,
+    }
+    intrusive_adapter!(ObjAdapter = Box<Obj>: Obj { link: LinkedListLink });
+    intrusive_adapter!(ObjTreeAdapter = Box<Obj>: Obj { tree_link: RBTreeLink });
+    impl<'a> KeyAdapter<'a> for ObjTreeAdapter {
+        type Key = u32;
+        fn get_key(&self, value: &'a Obj) -> u32 {
+            value.value
+        }
+    }
+
+    #[repr(transparent)]
+    struct Frozen(Obj);
+    unsafe impl TransparentWrapper for Frozen {
+        type Inner = Obj;
+    }
+
+    fn make_frozen(value: u32) -> Frozen {
+        Frozen(Obj {
+            link: LinkedListLink::new(),
+            tree_link: RBTreeLink::new(),
+            value,
+        })
+    }
+
+    #[test]
+    fn test_transparent_list() {
+        let mut list = LinkedList::new(TransparentAdapter::<ObjAdapter, Box<Frozen>>::default());
+        for value in 0..3 {
+            list.push_back(Box::new(make_frozen(value)));
+        }
+        assert_eq!(
+            list.iter().map(|x| x.0.value).collect::<Vec<_>>(),
+            [0, 1, 2]
+        );
+        let front = list.pop_front().unwrap();
+        assert!(!front.0.link.is_linked());
+        assert_eq!(front.0.value, 0);
+    }
+
+    #[test]
+    fn test_transparent_key() {
+        let objs = (0..3).map(make_frozen).collect::<Vec<_>>();
+        let mut tree = RBTree::new(
+            TransparentAdapter::<ObjTreeAdapter, UnsafeRef<Frozen>>::new(ObjTreeAdapter::new()),
+        );
+        for obj in objs.iter().rev() {
+            tree.insert(unsafe { UnsafeRef::from_raw(obj) });
+        }
+        assert_eq!(
+            tree.iter().map(|x| x.0.value).collect::<Vec<_>>(),
+            [0, 1, 2]
+        );
+        assert_eq!(tree.find(&1).get().unwrap().0.value, 1);
+        tree.fast_clear();
+    }
+}
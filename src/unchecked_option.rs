@@ -8,6 +8,7 @@
 use core::hint;
 
 /// An extension trait on `Option`.
+#[allow(dead_code)]
 pub trait UncheckedOptionExt<T> {
     /// Returns the contained value.
     ///
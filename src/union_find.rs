@@ -0,0 +1,328 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Intrusive disjoint-set forest, for tracking which objects are connected.
+
+use core::cell::Cell;
+use core::fmt;
+use core::mem;
+use core::ptr::NonNull;
+
+use crate::link_ops::{self, DefaultLinkOps};
+use crate::pointer_ops::PointerOps;
+use crate::Adapter;
+
+// =============================================================================
+// Link
+// =============================================================================
+
+/// Intrusive link that allows an object to be part of a `UnionFind`.
+///
+/// The link holds the parent of the object in its set and the rank of the
+/// object. A new link is the only element of its own set.
+pub struct Link {
+    parent: Cell<Option<NonNull<Link>>>,
+    rank: Cell<u32>,
+}
+
+impl Link {
+    /// Creates a new `Link`.
+    #[inline]
+    pub const fn new() -> Link {
+        Link {
+            parent: Cell::new(None),
+            rank: Cell::new(0),
+        }
+    }
+
+    /// Checks whether the object is the representative of its set, which is
+    /// the case until it is merged into another set.
+    #[inline]
+    pub fn is_root(&self) -> bool {
+        self.parent.get().is_none()
+    }
+}
+
+impl DefaultLinkOps for Link {
+    type Ops = LinkOps;
+
+    const NEW: Self::Ops = LinkOps;
+}
+
+// Provide an implementation of Clone which simply initializes the new link as
+// a set of its own. This allows structs containing a link to derive Clone.
+impl Clone for Link {
+    #[inline]
+    fn clone(&self) -> Link {
+        Link::new()
+    }
+}
+
+// Same as above
+impl Default for Link {
+    #[inline]
+    fn default() -> Link {
+        Link::new()
+    }
+}
+
+// Provide an implementation of Debug so that structs containing a link can
+// still derive Debug.
+impl fmt::Debug for Link {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // There isn't anything sensible to print here except whether the link
+        // is the root of its set.
+        if self.is_root() {
+            write!(f, "root")
+        } else {
+            write!(f, "child")
+        }
+    }
+}
+
+// =============================================================================
+// LinkOps
+// =============================================================================
+
+/// Default `LinkOps` implementation for `UnionFind`.
+///
+/// A `UnionFind` never takes ownership of its objects, so the links are
+/// always available.
+#[derive(Clone, Copy, Default)]
+pub struct LinkOps;
+
+unsafe impl link_ops::LinkOps for LinkOps {
+    type LinkPtr = NonNull<Link>;
+
+    const LINK_SIZE: Option<usize> = Some(mem::size_of::<Link>());
+
+    #[inline]
+    unsafe fn acquire_link(&mut self, _ptr: Self::LinkPtr) -> bool {
+        true
+    }
+
+    #[inline]
+    unsafe fn release_link(&mut self, _ptr: Self::LinkPtr) {}
+}
+
+// =============================================================================
+// UnionFind
+// =============================================================================
+
+/// An intrusive disjoint-set forest, with union by rank and path compression.
+///
+/// The sets are stored entirely in the links of the objects: each object
+/// points to a parent in its set, up to the representative of the set. The
+/// `UnionFind` only holds the adapter, and its operations take references to
+/// the objects, so it doesn't own them and has no notion of which objects it
+/// contains. This makes it cheap to track connectivity between objects that
+/// already live in other intrusive collections.
+///
+/// `find` and `same_set` take amortized time which is almost constant.
+///
+/// # Examples
+///
+/// ```
+/// use intrusive_collections::intrusive_adapter;
+/// use intrusive_collections::union_find::{Link, UnionFind};
+///
+/// struct Node {
+///     link: Link,
+///     id: u32,
+/// }
+/// intrusive_adapter!(NodeAdapter<'a> = &'a Node: Node { link: Link });
+///
+/// let nodes: Vec<Node> = (0..4).map(|id| Node { link: Link::new(), id }).collect();
+/// let sets = UnionFind::new(NodeAdapter::new());
+/// unsafe {
+///     assert!(sets.union(&nodes[0], &nodes[1]));
+///     assert!(sets.union(&nodes[2], &nodes[1]));
+///     assert!(!sets.union(&nodes[0], &nodes[2]));
+/// }
+/// assert!(sets.same_set(&nodes[0], &nodes[2]));
+/// assert!(!sets.same_set(&nodes[0], &nodes[3]));
+/// assert_eq!(sets.find(&nodes[2]).id, sets.find(&nodes[0]).id);
+/// ```
+pub struct UnionFind<A: Adapter<LinkOps = LinkOps>> {
+    adapter: A,
+}
+
+impl<A: Adapter<LinkOps = LinkOps>> UnionFind<A> {
+    /// Creates a new `UnionFind`.
+    #[inline]
+    pub const fn new(adapter: A) -> UnionFind<A> {
+        UnionFind { adapter }
+    }
+
+    /// Returns a reference to the adapter of the `UnionFind`.
+    #[inline]
+    pub fn adapter(&self) -> &A {
+        &self.adapter
+    }
+
+    // Returns the root of the set of `link`, pointing all the links on the
+    // way directly to it.
+    #[inline]
+    unsafe fn find_root(&self, link: NonNull<Link>) -> NonNull<Link> {
+        let mut root = link;
+        while let Some(parent) = root.as_ref().parent.get() {
+            root = parent;
+        }
+        let mut current = link;
+        while let Some(parent) = current.as_ref().parent.get() {
+            if parent != root {
+                current.as_ref().parent.set(Some(root));
+            }
+            current = parent;
+        }
+        root
+    }
+
+    /// Returns the representative of the set containing `val`.
+    ///
+    /// Two objects are in the same set if and only if they have the same
+    /// representative, which stays the same until the set is merged with
+    /// another one.
+    #[inline]
+    pub fn find<'a>(
+        &self,
+        val: &'a <A::PointerOps as PointerOps>::Value,
+    ) -> &'a <A::PointerOps as PointerOps>::Value {
+        // The other objects of the set outlive `val`, per the contract of
+        // `union`.
+        unsafe {
+            let root = self.find_root(self.adapter.get_link(val));
+            &*self.adapter.get_value(root)
+        }
+    }
+
+    /// Returns `true` if `a` and `b` are in the same set.
+    #[inline]
+    pub fn same_set(
+        &self,
+        a: &<A::PointerOps as PointerOps>::Value,
+        b: &<A::PointerOps as PointerOps>::Value,
+    ) -> bool {
+        unsafe {
+            self.find_root(self.adapter.get_link(a)) == self.find_root(self.adapter.get_link(b))
+        }
+    }
+
+    /// Merges the sets containing `a` and `b`.
+    ///
+    /// Returns `false` if they were already in the same set.
+    ///
+    /// # Safety
+    ///
+    /// The objects of the merged set point to each other, so none of them
+    /// may be moved or dropped while any of the others is still used with
+    /// this `UnionFind`. All the objects in the set must also be used with
+    /// the same adapter.
+    #[inline]
+    pub unsafe fn union(
+        &self,
+        a: &<A::PointerOps as PointerOps>::Value,
+        b: &<A::PointerOps as PointerOps>::Value,
+    ) -> bool {
+        let a = self.find_root(self.adapter.get_link(a));
+        let b = self.find_root(self.adapter.get_link(b));
+        if a == b {
+            return false;
+        }
+        let (rank_a, rank_b) = (a.as_ref().rank.get(), b.as_ref().rank.get());
+        if rank_a < rank_b {
+            a.as_ref().parent.set(Some(b));
+        } else {
+            b.as_ref().parent.set(Some(a));
+            if rank_a == rank_b {
+                a.as_ref().rank.set(rank_a + 1);
+            }
+        }
+        true
+    }
+}
+
+impl<A: Adapter<LinkOps = LinkOps> + Default> Default for UnionFind<A> {
+    fn default() -> UnionFind<A> {
+        UnionFind::new(A::default())
+    }
+}
+
+impl<A: Adapter<LinkOps = LinkOps>> fmt::Debug for UnionFind<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UnionFind").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Link, UnionFind};
+    use std::vec::Vec;
+
+    struct Obj {
+        link: Link,
+        value: u32,
+    }
+    intrusive_adapter!(ObjAdapter<'a> = &'a Obj: Obj { link: Link });
+
+    fn make_objs(n: u32) -> Vec<Obj> {
+        (0..n)
+            .map(|value| Obj {
+                link: Link::new(),
+                value,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_union_find() {
+        let objs = make_objs(6);
+        let sets = UnionFind::new(ObjAdapter::new());
+        for obj in &objs {
+            assert_eq!(sets.find(obj).value, obj.value);
+        }
+        unsafe {
+            assert!(sets.union(&objs[0], &objs[1]));
+            assert!(sets.union(&objs[2], &objs[3]));
+            assert!(sets.union(&objs[3], &objs[4]));
+            assert!(!sets.union(&objs[4], &objs[2]));
+        }
+        assert!(sets.same_set(&objs[0], &objs[1]));
+        assert!(sets.same_set(&objs[2], &objs[4]));
+        assert!(!sets.same_set(&objs[1], &objs[2]));
+        assert!(!sets.same_set(&objs[5], &objs[0]));
+
+        unsafe {
+            assert!(sets.union(&objs[1], &objs[4]));
+        }
+        let root = sets.find(&objs[0]).value;
+        assert!(objs[..5].iter().all(|x| sets.find(x).value == root));
+        assert_eq!(sets.find(&objs[5]).value, 5);
+        assert_eq!(objs.iter().filter(|x| x.link.is_root()).count(), 2);
+    }
+
+    #[test]
+    fn test_path_compression() {
+        let objs = make_objs(8);
+        let sets = UnionFind::new(ObjAdapter::new());
+        // Merging sets of equal size builds a tree of depth 3.
+        for &step in &[1, 2, 4] {
+            for i in (0..8).step_by(step * 2) {
+                unsafe { assert!(sets.union(&objs[i], &objs[i + step])) };
+            }
+        }
+        let root = sets.find(&objs[7]);
+        assert_eq!(root.value, 0);
+        assert_eq!(root.link.rank.get(), 3);
+        // After the `find`, the objects on its path point to the root.
+        for &i in &[7, 6, 4] {
+            let parent = objs[i].link.parent.get().unwrap();
+            assert_eq!(parent, (&root.link).into());
+        }
+    }
+}
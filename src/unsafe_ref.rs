@@ -7,8 +7,17 @@
 
 #[cfg(feature = "alloc")]
 use crate::alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use crate::{Adapter, LinkStateOps, PointerOps};
+use core::any::Any;
 use core::borrow::{Borrow, BorrowMut};
 use core::fmt;
+use core::hash::{Hash, Hasher};
+#[cfg(feature = "nightly")]
+use core::marker::Unsize;
+use core::mem;
+#[cfg(feature = "nightly")]
+use core::ops::CoerceUnsized;
 use core::ops::{Deref, DerefMut};
 use core::ptr::NonNull;
 
@@ -25,6 +34,13 @@ use core::ptr::NonNull;
 /// You must guarantee that an object managed by an `UnsafeRef` is not
 /// moved, dropped or accessed through a mutable reference as long as at least
 /// one `UnsafeRef` is pointing to it.
+///
+/// Unlike `Rc`, two `UnsafeRef`s are equal if they point to the same object,
+/// and their hash is that of the address, so that they can be used as object
+/// handles in a `HashSet`. The objects are not compared, and `T` doesn't have
+/// to implement `PartialEq` or `Hash`. Note that a set of `UnsafeRef<T>`
+/// therefore can't be searched with a `&T` through the `Borrow<T>`
+/// implementation.
 pub struct UnsafeRef<T: ?Sized> {
     ptr: NonNull<T>,
 }
@@ -37,16 +53,98 @@ impl<T: ?Sized> UnsafeRef<T> {
     /// You must ensure that the `UnsafeRef` guarantees are upheld.
     #[inline]
     pub unsafe fn from_raw(val: *const T) -> UnsafeRef<T> {
+        #[cfg(all(feature = "debug_refcount", debug_assertions))]
+        refcount::acquire(val);
         UnsafeRef {
             ptr: NonNull::new_unchecked(val as *mut _),
         }
     }
 
+    /// Creates an `UnsafeRef` from a static reference.
+    ///
+    /// This is safe since a `'static` object is never moved, dropped or
+    /// accessed through a mutable reference.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use intrusive_collections::{intrusive_adapter, LinkedList, LinkedListAtomicLink, UnsafeRef};
+    ///
+    /// struct Driver {
+    ///     link: LinkedListAtomicLink,
+    ///     name: &'static str,
+    /// }
+    /// intrusive_adapter!(DriverAdapter = UnsafeRef<Driver>: Driver { link: LinkedListAtomicLink });
+    ///
+    /// static UART: Driver = Driver { link: LinkedListAtomicLink::new(), name: "uart" };
+    ///
+    /// let mut drivers = LinkedList::new(DriverAdapter::new());
+    /// drivers.push_back(UnsafeRef::from_static(&UART));
+    /// assert_eq!(drivers.front().get().unwrap().name, "uart");
+    /// drivers.clear();
+    /// ```
+    #[inline]
+    pub fn from_static(val: &'static T) -> UnsafeRef<T> {
+        unsafe { UnsafeRef::from_raw(val) }
+    }
+
     /// Converts an `UnsafeRef` into a raw pointer
     #[inline]
     pub fn into_raw(ptr: Self) -> *mut T {
+        let ptr = mem::ManuallyDrop::new(ptr);
+        #[cfg(all(feature = "debug_refcount", debug_assertions))]
+        refcount::release(ptr.ptr.as_ptr());
         ptr.ptr.as_ptr()
     }
+
+    /// Returns a raw pointer to the object, without consuming the `UnsafeRef`.
+    #[inline]
+    pub fn as_ptr(this: &Self) -> *const T {
+        this.ptr.as_ptr()
+    }
+
+    /// Converts an `UnsafeRef` into one pointing to the reference returned by
+    /// `f`, which is either the object itself or a part of it.
+    ///
+    /// This is mostly useful to coerce an `UnsafeRef` to a trait object on
+    /// stable Rust, which the `nightly` feature allows to do implicitly:
+    ///
+    /// ```
+    /// use intrusive_collections::UnsafeRef;
+    /// use std::fmt::Debug;
+    ///
+    /// let r = UnsafeRef::from_box(Box::new(1));
+    /// let r: UnsafeRef<dyn Debug> = UnsafeRef::map(r, |x| x as &dyn Debug);
+    /// assert_eq!(format!("{:?}", r), "1");
+    /// # unsafe { drop(UnsafeRef::into_box(r)) };
+    /// ```
+    #[inline]
+    pub fn map<U: ?Sized>(this: Self, f: impl FnOnce(&T) -> &U) -> UnsafeRef<U> {
+        let ptr: *const U = f(&this);
+        UnsafeRef::into_raw(this);
+        unsafe { UnsafeRef::from_raw(ptr) }
+    }
+
+    /// Returns `true` if the two `UnsafeRef`s point to the same object.
+    ///
+    /// Only the addresses are compared: the metadata of pointers to unsized
+    /// types is ignored.
+    #[inline]
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.ptr.as_ptr() as *const u8 == other.ptr.as_ptr() as *const u8
+    }
+
+    /// Converts an `UnsafeRef` into an `UnsafeMut` pointing to the same
+    /// object.
+    ///
+    /// # Safety
+    ///
+    /// You must ensure that this is the only `UnsafeRef` managing this
+    /// object, and that the `UnsafeMut` guarantees are upheld from now on.
+    #[inline]
+    pub unsafe fn assume_unique(ptr: Self) -> UnsafeMut<T> {
+        UnsafeMut::from_raw(UnsafeRef::into_raw(ptr))
+    }
 }
 
 #[cfg(feature = "alloc")]
@@ -54,6 +152,8 @@ impl<T: ?Sized> UnsafeRef<T> {
     /// Creates an `UnsafeRef` from a `Box`
     #[inline]
     pub fn from_box(val: Box<T>) -> UnsafeRef<T> {
+        #[cfg(all(feature = "debug_refcount", debug_assertions))]
+        refcount::register(&*val);
         unsafe { UnsafeRef::from_raw(Box::into_raw(val)) }
     }
 
@@ -65,19 +165,87 @@ impl<T: ?Sized> UnsafeRef<T> {
     /// object and that it is not currently a member of any intrusive
     /// collections. This operation is only valid if the `UnsafeRef` was
     /// created using `UnsafeRef::from_box`.
+    ///
+    /// With the `debug_refcount` feature, debug builds panic if other
+    /// `UnsafeRef`s to the object still exist or if the object has already
+    /// been converted back into a `Box`.
     #[inline]
     pub unsafe fn into_box(ptr: Self) -> Box<T> {
+        #[cfg(all(feature = "debug_refcount", debug_assertions))]
+        refcount::free(ptr.ptr.as_ptr(), "UnsafeRef");
         Box::from_raw(UnsafeRef::into_raw(ptr))
     }
+
+    /// Converts an `UnsafeRef` into a `Box`, unless the object is still
+    /// linked into a collection through the link used by `adapter`.
+    ///
+    /// If the link is linked, the `UnsafeRef` is returned as an error. Only
+    /// the link used by `adapter` is checked, so an object with several links
+    /// must not be a member of a collection through any of the others.
+    ///
+    /// # Safety
+    ///
+    /// You must ensure that this is the only `UnsafeRef` managing this
+    /// object. This operation is only valid if the `UnsafeRef` was created
+    /// using `UnsafeRef::from_box`.
+    #[inline]
+    pub unsafe fn try_into_box<A>(ptr: Self, adapter: &A) -> Result<Box<T>, Self>
+    where
+        A: Adapter,
+        A::PointerOps: PointerOps<Value = T>,
+        A::LinkOps: LinkStateOps,
+    {
+        if crate::adapter::value_is_linked(adapter, UnsafeRef::as_ptr(&ptr)) {
+            Err(ptr)
+        } else {
+            Ok(UnsafeRef::into_box(ptr))
+        }
+    }
+}
+
+macro_rules! impl_downcast {
+    ($($any:tt)*) => {
+        impl UnsafeRef<$($any)*> {
+            /// Attempts to downcast the `UnsafeRef` to a concrete type.
+            ///
+            /// The `UnsafeRef` is returned unchanged if the object isn't a `T`.
+            #[inline]
+            pub fn downcast<T: Any>(self) -> Result<UnsafeRef<T>, Self> {
+                if (*self).is::<T>() {
+                    let ptr = UnsafeRef::into_raw(self) as *const T;
+                    Ok(unsafe { UnsafeRef::from_raw(ptr) })
+                } else {
+                    Err(self)
+                }
+            }
+        }
+    };
 }
 
+impl_downcast!(dyn Any);
+impl_downcast!(dyn Any + Send);
+impl_downcast!(dyn Any + Send + Sync);
+
+#[cfg(feature = "nightly")]
+impl<T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<UnsafeRef<U>> for UnsafeRef<T> {}
+
 impl<T: ?Sized> Clone for UnsafeRef<T> {
     #[inline]
     fn clone(&self) -> UnsafeRef<T> {
+        #[cfg(all(feature = "debug_refcount", debug_assertions))]
+        refcount::acquire(self.ptr.as_ptr());
         UnsafeRef { ptr: self.ptr }
     }
 }
 
+#[cfg(all(feature = "debug_refcount", debug_assertions))]
+impl<T: ?Sized> Drop for UnsafeRef<T> {
+    #[inline]
+    fn drop(&mut self) {
+        refcount::release(self.ptr.as_ptr());
+    }
+}
+
 impl<T: ?Sized> Deref for UnsafeRef<T> {
     type Target = T;
 
@@ -101,6 +269,22 @@ impl<T: ?Sized> Borrow<T> for UnsafeRef<T> {
     }
 }
 
+impl<T: ?Sized> PartialEq for UnsafeRef<T> {
+    #[inline]
+    fn eq(&self, other: &UnsafeRef<T>) -> bool {
+        UnsafeRef::ptr_eq(self, other)
+    }
+}
+
+impl<T: ?Sized> Eq for UnsafeRef<T> {}
+
+impl<T: ?Sized> Hash for UnsafeRef<T> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.ptr.as_ptr() as *const u8).hash(state);
+    }
+}
+
 impl<T: fmt::Debug + ?Sized> fmt::Debug for UnsafeRef<T> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -135,6 +319,8 @@ impl<T: ?Sized> UnsafeMut<T> {
     /// You must ensure that the `UnsafeMut` guarantees are upheld.
     #[inline]
     pub unsafe fn from_raw(val: *mut T) -> UnsafeMut<T> {
+        #[cfg(all(feature = "debug_refcount", debug_assertions))]
+        refcount::acquire(val);
         UnsafeMut {
             ptr: NonNull::new_unchecked(val),
         }
@@ -143,8 +329,24 @@ impl<T: ?Sized> UnsafeMut<T> {
     /// Converts an `UnsafeMut` into a raw pointer
     #[inline]
     pub fn into_raw(ptr: Self) -> *mut T {
+        let ptr = mem::ManuallyDrop::new(ptr);
+        #[cfg(all(feature = "debug_refcount", debug_assertions))]
+        refcount::release(ptr.ptr.as_ptr());
         ptr.ptr.as_ptr()
     }
+
+    /// Returns a raw pointer to the object, without consuming the `UnsafeMut`.
+    #[inline]
+    pub fn as_ptr(this: &Self) -> *mut T {
+        this.ptr.as_ptr()
+    }
+
+    /// Converts an `UnsafeMut` into an `UnsafeRef` pointing to the same
+    /// object, giving up exclusive access to it.
+    #[inline]
+    pub fn downgrade(ptr: Self) -> UnsafeRef<T> {
+        unsafe { UnsafeRef::from_raw(UnsafeMut::into_raw(ptr)) }
+    }
 }
 
 #[cfg(feature = "alloc")]
@@ -152,6 +354,8 @@ impl<T: ?Sized> UnsafeMut<T> {
     /// Creates an `UnsafeMut` from a `Box`
     #[inline]
     pub fn from_box(val: Box<T>) -> UnsafeMut<T> {
+        #[cfg(all(feature = "debug_refcount", debug_assertions))]
+        refcount::register(&*val);
         unsafe { UnsafeMut::from_raw(Box::into_raw(val)) }
     }
 
@@ -163,8 +367,14 @@ impl<T: ?Sized> UnsafeMut<T> {
     /// object and that it is not currently a member of an intrusive
     /// collection. This operation is only valid if the `UnsafeMut` was
     /// created using `UnsafeMut::from_box`.
+    ///
+    /// With the `debug_refcount` feature, debug builds panic if other
+    /// `UnsafeMut`s to the object still exist or if the object has already
+    /// been converted back into a `Box`.
     #[inline]
     pub unsafe fn into_box(ptr: Self) -> Box<T> {
+        #[cfg(all(feature = "debug_refcount", debug_assertions))]
+        refcount::free(ptr.ptr.as_ptr(), "UnsafeMut");
         Box::from_raw(UnsafeMut::into_raw(ptr))
     }
 }
@@ -219,6 +429,308 @@ impl<T: fmt::Debug + ?Sized> fmt::Debug for UnsafeMut<T> {
     }
 }
 
+#[cfg(all(feature = "debug_refcount", debug_assertions))]
+impl<T: ?Sized> Drop for UnsafeMut<T> {
+    #[inline]
+    fn drop(&mut self) {
+        refcount::release(self.ptr.as_ptr());
+    }
+}
+
 unsafe impl<T: ?Sized + Send> Send for UnsafeMut<T> {}
 
 unsafe impl<T: ?Sized + Sync> Sync for UnsafeMut<T> {}
+
+// =============================================================================
+// Debug reference counting
+// =============================================================================
+
+// Side table tracking the objects managed by `UnsafeRef` and `UnsafeMut`,
+// enabled by the `debug_refcount` feature in debug builds. Only objects
+// created with `from_box` are tracked, and only live `UnsafeRef`s and
+// `UnsafeMut`s count as references, not raw pointers. This is what makes
+// it work with collections, which keep their elements as raw pointers and may
+// leak them with `fast_clear`. Entries are kept after the count drops to zero
+// so that a raw pointer can be converted back, and after the object is freed
+// to catch double frees, until the address is reused by `from_box`.
+#[cfg(all(feature = "debug_refcount", debug_assertions))]
+mod refcount {
+    use crate::alloc::collections::BTreeMap;
+    use core::cell::UnsafeCell;
+    use core::hint;
+    use core::mem;
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    enum State {
+        Live(usize),
+        Freed,
+    }
+
+    struct Table {
+        locked: AtomicBool,
+        objects: UnsafeCell<Option<BTreeMap<usize, State>>>,
+    }
+
+    unsafe impl Sync for Table {}
+
+    static TABLE: Table = Table {
+        locked: AtomicBool::new(false),
+        objects: UnsafeCell::new(None),
+    };
+
+    fn with_table<R>(f: impl FnOnce(&mut BTreeMap<usize, State>) -> R) -> R {
+        while TABLE
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            hint::spin_loop();
+        }
+        let result = f(unsafe { (*TABLE.objects.get()).get_or_insert_with(BTreeMap::new) });
+        TABLE.locked.store(false, Ordering::Release);
+        result
+    }
+
+    fn key<T: ?Sized>(val: *const T) -> usize {
+        val as *const u8 as usize
+    }
+
+    // Zero-sized objects don't have a unique address, so they aren't tracked.
+    // The other functions only look at the address, so that they never touch
+    // an object which has already been freed.
+    pub(super) fn register<T: ?Sized>(val: &T) {
+        if mem::size_of_val(val) != 0 {
+            with_table(|objects| objects.insert(key(val), State::Live(0)));
+        }
+    }
+
+    pub(super) fn acquire<T: ?Sized>(val: *const T) {
+        with_table(|objects| {
+            if let Some(State::Live(count)) = objects.get_mut(&key(val)) {
+                *count += 1;
+            }
+        });
+    }
+
+    pub(super) fn release<T: ?Sized>(val: *const T) {
+        with_table(|objects| {
+            if let Some(State::Live(count)) = objects.get_mut(&key(val)) {
+                *count -= 1;
+            }
+        });
+    }
+
+    pub(super) fn free<T: ?Sized>(val: *const T, name: &str) {
+        let key = key(val);
+        let previous = with_table(|objects| match objects.get_mut(&key) {
+            Some(State::Live(1)) => {
+                objects.insert(key, State::Freed);
+                None
+            }
+            Some(State::Live(count)) => Some(*count),
+            Some(State::Freed) => Some(0),
+            None => None,
+        });
+        match previous {
+            None => {}
+            Some(0) => panic!(
+                "{}::into_box called on an object which was already freed",
+                name
+            ),
+            Some(count) => panic!(
+                "{}::into_box called while {} other references to the object exist",
+                name,
+                count - 1
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{UnsafeMut, UnsafeRef};
+    use std::boxed::Box;
+
+    #[test]
+    fn test_conversions() {
+        let mut m = UnsafeMut::from_box(Box::new(1));
+        *m += 1;
+        let ptr = UnsafeMut::as_ptr(&m);
+        let r = UnsafeMut::downgrade(m);
+        assert_eq!(UnsafeRef::as_ptr(&r), ptr as *const i32);
+        assert_eq!(*r, 2);
+        let mut m = unsafe { UnsafeRef::assume_unique(r) };
+        *m += 1;
+        assert_eq!(UnsafeMut::as_ptr(&m), ptr);
+        assert_eq!(*unsafe { UnsafeMut::into_box(m) }, 3);
+    }
+
+    #[test]
+    fn test_map_downcast() {
+        use core::any::Any;
+
+        let r = UnsafeRef::from_box(Box::new((1u32, 2u8)));
+        assert_eq!(*UnsafeRef::map(r.clone(), |x| &x.1), 2);
+        let any: UnsafeRef<dyn Any> = UnsafeRef::map(r, |x| x as &dyn Any);
+        let any = any.downcast::<u8>().unwrap_err();
+        let r = any.downcast::<(u32, u8)>().unwrap();
+        assert_eq!(*r, (1, 2));
+        unsafe { drop(UnsafeRef::into_box(r)) };
+    }
+
+    #[cfg(feature = "nightly")]
+    #[test]
+    fn test_coerce_unsized() {
+        use core::any::Any;
+
+        let r: UnsafeRef<dyn Any + Send> = UnsafeRef::from_box(Box::new(1i32));
+        let r = r.downcast::<i32>().unwrap();
+        assert_eq!(*r, 1);
+        unsafe { drop(UnsafeRef::into_box(r)) };
+    }
+
+    #[test]
+    fn test_from_static() {
+        static VALUE: i32 = 1;
+        let r = UnsafeRef::from_static(&VALUE);
+        assert_eq!(UnsafeRef::as_ptr(&r), &VALUE as *const i32);
+        assert!(r == UnsafeRef::from_static(&VALUE));
+        assert_eq!(*r, 1);
+    }
+
+    #[test]
+    fn test_address_eq() {
+        use std::collections::HashSet;
+
+        // Equal values in different objects are different handles.
+        let a = UnsafeRef::from_box(Box::new(1));
+        let b = UnsafeRef::from_box(Box::new(1));
+        assert!(UnsafeRef::ptr_eq(&a, &a.clone()));
+        assert!(!UnsafeRef::ptr_eq(&a, &b));
+        assert!(a == a.clone());
+        assert!(a != b);
+
+        let mut set = HashSet::new();
+        assert!(set.insert(a.clone()));
+        assert!(set.insert(b.clone()));
+        assert!(!set.insert(a.clone()));
+        assert_eq!(set.len(), 2);
+        set.clear();
+
+        // The metadata of unsized pointers is ignored.
+        let s = UnsafeRef::from_box(Box::new([1, 2]) as Box<[i32]>);
+        assert!(unsafe { UnsafeRef::from_raw(&s[..1] as *const [i32]) } == s);
+
+        unsafe {
+            drop(UnsafeRef::into_box(a));
+            drop(UnsafeRef::into_box(b));
+            drop(UnsafeRef::into_box(s));
+        }
+    }
+
+    #[test]
+    fn test_try_into_box() {
+        use crate::{intrusive_adapter, LinkedList, LinkedListLink};
+
+        struct Obj {
+            link: LinkedListLink,
+            value: u32,
+        }
+        intrusive_adapter!(ObjAdapter = UnsafeRef<Obj>: Obj { link: LinkedListLink });
+
+        let a = UnsafeRef::from_box(Box::new(Obj {
+            link: LinkedListLink::new(),
+            value: 1,
+        }));
+        let mut l = LinkedList::new(ObjAdapter::new());
+        l.push_back(a.clone());
+        let a = match unsafe { UnsafeRef::try_into_box(a, &ObjAdapter::new()) } {
+            Ok(_) => panic!("converted a linked object into a Box"),
+            Err(a) => a,
+        };
+        assert!(l.pop_front().is_some());
+        let a = unsafe { UnsafeRef::try_into_box(a, &ObjAdapter::new()) };
+        assert_eq!(a.ok().unwrap().value, 1);
+    }
+
+    #[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+    #[test]
+    fn test_try_into_box_leaves_link_unchanged() {
+        use crate::linked_list::AtomicLink;
+        use crate::{intrusive_adapter, GenerationLink, LinkedListLink};
+
+        struct Obj {
+            link: GenerationLink<LinkedListLink>,
+        }
+        intrusive_adapter!(ObjAdapter = UnsafeRef<Obj>: Obj { link: GenerationLink<LinkedListLink> });
+        let a = UnsafeRef::from_box(Box::new(Obj {
+            link: GenerationLink::new(LinkedListLink::new()),
+        }));
+        let generation = a.link.generation();
+        let a = unsafe { UnsafeRef::try_into_box(a, &ObjAdapter::new()) };
+        assert_eq!(a.ok().unwrap().link.generation(), generation);
+
+        struct AtomicObj {
+            link: AtomicLink,
+        }
+        intrusive_adapter!(AtomicObjAdapter = UnsafeRef<AtomicObj>: AtomicObj { link: AtomicLink });
+        let a = UnsafeRef::from_box(Box::new(AtomicObj {
+            link: AtomicLink::new(),
+        }));
+        assert!(a.link.try_acquire_for_insertion());
+        let a = match unsafe { UnsafeRef::try_into_box(a, &AtomicObjAdapter::new()) } {
+            Ok(_) => panic!("converted a claimed object into a Box"),
+            Err(a) => a,
+        };
+        assert!(a.link.is_linked());
+        assert!(!a.link.try_acquire_for_insertion());
+        unsafe {
+            a.link.force_unlink();
+            drop(UnsafeRef::into_box(a));
+        }
+    }
+
+    #[cfg(all(feature = "debug_refcount", debug_assertions))]
+    #[test]
+    fn test_into_box() {
+        let a = UnsafeRef::from_box(Box::new(1));
+        let b = a.clone();
+        drop(b);
+        let raw = UnsafeRef::into_raw(a);
+        let a = unsafe { UnsafeRef::from_raw(raw) };
+        assert_eq!(*unsafe { UnsafeRef::into_box(a) }, 1);
+    }
+
+    #[cfg(all(feature = "debug_refcount", debug_assertions))]
+    #[test]
+    #[should_panic(expected = "1 other references")]
+    fn test_into_box_shared() {
+        let a = UnsafeRef::from_box(Box::new(1));
+        let _b = a.clone();
+        unsafe { UnsafeRef::into_box(a) };
+    }
+
+    #[cfg(all(feature = "debug_refcount", debug_assertions))]
+    #[test]
+    #[should_panic(expected = "1 other references")]
+    fn test_into_box_from_raw_twice() {
+        let raw = UnsafeMut::into_raw(UnsafeMut::from_box(Box::new(1)));
+        unsafe {
+            let _a = UnsafeMut::from_raw(raw);
+            UnsafeMut::into_box(UnsafeMut::from_raw(raw));
+        }
+    }
+
+    #[cfg(all(feature = "debug_refcount", debug_assertions))]
+    #[test]
+    #[should_panic(expected = "already freed")]
+    fn test_into_box_twice() {
+        let a = UnsafeMut::from_box(Box::new(1));
+        let raw = UnsafeMut::into_raw(a);
+        unsafe {
+            let a = UnsafeMut::into_box(UnsafeMut::from_raw(raw));
+            let _b = UnsafeMut::into_box(UnsafeMut::from_raw(raw));
+            drop(a);
+        }
+    }
+}
@@ -0,0 +1,462 @@
+// Copyright 2020 Amari Robinson
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Intrusive doubly-linked list holding weak references.
+
+use core::fmt;
+
+use crate::link_ops::{self, LinkOps};
+use crate::linked_list::{link_between, remove, LinkedListOps};
+use crate::pointer_ops::{DowngradePointerOps, PointerOps};
+use crate::Adapter;
+
+// =============================================================================
+// WeakList
+// =============================================================================
+
+/// An intrusive doubly-linked list which only holds weak references to its
+/// elements.
+///
+/// Elements are inserted from a strong pointer such as an `Rc` or an `Arc`,
+/// but the list only keeps a weak pointer to them, so it does not keep them
+/// alive. Elements which have been dropped are removed lazily, whenever a
+/// traversal of the list comes across them. This is useful for observer lists
+/// and caches, where membership should not imply ownership.
+///
+/// Since elements may have been dropped, the list never gives out references
+/// to them. All accessors upgrade the weak pointer and return a strong pointer
+/// instead.
+///
+/// The list uses the same links and link operations as `LinkedList`. The
+/// memory of a dropped element stays allocated until the list removes it, so
+/// its link remains valid. `Adapter::on_insert` and `Adapter::on_remove` are
+/// not called by this collection.
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+/// use intrusive_collections::intrusive_adapter;
+/// use intrusive_collections::weak_list::WeakList;
+/// use intrusive_collections::LinkedListLink;
+///
+/// struct Observer {
+///     link: LinkedListLink,
+///     id: u32,
+/// }
+/// intrusive_adapter!(ObserverAdapter = Rc<Observer>: Observer { link: LinkedListLink });
+///
+/// let mut observers = WeakList::new(ObserverAdapter::new());
+/// let a = Rc::new(Observer { link: LinkedListLink::new(), id: 1 });
+/// let b = Rc::new(Observer { link: LinkedListLink::new(), id: 2 });
+/// observers.push_back(&a);
+/// observers.push_back(&b);
+///
+/// drop(a);
+/// assert_eq!(observers.iter().map(|o| o.id).collect::<Vec<_>>(), [2]);
+/// ```
+pub struct WeakList<A: Adapter>
+where
+    A::LinkOps: LinkedListOps,
+    A::PointerOps: DowngradePointerOps,
+{
+    head: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+    tail: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+    adapter: A,
+}
+
+impl<A: Adapter> WeakList<A>
+where
+    A::LinkOps: LinkedListOps,
+    A::PointerOps: DowngradePointerOps,
+{
+    /// Creates an empty `WeakList`.
+    #[inline]
+    pub const fn new(adapter: A) -> WeakList<A> {
+        WeakList {
+            head: None,
+            tail: None,
+            adapter,
+        }
+    }
+
+    /// Returns `true` if the `WeakList` is empty.
+    ///
+    /// Elements which have been dropped but not yet removed from the list are
+    /// counted, so this may return `false` even if all elements are dead.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    #[inline]
+    fn node_from_pointer(
+        &mut self,
+        ptr: &<A::PointerOps as PointerOps>::Pointer,
+    ) -> <A::LinkOps as link_ops::LinkOps>::LinkPtr {
+        unsafe {
+            let weak = self.adapter.pointer_ops().downgrade(ptr);
+            let raw = self.adapter.pointer_ops().weak_into_raw(weak);
+            let link = self.adapter.get_link(raw);
+
+            if !self.adapter.link_ops_mut().acquire_link(link) {
+                // convert the node back into a weak pointer
+                self.adapter.pointer_ops().weak_from_raw(raw);
+
+                crate::adapter::already_linked(&self.adapter, None, raw, None);
+            }
+
+            link
+        }
+    }
+
+    // Unlinks an element and drops the weak pointer held by the list.
+    #[inline]
+    unsafe fn unlink(&mut self, link: <A::LinkOps as link_ops::LinkOps>::LinkPtr) {
+        if self.head == Some(link) {
+            self.head = self.adapter.link_ops().next(link);
+        }
+        if self.tail == Some(link) {
+            self.tail = self.adapter.link_ops().prev(link);
+        }
+        remove(self.adapter.link_ops_mut(), link);
+        let raw = self.adapter.get_value(link);
+        self.adapter.pointer_ops().weak_from_raw(raw);
+    }
+
+    #[inline]
+    unsafe fn upgrade(
+        &self,
+        link: <A::LinkOps as link_ops::LinkOps>::LinkPtr,
+    ) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        self.adapter
+            .pointer_ops()
+            .upgrade(self.adapter.get_value(link))
+    }
+
+    /// Inserts a weak reference to an object at the start of the `WeakList`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the object is already linked to a different intrusive
+    /// collection.
+    #[inline]
+    pub fn push_front(&mut self, ptr: &<A::PointerOps as PointerOps>::Pointer) {
+        let new = self.node_from_pointer(ptr);
+        unsafe {
+            link_between(self.adapter.link_ops_mut(), new, None, self.head);
+        }
+        if self.tail.is_none() {
+            self.tail = Some(new);
+        }
+        self.head = Some(new);
+    }
+
+    /// Inserts a weak reference to an object at the end of the `WeakList`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the object is already linked to a different intrusive
+    /// collection.
+    #[inline]
+    pub fn push_back(&mut self, ptr: &<A::PointerOps as PointerOps>::Pointer) {
+        let new = self.node_from_pointer(ptr);
+        unsafe {
+            link_between(self.adapter.link_ops_mut(), new, self.tail, None);
+        }
+        if self.head.is_none() {
+            self.head = Some(new);
+        }
+        self.tail = Some(new);
+    }
+
+    /// Returns a pointer to the first live element of the `WeakList`,
+    /// removing any dead elements before it.
+    #[inline]
+    pub fn front(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        while let Some(head) = self.head {
+            unsafe {
+                if let Some(ptr) = self.upgrade(head) {
+                    return Some(ptr);
+                }
+                self.unlink(head);
+            }
+        }
+        None
+    }
+
+    /// Removes the first live element of the `WeakList` and returns a pointer
+    /// to it, removing any dead elements before it.
+    #[inline]
+    pub fn pop_front(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        let ptr = self.front()?;
+        unsafe {
+            self.unlink(self.head.unwrap_unchecked());
+        }
+        Some(ptr)
+    }
+
+    /// Returns an iterator over the live elements of the `WeakList`.
+    ///
+    /// Dead elements are removed from the list as the iterator passes them.
+    #[inline]
+    pub fn iter(&mut self) -> Iter<'_, A> {
+        Iter {
+            next: self.head,
+            list: self,
+        }
+    }
+
+    /// Removes all dead elements from the `WeakList`.
+    #[inline]
+    pub fn prune(&mut self) {
+        self.retain(|_| true);
+    }
+
+    /// Retains only the live elements for which `f` returns `true`.
+    #[inline]
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&<A::PointerOps as PointerOps>::Pointer) -> bool,
+    {
+        let mut current = self.head;
+        while let Some(link) = current {
+            unsafe {
+                current = self.adapter.link_ops().next(link);
+                let keep = match self.upgrade(link) {
+                    Some(ptr) => f(&ptr),
+                    None => false,
+                };
+                if !keep {
+                    self.unlink(link);
+                }
+            }
+        }
+    }
+
+    /// Removes all elements from the `WeakList`.
+    #[inline]
+    pub fn clear(&mut self) {
+        let mut current = self.head;
+        self.head = None;
+        self.tail = None;
+        while let Some(link) = current {
+            unsafe {
+                current = self.adapter.link_ops().next(link);
+                self.adapter.link_ops_mut().release_link(link);
+                let raw = self.adapter.get_value(link);
+                self.adapter.pointer_ops().weak_from_raw(raw);
+            }
+        }
+    }
+}
+
+// Allow sending to another thread if the weak pointers can be sent
+unsafe impl<A: Adapter + Send> Send for WeakList<A>
+where
+    <A::PointerOps as DowngradePointerOps>::WeakPointer: Send,
+    A::LinkOps: LinkedListOps,
+    A::PointerOps: DowngradePointerOps,
+{
+}
+
+// Drop all weak pointers if the collection is dropped
+impl<A: Adapter> Drop for WeakList<A>
+where
+    A::LinkOps: LinkedListOps,
+    A::PointerOps: DowngradePointerOps,
+{
+    #[inline]
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<A: Adapter + Default> Default for WeakList<A>
+where
+    A::LinkOps: LinkedListOps,
+    A::PointerOps: DowngradePointerOps,
+{
+    fn default() -> WeakList<A> {
+        WeakList::new(A::default())
+    }
+}
+
+impl<A: Adapter> fmt::Debug for WeakList<A>
+where
+    A::LinkOps: LinkedListOps,
+    A::PointerOps: DowngradePointerOps,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WeakList").finish_non_exhaustive()
+    }
+}
+
+// =============================================================================
+// Iter
+// =============================================================================
+
+/// An iterator over the live elements of a `WeakList`.
+pub struct Iter<'a, A: Adapter>
+where
+    A::LinkOps: LinkedListOps,
+    A::PointerOps: DowngradePointerOps,
+{
+    list: &'a mut WeakList<A>,
+    next: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+}
+
+impl<'a, A: Adapter + 'a> Iterator for Iter<'a, A>
+where
+    A::LinkOps: LinkedListOps,
+    A::PointerOps: DowngradePointerOps,
+{
+    type Item = <A::PointerOps as PointerOps>::Pointer;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(link) = self.next {
+            unsafe {
+                self.next = self.list.adapter.link_ops().next(link);
+                if let Some(ptr) = self.list.upgrade(link) {
+                    return Some(ptr);
+                }
+                self.list.unlink(link);
+            }
+        }
+        None
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::WeakList;
+    use crate::{LinkedList, LinkedListAtomicLink, LinkedListLink};
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use std::thread;
+    use std::vec::Vec;
+
+    struct Obj {
+        link: LinkedListLink,
+        value: u32,
+    }
+    intrusive_adapter!(ObjAdapter = Rc<Obj>: Obj { link: LinkedListLink });
+
+    fn make_obj(value: u32) -> Rc<Obj> {
+        Rc::new(Obj {
+            link: LinkedListLink::new(),
+            value,
+        })
+    }
+
+    fn values(list: &mut WeakList<ObjAdapter>) -> Vec<u32> {
+        list.iter().map(|x| x.value).collect()
+    }
+
+    #[test]
+    fn test_weak() {
+        let a = make_obj(1);
+        let b = make_obj(2);
+        let c = make_obj(3);
+        let weak_a = Rc::downgrade(&a);
+
+        let mut list = WeakList::new(ObjAdapter::new());
+        assert!(list.is_empty());
+        list.push_back(&b);
+        list.push_front(&a);
+        list.push_back(&c);
+        assert_eq!(Rc::strong_count(&a), 1);
+        assert_eq!(Rc::weak_count(&a), 2);
+        assert!(a.link.is_linked());
+        assert_eq!(values(&mut list), [1, 2, 3]);
+
+        drop(a);
+        assert!(weak_a.upgrade().is_none());
+        assert_eq!(list.front().unwrap().value, 2);
+
+        drop(c);
+        list.prune();
+        assert_eq!(values(&mut list), [2]);
+        assert_eq!(list.pop_front().unwrap().value, 2);
+        assert!(!b.link.is_linked());
+        assert!(list.is_empty());
+        assert!(list.pop_front().is_none());
+
+        // The object can now be owned by a normal list.
+        let mut strong = LinkedList::new(ObjAdapter::new());
+        strong.push_back(b.clone());
+        assert_eq!(Rc::strong_count(&b), 2);
+    }
+
+    #[test]
+    fn test_retain() {
+        let objs = (0..6).map(make_obj).collect::<Vec<_>>();
+        let mut list = WeakList::new(ObjAdapter::new());
+        for obj in &objs {
+            list.push_back(obj);
+        }
+        list.retain(|x| x.value % 2 == 0);
+        assert_eq!(values(&mut list), [0, 2, 4]);
+        assert!(!objs[1].link.is_linked());
+        drop(objs);
+        assert!(list.front().is_none());
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_drop() {
+        let a = make_obj(1);
+        let b = make_obj(2);
+        {
+            let mut list = WeakList::new(ObjAdapter::new());
+            list.push_back(&a);
+            list.push_back(&b);
+            drop(a);
+        }
+        assert_eq!(Rc::weak_count(&b), 0);
+        assert!(!b.link.is_linked());
+    }
+
+    #[test]
+    #[should_panic(expected = "attempted to insert an object that is already linked")]
+    fn test_already_linked() {
+        let a = make_obj(1);
+        let mut list = WeakList::new(ObjAdapter::new());
+        list.push_back(&a);
+        list.push_back(&a);
+    }
+
+    #[test]
+    fn test_arc() {
+        struct SharedObj {
+            link: LinkedListAtomicLink,
+            value: u32,
+        }
+        intrusive_adapter!(SharedObjAdapter = Arc<SharedObj>: SharedObj { link: LinkedListAtomicLink });
+
+        let a = Arc::new(SharedObj {
+            link: LinkedListAtomicLink::new(),
+            value: 1,
+        });
+        let mut list = WeakList::new(SharedObjAdapter::new());
+        list.push_back(&a);
+        let list = thread::spawn(move || {
+            assert_eq!(list.front().unwrap().value, 1);
+            list
+        })
+        .join()
+        .unwrap();
+        drop(a);
+        let mut list = list;
+        assert!(list.front().is_none());
+    }
+}
@@ -13,13 +13,43 @@
 
 use core::cell::Cell;
 use core::fmt;
+use core::mem;
+use core::ops::ControlFlow;
+use core::pin::Pin;
 use core::ptr::NonNull;
-use core::sync::atomic::{AtomicUsize, Ordering};
 
-use crate::link_ops::{self, DefaultLinkOps};
-use crate::pointer_ops::PointerOps;
+#[cfg(feature = "ghost-cell")]
+use ghost_cell::GhostToken;
+
+use crate::adapter::TryPointer;
+#[cfg(feature = "alloc")]
+use crate::alloc::boxed::Box;
+use crate::collection::Collection;
+use crate::cursor_ops::{CursorMutOps, CursorOps, DoubleEndedCursorOps};
+#[cfg(feature = "dump")]
+use crate::dump;
+use crate::footprint::MemoryOverhead;
+#[cfg(feature = "ghost-cell")]
+use crate::ghost_adapter::{GhostAdapter, GhostIterMut};
+use crate::insert_error::InsertError;
+use crate::link_ops::{
+    self, check_link_value, wrapper_ptr, DefaultLinkOps, LinkWrapper, WrapperLinkOps,
+};
+#[cfg(feature = "alloc")]
+use crate::pointer_ops::DefaultPointerOps;
+use crate::pointer_ops::{PointerOps, TryPointerOps};
+#[cfg(feature = "poison")]
+use crate::poison::Poison;
+use crate::priority_adapter::PriorityAdapter;
 use crate::singly_linked_list::SinglyLinkedListOps;
-use crate::{Adapter, ExclusivePointerOps, TryExclusivePointerOps};
+#[cfg(feature = "stats")]
+use crate::stats::{Stats, StatsCounters};
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+use crate::sync::{AtomicUsize, Ordering};
+use crate::{
+    Adapter, CowPointerOps, ExclusivePinnedPointerOps, ExclusivePointerOps, PinnedPointerOps,
+    TryExclusivePointerOps,
+};
 // Necessary for Rust 1.56 compatability
 #[allow(unused_imports)]
 use crate::unchecked_option::UncheckedOptionExt;
@@ -29,6 +59,11 @@ use crate::unchecked_option::UncheckedOptionExt;
 // =============================================================================
 
 /// Link operations for `XorLinkedList`.
+///
+/// # Safety
+///
+/// Implementations must correctly maintain the links of the objects passed
+/// to them, since collections rely on these operations for memory safety.
 pub unsafe trait XorLinkedListOps: link_ops::LinkOps {
     /// Returns the "next" link pointer of `ptr`.
     ///
@@ -82,6 +117,87 @@ pub unsafe trait XorLinkedListOps: link_ops::LinkOps {
         old: Option<Self::LinkPtr>,
         new: Option<Self::LinkPtr>,
     );
+
+    /// Records the identifier of the `XorLinkedList` that `ptr` was inserted
+    /// into, for the `debug_owner` feature.
+    ///
+    /// Link types which don't store an owner can leave this unimplemented.
+    ///
+    /// # Safety
+    /// An implementation of `set_owner` must not panic.
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn set_owner(&mut self, ptr: Self::LinkPtr, owner: usize) {
+        let _ = (ptr, owner);
+    }
+
+    /// Returns the identifier recorded by `set_owner`, 0 if the link was
+    /// released since, or `None` if the link type doesn't store an owner.
+    ///
+    /// # Safety
+    /// An implementation of `owner` must not panic.
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn owner(&self, ptr: Self::LinkPtr) -> Option<usize> {
+        let _ = ptr;
+        None
+    }
+}
+
+unsafe impl<W: LinkWrapper, O: XorLinkedListOps<LinkPtr = NonNull<W::Link>>> XorLinkedListOps
+    for WrapperLinkOps<W, O>
+{
+    #[inline]
+    unsafe fn next(
+        &self,
+        ptr: Self::LinkPtr,
+        prev: Option<Self::LinkPtr>,
+    ) -> Option<Self::LinkPtr> {
+        wrapper_ptr(self.ops.next(ptr.cast(), prev.map(NonNull::cast)))
+    }
+
+    #[inline]
+    unsafe fn prev(
+        &self,
+        ptr: Self::LinkPtr,
+        next: Option<Self::LinkPtr>,
+    ) -> Option<Self::LinkPtr> {
+        wrapper_ptr(self.ops.prev(ptr.cast(), next.map(NonNull::cast)))
+    }
+
+    #[inline]
+    unsafe fn set(
+        &mut self,
+        ptr: Self::LinkPtr,
+        prev: Option<Self::LinkPtr>,
+        next: Option<Self::LinkPtr>,
+    ) {
+        self.ops
+            .set(ptr.cast(), prev.map(NonNull::cast), next.map(NonNull::cast));
+    }
+
+    #[inline]
+    unsafe fn replace_next_or_prev(
+        &mut self,
+        ptr: Self::LinkPtr,
+        old: Option<Self::LinkPtr>,
+        new: Option<Self::LinkPtr>,
+    ) {
+        self.ops
+            .replace_next_or_prev(ptr.cast(), old.map(NonNull::cast), new.map(NonNull::cast));
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn set_owner(&mut self, ptr: Self::LinkPtr, owner: usize) {
+        self.ops.set_owner(ptr.cast(), owner);
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn owner(&self, ptr: Self::LinkPtr) -> Option<usize> {
+        self.ops.owner(ptr.cast())
+    }
 }
 
 // =============================================================================
@@ -90,20 +206,34 @@ pub unsafe trait XorLinkedListOps: link_ops::LinkOps {
 
 /// Intrusive link that allows an object to be inserted into a
 /// `XorLinkedList`.
-#[repr(align(2))]
+///
+/// The link is `repr(C)` with the layout of `struct ic_xor_linked_list_link`
+/// in the header written by `c_header::write_c_header`: a single `packed`
+/// word holding the XOR of the addresses of both neighbors, set to 1 while
+/// unlinked. The `debug_owner` feature appends an `owner` field.
+#[repr(C, align(2))]
 pub struct Link {
     packed: Cell<usize>,
+    #[cfg(feature = "debug_owner")]
+    owner: Cell<usize>,
 }
 
 // Use a special value to indicate an unlinked node
 const UNLINKED_MARKER: usize = 1_usize;
 
+// Marks a link claimed by `try_acquire_for_insertion`. The XOR of two link
+// addresses is even, so this is never the value of a linked link.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+pub(crate) const CLAIMED_MARKER: usize = 3;
+
 impl Link {
     /// Creates a new `Link`.
     #[inline]
     pub const fn new() -> Link {
         Link {
             packed: Cell::new(UNLINKED_MARKER),
+            #[cfg(feature = "debug_owner")]
+            owner: Cell::new(0),
         }
     }
 
@@ -168,6 +298,19 @@ impl fmt::Debug for Link {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for Link {
+    #[inline]
+    fn format(&self, f: defmt::Formatter<'_>) {
+        let state = if self.is_linked() {
+            "linked"
+        } else {
+            "unlinked"
+        };
+        defmt::write!(f, "{=str}", state)
+    }
+}
+
 // =============================================================================
 // LinkOps
 // =============================================================================
@@ -179,6 +322,8 @@ pub struct LinkOps;
 unsafe impl link_ops::LinkOps for LinkOps {
     type LinkPtr = NonNull<Link>;
 
+    const LINK_SIZE: Option<usize> = Some(mem::size_of::<Link>());
+
     #[inline]
     unsafe fn acquire_link(&mut self, ptr: Self::LinkPtr) -> bool {
         if ptr.as_ref().is_linked() {
@@ -191,10 +336,19 @@ unsafe impl link_ops::LinkOps for LinkOps {
 
     #[inline]
     unsafe fn release_link(&mut self, ptr: Self::LinkPtr) {
+        #[cfg(feature = "debug_owner")]
+        ptr.as_ref().owner.set(0);
         ptr.as_ref().packed.set(UNLINKED_MARKER);
     }
 }
 
+unsafe impl link_ops::LinkStateOps for LinkOps {
+    #[inline]
+    unsafe fn is_linked(&self, ptr: Self::LinkPtr) -> bool {
+        ptr.as_ref().is_linked()
+    }
+}
+
 unsafe impl XorLinkedListOps for LinkOps {
     #[inline]
     unsafe fn next(
@@ -202,7 +356,8 @@ unsafe impl XorLinkedListOps for LinkOps {
         ptr: Self::LinkPtr,
         prev: Option<Self::LinkPtr>,
     ) -> Option<Self::LinkPtr> {
-        let raw = ptr.as_ref().packed.get() ^ prev.map(|x| x.as_ptr() as usize).unwrap_or(0);
+        let raw = check_link_value(ptr.as_ref().packed.get())
+            ^ prev.map(|x| x.as_ptr() as usize).unwrap_or(0);
         NonNull::new(raw as *mut _)
     }
 
@@ -212,7 +367,8 @@ unsafe impl XorLinkedListOps for LinkOps {
         ptr: Self::LinkPtr,
         next: Option<Self::LinkPtr>,
     ) -> Option<Self::LinkPtr> {
-        let raw = ptr.as_ref().packed.get() ^ next.map(|x| x.as_ptr() as usize).unwrap_or(0);
+        let raw = check_link_value(ptr.as_ref().packed.get())
+            ^ next.map(|x| x.as_ptr() as usize).unwrap_or(0);
         NonNull::new(raw as *mut _)
     }
 
@@ -235,18 +391,30 @@ unsafe impl XorLinkedListOps for LinkOps {
         old: Option<Self::LinkPtr>,
         new: Option<Self::LinkPtr>,
     ) {
-        let new_packed = ptr.as_ref().packed.get()
+        let new_packed = check_link_value(ptr.as_ref().packed.get())
             ^ old.map(|x| x.as_ptr() as usize).unwrap_or(0)
             ^ new.map(|x| x.as_ptr() as usize).unwrap_or(0);
 
         ptr.as_ref().packed.set(new_packed);
     }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn set_owner(&mut self, ptr: Self::LinkPtr, owner: usize) {
+        ptr.as_ref().owner.set(owner);
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn owner(&self, ptr: Self::LinkPtr) -> Option<usize> {
+        Some(ptr.as_ref().owner.get())
+    }
 }
 
 unsafe impl SinglyLinkedListOps for LinkOps {
     #[inline]
     unsafe fn next(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
-        let raw = ptr.as_ref().packed.get();
+        let raw = check_link_value(ptr.as_ref().packed.get());
         NonNull::new(raw as *mut _)
     }
 
@@ -264,24 +432,63 @@ unsafe impl SinglyLinkedListOps for LinkOps {
 
 /// Intrusive link that allows an object to be inserted into a
 /// `XorLinkedList`. This link allows the structure to be shared between threads.
-#[repr(align(2))]
+///
+/// The link has the same layout as `Link`, described in the header written by
+/// `c_header::write_c_header` as `struct ic_xor_linked_list_atomic_link`.
+/// C code must access its `packed` field atomically.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
+#[repr(C, align(2))]
 pub struct AtomicLink {
     packed: AtomicUsize,
+    // Atomic since it is read when checking a link which may be owned by a
+    // list on another thread.
+    #[cfg(feature = "debug_owner")]
+    owner: AtomicUsize,
 }
 
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 impl AtomicLink {
-    /// Creates a new `Link`.
-    #[inline]
-    pub const fn new() -> AtomicLink {
-        AtomicLink {
-            packed: AtomicUsize::new(UNLINKED_MARKER),
+    loom_const_fn! {
+        /// Creates a new `Link`.
+        #[inline]
+        pub fn new() -> AtomicLink {
+            AtomicLink {
+                packed: AtomicUsize::new(UNLINKED_MARKER),
+                #[cfg(feature = "debug_owner")]
+                owner: AtomicUsize::new(0),
+            }
         }
     }
 
     /// Checks whether the `Link` is linked into a `XorLinkedList`.
+    ///
+    /// This can be called from any thread. The load has acquire ordering, so
+    /// if it returns `false`, everything the thread which removed the object
+    /// did before removing it is visible to the caller. An object claimed
+    /// with `try_acquire_for_insertion` counts as linked.
     #[inline]
     pub fn is_linked(&self) -> bool {
-        self.packed.load(core::sync::atomic::Ordering::Relaxed) != UNLINKED_MARKER
+        self.packed.load(Ordering::Acquire) != UNLINKED_MARKER
+    }
+
+    /// Atomically marks an unlinked `AtomicLink` as about to be inserted into a
+    /// `XorLinkedList`, returning `false` if it is linked or already claimed.
+    ///
+    /// This lets several threads race to queue the same object: only the one
+    /// which got `true` goes on to insert it, and `is_linked` returns `true`
+    /// from the moment it is claimed. Collections treat a claimed object as
+    /// linked, so the claim must be dropped with `force_unlink` before the
+    /// object is inserted.
+    #[inline]
+    pub fn try_acquire_for_insertion(&self) -> bool {
+        self.packed
+            .compare_exchange(
+                UNLINKED_MARKER,
+                CLAIMED_MARKER,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            )
+            .is_ok()
     }
 
     /// Forcibly unlinks an object from a `XorLinkedList`.
@@ -292,23 +499,40 @@ impl AtomicLink {
     /// `XorLinkedList`. The only situation where this function is useful is
     /// after calling `fast_clear` on a `XorLinkedList`, since this clears
     /// the collection without marking the nodes as unlinked.
+    ///
+    /// It can also be used to drop a claim taken with
+    /// `try_acquire_for_insertion`, before inserting the object or if it
+    /// won't be inserted.
     #[inline]
     pub unsafe fn force_unlink(&self) {
         self.packed.store(UNLINKED_MARKER, Ordering::Release);
     }
 
-    /// Access the `packed` pointer in an exclusive context.
+    /// Reads the `packed` pointer in an exclusive context.
+    ///
+    /// The value is still accessed atomically, since other threads may call
+    /// `is_linked` while the list is being modified.
+    ///
+    /// # Safety
+    ///
+    /// This can only be called after `acquire_link` has been succesfully called.
+    #[inline]
+    unsafe fn load_packed(&self) -> usize {
+        self.packed.load(Ordering::Relaxed)
+    }
+
+    /// Writes the `packed` pointer in an exclusive context.
     ///
     /// # Safety
     ///
     /// This can only be called after `acquire_link` has been succesfully called.
     #[inline]
-    unsafe fn packed_exclusive(&self) -> &Cell<usize> {
-        // This is safe because currently AtomicUsize has the same representation Cell<usize>.
-        core::mem::transmute(&self.packed)
+    unsafe fn store_packed(&self, packed: usize) {
+        self.packed.store(packed, Ordering::Relaxed);
     }
 }
 
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 impl DefaultLinkOps for AtomicLink {
     type Ops = AtomicLinkOps;
 
@@ -316,11 +540,14 @@ impl DefaultLinkOps for AtomicLink {
 }
 
 // An object containing a link can be sent to another thread since `acquire_link` is atomic.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 unsafe impl Send for AtomicLink {}
 
 // An object containing a link can be shared between threads since `acquire_link` is atomic.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 unsafe impl Sync for AtomicLink {}
 
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 impl Clone for AtomicLink {
     #[inline]
     fn clone(&self) -> AtomicLink {
@@ -328,6 +555,7 @@ impl Clone for AtomicLink {
     }
 }
 
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 impl Default for AtomicLink {
     #[inline]
     fn default() -> AtomicLink {
@@ -337,6 +565,7 @@ impl Default for AtomicLink {
 
 // Provide an implementation of Debug so that structs containing a link can
 // still derive Debug.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 impl fmt::Debug for AtomicLink {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -350,23 +579,41 @@ impl fmt::Debug for AtomicLink {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for AtomicLink {
+    #[inline]
+    fn format(&self, f: defmt::Formatter<'_>) {
+        let state = if self.is_linked() {
+            "linked"
+        } else {
+            "unlinked"
+        };
+        defmt::write!(f, "{=str}", state)
+    }
+}
+
 // =============================================================================
 // AtomicLinkOps
 // =============================================================================
 
 /// Default `AtomicLinkOps` implementation for `LinkedList`.
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 #[derive(Clone, Copy, Default)]
 pub struct AtomicLinkOps;
 
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 const LINKED_DEFAULT_VALUE: usize = 0;
 
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 unsafe impl link_ops::LinkOps for AtomicLinkOps {
     type LinkPtr = NonNull<AtomicLink>;
 
+    const LINK_SIZE: Option<usize> = Some(mem::size_of::<AtomicLink>());
+
     #[inline]
     unsafe fn acquire_link(&mut self, ptr: Self::LinkPtr) -> bool {
-        ptr.as_ref()
-            .packed
+        let packed = &ptr.as_ref().packed;
+        packed
             .compare_exchange(
                 UNLINKED_MARKER,
                 LINKED_DEFAULT_VALUE,
@@ -378,12 +625,22 @@ unsafe impl link_ops::LinkOps for AtomicLinkOps {
 
     #[inline]
     unsafe fn release_link(&mut self, ptr: Self::LinkPtr) {
+        #[cfg(feature = "debug_owner")]
+        ptr.as_ref().owner.store(0, Ordering::Relaxed);
         ptr.as_ref()
             .packed
             .store(UNLINKED_MARKER, Ordering::Release)
     }
 }
 
+unsafe impl link_ops::LinkStateOps for AtomicLinkOps {
+    #[inline]
+    unsafe fn is_linked(&self, ptr: Self::LinkPtr) -> bool {
+        ptr.as_ref().is_linked()
+    }
+}
+
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 unsafe impl XorLinkedListOps for AtomicLinkOps {
     #[inline]
     unsafe fn next(
@@ -391,8 +648,7 @@ unsafe impl XorLinkedListOps for AtomicLinkOps {
         ptr: Self::LinkPtr,
         prev: Option<Self::LinkPtr>,
     ) -> Option<Self::LinkPtr> {
-        let raw =
-            ptr.as_ref().packed_exclusive().get() ^ prev.map(|x| x.as_ptr() as usize).unwrap_or(0);
+        let raw = ptr.as_ref().load_packed() ^ prev.map(|x| x.as_ptr() as usize).unwrap_or(0);
         NonNull::new(raw as *mut _)
     }
 
@@ -402,8 +658,7 @@ unsafe impl XorLinkedListOps for AtomicLinkOps {
         ptr: Self::LinkPtr,
         next: Option<Self::LinkPtr>,
     ) -> Option<Self::LinkPtr> {
-        let raw =
-            ptr.as_ref().packed_exclusive().get() ^ next.map(|x| x.as_ptr() as usize).unwrap_or(0);
+        let raw = ptr.as_ref().load_packed() ^ next.map(|x| x.as_ptr() as usize).unwrap_or(0);
         NonNull::new(raw as *mut _)
     }
 
@@ -416,7 +671,7 @@ unsafe impl XorLinkedListOps for AtomicLinkOps {
     ) {
         let new_packed = prev.map(|x| x.as_ptr() as usize).unwrap_or(0)
             ^ next.map(|x| x.as_ptr() as usize).unwrap_or(0);
-        ptr.as_ref().packed_exclusive().set(new_packed);
+        ptr.as_ref().store_packed(new_packed);
     }
 
     #[inline]
@@ -426,26 +681,38 @@ unsafe impl XorLinkedListOps for AtomicLinkOps {
         old: Option<Self::LinkPtr>,
         new: Option<Self::LinkPtr>,
     ) {
-        let new_packed = ptr.as_ref().packed_exclusive().get()
+        let new_packed = ptr.as_ref().load_packed()
             ^ old.map(|x| x.as_ptr() as usize).unwrap_or(0)
             ^ new.map(|x| x.as_ptr() as usize).unwrap_or(0);
 
-        ptr.as_ref().packed_exclusive().set(new_packed);
+        ptr.as_ref().store_packed(new_packed);
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn set_owner(&mut self, ptr: Self::LinkPtr, owner: usize) {
+        ptr.as_ref().owner.store(owner, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    unsafe fn owner(&self, ptr: Self::LinkPtr) -> Option<usize> {
+        Some(ptr.as_ref().owner.load(Ordering::Relaxed))
     }
 }
 
+#[cfg(any(target_has_atomic = "ptr", feature = "portable-atomic"))]
 unsafe impl SinglyLinkedListOps for AtomicLinkOps {
     #[inline]
     unsafe fn next(&self, ptr: Self::LinkPtr) -> Option<Self::LinkPtr> {
-        let raw = ptr.as_ref().packed_exclusive().get();
+        let raw = ptr.as_ref().load_packed();
         NonNull::new(raw as *mut _)
     }
 
     #[inline]
     unsafe fn set_next(&mut self, ptr: Self::LinkPtr, next: Option<Self::LinkPtr>) {
         ptr.as_ref()
-            .packed_exclusive()
-            .set(next.map(|x| x.as_ptr() as usize).unwrap_or(0));
+            .store_packed(next.map(|x| x.as_ptr() as usize).unwrap_or(0));
     }
 }
 
@@ -517,6 +784,19 @@ where
         Some(unsafe { &*self.list.adapter.get_value(self.current?) })
     }
 
+    /// Returns a pinned reference to the object that the cursor is currently
+    /// pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the null
+    /// object.
+    #[inline]
+    pub fn get_pin(&self) -> Option<Pin<&'a <A::PointerOps as PointerOps>::Value>>
+    where
+        A::PointerOps: PinnedPointerOps,
+    {
+        Some(unsafe { Pin::new_unchecked(self.get()?) })
+    }
+
     /// Clones and returns the pointer that points to the element that the
     /// cursor is referencing.
     ///
@@ -541,6 +821,7 @@ where
     /// null object.
     #[inline]
     pub fn move_next(&mut self) {
+        record_stat!(self.list, cursor_moves);
         let prev = self.current;
         self.current = self.next;
         unsafe {
@@ -561,6 +842,7 @@ where
     /// element of the `XorLinkedList` then this will move it to the null object.
     #[inline]
     pub fn move_prev(&mut self) {
+        record_stat!(self.list, cursor_moves);
         let next = self.current;
         self.current = self.prev;
         unsafe {
@@ -630,6 +912,22 @@ where
         Some(unsafe { &*self.list.adapter.get_value(self.current?) })
     }
 
+    /// Clones and returns the pointer that points to the element that the
+    /// cursor is referencing.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the null
+    /// object.
+    #[inline]
+    pub fn clone_pointer(&self) -> Option<<A::PointerOps as PointerOps>::Pointer>
+    where
+        <A::PointerOps as PointerOps>::Pointer: Clone,
+    {
+        let raw_pointer = unsafe { self.list.adapter.get_value(self.current?) };
+        Some(unsafe {
+            crate::pointer_ops::clone_pointer_from_raw(self.list.adapter.pointer_ops(), raw_pointer)
+        })
+    }
+
     /// Returns a mutable reference to the object that the cursor is currently
     /// pointing to.
     ///
@@ -676,6 +974,53 @@ where
         )
     }
 
+    /// Returns a pinned mutable reference to the object that the cursor is
+    /// currently pointing to.
+    ///
+    /// This returns None if the cursor is currently pointing to the null
+    /// object.
+    ///
+    /// # Safety
+    ///
+    /// You must not modify any links that are linked
+    #[inline]
+    pub unsafe fn get_pin_mut(&mut self) -> Option<Pin<&mut <A::PointerOps as PointerOps>::Value>>
+    where
+        A::PointerOps: ExclusivePinnedPointerOps,
+    {
+        let value = self.list.adapter.get_value(self.current?);
+        Some(Pin::new_unchecked(
+            &mut *self.list.adapter.pointer_ops().get_pin_mut(value),
+        ))
+    }
+
+    /// Returns a mutable reference to the object that the cursor is currently
+    /// pointing to, cloning it first if it is shared.
+    ///
+    /// If the object is not uniquely owned by the collection, it is replaced
+    /// in the `XorLinkedList` by a clone and the collection's pointer to the
+    /// original object is dropped. Other pointers to the original object keep
+    /// seeing the old value.
+    ///
+    /// This returns None if the cursor is currently pointing to the null
+    /// object.
+    ///
+    /// # Safety
+    ///
+    /// You must not modify any links that are linked
+    #[inline]
+    pub unsafe fn make_mut(&mut self) -> Option<&mut <A::PointerOps as PointerOps>::Value>
+    where
+        A::PointerOps: CowPointerOps,
+    {
+        let value = self.list.adapter.get_value(self.current?);
+        if self.list.adapter.pointer_ops().try_get_mut(value).is_none() {
+            let clone = self.list.adapter.pointer_ops().clone_value(&*value);
+            let _ = self.replace_with(clone);
+        }
+        self.try_get_mut()
+    }
+
     /// Returns a read-only cursor pointing to the current element.
     ///
     /// The lifetime of the returned `Cursor` is bound to that of the
@@ -691,6 +1036,19 @@ where
         }
     }
 
+    /// Consumes `CursorMut` and returns a read-only cursor pointing to the
+    /// current element. Unlike [as_cursor](Self::as_cursor), the returned
+    /// cursor's lifetime is tied to `XorLinkedList`'s lifetime.
+    #[inline]
+    pub fn into_cursor(self) -> Cursor<'a, A> {
+        Cursor {
+            current: self.current,
+            prev: self.prev,
+            next: self.next,
+            list: self.list,
+        }
+    }
+
     /// Moves the cursor to the next element of the `XorLinkedList`.
     ///
     /// If the cursor is pointer to the null object then this will move it to
@@ -699,6 +1057,7 @@ where
     /// null object.
     #[inline]
     pub fn move_next(&mut self) {
+        record_stat!(self.list, cursor_moves);
         let prev = self.current;
         self.current = self.next;
         unsafe {
@@ -719,6 +1078,7 @@ where
     /// element of the `XorLinkedList` then this will move it to the null object.
     #[inline]
     pub fn move_prev(&mut self) {
+        record_stat!(self.list, cursor_moves);
         let next = self.current;
         self.current = self.prev;
         unsafe {
@@ -765,48 +1125,83 @@ where
     /// is removed and `None` is returned.
     #[inline]
     pub fn remove(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
-        use link_ops::LinkOps;
+        self.check_current_owner();
+        poisoning!(self.list, unsafe {
+            self.unlink_current().map(|current| {
+                record_stat!(self.list, removals);
+                crate::adapter::unlinked_pointer(&self.list.adapter, current)
+            })
+        })
+    }
 
-        unsafe {
-            let current = self.current?;
-            let result = current;
-
-            self.list.adapter.link_ops_mut().release_link(current);
-            if let Some(prev) = self.prev {
-                self.list.adapter.link_ops_mut().replace_next_or_prev(
-                    prev,
-                    Some(current),
-                    self.next,
-                );
-            }
-            if let Some(next) = self.next {
-                self.list.adapter.link_ops_mut().replace_next_or_prev(
-                    next,
-                    Some(current),
-                    self.prev,
-                );
-            }
-            if self.list.head == Some(current) {
-                self.list.head = self.next;
-            }
-            if self.list.tail == Some(current) {
-                self.list.tail = self.prev;
-            }
-            self.current = self.next;
-            if let Some(current) = self.current {
-                self.next = self.list.adapter.link_ops().next(current, self.prev);
-            } else {
-                self.prev = self.list.tail;
-                self.next = self.list.head;
-            }
+    /// Removes the current element from the `XorLinkedList`, converting it
+    /// back with `TryPointerOps::try_from_raw`.
+    ///
+    /// This is the same as `remove`, except that the conversion back into a
+    /// pointer may fail, in which case the error is returned. The element is
+    /// removed from the `XorLinkedList` either way.
+    ///
+    /// If the cursor is currently pointing to the null object then no element
+    /// is removed and `None` is returned.
+    #[inline]
+    pub fn remove_checked(&mut self) -> Option<TryPointer<A>>
+    where
+        A::PointerOps: TryPointerOps,
+    {
+        self.check_current_owner();
+        poisoning!(self.list, unsafe {
+            self.unlink_current().map(|current| {
+                record_stat!(self.list, removals);
+                crate::adapter::try_unlinked_pointer(&self.list.adapter, current)
+            })
+        })
+    }
 
-            Some(
-                self.list
-                    .adapter
-                    .pointer_ops()
-                    .from_raw(self.list.adapter.get_value(result)),
-            )
+    // Checks that the current element is in this list, for the `debug_owner`
+    // feature. This is done before modifying the list, so that the panic
+    // doesn't poison it.
+    #[inline]
+    #[track_caller]
+    fn check_current_owner(&self) {
+        if let Some(current) = self.current {
+            unsafe { self.list.check_owner(current) };
+        }
+    }
+
+    // Unlinks the current element and releases its link, moving the cursor to
+    // the next element.
+    #[inline]
+    unsafe fn unlink_current(&mut self) -> Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr> {
+        use link_ops::LinkOps;
+
+        let current = self.current?;
+        self.list.adapter.link_ops_mut().release_link(current);
+        if let Some(prev) = self.prev {
+            self.list
+                .adapter
+                .link_ops_mut()
+                .replace_next_or_prev(prev, Some(current), self.next);
+        }
+        if let Some(next) = self.next {
+            self.list
+                .adapter
+                .link_ops_mut()
+                .replace_next_or_prev(next, Some(current), self.prev);
+        }
+        if self.list.head == Some(current) {
+            self.list.head = self.next;
+        }
+        if self.list.tail == Some(current) {
+            self.list.tail = self.prev;
         }
+        self.current = self.next;
+        if let Some(current) = self.current {
+            self.next = self.list.adapter.link_ops().next(current, self.prev);
+        } else {
+            self.prev = self.list.tail;
+            self.next = self.list.head;
+        }
+        Some(current)
     }
 
     /// Removes the current element from the `XorLinkedList` and inserts another
@@ -828,51 +1223,86 @@ where
         val: <A::PointerOps as PointerOps>::Pointer,
     ) -> Result<<A::PointerOps as PointerOps>::Pointer, <A::PointerOps as PointerOps>::Pointer>
     {
-        use link_ops::LinkOps;
-
         unsafe {
             if let Some(current) = self.current {
+                self.list.check_owner(current);
                 let new = self.list.node_from_value(val);
-                let result = current;
+                Ok(poisoning!(self.list, self.replace_current(current, new)))
+            } else {
+                Err(val)
+            }
+        }
+    }
 
-                if self.list.head == Some(current) {
-                    self.list.head = Some(new);
-                }
-                if self.list.tail == Some(current) {
-                    self.list.tail = Some(new);
+    /// Removes the current element from the `XorLinkedList` and inserts another
+    /// object in its place, without panicking.
+    ///
+    /// This is the same as `replace_with`, except that an error containing
+    /// `val` is returned if the new element is already linked to an intrusive
+    /// collection, in which case the `XorLinkedList` is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// With the `debug_owner` feature, panics if the current element was
+    /// linked by a different `XorLinkedList`, like `replace_with`.
+    #[inline]
+    pub fn try_replace_with(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<
+        <A::PointerOps as PointerOps>::Pointer,
+        InsertError<<A::PointerOps as PointerOps>::Pointer>,
+    > {
+        unsafe {
+            match self.current {
+                Some(current) => {
+                    self.list.check_owner(current);
+                    self.list
+                        .try_node_from_value(val)
+                        .map(|new| poisoning!(self.list, self.replace_current(current, new)))
                 }
+                None => Err(InsertError::NullCursor(val)),
+            }
+        }
+    }
 
-                if let Some(prev) = self.prev {
-                    self.list.adapter.link_ops_mut().replace_next_or_prev(
-                        prev,
-                        Some(current),
-                        Some(new),
-                    );
-                }
-                if let Some(next) = self.next {
-                    self.list.adapter.link_ops_mut().replace_next_or_prev(
-                        next,
-                        Some(current),
-                        Some(new),
-                    );
-                }
+    #[inline]
+    unsafe fn replace_current(
+        &mut self,
+        current: <A::LinkOps as link_ops::LinkOps>::LinkPtr,
+        new: <A::LinkOps as link_ops::LinkOps>::LinkPtr,
+    ) -> <A::PointerOps as PointerOps>::Pointer {
+        use link_ops::LinkOps;
 
-                self.list
-                    .adapter
-                    .link_ops_mut()
-                    .set(new, self.prev, self.next);
-                self.list.adapter.link_ops_mut().release_link(result);
-                self.current = Some(new);
-
-                Ok(self
-                    .list
-                    .adapter
-                    .pointer_ops()
-                    .from_raw(self.list.adapter.get_value(result)))
-            } else {
-                Err(val)
-            }
+        if self.list.head == Some(current) {
+            self.list.head = Some(new);
         }
+        if self.list.tail == Some(current) {
+            self.list.tail = Some(new);
+        }
+
+        if let Some(prev) = self.prev {
+            self.list
+                .adapter
+                .link_ops_mut()
+                .replace_next_or_prev(prev, Some(current), Some(new));
+        }
+        if let Some(next) = self.next {
+            self.list
+                .adapter
+                .link_ops_mut()
+                .replace_next_or_prev(next, Some(current), Some(new));
+        }
+
+        self.list
+            .adapter
+            .link_ops_mut()
+            .set(new, self.prev, self.next);
+        self.list.adapter.link_ops_mut().release_link(current);
+        self.current = Some(new);
+        record_stat!(self.list, removals);
+
+        crate::adapter::unlinked_pointer(&self.list.adapter, current)
     }
 
     /// Inserts a new element into the `XorLinkedList` after the current one.
@@ -888,27 +1318,32 @@ where
     pub fn insert_after(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
         unsafe {
             let new = self.list.node_from_value(val);
-            if let Some(current) = self.current {
-                link_between(
-                    self.list.adapter.link_ops_mut(),
-                    new,
-                    Some(current),
-                    self.next,
-                );
-                if self.next.is_none() {
-                    // Current pointer was tail
-                    self.list.tail = Some(new);
-                }
-                self.next = Some(new);
-            } else {
-                link_between(self.list.adapter.link_ops_mut(), new, None, self.list.head);
-                self.list.head = Some(new);
-                if self.list.tail.is_none() {
-                    self.list.tail = Some(new);
-                }
-                self.prev = self.list.tail;
-                self.next = self.list.head;
+            poisoning!(self.list, self.link_node_after(new));
+        }
+    }
+
+    #[inline]
+    unsafe fn link_node_after(&mut self, new: <A::LinkOps as link_ops::LinkOps>::LinkPtr) {
+        if let Some(current) = self.current {
+            link_between(
+                self.list.adapter.link_ops_mut(),
+                new,
+                Some(current),
+                self.next,
+            );
+            if self.next.is_none() {
+                // Current pointer was tail
+                self.list.tail = Some(new);
+            }
+            self.next = Some(new);
+        } else {
+            link_between(self.list.adapter.link_ops_mut(), new, None, self.list.head);
+            self.list.head = Some(new);
+            if self.list.tail.is_none() {
+                self.list.tail = Some(new);
             }
+            self.prev = self.list.tail;
+            self.next = self.list.head;
         }
     }
 
@@ -925,27 +1360,198 @@ where
     pub fn insert_before(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
         unsafe {
             let new = self.list.node_from_value(val);
-            if let Some(current) = self.current {
-                link_between(
-                    self.list.adapter.link_ops_mut(),
-                    new,
-                    self.prev,
-                    Some(current),
-                );
-                if self.prev.is_none() {
-                    // Current pointer was tail
-                    self.list.head = Some(new);
+            poisoning!(self.list, self.link_node_before(new));
+        }
+    }
+
+    #[inline]
+    unsafe fn link_node_before(&mut self, new: <A::LinkOps as link_ops::LinkOps>::LinkPtr) {
+        if let Some(current) = self.current {
+            link_between(
+                self.list.adapter.link_ops_mut(),
+                new,
+                self.prev,
+                Some(current),
+            );
+            if self.prev.is_none() {
+                // Current pointer was tail
+                self.list.head = Some(new);
+            }
+            self.prev = Some(new);
+        } else {
+            link_between(self.list.adapter.link_ops_mut(), new, self.list.tail, None);
+            self.list.tail = Some(new);
+            if self.list.head.is_none() {
+                self.list.head = Some(new);
+            }
+            self.prev = self.list.tail;
+            self.next = self.list.head;
+        }
+    }
+
+    /// Moves the current element into another `XorLinkedList`, inserting it
+    /// after the element that `dest` is pointing to.
+    ///
+    /// The element is moved without converting it back into an owned pointer,
+    /// so reference counts are left untouched. The two lists may use different
+    /// links in the object, or the same one. The cursor is moved to the next
+    /// element, as with `remove`. This returns `false` if the cursor is
+    /// currently pointing to the null object.
+    ///
+    /// If `dest` is pointing at the null object then the element is inserted
+    /// at the start of its `XorLinkedList`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element is already linked to a different intrusive
+    /// collection through the link used by `dest`. The element is left in
+    /// this list in that case.
+    ///
+    /// Also panics if the `PointerOps` of the two lists don't have the same
+    /// state (see `PointerOps::same_state`), in which case neither list is
+    /// modified.
+    #[inline]
+    pub fn transfer_after<B>(&mut self, dest: &mut CursorMut<'_, B>) -> bool
+    where
+        B: Adapter<PointerOps = A::PointerOps>,
+        B::LinkOps: XorLinkedListOps,
+    {
+        crate::adapter::check_transfer(&self.list.adapter, &dest.list.adapter);
+        self.check_current_owner();
+        let result = poisoning!(
+            self.list,
+            poisoning!(dest.list, unsafe {
+                let result = self.unlink_for_transfer(dest.list);
+                if let Some(Ok(new)) = result {
+                    dest.link_node_after(new);
                 }
-                self.prev = Some(new);
-            } else {
-                link_between(self.list.adapter.link_ops_mut(), new, self.list.tail, None);
-                self.list.tail = Some(new);
-                if self.list.head.is_none() {
-                    self.list.head = Some(new);
+                result
+            })
+        );
+        unsafe { dest.list.transferred(result) }
+    }
+
+    /// Moves the current element into another `XorLinkedList`, inserting it
+    /// before the element that `dest` is pointing to.
+    ///
+    /// This is the same as `transfer_after`, except that if `dest` is
+    /// pointing at the null object then the element is inserted at the end of
+    /// its `XorLinkedList`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element is already linked to a different intrusive
+    /// collection through the link used by `dest`. The element is left in
+    /// this list in that case.
+    ///
+    /// Also panics if the `PointerOps` of the two lists don't have the same
+    /// state (see `PointerOps::same_state`), in which case neither list is
+    /// modified.
+    #[inline]
+    pub fn transfer_before<B>(&mut self, dest: &mut CursorMut<'_, B>) -> bool
+    where
+        B: Adapter<PointerOps = A::PointerOps>,
+        B::LinkOps: XorLinkedListOps,
+    {
+        crate::adapter::check_transfer(&self.list.adapter, &dest.list.adapter);
+        self.check_current_owner();
+        let result = poisoning!(
+            self.list,
+            poisoning!(dest.list, unsafe {
+                let result = self.unlink_for_transfer(dest.list);
+                if let Some(Ok(new)) = result {
+                    dest.link_node_before(new);
                 }
-                self.prev = self.list.tail;
-                self.next = self.list.head;
+                result
+            })
+        );
+        unsafe { dest.list.transferred(result) }
+    }
+
+    // Unlinks the current element and acquires its link in `dest`. The
+    // element is put back if the link can't be acquired, which happens after
+    // unlinking so that both lists can use the same link, and its link in
+    // `dest` is returned as an error.
+    #[inline]
+    unsafe fn unlink_for_transfer<B>(
+        &mut self,
+        dest: &mut XorLinkedList<B>,
+    ) -> crate::adapter::Transfer<B>
+    where
+        B: Adapter<PointerOps = A::PointerOps>,
+        B::LinkOps: XorLinkedListOps,
+    {
+        use link_ops::LinkOps;
+
+        let (prev, next) = (self.prev, self.next);
+        let current = self.unlink_current()?;
+        let value = self.list.adapter.get_value(current);
+
+        let new = dest.adapter.get_link(value);
+        if !dest.adapter.link_ops_mut().acquire_link(new) {
+            let acquired = self.list.adapter.link_ops_mut().acquire_link(current);
+            debug_assert!(acquired);
+            link_between(self.list.adapter.link_ops_mut(), current, prev, next);
+            if prev.is_none() {
+                self.list.head = Some(current);
+            }
+            if next.is_none() {
+                self.list.tail = Some(current);
             }
+            self.prev = prev;
+            self.current = Some(current);
+            self.next = next;
+            return Some(Err(new));
+        }
+        #[cfg(feature = "debug_owner")]
+        {
+            let owner = dest.owner_id();
+            dest.adapter.link_ops_mut().set_owner(new, owner);
+        }
+        // If a hook panics, the guard drops the element, which is then in
+        // neither list.
+        let guard = crate::adapter::AcquiredLink::new(&mut dest.adapter, new);
+        self.list.adapter.on_remove(&*value);
+        guard.on_insert();
+        guard.disarm();
+        record_stat!(self.list, removals);
+        record_stat!(dest, insertions);
+        Some(Ok(new))
+    }
+
+    /// Inserts a new element into the `XorLinkedList` after the current one,
+    /// without panicking.
+    ///
+    /// This is the same as `insert_after`, except that an error containing
+    /// `val` is returned if the new element is already linked to an intrusive
+    /// collection.
+    #[inline]
+    pub fn try_insert_after(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<(), InsertError<<A::PointerOps as PointerOps>::Pointer>> {
+        unsafe {
+            self.list
+                .try_node_from_value(val)
+                .map(|new| poisoning!(self.list, self.link_node_after(new)))
+        }
+    }
+
+    /// Inserts a new element into the `XorLinkedList` before the current one,
+    /// without panicking.
+    ///
+    /// This is the same as `insert_before`, except that an error containing
+    /// `val` is returned if the new element is already linked to an intrusive
+    /// collection.
+    #[inline]
+    pub fn try_insert_before(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<(), InsertError<<A::PointerOps as PointerOps>::Pointer>> {
+        unsafe {
+            self.list
+                .try_node_from_value(val)
+                .map(|new| poisoning!(self.list, self.link_node_before(new)))
         }
     }
 
@@ -953,76 +1559,96 @@ where
     ///
     /// If the cursor is pointing at the null object then the new elements are
     /// inserted at the start of the `XorLinkedList`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `PointerOps` of the two lists don't have the same state
+    /// (see `PointerOps::same_state`).
     #[inline]
     pub fn splice_after(&mut self, mut list: XorLinkedList<A>) {
-        if !list.is_empty() {
-            unsafe {
-                let head = list.head.unwrap_unchecked();
-                let tail = list.tail.unwrap_unchecked();
-
-                let link_ops = self.list.adapter.link_ops_mut();
-
-                if let Some(current) = self.current {
-                    if let Some(next) = self.next {
-                        link_ops.replace_next_or_prev(next, Some(current), Some(tail));
-                        link_ops.replace_next_or_prev(tail, None, Some(next));
+        crate::adapter::check_transfer(&self.list.adapter, &list.adapter);
+        poisoning!(self.list, {
+            if !list.is_empty() {
+                #[cfg(feature = "debug_owner")]
+                list.set_owner_all(self.list.owner_id());
+                unsafe {
+                    let head = list.head.unwrap_unchecked();
+                    let tail = list.tail.unwrap_unchecked();
+
+                    let link_ops = self.list.adapter.link_ops_mut();
+
+                    if let Some(current) = self.current {
+                        if let Some(next) = self.next {
+                            link_ops.replace_next_or_prev(next, Some(current), Some(tail));
+                            link_ops.replace_next_or_prev(tail, None, Some(next));
+                        }
+                        link_ops.replace_next_or_prev(head, None, Some(current));
+                        self.next = list.head;
+                        link_ops.set(current, self.prev, self.next);
+                    } else {
+                        if let Some(x) = self.list.head {
+                            link_ops.replace_next_or_prev(tail, None, Some(x));
+                            link_ops.replace_next_or_prev(x, None, Some(tail));
+                        }
+                        self.list.head = list.head;
+                        self.next = list.head;
                     }
-                    link_ops.replace_next_or_prev(head, None, Some(current));
-                    self.next = list.head;
-                    link_ops.set(current, self.prev, self.next);
-                } else {
-                    if let Some(x) = self.list.head {
-                        link_ops.replace_next_or_prev(tail, None, Some(x));
-                        link_ops.replace_next_or_prev(x, None, Some(tail));
+                    if self.list.tail == self.current {
+                        self.list.tail = list.tail;
                     }
-                    self.list.head = list.head;
-                    self.next = list.head;
-                }
-                if self.list.tail == self.current {
-                    self.list.tail = list.tail;
+                    list.head = None;
+                    list.tail = None;
                 }
-                list.head = None;
-                list.tail = None;
             }
-        }
+        })
     }
 
     /// Moves all element from the given `XorLinkedList` before the current one.
     ///
     /// If the cursor is pointing at the null object then the new elements are
     /// inserted at the end of the `XorLinkedList`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `PointerOps` of the two lists don't have the same state
+    /// (see `PointerOps::same_state`).
     #[inline]
     pub fn splice_before(&mut self, mut list: XorLinkedList<A>) {
-        if !list.is_empty() {
-            unsafe {
-                let head = list.head.unwrap_unchecked();
-                let tail = list.tail.unwrap_unchecked();
-
-                let link_ops = self.list.adapter.link_ops_mut();
-
-                if let Some(current) = self.current {
-                    if let Some(prev) = self.prev {
-                        link_ops.replace_next_or_prev(prev, Some(current), Some(head));
-                        link_ops.replace_next_or_prev(head, None, Some(prev));
+        crate::adapter::check_transfer(&self.list.adapter, &list.adapter);
+        poisoning!(self.list, {
+            if !list.is_empty() {
+                #[cfg(feature = "debug_owner")]
+                list.set_owner_all(self.list.owner_id());
+                unsafe {
+                    let head = list.head.unwrap_unchecked();
+                    let tail = list.tail.unwrap_unchecked();
+
+                    let link_ops = self.list.adapter.link_ops_mut();
+
+                    if let Some(current) = self.current {
+                        if let Some(prev) = self.prev {
+                            link_ops.replace_next_or_prev(prev, Some(current), Some(head));
+                            link_ops.replace_next_or_prev(head, None, Some(prev));
+                        }
+                        link_ops.replace_next_or_prev(tail, None, Some(current));
+                        self.prev = list.tail;
+                        link_ops.set(current, self.prev, self.next);
+                    } else {
+                        if let Some(x) = self.list.tail {
+                            link_ops.replace_next_or_prev(head, None, Some(x));
+                            link_ops.replace_next_or_prev(x, None, Some(head));
+                        }
+                        self.list.tail = list.tail;
+                        self.prev = list.tail;
                     }
-                    link_ops.replace_next_or_prev(tail, None, Some(current));
-                    self.prev = list.tail;
-                    link_ops.set(current, self.prev, self.next);
-                } else {
-                    if let Some(x) = self.list.tail {
-                        link_ops.replace_next_or_prev(head, None, Some(x));
-                        link_ops.replace_next_or_prev(x, None, Some(head));
+                    if self.list.head == self.current {
+                        self.list.head = list.head;
                     }
-                    self.list.head = list.head;
-                    self.next = list.head;
-                }
-                if self.list.tail == self.current {
-                    self.list.tail = list.tail;
+                    list.head = None;
+                    list.tail = None;
                 }
-                list.head = None;
-                list.tail = None;
             }
-        }
+        })
     }
 
     /// Splits the list into two after the current element. This will return a
@@ -1036,40 +1662,65 @@ where
     where
         A: Clone,
     {
-        if let Some(current) = self.current {
-            unsafe {
-                let mut list = XorLinkedList {
-                    head: self.next,
+        poisoning!(self.list, {
+            if let Some(current) = self.current {
+                unsafe {
+                    #[cfg_attr(not(feature = "debug_owner"), allow(unused_mut))]
+                    let mut list = XorLinkedList {
+                        head: self.next,
+                        tail: self.list.tail,
+                        adapter: self.list.adapter.clone(),
+                        #[cfg(debug_assertions)]
+                        debug_name: self.list.debug_name,
+                        #[cfg(feature = "stats")]
+                        stats: StatsCounters::new(),
+                        #[cfg(feature = "debug_owner")]
+                        owner: 0,
+                        #[cfg(feature = "poison")]
+                        poison: Poison::new(),
+                    };
+                    if let Some(head) = list.head {
+                        self.list.adapter.link_ops_mut().replace_next_or_prev(
+                            head,
+                            Some(current),
+                            None,
+                        );
+                        self.next = None;
+                    } else {
+                        list.tail = None;
+                    }
+                    self.list
+                        .adapter
+                        .link_ops_mut()
+                        .set(current, self.prev, None);
+                    self.list.tail = self.current;
+                    #[cfg(feature = "debug_owner")]
+                    list.set_owner_all(link_ops::new_owner_id());
+                    list
+                }
+            } else {
+                let list = XorLinkedList {
+                    head: self.list.head,
                     tail: self.list.tail,
                     adapter: self.list.adapter.clone(),
+                    #[cfg(debug_assertions)]
+                    debug_name: self.list.debug_name,
+                    #[cfg(feature = "stats")]
+                    stats: StatsCounters::new(),
+                    #[cfg(feature = "debug_owner")]
+                    owner: self.list.owner,
+                    #[cfg(feature = "poison")]
+                    poison: Poison::new(),
                 };
-                if let Some(head) = list.head {
-                    self.list.adapter.link_ops_mut().replace_next_or_prev(
-                        head,
-                        Some(current),
-                        None,
-                    );
-                    self.next = None;
-                } else {
-                    list.tail = None;
+                self.list.head = None;
+                self.list.tail = None;
+                #[cfg(feature = "debug_owner")]
+                {
+                    self.list.owner = 0;
                 }
-                self.list
-                    .adapter
-                    .link_ops_mut()
-                    .set(current, self.prev, None);
-                self.list.tail = self.current;
                 list
             }
-        } else {
-            let list = XorLinkedList {
-                head: self.list.head,
-                tail: self.list.tail,
-                adapter: self.list.adapter.clone(),
-            };
-            self.list.head = None;
-            self.list.tail = None;
-            list
-        }
+        })
     }
 
     /// Splits the list into two before the current element. This will return a
@@ -1083,40 +1734,65 @@ where
     where
         A: Clone,
     {
-        if let Some(current) = self.current {
-            unsafe {
-                let mut list = XorLinkedList {
+        poisoning!(self.list, {
+            if let Some(current) = self.current {
+                unsafe {
+                    #[cfg_attr(not(feature = "debug_owner"), allow(unused_mut))]
+                    let mut list = XorLinkedList {
+                        head: self.list.head,
+                        tail: self.prev,
+                        adapter: self.list.adapter.clone(),
+                        #[cfg(debug_assertions)]
+                        debug_name: self.list.debug_name,
+                        #[cfg(feature = "stats")]
+                        stats: StatsCounters::new(),
+                        #[cfg(feature = "debug_owner")]
+                        owner: 0,
+                        #[cfg(feature = "poison")]
+                        poison: Poison::new(),
+                    };
+                    if let Some(tail) = list.tail {
+                        self.list.adapter.link_ops_mut().replace_next_or_prev(
+                            tail,
+                            Some(current),
+                            None,
+                        );
+                        self.prev = None;
+                    } else {
+                        list.head = None;
+                    }
+                    self.list
+                        .adapter
+                        .link_ops_mut()
+                        .set(current, None, self.next);
+                    self.list.head = self.current;
+                    #[cfg(feature = "debug_owner")]
+                    list.set_owner_all(link_ops::new_owner_id());
+                    list
+                }
+            } else {
+                let list = XorLinkedList {
                     head: self.list.head,
-                    tail: self.prev,
+                    tail: self.list.tail,
                     adapter: self.list.adapter.clone(),
+                    #[cfg(debug_assertions)]
+                    debug_name: self.list.debug_name,
+                    #[cfg(feature = "stats")]
+                    stats: StatsCounters::new(),
+                    #[cfg(feature = "debug_owner")]
+                    owner: self.list.owner,
+                    #[cfg(feature = "poison")]
+                    poison: Poison::new(),
                 };
-                if let Some(tail) = list.tail {
-                    self.list.adapter.link_ops_mut().replace_next_or_prev(
-                        tail,
-                        Some(current),
-                        None,
-                    );
-                    self.prev = None;
-                } else {
-                    list.head = None;
+                self.list.head = None;
+                self.list.tail = None;
+                #[cfg(feature = "debug_owner")]
+                {
+                    self.list.owner = 0;
                 }
-                self.list
-                    .adapter
-                    .link_ops_mut()
-                    .set(current, None, self.next);
-                self.list.head = self.current;
                 list
             }
-        } else {
-            let list = XorLinkedList {
-                head: self.list.head,
-                tail: self.list.tail,
-                adapter: self.list.adapter.clone(),
-            };
-            self.list.head = None;
-            self.list.tail = None;
-            list
-        }
+        })
     }
 
     /// Consumes `CursorMut` and returns a reference to the object that
@@ -1195,6 +1871,190 @@ where
     A::LinkOps: XorLinkedListOps,
 {
 }
+unsafe impl<A: Adapter> Sync for CursorOwning<A>
+where
+    XorLinkedList<A>: Sync,
+    A::LinkOps: XorLinkedListOps,
+{
+}
+
+impl<'a, A: Adapter> CursorOps for Cursor<'a, A>
+where
+    A::LinkOps: XorLinkedListOps,
+{
+    type Adapter = A;
+
+    #[inline]
+    fn is_null(&self) -> bool {
+        Cursor::is_null(self)
+    }
+
+    #[inline]
+    fn get(&self) -> Option<&<A::PointerOps as PointerOps>::Value> {
+        Cursor::get(self)
+    }
+
+    #[inline]
+    fn move_next(&mut self) {
+        Cursor::move_next(self)
+    }
+}
+
+impl<'a, A: Adapter> DoubleEndedCursorOps for Cursor<'a, A>
+where
+    A::LinkOps: XorLinkedListOps,
+{
+    #[inline]
+    fn move_prev(&mut self) {
+        Cursor::move_prev(self)
+    }
+}
+
+impl<'a, A: Adapter> CursorOps for CursorMut<'a, A>
+where
+    A::LinkOps: XorLinkedListOps,
+{
+    type Adapter = A;
+
+    #[inline]
+    fn is_null(&self) -> bool {
+        CursorMut::is_null(self)
+    }
+
+    #[inline]
+    fn get(&self) -> Option<&<A::PointerOps as PointerOps>::Value> {
+        CursorMut::get(self)
+    }
+
+    #[inline]
+    fn move_next(&mut self) {
+        CursorMut::move_next(self)
+    }
+}
+
+impl<'a, A: Adapter> DoubleEndedCursorOps for CursorMut<'a, A>
+where
+    A::LinkOps: XorLinkedListOps,
+{
+    #[inline]
+    fn move_prev(&mut self) {
+        CursorMut::move_prev(self)
+    }
+}
+
+impl<'a, A: Adapter> CursorMutOps for CursorMut<'a, A>
+where
+    A::LinkOps: XorLinkedListOps,
+{
+    #[inline]
+    fn remove(&mut self) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        CursorMut::remove(self)
+    }
+
+    #[inline]
+    fn insert_after(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        CursorMut::insert_after(self, val)
+    }
+}
+
+impl<A: Adapter> fmt::Debug for Cursor<'_, A>
+where
+    A::LinkOps: XorLinkedListOps,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        crate::adapter::debug_cursor(
+            f,
+            "Cursor",
+            &self.list.adapter,
+            self.current,
+            Some(self.prev),
+            self.next,
+        )
+    }
+}
+
+impl<A: Adapter> fmt::Debug for CursorMut<'_, A>
+where
+    A::LinkOps: XorLinkedListOps,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        crate::adapter::debug_cursor(
+            f,
+            "CursorMut",
+            &self.list.adapter,
+            self.current,
+            Some(self.prev),
+            self.next,
+        )
+    }
+}
+
+impl<A: Adapter> fmt::Debug for CursorOwning<A>
+where
+    A::LinkOps: XorLinkedListOps,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        crate::adapter::debug_cursor(
+            f,
+            "CursorOwning",
+            &self.list.adapter,
+            self.current,
+            Some(self.prev),
+            self.next,
+        )
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<A: Adapter> defmt::Format for Cursor<'_, A>
+where
+    A::LinkOps: XorLinkedListOps,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        crate::adapter::defmt_cursor(
+            f,
+            "Cursor",
+            &self.list.adapter,
+            self.current,
+            Some(self.prev),
+            self.next,
+        )
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<A: Adapter> defmt::Format for CursorMut<'_, A>
+where
+    A::LinkOps: XorLinkedListOps,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        crate::adapter::defmt_cursor(
+            f,
+            "CursorMut",
+            &self.list.adapter,
+            self.current,
+            Some(self.prev),
+            self.next,
+        )
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<A: Adapter> defmt::Format for CursorOwning<A>
+where
+    A::LinkOps: XorLinkedListOps,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        crate::adapter::defmt_cursor(
+            f,
+            "CursorOwning",
+            &self.list.adapter,
+            self.current,
+            Some(self.prev),
+            self.next,
+        )
+    }
+}
 
 // =============================================================================
 // XorLinkedList
@@ -1214,6 +2074,14 @@ where
     head: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
     tail: Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
     adapter: A,
+    #[cfg(debug_assertions)]
+    debug_name: Option<&'static str>,
+    #[cfg(feature = "stats")]
+    stats: StatsCounters,
+    #[cfg(feature = "debug_owner")]
+    owner: usize,
+    #[cfg(feature = "poison")]
+    poison: Poison,
 }
 
 impl<A: Adapter> XorLinkedList<A>
@@ -1225,49 +2093,347 @@ where
         &mut self,
         val: <A::PointerOps as PointerOps>::Pointer,
     ) -> <A::LinkOps as link_ops::LinkOps>::LinkPtr {
+        unsafe {
+            let link = self.acquire_node(val);
+            crate::adapter::acquired_on_insert(&mut self.adapter, link);
+            record_stat!(self, insertions);
+
+            link
+        }
+    }
+
+    // Same as `node_from_value`, but hands `val` back if it is already linked
+    // instead of panicking.
+    #[inline]
+    fn try_node_from_value(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<
+        <A::LinkOps as link_ops::LinkOps>::LinkPtr,
+        InsertError<<A::PointerOps as PointerOps>::Pointer>,
+    > {
         use link_ops::LinkOps;
 
+        check_poison!(self);
         unsafe {
             let raw = self.adapter.pointer_ops().into_raw(val);
             let link = self.adapter.get_link(raw);
 
             if !self.adapter.link_ops_mut().acquire_link(link) {
-                // convert the node back into a pointer
-                self.adapter.pointer_ops().from_raw(raw);
+                let val = self.adapter.pointer_ops().from_raw(raw);
+                return Err(InsertError::AlreadyLinked(val));
+            }
+            #[cfg(feature = "debug_owner")]
+            {
+                let owner = self.owner_id();
+                self.adapter.link_ops_mut().set_owner(link, owner);
+            }
+            crate::adapter::acquired_on_insert(&mut self.adapter, link);
+            record_stat!(self, insertions);
 
-                panic!("attempted to insert an object that is already linked");
+            Ok(link)
+        }
+    }
+
+    // Converts a new element into its link and acquires it, without calling
+    // the `on_insert` hook of the adapter.
+    #[inline]
+    unsafe fn acquire_node(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> <A::LinkOps as link_ops::LinkOps>::LinkPtr {
+        use link_ops::LinkOps;
+
+        check_poison!(self);
+        let raw = self.adapter.pointer_ops().into_raw(val);
+        let link = self.adapter.get_link(raw);
+
+        if !self.adapter.link_ops_mut().acquire_link(link) {
+            // convert the node back into a pointer, after reading the
+            // owner of the link for the panic message
+            let owner = self.owner_of(link);
+            self.adapter.pointer_ops().from_raw(raw);
+
+            crate::adapter::already_linked(&self.adapter, self.debug_name(), raw, owner);
+        }
+        #[cfg(feature = "debug_owner")]
+        {
+            let owner = self.owner_id();
+            self.adapter.link_ops_mut().set_owner(link, owner);
+        }
+
+        link
+    }
+
+    // Returns whether `unlink_for_transfer` moved an element into this list,
+    // and panics if the element was already linked in it. This is done after
+    // both lists are consistent again, so that the panic doesn't poison them.
+    #[inline]
+    #[track_caller]
+    unsafe fn transferred(&self, result: crate::adapter::Transfer<A>) -> bool {
+        match result {
+            Some(Ok(_)) => true,
+            Some(Err(link)) => crate::adapter::already_linked(
+                &self.adapter,
+                self.debug_name(),
+                self.adapter.get_value(link),
+                self.owner_of(link),
+            ),
+            None => false,
+        }
+    }
+
+    // Returns the identifier of this list for the `debug_owner` feature,
+    // assigning one if needed.
+    #[cfg(feature = "debug_owner")]
+    #[inline]
+    fn owner_id(&mut self) -> usize {
+        if self.owner == 0 {
+            self.owner = link_ops::new_owner_id();
+        }
+        self.owner
+    }
+
+    // Records `owner` as the identifier of this list and the owner of all its
+    // elements, for the `debug_owner` feature.
+    #[cfg(feature = "debug_owner")]
+    fn set_owner_all(&mut self, owner: usize) {
+        self.owner = owner;
+        let mut prev = None;
+        let mut current = self.head;
+        while let Some(x) = current {
+            unsafe {
+                self.adapter.link_ops_mut().set_owner(x, owner);
+                current = self.adapter.link_ops().next(x, prev);
             }
+            prev = Some(x);
+        }
+    }
 
-            link
+    // Checks that `link` is in this list, for the `debug_owner` feature.
+    #[inline]
+    #[track_caller]
+    unsafe fn check_owner(&self, link: <A::LinkOps as link_ops::LinkOps>::LinkPtr) {
+        #[cfg(feature = "debug_owner")]
+        if let Some(owner) = self.adapter.link_ops().owner(link) {
+            if owner == 0 || owner != self.owner {
+                crate::adapter::wrong_owner(&self.adapter, self.debug_name());
+            }
         }
+        #[cfg(not(feature = "debug_owner"))]
+        let _ = link;
     }
 
-    /// Creates an empty `XorLinkedList`.
-    #[cfg(not(feature = "nightly"))]
+    // Returns the identifier of the collection `link` is in along with the one
+    // of this list, for the panic message of `already_linked`.
     #[inline]
-    pub fn new(adapter: A) -> XorLinkedList<A> {
-        XorLinkedList {
-            head: None,
-            tail: None,
-            adapter,
+    unsafe fn owner_of(
+        &self,
+        link: <A::LinkOps as link_ops::LinkOps>::LinkPtr,
+    ) -> Option<(usize, usize)> {
+        #[cfg(feature = "debug_owner")]
+        {
+            self.adapter
+                .link_ops()
+                .owner(link)
+                .map(|owner| (owner, self.owner))
+        }
+        #[cfg(not(feature = "debug_owner"))]
+        {
+            let _ = link;
+            None
         }
     }
 
     /// Creates an empty `XorLinkedList`.
-    #[cfg(feature = "nightly")]
+    ///
+    /// This is a `const fn`, which allows the collection to be used in a
+    /// `static` when combined with the `NEW` constant of an adapter.
     #[inline]
     pub const fn new(adapter: A) -> XorLinkedList<A> {
         XorLinkedList {
             head: None,
             tail: None,
             adapter,
+            #[cfg(debug_assertions)]
+            debug_name: None,
+            #[cfg(feature = "stats")]
+            stats: StatsCounters::new(),
+            #[cfg(feature = "debug_owner")]
+            owner: 0,
+            #[cfg(feature = "poison")]
+            poison: Poison::new(),
+        }
+    }
+
+    /// Sets a name for this `XorLinkedList` which is used in panic messages.
+    ///
+    /// The name is only stored in builds with debug assertions enabled.
+    #[inline]
+    pub fn set_debug_name(&mut self, name: &'static str) {
+        #[cfg(debug_assertions)]
+        {
+            self.debug_name = Some(name);
+        }
+        #[cfg(not(debug_assertions))]
+        let _ = name;
+    }
+
+    /// Returns the name set by `set_debug_name`.
+    ///
+    /// This always returns `None` in builds without debug assertions.
+    #[inline]
+    pub fn debug_name(&self) -> Option<&'static str> {
+        #[cfg(debug_assertions)]
+        return self.debug_name;
+        #[cfg(not(debug_assertions))]
+        None
+    }
+
+    /// Returns `true` if the `XorLinkedList` is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    /// Returns the memory used by the `XorLinkedList` and the links of its elements.
+    ///
+    /// This counts the elements by walking the list, in linear time.
+    #[inline]
+    pub fn memory_overhead(&self) -> MemoryOverhead {
+        MemoryOverhead::new::<A::LinkOps, Self>(self.iter().count())
+    }
+
+    /// Returns statistics about the operations performed on the
+    /// `XorLinkedList`.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn stats(&self) -> Stats {
+        self.stats.get()
+    }
+
+    /// Resets the statistics returned by `stats` to zero.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn reset_stats(&mut self) {
+        self.stats = StatsCounters::new();
+    }
+
+    /// Returns `true` if a panic escaped an operation which modified the
+    /// links of the `XorLinkedList`.
+    ///
+    /// Operations which modify the links of a poisoned list panic, since
+    /// they may be inconsistent. Reading the list is still allowed.
+    /// Panics raised before the links are modified, such as when inserting
+    /// an element which is already linked, don't poison the list.
+    #[cfg(feature = "poison")]
+    #[inline]
+    pub fn is_poisoned(&self) -> bool {
+        self.poison.get()
+    }
+
+    /// Clears the poisoned state of the `XorLinkedList`.
+    ///
+    /// This should only be done once the list is known to be consistent.
+    #[cfg(feature = "poison")]
+    #[inline]
+    pub fn clear_poison(&mut self) {
+        self.poison.clear();
+    }
+
+    /// Writes the chain of elements of the list to `out`, for post-mortem
+    /// analysis of a corrupted list.
+    ///
+    /// Every element is written on its own line, with its index, its address
+    /// and the addresses of its neighbors. The head and tail of the list are
+    /// written first, and a tail which isn't the last element is reported at
+    /// the end.
+    ///
+    /// Since elements only store the combination of the addresses of their
+    /// neighbors, the walk from the head can't loop, but an element linked
+    /// into two lists at once can't be detected where it is either: the
+    /// addresses written after it are garbage, which usually shows up as a
+    /// wrong tail.
+    ///
+    /// The pointers are followed as they are, so the elements they point to
+    /// must still be alive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use intrusive_collections::{intrusive_adapter, XorLinkedList, XorLinkedListLink};
+    ///
+    /// struct Obj {
+    ///     link: XorLinkedListLink,
+    /// }
+    /// intrusive_adapter!(ObjAdapter = Box<Obj>: Obj { link: XorLinkedListLink });
+    ///
+    /// let mut list = XorLinkedList::new(ObjAdapter::new());
+    /// list.push_back(Box::new(Obj { link: XorLinkedListLink::new() }));
+    /// list.push_back(Box::new(Obj { link: XorLinkedListLink::new() }));
+    ///
+    /// let mut dump = String::new();
+    /// list.dump(&mut dump).unwrap();
+    /// assert!(dump.ends_with("2 elements, no problems found\n"));
+    /// ```
+    #[cfg(feature = "dump")]
+    pub fn dump<W: fmt::Write>(&self, out: &mut W) -> fmt::Result {
+        self.dump_chain(out, None)
+    }
+
+    /// Same as `dump`, but also writes the `Debug` representation of every
+    /// element.
+    #[cfg(feature = "dump")]
+    pub fn dump_values<W: fmt::Write>(&self, out: &mut W) -> fmt::Result
+    where
+        <A::PointerOps as PointerOps>::Value: fmt::Debug,
+    {
+        self.dump_chain(out, Some(|value, out| write!(out, "{:?}", value)))
+    }
+
+    #[cfg(feature = "dump")]
+    fn dump_chain(
+        &self,
+        out: &mut dyn fmt::Write,
+        write_value: Option<dump::WriteValue<<A::PointerOps as PointerOps>::Value>>,
+    ) -> fmt::Result {
+        let link_ops = self.adapter.link_ops();
+        let value =
+            |link: Option<_>| dump::Addr(link.map(|link| unsafe { self.adapter.get_value(link) }));
+        dump::write_header(
+            out,
+            "XorLinkedList",
+            self.adapter.debug_name(),
+            self.debug_name(),
+        )?;
+        writeln!(out, " head {}, tail {}", value(self.head), value(self.tail))?;
+
+        let mut problems = 0;
+        let mut prev = None;
+        let mut current = self.head;
+        let mut index = 0;
+        while let Some(link) = current {
+            let next = unsafe { link_ops.next(link, prev) };
+            let ptr = unsafe { self.adapter.get_value(link) };
+            dump::write_element(out, index, ptr)?;
+            write!(out, " prev {}, next {}", value(prev), value(next))?;
+            unsafe { dump::write_value(out, ptr, write_value)? };
+            index += 1;
+            prev = current;
+            current = next;
         }
-    }
-
-    /// Returns `true` if the `XorLinkedList` is empty.
-    #[inline]
-    pub fn is_empty(&self) -> bool {
-        self.head.is_none()
+        if self.tail != prev {
+            dump::write_problem(
+                out,
+                &mut problems,
+                format_args!(
+                    "tail is {}, but the last element is {}",
+                    value(self.tail),
+                    value(prev)
+                ),
+            )?;
+        }
+        dump::write_summary(out, index, problems)
     }
 
     /// Returns a null `Cursor` for this list.
@@ -1303,6 +2469,55 @@ where
         }
     }
 
+    // Checks that `link` is in this list and that `prev` or `next`, if given,
+    // is its neighbor by scanning the list, for the `debug-checks` feature.
+    #[inline]
+    unsafe fn check_neighbors(
+        &self,
+        link: <A::LinkOps as link_ops::LinkOps>::LinkPtr,
+        prev: Option<Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>>,
+        next: Option<Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>>,
+    ) {
+        #[cfg(feature = "debug-checks")]
+        {
+            let value = self.adapter.get_value(link);
+            let mut x_prev = None;
+            let mut current = self.head;
+            while let Some(x) = current {
+                let x_next = self.adapter.link_ops().next(x, x_prev);
+                if x == link {
+                    if prev.map_or(false, |prev| prev != x_prev) {
+                        crate::adapter::bad_cursor_ptr(
+                            &self.adapter,
+                            self.debug_name(),
+                            value,
+                            "whose previous element isn't the given one",
+                        );
+                    }
+                    if next.map_or(false, |next| next != x_next) {
+                        crate::adapter::bad_cursor_ptr(
+                            &self.adapter,
+                            self.debug_name(),
+                            value,
+                            "whose next element isn't the given one",
+                        );
+                    }
+                    return;
+                }
+                x_prev = current;
+                current = x_next;
+            }
+            crate::adapter::bad_cursor_ptr(
+                &self.adapter,
+                self.debug_name(),
+                value,
+                "which is not in this collection",
+            );
+        }
+        #[cfg(not(feature = "debug-checks"))]
+        let _ = (link, prev, next);
+    }
+
     /// Creates a `Cursor` from a pointer to an element and a pointer to the previous element.
     ///
     /// # Safety
@@ -1321,6 +2536,8 @@ where
         } else {
             None
         };
+        self.check_neighbors(current, Some(prev), None);
+        self.check_owner(current);
         let next = self.adapter.link_ops().next(current, prev);
 
         Cursor {
@@ -1349,6 +2566,8 @@ where
         } else {
             None
         };
+        self.check_neighbors(current, Some(prev), None);
+        self.check_owner(current);
         let next = self.adapter.link_ops().next(current, prev);
 
         CursorMut {
@@ -1377,6 +2596,8 @@ where
         } else {
             None
         };
+        self.check_neighbors(current, Some(prev), None);
+        self.check_owner(current);
         let next = self.adapter.link_ops().next(current, prev);
 
         CursorOwning {
@@ -1405,6 +2626,8 @@ where
         } else {
             None
         };
+        self.check_neighbors(current, None, Some(next));
+        self.check_owner(current);
         let prev = self.adapter.link_ops().prev(current, next);
 
         Cursor {
@@ -1433,6 +2656,8 @@ where
         } else {
             None
         };
+        self.check_neighbors(current, None, Some(next));
+        self.check_owner(current);
         let prev = self.adapter.link_ops().prev(current, next);
 
         CursorMut {
@@ -1461,6 +2686,8 @@ where
         } else {
             None
         };
+        self.check_neighbors(current, None, Some(next));
+        self.check_owner(current);
         let prev = self.adapter.link_ops().prev(current, next);
 
         CursorOwning {
@@ -1525,6 +2752,104 @@ where
         cursor
     }
 
+    /// Returns a `Cursor` pointing to the element at index `n` of the list,
+    /// counting from 0 at the front. If the list has `n` elements or fewer
+    /// then a null cursor is returned.
+    ///
+    /// This takes time linear in `n`.
+    ///
+    /// The list doesn't store its length, so elements closer to the back are
+    /// best reached with `cursor_at_back` if the length is known.
+    #[inline]
+    pub fn cursor_at(&self, n: usize) -> Cursor<'_, A> {
+        let mut cursor = self.front();
+        for _ in 0..n {
+            if cursor.is_null() {
+                break;
+            }
+            cursor.move_next();
+        }
+        cursor
+    }
+
+    /// Returns a `CursorMut` pointing to the element at index `n` of the list,
+    /// counting from 0 at the front. If the list has `n` elements or fewer
+    /// then a null cursor is returned.
+    ///
+    /// This takes time linear in `n`.
+    #[inline]
+    pub fn cursor_mut_at(&mut self, n: usize) -> CursorMut<'_, A> {
+        let mut cursor = self.front_mut();
+        for _ in 0..n {
+            if cursor.is_null() {
+                break;
+            }
+            cursor.move_next();
+        }
+        cursor
+    }
+
+    /// Returns a `Cursor` pointing to the element at index `n` of the list,
+    /// counting from 0 at the back. If the list has `n` elements or fewer
+    /// then a null cursor is returned.
+    ///
+    /// This takes time linear in `n`. For a list of length `len`, this points
+    /// to the same element as `cursor_at(len - 1 - n)`, so positional access
+    /// can walk from whichever end is closer:
+    ///
+    /// ```
+    /// use intrusive_collections::{intrusive_adapter, XorLinkedList, XorLinkedListLink};
+    ///
+    /// struct Track {
+    ///     link: XorLinkedListLink,
+    ///     id: usize,
+    /// }
+    /// intrusive_adapter!(TrackAdapter = Box<Track>: Track { link: XorLinkedListLink });
+    ///
+    /// let mut playlist = XorLinkedList::new(TrackAdapter::new());
+    /// let len = 10;
+    /// for id in 0..len {
+    ///     playlist.push_back(Box::new(Track { link: XorLinkedListLink::new(), id }));
+    /// }
+    /// let track = |n| {
+    ///     if n < len / 2 {
+    ///         playlist.cursor_at(n)
+    ///     } else {
+    ///         playlist.cursor_at_back(len - 1 - n)
+    ///     }
+    /// };
+    /// assert_eq!(track(2).get().unwrap().id, 2);
+    /// assert_eq!(track(8).get().unwrap().id, 8);
+    /// ```
+    #[inline]
+    pub fn cursor_at_back(&self, n: usize) -> Cursor<'_, A> {
+        let mut cursor = self.back();
+        for _ in 0..n {
+            if cursor.is_null() {
+                break;
+            }
+            cursor.move_prev();
+        }
+        cursor
+    }
+
+    /// Returns a `CursorMut` pointing to the element at index `n` of the list,
+    /// counting from 0 at the back. If the list has `n` elements or fewer
+    /// then a null cursor is returned.
+    ///
+    /// This takes time linear in `n`.
+    #[inline]
+    pub fn cursor_mut_at_back(&mut self, n: usize) -> CursorMut<'_, A> {
+        let mut cursor = self.back_mut();
+        for _ in 0..n {
+            if cursor.is_null() {
+                break;
+            }
+            cursor.move_prev();
+        }
+        cursor
+    }
+
     /// Gets an iterator over the objects in the `XorLinkedList`.
     #[inline]
     pub fn iter(&self) -> Iter<'_, A> {
@@ -1537,6 +2862,48 @@ where
         }
     }
 
+    /// Calls `f` on each object in the `XorLinkedList`, until it returns
+    /// `ControlFlow::Break`.
+    ///
+    /// This is faster than `iter` since it doesn't have to keep the state of an
+    /// iterator between calls. The value from `ControlFlow::Break` is returned,
+    /// or `ControlFlow::Continue(())` if `f` never breaks.
+    #[inline]
+    pub fn visit<B, F>(&self, mut f: F) -> ControlFlow<B>
+    where
+        F: FnMut(&<A::PointerOps as PointerOps>::Value) -> ControlFlow<B>,
+    {
+        let mut prev = None;
+        let mut current = self.head;
+        while let Some(x) = current {
+            unsafe {
+                if let ControlFlow::Break(b) = f(&*self.adapter.get_value(x)) {
+                    return ControlFlow::Break(b);
+                }
+                current = self.adapter.link_ops().next(x, prev);
+            }
+            prev = Some(x);
+        }
+        ControlFlow::Continue(())
+    }
+
+    /// Gets an iterator over mutable references to the data of the objects
+    /// in the `XorLinkedList`, held in the `GhostCell`s returned by the adapter.
+    ///
+    /// Only the `GhostToken` has to be borrowed mutably, so this can be used
+    /// through a shared reference to the `XorLinkedList`.
+    #[cfg(feature = "ghost-cell")]
+    #[inline]
+    pub fn iter_ghost_mut<'a, 'brand>(
+        &'a self,
+        token: &'a mut GhostToken<'brand>,
+    ) -> GhostIterMut<'a, 'brand, A, Iter<'a, A>>
+    where
+        A: GhostAdapter<'brand>,
+    {
+        unsafe { GhostIterMut::new(self.iter(), &self.adapter, token) }
+    }
+
     /// Gets a mutable iterator over the objects in the `XorLinkedList`.
     ///
     /// # Safety
@@ -1564,22 +2931,27 @@ where
     #[inline]
     pub fn clear(&mut self) {
         use link_ops::LinkOps;
-
-        let mut current = self.head;
-        let mut prev = None;
-        self.head = None;
-        self.tail = None;
-        while let Some(x) = current {
-            unsafe {
-                let next = self.adapter.link_ops().next(x, prev);
-                self.adapter.link_ops_mut().release_link(x);
-                self.adapter
-                    .pointer_ops()
-                    .from_raw(self.adapter.get_value(x));
-                prev = current;
-                current = next;
+        poisoning!(self, {
+            // Elements are unlinked from the front one at a time, so that the
+            // remaining ones are still in the list if dropping one panics.
+            while let Some(x) = self.head {
+                unsafe {
+                    let link_ops = self.adapter.link_ops_mut();
+                    let next = link_ops.next(x, None);
+                    match next {
+                        Some(next) => {
+                            let next_next = link_ops.next(next, Some(x));
+                            link_ops.set(next, None, next_next);
+                        }
+                        None => self.tail = None,
+                    }
+                    self.head = next;
+                    link_ops.release_link(x);
+                    record_stat!(self, removals);
+                    crate::adapter::unlinked_pointer(&self.adapter, x);
+                }
             }
-        }
+        })
     }
 
     /// Empties the `XorLinkedList` without unlinking or freeing objects in it.
@@ -1596,19 +2968,45 @@ where
 
     /// Takes all the elements out of the `XorLinkedList`, leaving it empty.
     /// The taken elements are returned as a new `XorLinkedList`.
+    ///
+    /// This takes constant time.
     #[inline]
     pub fn take(&mut self) -> XorLinkedList<A>
     where
         A: Clone,
     {
-        let list = XorLinkedList {
-            head: self.head,
-            tail: self.tail,
-            adapter: self.adapter.clone(),
-        };
-        self.head = None;
-        self.tail = None;
-        list
+        poisoning!(self, {
+            let list = XorLinkedList {
+                head: self.head,
+                tail: self.tail,
+                adapter: self.adapter.clone(),
+                #[cfg(debug_assertions)]
+                debug_name: self.debug_name,
+                #[cfg(feature = "stats")]
+                stats: StatsCounters::new(),
+                #[cfg(feature = "debug_owner")]
+                owner: self.owner,
+                #[cfg(feature = "poison")]
+                poison: Poison::new(),
+            };
+            self.head = None;
+            self.tail = None;
+            #[cfg(feature = "debug_owner")]
+            {
+                self.owner = 0;
+            }
+            list
+        })
+    }
+
+    /// Swaps the elements of this `XorLinkedList` with those of `other`, along with
+    /// their adapters.
+    ///
+    /// This takes constant time, and is equivalent to `mem::swap`, which can
+    /// also be used since an `XorLinkedList` doesn't point into itself.
+    #[inline]
+    pub fn swap(&mut self, other: &mut XorLinkedList<A>) {
+        mem::swap(self, other);
     }
 
     /// Inserts a new element at the start of the `XorLinkedList`.
@@ -1623,6 +3021,32 @@ where
         self.cursor_mut().insert_before(val);
     }
 
+    /// Inserts a new element at the start of the `XorLinkedList`, without
+    /// panicking.
+    ///
+    /// An error containing `val` is returned if the new element is already
+    /// linked to an intrusive collection.
+    #[inline]
+    pub fn try_push_front(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<(), InsertError<<A::PointerOps as PointerOps>::Pointer>> {
+        self.cursor_mut().try_insert_after(val)
+    }
+
+    /// Inserts a new element at the end of the `XorLinkedList`, without
+    /// panicking.
+    ///
+    /// An error containing `val` is returned if the new element is already
+    /// linked to an intrusive collection.
+    #[inline]
+    pub fn try_push_back(
+        &mut self,
+        val: <A::PointerOps as PointerOps>::Pointer,
+    ) -> Result<(), InsertError<<A::PointerOps as PointerOps>::Pointer>> {
+        self.cursor_mut().try_insert_before(val)
+    }
+
     /// Removes the first element of the `XorLinkedList`.
     ///
     /// This returns `None` if the `XorLinkedList` is empty.
@@ -1639,40 +3063,275 @@ where
         self.back_mut().remove()
     }
 
-    /// Reverses the list in-place.
-    ///
-    /// Due to the structure of `XorLinkedList`, this operation is O(1).
+    /// Removes the first element of the `XorLinkedList`, converting it back
+    /// with `TryPointerOps::try_from_raw`.
+    ///
+    /// This returns `None` if the `XorLinkedList` is empty, and the error from
+    /// `try_from_raw` if the element can't be converted back. The element is
+    /// removed from the `XorLinkedList` either way.
+    #[inline]
+    pub fn pop_front_checked(&mut self) -> Option<TryPointer<A>>
+    where
+        A::PointerOps: TryPointerOps,
+    {
+        self.front_mut().remove_checked()
+    }
+
+    /// Removes the last element of the `XorLinkedList`, converting it back
+    /// with `TryPointerOps::try_from_raw`.
+    ///
+    /// This returns `None` if the `XorLinkedList` is empty, and the error from
+    /// `try_from_raw` if the element can't be converted back. The element is
+    /// removed from the `XorLinkedList` either way.
+    #[inline]
+    pub fn pop_back_checked(&mut self) -> Option<TryPointer<A>>
+    where
+        A::PointerOps: TryPointerOps,
+    {
+        self.back_mut().remove_checked()
+    }
+
+    /// Reverses the list in-place.
+    ///
+    /// Due to the structure of `XorLinkedList`, this operation is O(1).
+    #[inline]
+    pub fn reverse(&mut self) {
+        poisoning!(self, core::mem::swap(&mut self.head, &mut self.tail));
+    }
+
+    /// Inserts a new element into the `XorLinkedList`, keeping the list
+    /// sorted by increasing priority.
+    ///
+    /// The new element is inserted after all elements with a priority less
+    /// than or equal to its own, so elements with equal priorities are kept in
+    /// insertion order. The list must already be sorted for the result to be
+    /// sorted. This has linear time complexity.
+    ///
+    /// Returns a `CursorMut` pointing to the newly added element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new element is already linked to a different intrusive
+    /// collection.
+    #[inline]
+    pub fn insert_sorted(&mut self, val: <A::PointerOps as PointerOps>::Pointer) -> CursorMut<'_, A>
+    where
+        A: PriorityAdapter,
+    {
+        unsafe {
+            let new = self.acquire_node(val);
+
+            // If a priority panics, the guard drops the new element and the
+            // list is left untouched.
+            let guard = crate::adapter::AcquiredLink::new(&mut self.adapter, new);
+            let adapter = guard.adapter();
+            let priority = adapter.get_priority(&*adapter.get_value(new));
+
+            let mut prev = None;
+            let mut next = self.head;
+            while let Some(node) = next {
+                if priority < adapter.get_priority(&*adapter.get_value(node)) {
+                    break;
+                }
+                next = adapter.link_ops().next(node, prev);
+                prev = Some(node);
+            }
+            guard.on_insert();
+            guard.disarm();
+            record_stat!(self, insertions);
+
+            poisoning!(self, {
+                link_between(self.adapter.link_ops_mut(), new, prev, next);
+                if prev.is_none() {
+                    self.head = Some(new);
+                }
+                if next.is_none() {
+                    self.tail = Some(new);
+                }
+            });
+
+            CursorMut {
+                current: Some(new),
+                prev,
+                next,
+                list: self,
+            }
+        }
+    }
+}
+
+// Allow read-only access to values from multiple threads. Pointers can be
+// cloned through a shared reference with `clone_pointer`, so they must be
+// `Sync` too.
+unsafe impl<A: Adapter + Sync> Sync for XorLinkedList<A>
+where
+    <A::PointerOps as PointerOps>::Value: Sync,
+    <A::PointerOps as PointerOps>::Pointer: Sync,
+    A::LinkOps: XorLinkedListOps,
+{
+}
+
+// Allow sending to another thread if the ownership (represented by the <A::PointerOps as PointerOps>::Pointer owned
+// pointer type) can be transferred to another thread.
+unsafe impl<A: Adapter + Send> Send for XorLinkedList<A>
+where
+    <A::PointerOps as PointerOps>::Pointer: Send,
+    A::LinkOps: XorLinkedListOps,
+{
+}
+
+// Cursors and iterators get the same thread safety as a reference to the
+// list: shared for `Cursor` and `Iter`, exclusive for the others.
+unsafe impl<A: Adapter> Send for Cursor<'_, A>
+where
+    XorLinkedList<A>: Sync,
+    A::LinkOps: XorLinkedListOps,
+{
+}
+
+unsafe impl<A: Adapter> Sync for Cursor<'_, A>
+where
+    XorLinkedList<A>: Sync,
+    A::LinkOps: XorLinkedListOps,
+{
+}
+
+unsafe impl<A: Adapter> Send for CursorMut<'_, A>
+where
+    XorLinkedList<A>: Send,
+    A::LinkOps: XorLinkedListOps,
+{
+}
+
+unsafe impl<A: Adapter> Sync for CursorMut<'_, A>
+where
+    XorLinkedList<A>: Sync,
+    A::LinkOps: XorLinkedListOps,
+{
+}
+
+unsafe impl<A: Adapter> Send for Iter<'_, A>
+where
+    XorLinkedList<A>: Sync,
+    A::LinkOps: XorLinkedListOps,
+{
+}
+
+unsafe impl<A: Adapter> Sync for Iter<'_, A>
+where
+    XorLinkedList<A>: Sync,
+    A::LinkOps: XorLinkedListOps,
+{
+}
+
+unsafe impl<A: Adapter> Send for IterMut<'_, A>
+where
+    XorLinkedList<A>: Send,
+    <A::PointerOps as PointerOps>::Value: Send,
+    A::LinkOps: XorLinkedListOps,
+    A::PointerOps: ExclusivePointerOps,
+{
+}
+
+unsafe impl<A: Adapter> Sync for IterMut<'_, A>
+where
+    XorLinkedList<A>: Sync,
+    A::LinkOps: XorLinkedListOps,
+    A::PointerOps: ExclusivePointerOps,
+{
+}
+
+// Drop all owned pointers if the collection is dropped
+impl<A: Adapter> Drop for XorLinkedList<A>
+where
+    A::LinkOps: XorLinkedListOps,
+{
+    #[inline]
+    fn drop(&mut self) {
+        // The links of a poisoned list may be inconsistent, so its elements
+        // are leaked instead of being unlinked.
+        #[cfg(feature = "poison")]
+        if self.is_poisoned() {
+            return;
+        }
+        let leaked = crate::adapter::checks_leaks(&self.adapter) && !self.is_empty();
+        self.clear();
+        if leaked {
+            crate::adapter::leaked_on_drop(&self.adapter, self.debug_name());
+        }
+    }
+}
+
+impl<A: Adapter> Collection for XorLinkedList<A>
+where
+    A::LinkOps: XorLinkedListOps,
+{
+    type Adapter = A;
+    type Position = (
+        Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+        Option<<A::LinkOps as link_ops::LinkOps>::LinkPtr>,
+    );
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        XorLinkedList::is_empty(self)
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        XorLinkedList::clear(self);
+    }
+
+    #[inline]
+    fn insert(&mut self, val: <A::PointerOps as PointerOps>::Pointer) {
+        self.push_back(val);
+    }
+
+    #[inline]
+    fn remove_first<F: FnMut(&<A::PointerOps as PointerOps>::Value) -> bool>(
+        &mut self,
+        mut f: F,
+    ) -> Option<<A::PointerOps as PointerOps>::Pointer> {
+        let mut cursor = self.front_mut();
+        loop {
+            if f(cursor.get()?) {
+                return cursor.remove();
+            }
+            cursor.move_next();
+        }
+    }
+
+    #[inline]
+    fn take(&mut self) -> XorLinkedList<A>
+    where
+        A: Clone,
+    {
+        XorLinkedList::take(self)
+    }
+
     #[inline]
-    pub fn reverse(&mut self) {
-        core::mem::swap(&mut self.head, &mut self.tail);
+    fn swap(&mut self, other: &mut XorLinkedList<A>) {
+        XorLinkedList::swap(self, other);
     }
-}
 
-// Allow read-only access to values from multiple threads
-unsafe impl<A: Adapter + Sync> Sync for XorLinkedList<A>
-where
-    <A::PointerOps as PointerOps>::Value: Sync,
-    A::LinkOps: XorLinkedListOps,
-{
-}
+    #[inline]
+    fn front_position(&self) -> Self::Position {
+        (None, self.head)
+    }
 
-// Allow sending to another thread if the ownership (represented by the <A::PointerOps as PointerOps>::Pointer owned
-// pointer type) can be transferred to another thread.
-unsafe impl<A: Adapter + Send> Send for XorLinkedList<A>
-where
-    <A::PointerOps as PointerOps>::Pointer: Send,
-    A::LinkOps: XorLinkedListOps,
-{
-}
+    #[inline]
+    unsafe fn value_at(
+        &self,
+        position: Self::Position,
+    ) -> Option<&<A::PointerOps as PointerOps>::Value> {
+        position.1.map(|x| &*self.adapter.get_value(x))
+    }
 
-// Drop all owned pointers if the collection is dropped
-impl<A: Adapter> Drop for XorLinkedList<A>
-where
-    A::LinkOps: XorLinkedListOps,
-{
     #[inline]
-    fn drop(&mut self) {
-        self.clear();
+    unsafe fn next_position(&self, position: Self::Position) -> Self::Position {
+        let (prev, current) = position;
+        let current = current.unwrap_unchecked();
+        (Some(current), self.adapter.link_ops().next(current, prev))
     }
 }
 
@@ -1711,6 +3370,24 @@ where
     }
 }
 
+// Clones the values into new boxes. The links of the clones are new ones,
+// since cloning a link always returns an unlinked one.
+#[cfg(feature = "alloc")]
+impl<A, V> Clone for XorLinkedList<A>
+where
+    A: Adapter<PointerOps = DefaultPointerOps<Box<V>>> + Clone,
+    A::LinkOps: XorLinkedListOps,
+    V: Clone,
+{
+    fn clone(&self) -> XorLinkedList<A> {
+        let mut list = XorLinkedList::new(self.adapter.clone());
+        for value in self.iter() {
+            list.push_back(Box::new(value.clone()));
+        }
+        list
+    }
+}
+
 impl<A: Adapter> fmt::Debug for XorLinkedList<A>
 where
     A::LinkOps: XorLinkedListOps,
@@ -1721,6 +3398,17 @@ where
     }
 }
 
+#[cfg(feature = "defmt")]
+impl<A: Adapter> defmt::Format for XorLinkedList<A>
+where
+    A::LinkOps: XorLinkedListOps,
+    <A::PointerOps as PointerOps>::Value: defmt::Format,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        crate::adapter::defmt_list(f, self.iter())
+    }
+}
+
 // =============================================================================
 // Iter
 // =============================================================================
@@ -2105,6 +3793,431 @@ mod tests {
         assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), []);
     }
 
+    #[test]
+    fn test_cursor_at() {
+        let mut l = XorLinkedList::new(RcObjAdapter1::new());
+        assert!(l.cursor_at(0).is_null());
+        for value in 1..5 {
+            l.push_back(make_rc_obj(value));
+        }
+        assert_eq!(l.cursor_at(0).get().unwrap().value, 1);
+        assert_eq!(l.cursor_at(3).get().unwrap().value, 4);
+        assert!(l.cursor_at(4).is_null());
+        assert!(l.cursor_at(usize::MAX).is_null());
+        assert_eq!(l.cursor_mut_at(3).remove().unwrap().value, 4);
+        assert_eq!(l.cursor_at_back(0).get().unwrap().value, 3);
+        assert_eq!(l.cursor_at_back(2).get().unwrap().value, 1);
+        assert!(l.cursor_at_back(3).is_null());
+        assert_eq!(l.cursor_mut_at_back(1).remove().unwrap().value, 2);
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3]);
+    }
+
+    #[test]
+    fn test_into_cursor() {
+        let mut l = XorLinkedList::new(RcObjAdapter1::new());
+        for value in 1..4 {
+            l.push_back(make_rc_obj(value));
+        }
+        let mut cur = l.front_mut();
+        cur.move_next();
+        assert_eq!(cur.as_cursor().peek_next().get().unwrap().value, 3);
+        let mut cur = cur.into_cursor();
+        let c2 = cur.clone();
+        cur.move_next();
+        assert_eq!(cur.get().unwrap().value, 3);
+        assert_eq!(c2.get().unwrap().value, 2);
+    }
+
+    #[test]
+    fn test_clone() {
+        #[derive(Clone)]
+        struct Obj {
+            link: Link,
+            value: u32,
+        }
+        intrusive_adapter!(ObjAdapter = Box<Obj>: Obj { link: Link });
+
+        let mut l = XorLinkedList::new(ObjAdapter::new());
+        for value in 1..4 {
+            l.push_back(Box::new(Obj {
+                link: Link::new(),
+                value,
+            }));
+        }
+        let mut l2 = l.clone();
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 2, 3]);
+        l.clear();
+        assert!(l2.iter().all(|x| x.link.is_linked()));
+        l2.push_back(Box::new(Obj {
+            link: Link::new(),
+            value: 4,
+        }));
+        assert_eq!(l2.iter().count(), 4);
+    }
+
+    #[test]
+    fn test_visit() {
+        use core::ops::ControlFlow;
+
+        let mut l = XorLinkedList::new(RcObjAdapter1::new());
+        assert_eq!(
+            l.visit(|_| ControlFlow::Break(())),
+            ControlFlow::Continue(())
+        );
+        for value in 1..5 {
+            l.push_back(make_rc_obj(value));
+        }
+        let mut values = Vec::new();
+        let res = l.visit(|x| {
+            values.push(x.value);
+            ControlFlow::<()>::Continue(())
+        });
+        assert_eq!(res, ControlFlow::Continue(()));
+        assert_eq!(values, [1, 2, 3, 4]);
+
+        values.clear();
+        let res = l.visit(|x| {
+            values.push(x.value);
+            if x.value == 2 {
+                ControlFlow::Break(x.value * 10)
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        assert_eq!(res, ControlFlow::Break(20));
+        assert_eq!(values, [1, 2]);
+    }
+
+    #[test]
+    fn test_try_insert() {
+        use crate::InsertError;
+
+        let a = make_rc_obj(1);
+        let b = make_rc_obj(2);
+        let c = make_rc_obj(3);
+
+        let mut l = XorLinkedList::new(RcObjAdapter1::new());
+        let mut l2 = XorLinkedList::new(RcObjAdapter1::new());
+        assert!(l.try_push_back(a.clone()).is_ok());
+        assert!(l.try_push_front(b.clone()).is_ok());
+        assert!(matches!(
+            l2.try_push_front(a.clone()),
+            Err(InsertError::AlreadyLinked(_))
+        ));
+        assert!(l2.is_empty());
+        assert!(matches!(
+            l.front_mut().try_insert_after(b.clone()),
+            Err(InsertError::AlreadyLinked(_))
+        ));
+        assert!(l.back_mut().try_insert_before(c.clone()).is_ok());
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [2, 3, 1]);
+
+        assert!(matches!(
+            l.cursor_mut().try_replace_with(c.clone()),
+            Err(InsertError::NullCursor(_))
+        ));
+        assert!(matches!(
+            l.back_mut().try_replace_with(c.clone()),
+            Err(InsertError::AlreadyLinked(_))
+        ));
+        let d = make_rc_obj(4);
+        assert!(Rc::ptr_eq(&l.back_mut().try_replace_with(d).unwrap(), &a));
+        assert_eq!(l.iter().map(|x| x.value).collect::<Vec<_>>(), [2, 3, 4]);
+    }
+
+    #[cfg(feature = "dump")]
+    #[test]
+    fn test_dump() {
+        use super::{LinkOps, XorLinkedListOps};
+        use core::ptr::NonNull;
+        use std::string::String;
+
+        let dump = |l: &XorLinkedList<RcObjAdapter1>| {
+            let mut out = String::new();
+            l.dump_values(&mut out).unwrap();
+            out
+        };
+        let mut l = XorLinkedList::new(RcObjAdapter1::new());
+        assert!(dump(&l).ends_with(": head null, tail null\n0 elements, no problems found\n"));
+
+        let objs = (0..3).map(make_rc_obj).collect::<Vec<_>>();
+        for obj in &objs {
+            l.push_back(obj.clone());
+        }
+        let a = |i: usize| format!("{:p}", &*objs[i]);
+        let out = dump(&l);
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 5, "{}", out);
+        assert!(lines[0].starts_with("XorLinkedList<"), "{}", out);
+        assert!(lines[0].ends_with(&format!("RcObjAdapter1>: head {}, tail {}", a(0), a(2))));
+        assert_eq!(
+            lines[1],
+            format!("  #0 {}: prev null, next {}, value 0", a(0), a(1))
+        );
+        assert_eq!(
+            lines[2],
+            format!("  #1 {}: prev {}, next {}, value 1", a(1), a(0), a(2))
+        );
+        assert_eq!(lines[4], "3 elements, no problems found");
+
+        // A tail which isn't the last element.
+        let mut ops = LinkOps;
+        let link = |i: usize| NonNull::from(&objs[i].link1);
+        unsafe { ops.set(link(1), Some(link(0)), None) };
+        let out = dump(&l);
+        assert!(out.ends_with(&format!(
+            "  ! tail is {}, but the last element is {}\n2 elements, 1 problem found\n",
+            a(2),
+            a(1)
+        )));
+
+        unsafe { ops.set(link(1), Some(link(0)), Some(link(2))) };
+        assert!(dump(&l).ends_with("3 elements, no problems found\n"));
+    }
+
+    #[cfg(feature = "debug-checks")]
+    #[test]
+    fn test_cursor_from_ptr_checks() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::string::String;
+
+        let mut l = XorLinkedList::new(RcObjAdapter1::new());
+        let objs = (0..3).map(make_rc_obj).collect::<Vec<_>>();
+        for obj in &objs {
+            l.push_back(obj.clone());
+        }
+        let outside = make_rc_obj(3);
+        let p = |i: usize| &*objs[i] as *const Obj;
+        unsafe {
+            assert_eq!(
+                l.cursor_from_ptr_and_prev(p(1), p(0)).get().unwrap().value,
+                1
+            );
+            assert_eq!(
+                l.cursor_from_ptr_and_next(p(2), ptr::null())
+                    .get()
+                    .unwrap()
+                    .value,
+                2
+            );
+        }
+
+        let check = |f: &mut dyn FnMut(), problem: &str| {
+            let err = catch_unwind(AssertUnwindSafe(f)).unwrap_err();
+            let msg = err.downcast::<String>().unwrap();
+            assert!(msg.contains(problem), "{}", msg);
+        };
+        check(
+            &mut || unsafe {
+                l.cursor_from_ptr_and_prev(&*outside, ptr::null());
+            },
+            "which is not in this collection",
+        );
+        check(
+            &mut || unsafe {
+                l.cursor_mut_from_ptr_and_prev(p(2), p(0));
+            },
+            "whose previous element isn't the given one",
+        );
+        check(
+            &mut || unsafe {
+                l.cursor_from_ptr_and_next(p(0), ptr::null());
+            },
+            "whose next element isn't the given one",
+        );
+    }
+
+    #[test]
+    fn test_cursor_debug() {
+        let a = make_rc_obj(1);
+        let b = make_rc_obj(2);
+        let mut l = XorLinkedList::new(RcObjAdapter1::new());
+        l.push_back(a.clone());
+        l.push_back(b.clone());
+        let (a, b) = (&*a as *const Obj, &*b as *const Obj);
+
+        assert_eq!(
+            format!("{:?}", l.cursor()),
+            format!(
+                "Cursor {{ current: None, prev: {:?}, next: {:?} }}",
+                Some(b),
+                Some(a)
+            )
+        );
+        assert_eq!(
+            format!("{:?}", l.back_mut()),
+            format!(
+                "CursorMut {{ current: {:?}, prev: {:?}, next: None }}",
+                Some(b),
+                Some(a)
+            )
+        );
+    }
+
+    #[test]
+    fn test_transfer() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let a = make_rc_obj(1);
+        let b = make_rc_obj(2);
+        let c = make_rc_obj(3);
+        let mut l1 = XorLinkedList::new(RcObjAdapter1::new());
+        let mut l2 = XorLinkedList::new(RcObjAdapter1::new());
+        let mut l3 = XorLinkedList::new(RcObjAdapter2::new());
+        l1.push_back(a.clone());
+        l1.push_back(b.clone());
+        l1.push_back(c.clone());
+
+        // Same link
+        let mut cur = l1.front_mut();
+        cur.move_next();
+        assert!(cur.transfer_after(&mut l2.cursor_mut()));
+        assert_eq!(cur.get().unwrap().value, 3);
+        assert!(cur.transfer_before(&mut l2.cursor_mut()));
+        assert!(cur.is_null());
+        assert!(!cur.transfer_after(&mut l2.cursor_mut()));
+        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1]);
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(l2.iter().rev().map(|x| x.value).collect::<Vec<_>>(), [3, 2]);
+        assert_eq!(Rc::strong_count(&b), 2);
+
+        // Different link
+        assert!(l1.front_mut().transfer_after(&mut l3.cursor_mut()));
+        assert!(l2.back_mut().transfer_before(&mut l3.front_mut()));
+        assert!(l1.is_empty());
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [2]);
+        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), [3, 1]);
+        assert!(!a.link1.is_linked());
+        assert!(a.link2.is_linked());
+        assert_eq!(Rc::strong_count(&a), 2);
+        assert_eq!(Rc::strong_count(&c), 2);
+
+        // Already linked through the link of the destination
+        l1.push_back(make_rc_obj(4));
+        l1.push_back(a.clone());
+        l1.push_back(make_rc_obj(5));
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            let mut l4 = XorLinkedList::new(RcObjAdapter2::new());
+            let mut cur = l1.front_mut();
+            cur.move_next();
+            cur.transfer_after(&mut l4.cursor_mut())
+        }));
+        assert!(result.is_err());
+        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [4, 1, 5]);
+        assert_eq!(
+            l1.iter().rev().map(|x| x.value).collect::<Vec<_>>(),
+            [5, 1, 4]
+        );
+        assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), [3, 1]);
+        assert_eq!(Rc::strong_count(&a), 3);
+        #[cfg(feature = "poison")]
+        assert!(!l1.is_poisoned());
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn test_stats() {
+        use crate::Stats;
+
+        let mut l = XorLinkedList::new(RcObjAdapter1::new());
+        for value in 0..4 {
+            l.push_back(make_rc_obj(value));
+        }
+        let mut c = l.front_mut();
+        c.move_next();
+        assert_eq!(c.remove().unwrap().value, 1);
+        c.replace_with(make_rc_obj(5)).unwrap();
+        c.move_prev();
+        let mut l2 = XorLinkedList::new(RcObjAdapter2::new());
+        assert!(c.transfer_after(&mut l2.cursor_mut()));
+        assert_eq!(
+            l.stats(),
+            Stats {
+                insertions: 5,
+                removals: 3,
+                rotations: 0,
+                max_depth: 0,
+                // `front_mut` moves to the first element.
+                cursor_moves: 3,
+            }
+        );
+        assert_eq!(l2.stats().insertions, 1);
+
+        let mut l3 = l.front_mut().split_after();
+        assert_eq!(l3.stats(), Stats::default());
+        l3.clear();
+        assert_eq!(l3.stats().removals, 1);
+        l.reset_stats();
+        assert_eq!(l.stats(), Stats::default());
+    }
+
+    #[cfg(feature = "debug_owner")]
+    #[test]
+    fn test_debug_owner() {
+        use std::panic::{self, AssertUnwindSafe};
+
+        let a = make_rc_obj(1);
+        let b = make_rc_obj(2);
+        let c = make_rc_obj(3);
+        let mut l1 = XorLinkedList::new(RcObjAdapter1::new());
+        let mut l2 = XorLinkedList::new(RcObjAdapter1::new());
+        l1.push_back(a.clone());
+        l1.push_back(b.clone());
+        l2.push_back(c.clone());
+
+        let wrong_list = |l: &mut XorLinkedList<RcObjAdapter1>, obj: &Rc<Obj>| {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| unsafe {
+                l.cursor_mut_from_ptr_and_prev(&**obj, ptr::null()).remove()
+            }));
+            let payload = result.err().unwrap();
+            let message = match payload.downcast_ref::<std::string::String>() {
+                Some(message) => message.as_str(),
+                None => payload.downcast_ref::<&str>().unwrap(),
+            };
+            assert!(message.contains("not in this collection"), "{}", message);
+        };
+        wrong_list(&mut l2, &a);
+        wrong_list(&mut l1, &c);
+        unsafe {
+            assert_eq!(
+                l1.cursor_from_ptr_and_prev(&*b, &*a).get().unwrap().value,
+                2
+            );
+        }
+
+        // Elements which are moved to another list belong to that list.
+        let mut l3 = l1.take();
+        wrong_list(&mut l1, &a);
+        l2.front_mut().splice_after(l3.take());
+        assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), [3, 1, 2]);
+        wrong_list(&mut l3, &b);
+        let mut l4 = unsafe {
+            l2.cursor_mut_from_ptr_and_prev(&*c, ptr::null())
+                .split_after()
+        };
+        wrong_list(&mut l2, &a);
+        unsafe {
+            assert!(Rc::ptr_eq(
+                &l4.cursor_mut_from_ptr_and_prev(&*a, ptr::null())
+                    .remove()
+                    .unwrap(),
+                &a
+            ));
+        }
+
+        // Removed elements don't belong to any list.
+        wrong_list(&mut l4, &a);
+        l2.push_back(a.clone());
+        unsafe {
+            assert!(Rc::ptr_eq(
+                &l2.cursor_mut_from_ptr_and_next(&*a, ptr::null())
+                    .remove()
+                    .unwrap(),
+                &a
+            ));
+        }
+    }
+
     #[test]
     fn test_split_splice() {
         let mut l1 = XorLinkedList::new(RcObjAdapter1::new());
@@ -2194,6 +4307,31 @@ mod tests {
         assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3, 4, 2]);
         assert_eq!(l2.iter().map(|x| x.value).collect::<Vec<_>>(), []);
         assert_eq!(l3.iter().map(|x| x.value).collect::<Vec<_>>(), []);
+        {
+            l2 = l1.front_mut().split_after();
+            let mut cur = l1.cursor_mut();
+            cur.splice_before(l2.take());
+            assert!(cur.is_null());
+            cur.move_prev();
+            assert_eq!(cur.get().unwrap().value, 2);
+        }
+        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3, 4, 2]);
+        assert_eq!(
+            l1.iter().rev().map(|x| x.value).collect::<Vec<_>>(),
+            [2, 4, 3, 1]
+        );
+        {
+            l2 = l1.back_mut().split_before();
+            let mut cur = l1.front_mut();
+            cur.splice_before(l2.take());
+            cur.move_prev();
+            assert_eq!(cur.get().unwrap().value, 4);
+        }
+        assert_eq!(l1.iter().map(|x| x.value).collect::<Vec<_>>(), [1, 3, 4, 2]);
+        assert_eq!(
+            l1.iter().rev().map(|x| x.value).collect::<Vec<_>>(),
+            [2, 4, 3, 1]
+        );
     }
 
     #[test]
@@ -2386,6 +4524,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_mut() {
+        intrusive_adapter!(BoxObjAdapter = Box<Obj>: Obj { link1: Link });
+        let mut l = XorLinkedList::new(BoxObjAdapter::new());
+        l.push_front(Box::new(make_obj(1)));
+        unsafe {
+            l.front_mut().get_mut().unwrap().value = 2;
+            let mut cur = l.front_mut();
+            cur.move_next();
+            assert!(cur.get_mut().is_none());
+        }
+        assert_eq!(l.front().get().unwrap().value, 2);
+
+        let a = make_rc_obj(3);
+        let mut l = XorLinkedList::new(RcObjAdapter1::new());
+        l.push_front(a.clone());
+        unsafe {
+            assert!(l.front_mut().try_get_mut().is_none());
+        }
+        drop(a);
+        unsafe {
+            l.front_mut().try_get_mut().unwrap().value = 4;
+        }
+        assert_eq!(l.front().get().unwrap().value, 4);
+    }
+
     #[test]
     fn test_non_static() {
         #[derive(Clone)]
@@ -2406,6 +4570,7 @@ mod tests {
         l.cursor_mut().insert_before(&b);
         assert_eq!(*l.front().get().unwrap().value, 5);
         assert_eq!(*l.back().get().unwrap().value, 5);
+        l.clear();
     }
 
     #[test]
@@ -2468,6 +4633,10 @@ mod tests {
             assert_eq!(pointer.value, 5);
             assert_eq!(3, $ptr::strong_count(&a));
 
+            let pointer = l.front_mut().clone_pointer().unwrap();
+            assert_eq!(pointer.value, 5);
+            assert_eq!(4, $ptr::strong_count(&a));
+
             l.front_mut().remove();
             assert!(l.front().clone_pointer().is_none());
         };
@@ -2482,4 +4651,158 @@ mod tests {
     fn test_clone_pointer_arc() {
         test_clone_pointer!(Arc, std::sync::Arc);
     }
+
+    #[cfg(loom)]
+    #[test]
+    fn test_loom_try_acquire_for_insertion() {
+        use super::AtomicLink;
+        use loom::sync::atomic::{AtomicUsize, Ordering};
+        use loom::thread;
+        use std::sync::Arc;
+
+        struct Obj {
+            link: AtomicLink,
+            value: u32,
+        }
+        intrusive_adapter!(ObjAdapter = Arc<Obj>: Obj { link: AtomicLink });
+
+        loom::model(|| {
+            let obj = Arc::new(Obj {
+                link: AtomicLink::new(),
+                value: 1,
+            });
+            let claimed = Arc::new(AtomicUsize::new(0));
+            let threads: Vec<_> = (0..2)
+                .map(|_| {
+                    let obj = obj.clone();
+                    let claimed = claimed.clone();
+                    thread::spawn(move || {
+                        if obj.link.try_acquire_for_insertion() {
+                            claimed.fetch_add(1, Ordering::Relaxed);
+                        }
+                        assert!(obj.link.is_linked());
+                    })
+                })
+                .collect();
+            for thread in threads {
+                thread.join().unwrap();
+            }
+            assert_eq!(claimed.load(Ordering::Relaxed), 1);
+            let mut list = XorLinkedList::new(ObjAdapter::new());
+            unsafe {
+                obj.link.force_unlink();
+            }
+            list.push_back(obj.clone());
+            assert_eq!(list.iter().map(|x| x.value).sum::<u32>(), 1);
+            list.clear();
+            assert!(!obj.link.is_linked());
+        });
+    }
+
+    #[cfg(loom)]
+    #[test]
+    fn test_loom_is_linked() {
+        use super::AtomicLink;
+        use loom::sync::atomic::{AtomicUsize, Ordering};
+        use loom::thread;
+        use std::sync::Arc;
+
+        struct Obj {
+            link: AtomicLink,
+            data: AtomicUsize,
+        }
+        intrusive_adapter!(ObjAdapter = Arc<Obj>: Obj { link: AtomicLink });
+
+        loom::model(|| {
+            let obj = Arc::new(Obj {
+                link: AtomicLink::new(),
+                data: AtomicUsize::new(0),
+            });
+            let mut list = XorLinkedList::new(ObjAdapter::new());
+            list.push_back(obj.clone());
+            let watcher = {
+                let obj = obj.clone();
+                thread::spawn(move || {
+                    // Removing the object releases everything written before.
+                    if !obj.link.is_linked() {
+                        assert_eq!(obj.data.load(Ordering::Relaxed), 1);
+                    }
+                })
+            };
+            obj.data.store(1, Ordering::Relaxed);
+            list.clear();
+            watcher.join().unwrap();
+        });
+    }
+
+    #[test]
+    fn test_try_acquire_for_insertion() {
+        use super::AtomicLink;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::sync::Arc;
+
+        struct Obj {
+            link: AtomicLink,
+            value: u32,
+        }
+        intrusive_adapter!(ObjAdapter = Arc<Obj>: Obj { link: AtomicLink });
+
+        let a = Arc::new(Obj {
+            link: AtomicLink::new(),
+            value: 1,
+        });
+        let b = Arc::new(Obj {
+            link: AtomicLink::new(),
+            value: 2,
+        });
+        assert!(a.link.try_acquire_for_insertion());
+        assert!(a.link.is_linked());
+        assert!(!a.link.try_acquire_for_insertion());
+
+        let mut l = XorLinkedList::new(ObjAdapter::new());
+        let result = catch_unwind(AssertUnwindSafe(|| l.push_back(a.clone())));
+        assert!(result.is_err());
+        assert!(a.link.is_linked());
+        assert!(l.is_empty());
+        unsafe {
+            a.link.force_unlink();
+        }
+        l.push_back(a.clone());
+        l.push_back(b.clone());
+        assert!(!b.link.try_acquire_for_insertion());
+        assert_eq!(l.iter().map(|x| x.value).sum::<u32>(), 3);
+        l.clear();
+        assert!(!a.link.is_linked());
+        assert!(a.link.try_acquire_for_insertion());
+        unsafe {
+            a.link.force_unlink();
+        }
+        assert!(!a.link.is_linked());
+    }
+
+    #[test]
+    fn test_insert_sorted() {
+        intrusive_adapter!(PriorityObjAdapter = Rc<Obj>: Obj { link1: Link }
+            priority: u32 = |obj| { obj.value / 10 });
+
+        let mut l = XorLinkedList::new(PriorityObjAdapter::new());
+        for &value in &[21, 10, 30, 22, 0, 11, 1] {
+            let mut cur = l.insert_sorted(make_rc_obj(value));
+            assert_eq!(cur.get().unwrap().value, value);
+            // The cursor can move both ways from the new element.
+            cur.move_prev();
+            cur.move_next();
+            cur.move_next();
+            cur.move_prev();
+            assert_eq!(cur.get().unwrap().value, value);
+        }
+        assert_eq!(
+            l.iter().map(|x| x.value).collect::<Vec<_>>(),
+            [0, 1, 10, 11, 21, 22, 30]
+        );
+        assert_eq!(
+            l.iter().rev().map(|x| x.value).collect::<Vec<_>>(),
+            [30, 22, 21, 11, 10, 1, 0]
+        );
+    }
 }